@@ -276,6 +276,7 @@ export function ClassWizardScreen(props: {
           <option value="2">Mode</option>
           <option value="3">Blended Mode</option>
           <option value="4">Blended Median</option>
+          <option value="5">Trimmed Mean</option>
         </select>
       </label>
       <label style={{ display: "flex", flexDirection: "column", gap: 4 }}>
@@ -826,6 +826,7 @@ export function MarkSetSetupScreen(props: {
             <option value="2">Mode</option>
             <option value="3">Blend Mode</option>
             <option value="4">Blend Median</option>
+            <option value="5">Trimmed Mean</option>
           </select>
           <button
             data-testid="markset-manager-create-btn"
@@ -1230,6 +1231,7 @@ export function MarkSetSetupScreen(props: {
             <option value="2">Calc Method 2</option>
             <option value="3">Calc Method 3</option>
             <option value="4">Calc Method 4</option>
+            <option value="5">Calc Method 5</option>
           </select>
           <button data-testid="markset-save-settings-btn" onClick={() => void saveMarkSetSettings()}>
             Save Settings
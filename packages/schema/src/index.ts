@@ -32,7 +32,8 @@ export const ClassesListResultSchema = z.object({
       name: z.string(),
       // Optional to preserve compatibility as the schema evolves.
       studentCount: z.number().optional(),
-      markSetCount: z.number().optional()
+      markSetCount: z.number().optional(),
+      archived: z.boolean().optional()
     })
   )
 });
@@ -42,6 +43,32 @@ export const ClassesCreateResultSchema = z.object({
   name: z.string()
 });
 
+export const ClassesReorderResultSchema = z.object({
+  ok: z.literal(true)
+});
+
+export const ClassesArchiveResultSchema = z.object({
+  ok: z.literal(true),
+  archived: z.boolean()
+});
+
+export const ClassesRenameResultSchema = z.object({
+  classId: z.string(),
+  name: z.string()
+});
+
+export const ClassesDuplicateResultSchema = z.object({
+  classId: z.string(),
+  name: z.string(),
+  copied: z.object({
+    markSets: z.number(),
+    categories: z.number(),
+    assessments: z.number(),
+    commentSets: z.number(),
+    attendanceSettings: z.boolean()
+  })
+});
+
 export const ClassesWizardDefaultsResultSchema = z.object({
   defaults: z.object({
     name: z.string(),
@@ -103,7 +130,42 @@ export const ClassImportLegacyResultSchema = z.object({
   missingMarkFiles: z.array(z.unknown()).optional(),
   loanedItemsImported: z.number().optional(),
   deviceMappingsImported: z.number().optional(),
-  combinedCommentSetsImported: z.number().optional()
+  combinedCommentSetsImported: z.number().optional(),
+  sectionsRun: z.array(z.string()).optional()
+});
+
+export const LegacyExportClassResultSchema = z.object({
+  classId: z.string(),
+  files: z.array(z.string())
+});
+
+export const LegacyPreviewImportResultSchema = z.object({
+  name: z.string(),
+  sourceClFile: z.string(),
+  studentsFound: z.number(),
+  markSetsFound: z.number(),
+  assessmentsFound: z.number(),
+  markFiles: z.array(z.string()),
+  missingMarkFiles: z.array(z.unknown()),
+  companionFiles: z.array(z.object({ kind: z.string(), path: z.string() })),
+  warnings: z.array(z.record(z.string(), z.unknown()))
+});
+
+export const LegacyValidateFolderResultSchema = z.object({
+  folders: z.array(
+    z.object({
+      folder: z.string(),
+      ok: z.boolean(),
+      report: LegacyPreviewImportResultSchema.nullable(),
+      errors: z.array(
+        z.object({
+          code: z.string(),
+          message: z.string(),
+          details: z.record(z.string(), z.unknown()).optional()
+        })
+      )
+    })
+  )
 });
 
 export const ClassesLegacyPreviewResultSchema = z.object({
@@ -196,18 +258,40 @@ export const MarkSetsCreateResultSchema = z.object({
   markSetId: z.string()
 });
 
-export const MarkSetsDeleteResultSchema = z.object({
+export const MarkSetsUpdateResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const MarkSetsDeleteResultSchema = z.object({
+  ok: z.literal(true),
+  permanent: z.boolean().optional()
+});
+
 export const MarkSetsUndeleteResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const MarkSetsReorderResultSchema = z.object({
+  ok: z.literal(true)
+});
+
 export const MarkSetsSetDefaultResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const MarkSetsWeightCheckResultSchema = z.object({
+  weightMethod: z.number(),
+  total: z.number(),
+  expected: z.number(),
+  ok: z.boolean(),
+  categories: z.array(
+    z.object({
+      name: z.string(),
+      weight: z.number()
+    })
+  )
+});
+
 export const MarkSetsCloneResultSchema = z.object({
   markSetId: z.string()
 });
@@ -258,7 +342,8 @@ export const MarkSetOpenResultSchema = z.object({
       id: z.string(),
       displayName: z.string(),
       sortOrder: z.number(),
-      active: z.boolean()
+      active: z.boolean(),
+      gridRow: z.number()
     })
   ),
   assessments: z.array(
@@ -268,6 +353,7 @@ export const MarkSetOpenResultSchema = z.object({
       date: z.string().nullable(),
       categoryName: z.string().nullable(),
       title: z.string(),
+      term: z.number().nullable(),
       weight: z.number().nullable(),
       outOf: z.number().nullable()
     })
@@ -309,6 +395,74 @@ export const GridBulkUpdateResultSchema = z.object({
     .optional()
 });
 
+export const ScoreStatusSchema = z.enum(["scored", "zero", "no_mark"]);
+
+export const GridGetScoresResultSchema = z.object({
+  scores: z.array(
+    z.object({
+      assessmentId: z.string(),
+      studentId: z.string(),
+      rawValue: z.number().nullable(),
+      status: ScoreStatusSchema,
+      remark: z.string().nullable()
+    })
+  )
+});
+
+export const ScoresSetCellResultSchema = z.object({
+  rawValue: z.number().nullable(),
+  status: ScoreStatusSchema
+});
+
+export const ScoresBulkSetResultSchema = z.object({
+  updated: z.number(),
+  skipped: z.number()
+});
+
+export const ScoresClearColumnResultSchema = z.object({
+  cleared: z.number()
+});
+
+export const ScoresClearRowResultSchema = z.object({
+  cleared: z.number()
+});
+
+export const ScoresRecalcAveragesResultSchema = z.object({
+  updated: z.number()
+});
+
+export const ScoresHistoryResultSchema = z.object({
+  history: z.array(
+    z.object({
+      oldRaw: z.number().nullable(),
+      oldStatus: ScoreStatusSchema.nullable(),
+      newRaw: z.number().nullable(),
+      newStatus: ScoreStatusSchema,
+      changedAt: z.string()
+    })
+  )
+});
+
+export const EditLogEntrySchema = z.object({
+  id: z.number(),
+  op: z.enum(["scores.setCell", "scores.bulkSet", "students.delete"]),
+  createdAt: z.string(),
+  summary: z.union([
+    z.object({ cells: z.number() }),
+    z.object({ studentId: z.string(), displayName: z.string() })
+  ])
+});
+
+export const EditsListResultSchema = z.object({
+  entries: z.array(EditLogEntrySchema)
+});
+
+export const EditsUndoResultSchema = z.object({
+  undone: EditLogEntrySchema,
+  restored: z.number(),
+  skipped: z.number()
+});
+
 export const EntriesDeleteResultSchema = z.object({
   ok: z.literal(true)
 });
@@ -389,7 +543,8 @@ export const ReportsMarkSetGridModelResultSchema = z.object({
     .object({
       term: z.number().nullable(),
       categoryName: z.string().nullable(),
-      typesMask: z.number().nullable()
+      typesMask: z.number().nullable(),
+      includeUntermed: z.boolean().optional()
     })
     .optional(),
   studentScope: z.enum(["all", "active", "valid"]).optional()
@@ -405,7 +560,22 @@ export const StudentsListResultSchema = z.object({
       studentNo: z.string().nullable(),
       birthDate: z.string().nullable(),
       active: z.boolean(),
-      sortOrder: z.number()
+      sortOrder: z.number(),
+      archived: z.boolean(),
+      pronouns: z.enum(["he", "she", "they"]).nullable()
+    })
+  ),
+  total: z.number().optional()
+});
+
+export const StudentsSearchResultSchema = z.object({
+  results: z.array(
+    z.object({
+      studentId: z.string(),
+      classId: z.string(),
+      className: z.string(),
+      displayName: z.string(),
+      studentNo: z.string().nullable()
     })
   )
 });
@@ -414,6 +584,49 @@ export const StudentsCreateResultSchema = z.object({
   studentId: z.string()
 });
 
+export const StudentsBulkCreateResultSchema = z.object({
+  createdStudentIds: z.array(z.string())
+});
+
+export const StudentsMoveResultSchema = z.object({
+  ok: z.literal(true),
+  droppedScores: z.number()
+});
+
+export const StudentsFindDuplicatesResultSchema = z.object({
+  groups: z.array(
+    z.object({
+      lastName: z.string(),
+      firstName: z.string(),
+      birthDate: z.string().nullable(),
+      students: z.array(
+        z.object({
+          id: z.string(),
+          displayName: z.string(),
+          studentNo: z.string().nullable(),
+          birthDate: z.string().nullable(),
+          active: z.boolean(),
+          sortOrder: z.number(),
+          archived: z.boolean()
+        })
+      )
+    })
+  )
+});
+
+export const StudentsMergeResultSchema = z.object({
+  ok: z.literal(true),
+  keepId: z.string(),
+  merged: z.number(),
+  repointed: z.record(z.string(), z.number()),
+  dropped: z.record(z.string(), z.number())
+});
+
+export const StudentsArchiveResultSchema = z.object({
+  ok: z.literal(true),
+  archived: z.boolean()
+});
+
 export const StudentsUpdateResultSchema = z.object({
   ok: z.literal(true)
 });
@@ -470,7 +683,8 @@ export const CategoriesListResultSchema = z.object({
       id: z.string(),
       name: z.string(),
       weight: z.number().nullable(),
-      sortOrder: z.number()
+      sortOrder: z.number(),
+      dropLowest: z.number().int().nullable().optional()
     })
   )
 });
@@ -487,6 +701,10 @@ export const CategoriesDeleteResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const CategoriesReorderResultSchema = z.object({
+  ok: z.literal(true)
+});
+
 export const AssessmentsListResultSchema = z.object({
   assessments: z.array(
     z.object({
@@ -508,6 +726,12 @@ export const AssessmentsCreateResultSchema = z.object({
   assessmentId: z.string()
 });
 
+export const AssessmentsDuplicateResultSchema = z.object({
+  assessmentId: z.string(),
+  idx: z.number(),
+  scoresCopied: z.number()
+});
+
 export const AssessmentsBulkCreateResultSchema = z.object({
   ok: z.literal(true),
   created: z.number(),
@@ -542,6 +766,20 @@ export const AssessmentsReorderResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const AssessmentsStatsResultSchema = z.object({
+  stats: z.array(
+    z.object({
+      assessmentId: z.string(),
+      mean: z.number().nullable(),
+      median: z.number().nullable(),
+      stdDev: z.number().nullable(),
+      min: z.number().nullable(),
+      max: z.number().nullable(),
+      n: z.number().int()
+    })
+  )
+});
+
 export const NotesGetResultSchema = z.object({
   notes: z.array(
     z.object({
@@ -551,6 +789,10 @@ export const NotesGetResultSchema = z.object({
   )
 });
 
+export const NotesGetOneResultSchema = z.object({
+  note: z.string()
+});
+
 export const NotesUpdateResultSchema = z.object({
   ok: z.literal(true)
 });
@@ -588,6 +830,50 @@ export const LoanedUpdateResultSchema = z.object({
   itemId: z.string()
 });
 
+export const LoanedCreateResultSchema = z.object({
+  itemId: z.string()
+});
+
+export const LoanedDeleteResultSchema = z.object({
+  ok: z.literal(true)
+});
+
+export const LoanedReportResultSchema = z.object({
+  classId: z.string(),
+  markSetId: z.string().nullable(),
+  students: z.array(
+    z.object({
+      studentId: z.string(),
+      displayName: z.string(),
+      itemCount: z.number(),
+      totalQuantity: z.number(),
+      items: z.array(
+        z.object({
+          id: z.string(),
+          markSetId: z.string().nullable(),
+          itemName: z.string(),
+          quantity: z.number().nullable(),
+          notes: z.string().nullable()
+        })
+      )
+    })
+  ),
+  outstanding: z.array(
+    z.object({
+      studentId: z.string(),
+      displayName: z.string(),
+      itemName: z.string(),
+      quantity: z.number().nullable(),
+      notes: z.string().nullable()
+    })
+  ),
+  totals: z.object({
+    students: z.number(),
+    items: z.number(),
+    quantity: z.number()
+  })
+});
+
 export const DevicesListResultSchema = z.object({
   devices: z.array(
     z.object({
@@ -616,6 +902,33 @@ export const DevicesUpdateResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const DevicesCreateResultSchema = z.object({
+  deviceId: z.string()
+});
+
+export const DevicesDeleteResultSchema = z.object({
+  ok: z.literal(true)
+});
+
+export const AssetsPutStudentPhotoResultSchema = z.object({
+  ok: z.literal(true),
+  path: z.string(),
+  mimeType: z.enum(["image/png", "image/jpeg"]),
+  bytes: z.number()
+});
+
+export const AssetsGetStudentPhotoResultSchema = z.object({
+  studentId: z.string(),
+  photo: z
+    .object({
+      path: z.string(),
+      mimeType: z.string(),
+      bytes: z.number(),
+      dataBase64: z.string()
+    })
+    .nullable()
+});
+
 export const MarkSetSettingsGetResultSchema = z.object({
   markSet: z.object({
     id: z.string(),
@@ -647,6 +960,9 @@ const CalcPerAssessmentSchema = z.object({
   avgRaw: z.number(),
   avgPercent: z.number(),
   medianPercent: z.number(),
+  stdDevPercent: z.number(),
+  minPercent: z.number(),
+  maxPercent: z.number(),
   scoredCount: z.number(),
   zeroCount: z.number(),
   noMarkCount: z.number()
@@ -696,6 +1012,58 @@ export const CalcAssessmentStatsResultSchema = z.object({
   assessments: z.array(CalcPerAssessmentSchema)
 });
 
+export const CalcMarkSetAveragesResultSchema = z.object({
+  averages: z.array(
+    z.object({
+      studentId: z.string(),
+      percent: z.number().nullable()
+    })
+  )
+});
+
+export const CalcClassRankResultSchema = z.object({
+  ranks: z.array(
+    z.object({
+      studentId: z.string(),
+      percent: z.number().nullable(),
+      rank: z.number().nullable()
+    })
+  )
+});
+
+export const GradeBandSchema = z.object({
+  minPercent: z.number(),
+  label: z.string()
+});
+
+export const GradeScalesListResultSchema = z.object({
+  bands: z.array(GradeBandSchema)
+});
+
+export const GradeScalesUpsertResultSchema = GradeScalesListResultSchema;
+
+export const CalcLetterGradeResultSchema = z.object({
+  grades: z.array(
+    z.object({
+      studentId: z.string(),
+      percent: z.number().nullable(),
+      letter: z.string().nullable()
+    })
+  )
+});
+
+export const CalcProjectNeededResultSchema = z.object({
+  targetPercent: z.number(),
+  outOf: z.number(),
+  currentMark: z.number().nullable(),
+  maxMark: z.number().nullable(),
+  neededRaw: z.number().nullable(),
+  neededPercent: z.number().nullable(),
+  projectedMark: z.number().nullable(),
+  alreadyAchieved: z.boolean(),
+  impossible: z.boolean()
+});
+
 export const CalcMarkSetSummaryResultSchema = z.object({
   class: z.object({
     id: z.string(),
@@ -717,7 +1085,8 @@ export const CalcMarkSetSummaryResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   categories: z.array(
     z.object({
@@ -790,7 +1159,8 @@ export const AnalyticsClassOpenResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: AnalyticsStudentScopeSchema,
   kpis: z.object({
@@ -848,7 +1218,8 @@ export const AnalyticsClassAssessmentDrilldownResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: AnalyticsStudentScopeSchema,
   assessment: z.object({
@@ -903,7 +1274,8 @@ export const AnalyticsStudentOpenResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: AnalyticsStudentScopeSchema.optional(),
   student: CalcPerStudentSchema,
@@ -960,7 +1332,8 @@ export const AnalyticsStudentCompareResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: AnalyticsStudentScopeSchema,
   student: CalcPerStudentSchema,
@@ -1011,7 +1384,8 @@ export const AnalyticsStudentTrendResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   points: z.array(
     z.object({
@@ -1086,7 +1460,8 @@ export const AnalyticsCombinedOpenResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: AnalyticsStudentScopeSchema,
   settingsApplied: z
@@ -1133,6 +1508,23 @@ export const AnalyticsCombinedOpenResultSchema = z.object({
 
 export const ReportsMarkSetSummaryModelResultSchema = CalcMarkSetSummaryResultSchema;
 
+export const ReportsMarkSetSummaryResultSchema = z.object({
+  class: CalcMarkSetSummaryResultSchema.shape.class,
+  markSet: CalcMarkSetSummaryResultSchema.shape.markSet,
+  activeStudents: z.number(),
+  assessments: z.array(
+    z.object({
+      assessmentId: z.string(),
+      idx: z.number(),
+      title: z.string(),
+      outOf: z.number(),
+      classMean: z.number().nullable(),
+      n: z.number()
+    })
+  ),
+  overallClassMean: z.number().nullable()
+});
+
 export const CalcConfigGetResultSchema = z.object({
   source: z.object({
     basePresent: z.boolean(),
@@ -1152,6 +1544,11 @@ export const CalcConfigClearOverrideResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const SystemMethodsResultSchema = z.object({
+  count: z.number(),
+  methods: z.array(z.string())
+});
+
 export const PlannerUnitSchema = z.object({
   id: z.string(),
   sortOrder: z.number(),
@@ -1395,6 +1792,9 @@ export const SetupGetResultSchema = z.object({
     defaultHideDeletedEntries: z.boolean(),
     defaultAutoPreviewBeforeBulkApply: z.boolean()
   }),
+  students: z.object({
+    requireUniqueStudentNo: z.boolean()
+  }),
   exchange: z.object({
     defaultExportStudentScope: z.enum(["all", "active", "valid"]),
     includeStateColumnsByDefault: z.boolean()
@@ -1470,6 +1870,32 @@ export const AttendanceMonthOpenResultSchema = z.object({
   )
 });
 
+export const AttendanceYearOpenResultSchema = z.object({
+  schoolYearStartMonth: z.number(),
+  schoolYear: z.number().nullable(),
+  students: AttendanceMonthOpenResultSchema.shape.students,
+  months: z.array(
+    AttendanceMonthOpenResultSchema.pick({
+      month: true,
+      daysInMonth: true,
+      typeOfDayCodes: true,
+      rows: true
+    })
+  )
+});
+
+export const AttendanceSummaryResultSchema = z.object({
+  summaries: z.array(
+    z.object({
+      studentId: z.string(),
+      present: z.number().int(),
+      absent: z.number().int(),
+      late: z.number().int(),
+      excused: z.number().int()
+    })
+  )
+});
+
 export const AttendanceSetTypeOfDayResultSchema = z.object({
   ok: z.literal(true)
 });
@@ -1478,11 +1904,31 @@ export const AttendanceSetStudentDayResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const AttendanceSetStudentRangeResultSchema = z.object({
+  updated: z.number()
+});
+
 export const AttendanceBulkStampDayResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const AttendanceCodeSchema = z.object({
+  code: z.string(),
+  label: z.string(),
+  countsAsAbsent: z.boolean(),
+  countsAsLate: z.boolean(),
+  isExcused: z.boolean()
+});
+
+export const AttendanceCodesListResultSchema = z.object({
+  codes: z.array(AttendanceCodeSchema)
+});
+
+export const AttendanceCodesUpsertResultSchema = AttendanceCodesListResultSchema;
+
 export const SeatingGetResultSchema = z.object({
+  planId: z.string().nullable(),
+  planName: z.string().nullable(),
   rows: z.number(),
   seatsPerRow: z.number(),
   blockedSeatCodes: z.array(z.number()),
@@ -1490,7 +1936,62 @@ export const SeatingGetResultSchema = z.object({
 });
 
 export const SeatingSaveResultSchema = z.object({
-  ok: z.literal(true)
+  ok: z.literal(true),
+  planId: z.string()
+});
+
+export const SeatingAutoArrangeResultSchema = SeatingGetResultSchema.extend({
+  unseatedStudentIds: z.array(z.string())
+});
+
+export const SeatingSwapResultSchema = z.object({
+  assignments: z.array(
+    z.object({
+      studentId: z.string(),
+      seatCode: z.number().nullable()
+    })
+  )
+});
+
+export const SeatingPlansListResultSchema = z.object({
+  plans: z.array(
+    z.object({
+      planId: z.string(),
+      name: z.string(),
+      isDefault: z.boolean(),
+      rows: z.number(),
+      seatsPerRow: z.number(),
+      seatedCount: z.number()
+    })
+  )
+});
+
+export const SeatingPlansCreateResultSchema = z.object({
+  planId: z.string(),
+  name: z.string(),
+  isDefault: z.boolean()
+});
+
+export const SeatingPlansDeleteResultSchema = z.object({
+  ok: z.literal(true),
+  defaultPlanId: z.string().nullable()
+});
+
+export const SeatingClearResultSchema = z.object({
+  cleared: z.number()
+});
+
+export const SeatingSetBlockedResultSchema = z.object({
+  planId: z.string(),
+  blockedMask: z.string(),
+  blockedSeatCodes: z.array(z.number())
+});
+
+export const SeatingExportSvgResultSchema = z.object({
+  path: z.string(),
+  rows: z.number(),
+  seatsPerRow: z.number(),
+  photosEmbedded: z.number().optional()
 });
 
 export const CommentsSetsListResultSchema = z.object({
@@ -1505,11 +2006,16 @@ export const CommentsSetsListResultSchema = z.object({
       fitSubj: z.string(),
       maxChars: z.number(),
       isDefault: z.boolean(),
-      bankShort: z.string().nullable()
+      bankShort: z.string().nullable(),
+      id: z.string()
     })
   )
 });
 
+export const CommentsSetsReorderResultSchema = z.object({
+  ok: z.literal(true)
+});
+
 export const CommentsSetsOpenResultSchema = z.object({
   set: z.object({
     id: z.string(),
@@ -1535,6 +2041,37 @@ export const CommentsSetsOpenResultSchema = z.object({
   )
 });
 
+export const CommentsRenderResultSchema = z.object({
+  remarks: z.array(
+    z.object({
+      studentId: z.string(),
+      displayName: z.string(),
+      remark: z.string(),
+      rendered: z.string()
+    })
+  ),
+  warnings: z.array(
+    z.object({
+      code: z.literal("unknown_merge_field"),
+      studentId: z.string(),
+      placeholder: z.string()
+    })
+  )
+});
+
+export const CommentsFitCheckResultSchema = z.object({
+  maxChars: z.number(),
+  checked: z.number(),
+  overLimit: z.array(
+    z.object({
+      studentId: z.string(),
+      displayName: z.string(),
+      length: z.number(),
+      overBy: z.number()
+    })
+  )
+});
+
 export const CommentsSetsUpsertResultSchema = z.object({
   setNumber: z.number()
 });
@@ -1579,10 +2116,26 @@ export const CommentsBanksOpenResultSchema = z.object({
   )
 });
 
+export const CommentsBanksSearchResultSchema = z.object({
+  results: z.array(
+    z.object({
+      bankId: z.string(),
+      entryId: z.string(),
+      typeCode: z.string(),
+      levelCode: z.string(),
+      text: z.string()
+    })
+  )
+});
+
 export const CommentsBanksCreateResultSchema = z.object({
   bankId: z.string()
 });
 
+export const CommentsBanksDuplicateResultSchema = z.object({
+  bankId: z.string()
+});
+
 export const CommentsBanksUpdateMetaResultSchema = z.object({
   ok: z.literal(true)
 });
@@ -1595,6 +2148,26 @@ export const CommentsBanksEntryDeleteResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const CommentsBanksDeleteResultSchema = z.object({
+  ok: z.literal(true),
+  entriesDeleted: z.number(),
+  details: z
+    .object({
+      warning: z.literal("bank_still_referenced"),
+      shortName: z.string(),
+      commentSets: z.array(
+        z.object({
+          classId: z.string(),
+          markSetId: z.string(),
+          markSetCode: z.string(),
+          setNumber: z.number(),
+          title: z.string()
+        })
+      )
+    })
+    .optional()
+});
+
 export const CommentsBanksImportBnkResultSchema = z.object({
   bankId: z.string()
 });
@@ -1603,6 +2176,18 @@ export const CommentsBanksExportBnkResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const CommentsBanksExportAllBnkResultSchema = z.object({
+  ok: z.literal(true),
+  count: z.number(),
+  files: z.array(
+    z.object({
+      bankId: z.string(),
+      shortName: z.string(),
+      path: z.string()
+    })
+  )
+});
+
 export const CommentsTransferPreviewResultSchema = z.object({
   counts: z.object({
     sourceRows: z.number(),
@@ -1663,7 +2248,8 @@ export const ReportsCategoryAnalysisModelResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   categories: z.array(
     z.object({
@@ -1745,7 +2331,8 @@ export const ReportsStudentSummaryModelResultSchema = z.object({
   filters: z.object({
     term: z.number().nullable(),
     categoryName: z.string().nullable(),
-    typesMask: z.number().nullable()
+    typesMask: z.number().nullable(),
+    includeUntermed: z.boolean().optional()
   }),
   studentScope: z.enum(["all", "active", "valid"]).optional(),
   student: CalcPerStudentSchema,
@@ -1789,6 +2376,10 @@ export const LearningSkillsUpdateCellResultSchema = z.object({
   ok: z.literal(true)
 });
 
+export const LearningSkillsBulkSetResultSchema = z.object({
+  updated: z.number()
+});
+
 export const LearningSkillsReportModelResultSchema = z.object({
   class: z.object({
     id: z.string(),
@@ -1813,6 +2404,31 @@ export const LearningSkillsReportModelResultSchema = z.object({
   )
 });
 
+export const DbIntegrityCheckResultSchema = z.object({
+  ok: z.boolean(),
+  problems: z.array(z.record(z.string(), z.unknown()))
+});
+
+export const WorkspaceStatsResultSchema = z.object({
+  classCount: z.number(),
+  studentCount: z.number(),
+  markSetCount: z.number(),
+  assessmentCount: z.number(),
+  scoreCount: z.number(),
+  commentBankCount: z.number(),
+  activeClassCount: z.number(),
+  activeStudentCount: z.number(),
+  workspacePath: z.string(),
+  dbSizeBytes: z.number()
+});
+
+export const DbVacuumResultSchema = z.object({
+  ok: z.literal(true),
+  sizeBefore: z.number(),
+  sizeAfter: z.number(),
+  bytesReclaimed: z.number()
+});
+
 export const BackupExportWorkspaceBundleResultSchema = z.object({
   ok: z.literal(true),
   path: z.string(),
@@ -1824,12 +2440,58 @@ export const BackupExportWorkspaceBundleResultSchema = z.object({
 export const BackupImportWorkspaceBundleResultSchema = z.object({
   ok: z.literal(true),
   workspacePath: z.string().optional(),
-  bundleFormatDetected: z.string().optional()
+  bundleFormatDetected: z.string().optional(),
+  manifestPresent: z.boolean().optional()
+});
+
+export const BackupVerifyBundleResultSchema = z.object({
+  ok: z.boolean(),
+  bundleFormat: z.string(),
+  entryCount: z.number(),
+  problems: z.array(
+    z.object({
+      entry: z.string().nullable(),
+      message: z.string()
+    })
+  )
+});
+
+export const BackupExportClassBundleResultSchema = z.object({
+  ok: z.literal(true),
+  path: z.string(),
+  bundleFormat: z.string(),
+  classId: z.string(),
+  className: z.string(),
+  counts: z.record(z.string(), z.number())
+});
+
+export const BackupImportClassBundleResultSchema = z.object({
+  ok: z.literal(true),
+  classId: z.string(),
+  className: z.string(),
+  counts: z.record(z.string(), z.number())
 });
 
 export const ExchangeExportClassCsvResultSchema = z.object({
   ok: z.literal(true),
   rowsExported: z.number(),
+  path: z.string(),
+  layout: z.enum(["long", "wide"])
+});
+
+export const ExchangeExportAttendanceCsvResultSchema = z.object({
+  ok: z.literal(true),
+  rowsExported: z.number(),
+  path: z.string()
+});
+
+export const ExchangeExportLearningSkillsCsvResultSchema =
+  ExchangeExportAttendanceCsvResultSchema;
+
+export const ExchangeExportClassXlsxResultSchema = z.object({
+  ok: z.literal(true),
+  sheetCount: z.number(),
+  studentsExported: z.number(),
   path: z.string()
 });
 
@@ -1884,6 +2546,70 @@ export const ExchangeImportClassCsvResultSchema = z.object({
   path: z.string().optional()
 });
 
+export const ExchangeImportAttendanceCsvResultSchema = z.object({
+  ok: z.literal(true),
+  updated: z.number(),
+  rowsTotal: z.number(),
+  skipped: z.number(),
+  warningsCount: z.number(),
+  warnings: z.array(ExchangeWarningSchema),
+  mode: z.string(),
+  path: z.string()
+});
+
+export const ExchangeExportClassJsonResultSchema = z.object({
+  ok: z.literal(true),
+  path: z.string(),
+  bytes: z.number()
+});
+
+export const ClassJsonWarningSchema = z.object({
+  path: z.string(),
+  code: z.string(),
+  message: z.string()
+});
+
+export const ExchangeImportClassJsonResultSchema = z.object({
+  ok: z.literal(true),
+  classId: z.string(),
+  className: z.string(),
+  merged: z.boolean(),
+  counts: z.record(z.string(), z.number()),
+  warningsCount: z.number(),
+  warnings: z.array(ClassJsonWarningSchema),
+  path: z.string()
+});
+
+export const ExchangeImportClassCsvDryRunResultSchema = z.object({
+  ok: z.literal(true),
+  dryRun: z.literal(true),
+  wouldUpdate: z.number(),
+  wouldSkip: z.number(),
+  unmatchedStudents: z.array(z.string()),
+  unmatchedAssessments: z.array(
+    z.object({
+      markSetCode: z.string(),
+      assessmentIdx: z.number()
+    })
+  ),
+  sampleRows: z.array(
+    z.object({
+      line: z.number(),
+      studentId: z.string(),
+      markSetCode: z.string(),
+      assessmentIdx: z.number(),
+      action: z.enum(["update", "skip"]),
+      reason: z.string().nullable()
+    })
+  ),
+  rowsTotal: z.number(),
+  rowsParsed: z.number(),
+  warningsCount: z.number(),
+  warnings: z.array(ExchangeWarningSchema),
+  mode: z.string(),
+  path: z.string()
+});
+
 export const IntegrationsSisPreviewImportResultSchema = z.object({
   ok: z.literal(true),
   classId: z.string(),
@@ -1997,3 +2723,42 @@ export const ReportsClassListModelResultSchema = z.object({
 
 export const ReportsLearningSkillsSummaryModelResultSchema =
   LearningSkillsReportModelResultSchema;
+
+export const ReportsReportCardHtmlResultSchema = z.object({
+  classId: z.string(),
+  studentCount: z.number(),
+  path: z.string().optional(),
+  html: z.string().optional()
+});
+
+export const ReportsClassReportCardsPdfResultSchema = z.object({
+  path: z.string(),
+  pageCount: z.number()
+});
+
+export const ReportsRosterHtmlResultSchema = z.object({
+  classId: z.string(),
+  studentCount: z.number(),
+  path: z.string()
+});
+
+export const ReportsHonorRollResultSchema = z.object({
+  honorRoll: z.array(
+    z.object({
+      studentId: z.string(),
+      displayName: z.string(),
+      average: z.number()
+    })
+  )
+});
+
+export const ReportsStudentCommentsResultSchema = z.object({
+  sets: z.array(
+    z.object({
+      markSetCode: z.string(),
+      setNumber: z.number(),
+      title: z.string(),
+      remark: z.string()
+    })
+  )
+});
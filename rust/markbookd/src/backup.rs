@@ -1,16 +1,21 @@
 use anyhow::{anyhow, Context};
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use zip::write::FileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
-const MANIFEST_ENTRY: &str = "manifest.json";
+pub(crate) const MANIFEST_ENTRY: &str = "manifest.json";
 const DB_ENTRY: &str = "db/markbook.sqlite3";
 const META_WORKSPACE_ENTRY: &str = "meta/workspace.json";
 pub const BUNDLE_FORMAT_V2: &str = "markbook-workspace-v2";
+/// Backoff between attempts to replace the workspace database. Windows can
+/// keep a just-closed file locked for a moment (antivirus, indexer, a second
+/// window), so a failed replace is retried before giving up.
+const REPLACE_RETRY_DELAYS_MS: &[u64] = &[50, 100, 200, 400, 800];
 
 #[derive(Debug, Clone)]
 pub struct ExportSummary {
@@ -21,6 +26,63 @@ pub struct ExportSummary {
 #[derive(Debug, Clone)]
 pub struct ImportSummary {
     pub bundle_format_detected: String,
+    pub manifest_present: bool,
+}
+
+/// An entry whose bytes don't match the checksum recorded in the manifest.
+/// Returned (wrapped in `anyhow`) by `import_workspace_bundle` so callers can
+/// tell a damaged bundle apart from ordinary I/O failures.
+#[derive(Debug, Clone)]
+pub struct BundleCorrupt {
+    pub entry: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for BundleCorrupt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bundle entry {} is corrupt: {}",
+            self.entry, self.message
+        )
+    }
+}
+
+impl std::error::Error for BundleCorrupt {}
+
+/// The workspace database could not be replaced because another handle still
+/// has it open, even after retrying. Returned (wrapped in `anyhow`) by
+/// `import_workspace_bundle`; the existing database is left in place.
+#[derive(Debug, Clone)]
+pub struct DbInUse {
+    pub path: String,
+    pub attempts: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for DbInUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "database {} is in use after {} attempts: {}",
+            self.path, self.attempts, self.message
+        )
+    }
+}
+
+impl std::error::Error for DbInUse {}
+
+#[derive(Debug, Clone)]
+pub struct BundleProblem {
+    pub entry: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifySummary {
+    pub bundle_format: String,
+    pub entry_count: usize,
+    pub problems: Vec<BundleProblem>,
 }
 
 pub fn export_workspace_bundle(
@@ -49,6 +111,16 @@ pub fn export_workspace_bundle(
     let mut zip = ZipWriter::new(out_file);
     let opts = FileOptions::default().compression_method(CompressionMethod::Deflated);
 
+    let workspace_meta = serde_json::to_string_pretty(&json!({
+        "sourceWorkspace": workspace_path.to_string_lossy(),
+    }))
+    .context("failed to serialize workspace metadata")?;
+    let (db_sha256, db_size) = File::open(&db_path)
+        .and_then(|mut f| sha256_hex(&mut f))
+        .with_context(|| format!("failed to hash database {}", db_path.to_string_lossy()))?;
+    let (meta_sha256, meta_size) =
+        sha256_hex(&mut workspace_meta.as_bytes()).context("failed to hash workspace metadata")?;
+
     let exported_at = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -58,6 +130,10 @@ pub fn export_workspace_bundle(
         "version": 2,
         "appVersion": env!("CARGO_PKG_VERSION"),
         "exportedAt": exported_at,
+        "entries": [
+            { "path": DB_ENTRY, "sha256": db_sha256, "size": db_size },
+            { "path": META_WORKSPACE_ENTRY, "sha256": meta_sha256, "size": meta_size },
+        ],
     });
     zip.start_file(MANIFEST_ENTRY, opts)
         .context("failed to start manifest entry")?;
@@ -74,17 +150,10 @@ pub fn export_workspace_bundle(
         .with_context(|| format!("failed to open database {}", db_path.to_string_lossy()))?;
     std::io::copy(&mut db_file, &mut zip).context("failed to write database entry")?;
 
-    let workspace_meta = json!({
-        "sourceWorkspace": workspace_path.to_string_lossy(),
-    });
     zip.start_file(META_WORKSPACE_ENTRY, opts)
         .context("failed to start workspace metadata entry")?;
-    zip.write_all(
-        serde_json::to_string_pretty(&workspace_meta)
-            .context("failed to serialize workspace metadata")?
-            .as_bytes(),
-    )
-    .context("failed to write workspace metadata entry")?;
+    zip.write_all(workspace_meta.as_bytes())
+        .context("failed to write workspace metadata entry")?;
 
     zip.finish().context("failed to finalize zip bundle")?;
 
@@ -107,7 +176,10 @@ pub fn import_workspace_bundle(
     let dst = workspace_path.join("markbook.sqlite3");
 
     if !is_zip_file(in_path)? {
-        std::fs::copy(in_path, &dst).with_context(|| {
+        replace_with_retry(&dst, REPLACE_RETRY_DELAYS_MS, || {
+            std::fs::copy(in_path, &dst).map(|_| ())
+        })
+        .with_context(|| {
             format!(
                 "failed to copy legacy sqlite backup from {} to {}",
                 in_path.to_string_lossy(),
@@ -116,6 +188,7 @@ pub fn import_workspace_bundle(
         })?;
         return Ok(ImportSummary {
             bundle_format_detected: "legacy-sqlite3".to_string(),
+            manifest_present: false,
         });
     }
 
@@ -139,6 +212,12 @@ pub fn import_workspace_bundle(
         return Err(anyhow!("unsupported bundle format: {}", format));
     }
 
+    // Bundles written before checksums were added have no `entries` list and
+    // are restored unverified.
+    let expected = manifest_checksums(&manifest);
+    let manifest_present = expected.is_some();
+    check_manifest_checksums(&mut archive, expected.unwrap_or_default())?;
+
     let tmp_dst = workspace_path.join("markbook.sqlite3.importing");
     if tmp_dst.exists() {
         let _ = std::fs::remove_file(&tmp_dst);
@@ -160,26 +239,301 @@ pub fn import_workspace_bundle(
         .flush()
         .context("failed to flush extracted database")?;
 
-    if dst.exists() {
-        std::fs::remove_file(&dst).with_context(|| {
-            format!(
-                "failed to remove existing database {}",
-                dst.to_string_lossy()
-            )
-        })?;
-    }
-    std::fs::rename(&tmp_dst, &dst).with_context(|| {
-        format!(
+    // Windows will not rename a file this process still has open.
+    drop(db_out);
+    let replaced = replace_with_retry(&dst, REPLACE_RETRY_DELAYS_MS, || {
+        if dst.exists() {
+            std::fs::remove_file(&dst)?;
+        }
+        std::fs::rename(&tmp_dst, &dst)
+    });
+    if let Err(e) = replaced {
+        let _ = std::fs::remove_file(&tmp_dst);
+        return Err(e.context(format!(
             "failed to move extracted database to {}",
             dst.to_string_lossy()
-        )
-    })?;
+        )));
+    }
 
     Ok(ImportSummary {
         bundle_format_detected: BUNDLE_FORMAT_V2.to_string(),
+        manifest_present,
+    })
+}
+
+/// Read-only check of a bundle before it is restored: the manifest format,
+/// every entry's compressed data, and `PRAGMA integrity_check` on a temp copy
+/// of the database. Damage is reported as problems; only an unreadable input
+/// file is an error.
+pub fn verify_workspace_bundle(in_path: &Path) -> anyhow::Result<VerifySummary> {
+    let problem = |entry: Option<&str>, message: String| BundleProblem {
+        entry: entry.map(|s| s.to_string()),
+        message,
+    };
+
+    if !is_zip_file(in_path)? {
+        let mut problems = Vec::new();
+        check_sqlite_copy(&mut File::open(in_path)?, None, &mut problems)?;
+        return Ok(VerifySummary {
+            bundle_format: "legacy-sqlite3".to_string(),
+            entry_count: 1,
+            problems,
+        });
+    }
+
+    let in_file = File::open(in_path)
+        .with_context(|| format!("failed to open bundle {}", in_path.to_string_lossy()))?;
+    let mut archive = match ZipArchive::new(in_file) {
+        Ok(a) => a,
+        Err(e) => {
+            return Ok(VerifySummary {
+                bundle_format: String::new(),
+                entry_count: 0,
+                problems: vec![problem(None, format!("invalid zip archive: {}", e))],
+            })
+        }
+    };
+
+    let mut problems = Vec::new();
+    let mut bundle_format = String::new();
+    let mut expected: Vec<(String, String, u64)> = Vec::new();
+    let manifest_text = archive.by_name(MANIFEST_ENTRY).ok().and_then(|mut f| {
+        let mut text = String::new();
+        f.read_to_string(&mut text).ok().map(|_| text)
+    });
+    match manifest_text.map(|t| serde_json::from_str::<serde_json::Value>(&t)) {
+        None => problems.push(problem(
+            Some(MANIFEST_ENTRY),
+            "bundle missing manifest.json".to_string(),
+        )),
+        Some(Err(e)) => problems.push(problem(
+            Some(MANIFEST_ENTRY),
+            format!("manifest.json is invalid JSON: {}", e),
+        )),
+        Some(Ok(manifest)) => {
+            bundle_format = manifest
+                .get("format")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            if bundle_format != BUNDLE_FORMAT_V2 {
+                problems.push(problem(
+                    Some(MANIFEST_ENTRY),
+                    format!("unsupported bundle format: {}", bundle_format),
+                ));
+            }
+            expected = manifest_checksums(&manifest).unwrap_or_default();
+        }
+    }
+
+    // Reading each entry to the end makes the zip reader check its CRC.
+    let entry_count = archive.len();
+    for i in 0..entry_count {
+        let mut entry = match archive.by_index(i) {
+            Ok(e) => e,
+            Err(e) => {
+                problems.push(problem(None, format!("entry {} unreadable: {}", i, e)));
+                continue;
+            }
+        };
+        let name = entry.name().to_string();
+        match sha256_hex(&mut entry) {
+            Err(e) => problems.push(problem(Some(&name), format!("entry unreadable: {}", e))),
+            Ok((actual_sha256, actual_size)) => {
+                let listed = expected.iter().find(|(path, _, _)| *path == name);
+                if let Some((_, sha256, size)) = listed {
+                    if actual_size != *size || actual_sha256 != *sha256 {
+                        problems.push(problem(
+                            Some(&name),
+                            "checksum does not match manifest".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    for (path, _, _) in &expected {
+        if !archive.file_names().any(|n| n == path) {
+            problems.push(problem(
+                Some(path),
+                "entry listed in manifest is missing".to_string(),
+            ));
+        }
+    }
+
+    match archive.by_name(DB_ENTRY) {
+        Ok(mut db_entry) => check_sqlite_copy(&mut db_entry, Some(DB_ENTRY), &mut problems)?,
+        Err(_) => problems.push(problem(
+            Some(DB_ENTRY),
+            "bundle missing db/markbook.sqlite3".to_string(),
+        )),
+    }
+
+    Ok(VerifySummary {
+        bundle_format,
+        entry_count,
+        problems,
     })
 }
 
+/// Copies a database to a temp file and runs `PRAGMA integrity_check` on it,
+/// so neither the bundle nor the live workspace is opened by SQLite.
+fn check_sqlite_copy(
+    src: &mut impl Read,
+    entry: Option<&str>,
+    problems: &mut Vec<BundleProblem>,
+) -> anyhow::Result<()> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let tmp_path = std::env::temp_dir().join(format!(
+        "markbook-verify-{}-{}.sqlite3",
+        std::process::id(),
+        nanos
+    ));
+    let mut tmp = File::create(&tmp_path).with_context(|| {
+        format!(
+            "failed to create temp database {}",
+            tmp_path.to_string_lossy()
+        )
+    })?;
+    let copied = std::io::copy(src, &mut tmp).and_then(|_| tmp.flush());
+    drop(tmp);
+
+    let messages: Vec<String> = match copied {
+        Err(e) => vec![format!("database unreadable: {}", e)],
+        Ok(()) => match run_integrity_check(&tmp_path) {
+            Ok(rows) => rows.into_iter().filter(|m| m != "ok").collect(),
+            Err(e) => vec![format!("database cannot be opened: {}", e)],
+        },
+    };
+    let _ = std::fs::remove_file(&tmp_path);
+
+    problems.extend(messages.into_iter().map(|message| BundleProblem {
+        entry: entry.map(|s| s.to_string()),
+        message,
+    }));
+    Ok(())
+}
+
+fn run_integrity_check(db_path: &Path) -> rusqlite::Result<Vec<String>> {
+    let conn =
+        rusqlite::Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+    rows.collect()
+}
+
+/// `(path, sha256, size)` for each entry listed in the manifest, or `None` for
+/// bundles exported before checksums were recorded.
+pub(crate) fn manifest_checksums(
+    manifest: &serde_json::Value,
+) -> Option<Vec<(String, String, u64)>> {
+    let entries = manifest.get("entries")?.as_array()?;
+    Some(
+        entries
+            .iter()
+            .filter_map(|e| {
+                Some((
+                    e.get("path")?.as_str()?.to_string(),
+                    e.get("sha256")?.as_str()?.to_ascii_lowercase(),
+                    e.get("size")?.as_u64()?,
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Fails with `BundleCorrupt` on the first listed entry that is missing,
+/// unreadable, or whose size or digest differs from the manifest.
+pub(crate) fn check_manifest_checksums(
+    archive: &mut ZipArchive<File>,
+    expected: Vec<(String, String, u64)>,
+) -> Result<(), BundleCorrupt> {
+    for (path, sha256, size) in expected {
+        let actual = match archive.by_name(&path) {
+            Ok(mut entry) => sha256_hex(&mut entry).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let mismatch = match actual {
+            Err(e) => Some(format!("unreadable: {}", e)),
+            Ok((actual_sha256, actual_size)) if actual_size != size => Some(format!(
+                "size {} does not match manifest size {} (sha256 {})",
+                actual_size, size, actual_sha256
+            )),
+            Ok((actual_sha256, _)) if actual_sha256 != sha256 => Some(format!(
+                "sha256 {} does not match manifest sha256 {}",
+                actual_sha256, sha256
+            )),
+            Ok(_) => None,
+        };
+        if let Some(message) = mismatch {
+            return Err(BundleCorrupt {
+                entry: path,
+                message,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Streams `reader` through SHA-256, returning the lowercase hex digest and
+/// the number of bytes read.
+pub(crate) fn sha256_hex(reader: &mut impl Read) -> std::io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(reader, &mut hasher)?;
+    let hex = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    Ok((hex, size))
+}
+
+/// Runs `replace` until it succeeds, sleeping through `delays_ms` while the
+/// failure looks like another handle holding `dst` open. Any other error is
+/// returned at once; running out of delays yields `DbInUse`.
+fn replace_with_retry(
+    dst: &Path,
+    delays_ms: &[u64],
+    mut replace: impl FnMut() -> std::io::Result<()>,
+) -> anyhow::Result<()> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let e = match replace() {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if !is_file_in_use(&e) {
+            return Err(e.into());
+        }
+        let Some(delay) = delays_ms.get(attempts - 1) else {
+            return Err(DbInUse {
+                path: dst.to_string_lossy().to_string(),
+                attempts,
+                message: e.to_string(),
+            }
+            .into());
+        };
+        std::thread::sleep(Duration::from_millis(*delay));
+    }
+}
+
+fn is_file_in_use(e: &std::io::Error) -> bool {
+    if cfg!(windows) {
+        // ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION; Windows also reports an
+        // open file as access denied when asked to delete it.
+        e.kind() == std::io::ErrorKind::PermissionDenied
+            || matches!(e.raw_os_error(), Some(32) | Some(33))
+    } else {
+        // Elsewhere an open handle never blocks the replace; access denied is a
+        // real permission problem, and only EBUSY is worth waiting out.
+        e.kind() == std::io::ErrorKind::ResourceBusy
+    }
+}
+
 fn is_zip_file(path: &Path) -> anyhow::Result<bool> {
     let mut f = File::open(path)
         .with_context(|| format!("failed to open input file {}", path.to_string_lossy()))?;
@@ -190,3 +544,130 @@ fn is_zip_file(path: &Path) -> anyhow::Result<bool> {
     }
     Ok(sig == [0x50, 0x4B, 0x03, 0x04])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    fn held() -> Error {
+        if cfg!(windows) {
+            Error::from_raw_os_error(32)
+        } else {
+            Error::new(ErrorKind::ResourceBusy, "file is held open")
+        }
+    }
+
+    fn temp_workspace(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "markbook-{}-{}",
+            name,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("clock")
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).expect("create tmp dir");
+        dir
+    }
+
+    /// Opens `path` the way another program holding the database would. On
+    /// Windows that means denying other handles any sharing.
+    fn hold_open(path: &Path) -> File {
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true);
+        #[cfg(windows)]
+        std::os::windows::fs::OpenOptionsExt::share_mode(&mut options, 0);
+        options.open(path).expect("hold destination open")
+    }
+
+    #[test]
+    fn replace_retries_until_the_handle_is_released() {
+        let mut calls = 0;
+        let result = replace_with_retry(Path::new("markbook.sqlite3"), &[0, 0, 0], || {
+            calls += 1;
+            if calls < 3 {
+                Err(held())
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn replace_reports_db_in_use_when_the_handle_is_never_released() {
+        let mut calls = 0;
+        let e = replace_with_retry(Path::new("markbook.sqlite3"), &[0, 0], || {
+            calls += 1;
+            Err(held())
+        })
+        .expect_err("still held");
+        let in_use = e.downcast_ref::<DbInUse>().expect("DbInUse");
+        assert_eq!(in_use.attempts, 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn replace_does_not_retry_unrelated_errors() {
+        let mut calls = 0;
+        let e = replace_with_retry(Path::new("markbook.sqlite3"), &[0, 0], || {
+            calls += 1;
+            Err(Error::new(ErrorKind::NotFound, "gone"))
+        })
+        .expect_err("not found");
+        assert!(e.downcast_ref::<DbInUse>().is_none());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn permission_denied_counts_as_in_use_only_on_windows() {
+        let mut calls = 0;
+        let _ = replace_with_retry(Path::new("markbook.sqlite3"), &[0, 0], || {
+            calls += 1;
+            Err(Error::new(ErrorKind::PermissionDenied, "denied"))
+        });
+        assert_eq!(calls, if cfg!(windows) { 3 } else { 1 });
+    }
+
+    #[test]
+    fn import_replaces_a_destination_another_handle_holds_open() {
+        let dir = temp_workspace("backup-held");
+        let src = dir.join("backup.sqlite3");
+        let dst = dir.join("markbook.sqlite3");
+        std::fs::write(&src, b"restored").expect("write backup");
+        std::fs::write(&dst, b"current").expect("write workspace db");
+
+        // Windows refuses the replace until the handle goes away, so release it
+        // part way through the backoff; elsewhere the open handle is no obstacle.
+        let handle = hold_open(&dst);
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(120));
+            drop(handle);
+        });
+        let summary = import_workspace_bundle(&src, &dir).expect("import");
+        release.join().expect("release thread");
+        assert_eq!(summary.bundle_format_detected, "legacy-sqlite3");
+        assert_eq!(std::fs::read(&dst).expect("read db"), b"restored");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn import_reports_db_in_use_while_another_handle_holds_the_destination() {
+        let dir = temp_workspace("backup-held-forever");
+        let src = dir.join("backup.sqlite3");
+        let dst = dir.join("markbook.sqlite3");
+        std::fs::write(&src, b"restored").expect("write backup");
+        std::fs::write(&dst, b"current").expect("write workspace db");
+
+        let handle = hold_open(&dst);
+        let e = import_workspace_bundle(&src, &dir).expect_err("still held");
+        drop(handle);
+        let in_use = e.downcast_ref::<DbInUse>().expect("DbInUse");
+        assert_eq!(in_use.attempts, REPLACE_RETRY_DELAYS_MS.len() + 1);
+        assert_eq!(std::fs::read(&dst).expect("read db"), b"current");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -99,6 +99,19 @@ pub struct SummaryFilters {
     pub term: Option<i64>,
     pub category_name: Option<String>,
     pub types_mask: Option<i64>,
+    /// With `term` set, also keep assessments that have no term.
+    #[serde(default)]
+    pub include_untermed: bool,
+}
+
+impl SummaryFilters {
+    pub fn term_matches(&self, term: Option<i64>) -> bool {
+        match (self.term, term) {
+            (None, _) => true,
+            (Some(want), Some(t)) => want == t,
+            (Some(_), None) => self.include_untermed,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -131,6 +144,10 @@ pub struct MarkSetSettings {
     pub day: Option<String>,
     pub period: Option<String>,
     pub weight_method: i64,
+    /// Final-mark method: 0 average, 1 median, 2 mode, 3 blended mode, 4 blended median
+    /// (per-category mode/median combined by category weight), 5 trimmed mean (average after
+    /// dropping the single highest and lowest mark). Values 0..=4 match VB6; anything outside
+    /// 0..=5 is treated as 0.
     pub calc_method: i64,
 }
 
@@ -168,6 +185,10 @@ pub struct AssessmentStats {
     pub avg_raw: f64,
     pub avg_percent: f64,
     pub median_percent: f64,
+    /// Population standard deviation and range of the scored and zero percents; 0 when none.
+    pub std_dev_percent: f64,
+    pub min_percent: f64,
+    pub max_percent: f64,
     pub scored_count: usize,
     pub zero_count: usize,
     pub no_mark_count: usize,
@@ -278,6 +299,7 @@ struct SummaryCategory {
     name: String,
     weight: f64,
     sort_order: i64,
+    drop_lowest: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -389,6 +411,41 @@ struct StudentEntry {
     cat_idx: usize,
 }
 
+/// Removes each category's `drop_by_cat[cat]` lowest marks (by percent). A category with fewer
+/// counted marks than that loses all of them. Order of the remaining items is preserved.
+fn drop_lowest_by_category<T>(
+    items: Vec<T>,
+    drop_by_cat: &[usize],
+    key: impl Fn(&T) -> (usize, f64),
+) -> Vec<T> {
+    if drop_by_cat.iter().all(|n| *n == 0) {
+        return items;
+    }
+    let mut dropped = vec![false; items.len()];
+    for (cat, &n) in drop_by_cat.iter().enumerate() {
+        if n == 0 {
+            continue;
+        }
+        let mut in_cat: Vec<(usize, f64)> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let (c, pct) = key(item);
+                (c == cat).then_some((i, pct))
+            })
+            .collect();
+        in_cat.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        for (i, _) in in_cat.into_iter().take(n) {
+            dropped[i] = true;
+        }
+    }
+    items
+        .into_iter()
+        .zip(dropped)
+        .filter_map(|(item, d)| (!d).then_some(item))
+        .collect()
+}
+
 fn vb6_mode_mark(
     cfg: &ModeConfig,
     entries: &[StudentEntry],
@@ -533,6 +590,62 @@ fn vb6_median_mark(
     pts.last().map(|e| e.pct)
 }
 
+/// Trimmed mean (calc method 5): drops the single highest and single lowest non-BONUS mark, when
+/// at least three are present, and averages the rest with the same entry/category weighting as
+/// the plain average. BONUS is excluded from the trim and added on top as in the average method.
+fn trimmed_mean_mark(
+    entries: &[StudentEntry],
+    wrk_wt_meth: i64,
+    wrk_cat_wt: &[f64],
+    bonus_cat_idx: Option<usize>,
+    bonus_avg: Option<f64>,
+) -> Option<f64> {
+    let mut pts: Vec<StudentEntry> = entries
+        .iter()
+        .copied()
+        .filter(|e| Some(e.cat_idx) != bonus_cat_idx)
+        .collect();
+    pts.sort_by(|a, b| a.pct.partial_cmp(&b.pct).unwrap_or(Ordering::Equal));
+    if pts.len() >= 3 {
+        pts.pop();
+        pts.remove(0);
+    }
+
+    let cat_count = pts.iter().map(|e| e.cat_idx + 1).max()?;
+    let mut cat_sum = vec![0.0_f64; cat_count];
+    let mut cat_wsum = vec![0.0_f64; cat_count];
+    for e in &pts {
+        cat_sum[e.cat_idx] += e.pct * e.entry_wt;
+        cat_wsum[e.cat_idx] += e.entry_wt;
+    }
+    let cat_wt = |cat: usize| {
+        if wrk_wt_meth == 1 {
+            wrk_cat_wt.get(cat).copied().unwrap_or(0.0)
+        } else {
+            cat_wsum[cat]
+        }
+    };
+    let total: f64 = (0..cat_count)
+        .filter(|&cat| cat_wsum[cat] > 0.0)
+        .map(cat_wt)
+        .sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut base = 0.0_f64;
+    for cat in 0..cat_count {
+        if cat_wsum[cat] <= 0.0 {
+            continue;
+        }
+        base += (cat_sum[cat] / cat_wsum[cat]) * (cat_wt(cat) / total);
+    }
+    if let (Some(b), Some(bavg)) = (bonus_cat_idx, bonus_avg) {
+        base += bavg * (wrk_cat_wt.get(b).copied().unwrap_or(0.0) / 100.0);
+    }
+    Some(base)
+}
+
 pub fn parse_summary_filters(raw: Option<&serde_json::Value>) -> Result<SummaryFilters, CalcError> {
     let Some(raw) = raw else {
         return Ok(SummaryFilters::default());
@@ -541,26 +654,8 @@ pub fn parse_summary_filters(raw: Option<&serde_json::Value>) -> Result<SummaryF
         return Err(CalcError::new("bad_params", "filters must be an object"));
     };
 
-    let term = match obj.get("term") {
-        None => None,
-        Some(v) if v.is_null() => None,
-        Some(v)
-            if v.as_str()
-                .map(|s| s.eq_ignore_ascii_case("ALL"))
-                .unwrap_or(false) =>
-        {
-            None
-        }
-        Some(v) => {
-            let Some(n) = v.as_i64() else {
-                return Err(CalcError::new(
-                    "bad_params",
-                    "filters.term must be integer or 'ALL'",
-                ));
-            };
-            Some(n)
-        }
-    };
+    let term = parse_term_value(obj.get("term"), "filters.term")?;
+    let include_untermed = parse_include_untermed(obj.get("includeUntermed"), "filters.")?;
 
     let category_name = match obj.get("categoryName") {
         None => None,
@@ -599,6 +694,54 @@ pub fn parse_summary_filters(raw: Option<&serde_json::Value>) -> Result<SummaryF
         term,
         category_name,
         types_mask,
+        include_untermed,
+    })
+}
+
+/// A term filter value: an integer term, or null / `"ALL"` for every term.
+fn parse_term_value(
+    raw: Option<&serde_json::Value>,
+    field: &str,
+) -> Result<Option<i64>, CalcError> {
+    match raw {
+        None => Ok(None),
+        Some(v) if v.is_null() => Ok(None),
+        Some(v)
+            if v.as_str()
+                .map(|s| s.eq_ignore_ascii_case("ALL"))
+                .unwrap_or(false) =>
+        {
+            Ok(None)
+        }
+        Some(v) => v.as_i64().map(Some).ok_or_else(|| {
+            CalcError::new("bad_params", format!("{} must be integer or 'ALL'", field))
+        }),
+    }
+}
+
+fn parse_include_untermed(
+    raw: Option<&serde_json::Value>,
+    prefix: &str,
+) -> Result<bool, CalcError> {
+    match raw {
+        None => Ok(false),
+        Some(v) if v.is_null() => Ok(false),
+        Some(v) => v.as_bool().ok_or_else(|| {
+            CalcError::new(
+                "bad_params",
+                format!("{}includeUntermed must be a boolean", prefix),
+            )
+        }),
+    }
+}
+
+/// Term-only filters from top-level `term` / `includeUntermed` request params, for
+/// views (mark set open, assessment lists, averages) that show one reporting term.
+pub fn parse_term_filters(params: &serde_json::Value) -> Result<SummaryFilters, CalcError> {
+    Ok(SummaryFilters {
+        term: parse_term_value(params.get("term"), "term")?,
+        include_untermed: parse_include_untermed(params.get("includeUntermed"), "")?,
+        ..SummaryFilters::default()
     })
 }
 
@@ -656,6 +799,60 @@ fn compute_median(values: &[f64]) -> f64 {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionStats {
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub n: usize,
+}
+
+/// Mean, median, population standard deviation and range of `values`; `None` when empty.
+pub fn distribution_stats(values: &[f64]) -> Option<DistributionStats> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len();
+    let mean = values.iter().sum::<f64>() / (n as f64);
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n as f64);
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some(DistributionStats {
+        mean,
+        median: compute_median(values),
+        std_dev: variance.sqrt(),
+        min,
+        max,
+        n,
+    })
+}
+
+/// Standard competition ranks ("1224") for `percents`, highest first: equal percentages share a
+/// rank and the next rank skips past the tie. `None` stays unranked and is left out of the count.
+pub fn competition_ranks(percents: &[Option<f64>]) -> Vec<Option<usize>> {
+    let mut order: Vec<(usize, f64)> = percents
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| p.map(|p| (i, p)))
+        .collect();
+    order.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    let mut ranks = vec![None; percents.len()];
+    let mut prev: Option<f64> = None;
+    let mut rank = 0;
+    for (pos, (i, p)) in order.into_iter().enumerate() {
+        if prev.is_none_or(|q| (q - p).abs() > 1e-9) {
+            rank = pos + 1;
+        }
+        prev = Some(p);
+        ranks[i] = Some(rank);
+    }
+    ranks
+}
+
 fn weighted_average(values: &[(f64, f64)]) -> Option<f64> {
     let mut sum = 0.0_f64;
     let mut denom = 0.0_f64;
@@ -728,6 +925,79 @@ fn weighted_mode(values: &[(f64, f64)]) -> Option<f64> {
     Some((best_key as f64) / 10.0)
 }
 
+/// Under category weighting the non-BONUS category weights are percentages and
+/// should total this much; anything else silently rescales every category.
+pub const CATEGORY_WEIGHT_EXPECTED_TOTAL: f64 = 100.0;
+const CATEGORY_WEIGHT_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryWeight {
+    pub name: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightReport {
+    pub weight_method: i64,
+    pub total: f64,
+    pub expected: f64,
+    pub ok: bool,
+    pub categories: Vec<CategoryWeight>,
+}
+
+/// Sums the mark set's category weights (BONUS excluded, as in the final-mark
+/// calc). Only category weighting (`weight_method == 1`) can be misconfigured;
+/// the report is advisory and never blocks a calculation.
+pub fn validate_category_weights(
+    conn: &Connection,
+    mark_set_id: &str,
+) -> Result<WeightReport, CalcError> {
+    let weight_method: i64 = conn
+        .query_row(
+            "SELECT weight_method FROM mark_sets WHERE id = ?",
+            [mark_set_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?
+        .ok_or_else(|| CalcError::new("not_found", "mark set not found"))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, COALESCE(weight, 0)
+             FROM categories
+             WHERE mark_set_id = ?
+             ORDER BY sort_order",
+        )
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
+    let categories: Vec<CategoryWeight> = stmt
+        .query_map([mark_set_id], |r| {
+            Ok(CategoryWeight {
+                name: r.get(0)?,
+                weight: r.get(1)?,
+            })
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
+
+    let total: f64 = categories
+        .iter()
+        .filter(|c| !c.name.trim().eq_ignore_ascii_case("BONUS"))
+        .map(|c| c.weight)
+        .sum();
+    let ok = weight_method != 1
+        || (total - CATEGORY_WEIGHT_EXPECTED_TOTAL).abs() <= CATEGORY_WEIGHT_TOLERANCE;
+    Ok(WeightReport {
+        weight_method,
+        total,
+        expected: CATEGORY_WEIGHT_EXPECTED_TOTAL,
+        ok,
+        categories,
+    })
+}
+
 pub fn compute_assessment_stats(
     ctx: &CalcContext<'_>,
     filters: &SummaryFilters,
@@ -738,6 +1008,16 @@ pub fn compute_assessment_stats(
 pub fn compute_mark_set_summary(
     ctx: &CalcContext<'_>,
     filters: &SummaryFilters,
+) -> Result<SummaryModel, CalcError> {
+    compute_mark_set_summary_with(ctx, filters, &HashMap::new())
+}
+
+/// As `compute_mark_set_summary`, with `overrides` standing in for the stored score of each
+/// (assessment id, student id) pair. Nothing is written.
+fn compute_mark_set_summary_with(
+    ctx: &CalcContext<'_>,
+    filters: &SummaryFilters,
+    overrides: &HashMap<(String, String), ScoreState>,
 ) -> Result<SummaryModel, CalcError> {
     let conn = ctx.conn;
     let class_id = ctx.class_id;
@@ -800,13 +1080,14 @@ pub fn compute_mark_set_summary(
         return Err(CalcError::new("not_found", "mark set not found"));
     };
 
-    let calc_method_applied = if (0..=4).contains(&calc_method) {
+    let calc_method_applied = if (0..=5).contains(&calc_method) {
         calc_method
     } else {
         0
     };
+    let blended = matches!(calc_method_applied, 3 | 4);
     let mut filters_applied = filters.clone();
-    if calc_method_applied > 2 {
+    if blended {
         // VB6 EvalOne_Calculate forces category filter to [ALL] for blended methods.
         filters_applied.category_name = None;
     }
@@ -815,7 +1096,7 @@ pub fn compute_mark_set_summary(
         .prepare(
             "SELECT id, last_name, first_name, sort_order, active, COALESCE(mark_set_mask, 'TBA')
              FROM students
-             WHERE class_id = ?
+             WHERE class_id = ? AND archived = 0
              ORDER BY sort_order",
         )
         .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
@@ -837,7 +1118,7 @@ pub fn compute_mark_set_summary(
 
     let mut categories_stmt = conn
         .prepare(
-            "SELECT name, COALESCE(weight, 0), sort_order
+            "SELECT name, COALESCE(weight, 0), sort_order, COALESCE(drop_lowest, 0)
              FROM categories
              WHERE mark_set_id = ?
              ORDER BY sort_order",
@@ -849,6 +1130,7 @@ pub fn compute_mark_set_summary(
                 name: r.get(0)?,
                 weight: r.get::<_, f64>(1)?,
                 sort_order: r.get(2)?,
+                drop_lowest: usize::try_from(r.get::<_, i64>(3)?).unwrap_or(0),
             })
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
@@ -882,10 +1164,7 @@ pub fn compute_mark_set_summary(
     let selected_assessments: Vec<SummaryAssessment> = all_assessments
         .iter()
         .filter(|a| {
-            let term_ok = filters_applied
-                .term
-                .map(|t| a.term == Some(t))
-                .unwrap_or(true);
+            let term_ok = filters_applied.term_matches(a.term);
             let cat_ok = filters_applied
                 .category_name
                 .as_ref()
@@ -956,6 +1235,9 @@ pub fn compute_mark_set_summary(
             score_by_pair.insert((assessment_id, student_id), state);
         }
     }
+    for (pair, state) in overrides {
+        score_by_pair.insert(pair.clone(), *state);
+    }
 
     let mut per_assessment: Vec<AssessmentStats> = Vec::new();
     for a in &selected_assessments {
@@ -984,6 +1266,7 @@ pub fn compute_mark_set_summary(
         }
 
         let stats = assessment_average(score_states, a.out_of);
+        let spread = distribution_stats(&median_values);
         per_assessment.push(AssessmentStats {
             assessment_id: a.id.clone(),
             idx: a.idx,
@@ -994,6 +1277,9 @@ pub fn compute_mark_set_summary(
             avg_raw: round_off_1_decimal(stats.avg_raw),
             avg_percent: round_off_1_decimal(stats.avg_percent),
             median_percent: round_off_1_decimal(compute_median(&median_values)),
+            std_dev_percent: spread.map_or(0.0, |d| round_off_1_decimal(d.std_dev)),
+            min_percent: spread.map_or(0.0, |d| round_off_1_decimal(d.min)),
+            max_percent: spread.map_or(0.0, |d| round_off_1_decimal(d.max)),
             scored_count: stats.scored_count,
             zero_count: stats.zero_count,
             no_mark_count: stats.no_mark_count,
@@ -1032,8 +1318,8 @@ pub fn compute_mark_set_summary(
     // VB6: if calc method is blended (3/4), force category weighting and ignore category filter.
     // We reflect that in calc computations. (Caller-provided filter value is still returned in
     // `settings`, but `filters` in the response reflects what was actually applied.)
-    let ev_wt_meth_for_weights = if calc_method_applied > 2 { 1 } else { weight_method_setting };
-    let weight_method_applied = if calc_method_applied > 2 {
+    let ev_wt_meth_for_weights = if blended { 1 } else { weight_method_setting };
+    let weight_method_applied = if blended {
         1
     } else if weight_method_setting == 1 && non_bonus_cat_weight_sum == 0.0 {
         0
@@ -1066,6 +1352,8 @@ pub fn compute_mark_set_summary(
         })
         .collect();
 
+    let cat_drop_lowest: Vec<usize> = categories.iter().map(|c| c.drop_lowest).collect();
+
     let mut excluded_by_weight_count = 0usize;
     let mut excluded_by_category_weight_count = 0usize;
 
@@ -1128,6 +1416,7 @@ pub fn compute_mark_set_summary(
         let mut cat_wsum: Vec<f64> = vec![0.0; cat_count];
         let mut cat_has_nonzero: Vec<bool> = vec![false; cat_count];
         let mut entries: Vec<StudentEntry> = Vec::new();
+        let mut candidates: Vec<(StudentEntry, bool)> = Vec::new();
 
         if valid_kid {
             for a in &selected_assessments_for_calc {
@@ -1154,9 +1443,6 @@ pub fn compute_mark_set_summary(
                     }
                     ScoreState::Scored(v) => {
                         scored_count += 1;
-                        if v > 0.0 {
-                            cat_has_nonzero[cat_idx] = true;
-                        }
                         if a.out_of > 0.0 {
                             Some(100.0 * v / a.out_of)
                         } else {
@@ -1168,14 +1454,26 @@ pub fn compute_mark_set_summary(
                     continue;
                 };
                 let entry_wt = if ev_wt_meth_for_weights == 2 { 1.0 } else { a.weight };
-                cat_sum[cat_idx] += pct * entry_wt;
-                cat_wsum[cat_idx] += entry_wt;
-                entries.push(StudentEntry {
-                    pct,
-                    entry_wt,
-                    cat_idx,
-                });
+                let nonzero = matches!(state, ScoreState::Scored(v) if v > 0.0);
+                candidates.push((
+                    StudentEntry {
+                        pct,
+                        entry_wt,
+                        cat_idx,
+                    },
+                    nonzero,
+                ));
+            }
+        }
+        let candidates =
+            drop_lowest_by_category(candidates, &cat_drop_lowest, |(e, _)| (e.cat_idx, e.pct));
+        for (e, nonzero) in candidates {
+            if nonzero {
+                cat_has_nonzero[e.cat_idx] = true;
             }
+            cat_sum[e.cat_idx] += e.pct * e.entry_wt;
+            cat_wsum[e.cat_idx] += e.entry_wt;
+            entries.push(e);
         }
 
         // VB6 EV_CatWT(k,0): overall denominator excludes BONUS.
@@ -1286,10 +1584,8 @@ pub fn compute_mark_set_summary(
                                 let mut entries_modecats: Vec<StudentEntry> = Vec::new();
                                 for a in &all_assessments {
                                     // Term filter only.
-                                    if let Some(t) = filters_applied.term {
-                                        if a.term != Some(t) {
-                                            continue;
-                                        }
+                                    if !filters_applied.term_matches(a.term) {
+                                        continue;
                                     }
                                     if a.weight <= 0.0 {
                                         continue;
@@ -1339,6 +1635,11 @@ pub fn compute_mark_set_summary(
                                         cat_idx: cat,
                                     });
                                 }
+                                let entries_modecats = drop_lowest_by_category(
+                                    entries_modecats,
+                                    &cat_drop_lowest,
+                                    |e| (e.cat_idx, e.pct),
+                                );
                                 vb6_mode_mark(
                                     &mode_cfg,
                                     &entries_modecats,
@@ -1370,6 +1671,13 @@ pub fn compute_mark_set_summary(
                         Some(total)
                     }
                 }
+                5 => trimmed_mean_mark(
+                    &entries,
+                    wrk_wt_meth,
+                    &wrk_cat_wt,
+                    bonus_cat_idx,
+                    bonus_cat_idx.and_then(|b| cat_avg[b]),
+                ),
                 _ => {
                     // Average with BONUS add-on outside denominator.
                     let mut base = 0.0_f64;
@@ -1537,6 +1845,348 @@ pub fn compute_mark_set_summary(
     })
 }
 
+/// Final percentage per student in a mark set (`final_mark`), honoring the mark set's weight
+/// and calc methods, over the assessments `filters` keeps. The one path behind
+/// `calc.markSetAverages` and `reports.honorRoll`; `final_mark` is `None` when a student has
+/// no counted marks (or is not a valid kid for the set).
+pub fn markset_averages(
+    ctx: &CalcContext,
+    filters: &SummaryFilters,
+) -> Result<Vec<StudentFinal>, CalcError> {
+    Ok(compute_mark_set_summary(ctx, filters)?.per_student)
+}
+
+/// One student's entry from `markset_averages`. A thin wrapper: the whole mark set is still
+/// computed, so callers that need several students should use `markset_averages`.
+pub fn markset_average(
+    conn: &Connection,
+    mark_set_id: &str,
+    student_id: &str,
+    filters: &SummaryFilters,
+) -> Result<Option<f64>, CalcError> {
+    let class_id: Option<String> = conn
+        .query_row(
+            "SELECT class_id FROM mark_sets WHERE id = ?",
+            [mark_set_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
+    let Some(class_id) = class_id else {
+        return Err(CalcError::new("not_found", "mark set not found"));
+    };
+
+    let ctx = CalcContext {
+        conn,
+        class_id: &class_id,
+        mark_set_id,
+    };
+    markset_averages(&ctx, filters)?
+        .into_iter()
+        .find(|s| s.student_id == student_id)
+        .map(|s| s.final_mark)
+        .ok_or_else(|| CalcError::new("not_found", "student not found"))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeededProjection {
+    pub target_percent: f64,
+    pub out_of: f64,
+    /// Final mark with the scores as stored.
+    pub current_mark: Option<f64>,
+    /// Final mark with full marks on the assessment.
+    pub max_mark: Option<f64>,
+    /// Smallest raw score (to 0.01) that reaches the target; `None` when it is out of reach.
+    pub needed_raw: Option<f64>,
+    pub needed_percent: Option<f64>,
+    /// Final mark with `needed_raw` on the assessment.
+    pub projected_mark: Option<f64>,
+    /// Even a score of 0 reaches the target.
+    pub already_achieved: bool,
+    /// Not even full marks reach the target.
+    pub impossible: bool,
+}
+
+/// What a student needs on `assessment_id` for their final mark in the set to reach
+/// `target_percent`, with every other score as stored. Found by bisection over the assessment's
+/// raw range, which relies on a higher score never lowering the final mark. That holds for
+/// average and median marks but not for the mode (calc methods 2 and 3), where one score can
+/// move the mark into a different level, so those methods are rejected.
+pub fn project_needed(
+    ctx: &CalcContext<'_>,
+    student_id: &str,
+    assessment_id: &str,
+    target_percent: f64,
+) -> Result<NeededProjection, CalcError> {
+    let student_mark = |summary: SummaryModel| -> Result<Option<f64>, CalcError> {
+        summary
+            .per_student
+            .into_iter()
+            .find(|s| s.student_id == student_id)
+            .map(|s| s.final_mark)
+            .ok_or_else(|| CalcError::new("not_found", "student not found"))
+    };
+    let final_mark = |state: ScoreState| -> Result<Option<f64>, CalcError> {
+        let overrides =
+            HashMap::from([((assessment_id.to_string(), student_id.to_string()), state)]);
+        student_mark(compute_mark_set_summary_with(
+            ctx,
+            &SummaryFilters::default(),
+            &overrides,
+        )?)
+    };
+    let reaches = |mark: Option<f64>| mark.is_some_and(|m| m + 1e-9 >= target_percent);
+
+    // Resolves the class, mark set and student before the assessment.
+    let current = compute_mark_set_summary(ctx, &SummaryFilters::default())?;
+    let calc_method = current
+        .settings_applied
+        .as_ref()
+        .map(|s| s.calc_method_applied)
+        .unwrap_or(0);
+    let current_mark = student_mark(current)?;
+    if matches!(calc_method, 2 | 3) {
+        return Err(CalcError {
+            details: Some(serde_json::json!({ "calcMethod": calc_method })),
+            ..CalcError::new(
+                "bad_params",
+                "cannot project a needed score under a mode-based calc method",
+            )
+        });
+    }
+    let out_of: Option<Option<f64>> = ctx
+        .conn
+        .query_row(
+            "SELECT out_of FROM assessments WHERE id = ? AND mark_set_id = ?",
+            (assessment_id, ctx.mark_set_id),
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
+    let Some(out_of) = out_of else {
+        return Err(CalcError::new("not_found", "assessment not found"));
+    };
+    let out_of = out_of.unwrap_or(0.0);
+    if out_of <= 0.0 {
+        return Err(CalcError::new(
+            "bad_params",
+            "assessment has no outOf to project against",
+        ));
+    }
+
+    let max_mark = final_mark(ScoreState::Scored(out_of))?;
+    let mut projection = NeededProjection {
+        target_percent,
+        out_of,
+        current_mark,
+        max_mark,
+        needed_raw: None,
+        needed_percent: None,
+        projected_mark: None,
+        already_achieved: false,
+        impossible: false,
+    };
+    if !reaches(max_mark) {
+        projection.impossible = true;
+        return Ok(projection);
+    }
+    let floor_mark = final_mark(ScoreState::Scored(0.0))?;
+    if reaches(floor_mark) {
+        projection.already_achieved = true;
+        projection.needed_raw = Some(0.0);
+        projection.needed_percent = Some(0.0);
+        projection.projected_mark = floor_mark;
+        return Ok(projection);
+    }
+
+    // `lo` never reaches the target and `hi` always does.
+    let (mut lo, mut hi) = (0.0_f64, out_of);
+    while hi - lo > 0.005 {
+        let mid = (lo + hi) / 2.0;
+        if reaches(final_mark(ScoreState::Scored(mid))?) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    // Report a whole hundredth: the one at or below `hi` when it still reaches the target.
+    let down = (hi * 100.0).floor() / 100.0;
+    let down_mark = final_mark(ScoreState::Scored(down))?;
+    let (needed, projected) = if reaches(down_mark) {
+        (down, down_mark)
+    } else {
+        let up = ((hi * 100.0).ceil() / 100.0).min(out_of);
+        (up, final_mark(ScoreState::Scored(up))?)
+    };
+    projection.needed_raw = Some(needed);
+    projection.needed_percent = Some(round_off_1_decimal(100.0 * needed / out_of));
+    projection.projected_mark = projected;
+    Ok(projection)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeBand {
+    pub min_percent: f64,
+    pub label: String,
+}
+
+/// Workspace letter-grade scale, highest band first.
+pub fn load_grade_scale(conn: &Connection) -> Result<Vec<GradeBand>, CalcError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT min_percent, label
+             FROM grade_scales
+             ORDER BY min_percent DESC",
+        )
+        .map_err(|e| CalcError::new("db_query_failed", e.to_string()))?;
+    stmt.query_map([], |r| {
+        Ok(GradeBand {
+            min_percent: r.get(0)?,
+            label: r.get(1)?,
+        })
+    })
+    .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    .map_err(|e| CalcError::new("db_query_failed", e.to_string()))
+}
+
+/// Label of the highest band whose minimum is at or below `pct`. A percentage sitting exactly on
+/// a boundary belongs to the upper band; anything above the top band gets the top label. Returns
+/// `None` when `pct` falls below every band.
+pub fn letter_for_percent(scale: &[GradeBand], pct: f64) -> Option<&str> {
+    scale
+        .iter()
+        .filter(|b| pct + 1e-9 >= b.min_percent)
+        .max_by(|a, b| {
+            a.min_percent
+                .partial_cmp(&b.min_percent)
+                .unwrap_or(Ordering::Equal)
+        })
+        .map(|b| b.label.as_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pronouns {
+    He,
+    She,
+    They,
+}
+
+impl Pronouns {
+    /// Accepts the stored form ("he"/"she"/"they") and the common slash forms
+    /// ("she/her", "they/them").
+    pub fn parse(raw: &str) -> Option<Pronouns> {
+        let head = raw.trim().split('/').next().unwrap_or_default();
+        match head.trim().to_ascii_lowercase().as_str() {
+            "he" => Some(Pronouns::He),
+            "she" => Some(Pronouns::She),
+            "they" => Some(Pronouns::They),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Pronouns::He => "he",
+            Pronouns::She => "she",
+            Pronouns::They => "they",
+        }
+    }
+
+    fn subject(self) -> &'static str {
+        self.as_str()
+    }
+
+    fn object(self) -> &'static str {
+        match self {
+            Pronouns::He => "him",
+            Pronouns::She => "her",
+            Pronouns::They => "them",
+        }
+    }
+
+    fn possessive(self) -> &'static str {
+        match self {
+            Pronouns::He => "his",
+            Pronouns::She => "her",
+            Pronouns::They => "their",
+        }
+    }
+}
+
+pub struct CommentStudent<'a> {
+    pub first_name: &'a str,
+    pub last_name: &'a str,
+    pub pronouns: Option<Pronouns>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpandedComment {
+    pub text: String,
+    /// Placeholders (with braces) that were not recognised and left as-is.
+    pub unknown_fields: Vec<String>,
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Expands `{firstName}`, `{lastName}` and pronoun fields in a comment template.
+/// Pronoun fields name the he/she forms (`{he/she}`, `{him/her}`, `{his/her}`);
+/// a leading capital (`{He/She}`) capitalises the result. Students without
+/// pronouns get they/them/their.
+pub fn expand_comment(template: &str, student: &CommentStudent) -> ExpandedComment {
+    let pronouns = student.pronouns.unwrap_or(Pronouns::They);
+    let mut text = String::with_capacity(template.len());
+    let mut unknown_fields: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after
+            .find(['{', '}'])
+            .filter(|i| after[*i..].starts_with('}'))
+        else {
+            text.push('{');
+            rest = after;
+            continue;
+        };
+        let field = &after[..close];
+        let value = match field.to_ascii_lowercase().as_str() {
+            "firstname" => Some(student.first_name.to_string()),
+            "lastname" => Some(student.last_name.to_string()),
+            "he/she" => Some(pronouns.subject().to_string()),
+            "him/her" => Some(pronouns.object().to_string()),
+            "his/her" => Some(pronouns.possessive().to_string()),
+            _ => None,
+        };
+        match value {
+            Some(v) if field.contains('/') && field.starts_with(char::is_uppercase) => {
+                text.push_str(&capitalize(&v))
+            }
+            Some(v) => text.push_str(&v),
+            None => {
+                let placeholder = format!("{{{}}}", field);
+                text.push_str(&placeholder);
+                if !unknown_fields.contains(&placeholder) {
+                    unknown_fields.push(placeholder);
+                }
+            }
+        }
+        rest = &after[close + 1..];
+    }
+    text.push_str(rest);
+    ExpandedComment {
+        text,
+        unknown_fields,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1594,6 +2244,22 @@ mod tests {
         assert_eq!(parsed.types_mask, None);
     }
 
+    #[test]
+    fn term_filter_keeps_untermed_only_when_asked() {
+        let raw = serde_json::json!({ "term": 2, "includeUntermed": true });
+        let parsed = parse_summary_filters(Some(&raw)).expect("parse filters");
+        assert!(parsed.term_matches(Some(2)));
+        assert!(parsed.term_matches(None));
+        assert!(!parsed.term_matches(Some(1)));
+
+        let strict = SummaryFilters {
+            term: Some(2),
+            ..SummaryFilters::default()
+        };
+        assert!(!strict.term_matches(None));
+        assert!(SummaryFilters::default().term_matches(None));
+    }
+
     #[test]
     fn weighted_median_respects_weights() {
         let values = vec![(10.0, 1.0), (20.0, 1.0), (90.0, 8.0)];
@@ -1614,4 +2280,178 @@ mod tests {
         let m = weighted_mode(&values).expect("weighted mode");
         assert!((m - 80.0).abs() < 1e-9);
     }
+
+    #[test]
+    fn letter_for_percent_uses_upper_band_on_boundaries() {
+        let scale = vec![
+            GradeBand {
+                min_percent: 80.0,
+                label: "A".to_string(),
+            },
+            GradeBand {
+                min_percent: 70.0,
+                label: "B".to_string(),
+            },
+            GradeBand {
+                min_percent: 50.0,
+                label: "C".to_string(),
+            },
+        ];
+        assert_eq!(letter_for_percent(&scale, 80.0), Some("A"));
+        assert_eq!(letter_for_percent(&scale, 79.9), Some("B"));
+        assert_eq!(letter_for_percent(&scale, 70.0), Some("B"));
+        assert_eq!(letter_for_percent(&scale, 104.5), Some("A"));
+        assert_eq!(letter_for_percent(&scale, 49.9), None);
+        assert_eq!(letter_for_percent(&[], 90.0), None);
+    }
+
+    #[test]
+    fn markset_average_drops_lowest_quiz_per_category() {
+        let dir =
+            std::env::temp_dir().join(format!("markbook-calc-drop-lowest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let conn = db::open_db(&dir).expect("open db");
+
+        conn.execute("INSERT INTO classes(id, name) VALUES('c1', 'Math')", [])
+            .expect("class");
+        conn.execute(
+            "INSERT INTO students(id, class_id, last_name, first_name, active, sort_order, raw_line)
+             VALUES('s1', 'c1', 'Lee', 'Sam', 1, 0, '')",
+            [],
+        )
+        .expect("student");
+        conn.execute(
+            "INSERT INTO mark_sets(id, class_id, code, file_prefix, description, sort_order)
+             VALUES('m1', 'c1', 'T1', 'T1', 'Term 1', 0)",
+            [],
+        )
+        .expect("mark set");
+        conn.execute(
+            "INSERT INTO categories(id, mark_set_id, name, weight, sort_order, drop_lowest)
+             VALUES('k1', 'm1', 'Quizzes', 40, 0, 1), ('k2', 'm1', 'Tests', 60, 1, NULL)",
+            [],
+        )
+        .expect("categories");
+
+        let marks = [
+            ("Quizzes", 10.0, 10.0),
+            ("Quizzes", 10.0, 9.0),
+            ("Quizzes", 10.0, 2.0),
+            ("Quizzes", 10.0, 8.0),
+            ("Quizzes", 10.0, 7.0),
+            ("Tests", 100.0, 70.0),
+        ];
+        for (i, (cat, out_of, raw)) in marks.iter().enumerate() {
+            let assessment_id = format!("a{}", i);
+            conn.execute(
+                "INSERT INTO assessments(id, mark_set_id, idx, category_name, title, weight, out_of)
+                 VALUES(?, 'm1', ?, ?, ?, 1, ?)",
+                (&assessment_id, i as i64, cat, format!("Item {}", i), out_of),
+            )
+            .expect("assessment");
+            conn.execute(
+                "INSERT INTO scores(id, assessment_id, student_id, raw_value, status)
+                 VALUES(?, ?, 's1', ?, 'scored')",
+                (format!("sc{}", i), &assessment_id, raw),
+            )
+            .expect("score");
+        }
+
+        // Quizzes drop the 2/10: (100 + 90 + 80 + 70) / 4 = 85; 0.4 * 85 + 0.6 * 70 = 76.
+        let pct = markset_average(&conn, "m1", "s1", &SummaryFilters::default()).expect("average");
+        assert_eq!(pct, Some(76.0));
+
+        // Dropping more quizzes than were written leaves only the Tests category.
+        conn.execute("UPDATE categories SET drop_lowest = 9 WHERE id = 'k1'", [])
+            .expect("update");
+        let pct = markset_average(&conn, "m1", "s1", &SummaryFilters::default()).expect("average");
+        assert_eq!(pct, Some(70.0));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn distribution_stats_uses_population_std_dev() {
+        assert_eq!(distribution_stats(&[]), None);
+
+        let stats = distribution_stats(&[90.0, 70.0, 80.0, 60.0]).expect("stats");
+        assert_eq!(stats.n, 4);
+        assert!((stats.mean - 75.0).abs() < 1e-9);
+        assert!((stats.median - 75.0).abs() < 1e-9);
+        assert!((stats.std_dev - 125.0_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(stats.min, 60.0);
+        assert_eq!(stats.max, 90.0);
+    }
+
+    #[test]
+    fn competition_ranks_share_ties_and_skip_after_them() {
+        assert_eq!(competition_ranks(&[]), Vec::<Option<usize>>::new());
+        assert_eq!(
+            competition_ranks(&[Some(70.0), Some(90.0), Some(80.0), Some(90.0), Some(60.0)]),
+            vec![Some(4), Some(1), Some(3), Some(1), Some(5)]
+        );
+        assert_eq!(
+            competition_ranks(&[Some(50.0), Some(50.0), Some(50.0)]),
+            vec![Some(1), Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn competition_ranks_leave_missing_averages_unranked() {
+        assert_eq!(
+            competition_ranks(&[None, Some(88.5), Some(72.0), None, Some(88.5)]),
+            vec![None, Some(1), Some(3), None, Some(1)]
+        );
+        assert_eq!(competition_ranks(&[None, None]), vec![None, None]);
+    }
+
+    fn comment_student(pronouns: Option<Pronouns>) -> CommentStudent<'static> {
+        CommentStudent {
+            first_name: "Sam",
+            last_name: "Lee",
+            pronouns,
+        }
+    }
+
+    #[test]
+    fn expand_comment_substitutes_subject_object_and_possessive_forms() {
+        let template = "{He/She} finished {his/her} lab; I thanked {him/her}.";
+        let cases = [
+            (Some(Pronouns::He), "He finished his lab; I thanked him."),
+            (Some(Pronouns::She), "She finished her lab; I thanked her."),
+            (
+                Some(Pronouns::They),
+                "They finished their lab; I thanked them.",
+            ),
+            (None, "They finished their lab; I thanked them."),
+        ];
+        for (pronouns, expected) in cases {
+            let out = expand_comment(template, &comment_student(pronouns));
+            assert_eq!(out.text, expected);
+            assert!(out.unknown_fields.is_empty());
+        }
+        let lower = expand_comment(
+            "and {he/she} {His/Her}",
+            &comment_student(Some(Pronouns::She)),
+        );
+        assert_eq!(lower.text, "and she Her");
+    }
+
+    #[test]
+    fn expand_comment_fills_names_and_keeps_unknown_placeholders() {
+        let out = expand_comment(
+            "{firstName} {lastName}: {grade} {grade} {unclosed",
+            &comment_student(Some(Pronouns::He)),
+        );
+        assert_eq!(out.text, "Sam Lee: {grade} {grade} {unclosed");
+        assert_eq!(out.unknown_fields, vec!["{grade}".to_string()]);
+    }
+
+    #[test]
+    fn pronouns_parse_accepts_slash_forms() {
+        assert_eq!(Pronouns::parse("She/Her"), Some(Pronouns::She));
+        assert_eq!(Pronouns::parse(" they "), Some(Pronouns::They));
+        assert_eq!(Pronouns::parse("he/him"), Some(Pronouns::He));
+        assert_eq!(Pronouns::parse("xe"), None);
+    }
 }
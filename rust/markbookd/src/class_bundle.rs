@@ -0,0 +1,391 @@
+use crate::backup::{
+    check_manifest_checksums, manifest_checksums, sha256_hex, BundleCorrupt, MANIFEST_ENTRY,
+};
+use crate::db;
+use anyhow::{anyhow, Context};
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const CLASS_ROWS_ENTRY: &str = "class/rows.json";
+pub const CLASS_BUNDLE_FORMAT_V1: &str = "markbook-class-v1";
+
+/// Outcome of a class bundle export or import. `class_id` is the class in the
+/// workspace the bundle was read from or written into; `counts` has one
+/// `(label, rows)` pair per class table, in `CLASS_TABLES` order.
+#[derive(Debug, Clone)]
+pub struct ClassBundleSummary {
+    pub class_id: String,
+    pub class_name: String,
+    pub counts: Vec<(&'static str, usize)>,
+}
+
+/// A class-owned table carried by class bundles.
+struct ClassTable {
+    name: &'static str,
+    /// Key used for the table's row count in results.
+    label: &'static str,
+    /// Selects the class's rows; `?1` is the class id.
+    scope: &'static str,
+    /// Whether `id` is a row id that gets a fresh UUID on import.
+    fresh_id: bool,
+    /// Columns holding ids of rows remapped earlier in the import.
+    refs: &'static [&'static str],
+}
+
+/// Class tables in insert order: every table comes after the tables its
+/// `refs` point at. Mirrors the tables `classes.delete` clears.
+const CLASS_TABLES: &[ClassTable] = &[
+    ClassTable {
+        name: "class_meta",
+        label: "classMeta",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "students",
+        label: "students",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "student_notes",
+        label: "studentNotes",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id", "student_id"],
+    },
+    ClassTable {
+        name: "learning_skills_cells",
+        label: "learningSkills",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id", "student_id"],
+    },
+    ClassTable {
+        name: "student_device_map",
+        label: "deviceMappings",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id", "student_id"],
+    },
+    ClassTable {
+        name: "attendance_settings",
+        label: "attendanceSettings",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "attendance_months",
+        label: "attendanceMonths",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "attendance_student_months",
+        label: "attendanceStudentMonths",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id", "student_id"],
+    },
+    ClassTable {
+        name: "mark_sets",
+        label: "markSets",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "categories",
+        label: "categories",
+        scope: "mark_set_id IN (SELECT id FROM mark_sets WHERE class_id = ?1)",
+        fresh_id: true,
+        refs: &["mark_set_id"],
+    },
+    ClassTable {
+        name: "assessments",
+        label: "assessments",
+        scope: "mark_set_id IN (SELECT id FROM mark_sets WHERE class_id = ?1)",
+        fresh_id: true,
+        refs: &["mark_set_id"],
+    },
+    ClassTable {
+        name: "scores",
+        label: "scores",
+        scope: "assessment_id IN (
+            SELECT a.id FROM assessments a
+            JOIN mark_sets ms ON ms.id = a.mark_set_id
+            WHERE ms.class_id = ?1
+        )",
+        fresh_id: true,
+        refs: &["assessment_id", "student_id"],
+    },
+    ClassTable {
+        name: "seating_plans",
+        label: "seatingPlans",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id"],
+    },
+    ClassTable {
+        name: "seating_assignments",
+        label: "seatingAssignments",
+        scope: "class_id = ?1",
+        fresh_id: false,
+        refs: &["class_id", "plan_id", "student_id"],
+    },
+    ClassTable {
+        name: "comment_set_indexes",
+        label: "commentSets",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id", "mark_set_id"],
+    },
+    ClassTable {
+        name: "comment_set_remarks",
+        label: "commentRemarks",
+        scope: "comment_set_index_id IN (SELECT id FROM comment_set_indexes WHERE class_id = ?1)",
+        fresh_id: true,
+        refs: &["comment_set_index_id", "student_id"],
+    },
+    ClassTable {
+        name: "loaned_items",
+        label: "loanedItems",
+        scope: "class_id = ?1",
+        fresh_id: true,
+        refs: &["class_id", "student_id", "mark_set_id"],
+    },
+];
+
+/// Writes one class's rows to a zip holding a manifest and a single JSON entry
+/// keyed by table name. Rows keep their column names, so a bundle can be read
+/// back by a build whose tables gained or lost columns since.
+pub fn export_class_bundle(
+    conn: &Connection,
+    class_id: &str,
+    out_path: &Path,
+) -> anyhow::Result<ClassBundleSummary> {
+    let class_name: String = conn
+        .query_row("SELECT name FROM classes WHERE id = ?", [class_id], |r| {
+            r.get(0)
+        })
+        .context("failed to read class")?;
+
+    let mut tables = serde_json::Map::new();
+    let mut counts = Vec::with_capacity(CLASS_TABLES.len());
+    for table in CLASS_TABLES {
+        let rows = select_class_rows(conn, table, class_id)
+            .with_context(|| format!("failed to read {}", table.name))?;
+        counts.push((table.label, rows.len()));
+        tables.insert(table.name.to_string(), serde_json::Value::Array(rows));
+    }
+    let rows_text = serde_json::to_string(&json!({
+        "class": { "id": class_id, "name": class_name },
+        "tables": tables,
+    }))
+    .context("failed to serialize class rows")?;
+    let (rows_sha256, rows_size) =
+        sha256_hex(&mut rows_text.as_bytes()).context("failed to hash class rows")?;
+
+    if let Some(parent) = out_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.to_string_lossy()))?;
+    }
+    let out_file = File::create(out_path).with_context(|| {
+        format!(
+            "failed to create output file {}",
+            out_path.to_string_lossy()
+        )
+    })?;
+    let mut zip = ZipWriter::new(out_file);
+    let opts = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let exported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let manifest = json!({
+        "format": CLASS_BUNDLE_FORMAT_V1,
+        "version": 1,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "exportedAt": exported_at,
+        "className": class_name,
+        "entries": [
+            { "path": CLASS_ROWS_ENTRY, "sha256": rows_sha256, "size": rows_size },
+        ],
+    });
+    zip.start_file(MANIFEST_ENTRY, opts)
+        .context("failed to start manifest entry")?;
+    zip.write_all(
+        serde_json::to_string_pretty(&manifest)
+            .context("failed to serialize manifest")?
+            .as_bytes(),
+    )
+    .context("failed to write manifest entry")?;
+    zip.start_file(CLASS_ROWS_ENTRY, opts)
+        .context("failed to start class rows entry")?;
+    zip.write_all(rows_text.as_bytes())
+        .context("failed to write class rows entry")?;
+    zip.finish().context("failed to finalize zip bundle")?;
+
+    Ok(ClassBundleSummary {
+        class_id: class_id.to_string(),
+        class_name,
+        counts,
+    })
+}
+
+/// Inserts a class bundle into `conn` as a new class. Every row id is replaced
+/// with a fresh UUID and references are rewritten to match, so the same bundle
+/// can be imported repeatedly, or into the workspace it came from. Runs in one
+/// transaction; a failure leaves the workspace untouched.
+pub fn import_class_bundle(
+    conn: &Connection,
+    in_path: &Path,
+) -> anyhow::Result<ClassBundleSummary> {
+    let in_file = File::open(in_path)
+        .with_context(|| format!("failed to open bundle {}", in_path.to_string_lossy()))?;
+    let mut archive = ZipArchive::new(in_file).context("invalid zip archive")?;
+
+    let mut manifest_text = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .context("bundle missing manifest.json")?
+        .read_to_string(&mut manifest_text)
+        .context("failed to read manifest.json")?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&manifest_text).context("manifest.json is invalid JSON")?;
+    let format = manifest
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if format != CLASS_BUNDLE_FORMAT_V1 {
+        return Err(anyhow!("unsupported class bundle format: {}", format));
+    }
+    check_manifest_checksums(
+        &mut archive,
+        manifest_checksums(&manifest).unwrap_or_default(),
+    )?;
+
+    let mut rows_text = String::new();
+    archive
+        .by_name(CLASS_ROWS_ENTRY)
+        .context("bundle missing class/rows.json")?
+        .read_to_string(&mut rows_text)
+        .context("failed to read class/rows.json")?;
+    let corrupt = |message: &str| BundleCorrupt {
+        entry: CLASS_ROWS_ENTRY.to_string(),
+        message: message.to_string(),
+    };
+    let rows: serde_json::Value =
+        serde_json::from_str(&rows_text).map_err(|e| corrupt(&e.to_string()))?;
+    let old_class_id = rows
+        .pointer("/class/id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| corrupt("missing class id"))?;
+    let class_name = rows
+        .pointer("/class/name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| corrupt("missing class name"))?
+        .to_string();
+
+    let class_id = Uuid::new_v4().to_string();
+    let mut id_map: HashMap<String, String> = HashMap::new();
+    id_map.insert(old_class_id.to_string(), class_id.clone());
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO classes(id, name, sort_order)
+         VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
+        (&class_id, &class_name),
+    )
+    .context("failed to insert class")?;
+
+    let mut counts = Vec::with_capacity(CLASS_TABLES.len());
+    for table in CLASS_TABLES {
+        let table_rows = match rows.pointer(&format!("/tables/{}", table.name)) {
+            None | Some(serde_json::Value::Null) => &[][..],
+            Some(serde_json::Value::Array(a)) => a.as_slice(),
+            Some(_) => return Err(corrupt(&format!("{} is not an array", table.name)).into()),
+        };
+        let columns = db::table_columns(&tx, table.name)?;
+        for row in table_rows {
+            let Some(row) = row.as_object() else {
+                return Err(corrupt(&format!("{} row is not an object", table.name)).into());
+            };
+            let mut names = Vec::new();
+            let mut values = Vec::new();
+            for column in &columns {
+                let Some(value) = row.get(column) else {
+                    continue;
+                };
+                let value = if table.fresh_id && column == "id" {
+                    let old_id = value
+                        .as_str()
+                        .ok_or_else(|| corrupt(&format!("{} row has no id", table.name)))?;
+                    let new_id = Uuid::new_v4().to_string();
+                    id_map.insert(old_id.to_string(), new_id.clone());
+                    Value::Text(new_id)
+                } else if table.refs.contains(&column.as_str()) && !value.is_null() {
+                    let new_id = value
+                        .as_str()
+                        .and_then(|old_id| id_map.get(old_id))
+                        .ok_or_else(|| {
+                            corrupt(&format!(
+                                "{}.{} references a row outside the bundle",
+                                table.name, column
+                            ))
+                        })?;
+                    Value::Text(new_id.clone())
+                } else {
+                    db::json_to_sql_value(value)
+                };
+                names.push(column.as_str());
+                values.push(value);
+            }
+            let sql = format!(
+                "INSERT INTO {}({}) VALUES({})",
+                table.name,
+                names.join(", "),
+                vec!["?"; names.len()].join(", ")
+            );
+            tx.execute(&sql, rusqlite::params_from_iter(values))
+                .with_context(|| format!("failed to insert into {}", table.name))?;
+        }
+        counts.push((table.label, table_rows.len()));
+    }
+    tx.commit().context("failed to commit class import")?;
+
+    Ok(ClassBundleSummary {
+        class_id,
+        class_name,
+        counts,
+    })
+}
+
+fn select_class_rows(
+    conn: &Connection,
+    table: &ClassTable,
+    class_id: &str,
+) -> rusqlite::Result<Vec<serde_json::Value>> {
+    db::query_rows_json(
+        conn,
+        &format!(
+            "SELECT * FROM {} WHERE {} ORDER BY rowid",
+            table.name, table.scope
+        ),
+        [class_id],
+    )
+}
@@ -0,0 +1,1003 @@
+//! Class JSON: a documented, nested interchange format for one class.
+//!
+//! Unlike class bundles, which carry raw table rows, a class JSON document is
+//! meant to be read and written by other tools. Its shape is stable within a
+//! `schemaVersion`; new optional keys may be added, but keys are never renamed
+//! or repurposed without bumping the version.
+//!
+//! ```text
+//! {
+//!   "format": "markbook-class-json",
+//!   "schemaVersion": 1,
+//!   "appVersion": "…", "exportedAt": <unix seconds>,
+//!   "class": { "id", "name", "meta": { "classCode", "schoolYear", "schoolName",
+//!              "teacherName", "calcMethodDefault", "weightMethodDefault",
+//!              "schoolYearStartMonth" } | null },
+//!   "students": [ { "id", "lastName", "firstName", "studentNo", "birthDate",
+//!                   "active", "archived", "sortOrder", "pronouns",
+//!                   "markSetMask", "note" } ],
+//!   "markSets": [ { "id", "code", "filePrefix", "description", "fullCode",
+//!                   "room", "day", "period", "blockTitle", "weight",
+//!                   "weightMethod", "calcMethod", "isDefault", "sortOrder",
+//!                   "deletedAt",
+//!                   "categories":  [ { "id", "name", "weight", "sortOrder", "dropLowest" } ],
+//!                   "assessments": [ { "id", "idx", "date", "categoryName", "title",
+//!                                      "term", "legacyKind", "legacyType", "weight", "outOf",
+//!                                      "scores": [ { "studentId", "rawValue", "status", "remark" } ] } ],
+//!                   "commentSets": [ { "id", "setNumber", "title", "isDefault", "bankShort",
+//!                                      "maxChars", "fitMode", "fitFontSize", "fitWidth",
+//!                                      "fitLines", "fitSubj",
+//!                                      "remarks": [ { "studentId", "remark" } ] } ] } ],
+//!   "attendance": { "schoolYearStartMonth",
+//!                   "months":   [ { "month", "typeOfDayCodes" } ],
+//!                   "students": [ { "studentId", "month", "dayCodes" } ] },
+//!   "seating": { "plans": [ { "id", "name", "isDefault", "rows", "seatsPerRow",
+//!                             "blockedMask", "assignments": [ { "studentId", "seatCode" } ] } ] },
+//!   "loanedItems": [ { "id", "studentId", "markSetId", "itemName", "quantity", "notes" } ]
+//! }
+//! ```
+//!
+//! Ids are the exporting workspace's ids and only serve to link entries within
+//! the document. Numbers stay numeric, flags are booleans, score `status` keeps
+//! its stored string (`scored`, `zero`, `no_mark`, …), and attendance `month`
+//! keeps its stored key (`MM` or `YYYY-MM`).
+//!
+//! On import only `schemaVersion` and `class.name` are required; every other
+//! key falls back to the defaults new rows get elsewhere in the app.
+
+use crate::db;
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+pub const CLASS_JSON_FORMAT: &str = "markbook-class-json";
+pub const CLASS_JSON_SCHEMA_VERSION: i64 = 1;
+
+/// Returned (wrapped in `anyhow`) by `import_class_json` when the document is
+/// not one this build can read, so callers can tell it apart from database
+/// failures. `code` is `unsupported_schema_version` or `invalid_class_json`.
+#[derive(Debug, Clone)]
+pub struct ClassJsonInvalid {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for ClassJsonInvalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ClassJsonInvalid {}
+
+/// Outcome of `import_class_json`. `counts` has one `(label, rows)` pair per
+/// entity written; `warnings` holds `{ path, code, message }` objects for rows
+/// that were skipped, where `path` locates the row in the document.
+#[derive(Debug, Clone)]
+pub struct ClassJsonImport {
+    pub class_id: String,
+    pub class_name: String,
+    pub counts: Vec<(&'static str, usize)>,
+    pub warnings: Vec<Value>,
+}
+
+/// Builds the class JSON document for `class_id`. Fails if the class does not
+/// exist.
+pub fn export_class_json(conn: &Connection, class_id: &str) -> anyhow::Result<Value> {
+    let class_name: String = conn
+        .query_row("SELECT name FROM classes WHERE id = ?", [class_id], |r| {
+            r.get(0)
+        })
+        .context("failed to read class")?;
+
+    let meta = rows(
+        conn,
+        "SELECT class_code AS classCode, school_year AS schoolYear,
+                school_name AS schoolName, teacher_name AS teacherName,
+                calc_method_default AS calcMethodDefault,
+                weight_method_default AS weightMethodDefault,
+                school_year_start_month AS schoolYearStartMonth
+         FROM class_meta WHERE class_id = ?",
+        class_id,
+    )?
+    .into_iter()
+    .next()
+    .map(Value::Object)
+    .unwrap_or(Value::Null);
+
+    let mut students = rows(
+        conn,
+        "SELECT s.id, s.last_name AS lastName, s.first_name AS firstName,
+                s.student_no AS studentNo, s.birth_date AS birthDate,
+                s.active, s.archived, s.sort_order AS sortOrder, s.pronouns,
+                s.mark_set_mask AS markSetMask, n.note
+         FROM students s
+         LEFT JOIN student_notes n ON n.class_id = s.class_id AND n.student_id = s.id
+         WHERE s.class_id = ?
+         ORDER BY s.sort_order, s.rowid",
+        class_id,
+    )?;
+    for s in &mut students {
+        to_bool(s, &["active", "archived"]);
+    }
+
+    let mut categories = group_by(
+        rows(
+            conn,
+            "SELECT c.mark_set_id AS markSetId, c.id, c.name, c.weight,
+                    c.sort_order AS sortOrder, c.drop_lowest AS dropLowest
+             FROM categories c JOIN mark_sets m ON m.id = c.mark_set_id
+             WHERE m.class_id = ?
+             ORDER BY c.sort_order, c.rowid",
+            class_id,
+        )?,
+        "markSetId",
+    );
+    let mut scores = group_by(
+        rows(
+            conn,
+            "SELECT sc.assessment_id AS assessmentId, sc.student_id AS studentId,
+                    sc.raw_value AS rawValue, sc.status, sc.remark
+             FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets m ON m.id = a.mark_set_id
+             WHERE m.class_id = ?
+             ORDER BY sc.rowid",
+            class_id,
+        )?,
+        "assessmentId",
+    );
+    let mut assessments = group_by(
+        rows(
+            conn,
+            "SELECT a.mark_set_id AS markSetId, a.id, a.idx, a.date,
+                    a.category_name AS categoryName, a.title, a.term,
+                    a.legacy_kind AS legacyKind, a.legacy_type AS legacyType,
+                    a.weight, a.out_of AS outOf
+             FROM assessments a JOIN mark_sets m ON m.id = a.mark_set_id
+             WHERE m.class_id = ?
+             ORDER BY a.idx",
+            class_id,
+        )?,
+        "markSetId",
+    );
+    for list in assessments.values_mut() {
+        for a in list.iter_mut() {
+            let id = a.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            let list = scores.remove(id).unwrap_or_default();
+            a.insert("scores".into(), objects(list));
+        }
+    }
+    let mut remarks = group_by(
+        rows(
+            conn,
+            "SELECT r.comment_set_index_id AS commentSetId, r.student_id AS studentId, r.remark
+             FROM comment_set_remarks r
+             JOIN comment_set_indexes i ON i.id = r.comment_set_index_id
+             WHERE i.class_id = ?
+             ORDER BY r.rowid",
+            class_id,
+        )?,
+        "commentSetId",
+    );
+    let mut comment_sets = group_by(
+        rows(
+            conn,
+            "SELECT mark_set_id AS markSetId, id, set_number AS setNumber, title,
+                    is_default AS isDefault, bank_short AS bankShort,
+                    max_chars AS maxChars, fit_mode AS fitMode,
+                    fit_font_size AS fitFontSize, fit_width AS fitWidth,
+                    fit_lines AS fitLines, fit_subj AS fitSubj
+             FROM comment_set_indexes
+             WHERE class_id = ?
+             ORDER BY set_number",
+            class_id,
+        )?,
+        "markSetId",
+    );
+    for list in comment_sets.values_mut() {
+        for c in list.iter_mut() {
+            to_bool(c, &["isDefault"]);
+            let id = c.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            let list = remarks.remove(id).unwrap_or_default();
+            c.insert("remarks".into(), objects(list));
+        }
+    }
+
+    let mut mark_sets = rows(
+        conn,
+        "SELECT id, code, file_prefix AS filePrefix, description,
+                full_code AS fullCode, room, day, period, block_title AS blockTitle,
+                weight, weight_method AS weightMethod, calc_method AS calcMethod,
+                is_default AS isDefault, sort_order AS sortOrder, deleted_at AS deletedAt
+         FROM mark_sets
+         WHERE class_id = ?
+         ORDER BY sort_order, rowid",
+        class_id,
+    )?;
+    for m in &mut mark_sets {
+        to_bool(m, &["isDefault"]);
+        let id = m
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        m.insert(
+            "categories".into(),
+            objects(categories.remove(&id).unwrap_or_default()),
+        );
+        m.insert(
+            "assessments".into(),
+            objects(assessments.remove(&id).unwrap_or_default()),
+        );
+        m.insert(
+            "commentSets".into(),
+            objects(comment_sets.remove(&id).unwrap_or_default()),
+        );
+    }
+
+    let school_year_start_month: Option<i64> = conn
+        .query_row(
+            "SELECT school_year_start_month FROM attendance_settings WHERE class_id = ?",
+            [class_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .context("failed to read attendance_settings")?;
+    let attendance_months = rows(
+        conn,
+        "SELECT CAST(month AS TEXT) AS month, type_of_day_codes AS typeOfDayCodes
+         FROM attendance_months
+         WHERE class_id = ?
+         ORDER BY rowid",
+        class_id,
+    )?;
+    let attendance_students = rows(
+        conn,
+        "SELECT student_id AS studentId, CAST(month AS TEXT) AS month, day_codes AS dayCodes
+         FROM attendance_student_months
+         WHERE class_id = ?
+         ORDER BY rowid",
+        class_id,
+    )?;
+
+    let mut seats = group_by(
+        rows(
+            conn,
+            "SELECT plan_id AS planId, student_id AS studentId, seat_code AS seatCode
+             FROM seating_assignments
+             WHERE class_id = ?
+             ORDER BY seat_code",
+            class_id,
+        )?,
+        "planId",
+    );
+    let mut plans = rows(
+        conn,
+        "SELECT id, name, is_default AS isDefault, rows, seats_per_row AS seatsPerRow,
+                blocked_mask AS blockedMask
+         FROM seating_plans
+         WHERE class_id = ?
+         ORDER BY rowid",
+        class_id,
+    )?;
+    for p in &mut plans {
+        to_bool(p, &["isDefault"]);
+        let id = p.get("id").and_then(|v| v.as_str()).unwrap_or("");
+        let list = seats.remove(id).unwrap_or_default();
+        p.insert("assignments".into(), objects(list));
+    }
+
+    let loaned_items = rows(
+        conn,
+        "SELECT id, student_id AS studentId, mark_set_id AS markSetId,
+                item_name AS itemName, quantity, notes
+         FROM loaned_items
+         WHERE class_id = ?
+         ORDER BY rowid",
+        class_id,
+    )?;
+
+    let exported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok(json!({
+        "format": CLASS_JSON_FORMAT,
+        "schemaVersion": CLASS_JSON_SCHEMA_VERSION,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "exportedAt": exported_at,
+        "class": { "id": class_id, "name": class_name, "meta": meta },
+        "students": objects(students),
+        "markSets": objects(mark_sets),
+        "attendance": {
+            "schoolYearStartMonth": school_year_start_month,
+            "months": objects(attendance_months),
+            "students": objects(attendance_students),
+        },
+        "seating": { "plans": objects(plans) },
+        "loanedItems": objects(loaned_items),
+    }))
+}
+
+/// Writes a class JSON document into `conn`. Without `merge_into` the document
+/// becomes a new class and every row gets a fresh UUID. With `merge_into`,
+/// only students and scores are imported into that existing class: students
+/// are matched by `studentNo` (others are added at the end of the roster), and
+/// scores land on the assessment with the same `idx` in the mark set with the
+/// same `code`. Runs in one transaction; a failure leaves the workspace
+/// untouched.
+pub fn import_class_json(
+    conn: &Connection,
+    doc: &Value,
+    merge_into: Option<&str>,
+) -> anyhow::Result<ClassJsonImport> {
+    let invalid = |message: &str| ClassJsonInvalid {
+        code: "invalid_class_json",
+        message: message.to_string(),
+    };
+    if !doc.is_object() {
+        return Err(invalid("document is not a JSON object").into());
+    }
+    match doc.get("schemaVersion").and_then(|v| v.as_i64()) {
+        Some(CLASS_JSON_SCHEMA_VERSION) => {}
+        Some(v) => {
+            return Err(ClassJsonInvalid {
+                code: "unsupported_schema_version",
+                message: format!(
+                    "unsupported schemaVersion {} (expected {})",
+                    v, CLASS_JSON_SCHEMA_VERSION
+                ),
+            }
+            .into())
+        }
+        None => return Err(invalid("missing schemaVersion").into()),
+    }
+    let class_name = doc
+        .pointer("/class/name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| invalid("missing class.name"))?
+        .to_string();
+
+    let tx = conn.unchecked_transaction()?;
+    let mut warnings = Vec::new();
+    let merging = merge_into.is_some();
+    let (class_id, class_name) = match merge_into {
+        Some(id) => {
+            let name: String = tx
+                .query_row("SELECT name FROM classes WHERE id = ?", [id], |r| r.get(0))
+                .context("failed to read class")?;
+            (id.to_string(), name)
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO classes(id, name, sort_order)
+                 VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
+                (&id, &class_name),
+            )
+            .context("failed to insert class")?;
+            (id, class_name)
+        }
+    };
+
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    let mut count = |label: &'static str, n: usize| counts.push((label, n));
+
+    if let Some(meta) = doc.pointer("/class/meta").filter(|_| !merging) {
+        if meta.is_object() {
+            tx.execute(
+                "INSERT INTO class_meta(
+                   class_id, class_code, school_year, school_name, teacher_name,
+                   calc_method_default, weight_method_default, school_year_start_month
+                 ) VALUES(?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    class_id,
+                    str_at(meta, "classCode"),
+                    str_at(meta, "schoolYear"),
+                    str_at(meta, "schoolName"),
+                    str_at(meta, "teacherName"),
+                    int_at(meta, "calcMethodDefault"),
+                    int_at(meta, "weightMethodDefault"),
+                    int_at(meta, "schoolYearStartMonth"),
+                ],
+            )
+            .context("failed to insert class_meta")?;
+        }
+    }
+
+    // Students: old id -> id in the target class.
+    let mut student_ids: HashMap<String, String> = HashMap::new();
+    let mut by_student_no: HashMap<String, String> = HashMap::new();
+    let mut next_sort: i64 = 0;
+    if merging {
+        let mut stmt = tx.prepare(
+            "SELECT id, student_no FROM students WHERE class_id = ? AND student_no IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([&class_id], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (id, no) = row?;
+            if !no.trim().is_empty() {
+                by_student_no.insert(no.trim().to_string(), id);
+            }
+        }
+        next_sort = tx.query_row(
+            "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM students WHERE class_id = ?",
+            [&class_id],
+            |r| r.get(0),
+        )?;
+    }
+    let (mut inserted, mut matched) = (0, 0);
+    for (i, st) in array_at(doc, "students").iter().enumerate() {
+        let path = format!("students[{}]", i);
+        let old_id = str_at(st, "id");
+        let student_no = str_at(st, "studentNo")
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        if let Some(existing) = student_no.and_then(|no| by_student_no.get(no)) {
+            if let Some(old_id) = old_id {
+                student_ids.insert(old_id.to_string(), existing.clone());
+            }
+            matched += 1;
+            continue;
+        }
+        let (Some(last_name), Some(first_name)) = (str_at(st, "lastName"), str_at(st, "firstName"))
+        else {
+            warn(
+                &mut warnings,
+                &path,
+                "missing_name",
+                "student has no lastName/firstName",
+            );
+            continue;
+        };
+        let id = Uuid::new_v4().to_string();
+        let sort_order = if merging {
+            next_sort + inserted as i64
+        } else {
+            int_at(st, "sortOrder").unwrap_or(i as i64)
+        };
+        tx.execute(
+            "INSERT INTO students(
+               id, class_id, last_name, first_name, student_no, birth_date, active,
+               sort_order, raw_line, mark_set_mask, archived, pronouns, updated_at
+             ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, '', ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
+            params![
+                id,
+                class_id,
+                last_name,
+                first_name,
+                student_no,
+                str_at(st, "birthDate"),
+                bool_at(st, "active").unwrap_or(true) as i64,
+                sort_order,
+                str_at(st, "markSetMask").unwrap_or("TBA"),
+                bool_at(st, "archived").unwrap_or(false) as i64,
+                str_at(st, "pronouns"),
+            ],
+        )
+        .context("failed to insert student")?;
+        if let Some(note) = str_at(st, "note").filter(|n| !n.is_empty()) {
+            tx.execute(
+                "INSERT INTO student_notes(id, class_id, student_id, note) VALUES(?, ?, ?, ?)",
+                (Uuid::new_v4().to_string(), &class_id, &id, note),
+            )
+            .context("failed to insert student note")?;
+        }
+        if let Some(old_id) = old_id {
+            student_ids.insert(old_id.to_string(), id);
+        }
+        inserted += 1;
+    }
+    count("students", inserted);
+    count("studentsMatched", matched);
+
+    let student = |warnings: &mut Vec<Value>, row: &Value, path: &str| -> Option<String> {
+        let id = str_at(row, "studentId")
+            .and_then(|id| student_ids.get(id))
+            .cloned();
+        if id.is_none() {
+            warn(
+                warnings,
+                path,
+                "unknown_student",
+                "studentId does not match a student in the document",
+            );
+        }
+        id
+    };
+
+    // Mark sets: old id -> id in the target class.
+    let mut mark_set_ids: HashMap<String, String> = HashMap::new();
+    let (mut n_mark_sets, mut n_categories, mut n_assessments, mut n_scores) = (0, 0, 0, 0);
+    let (mut n_comment_sets, mut n_remarks) = (0, 0);
+    for (i, ms) in array_at(doc, "markSets").iter().enumerate() {
+        let path = format!("markSets[{}]", i);
+        let Some(code) = str_at(ms, "code").filter(|c| !c.trim().is_empty()) else {
+            warn(&mut warnings, &path, "missing_code", "mark set has no code");
+            continue;
+        };
+        let mark_set_id = if merging {
+            let found: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM mark_sets
+                     WHERE class_id = ? AND code = ? AND deleted_at IS NULL
+                     ORDER BY sort_order LIMIT 1",
+                    (&class_id, code),
+                    |r| r.get(0),
+                )
+                .optional()?;
+            let Some(found) = found else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_mark_set",
+                    &format!("no mark set with code {} in the target class", code),
+                );
+                continue;
+            };
+            found
+        } else {
+            let id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO mark_sets(
+                   id, class_id, code, file_prefix, description, weight, sort_order, full_code,
+                   room, day, period, block_title, weight_method, calc_method, is_default, deleted_at
+                 ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    id,
+                    class_id,
+                    code,
+                    str_at(ms, "filePrefix").unwrap_or(code),
+                    str_at(ms, "description").unwrap_or(code),
+                    num_at(ms, "weight"),
+                    int_at(ms, "sortOrder").unwrap_or(i as i64),
+                    str_at(ms, "fullCode"),
+                    str_at(ms, "room"),
+                    str_at(ms, "day"),
+                    str_at(ms, "period"),
+                    str_at(ms, "blockTitle"),
+                    int_at(ms, "weightMethod").unwrap_or(1),
+                    int_at(ms, "calcMethod").unwrap_or(0),
+                    bool_at(ms, "isDefault").unwrap_or(false) as i64,
+                    str_at(ms, "deletedAt"),
+                ],
+            )
+            .context("failed to insert mark set")?;
+            n_mark_sets += 1;
+            id
+        };
+        if let Some(old_id) = str_at(ms, "id") {
+            mark_set_ids.insert(old_id.to_string(), mark_set_id.clone());
+        }
+
+        if !merging {
+            for (j, cat) in array_at(ms, "categories").iter().enumerate() {
+                let path = format!("{}.categories[{}]", path, j);
+                let Some(name) = str_at(cat, "name") else {
+                    warn(&mut warnings, &path, "missing_name", "category has no name");
+                    continue;
+                };
+                let n = tx
+                    .execute(
+                        "INSERT OR IGNORE INTO categories(id, mark_set_id, name, weight, sort_order, drop_lowest)
+                         VALUES(?, ?, ?, ?, ?, ?)",
+                        params![
+                            Uuid::new_v4().to_string(),
+                            mark_set_id,
+                            name,
+                            num_at(cat, "weight"),
+                            int_at(cat, "sortOrder").unwrap_or(j as i64),
+                            int_at(cat, "dropLowest"),
+                        ],
+                    )
+                    .context("failed to insert category")?;
+                if n == 0 {
+                    warn(&mut warnings, &path, "duplicate_category", name);
+                }
+                n_categories += n;
+            }
+        }
+
+        for (j, a) in array_at(ms, "assessments").iter().enumerate() {
+            let path = format!("{}.assessments[{}]", path, j);
+            let idx = int_at(a, "idx").unwrap_or(j as i64);
+            let assessment_id = if merging {
+                let found: Option<String> = tx
+                    .query_row(
+                        "SELECT id FROM assessments WHERE mark_set_id = ? AND idx = ?",
+                        (&mark_set_id, idx),
+                        |r| r.get(0),
+                    )
+                    .optional()?;
+                let Some(found) = found else {
+                    warn(
+                        &mut warnings,
+                        &path,
+                        "missing_assessment",
+                        &format!("no assessment {} in mark set {}", idx, code),
+                    );
+                    continue;
+                };
+                found
+            } else {
+                let id = Uuid::new_v4().to_string();
+                let n = tx
+                    .execute(
+                        "INSERT OR IGNORE INTO assessments(
+                           id, mark_set_id, idx, date, category_name, title, term,
+                           legacy_kind, legacy_type, weight, out_of
+                         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        params![
+                            id,
+                            mark_set_id,
+                            idx,
+                            str_at(a, "date"),
+                            str_at(a, "categoryName"),
+                            str_at(a, "title").unwrap_or(""),
+                            int_at(a, "term"),
+                            int_at(a, "legacyKind"),
+                            int_at(a, "legacyType"),
+                            num_at(a, "weight"),
+                            num_at(a, "outOf"),
+                        ],
+                    )
+                    .context("failed to insert assessment")?;
+                if n == 0 {
+                    warn(
+                        &mut warnings,
+                        &path,
+                        "duplicate_assessment",
+                        &format!("idx {} already used in mark set {}", idx, code),
+                    );
+                    continue;
+                }
+                n_assessments += 1;
+                id
+            };
+
+            for (k, sc) in array_at(a, "scores").iter().enumerate() {
+                let path = format!("{}.scores[{}]", path, k);
+                let Some(student_id) = student(&mut warnings, sc, &path) else {
+                    continue;
+                };
+                let raw_value = num_at(sc, "rawValue");
+                let status = str_at(sc, "status").unwrap_or(if raw_value.is_some() {
+                    "scored"
+                } else {
+                    "no_mark"
+                });
+                tx.execute(
+                    "INSERT INTO scores(id, assessment_id, student_id, raw_value, status, remark)
+                     VALUES(?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(assessment_id, student_id) DO UPDATE SET
+                       raw_value = excluded.raw_value,
+                       status = excluded.status,
+                       remark = excluded.remark",
+                    params![
+                        Uuid::new_v4().to_string(),
+                        assessment_id,
+                        student_id,
+                        raw_value,
+                        status,
+                        str_at(sc, "remark"),
+                    ],
+                )
+                .context("failed to insert score")?;
+                n_scores += 1;
+            }
+        }
+
+        if merging {
+            continue;
+        }
+        for (j, cs) in array_at(ms, "commentSets").iter().enumerate() {
+            let path = format!("{}.commentSets[{}]", path, j);
+            let Some(set_number) = int_at(cs, "setNumber") else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_set_number",
+                    "comment set has no setNumber",
+                );
+                continue;
+            };
+            let id = Uuid::new_v4().to_string();
+            let n = tx
+                .execute(
+                    "INSERT OR IGNORE INTO comment_set_indexes(
+                       id, class_id, mark_set_id, set_number, title, fit_mode, fit_font_size,
+                       fit_width, fit_lines, fit_subj, max_chars, is_default, bank_short
+                     ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        class_id,
+                        mark_set_id,
+                        set_number,
+                        str_at(cs, "title").unwrap_or(""),
+                        int_at(cs, "fitMode").unwrap_or(0),
+                        int_at(cs, "fitFontSize").unwrap_or(8),
+                        int_at(cs, "fitWidth").unwrap_or(50),
+                        int_at(cs, "fitLines").unwrap_or(1),
+                        str_at(cs, "fitSubj").unwrap_or(""),
+                        int_at(cs, "maxChars").unwrap_or(100),
+                        bool_at(cs, "isDefault").unwrap_or(false) as i64,
+                        str_at(cs, "bankShort"),
+                    ],
+                )
+                .context("failed to insert comment set")?;
+            if n == 0 {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "duplicate_comment_set",
+                    &format!("set {} already used in mark set {}", set_number, code),
+                );
+                continue;
+            }
+            n_comment_sets += 1;
+            for (k, r) in array_at(cs, "remarks").iter().enumerate() {
+                let path = format!("{}.remarks[{}]", path, k);
+                let Some(student_id) = student(&mut warnings, r, &path) else {
+                    continue;
+                };
+                n_remarks += tx
+                    .execute(
+                        "INSERT OR IGNORE INTO comment_set_remarks(id, comment_set_index_id, student_id, remark)
+                         VALUES(?, ?, ?, ?)",
+                        (
+                            Uuid::new_v4().to_string(),
+                            &id,
+                            &student_id,
+                            str_at(r, "remark").unwrap_or(""),
+                        ),
+                    )
+                    .context("failed to insert remark")?;
+            }
+        }
+    }
+    count("markSets", n_mark_sets);
+    count("categories", n_categories);
+    count("assessments", n_assessments);
+    count("scores", n_scores);
+    count("commentSets", n_comment_sets);
+    count("remarks", n_remarks);
+
+    let (mut n_months, mut n_student_months, mut n_plans, mut n_seats, mut n_loaned) =
+        (0, 0, 0, 0, 0);
+    if !merging {
+        let attendance = doc.get("attendance").unwrap_or(&Value::Null);
+        if let Some(start) = int_at(attendance, "schoolYearStartMonth") {
+            tx.execute(
+                "INSERT INTO attendance_settings(class_id, school_year_start_month) VALUES(?, ?)",
+                (&class_id, start),
+            )
+            .context("failed to insert attendance_settings")?;
+        }
+        for (i, m) in array_at(attendance, "months").iter().enumerate() {
+            let path = format!("attendance.months[{}]", i);
+            let (Some(month), Some(codes)) = (str_at(m, "month"), str_at(m, "typeOfDayCodes"))
+            else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_month",
+                    "month row has no month/typeOfDayCodes",
+                );
+                continue;
+            };
+            n_months += tx
+                .execute(
+                    "INSERT OR IGNORE INTO attendance_months(class_id, month, type_of_day_codes)
+                     VALUES(?, ?, ?)",
+                    (&class_id, month, codes),
+                )
+                .context("failed to insert attendance month")?;
+        }
+        for (i, m) in array_at(attendance, "students").iter().enumerate() {
+            let path = format!("attendance.students[{}]", i);
+            let (Some(month), Some(codes)) = (str_at(m, "month"), str_at(m, "dayCodes")) else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_month",
+                    "student month row has no month/dayCodes",
+                );
+                continue;
+            };
+            let Some(student_id) = student(&mut warnings, m, &path) else {
+                continue;
+            };
+            n_student_months += tx
+                .execute(
+                    "INSERT OR IGNORE INTO attendance_student_months(class_id, student_id, month, day_codes)
+                     VALUES(?, ?, ?, ?)",
+                    (&class_id, &student_id, month, codes),
+                )
+                .context("failed to insert attendance student month")?;
+        }
+
+        let seating = doc.get("seating").unwrap_or(&Value::Null);
+        for (i, p) in array_at(seating, "plans").iter().enumerate() {
+            let path = format!("seating.plans[{}]", i);
+            let (Some(rows), Some(seats_per_row)) = (int_at(p, "rows"), int_at(p, "seatsPerRow"))
+            else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_size",
+                    "seating plan has no rows/seatsPerRow",
+                );
+                continue;
+            };
+            let id = Uuid::new_v4().to_string();
+            let n = tx
+                .execute(
+                    "INSERT OR IGNORE INTO seating_plans(id, class_id, name, is_default, rows, seats_per_row, blocked_mask)
+                     VALUES(?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        class_id,
+                        str_at(p, "name").unwrap_or("Default"),
+                        bool_at(p, "isDefault").unwrap_or(false) as i64,
+                        rows,
+                        seats_per_row,
+                        str_at(p, "blockedMask").unwrap_or(""),
+                    ],
+                )
+                .context("failed to insert seating plan")?;
+            if n == 0 {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "duplicate_plan",
+                    "seating plan name already used",
+                );
+                continue;
+            }
+            n_plans += 1;
+            for (j, s) in array_at(p, "assignments").iter().enumerate() {
+                let path = format!("{}.assignments[{}]", path, j);
+                let Some(seat_code) = int_at(s, "seatCode") else {
+                    warn(
+                        &mut warnings,
+                        &path,
+                        "missing_seat",
+                        "assignment has no seatCode",
+                    );
+                    continue;
+                };
+                let Some(student_id) = student(&mut warnings, s, &path) else {
+                    continue;
+                };
+                n_seats += tx
+                    .execute(
+                        "INSERT OR IGNORE INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+                         VALUES(?, ?, ?, ?)",
+                        (&class_id, &id, &student_id, seat_code),
+                    )
+                    .context("failed to insert seating assignment")?;
+            }
+        }
+
+        for (i, item) in array_at(doc, "loanedItems").iter().enumerate() {
+            let path = format!("loanedItems[{}]", i);
+            let Some(item_name) = str_at(item, "itemName") else {
+                warn(
+                    &mut warnings,
+                    &path,
+                    "missing_name",
+                    "loaned item has no itemName",
+                );
+                continue;
+            };
+            let Some(student_id) = student(&mut warnings, item, &path) else {
+                continue;
+            };
+            let mark_set_id = str_at(item, "markSetId").and_then(|id| mark_set_ids.get(id));
+            tx.execute(
+                "INSERT INTO loaned_items(id, class_id, student_id, mark_set_id, item_name, quantity, notes, raw_line)
+                 VALUES(?, ?, ?, ?, ?, ?, ?, '')",
+                params![
+                    Uuid::new_v4().to_string(),
+                    class_id,
+                    student_id,
+                    mark_set_id,
+                    item_name,
+                    num_at(item, "quantity"),
+                    str_at(item, "notes"),
+                ],
+            )
+            .context("failed to insert loaned item")?;
+            n_loaned += 1;
+        }
+    }
+    count("attendanceMonths", n_months);
+    count("attendanceStudentMonths", n_student_months);
+    count("seatingPlans", n_plans);
+    count("seatingAssignments", n_seats);
+    count("loanedItems", n_loaned);
+
+    tx.commit().context("failed to commit class import")?;
+    Ok(ClassJsonImport {
+        class_id,
+        class_name,
+        counts,
+        warnings,
+    })
+}
+
+fn warn(warnings: &mut Vec<Value>, path: &str, code: &str, message: &str) {
+    warnings.push(json!({ "path": path, "code": code, "message": message }));
+}
+
+fn str_at<'a>(v: &'a Value, key: &str) -> Option<&'a str> {
+    v.get(key).and_then(|v| v.as_str())
+}
+
+fn int_at(v: &Value, key: &str) -> Option<i64> {
+    v.get(key).and_then(|v| v.as_i64())
+}
+
+fn num_at(v: &Value, key: &str) -> Option<f64> {
+    v.get(key).and_then(|v| v.as_f64())
+}
+
+/// Accepts booleans as well as 0/1 integers.
+fn bool_at(v: &Value, key: &str) -> Option<bool> {
+    match v.get(key) {
+        Some(Value::Bool(b)) => Some(*b),
+        Some(v) => v.as_i64().map(|n| n != 0),
+        None => None,
+    }
+}
+
+fn array_at<'a>(v: &'a Value, key: &str) -> &'a [Value] {
+    v.get(key)
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn rows(conn: &Connection, sql: &str, class_id: &str) -> anyhow::Result<Vec<Map<String, Value>>> {
+    let rows = db::query_rows_json(conn, sql, [class_id]).context("failed to read class rows")?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| match row {
+            Value::Object(m) => Some(m),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Splits rows by the string in `key`, dropping that key from each row since
+/// the parent entry already carries it.
+fn group_by(rows: Vec<Map<String, Value>>, key: &str) -> HashMap<String, Vec<Map<String, Value>>> {
+    let mut groups: HashMap<String, Vec<Map<String, Value>>> = HashMap::new();
+    for mut row in rows {
+        let Some(Value::String(parent)) = row.remove(key) else {
+            continue;
+        };
+        groups.entry(parent).or_default().push(row);
+    }
+    groups
+}
+
+fn to_bool(row: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(v) = row.get_mut(*key) {
+            *v = Value::Bool(v.as_i64().unwrap_or(0) != 0);
+        }
+    }
+}
+
+fn objects(rows: Vec<Map<String, Value>>) -> Value {
+    Value::Array(rows.into_iter().map(Value::Object).collect())
+}
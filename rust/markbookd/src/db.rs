@@ -52,6 +52,8 @@ pub fn open_db(workspace: &Path) -> anyhow::Result<Connection> {
             raw_line TEXT NOT NULL,
             mark_set_mask TEXT,
             updated_at TEXT,
+            archived INTEGER NOT NULL DEFAULT 0,
+            pronouns TEXT,
             FOREIGN KEY(class_id) REFERENCES classes(id)
         )",
         [],
@@ -65,6 +67,8 @@ pub fn open_db(workspace: &Path) -> anyhow::Result<Connection> {
     ensure_students_sort_order(&conn)?;
     ensure_students_updated_at(&conn)?;
     ensure_students_mark_set_mask(&conn)?;
+    ensure_students_archived(&conn)?;
+    ensure_students_pronouns(&conn)?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_students_class_sort ON students(class_id, sort_order)",
         [],
@@ -157,33 +161,18 @@ pub fn open_db(workspace: &Path) -> anyhow::Result<Connection> {
         [],
     )?;
 
+    create_seating_tables(&conn)?;
+    ensure_seating_plan_ids(&conn)?;
     conn.execute(
-        "CREATE TABLE IF NOT EXISTS seating_plans(
-            class_id TEXT PRIMARY KEY,
-            rows INTEGER NOT NULL,
-            seats_per_row INTEGER NOT NULL,
-            blocked_mask TEXT NOT NULL,
-            FOREIGN KEY(class_id) REFERENCES classes(id)
-        )",
-        [],
-    )?;
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS seating_assignments(
-            class_id TEXT NOT NULL,
-            student_id TEXT NOT NULL,
-            seat_code INTEGER NOT NULL,
-            PRIMARY KEY(class_id, student_id),
-            FOREIGN KEY(class_id) REFERENCES classes(id),
-            FOREIGN KEY(student_id) REFERENCES students(id)
-        )",
+        "CREATE INDEX IF NOT EXISTS idx_seating_assignments_class ON seating_assignments(class_id)",
         [],
     )?;
     conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_seating_assignments_class ON seating_assignments(class_id)",
+        "CREATE INDEX IF NOT EXISTS idx_seating_assignments_student ON seating_assignments(student_id)",
         [],
     )?;
     conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_seating_assignments_student ON seating_assignments(student_id)",
+        "CREATE INDEX IF NOT EXISTS idx_seating_assignments_plan ON seating_assignments(plan_id)",
         [],
     )?;
 
@@ -274,11 +263,13 @@ pub fn open_db(workspace: &Path) -> anyhow::Result<Connection> {
             name TEXT NOT NULL,
             weight REAL,
             sort_order INTEGER NOT NULL,
+            drop_lowest INTEGER,
             FOREIGN KEY(mark_set_id) REFERENCES mark_sets(id),
             UNIQUE(mark_set_id, name)
         )",
         [],
     )?;
+    ensure_categories_drop_lowest(&conn)?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_categories_mark_set ON categories(mark_set_id)",
         [],
@@ -521,14 +512,66 @@ pub fn open_db(workspace: &Path) -> anyhow::Result<Connection> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS grade_scales(
+            id TEXT PRIMARY KEY,
+            min_percent REAL NOT NULL,
+            label TEXT NOT NULL,
+            sort_order INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     // Migrate older workspaces to the expanded mark-state semantics:
     // - "missing" (raw_value NULL) => "zero"
     // - "scored" with raw_value=0 => "no_mark"
     migrate_scores_statuses(&conn)?;
 
+    // Versioned, run-once steps on top of the idempotent table setup above.
+    run_migrations(&conn)?;
+
     Ok(conn)
 }
 
+/// Schema version stored in `PRAGMA user_version` once every migration has run.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Ordered migrations: entry `i` moves a workspace from version `i` to `i + 1`.
+/// Append new steps at the end; never reorder or edit steps that have shipped.
+const MIGRATIONS: &[fn(&Connection) -> anyhow::Result<()>] = &[
+    create_mark_sets_code_index,
+    create_edit_log,
+    ensure_attendance_codes,
+    ensure_students_photo_path,
+    create_score_history,
+    ensure_classes_sort_order,
+    ensure_classes_archived,
+];
+
+pub fn schema_version(conn: &Connection) -> anyhow::Result<i64> {
+    Ok(conn.query_row("PRAGMA user_version", [], |r| r.get(0))?)
+}
+
+fn run_migrations(conn: &Connection) -> anyhow::Result<()> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        anyhow::bail!(
+            "workspace schema version {} is newer than this build supports ({})",
+            current,
+            SCHEMA_VERSION
+        );
+    }
+    for (i, step) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        // Each step commits together with its version bump, so a failed step
+        // leaves the workspace at the last fully applied version.
+        let tx = conn.unchecked_transaction()?;
+        step(&tx)?;
+        tx.pragma_update(None, "user_version", (i + 1) as i64)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
 fn ensure_workspace_settings(conn: &Connection) -> anyhow::Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS workspace_settings(
@@ -540,6 +583,55 @@ fn ensure_workspace_settings(conn: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Workspace legend for attendance day codes. An empty table is seeded with
+/// present/absent/late/excused, using the letters from Setup > Attendance when
+/// they were customized before the legend existed.
+fn ensure_attendance_codes(conn: &Connection) -> anyhow::Result<()> {
+    // v2 -> v3.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attendance_codes(
+            code TEXT PRIMARY KEY,
+            label TEXT NOT NULL,
+            counts_as_absent INTEGER NOT NULL DEFAULT 0,
+            counts_as_late INTEGER NOT NULL DEFAULT 0,
+            is_excused INTEGER NOT NULL DEFAULT 0,
+            sort_order INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    let existing: i64 =
+        conn.query_row("SELECT COUNT(*) FROM attendance_codes", [], |r| r.get(0))?;
+    if existing > 0 {
+        return Ok(());
+    }
+
+    let setup = settings_get_json(conn, "setup.attendance")?;
+    let pick = |field: &str, default: &str| {
+        setup
+            .as_ref()
+            .and_then(|v| v.get(field))
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.trim().chars().next())
+            .map(|c| c.to_ascii_uppercase().to_string())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let defaults = [
+        (pick("presentCode", "P"), "Present", false, false, false),
+        (pick("absentCode", "A"), "Absent", true, false, false),
+        (pick("lateCode", "L"), "Late", false, true, false),
+        (pick("excusedCode", "E"), "Excused", true, false, true),
+    ];
+    for (i, (code, label, absent, late, excused)) in defaults.iter().enumerate() {
+        conn.execute(
+            "INSERT OR IGNORE INTO attendance_codes(
+                code, label, counts_as_absent, counts_as_late, is_excused, sort_order
+             ) VALUES(?, ?, ?, ?, ?, ?)",
+            (code, label, absent, late, excused, i as i64),
+        )?;
+    }
+    Ok(())
+}
+
 pub fn settings_get_json(conn: &Connection, key: &str) -> anyhow::Result<Option<JsonValue>> {
     use rusqlite::OptionalExtension;
     let s: Option<String> = conn
@@ -571,6 +663,209 @@ pub fn settings_delete(conn: &Connection, key: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Read-only health scan for support: SQLite's own integrity and foreign key
+/// checks, plus (optionally) scores left behind by manual delete ordering.
+pub fn integrity_problems(
+    conn: &Connection,
+    include_orphans: bool,
+) -> anyhow::Result<Vec<JsonValue>> {
+    let mut problems = Vec::new();
+
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+    for row in rows {
+        let message = row?;
+        if message != "ok" {
+            problems.push(serde_json::json!({ "kind": "integrity", "message": message }));
+        }
+    }
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let rows = stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, Option<i64>>(1)?,
+            r.get::<_, String>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (table, rowid, parent) = row?;
+        problems.push(serde_json::json!({
+            "kind": "foreign_key",
+            "table": table,
+            "rowid": rowid,
+            "parent": parent
+        }));
+    }
+
+    if include_orphans {
+        let mut stmt = conn.prepare(
+            "SELECT sc.id, sc.assessment_id, sc.student_id,
+                    a.id IS NULL AS missing_assessment,
+                    st.id IS NULL AS missing_student
+             FROM scores sc
+             LEFT JOIN assessments a ON a.id = sc.assessment_id
+             LEFT JOIN students st ON st.id = sc.student_id
+             WHERE a.id IS NULL OR st.id IS NULL
+             ORDER BY sc.id",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, bool>(3)?,
+                r.get::<_, bool>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (score_id, assessment_id, student_id, missing_assessment, missing_student) = row?;
+            let mut missing = Vec::new();
+            if missing_assessment {
+                missing.push("assessment");
+            }
+            if missing_student {
+                missing.push("student");
+            }
+            problems.push(serde_json::json!({
+                "kind": "orphan_score",
+                "scoreId": score_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "missing": missing
+            }));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Compacts the workspace file: checkpoints and truncates the WAL, rebuilds the
+/// database with `VACUUM`, then refreshes planner statistics. `VACUUM` cannot
+/// run inside a transaction, so this refuses to start while one is open.
+pub fn vacuum(conn: &Connection) -> anyhow::Result<()> {
+    if !conn.is_autocommit() {
+        anyhow::bail!("cannot vacuum while a transaction is open");
+    }
+    conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM; PRAGMA optimize;")?;
+    Ok(())
+}
+
+/// Column names of `table`, in declaration order.
+pub fn table_columns(conn: &Connection, table: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(1))?;
+    rows.collect()
+}
+
+/// Runs `sql` and returns each row as a JSON object keyed by column name, for
+/// snapshots that are written back later with `insert_row_json`.
+pub fn query_rows_json<P: rusqlite::Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+) -> rusqlite::Result<Vec<JsonValue>> {
+    let mut stmt = conn.prepare(sql)?;
+    let names: Vec<String> = stmt.column_names().iter().map(|n| n.to_string()).collect();
+    let rows = stmt.query_map(params, |r| {
+        let mut row = serde_json::Map::new();
+        for (i, name) in names.iter().enumerate() {
+            let value: rusqlite::types::Value = r.get(i)?;
+            row.insert(name.clone(), sql_value_to_json(value));
+        }
+        Ok(JsonValue::Object(row))
+    })?;
+    rows.collect()
+}
+
+/// Inserts a row captured by `query_rows_json`. Keys that are not columns of
+/// `table` are ignored, so snapshots survive later column drops.
+pub fn insert_row_json(
+    conn: &Connection,
+    table: &str,
+    row: &serde_json::Map<String, JsonValue>,
+) -> rusqlite::Result<usize> {
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for column in table_columns(conn, table)? {
+        if let Some(value) = row.get(&column) {
+            values.push(json_to_sql_value(value));
+            names.push(column);
+        }
+    }
+    let sql = format!(
+        "INSERT INTO {}({}) VALUES({})",
+        table,
+        names.join(", "),
+        vec!["?"; names.len()].join(", ")
+    );
+    conn.execute(&sql, rusqlite::params_from_iter(values))
+}
+
+pub fn sql_value_to_json(value: rusqlite::types::Value) -> JsonValue {
+    use rusqlite::types::Value;
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Integer(v) => serde_json::json!(v),
+        Value::Real(v) => serde_json::json!(v),
+        Value::Text(v) => serde_json::json!(v),
+        // No workspace table stores blobs; keep snapshots plain JSON regardless.
+        Value::Blob(v) => serde_json::json!(v),
+    }
+}
+
+pub fn json_to_sql_value(value: &JsonValue) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+    match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(v) => Value::Integer(*v as i64),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(v) => Value::Integer(v),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(v) => Value::Text(v.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn ensure_classes_sort_order(conn: &Connection) -> anyhow::Result<()> {
+    // v5 -> v6: classes become reorderable.
+    if table_has_column(conn, "classes", "sort_order")? {
+        return Ok(());
+    }
+
+    conn.execute(
+        "ALTER TABLE classes ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    // Backfill with the name order the class list used before it was reorderable.
+    let mut stmt = conn.prepare("SELECT id FROM classes ORDER BY name, rowid")?;
+    let class_ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, cid) in class_ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE classes SET sort_order = ? WHERE id = ?",
+            (i as i64, cid),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn ensure_classes_archived(conn: &Connection) -> anyhow::Result<()> {
+    // v6 -> v7: soft archive for classes.
+    if table_has_column(conn, "classes", "archived")? {
+        return Ok(());
+    }
+    conn.execute(
+        "ALTER TABLE classes ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
 fn ensure_students_sort_order(conn: &Connection) -> anyhow::Result<()> {
     // If the column already exists, we're done.
     if table_has_column(conn, "students", "sort_order")? {
@@ -653,6 +948,120 @@ fn ensure_students_mark_set_mask(conn: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn ensure_students_archived(conn: &Connection) -> anyhow::Result<()> {
+    if table_has_column(conn, "students", "archived")? {
+        return Ok(());
+    }
+    conn.execute(
+        "ALTER TABLE students ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+fn ensure_students_pronouns(conn: &Connection) -> anyhow::Result<()> {
+    if table_has_column(conn, "students", "pronouns")? {
+        return Ok(());
+    }
+    conn.execute("ALTER TABLE students ADD COLUMN pronouns TEXT", [])?;
+    Ok(())
+}
+
+fn ensure_students_photo_path(conn: &Connection) -> anyhow::Result<()> {
+    // v3 -> v4: optional student photo under the workspace assets.
+    if table_has_column(conn, "students", "photo_path")? {
+        return Ok(());
+    }
+    conn.execute("ALTER TABLE students ADD COLUMN photo_path TEXT", [])?;
+    Ok(())
+}
+
+fn create_seating_tables(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS seating_plans(
+            id TEXT PRIMARY KEY,
+            class_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            is_default INTEGER NOT NULL DEFAULT 0,
+            rows INTEGER NOT NULL,
+            seats_per_row INTEGER NOT NULL,
+            blocked_mask TEXT NOT NULL,
+            FOREIGN KEY(class_id) REFERENCES classes(id),
+            UNIQUE(class_id, name)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS seating_assignments(
+            class_id TEXT NOT NULL,
+            plan_id TEXT NOT NULL,
+            student_id TEXT NOT NULL,
+            seat_code INTEGER NOT NULL,
+            PRIMARY KEY(plan_id, student_id),
+            FOREIGN KEY(class_id) REFERENCES classes(id),
+            FOREIGN KEY(plan_id) REFERENCES seating_plans(id),
+            FOREIGN KEY(student_id) REFERENCES students(id)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn ensure_seating_plan_ids(conn: &Connection) -> anyhow::Result<()> {
+    if table_has_column(conn, "seating_plans", "id")? {
+        return Ok(());
+    }
+
+    // Older workspaces kept one plan per class (keyed by class_id) and assignments keyed by
+    // (class_id, student_id). Rebuild both tables and turn each class's layout into its
+    // default plan.
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        "ALTER TABLE seating_plans RENAME TO seating_plans_v1;
+         ALTER TABLE seating_assignments RENAME TO seating_assignments_v1;
+         DROP INDEX IF EXISTS idx_seating_assignments_class;
+         DROP INDEX IF EXISTS idx_seating_assignments_student;",
+    )?;
+    create_seating_tables(&tx)?;
+
+    let class_ids = {
+        let mut stmt = tx.prepare(
+            "SELECT class_id FROM seating_plans_v1
+             UNION
+             SELECT class_id FROM seating_assignments_v1",
+        )?;
+        let ids = stmt
+            .query_map([], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        ids
+    };
+    for class_id in class_ids {
+        let plan_id = uuid::Uuid::new_v4().to_string();
+        // Assignments without a plan row were laid out on the 6x5 default grid.
+        tx.execute(
+            "INSERT INTO seating_plans(id, class_id, name, is_default, rows, seats_per_row, blocked_mask)
+             SELECT ?1, ?2, 'Default', 1,
+                    COALESCE(p.rows, 6), COALESCE(p.seats_per_row, 5), COALESCE(p.blocked_mask, ?3)
+             FROM (SELECT 1) LEFT JOIN seating_plans_v1 p ON p.class_id = ?2",
+            (&plan_id, &class_id, "0".repeat(100)),
+        )?;
+        tx.execute(
+            "INSERT INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+             SELECT class_id, ?1, student_id, seat_code
+             FROM seating_assignments_v1
+             WHERE class_id = ?2",
+            (&plan_id, &class_id),
+        )?;
+    }
+
+    tx.execute_batch(
+        "DROP TABLE seating_assignments_v1;
+         DROP TABLE seating_plans_v1;",
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
 fn extract_mark_set_mask_from_raw_line(raw_line: &str) -> Option<String> {
     let t = raw_line.trim();
     if t.is_empty() {
@@ -749,6 +1158,14 @@ fn ensure_assessments_legacy_type(conn: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn ensure_categories_drop_lowest(conn: &Connection) -> anyhow::Result<()> {
+    if table_has_column(conn, "categories", "drop_lowest")? {
+        return Ok(());
+    }
+    conn.execute("ALTER TABLE categories ADD COLUMN drop_lowest INTEGER", [])?;
+    Ok(())
+}
+
 fn ensure_scores_remark(conn: &Connection) -> anyhow::Result<()> {
     if table_has_column(conn, "scores", "remark")? {
         return Ok(());
@@ -776,6 +1193,64 @@ fn migrate_scores_statuses(conn: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn create_mark_sets_code_index(conn: &Connection) -> anyhow::Result<()> {
+    // v0 -> v1: legacy re-imports and exchange CSV imports resolve mark sets by code.
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_mark_sets_class_code ON mark_sets(class_id, code)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn create_edit_log(conn: &Connection) -> anyhow::Result<()> {
+    // v1 -> v2: undo log for destructive edits; `payload_json` holds the rows as
+    // they were before the operation. Pruned per class by the edits handlers.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS edit_log(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            class_id TEXT NOT NULL,
+            op TEXT NOT NULL,
+            payload_json TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY(class_id) REFERENCES classes(id)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_edit_log_class ON edit_log(class_id, id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn create_score_history(conn: &Connection) -> anyhow::Result<()> {
+    // v4 -> v5: per-cell audit trail written by every score writer and capped per
+    // cell there. A null old/new status means the cell had no row on that side.
+    // No ON DELETE CASCADE: rows are deleted, re-pointed or snapshotted explicitly
+    // wherever scores are.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS score_history(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            assessment_id TEXT NOT NULL,
+            student_id TEXT NOT NULL,
+            old_raw REAL,
+            old_status TEXT,
+            new_raw REAL,
+            new_status TEXT,
+            changed_at TEXT NOT NULL,
+            FOREIGN KEY(assessment_id) REFERENCES assessments(id),
+            FOREIGN KEY(student_id) REFERENCES students(id)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_score_history_cell
+         ON score_history(assessment_id, student_id, id)",
+        [],
+    )?;
+    Ok(())
+}
+
 fn table_has_column(conn: &Connection, table: &str, column: &str) -> anyhow::Result<bool> {
     let sql = format!("PRAGMA table_info({})", table);
     let mut stmt = conn.prepare(&sql)?;
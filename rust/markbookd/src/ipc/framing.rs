@@ -0,0 +1,242 @@
+//! Message framing on stdin/stdout. `newline` (the default) carries one JSON
+//! request, or batch array, per line. `length`, selected with `--framing length`,
+//! sends each message as its byte length in ASCII decimal on a line of its own,
+//! followed by exactly that many bytes of UTF-8 JSON:
+//!
+//! ```text
+//! 40\n{"id":"1","method":"health","params":{}}
+//! ```
+//!
+//! Payloads may then span lines and grow to `MAX_FRAME_BYTES` without depending
+//! on how the client's writes get split. Responses use the same framing as
+//! requests. Blank lines between frames are ignored.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Largest payload accepted in `length` mode. Bigger frames are skipped
+/// without being buffered and answered with `bad_frame`.
+pub const MAX_FRAME_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    Newline,
+    Length,
+}
+
+impl Framing {
+    /// Reads `--framing <mode>` or `--framing=<mode>` from the process
+    /// arguments (without the program name). Other arguments are ignored.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Framing, String> {
+        let mut args = args.into_iter();
+        let mut framing = Framing::Newline;
+        while let Some(arg) = args.next() {
+            let value = if arg == "--framing" {
+                args.next()
+                    .ok_or_else(|| "--framing needs a value".to_string())?
+            } else if let Some(v) = arg.strip_prefix("--framing=") {
+                v.to_string()
+            } else {
+                continue;
+            };
+            framing = match value.as_str() {
+                "newline" => Framing::Newline,
+                "length" => Framing::Length,
+                other => {
+                    return Err(format!(
+                        "unknown framing {:?} (expected newline or length)",
+                        other
+                    ))
+                }
+            };
+        }
+        Ok(framing)
+    }
+}
+
+/// One unit read from stdin.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// Raw payload bytes; not yet checked for UTF-8.
+    Message(Vec<u8>),
+    /// A `length` frame that could not be read. An oversized or truncated
+    /// frame has been consumed whole; for an unparseable header only that
+    /// line has been consumed, so the next read starts on the line after it.
+    Bad(String),
+}
+
+/// Reads the next frame, or `None` at end of input. In `newline` mode
+/// messages are returned with their line ending stripped, blank lines included.
+pub fn read_frame<R: BufRead>(reader: &mut R, framing: Framing) -> io::Result<Option<Frame>> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        while matches!(line.last(), Some(b'\n' | b'\r')) {
+            line.pop();
+        }
+        if framing == Framing::Newline {
+            return Ok(Some(Frame::Message(line)));
+        }
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            continue;
+        }
+        break;
+    }
+
+    let header = String::from_utf8_lossy(&line);
+    let Ok(len) = header.trim().parse::<usize>() else {
+        let shown: String = header.chars().take(32).collect();
+        return Ok(Some(Frame::Bad(format!(
+            "invalid frame header {:?}; expected a byte length",
+            shown
+        ))));
+    };
+    if len > MAX_FRAME_BYTES {
+        io::copy(&mut reader.take(len as u64), &mut io::sink())?;
+        return Ok(Some(Frame::Bad(format!(
+            "frame of {} bytes exceeds the {} byte limit",
+            len, MAX_FRAME_BYTES
+        ))));
+    }
+    // Grow the buffer as bytes arrive rather than trusting the header, so a
+    // large length with no payload behind it doesn't allocate up front.
+    let mut payload = Vec::new();
+    reader.take(len as u64).read_to_end(&mut payload)?;
+    if payload.len() < len {
+        return Ok(Some(Frame::Bad(format!(
+            "input ended inside a {} byte frame",
+            len
+        ))));
+    }
+    Ok(Some(Frame::Message(payload)))
+}
+
+/// Writes one response and flushes.
+pub fn write_frame<W: Write>(writer: &mut W, framing: Framing, body: &str) -> io::Result<()> {
+    match framing {
+        Framing::Newline => writeln!(writer, "{}", body)?,
+        Framing::Length => {
+            writeln!(writer, "{}", body.len())?;
+            writer.write_all(body.as_bytes())?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Cursor};
+
+    fn framed(payloads: &[&[u8]]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for p in payloads {
+            out.extend_from_slice(format!("{}\n", p.len()).as_bytes());
+            out.extend_from_slice(p);
+        }
+        out
+    }
+
+    #[test]
+    fn parses_framing_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(Framing::from_args(args(&[])), Ok(Framing::Newline));
+        assert_eq!(
+            Framing::from_args(args(&["--framing", "length"])),
+            Ok(Framing::Length)
+        );
+        assert_eq!(
+            Framing::from_args(args(&["--framing=newline"])),
+            Ok(Framing::Newline)
+        );
+        assert!(Framing::from_args(args(&["--framing", "xml"])).is_err());
+        assert!(Framing::from_args(args(&["--framing"])).is_err());
+    }
+
+    #[test]
+    fn round_trips_a_2mb_request_through_small_reads() {
+        let note = "line\n".repeat(2 * 1024 * 1024 / 5);
+        let request = serde_json::to_string_pretty(&serde_json::json!({
+            "id": "big",
+            "method": "notes.update",
+            "params": { "note": note },
+        }))
+        .expect("serialize");
+        assert!(request.len() > 2 * 1024 * 1024);
+
+        let mut out = Vec::new();
+        write_frame(&mut out, Framing::Length, &request).expect("write");
+        write_frame(&mut out, Framing::Length, "{}").expect("write");
+        // A tiny buffer forces the payload to arrive in many partial reads.
+        let mut reader = BufReader::with_capacity(7, Cursor::new(out));
+        assert_eq!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            Some(Frame::Message(request.into_bytes()))
+        );
+        assert_eq!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            Some(Frame::Message(b"{}".to_vec()))
+        );
+        assert_eq!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            None
+        );
+    }
+
+    #[test]
+    fn recovers_after_bad_headers_and_truncated_frames() {
+        let mut input = b"\nnope\n".to_vec();
+        input.extend(framed(&[b"[1]"]));
+        input.extend_from_slice(b"10\nabc");
+        let mut reader = Cursor::new(input);
+        assert!(matches!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            Some(Frame::Bad(_))
+        ));
+        assert_eq!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            Some(Frame::Message(b"[1]".to_vec()))
+        );
+        assert!(matches!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            Some(Frame::Bad(_))
+        ));
+        assert_eq!(
+            read_frame(&mut reader, Framing::Length).expect("read"),
+            None
+        );
+    }
+
+    #[test]
+    fn large_header_without_payload_is_truncated_not_preallocated() {
+        let header = format!("{}\nabc", MAX_FRAME_BYTES);
+        let mut reader = Cursor::new(header.into_bytes());
+        let frame = read_frame(&mut reader, Framing::Length).expect("read");
+        assert_eq!(
+            frame,
+            Some(Frame::Bad(format!(
+                "input ended inside a {} byte frame",
+                MAX_FRAME_BYTES
+            )))
+        );
+    }
+
+    #[test]
+    fn newline_mode_keeps_invalid_utf8_bytes() {
+        let mut reader = Cursor::new(b"{\"id\":\"1\"}\r\n\xff\xfe\n".to_vec());
+        assert_eq!(
+            read_frame(&mut reader, Framing::Newline).expect("read"),
+            Some(Frame::Message(b"{\"id\":\"1\"}".to_vec()))
+        );
+        assert_eq!(
+            read_frame(&mut reader, Framing::Newline).expect("read"),
+            Some(Frame::Message(b"\xff\xfe".to_vec()))
+        );
+        assert_eq!(
+            read_frame(&mut reader, Framing::Newline).expect("read"),
+            None
+        );
+    }
+}
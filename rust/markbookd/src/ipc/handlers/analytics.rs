@@ -1550,6 +1550,9 @@ fn handle_analytics_class_assessment_drilldown(
             avg_raw: 0.0,
             avg_percent: 0.0,
             median_percent: 0.0,
+            std_dev_percent: 0.0,
+            min_percent: 0.0,
+            max_percent: 0.0,
             scored_count: 0,
             zero_count: 0,
             no_mark_count: 0,
@@ -2150,6 +2153,18 @@ fn handle_analytics_student_trend(state: &mut AppState, req: &Request) -> serde_
     )
 }
 
+pub const METHODS: &[&str] = &[
+    "analytics.class.open",
+    "analytics.class.rows",
+    "analytics.class.assessmentDrilldown",
+    "analytics.student.open",
+    "analytics.student.compare",
+    "analytics.student.trend",
+    "analytics.filters.options",
+    "analytics.combined.options",
+    "analytics.combined.open",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "analytics.class.open" => Some(handle_analytics_class_open(state, req)),
@@ -1,10 +1,15 @@
 use crate::ipc::error::{err, ok};
+use crate::ipc::helpers::{base64_decode, base64_encode};
 use crate::ipc::types::{AppState, Request};
 use rusqlite::{params_from_iter, Connection, OptionalExtension, ToSql};
 use serde_json::json;
 use std::collections::HashMap;
+use std::path::Path;
 use uuid::Uuid;
 
+/// Largest decoded student photo accepted by `assets.putStudentPhoto`.
+const MAX_PHOTO_BYTES: usize = 2 * 1024 * 1024;
+
 struct HandlerErr {
     code: &'static str,
     message: String,
@@ -25,6 +30,13 @@ struct BasicStudent {
     active: bool,
 }
 
+struct LoanedReportGroup {
+    student_id: String,
+    display_name: String,
+    items: Vec<serde_json::Value>,
+    total_quantity: f64,
+}
+
 fn get_required_str(params: &serde_json::Value, key: &str) -> Result<String, HandlerErr> {
     params
         .get(key)
@@ -291,6 +303,246 @@ fn loaned_update(
     Ok(json!({ "ok": true, "itemId": item_id }))
 }
 
+fn loaned_create(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let item_name = get_required_str(params, "itemName")?.trim().to_string();
+    if item_name.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "itemName must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let mark_set_id = params
+        .get("markSetId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let quantity = params.get("quantity").and_then(|v| v.as_f64());
+    let notes = params
+        .get("notes")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let student_exists = conn
+        .query_row(
+            "SELECT 1 FROM students WHERE class_id = ? AND id = ?",
+            (&class_id, &student_id),
+            |r| r.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?
+        .is_some();
+    if !student_exists {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "student not found".to_string(),
+            details: None,
+        });
+    }
+    if let Some(ref msid) = mark_set_id {
+        let mark_set_exists = conn
+            .query_row(
+                "SELECT 1 FROM mark_sets WHERE class_id = ? AND id = ? AND deleted_at IS NULL",
+                (&class_id, msid),
+                |r| r.get::<_, i64>(0),
+            )
+            .optional()
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?
+            .is_some();
+        if !mark_set_exists {
+            return Err(HandlerErr {
+                code: "not_found",
+                message: "mark set not found".to_string(),
+                details: None,
+            });
+        }
+    }
+
+    // Manual items carry an empty raw_line; TBK re-imports only replace
+    // rows that came from a legacy file.
+    let item_id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO loaned_items(id, class_id, student_id, mark_set_id, item_name, quantity, notes, raw_line)
+         VALUES(?, ?, ?, ?, ?, ?, ?, '')",
+        (
+            &item_id,
+            &class_id,
+            &student_id,
+            mark_set_id.as_deref(),
+            &item_name,
+            quantity,
+            notes.as_deref(),
+        ),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "loaned_items" })),
+    })?;
+    Ok(json!({ "itemId": item_id }))
+}
+
+fn loaned_delete(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let item_id = get_required_str(params, "itemId")?;
+    let deleted = conn
+        .execute(
+            "DELETE FROM loaned_items WHERE class_id = ? AND id = ?",
+            (&class_id, &item_id),
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "loaned_items" })),
+        })?;
+    if deleted == 0 {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "loaned item not found".to_string(),
+            details: None,
+        });
+    }
+    Ok(json!({ "ok": true }))
+}
+
+fn loaned_report(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let mark_set_id = params
+        .get("markSetId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let mut sql = String::from(
+        "SELECT li.id, li.student_id, s.last_name, s.first_name, li.mark_set_id, li.item_name, li.quantity, li.notes
+         FROM loaned_items li
+         JOIN students s ON s.id = li.student_id
+         WHERE li.class_id = ?",
+    );
+    let mut binds: Vec<&dyn ToSql> = vec![&class_id];
+    if let Some(ref msid) = mark_set_id {
+        sql.push_str(" AND li.mark_set_id = ?");
+        binds.push(msid);
+    }
+    sql.push_str(" ORDER BY s.sort_order, li.item_name, li.id");
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let rows = stmt
+        .query_map(params_from_iter(binds), |r| {
+            let last_name: String = r.get(2)?;
+            let first_name: String = r.get(3)?;
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                format!("{}, {}", last_name, first_name),
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, String>(5)?,
+                r.get::<_, Option<f64>>(6)?,
+                r.get::<_, Option<String>>(7)?,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+
+    // Rows arrive ordered by student, so each group is a contiguous run.
+    let mut groups: Vec<LoanedReportGroup> = Vec::new();
+    let mut outstanding: Vec<serde_json::Value> = Vec::with_capacity(rows.len());
+    for (id, student_id, display_name, row_mark_set_id, item_name, quantity, notes) in rows {
+        if groups
+            .last()
+            .map(|g| g.student_id != student_id)
+            .unwrap_or(true)
+        {
+            groups.push(LoanedReportGroup {
+                student_id: student_id.clone(),
+                display_name: display_name.clone(),
+                items: Vec::new(),
+                total_quantity: 0.0,
+            });
+        }
+        let group = groups.last_mut().expect("student group");
+        group.total_quantity += quantity.unwrap_or(0.0);
+        group.items.push(json!({
+            "id": id,
+            "markSetId": row_mark_set_id,
+            "itemName": item_name,
+            "quantity": quantity,
+            "notes": notes
+        }));
+        outstanding.push(json!({
+            "studentId": student_id,
+            "displayName": display_name,
+            "itemName": item_name,
+            "quantity": quantity,
+            "notes": notes
+        }));
+    }
+    let total_quantity: f64 = groups.iter().map(|g| g.total_quantity).sum();
+    let students: Vec<serde_json::Value> = groups
+        .into_iter()
+        .map(|g| {
+            json!({
+                "studentId": g.student_id,
+                "displayName": g.display_name,
+                "itemCount": g.items.len(),
+                "totalQuantity": g.total_quantity,
+                "items": g.items
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "classId": class_id,
+        "markSetId": mark_set_id,
+        "students": students,
+        "outstanding": outstanding,
+        "totals": {
+            "students": students.len(),
+            "items": outstanding.len(),
+            "quantity": total_quantity
+        }
+    }))
+}
+
 fn devices_list(
     conn: &Connection,
     params: &serde_json::Value,
@@ -469,6 +721,267 @@ fn devices_update(
     Ok(json!({ "ok": true }))
 }
 
+fn devices_create(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let device_code = get_required_str(params, "deviceCode")?.trim().to_string();
+    if device_code.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "deviceCode must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let raw_line = params
+        .get("rawLine")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let student_exists = conn
+        .query_row(
+            "SELECT 1 FROM students WHERE class_id = ? AND id = ?",
+            (&class_id, &student_id),
+            |r| r.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?
+        .is_some();
+    if !student_exists {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "student not found".to_string(),
+            details: None,
+        });
+    }
+
+    // One device per student per class; devices.update is the way to change it.
+    let device_id = Uuid::new_v4().to_string();
+    let inserted = conn
+        .execute(
+            "INSERT INTO student_device_map(id, class_id, student_id, device_code, raw_line)
+             VALUES(?, ?, ?, ?, ?)
+             ON CONFLICT(class_id, student_id) DO NOTHING",
+            (&device_id, &class_id, &student_id, &device_code, &raw_line),
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_insert_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "student_device_map" })),
+        })?;
+    if inserted == 0 {
+        return Err(HandlerErr {
+            code: "duplicate_device",
+            message: "student already has a device".to_string(),
+            details: Some(json!({ "studentId": student_id })),
+        });
+    }
+    Ok(json!({ "deviceId": device_id }))
+}
+
+fn devices_delete(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let deleted = conn
+        .execute(
+            "DELETE FROM student_device_map WHERE class_id = ? AND student_id = ?",
+            (&class_id, &student_id),
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "student_device_map" })),
+        })?;
+    if deleted == 0 {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "device not found".to_string(),
+            details: None,
+        });
+    }
+    Ok(json!({ "ok": true }))
+}
+
+/// File extension and MIME type for PNG and JPEG data, sniffed from magic bytes.
+pub(crate) fn photo_format(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("png", "image/png"))
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("jpg", "image/jpeg"))
+    } else {
+        None
+    }
+}
+
+/// The student's stored photo path (relative to the workspace), or
+/// `not_found` when the student is not in the class.
+fn student_photo_path(
+    conn: &Connection,
+    class_id: &str,
+    student_id: &str,
+) -> Result<Option<String>, HandlerErr> {
+    conn.query_row(
+        "SELECT photo_path FROM students WHERE class_id = ? AND id = ?",
+        (class_id, student_id),
+        |r| r.get::<_, Option<String>>(0),
+    )
+    .optional()
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?
+    .ok_or_else(|| HandlerErr {
+        code: "not_found",
+        message: "student not found".to_string(),
+        details: None,
+    })
+}
+
+/// Reads a stored photo as a `data:` URL for embedding in exported documents.
+/// `None` when the file is gone or no longer holds a PNG or JPEG.
+pub(crate) fn photo_data_url(workspace: &Path, rel_path: &str) -> Option<String> {
+    let bytes = std::fs::read(workspace.join(rel_path)).ok()?;
+    let (_, mime_type) = photo_format(&bytes)?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64_encode(&bytes)
+    ))
+}
+
+fn student_photo_put(
+    conn: &Connection,
+    workspace: &Path,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let data = get_required_str(params, "dataBase64")?;
+    // Accept data URLs as produced by FileReader.readAsDataURL.
+    let data = match data.strip_prefix("data:") {
+        Some(rest) => rest.split_once(',').map(|(_, b64)| b64).unwrap_or(""),
+        None => data.as_str(),
+    };
+    let too_large = || HandlerErr {
+        code: "bad_params",
+        message: format!("photo exceeds {} bytes", MAX_PHOTO_BYTES),
+        details: Some(json!({ "maxBytes": MAX_PHOTO_BYTES })),
+    };
+    // Reject before decoding; whitespace may pad the text a little.
+    if data.len() > MAX_PHOTO_BYTES.div_ceil(3) * 4 * 2 {
+        return Err(too_large());
+    }
+    let Some(bytes) = base64_decode(data) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "dataBase64 is not valid base64".to_string(),
+            details: None,
+        });
+    };
+    if bytes.len() > MAX_PHOTO_BYTES {
+        return Err(too_large());
+    }
+    let Some((ext, mime_type)) = photo_format(&bytes) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "photo must be a PNG or JPEG image".to_string(),
+            details: None,
+        });
+    };
+
+    let old_path = student_photo_path(conn, &class_id, &student_id)?;
+    let rel_path = format!("assets/photos/{}/{}.{}", class_id, student_id, ext);
+    let abs_path = workspace.join(&rel_path);
+    let io_failed = |e: std::io::Error| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": abs_path.to_string_lossy() })),
+    };
+    if let Some(parent) = abs_path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_failed)?;
+    }
+    std::fs::write(&abs_path, &bytes).map_err(io_failed)?;
+
+    conn.execute(
+        "UPDATE students SET photo_path = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE class_id = ? AND id = ?",
+        (&rel_path, &class_id, &student_id),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "students" })),
+    })?;
+    // A PNG replacing a JPEG (or the reverse) leaves the old file behind otherwise.
+    if let Some(old_path) = old_path.filter(|p| *p != rel_path) {
+        let _ = std::fs::remove_file(workspace.join(old_path));
+    }
+
+    Ok(json!({
+        "ok": true,
+        "path": rel_path,
+        "mimeType": mime_type,
+        "bytes": bytes.len()
+    }))
+}
+
+fn student_photo_get(
+    conn: &Connection,
+    workspace: &Path,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let Some(rel_path) = student_photo_path(conn, &class_id, &student_id)? else {
+        return Ok(json!({ "studentId": student_id, "photo": null }));
+    };
+    let abs_path = workspace.join(&rel_path);
+    let bytes = match std::fs::read(&abs_path) {
+        Ok(v) => v,
+        // The file was removed behind our back; report no photo.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(json!({ "studentId": student_id, "photo": null }))
+        }
+        Err(e) => {
+            return Err(HandlerErr {
+                code: "io_failed",
+                message: e.to_string(),
+                details: Some(json!({ "path": abs_path.to_string_lossy() })),
+            })
+        }
+    };
+    let mime_type = photo_format(&bytes)
+        .map(|(_, mime)| mime)
+        .unwrap_or("application/octet-stream");
+    Ok(json!({
+        "studentId": student_id,
+        "photo": {
+            "path": rel_path,
+            "mimeType": mime_type,
+            "bytes": bytes.len(),
+            "dataBase64": base64_encode(&bytes)
+        }
+    }))
+}
+
 fn learning_skills_open(
     conn: &Connection,
     params: &serde_json::Value,
@@ -633,6 +1146,102 @@ fn learning_skills_update_cell(
     Ok(json!({ "ok": true }))
 }
 
+/// Stamps one skill value on every active student for a term, in a single transaction.
+/// With `onlyEmpty`, cells that already hold a value are left alone.
+fn learning_skills_bulk_set(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let skill_code = get_required_str(params, "skillCode")?.to_ascii_uppercase();
+    if skill_code.is_empty() || skill_code.len() > 8 {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "skillCode must be 1..8 chars".to_string(),
+            details: None,
+        });
+    }
+    let term = params
+        .get("term")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(1)
+        .clamp(1, 3);
+    let value = get_required_str(params, "value")?.trim().to_string();
+    if value.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "value must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let only_empty = match params.get("onlyEmpty") {
+        None => false,
+        Some(v) => v.as_bool().ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "onlyEmpty must be a boolean".to_string(),
+            details: None,
+        })?,
+    };
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+
+    let active_ids: Vec<String> = list_students_for_class(conn, &class_id)?
+        .into_iter()
+        .filter(|s| s.active)
+        .map(|s| s.id)
+        .collect();
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let mut updated = 0usize;
+    {
+        // A blank legacy cell counts as empty; the conflict WHERE makes the
+        // statement a no-op (0 changes) for cells that keep their value.
+        let sql = if only_empty {
+            "INSERT INTO learning_skills_cells(class_id, student_id, term, skill_code, value, updated_at)
+             VALUES(?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))
+             ON CONFLICT(class_id, student_id, term, skill_code) DO UPDATE SET
+               value = excluded.value,
+               updated_at = excluded.updated_at
+             WHERE TRIM(learning_skills_cells.value) = ''"
+        } else {
+            "INSERT INTO learning_skills_cells(class_id, student_id, term, skill_code, value, updated_at)
+             VALUES(?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))
+             ON CONFLICT(class_id, student_id, term, skill_code) DO UPDATE SET
+               value = excluded.value,
+               updated_at = excluded.updated_at"
+        };
+        let mut stmt = tx.prepare(sql).map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+        for student_id in &active_ids {
+            updated += stmt
+                .execute((&class_id, student_id, term, &skill_code, &value))
+                .map_err(|e| HandlerErr {
+                    code: "db_update_failed",
+                    message: e.to_string(),
+                    details: Some(json!({ "table": "learning_skills_cells" })),
+                })?;
+        }
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    Ok(json!({ "updated": updated }))
+}
+
 fn learning_skills_report_model(
     conn: &Connection,
     params: &serde_json::Value,
@@ -698,6 +1307,36 @@ fn handle_loaned_update(state: &mut AppState, req: &Request) -> serde_json::Valu
     }
 }
 
+fn handle_loaned_create(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match loaned_create(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_loaned_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match loaned_delete(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_loaned_report(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match loaned_report(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_devices_list(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -728,6 +1367,46 @@ fn handle_devices_update(state: &mut AppState, req: &Request) -> serde_json::Val
     }
 }
 
+fn handle_devices_create(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match devices_create(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_devices_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match devices_delete(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_student_photo_put(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let (Some(conn), Some(workspace)) = (state.db.as_ref(), state.workspace.as_ref()) else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match student_photo_put(conn, workspace, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_student_photo_get(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let (Some(conn), Some(workspace)) = (state.db.as_ref(), state.workspace.as_ref()) else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match student_photo_get(conn, workspace, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_learning_skills_open(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -748,6 +1427,16 @@ fn handle_learning_skills_update_cell(state: &mut AppState, req: &Request) -> se
     }
 }
 
+fn handle_learning_skills_bulk_set(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match learning_skills_bulk_set(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_learning_skills_report_model(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -758,16 +1447,44 @@ fn handle_learning_skills_report_model(state: &mut AppState, req: &Request) -> s
     }
 }
 
+pub const METHODS: &[&str] = &[
+    "loaned.list",
+    "loaned.get",
+    "loaned.update",
+    "loaned.create",
+    "loaned.delete",
+    "loaned.report",
+    "devices.list",
+    "devices.get",
+    "devices.update",
+    "devices.create",
+    "devices.delete",
+    "assets.putStudentPhoto",
+    "assets.getStudentPhoto",
+    "learningSkills.open",
+    "learningSkills.updateCell",
+    "learningSkills.bulkSet",
+    "learningSkills.reportModel",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "loaned.list" => Some(handle_loaned_list(state, req)),
         "loaned.get" => Some(handle_loaned_get(state, req)),
         "loaned.update" => Some(handle_loaned_update(state, req)),
+        "loaned.create" => Some(handle_loaned_create(state, req)),
+        "loaned.delete" => Some(handle_loaned_delete(state, req)),
+        "loaned.report" => Some(handle_loaned_report(state, req)),
         "devices.list" => Some(handle_devices_list(state, req)),
         "devices.get" => Some(handle_devices_get(state, req)),
         "devices.update" => Some(handle_devices_update(state, req)),
+        "devices.create" => Some(handle_devices_create(state, req)),
+        "devices.delete" => Some(handle_devices_delete(state, req)),
+        "assets.putStudentPhoto" => Some(handle_student_photo_put(state, req)),
+        "assets.getStudentPhoto" => Some(handle_student_photo_get(state, req)),
         "learningSkills.open" => Some(handle_learning_skills_open(state, req)),
         "learningSkills.updateCell" => Some(handle_learning_skills_update_cell(state, req)),
+        "learningSkills.bulkSet" => Some(handle_learning_skills_bulk_set(state, req)),
         "learningSkills.reportModel" => Some(handle_learning_skills_report_model(state, req)),
         _ => None,
     }
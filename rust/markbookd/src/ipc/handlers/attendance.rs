@@ -4,14 +4,14 @@ use rusqlite::{Connection, OptionalExtension};
 use serde_json::json;
 use std::collections::HashMap;
 
-struct HandlerErr {
+pub(crate) struct HandlerErr {
     code: &'static str,
     message: String,
     details: Option<serde_json::Value>,
 }
 
 impl HandlerErr {
-    fn response(self, id: &str) -> serde_json::Value {
+    pub(crate) fn response(self, id: &str) -> serde_json::Value {
         err(id, self.code, self.message, self.details)
     }
 }
@@ -83,11 +83,14 @@ fn list_students_for_class(
     })
 }
 
+/// Placeholder year for bare MM month keys, which carry no year of their own.
+pub(crate) const UNDATED_YEAR: i32 = 2001;
+
 fn parse_month_key(month: &str) -> Result<(i32, u32), HandlerErr> {
     let t = month.trim();
     if let Ok(m) = t.parse::<u32>() {
         if (1..=12).contains(&m) {
-            return Ok((2001, m));
+            return Ok((UNDATED_YEAR, m));
         }
     }
     let Some((y, m)) = t.split_once('-') else {
@@ -117,7 +120,15 @@ fn parse_month_key(month: &str) -> Result<(i32, u32), HandlerErr> {
     Ok((year, month_num))
 }
 
-fn days_in_month(year: i32, month: u32) -> usize {
+/// Splits a stored or imported month key into its year and calendar month. Bare MM keys
+/// (legacy import) come back with no year.
+pub(crate) fn parse_stored_month(key: &str) -> Option<(Option<i32>, u32)> {
+    let t = key.trim();
+    let (year, month) = parse_month_key(t).ok()?;
+    Some((t.contains('-').then_some(year), month))
+}
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> usize {
     let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
     match month {
         1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
@@ -166,6 +177,152 @@ fn parse_optional_code_char(v: Option<&serde_json::Value>) -> Result<Option<char
     Ok(t.chars().next())
 }
 
+fn school_year_start_month(conn: &Connection, class_id: &str) -> Result<i64, HandlerErr> {
+    conn.query_row(
+        "SELECT school_year_start_month FROM attendance_settings WHERE class_id = ?",
+        [class_id],
+        |r| r.get(0),
+    )
+    .optional()
+    .map(|v| v.unwrap_or(9))
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })
+}
+
+/// Calendar months of a school year, starting at `start_month` (1..=12) and wrapping at December.
+fn school_year_months(start_month: u32) -> Vec<u32> {
+    (0..12).map(|i| (start_month - 1 + i) % 12 + 1).collect()
+}
+
+/// Stored attendance strings keyed by `(year, month)`; see [`parse_stored_month`].
+type MonthRows = HashMap<(Option<i32>, u32), String>;
+
+/// The twelve months a year-wide read covers. `start_year` is the calendar year of the first
+/// month, or `None` when the class only has undated (MM) rows.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SchoolYear {
+    start_month: u32,
+    start_year: Option<i32>,
+}
+
+impl SchoolYear {
+    fn months(&self) -> Vec<u32> {
+        school_year_months(self.start_month)
+    }
+
+    /// Calendar year of `month` within this school year.
+    pub(crate) fn year_of(&self, month: u32) -> Option<i32> {
+        self.start_year
+            .map(|y| if month >= self.start_month { y } else { y + 1 })
+    }
+
+    fn days_in(&self, month: u32) -> usize {
+        days_in_month(self.year_of(month).unwrap_or(UNDATED_YEAR), month)
+    }
+
+    fn month_key(&self, month: u32) -> String {
+        match self.year_of(month) {
+            Some(y) => format!("{:04}-{:02}", y, month),
+            None => month.to_string(),
+        }
+    }
+
+    /// The row stored for `month`: its YYYY-MM row in this school year, else the bare MM row.
+    /// Rows dated in other school years are never picked.
+    fn row<'a>(&self, rows: &'a MonthRows, month: u32) -> Option<&'a String> {
+        self.year_of(month)
+            .and_then(|y| rows.get(&(Some(y), month)))
+            .or_else(|| rows.get(&(None, month)))
+    }
+}
+
+/// Resolves the school year a year-wide read covers: the optional `schoolYear` param (the
+/// calendar year the school year starts in), else the latest school year with a dated row.
+fn resolve_school_year(
+    conn: &Connection,
+    class_id: &str,
+    params: &serde_json::Value,
+    start_month: u32,
+) -> Result<SchoolYear, HandlerErr> {
+    let start_year = match params.get("schoolYear") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_i64() {
+            Some(y) if (1000..=9999).contains(&y) => Some(y as i32),
+            _ => {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: "schoolYear must be a four-digit year".to_string(),
+                    details: Some(json!({ "schoolYear": v })),
+                })
+            }
+        },
+    };
+    if start_year.is_some() {
+        return Ok(SchoolYear {
+            start_month,
+            start_year,
+        });
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(month AS TEXT) FROM attendance_months WHERE class_id = ?1
+             UNION
+             SELECT CAST(month AS TEXT) FROM attendance_student_months WHERE class_id = ?1",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let keys = stmt
+        .query_map([class_id], |r| r.get::<_, String>(0))
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let start_year = keys
+        .iter()
+        .filter_map(|k| match parse_stored_month(k) {
+            Some((Some(y), m)) => Some(if m >= start_month { y } else { y - 1 }),
+            _ => None,
+        })
+        .max();
+    Ok(SchoolYear {
+        start_month,
+        start_year,
+    })
+}
+
+/// The class's school year when no `schoolYear` is given; see [`resolve_school_year`].
+pub(crate) fn default_school_year(
+    conn: &Connection,
+    class_id: &str,
+) -> Result<SchoolYear, HandlerErr> {
+    let start_month = school_year_start_month(conn, class_id)?.clamp(1, 12) as u32;
+    resolve_school_year(conn, class_id, &json!({}), start_month)
+}
+
+fn students_json(students: &[BasicStudent]) -> Vec<serde_json::Value> {
+    students
+        .iter()
+        .map(|s| {
+            json!({
+                "id": s.id,
+                "displayName": s.display_name,
+                "sortOrder": s.sort_order,
+                "active": s.active
+            })
+        })
+        .collect()
+}
+
 fn attendance_month_open(
     conn: &Connection,
     params: &serde_json::Value,
@@ -183,19 +340,7 @@ fn attendance_month_open(
         });
     }
     let students = list_students_for_class(conn, &class_id)?;
-    let school_year_start_month: i64 = conn
-        .query_row(
-            "SELECT school_year_start_month FROM attendance_settings WHERE class_id = ?",
-            [&class_id],
-            |r| r.get(0),
-        )
-        .optional()
-        .map_err(|e| HandlerErr {
-            code: "db_query_failed",
-            message: e.to_string(),
-            details: None,
-        })?
-        .unwrap_or(9);
+    let school_year_start_month = school_year_start_month(conn, &class_id)?;
 
     let type_of_day_codes_raw: Option<String> = conn
         .query_row(
@@ -238,17 +383,7 @@ fn attendance_month_open(
         by_student.insert(student_id, normalize_day_codes(&day_codes, days));
     }
 
-    let students_json: Vec<serde_json::Value> = students
-        .iter()
-        .map(|s| {
-            json!({
-                "id": s.id,
-                "displayName": s.display_name,
-                "sortOrder": s.sort_order,
-                "active": s.active
-            })
-        })
-        .collect();
+    let students_json = students_json(&students);
     let rows_json: Vec<serde_json::Value> = students
         .iter()
         .map(|s| {
@@ -273,6 +408,591 @@ fn attendance_month_open(
     }))
 }
 
+/// Stored type-of-day rows. Month keys are stored as MM (legacy import, no year) or YYYY-MM.
+fn type_of_day_codes_by_month(conn: &Connection, class_id: &str) -> Result<MonthRows, HandlerErr> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(month AS TEXT), type_of_day_codes
+             FROM attendance_months
+             WHERE class_id = ?",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let rows = stmt
+        .query_map([class_id], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(month_key, codes)| parse_stored_month(&month_key).map(|k| (k, codes)))
+        .collect())
+}
+
+/// Stored day codes for a class by student, each keyed like [`type_of_day_codes_by_month`].
+fn student_day_codes_by_month(
+    conn: &Connection,
+    class_id: &str,
+) -> Result<HashMap<String, MonthRows>, HandlerErr> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(month AS TEXT), student_id, day_codes
+             FROM attendance_student_months
+             WHERE class_id = ?",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let rows = stmt
+        .query_map([class_id], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let mut by_student: HashMap<String, MonthRows> = HashMap::new();
+    for (month_key, student_id, codes) in rows {
+        if let Some(k) = parse_stored_month(&month_key) {
+            by_student.entry(student_id).or_default().insert(k, codes);
+        }
+    }
+    Ok(by_student)
+}
+
+fn attendance_year_open(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let start_month = match params.get("schoolYearStartMonth") {
+        None => school_year_start_month(conn, &class_id)?,
+        Some(v) if v.is_null() => school_year_start_month(conn, &class_id)?,
+        Some(v) => match v.as_i64() {
+            Some(m) if (1..=12).contains(&m) => m,
+            _ => {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: "schoolYearStartMonth must be between 1 and 12".to_string(),
+                    details: Some(json!({ "schoolYearStartMonth": v })),
+                })
+            }
+        },
+    };
+    let start_month = if (1..=12).contains(&start_month) {
+        start_month
+    } else {
+        9
+    };
+    let school_year = resolve_school_year(conn, &class_id, params, start_month as u32)?;
+    let students = list_students_for_class(conn, &class_id)?;
+
+    let type_of_day_by_month = type_of_day_codes_by_month(conn, &class_id)?;
+    let day_codes_by_student = student_day_codes_by_month(conn, &class_id)?;
+
+    let months_json: Vec<serde_json::Value> = school_year
+        .months()
+        .into_iter()
+        .map(|month_num| {
+            let days = school_year.days_in(month_num);
+            let type_of_day_codes = normalize_day_codes(
+                school_year
+                    .row(&type_of_day_by_month, month_num)
+                    .map(|v| v.as_str())
+                    .unwrap_or(""),
+                days,
+            );
+            let rows_json: Vec<serde_json::Value> = students
+                .iter()
+                .map(|s| {
+                    let raw = day_codes_by_student
+                        .get(&s.id)
+                        .and_then(|m| school_year.row(m, month_num))
+                        .map(|v| v.as_str())
+                        .unwrap_or("");
+                    json!({
+                        "studentId": s.id,
+                        "dayCodes": normalize_day_codes(raw, days)
+                    })
+                })
+                .collect();
+            json!({
+                "month": month_num.to_string(),
+                "daysInMonth": days,
+                "typeOfDayCodes": type_of_day_codes,
+                "rows": rows_json
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "schoolYearStartMonth": start_month,
+        "schoolYear": school_year.start_year,
+        "students": students_json(&students),
+        "months": months_json
+    }))
+}
+
+/// One row of the workspace `attendance_codes` legend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttendanceCode {
+    code: char,
+    label: String,
+    counts_as_absent: bool,
+    counts_as_late: bool,
+    is_excused: bool,
+}
+
+impl AttendanceCode {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "code": self.code.to_string(),
+            "label": self.label,
+            "countsAsAbsent": self.counts_as_absent,
+            "countsAsLate": self.counts_as_late,
+            "isExcused": self.is_excused
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct AttendanceTally {
+    present: usize,
+    absent: usize,
+    late: usize,
+    excused: usize,
+}
+
+/// The workspace legend in display order (seeded with P/A/L/E when the workspace opens).
+fn attendance_codes(conn: &Connection) -> Result<Vec<AttendanceCode>, HandlerErr> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT code, label, counts_as_absent, counts_as_late, is_excused
+             FROM attendance_codes
+             ORDER BY sort_order, code",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, i64>(2)? != 0,
+                r.get::<_, i64>(3)? != 0,
+                r.get::<_, i64>(4)? != 0,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    Ok(rows
+        .into_iter()
+        .filter_map(
+            |(code, label, counts_as_absent, counts_as_late, is_excused)| {
+                code.chars().next().map(|code| AttendanceCode {
+                    code,
+                    label,
+                    counts_as_absent,
+                    counts_as_late,
+                    is_excused,
+                })
+            },
+        )
+        .collect())
+}
+
+/// Adds one month of a student's `day_codes` to `tally`. Any non-blank type-of-day code marks a
+/// non-school day (holiday, PD day, weekend) and that day is skipped entirely. A legend code
+/// counts as excused, else absent, else late by its flags, and as present with no flags set;
+/// codes missing from the legend are ignored.
+fn tally_day_codes(
+    type_of_day_codes: &str,
+    day_codes: &str,
+    codes: &[AttendanceCode],
+    tally: &mut AttendanceTally,
+) {
+    let mut type_of_day = type_of_day_codes.chars();
+    for code in day_codes.chars() {
+        let day_type = type_of_day.next().unwrap_or(' ');
+        if day_type != ' ' {
+            continue;
+        }
+        let code = code.to_ascii_uppercase();
+        let Some(entry) = codes.iter().find(|c| c.code == code) else {
+            continue;
+        };
+        if entry.is_excused {
+            tally.excused += 1;
+        } else if entry.counts_as_absent {
+            tally.absent += 1;
+        } else if entry.counts_as_late {
+            tally.late += 1;
+        } else {
+            tally.present += 1;
+        }
+    }
+}
+
+fn attendance_codes_list(conn: &Connection) -> Result<serde_json::Value, HandlerErr> {
+    let codes: Vec<serde_json::Value> = attendance_codes(conn)?
+        .iter()
+        .map(AttendanceCode::to_json)
+        .collect();
+    Ok(json!({ "codes": codes }))
+}
+
+/// Adds or relabels one legend code. Codes are single characters, stored upper-case to match
+/// how day codes are tallied; new codes go to the end of the legend.
+fn attendance_codes_upsert(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let raw_code = get_required_str(params, "code")?;
+    let mut chars = raw_code.trim().chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => c.to_ascii_uppercase(),
+        _ => {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "code must be a single character".to_string(),
+                details: Some(json!({ "code": raw_code })),
+            })
+        }
+    };
+    let label = get_required_str(params, "label")?.trim().to_string();
+    if label.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "label must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let mut flags = [false; 3];
+    for (flag, key) in flags
+        .iter_mut()
+        .zip(["countsAsAbsent", "countsAsLate", "isExcused"])
+    {
+        *flag = match params.get(key) {
+            None => false,
+            Some(v) if v.is_null() => false,
+            Some(v) => v.as_bool().ok_or_else(|| HandlerErr {
+                code: "bad_params",
+                message: format!("{} must be a boolean", key),
+                details: None,
+            })?,
+        };
+    }
+    let [counts_as_absent, counts_as_late, is_excused] = flags;
+
+    conn.execute(
+        "INSERT INTO attendance_codes(
+            code, label, counts_as_absent, counts_as_late, is_excused, sort_order
+         ) VALUES(?, ?, ?, ?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM attendance_codes))
+         ON CONFLICT(code) DO UPDATE SET
+           label = excluded.label,
+           counts_as_absent = excluded.counts_as_absent,
+           counts_as_late = excluded.counts_as_late,
+           is_excused = excluded.is_excused",
+        (
+            code.to_string(),
+            &label,
+            counts_as_absent,
+            counts_as_late,
+            is_excused,
+        ),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "attendance_codes" })),
+    })?;
+    attendance_codes_list(conn)
+}
+
+fn parse_optional_month(params: &serde_json::Value, key: &str) -> Result<Option<u32>, HandlerErr> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(v) if v.is_null() => Ok(None),
+        Some(v) => {
+            let raw = match v {
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::String(s) => s.clone(),
+                _ => {
+                    return Err(HandlerErr {
+                        code: "bad_params",
+                        message: format!("{} must be MM or YYYY-MM", key),
+                        details: None,
+                    })
+                }
+            };
+            parse_month_key(&raw).map(|(_, m)| Some(m))
+        }
+    }
+}
+
+/// The school year (see [`resolve_school_year`]) and its calendar months picked by the optional
+/// `monthFrom`/`monthTo` params, in school-year order. Ranges follow the school year, so
+/// monthFrom=9, monthTo=1 covers September..January.
+fn selected_months(
+    conn: &Connection,
+    class_id: &str,
+    params: &serde_json::Value,
+) -> Result<(SchoolYear, Vec<u32>), HandlerErr> {
+    let month_from = parse_optional_month(params, "monthFrom")?;
+    let month_to = parse_optional_month(params, "monthTo")?;
+
+    let start_month = school_year_start_month(conn, class_id)?.clamp(1, 12) as u32;
+    let school_year = resolve_school_year(conn, class_id, params, start_month)?;
+    let year_months = school_year.months();
+    let pos = |m: u32| year_months.iter().position(|x| *x == m).unwrap_or(0);
+    let from_pos = month_from.map(pos).unwrap_or(0);
+    let to_pos = month_to.map(pos).unwrap_or(11);
+    if from_pos > to_pos {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "monthFrom must not come after monthTo in the school year".to_string(),
+            details: Some(json!({ "monthFrom": month_from, "monthTo": month_to })),
+        });
+    }
+    Ok((school_year, year_months[from_pos..=to_pos].to_vec()))
+}
+
+/// One school day of one student's attendance, as written by `exchange.exportAttendanceCsv`.
+pub(crate) struct SchoolDayRow {
+    pub student_id: String,
+    pub student_name: String,
+    /// The month's key in the school year read: YYYY-MM, or MM for undated rows.
+    pub month: String,
+    pub day: usize,
+    pub code: String,
+}
+
+/// Every school day of every recorded student-month for `params.classId` in one school year,
+/// limited to the optional month range. Non-school days (non-blank type-of-day code) are skipped; school days
+/// without a recorded code come back with an empty code so each exported month is complete.
+pub(crate) fn school_day_rows(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<Vec<SchoolDayRow>, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let (school_year, months) = selected_months(conn, &class_id, params)?;
+    let students = list_students_for_class(conn, &class_id)?;
+    let type_of_day_by_month = type_of_day_codes_by_month(conn, &class_id)?;
+    let day_codes_by_student = student_day_codes_by_month(conn, &class_id)?;
+
+    let mut out: Vec<SchoolDayRow> = Vec::new();
+    for s in &students {
+        for &month_num in &months {
+            let Some(raw) = day_codes_by_student
+                .get(&s.id)
+                .and_then(|m| school_year.row(m, month_num))
+            else {
+                continue;
+            };
+            let days = school_year.days_in(month_num);
+            let type_codes: Vec<char> = normalize_day_codes(
+                school_year
+                    .row(&type_of_day_by_month, month_num)
+                    .map(|v| v.as_str())
+                    .unwrap_or(""),
+                days,
+            )
+            .chars()
+            .collect();
+            for (i, code) in normalize_day_codes(raw, days).chars().enumerate() {
+                if type_codes[i] != ' ' {
+                    continue;
+                }
+                out.push(SchoolDayRow {
+                    student_id: s.id.clone(),
+                    student_name: s.display_name.clone(),
+                    month: school_year.month_key(month_num),
+                    day: i + 1,
+                    code: if code == ' ' {
+                        String::new()
+                    } else {
+                        code.to_string()
+                    },
+                });
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Writes one day code for a student, creating the class's `attendance_months` row (all
+/// school days) when the month has none yet. An existing key for the same year and month is
+/// reused, whatever its spelling. `day` must already be in range for `month` of `year`.
+pub(crate) fn import_student_day(
+    conn: &Connection,
+    class_id: &str,
+    student_id: &str,
+    year: Option<i32>,
+    month: u32,
+    day: usize,
+    code: Option<char>,
+) -> Result<(), HandlerErr> {
+    let days = days_in_month(year.unwrap_or(UNDATED_YEAR), month);
+    let month_key = {
+        let mut stmt = conn
+            .prepare("SELECT CAST(month AS TEXT) FROM attendance_months WHERE class_id = ?")
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        let keys = stmt
+            .query_map([class_id], |r| r.get::<_, String>(0))
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        keys.into_iter()
+            .find(|k| parse_stored_month(k) == Some((year, month)))
+            .unwrap_or_else(|| match year {
+                Some(y) => format!("{:04}-{:02}", y, month),
+                None => month.to_string(),
+            })
+    };
+    conn.execute(
+        "INSERT INTO attendance_months(class_id, month, type_of_day_codes)
+         VALUES(?, ?, ?)
+         ON CONFLICT(class_id, month) DO NOTHING",
+        (class_id, &month_key, " ".repeat(days)),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "attendance_months" })),
+    })?;
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT day_codes FROM attendance_student_months WHERE class_id = ? AND student_id = ? AND month = ?",
+            (class_id, student_id, &month_key),
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let patched = patch_day_code(existing.as_deref().unwrap_or(""), days, day, code);
+    conn.execute(
+        "INSERT INTO attendance_student_months(class_id, student_id, month, day_codes)
+         VALUES(?, ?, ?, ?)
+         ON CONFLICT(class_id, student_id, month) DO UPDATE SET
+           day_codes = excluded.day_codes",
+        (class_id, student_id, &month_key, &patched),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "attendance_student_months" })),
+    })?;
+    Ok(())
+}
+
+/// Present/absent/late/excused counts per student over the optional month range of one
+/// school year.
+pub(crate) fn attendance_summary(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let (school_year, months) = selected_months(conn, &class_id, params)?;
+
+    let codes = attendance_codes(conn)?;
+    let students = list_students_for_class(conn, &class_id)?;
+
+    let type_of_day_by_month = type_of_day_codes_by_month(conn, &class_id)?;
+
+    let mut tallies: HashMap<String, AttendanceTally> = HashMap::new();
+    for (student_id, by_month) in student_day_codes_by_month(conn, &class_id)? {
+        for &month_num in &months {
+            let Some(day_codes) = school_year.row(&by_month, month_num) else {
+                continue;
+            };
+            let days = school_year.days_in(month_num);
+            let type_codes = normalize_day_codes(
+                school_year
+                    .row(&type_of_day_by_month, month_num)
+                    .map(|v| v.as_str())
+                    .unwrap_or(""),
+                days,
+            );
+            tally_day_codes(
+                &type_codes,
+                &normalize_day_codes(day_codes, days),
+                &codes,
+                tallies.entry(student_id.clone()).or_default(),
+            );
+        }
+    }
+
+    let summaries: Vec<serde_json::Value> = students
+        .iter()
+        .map(|s| {
+            let t = tallies.get(&s.id).copied().unwrap_or_default();
+            json!({
+                "studentId": s.id,
+                "present": t.present,
+                "absent": t.absent,
+                "late": t.late,
+                "excused": t.excused
+            })
+        })
+        .collect();
+
+    Ok(json!({ "summaries": summaries }))
+}
+
 fn attendance_set_type_of_day(
     conn: &Connection,
     params: &serde_json::Value,
@@ -398,6 +1118,119 @@ fn attendance_set_student_day(
     Ok(json!({ "ok": true }))
 }
 
+/// Stamps one code on every school day from `dayFrom` to `dayTo` (inclusive) for a
+/// single student; days with a non-blank type-of-day code are left untouched.
+fn attendance_set_student_range(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let month_key = get_required_str(params, "month")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let mut range = [0usize; 2];
+    for (slot, key) in range.iter_mut().zip(["dayFrom", "dayTo"]) {
+        *slot = params
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| HandlerErr {
+                code: "bad_params",
+                message: format!("missing {}", key),
+                details: None,
+            })? as usize;
+    }
+    let [day_from, day_to] = range;
+    let code = parse_optional_code_char(params.get("code"))?;
+    let (year, month_num) = parse_month_key(&month_key)?;
+    let days = days_in_month(year, month_num);
+    if day_from == 0 || day_from > day_to || day_to > days {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "day range out of range for month".to_string(),
+            details: Some(json!({ "dayFrom": day_from, "dayTo": day_to, "days": days })),
+        });
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let student_exists = tx
+        .query_row(
+            "SELECT 1 FROM students WHERE class_id = ? AND id = ?",
+            (&class_id, &student_id),
+            |r| r.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?
+        .is_some();
+    if !student_exists {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "student not found".to_string(),
+            details: None,
+        });
+    }
+    let type_of_day: Option<String> = tx
+        .query_row(
+            "SELECT type_of_day_codes FROM attendance_months WHERE class_id = ? AND month = ?",
+            (&class_id, &month_key),
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let type_codes: Vec<char> = normalize_day_codes(type_of_day.as_deref().unwrap_or(""), days)
+        .chars()
+        .collect();
+    let existing: Option<String> = tx
+        .query_row(
+            "SELECT day_codes FROM attendance_student_months WHERE class_id = ? AND student_id = ? AND month = ?",
+            (&class_id, &student_id, &month_key),
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let mut patched = normalize_day_codes(existing.as_deref().unwrap_or(""), days);
+    let mut updated = 0;
+    for day in day_from..=day_to {
+        if type_codes[day - 1] != ' ' {
+            continue;
+        }
+        patched = patch_day_code(&patched, days, day, code);
+        updated += 1;
+    }
+    tx.execute(
+        "INSERT INTO attendance_student_months(class_id, student_id, month, day_codes)
+         VALUES(?, ?, ?, ?)
+         ON CONFLICT(class_id, student_id, month) DO UPDATE SET
+           day_codes = excluded.day_codes",
+        (&class_id, &student_id, &month_key, &patched),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "attendance_student_months" })),
+    })?;
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    Ok(json!({ "updated": updated }))
+}
+
 fn attendance_bulk_stamp_day(
     conn: &Connection,
     params: &serde_json::Value,
@@ -500,6 +1333,26 @@ fn handle_attendance_month_open(state: &mut AppState, req: &Request) -> serde_js
     }
 }
 
+fn handle_attendance_year_open(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match attendance_year_open(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_attendance_summary(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match attendance_summary(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_attendance_set_type_of_day(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -520,6 +1373,16 @@ fn handle_attendance_set_student_day(state: &mut AppState, req: &Request) -> ser
     }
 }
 
+fn handle_attendance_set_student_range(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match attendance_set_student_range(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_attendance_bulk_stamp_day(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -530,12 +1393,122 @@ fn handle_attendance_bulk_stamp_day(state: &mut AppState, req: &Request) -> serd
     }
 }
 
+fn handle_attendance_codes_list(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match attendance_codes_list(conn) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_attendance_codes_upsert(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match attendance_codes_upsert(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+pub const METHODS: &[&str] = &[
+    "attendance.monthOpen",
+    "attendance.yearOpen",
+    "attendance.summary",
+    "attendance.setTypeOfDay",
+    "attendance.setStudentDay",
+    "attendance.setStudentRange",
+    "attendance.bulkStampDay",
+    "attendance.codes.list",
+    "attendance.codes.upsert",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "attendance.monthOpen" => Some(handle_attendance_month_open(state, req)),
+        "attendance.yearOpen" => Some(handle_attendance_year_open(state, req)),
+        "attendance.summary" => Some(handle_attendance_summary(state, req)),
         "attendance.setTypeOfDay" => Some(handle_attendance_set_type_of_day(state, req)),
         "attendance.setStudentDay" => Some(handle_attendance_set_student_day(state, req)),
+        "attendance.setStudentRange" => Some(handle_attendance_set_student_range(state, req)),
         "attendance.bulkStampDay" => Some(handle_attendance_bulk_stamp_day(state, req)),
+        "attendance.codes.list" => Some(handle_attendance_codes_list(state, req)),
+        "attendance.codes.upsert" => Some(handle_attendance_codes_upsert(state, req)),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legend() -> Vec<AttendanceCode> {
+        [
+            ('P', false, false, false),
+            ('A', true, false, false),
+            ('L', false, true, false),
+            ('E', true, false, true),
+        ]
+        .into_iter()
+        .map(|(code, absent, late, excused)| AttendanceCode {
+            code,
+            label: code.to_string(),
+            counts_as_absent: absent,
+            counts_as_late: late,
+            is_excused: excused,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn tally_skips_non_school_days() {
+        let codes = legend();
+        let mut tally = AttendanceTally::default();
+        // Day 3 is a holiday; the P stamped on it must not count as present.
+        tally_day_codes("  H   ", "PAPLEp", &codes, &mut tally);
+        assert_eq!(
+            tally,
+            AttendanceTally {
+                present: 2,
+                absent: 1,
+                late: 1,
+                excused: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn tally_ignores_blank_and_unknown_codes() {
+        let mut codes = legend();
+        codes[0].code = '/';
+        let mut tally = AttendanceTally::default();
+        tally_day_codes("", "/ X/A", &codes, &mut tally);
+        assert_eq!(tally.present, 2);
+        assert_eq!(tally.absent, 1);
+    }
+
+    #[test]
+    fn tally_uses_legend_flags_for_local_codes() {
+        let mut codes = legend();
+        codes.push(AttendanceCode {
+            code: 'S',
+            label: "Suspension".to_string(),
+            counts_as_absent: true,
+            counts_as_late: false,
+            is_excused: false,
+        });
+        codes.push(AttendanceCode {
+            code: 'F',
+            label: "Field trip".to_string(),
+            counts_as_absent: false,
+            counts_as_late: false,
+            is_excused: false,
+        });
+        let mut tally = AttendanceTally::default();
+        tally_day_codes("", "SsFP", &codes, &mut tally);
+        assert_eq!(tally.absent, 2);
+        assert_eq!(tally.present, 2);
+    }
+}
@@ -1,10 +1,18 @@
+use super::attendance;
+use super::grid;
 use crate::backup;
+use crate::class_bundle;
+use crate::class_json;
 use crate::db;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
+use crate::xlsx;
 use rusqlite::{Connection, OptionalExtension};
 use serde_json::json;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 struct HandlerErr {
@@ -19,43 +27,63 @@ impl HandlerErr {
     }
 }
 
+/// RFC 4180 field quoting: wrap when the field holds a delimiter, quote, or
+/// line break, doubling any embedded quotes.
 fn csv_quote(s: &str) -> String {
-    if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
+    if s.contains([',', '"', '\r', '\n']) {
         format!("\"{}\"", s.replace('"', "\"\""))
     } else {
         s.to_string()
     }
 }
 
-fn parse_csv_record(line: &str) -> Vec<String> {
-    let mut out: Vec<String> = Vec::new();
+/// Tokenizes a whole CSV document. Quoted fields may span line breaks, so
+/// records are split on unquoted CR/LF only. Each record carries the 1-based
+/// line number it starts on.
+fn parse_csv_records(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut fields: Vec<String> = Vec::new();
     let mut buf = String::new();
     let mut in_quotes = false;
-    let chars: Vec<char> = line.chars().collect();
-    let mut i = 0usize;
-    while i < chars.len() {
-        let ch = chars[i];
+    let mut line_no = 1usize;
+    let mut record_line = 1usize;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
         if ch == '"' {
-            if in_quotes && i + 1 < chars.len() && chars[i + 1] == '"' {
+            if in_quotes && chars.peek() == Some(&'"') {
                 buf.push('"');
-                i += 2;
+                chars.next();
                 continue;
             }
             in_quotes = !in_quotes;
-            i += 1;
             continue;
         }
-        if ch == ',' && !in_quotes {
-            out.push(buf);
-            buf = String::new();
-            i += 1;
+        if in_quotes {
+            if ch == '\n' {
+                line_no += 1;
+            }
+            buf.push(ch);
             continue;
         }
-        buf.push(ch);
-        i += 1;
+        match ch {
+            ',' => fields.push(std::mem::take(&mut buf)),
+            '\r' | '\n' => {
+                if ch == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                fields.push(std::mem::take(&mut buf));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line_no += 1;
+                record_line = line_no;
+            }
+            _ => buf.push(ch),
+        }
     }
-    out.push(buf);
-    out
+    if !buf.is_empty() || !fields.is_empty() {
+        fields.push(buf);
+        records.push((record_line, fields));
+    }
+    records
 }
 
 #[derive(Clone, Debug)]
@@ -68,50 +96,174 @@ struct ParsedExchangeRow {
     raw_value: Option<f64>,
 }
 
-fn parse_exchange_rows(text: &str) -> (Vec<ParsedExchangeRow>, Vec<serde_json::Value>, usize) {
+/// Column positions of the fields a marks CSV row is read from.
+#[derive(Clone, Copy, Debug)]
+struct ExchangeColumns {
+    student_id: usize,
+    mark_set_code: usize,
+    assessment_idx: usize,
+    status: usize,
+    raw_value: usize,
+}
+
+impl ExchangeColumns {
+    /// The layout `exchange.exportClassCsv` writes.
+    const POSITIONAL: ExchangeColumns = ExchangeColumns {
+        student_id: 0,
+        mark_set_code: 2,
+        assessment_idx: 3,
+        status: 5,
+        raw_value: 6,
+    };
+
+    fn min_len(&self) -> usize {
+        [
+            self.student_id,
+            self.mark_set_code,
+            self.assessment_idx,
+            self.status,
+            self.raw_value,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+            + 1
+    }
+}
+
+const EXCHANGE_COLUMN_FIELDS: &[&str] = &[
+    "studentId",
+    "markSetCode",
+    "assessmentIdx",
+    "status",
+    "rawValue",
+];
+
+/// Resolves the optional `columnMap` param (`{ "<header name>": "<field>" }`)
+/// against the CSV header row. Header names match case-insensitively; without
+/// a map the positional export layout is used.
+fn exchange_columns(req: &Request, text: &str) -> Result<ExchangeColumns, serde_json::Value> {
+    let column_map = match req.params.get("columnMap") {
+        None | Some(serde_json::Value::Null) => return Ok(ExchangeColumns::POSITIONAL),
+        Some(serde_json::Value::Object(m)) => m,
+        Some(_) => {
+            return Err(err(
+                &req.id,
+                "bad_params",
+                "columnMap must be an object",
+                None,
+            ))
+        }
+    };
+    let normalize = |h: &str| h.trim().trim_start_matches('\u{feff}').to_ascii_lowercase();
+    let header: Vec<String> = parse_csv_records(text)
+        .into_iter()
+        .next()
+        .map(|(_, fields)| fields.iter().map(|f| normalize(f)).collect())
+        .unwrap_or_default();
+
+    let mut positions: [Option<usize>; 5] = [None; 5];
+    for (column, field) in column_map {
+        let Some(slot) = field
+            .as_str()
+            .and_then(|f| EXCHANGE_COLUMN_FIELDS.iter().position(|k| *k == f))
+        else {
+            return Err(err(
+                &req.id,
+                "bad_params",
+                "columnMap values must be one of: studentId, markSetCode, assessmentIdx, status, rawValue",
+                Some(json!({ "column": column, "field": field })),
+            ));
+        };
+        if positions[slot].is_some() {
+            return Err(err(
+                &req.id,
+                "bad_params",
+                "columnMap maps more than one column to the same field",
+                Some(json!({ "field": field })),
+            ));
+        }
+        let Some(pos) = header.iter().position(|h| *h == normalize(column)) else {
+            return Err(err(
+                &req.id,
+                "bad_params",
+                "mapped column is missing from the CSV header",
+                Some(json!({ "column": column, "field": field })),
+            ));
+        };
+        positions[slot] = Some(pos);
+    }
+    let missing: Vec<&str> = EXCHANGE_COLUMN_FIELDS
+        .iter()
+        .zip(positions.iter())
+        .filter(|(_, p)| p.is_none())
+        .map(|(f, _)| *f)
+        .collect();
+    let [Some(student_id), Some(mark_set_code), Some(assessment_idx), Some(status), Some(raw_value)] =
+        positions
+    else {
+        return Err(err(
+            &req.id,
+            "bad_params",
+            "columnMap must map a column to every field",
+            Some(json!({ "missingFields": missing })),
+        ));
+    };
+    Ok(ExchangeColumns {
+        student_id,
+        mark_set_code,
+        assessment_idx,
+        status,
+        raw_value,
+    })
+}
+
+fn parse_exchange_rows(
+    text: &str,
+    columns: &ExchangeColumns,
+) -> (Vec<ParsedExchangeRow>, Vec<serde_json::Value>, usize) {
     let mut rows = Vec::new();
     let mut warnings = Vec::new();
     let mut total = 0usize;
-    for (line_no, raw_line) in text.lines().enumerate() {
-        if line_no == 0 {
+    let min_len = columns.min_len();
+    for (idx, (line_no, fields)) in parse_csv_records(text).into_iter().enumerate() {
+        if idx == 0 {
             continue;
         }
-        let line = raw_line.trim();
-        if line.is_empty() {
+        if fields.iter().all(|f| f.trim().is_empty()) {
             continue;
         }
         total += 1;
-        let fields = parse_csv_record(line);
-        if fields.len() < 7 {
+        if fields.len() < min_len {
             warnings.push(json!({
-                "line": line_no + 1,
+                "line": line_no,
                 "code": "bad_columns",
-                "message": "expected at least 7 CSV columns"
+                "message": format!("expected at least {} CSV columns", min_len)
             }));
             continue;
         }
-        let student_id = fields[0].trim().to_string();
-        let mark_set_code = fields[2].trim().to_string();
-        let assessment_idx = match fields[3].trim().parse::<i64>() {
+        let student_id = fields[columns.student_id].trim().to_string();
+        let mark_set_code = fields[columns.mark_set_code].trim().to_string();
+        let assessment_idx = match fields[columns.assessment_idx].trim().parse::<i64>() {
             Ok(v) => v,
             Err(_) => {
                 warnings.push(json!({
-                    "line": line_no + 1,
+                    "line": line_no,
                     "code": "bad_assessment_idx",
                     "message": "assessment_idx must be an integer"
                 }));
                 continue;
             }
         };
-        let status = fields[5].trim().to_ascii_lowercase();
-        let raw_value = if fields[6].trim().is_empty() {
+        let status = fields[columns.status].trim().to_ascii_lowercase();
+        let raw_value = if fields[columns.raw_value].trim().is_empty() {
             None
         } else {
-            match fields[6].trim().parse::<f64>() {
+            match fields[columns.raw_value].trim().parse::<f64>() {
                 Ok(v) => Some(v),
                 Err(_) => {
                     warnings.push(json!({
-                        "line": line_no + 1,
+                        "line": line_no,
                         "code": "bad_raw_value",
                         "message": "raw_value must be numeric when provided"
                     }));
@@ -120,7 +272,7 @@ fn parse_exchange_rows(text: &str) -> (Vec<ParsedExchangeRow>, Vec<serde_json::V
             }
         };
         rows.push(ParsedExchangeRow {
-            line_no: line_no + 1,
+            line_no,
             student_id,
             mark_set_code,
             assessment_idx,
@@ -188,6 +340,11 @@ fn upsert_score(
     raw_value: Option<f64>,
     status: &str,
 ) -> Result<(), HandlerErr> {
+    let old = grid::read_score_cell(conn, assessment_id, student_id).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
     let score_id = Uuid::new_v4().to_string();
     conn.execute(
         "INSERT INTO scores(id, assessment_id, student_id, raw_value, status)
@@ -202,7 +359,18 @@ fn upsert_score(
         message: e.to_string(),
         details: Some(json!({ "table": "scores" })),
     })?;
-    Ok(())
+    grid::record_score_history(
+        conn,
+        assessment_id,
+        student_id,
+        &old,
+        &Some((raw_value, status.to_string())),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "score_history" })),
+    })
 }
 
 fn handle_backup_export_workspace_bundle(state: &mut AppState, req: &Request) -> serde_json::Value {
@@ -220,100 +388,953 @@ fn handle_backup_export_workspace_bundle(state: &mut AppState, req: &Request) ->
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
 
-    if let Some(conn) = state.db.as_ref() {
-        let _ = conn.execute_batch("PRAGMA wal_checkpoint(FULL)");
+    if let Some(conn) = state.db.as_ref() {
+        let _ = conn.execute_batch("PRAGMA wal_checkpoint(FULL)");
+    }
+
+    let out = PathBuf::from(&out_path);
+    let export = match backup::export_workspace_bundle(&workspace_path, &out) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": out_path })),
+            )
+        }
+    };
+
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "path": out_path,
+            "bundleFormat": export.bundle_format,
+            "entryCount": export.entry_count
+        }),
+    )
+}
+
+fn handle_backup_import_workspace_bundle(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let in_path = match req.params.get("inPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing inPath", None),
+    };
+    let workspace_path = req
+        .params
+        .get("workspacePath")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .or_else(|| state.workspace.clone());
+    let Some(workspace_path) = workspace_path else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let src = PathBuf::from(&in_path);
+    if !src.is_file() {
+        return err(
+            &req.id,
+            "not_found",
+            "bundle file not found",
+            Some(json!({ "path": in_path })),
+        );
+    }
+    if let Err(e) = std::fs::create_dir_all(&workspace_path) {
+        return err(
+            &req.id,
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": workspace_path.to_string_lossy() })),
+        );
+    }
+
+    // Drop open handle before replacing file.
+    state.db = None;
+
+    let import = match backup::import_workspace_bundle(&src, &workspace_path) {
+        Ok(v) => v,
+        Err(e) => {
+            if let Some(corrupt) = e.downcast_ref::<backup::BundleCorrupt>() {
+                return err(
+                    &req.id,
+                    "bundle_corrupt",
+                    e.to_string(),
+                    Some(json!({
+                        "path": src.to_string_lossy(),
+                        "entry": corrupt.entry,
+                        "reason": corrupt.message
+                    })),
+                );
+            }
+            if let Some(in_use) = e.downcast_ref::<backup::DbInUse>() {
+                // The old database was never removed, so the session can keep using it.
+                if state.workspace.as_deref() == Some(workspace_path.as_path()) {
+                    state.db = db::open_db(&workspace_path).ok();
+                }
+                return err(
+                    &req.id,
+                    "db_in_use",
+                    "the workspace database is open in another program; close other MarkBook \
+                     windows or tools using this workspace and try the restore again",
+                    Some(json!({
+                        "path": in_use.path,
+                        "attempts": in_use.attempts,
+                        "reason": in_use.message
+                    })),
+                );
+            }
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": src.to_string_lossy() })),
+            );
+        }
+    };
+
+    match db::open_db(&workspace_path) {
+        Ok(conn) => {
+            state.workspace = Some(workspace_path.clone());
+            state.db = Some(conn);
+            ok(
+                &req.id,
+                json!({
+                    "ok": true,
+                    "workspacePath": workspace_path.to_string_lossy(),
+                    "bundleFormatDetected": import.bundle_format_detected,
+                    "manifestPresent": import.manifest_present
+                }),
+            )
+        }
+        Err(e) => err(&req.id, "db_open_failed", e.to_string(), None),
+    }
+}
+
+fn handle_backup_verify_bundle(req: &Request) -> serde_json::Value {
+    let in_path = match req.params.get("inPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing inPath", None),
+    };
+    let src = PathBuf::from(&in_path);
+    if !src.is_file() {
+        return err(
+            &req.id,
+            "not_found",
+            "bundle file not found",
+            Some(json!({ "path": in_path })),
+        );
+    }
+
+    let verify = match backup::verify_workspace_bundle(&src) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": in_path })),
+            )
+        }
+    };
+    let problems: Vec<serde_json::Value> = verify
+        .problems
+        .iter()
+        .map(|p| json!({ "entry": p.entry, "message": p.message }))
+        .collect();
+    ok(
+        &req.id,
+        json!({
+            "ok": problems.is_empty(),
+            "bundleFormat": verify.bundle_format,
+            "entryCount": verify.entry_count,
+            "problems": problems
+        }),
+    )
+}
+
+fn class_bundle_counts(summary: &class_bundle::ClassBundleSummary) -> serde_json::Value {
+    let counts: serde_json::Map<String, serde_json::Value> = summary
+        .counts
+        .iter()
+        .map(|(label, n)| (label.to_string(), json!(n)))
+        .collect();
+    serde_json::Value::Object(counts)
+}
+
+fn handle_backup_export_class_bundle(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let out_path = match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    };
+
+    let exists: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if exists.is_none() {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    let out = PathBuf::from(&out_path);
+    let export = match class_bundle::export_class_bundle(conn, &class_id, &out) {
+        Ok(v) => v,
+        Err(e) if e.downcast_ref::<rusqlite::Error>().is_some() => {
+            return err(&req.id, "db_query_failed", format!("{:#}", e), None)
+        }
+        Err(e) => {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": out_path })),
+            )
+        }
+    };
+
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "path": out_path,
+            "bundleFormat": class_bundle::CLASS_BUNDLE_FORMAT_V1,
+            "classId": export.class_id,
+            "className": export.class_name,
+            "counts": class_bundle_counts(&export)
+        }),
+    )
+}
+
+fn handle_backup_import_class_bundle(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let in_path = match req.params.get("inPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing inPath", None),
+    };
+    let src = PathBuf::from(&in_path);
+    if !src.is_file() {
+        return err(
+            &req.id,
+            "not_found",
+            "bundle file not found",
+            Some(json!({ "path": in_path })),
+        );
+    }
+
+    let import = match class_bundle::import_class_bundle(conn, &src) {
+        Ok(v) => v,
+        Err(e) => {
+            if let Some(corrupt) = e.downcast_ref::<backup::BundleCorrupt>() {
+                return err(
+                    &req.id,
+                    "bundle_corrupt",
+                    e.to_string(),
+                    Some(json!({
+                        "path": in_path,
+                        "entry": corrupt.entry,
+                        "reason": corrupt.message
+                    })),
+                );
+            }
+            if e.downcast_ref::<rusqlite::Error>().is_some() {
+                return err(&req.id, "db_insert_failed", format!("{:#}", e), None);
+            }
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": in_path })),
+            );
+        }
+    };
+
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "classId": import.class_id,
+            "className": import.class_name,
+            "counts": class_bundle_counts(&import)
+        }),
+    )
+}
+
+fn handle_exchange_export_class_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let out_path = match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    };
+
+    let layout = match req.params.get("layout") {
+        None => "long",
+        Some(v) => match v.as_str() {
+            Some(layout @ ("long" | "wide")) => layout,
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "layout must be \"long\" or \"wide\"",
+                    None,
+                )
+            }
+        },
+    };
+
+    let out = PathBuf::from(&out_path);
+    let written = if layout == "wide" {
+        write_class_csv_wide(conn, &class_id, &out)
+    } else {
+        write_class_csv(conn, &class_id, &out)
+    };
+    match written {
+        Ok(rows_exported) => ok(
+            &req.id,
+            json!({
+                "ok": true,
+                "rowsExported": rows_exported,
+                "path": out_path,
+                "layout": layout
+            }),
+        ),
+        Err(e) => e.response(&req.id),
+    }
+}
+
+/// Streams one CSV row per score straight from the query into `out`, so large
+/// classes never hold the whole file in memory. Returns the number of rows.
+fn write_class_csv(conn: &Connection, class_id: &str, out: &Path) -> Result<usize, HandlerErr> {
+    let io_failed = |e: std::io::Error| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": out.to_string_lossy() })),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.last_name, s.first_name, ms.code, a.idx, a.title, sc.status, sc.raw_value
+             FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             JOIN students s ON s.id = sc.student_id
+             WHERE s.class_id = ?
+             ORDER BY s.sort_order, ms.sort_order, a.idx",
+        )
+        .map_err(query_failed)?;
+    let mut rows = stmt.query([class_id]).map_err(query_failed)?;
+
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent).map_err(io_failed)?;
+    }
+    let mut csv = BufWriter::new(File::create(out).map_err(io_failed)?);
+    csv.write_all(
+        b"student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\n",
+    )
+    .map_err(io_failed)?;
+
+    let mut rows_exported = 0;
+    while let Some(r) = rows.next().map_err(query_failed)? {
+        let student_id: String = r.get(0).map_err(query_failed)?;
+        let last: String = r.get(1).map_err(query_failed)?;
+        let first: String = r.get(2).map_err(query_failed)?;
+        let mark_set_code: String = r.get(3).map_err(query_failed)?;
+        let assessment_idx: i64 = r.get(4).map_err(query_failed)?;
+        let title: String = r.get(5).map_err(query_failed)?;
+        let status: String = r.get(6).map_err(query_failed)?;
+        let raw_value: Option<f64> = r.get(7).map_err(query_failed)?;
+        let display_name = format!("{}, {}", last, first);
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            csv_quote(&student_id),
+            csv_quote(&display_name),
+            csv_quote(&mark_set_code),
+            assessment_idx,
+            csv_quote(&title),
+            csv_quote(&status),
+            raw_value.map(|v| v.to_string()).unwrap_or_default()
+        )
+        .map_err(io_failed)?;
+        rows_exported += 1;
+    }
+    csv.flush().map_err(io_failed)?;
+    Ok(rows_exported)
+}
+
+/// One row per student and one `MARKSETCODE/idx` column per assessment, in
+/// mark set then assessment order. `zero` cells write 0; `no_mark` and
+/// unentered cells stay blank. Returns the number of student rows.
+fn write_class_csv_wide(
+    conn: &Connection,
+    class_id: &str,
+    out: &Path,
+) -> Result<usize, HandlerErr> {
+    let io_failed = |e: std::io::Error| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": out.to_string_lossy() })),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, ms.code, a.idx
+             FROM assessments a
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             WHERE ms.class_id = ?
+             ORDER BY ms.sort_order, a.idx",
+        )
+        .map_err(query_failed)?;
+    let assessments: Vec<(String, String)> = stmt
+        .query_map([class_id], |r| {
+            let code: String = r.get(1)?;
+            let idx: i64 = r.get(2)?;
+            Ok((r.get(0)?, format!("{}/{}", code, idx)))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(query_failed)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, last_name, first_name
+             FROM students
+             WHERE class_id = ?
+             ORDER BY sort_order",
+        )
+        .map_err(query_failed)?;
+    let students: Vec<(String, String)> = stmt
+        .query_map([class_id], |r| {
+            let last: String = r.get(1)?;
+            let first: String = r.get(2)?;
+            Ok((r.get(0)?, format!("{}, {}", last, first)))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(query_failed)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT sc.student_id, sc.assessment_id, sc.status, sc.raw_value
+             FROM scores sc
+             JOIN students s ON s.id = sc.student_id
+             WHERE s.class_id = ?",
+        )
+        .map_err(query_failed)?;
+    let cells: HashMap<(String, String), String> = stmt
+        .query_map([class_id], |r| {
+            let status: String = r.get(2)?;
+            let raw_value: Option<f64> = r.get(3)?;
+            let cell = match status.as_str() {
+                "zero" => "0".to_string(),
+                "no_mark" => String::new(),
+                _ => raw_value.map(|v| v.to_string()).unwrap_or_default(),
+            };
+            Ok(((r.get(0)?, r.get(1)?), cell))
+        })
+        .and_then(|it| it.collect::<Result<HashMap<_, _>, _>>())
+        .map_err(query_failed)?;
+
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent).map_err(io_failed)?;
+    }
+    let mut csv = BufWriter::new(File::create(out).map_err(io_failed)?);
+    let mut header = String::from("student_id,student_name");
+    for (_, column) in &assessments {
+        header.push(',');
+        header.push_str(&csv_quote(column));
+    }
+    writeln!(csv, "{}", header).map_err(io_failed)?;
+    for (student_id, display_name) in &students {
+        let mut line = format!("{},{}", csv_quote(student_id), csv_quote(display_name));
+        for (assessment_id, _) in &assessments {
+            line.push(',');
+            if let Some(cell) = cells.get(&(student_id.clone(), assessment_id.clone())) {
+                line.push_str(cell);
+            }
+        }
+        writeln!(csv, "{}", line).map_err(io_failed)?;
+    }
+    csv.flush().map_err(io_failed)?;
+    Ok(students.len())
+}
+
+fn handle_exchange_export_attendance_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let out_path = match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    };
+
+    let rows = match attendance::school_day_rows(conn, &req.params) {
+        Ok(v) => v,
+        Err(e) => return e.response(&req.id),
+    };
+
+    let mut csv = String::from("student_id,student_name,month,day,code\n");
+    let rows_exported = rows.len();
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_quote(&row.student_id),
+            csv_quote(&row.student_name),
+            row.month,
+            row.day,
+            csv_quote(&row.code)
+        ));
+    }
+
+    let out = PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": out_path })),
+            );
+        }
+    }
+    if let Err(e) = std::fs::write(&out, csv) {
+        return err(
+            &req.id,
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": out_path })),
+        );
+    }
+
+    ok(
+        &req.id,
+        json!({ "ok": true, "rowsExported": rows_exported, "path": out_path }),
+    )
+}
+
+fn handle_exchange_export_class_json(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let out_path = match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    };
+
+    let exists: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if exists.is_none() {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    let doc = match class_json::export_class_json(conn, &class_id) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", format!("{:#}", e), None),
+    };
+    let text = match serde_json::to_string_pretty(&doc) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "io_failed", e.to_string(), None),
+    };
+
+    let out = PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": out_path })),
+            );
+        }
+    }
+    if let Err(e) = std::fs::write(&out, &text) {
+        return err(
+            &req.id,
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": out_path })),
+        );
+    }
+
+    ok(
+        &req.id,
+        json!({ "ok": true, "path": out_path, "bytes": text.len() }),
+    )
+}
+
+fn handle_exchange_import_class_json(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let in_path = match req.params.get("inPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing inPath", None),
+    };
+    let merge_into = match req.params.get("mergeIntoClassId") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => match v.as_str() {
+            Some(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "mergeIntoClassId must be a non-empty string",
+                    None,
+                )
+            }
+        },
+    };
+    if let Some(class_id) = merge_into.as_deref() {
+        let exists: Option<i64> = match conn
+            .query_row("SELECT 1 FROM classes WHERE id = ?", [class_id], |r| {
+                r.get(0)
+            })
+            .optional()
+        {
+            Ok(v) => v,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        if exists.is_none() {
+            return err(&req.id, "not_found", "class not found", None);
+        }
+    }
+
+    let text = match std::fs::read_to_string(&in_path) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": in_path })),
+            )
+        }
+    };
+    let doc: serde_json::Value = match serde_json::from_str(&text) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "invalid_class_json",
+                e.to_string(),
+                Some(json!({ "path": in_path })),
+            )
+        }
+    };
+
+    let import = match class_json::import_class_json(conn, &doc, merge_into.as_deref()) {
+        Ok(v) => v,
+        Err(e) => {
+            if let Some(invalid) = e.downcast_ref::<class_json::ClassJsonInvalid>() {
+                return err(
+                    &req.id,
+                    invalid.code,
+                    invalid.message.clone(),
+                    Some(json!({ "path": in_path, "schemaVersion": doc.get("schemaVersion") })),
+                );
+            }
+            return err(&req.id, "db_insert_failed", format!("{:#}", e), None);
+        }
+    };
+
+    let counts: serde_json::Map<String, serde_json::Value> = import
+        .counts
+        .iter()
+        .map(|(label, n)| (label.to_string(), json!(n)))
+        .collect();
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "classId": import.class_id,
+            "className": import.class_name,
+            "merged": merge_into.is_some(),
+            "counts": counts,
+            "warningsCount": import.warnings.len(),
+            "warnings": import.warnings,
+            "path": in_path
+        }),
+    )
+}
+
+/// One row per student in roster order with a column per skill found in
+/// `learning_skills_cells`. Without a `term` filter columns are `T<term>_<skill>`
+/// so every term fits in one sheet; students without a cell get blanks.
+fn learning_skills_csv(
+    conn: &Connection,
+    class_id: &str,
+    term: Option<i64>,
+) -> Result<(String, usize), HandlerErr> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT student_id, term, UPPER(skill_code), value
+             FROM learning_skills_cells
+             WHERE class_id = ?1 AND (?2 IS NULL OR term = ?2)
+             ORDER BY term, UPPER(skill_code)",
+        )
+        .map_err(query_failed)?;
+    let cells = stmt
+        .query_map((class_id, term), |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, i64>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(query_failed)?;
+
+    let mut columns: Vec<(i64, String)> = Vec::new();
+    let mut values: HashMap<(String, i64, String), String> = HashMap::new();
+    for (student_id, cell_term, skill_code, value) in cells {
+        let column = (cell_term, skill_code);
+        if !columns.contains(&column) {
+            columns.push(column.clone());
+        }
+        values.insert((student_id, column.0, column.1), value);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, last_name, first_name
+             FROM students
+             WHERE class_id = ?
+             ORDER BY sort_order",
+        )
+        .map_err(query_failed)?;
+    let students = stmt
+        .query_map([class_id], |r| {
+            let last: String = r.get(1)?;
+            let first: String = r.get(2)?;
+            Ok((r.get::<_, String>(0)?, format!("{}, {}", last, first)))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(query_failed)?;
+
+    let mut csv = String::from("student_id,student_name");
+    for (column_term, skill_code) in &columns {
+        let header = match term {
+            Some(_) => skill_code.clone(),
+            None => format!("T{}_{}", column_term, skill_code),
+        };
+        csv.push(',');
+        csv.push_str(&csv_quote(&header));
+    }
+    csv.push('\n');
+    for (student_id, display_name) in &students {
+        csv.push_str(&csv_quote(student_id));
+        csv.push(',');
+        csv.push_str(&csv_quote(display_name));
+        for (column_term, skill_code) in &columns {
+            csv.push(',');
+            if let Some(v) = values.get(&(student_id.clone(), *column_term, skill_code.clone())) {
+                csv.push_str(&csv_quote(v));
+            }
+        }
+        csv.push('\n');
+    }
+    Ok((csv, students.len()))
+}
+
+fn handle_exchange_export_learning_skills_csv(
+    state: &mut AppState,
+    req: &Request,
+) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let Some(class_id) = req.params.get("classId").and_then(|v| v.as_str()) else {
+        return err(&req.id, "bad_params", "missing classId", None);
+    };
+    let out_path = match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    };
+    let term = match req.params.get("term") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_i64() {
+            Some(t) if (1..=3).contains(&t) => Some(t),
+            _ => return err(&req.id, "bad_params", "term must be 1, 2 or 3", None),
+        },
+    };
+
+    match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [class_id], |r| {
+            r.get::<_, i64>(0)
+        })
+        .optional()
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return err(&req.id, "not_found", "class not found", None),
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
     }
 
-    let out = PathBuf::from(&out_path);
-    let export = match backup::export_workspace_bundle(&workspace_path, &out) {
+    let (csv, rows_exported) = match learning_skills_csv(conn, class_id, term) {
         Ok(v) => v,
-        Err(e) => {
+        Err(e) => return e.response(&req.id),
+    };
+
+    let out = PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
             return err(
                 &req.id,
                 "io_failed",
                 e.to_string(),
                 Some(json!({ "path": out_path })),
-            )
+            );
         }
-    };
+    }
+    if let Err(e) = std::fs::write(&out, csv) {
+        return err(
+            &req.id,
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": out_path })),
+        );
+    }
 
     ok(
         &req.id,
-        json!({
-            "ok": true,
-            "path": out_path,
-            "bundleFormat": export.bundle_format,
-            "entryCount": export.entry_count
-        }),
+        json!({ "ok": true, "rowsExported": rows_exported, "path": out_path }),
     )
 }
 
-fn handle_backup_import_workspace_bundle(state: &mut AppState, req: &Request) -> serde_json::Value {
-    let in_path = match req.params.get("inPath").and_then(|v| v.as_str()) {
-        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
-        _ => return err(&req.id, "bad_params", "missing inPath", None),
-    };
-    let workspace_path = req
-        .params
-        .get("workspacePath")
-        .and_then(|v| v.as_str())
-        .map(PathBuf::from)
-        .or_else(|| state.workspace.clone());
-    let Some(workspace_path) = workspace_path else {
-        return err(&req.id, "no_workspace", "select a workspace first", None);
-    };
-
-    let src = PathBuf::from(&in_path);
-    if !src.is_file() {
-        return err(
-            &req.id,
-            "not_found",
-            "bundle file not found",
-            Some(json!({ "path": in_path })),
-        );
+fn query_failed(e: rusqlite::Error) -> HandlerErr {
+    HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
     }
-    if let Err(e) = std::fs::create_dir_all(&workspace_path) {
-        return err(
-            &req.id,
-            "io_failed",
-            e.to_string(),
-            Some(json!({ "path": workspace_path.to_string_lossy() })),
-        );
+}
+
+/// One sheet per live mark set: students as rows, assessments as columns.
+/// Scored cells hold the raw mark, zero cells hold 0, no_mark cells stay blank.
+fn class_xlsx_sheets(
+    conn: &Connection,
+    class_id: &str,
+) -> Result<(Vec<xlsx::Sheet>, usize), HandlerErr> {
+    let class_exists = conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [class_id], |r| {
+            r.get::<_, i64>(0)
+        })
+        .optional()
+        .map_err(query_failed)?
+        .is_some();
+    if !class_exists {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
     }
 
-    // Drop open handle before replacing file.
-    state.db = None;
+    let students: Vec<(String, String)> = conn
+        .prepare(
+            "SELECT id, last_name, first_name FROM students WHERE class_id = ? ORDER BY sort_order",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    format!("{}, {}", r.get::<_, String>(1)?, r.get::<_, String>(2)?),
+                ))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        })
+        .map_err(query_failed)?;
+    let mark_sets: Vec<(String, String)> = conn
+        .prepare(
+            "SELECT id, code FROM mark_sets
+             WHERE class_id = ? AND deleted_at IS NULL
+             ORDER BY sort_order",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        })
+        .map_err(query_failed)?;
 
-    let import = match backup::import_workspace_bundle(&src, &workspace_path) {
-        Ok(v) => v,
-        Err(e) => {
-            return err(
-                &req.id,
-                "io_failed",
-                e.to_string(),
-                Some(json!({ "path": src.to_string_lossy() })),
+    let mut sheets = Vec::with_capacity(mark_sets.len());
+    for (mark_set_id, code) in mark_sets {
+        let assessments: Vec<(String, String)> = conn
+            .prepare("SELECT id, title FROM assessments WHERE mark_set_id = ? ORDER BY idx")
+            .and_then(|mut stmt| {
+                stmt.query_map([&mark_set_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                    .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            })
+            .map_err(query_failed)?;
+        let scores: HashMap<(String, String), (Option<f64>, String)> = conn
+            .prepare(
+                "SELECT sc.assessment_id, sc.student_id, sc.raw_value, sc.status
+                 FROM scores sc
+                 JOIN assessments a ON a.id = sc.assessment_id
+                 WHERE a.mark_set_id = ?",
             )
-        }
-    };
+            .and_then(|mut stmt| {
+                stmt.query_map([&mark_set_id], |r| {
+                    Ok(((r.get(0)?, r.get(1)?), (r.get(2)?, r.get(3)?)))
+                })
+                .and_then(|it| it.collect::<Result<HashMap<_, _>, _>>())
+            })
+            .map_err(query_failed)?;
 
-    match db::open_db(&workspace_path) {
-        Ok(conn) => {
-            state.workspace = Some(workspace_path.clone());
-            state.db = Some(conn);
-            ok(
-                &req.id,
-                json!({
-                    "ok": true,
-                    "workspacePath": workspace_path.to_string_lossy(),
-                    "bundleFormatDetected": import.bundle_format_detected
-                }),
-            )
+        let mut rows = Vec::with_capacity(students.len() + 1);
+        let mut header = vec![xlsx::Cell::Text("Student".to_string())];
+        header.extend(
+            assessments
+                .iter()
+                .map(|(_, title)| xlsx::Cell::Text(title.clone())),
+        );
+        rows.push(header);
+        for (student_id, display_name) in &students {
+            let mut row = vec![xlsx::Cell::Text(display_name.clone())];
+            for (assessment_id, _) in &assessments {
+                let cell = match scores.get(&(assessment_id.clone(), student_id.clone())) {
+                    Some((_, status)) if status == "zero" => xlsx::Cell::Number(0.0),
+                    Some((Some(v), status)) if status == "scored" => xlsx::Cell::Number(*v),
+                    _ => xlsx::Cell::Empty,
+                };
+                row.push(cell);
+            }
+            rows.push(row);
         }
-        Err(e) => err(&req.id, "db_open_failed", e.to_string(), None),
+        sheets.push(xlsx::Sheet { name: code, rows });
     }
+    Ok((sheets, students.len()))
 }
 
-fn handle_exchange_export_class_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
+fn handle_exchange_export_class_xlsx(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
@@ -326,56 +1347,11 @@ fn handle_exchange_export_class_csv(state: &mut AppState, req: &Request) -> serd
         _ => return err(&req.id, "bad_params", "missing outPath", None),
     };
 
-    let mut stmt = match conn.prepare(
-        "SELECT s.id, s.last_name, s.first_name, ms.code, a.idx, a.title, sc.status, sc.raw_value
-         FROM scores sc
-         JOIN assessments a ON a.id = sc.assessment_id
-         JOIN mark_sets ms ON ms.id = a.mark_set_id
-         JOIN students s ON s.id = sc.student_id
-         WHERE s.class_id = ?
-         ORDER BY s.sort_order, ms.sort_order, a.idx",
-    ) {
-        Ok(s) => s,
-        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
-    };
-
-    let rows = match stmt
-        .query_map([&class_id], |r| {
-            Ok((
-                r.get::<_, String>(0)?,
-                r.get::<_, String>(1)?,
-                r.get::<_, String>(2)?,
-                r.get::<_, String>(3)?,
-                r.get::<_, i64>(4)?,
-                r.get::<_, String>(5)?,
-                r.get::<_, String>(6)?,
-                r.get::<_, Option<f64>>(7)?,
-            ))
-        })
-        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
-    {
+    let (sheets, students_exported) = match class_xlsx_sheets(conn, &class_id) {
         Ok(v) => v,
-        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        Err(e) => return e.response(&req.id),
     };
 
-    let mut csv = String::from(
-        "student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\n",
-    );
-    let rows_exported = rows.len();
-    for (student_id, last, first, mark_set_code, assessment_idx, title, status, raw_value) in rows {
-        let display_name = format!("{}, {}", last, first);
-        csv.push_str(&format!(
-            "{},{},{},{},{},{},{}\n",
-            csv_quote(&student_id),
-            csv_quote(&display_name),
-            csv_quote(&mark_set_code),
-            assessment_idx,
-            csv_quote(&title),
-            csv_quote(&status),
-            raw_value.map(|v| v.to_string()).unwrap_or_default()
-        ));
-    }
-
     let out = PathBuf::from(&out_path);
     if let Some(parent) = out.parent() {
         if let Err(e) = std::fs::create_dir_all(parent) {
@@ -387,7 +1363,7 @@ fn handle_exchange_export_class_csv(state: &mut AppState, req: &Request) -> serd
             );
         }
     }
-    if let Err(e) = std::fs::write(&out, csv) {
+    if let Err(e) = xlsx::write_workbook(&out, &sheets) {
         return err(
             &req.id,
             "io_failed",
@@ -398,7 +1374,12 @@ fn handle_exchange_export_class_csv(state: &mut AppState, req: &Request) -> serd
 
     ok(
         &req.id,
-        json!({ "ok": true, "rowsExported": rows_exported, "path": out_path }),
+        json!({
+            "ok": true,
+            "sheetCount": sheets.len(),
+            "studentsExported": students_exported,
+            "path": out_path
+        }),
     )
 }
 
@@ -445,7 +1426,11 @@ fn handle_exchange_preview_class_csv(state: &mut AppState, req: &Request) -> ser
         Err(e) => return e,
     };
 
-    let (parsed_rows, mut warnings, rows_total) = parse_exchange_rows(&text);
+    let columns = match exchange_columns(req, &text) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (parsed_rows, mut warnings, rows_total) = parse_exchange_rows(&text, &columns);
     let mut matched = 0usize;
     let mut unmatched = 0usize;
     let mut preview_rows = Vec::new();
@@ -530,6 +1515,12 @@ fn handle_exchange_preview_class_csv(state: &mut AppState, req: &Request) -> ser
 }
 
 fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
+    apply_class_csv(state, req, false)
+}
+
+/// Runs the full match/validate/upsert pass. With `dry_run` the transaction is
+/// always rolled back and the response reports what would have changed.
+fn apply_class_csv(state: &mut AppState, req: &Request, dry_run: bool) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
@@ -538,21 +1529,25 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
         Err(e) => return e,
     };
 
-    let (parsed_rows, mut warnings, rows_total) = parse_exchange_rows(&text);
+    let columns = match exchange_columns(req, &text) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (parsed_rows, mut warnings, rows_total) = parse_exchange_rows(&text, &columns);
     let tx = match conn.unchecked_transaction() {
         Ok(t) => t,
         Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
     };
     if mode == "replace" {
-        if let Err(e) = tx.execute(
-            "DELETE FROM scores
-             WHERE assessment_id IN (
+        if let Err(e) = grid::delete_scores_with_history(
+            &tx,
+            "assessment_id IN (
                SELECT a.id
                FROM assessments a
                JOIN mark_sets ms ON ms.id = a.mark_set_id
                WHERE ms.class_id = ?
              )",
-            [&class_id],
+            &[&class_id],
         ) {
             let _ = tx.rollback();
             return err(
@@ -566,6 +1561,9 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
 
     let mut updated = 0usize;
     let mut skipped = 0usize;
+    let mut unmatched_students: Vec<String> = Vec::new();
+    let mut unmatched_assessments: Vec<serde_json::Value> = Vec::new();
+    let mut sample_rows: Vec<serde_json::Value> = Vec::new();
     for row in &parsed_rows {
         let student_id = row.student_id.as_str();
         let mark_set_code = row.mark_set_code.as_str();
@@ -590,6 +1588,10 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
                 "code": "missing_student",
                 "message": "student_id does not belong to target class"
             }));
+            if !unmatched_students.iter().any(|s| s == student_id) {
+                unmatched_students.push(student_id.to_string());
+            }
+            push_sample_row(&mut sample_rows, row, "skip", Some("missing_student"));
             continue;
         }
         let assessment_id: Option<String> = tx
@@ -611,12 +1613,18 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
                 "code": "missing_assessment",
                 "message": "assessment not found in target class/mark set"
             }));
+            let key = json!({ "markSetCode": mark_set_code, "assessmentIdx": assessment_idx });
+            if !unmatched_assessments.contains(&key) {
+                unmatched_assessments.push(key);
+            }
+            push_sample_row(&mut sample_rows, row, "skip", Some("missing_assessment"));
             continue;
         };
         let (resolved_raw, resolved_state) = match resolve_score_state(Some(&status), raw_value) {
             Ok(v) => v,
             Err(e) => {
                 skipped += 1;
+                push_sample_row(&mut sample_rows, row, "skip", Some(e.code));
                 warnings.push(json!({
                     "line": row.line_no,
                     "code": e.code,
@@ -636,6 +1644,29 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
             return e.response(&req.id);
         }
         updated += 1;
+        push_sample_row(&mut sample_rows, row, "update", None);
+    }
+
+    if dry_run {
+        let _ = tx.rollback();
+        return ok(
+            &req.id,
+            json!({
+                "ok": true,
+                "dryRun": true,
+                "wouldUpdate": updated,
+                "wouldSkip": skipped,
+                "unmatchedStudents": unmatched_students,
+                "unmatchedAssessments": unmatched_assessments,
+                "sampleRows": sample_rows,
+                "rowsTotal": rows_total,
+                "rowsParsed": parsed_rows.len(),
+                "warningsCount": warnings.len(),
+                "warnings": warnings,
+                "mode": mode,
+                "path": in_path
+            }),
+        );
     }
 
     if let Err(e) = tx.commit() {
@@ -658,18 +1689,281 @@ fn handle_exchange_apply_class_csv(state: &mut AppState, req: &Request) -> serde
     )
 }
 
+fn push_sample_row(
+    sample_rows: &mut Vec<serde_json::Value>,
+    row: &ParsedExchangeRow,
+    action: &str,
+    reason: Option<&str>,
+) {
+    if sample_rows.len() < 250 {
+        sample_rows.push(json!({
+            "line": row.line_no,
+            "studentId": row.student_id,
+            "markSetCode": row.mark_set_code,
+            "assessmentIdx": row.assessment_idx,
+            "action": action,
+            "reason": reason
+        }));
+    }
+}
+
 fn handle_exchange_import_class_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
-    handle_exchange_apply_class_csv(state, req)
+    let dry_run = req
+        .params
+        .get("dryRun")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    apply_class_csv(state, req, dry_run)
+}
+
+/// Reads `student_id,month,day,code` rows (extra columns such as the export's
+/// `student_name` are ignored, columns are found by header) into
+/// `attendance_student_months`. `replace` clears the class's student attendance
+/// first; a blank code clears that day.
+fn handle_exchange_import_attendance_csv(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let (class_id, in_path, mode, text) = match read_exchange_input(req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if mode != "upsert" && mode != "replace" {
+        return err(
+            &req.id,
+            "bad_params",
+            "mode must be upsert or replace",
+            Some(json!({ "mode": mode })),
+        );
+    }
+    match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get::<_, i64>(0)
+        })
+        .optional()
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return err(&req.id, "not_found", "class not found", None),
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    }
+
+    let mut records = parse_csv_records(&text).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .map(|(_, fields)| {
+            fields
+                .iter()
+                .map(|f| f.trim().trim_start_matches('\u{feff}').to_ascii_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(student_col), Some(month_col), Some(day_col), Some(code_col)) = (
+        column("student_id"),
+        column("month"),
+        column("day"),
+        column("code"),
+    ) else {
+        return err(
+            &req.id,
+            "bad_params",
+            "CSV header must include student_id, month, day and code",
+            Some(json!({ "path": in_path })),
+        );
+    };
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    if mode == "replace" {
+        if let Err(e) = tx.execute(
+            "DELETE FROM attendance_student_months WHERE class_id = ?",
+            [&class_id],
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_delete_failed",
+                e.to_string(),
+                Some(json!({ "table": "attendance_student_months" })),
+            );
+        }
+    }
+
+    // Bare MM months land in the same school year attendance reads default to.
+    let school_year = match attendance::default_school_year(&tx, &class_id) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return e.response(&req.id);
+        }
+    };
+
+    let mut updated = 0usize;
+    let mut skipped = 0usize;
+    let mut rows_total = 0usize;
+    let mut warnings: Vec<serde_json::Value> = Vec::new();
+    for (line_no, fields) in records {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        rows_total += 1;
+        let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or("");
+        let student_id = field(student_col);
+        let month = attendance::parse_stored_month(field(month_col))
+            .map(|(y, m)| (y.or_else(|| school_year.year_of(m)), m));
+        let checked = match (month, field(day_col).parse::<usize>().ok()) {
+            (None, _) => Err(("bad_month", "month must be MM or YYYY-MM")),
+            (Some(_), None) => Err(("bad_day", "day must be a whole number")),
+            (Some((y, m)), Some(d))
+                if d == 0
+                    || d > attendance::days_in_month(y.unwrap_or(attendance::UNDATED_YEAR), m) =>
+            {
+                Err(("bad_day", "day out of range for month"))
+            }
+            (Some((y, m)), Some(d)) => {
+                let student_ok = tx
+                    .query_row(
+                        "SELECT 1 FROM students WHERE id = ? AND class_id = ?",
+                        (student_id, &class_id),
+                        |r| r.get::<_, i64>(0),
+                    )
+                    .optional()
+                    .ok()
+                    .flatten()
+                    .is_some();
+                if student_ok {
+                    Ok((y, m, d))
+                } else {
+                    Err((
+                        "missing_student",
+                        "student_id does not belong to target class",
+                    ))
+                }
+            }
+        };
+        let (year, month, day) = match checked {
+            Ok(v) => v,
+            Err((code, message)) => {
+                skipped += 1;
+                warnings.push(json!({ "line": line_no, "code": code, "message": message }));
+                continue;
+            }
+        };
+        if let Err(e) = attendance::import_student_day(
+            &tx,
+            &class_id,
+            student_id,
+            year,
+            month,
+            day,
+            field(code_col).chars().next(),
+        ) {
+            let _ = tx.rollback();
+            return e.response(&req.id);
+        }
+        updated += 1;
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "updated": updated,
+            "rowsTotal": rows_total,
+            "skipped": skipped,
+            "warningsCount": warnings.len(),
+            "warnings": warnings,
+            "mode": mode,
+            "path": in_path
+        }),
+    )
 }
 
+pub const METHODS: &[&str] = &[
+    "backup.exportWorkspaceBundle",
+    "backup.importWorkspaceBundle",
+    "backup.verifyBundle",
+    "backup.exportClassBundle",
+    "backup.importClassBundle",
+    "exchange.exportClassCsv",
+    "exchange.exportAttendanceCsv",
+    "exchange.exportLearningSkillsCsv",
+    "exchange.exportClassXlsx",
+    "exchange.previewClassCsv",
+    "exchange.applyClassCsv",
+    "exchange.importClassCsv",
+    "exchange.importAttendanceCsv",
+    "exchange.exportClassJson",
+    "exchange.importClassJson",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "backup.exportWorkspaceBundle" => Some(handle_backup_export_workspace_bundle(state, req)),
         "backup.importWorkspaceBundle" => Some(handle_backup_import_workspace_bundle(state, req)),
+        "backup.verifyBundle" => Some(handle_backup_verify_bundle(req)),
+        "backup.exportClassBundle" => Some(handle_backup_export_class_bundle(state, req)),
+        "backup.importClassBundle" => Some(handle_backup_import_class_bundle(state, req)),
         "exchange.exportClassCsv" => Some(handle_exchange_export_class_csv(state, req)),
+        "exchange.exportAttendanceCsv" => Some(handle_exchange_export_attendance_csv(state, req)),
+        "exchange.exportLearningSkillsCsv" => {
+            Some(handle_exchange_export_learning_skills_csv(state, req))
+        }
+        "exchange.exportClassXlsx" => Some(handle_exchange_export_class_xlsx(state, req)),
         "exchange.previewClassCsv" => Some(handle_exchange_preview_class_csv(state, req)),
         "exchange.applyClassCsv" => Some(handle_exchange_apply_class_csv(state, req)),
         "exchange.importClassCsv" => Some(handle_exchange_import_class_csv(state, req)),
+        "exchange.importAttendanceCsv" => Some(handle_exchange_import_attendance_csv(state, req)),
+        "exchange.exportClassJson" => Some(handle_exchange_export_class_json(state, req)),
+        "exchange.importClassJson" => Some(handle_exchange_import_class_json(state, req)),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_quote, parse_csv_records};
+
+    #[test]
+    fn csv_quote_round_trips_quotes_commas_and_newlines() {
+        let name = "O\"Brien, Jr.\n";
+        let quoted = csv_quote(name);
+        assert_eq!(quoted, "\"O\"\"Brien, Jr.\n\"");
+
+        let record = format!("{},{},{}", csv_quote("s1"), quoted, csv_quote("plain"));
+        assert_eq!(
+            parse_csv_records(&record),
+            vec![(
+                1,
+                vec!["s1".to_string(), name.to_string(), "plain".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn csv_quote_only_wraps_fields_that_need_it() {
+        assert_eq!(csv_quote("Adams Pat"), "Adams Pat");
+        assert_eq!(csv_quote("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn parse_csv_records_keeps_quoted_line_breaks_inside_one_record() {
+        let text = "h1,h2\r\na,\"two\nlines\"\r\n\r\nb,c";
+        let records = parse_csv_records(text);
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["h1".to_string(), "h2".to_string()]),
+                (2, vec!["a".to_string(), "two\nlines".to_string()]),
+                (4, vec![String::new()]),
+                (5, vec!["b".to_string(), "c".to_string()]),
+            ]
+        );
+    }
+}
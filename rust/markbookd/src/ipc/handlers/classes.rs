@@ -2,14 +2,20 @@ use crate::db;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
 use rusqlite::types::Value;
-use rusqlite::OptionalExtension;
+use rusqlite::{Connection, OptionalExtension};
 use serde_json::json;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 fn handle_classes_list(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return ok(&req.id, json!({ "classes": [] }));
     };
+    let include_archived = req
+        .params
+        .get("includeArchived")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
     // Include basic counts so the UI can show a useful dashboard.
     // Use correlated subqueries to avoid double-counting from joins.
@@ -18,25 +24,29 @@ fn handle_classes_list(state: &mut AppState, req: &Request) -> serde_json::Value
            c.id,
            c.name,
            (SELECT COUNT(*) FROM students s WHERE s.class_id = c.id) AS student_count,
-           (SELECT COUNT(*) FROM mark_sets ms WHERE ms.class_id = c.id AND ms.deleted_at IS NULL) AS mark_set_count
+           (SELECT COUNT(*) FROM mark_sets ms WHERE ms.class_id = c.id AND ms.deleted_at IS NULL) AS mark_set_count,
+           c.archived
          FROM classes c
-         ORDER BY c.name",
+         WHERE ? OR c.archived = 0
+         ORDER BY c.sort_order, c.name",
     ) {
         Ok(s) => s,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
     };
 
     let rows = stmt
-        .query_map([], |row| {
+        .query_map([include_archived], |row| {
             let id: String = row.get(0)?;
             let name: String = row.get(1)?;
             let student_count: i64 = row.get(2)?;
             let mark_set_count: i64 = row.get(3)?;
+            let archived: i64 = row.get(4)?;
             Ok(json!({
                 "id": id,
                 "name": name,
                 "studentCount": student_count,
-                "markSetCount": mark_set_count
+                "markSetCount": mark_set_count,
+                "archived": archived != 0
             }))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>());
@@ -47,6 +57,147 @@ fn handle_classes_list(state: &mut AppState, req: &Request) -> serde_json::Value
     }
 }
 
+fn handle_classes_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let Some(arr) = req.params.get("orderedClassIds").and_then(|v| v.as_array()) else {
+        return err(
+            &req.id,
+            "bad_params",
+            "missing/invalid orderedClassIds",
+            None,
+        );
+    };
+    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
+    for v in arr {
+        let Some(s) = v.as_str() else {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedClassIds must be strings",
+                None,
+            );
+        };
+        ordered.push(s.to_string());
+    }
+
+    // Archived classes are hidden from the dashboard; they keep their relative
+    // order after everything in orderedClassIds.
+    let mut stmt = match conn.prepare("SELECT id, archived FROM classes ORDER BY sort_order, name")
+    {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let (archived_ids, current): (Vec<String>, HashSet<String>) = match stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? != 0))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => {
+            let (archived, current): (Vec<_>, Vec<_>) = v.into_iter().partition(|(_, a)| *a);
+            (
+                archived.into_iter().map(|(id, _)| id).collect(),
+                current.into_iter().map(|(id, _)| id).collect(),
+            )
+        }
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    if ordered.len() != current.len() {
+        return err(
+            &req.id,
+            "bad_params",
+            "orderedClassIds must be a permutation of the unarchived classes",
+            Some(json!({ "expected": current.len(), "got": ordered.len() })),
+        );
+    }
+    let mut seen: HashSet<&str> = HashSet::new();
+    for id in &ordered {
+        if !seen.insert(id) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedClassIds contains duplicates",
+                Some(json!({ "classId": id })),
+            );
+        }
+        if !current.contains(id) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedClassIds contains unknown classId",
+                Some(json!({ "classId": id })),
+            );
+        }
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    for (i, class_id) in ordered.iter().chain(archived_ids.iter()).enumerate() {
+        if let Err(e) = tx.execute(
+            "UPDATE classes SET sort_order = ? WHERE id = ?",
+            (i as i64, class_id),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "classes" })),
+            );
+        }
+    }
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
+fn set_class_archived(state: &mut AppState, req: &Request, archived: bool) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+
+    let changed = match conn.execute(
+        "UPDATE classes SET archived = ? WHERE id = ?",
+        (archived as i64, &class_id),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "classes" })),
+            )
+        }
+    };
+    if changed == 0 {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    ok(&req.id, json!({ "ok": true, "archived": archived }))
+}
+
+fn handle_classes_archive(state: &mut AppState, req: &Request) -> serde_json::Value {
+    set_class_archived(state, req, true)
+}
+
+fn handle_classes_unarchive(state: &mut AppState, req: &Request) -> serde_json::Value {
+    set_class_archived(state, req, false)
+}
+
 fn handle_classes_create(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -62,7 +213,8 @@ fn handle_classes_create(state: &mut AppState, req: &Request) -> serde_json::Val
 
     let class_id = Uuid::new_v4().to_string();
     if let Err(e) = conn.execute(
-        "INSERT INTO classes(id, name) VALUES(?, ?)",
+        "INSERT INTO classes(id, name, sort_order)
+         VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
         (&class_id, &name),
     ) {
         return err(
@@ -76,6 +228,274 @@ fn handle_classes_create(state: &mut AppState, req: &Request) -> serde_json::Val
     ok(&req.id, json!({ "classId": class_id, "name": name }))
 }
 
+fn handle_classes_rename(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let name = match req.params.get("name").and_then(|v| v.as_str()) {
+        Some(v) => v.trim().to_string(),
+        None => return err(&req.id, "bad_params", "missing name", None),
+    };
+    if name.is_empty() {
+        return err(&req.id, "bad_params", "name must not be empty", None);
+    }
+
+    let changed = match conn.execute(
+        "UPDATE classes SET name = ? WHERE id = ?",
+        (&name, &class_id),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "classes" })),
+            )
+        }
+    };
+    if changed == 0 {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    ok(&req.id, json!({ "classId": class_id, "name": name }))
+}
+
+#[derive(Default)]
+struct DuplicateCounts {
+    mark_sets: usize,
+    categories: usize,
+    assessments: usize,
+    comment_sets: usize,
+    attendance_settings: bool,
+}
+
+fn ids_where(conn: &Connection, sql: &str, key: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt
+        .query_map([key], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Copies the live mark set skeleton (mark sets, categories, assessments) from
+/// one class into another with fresh ids. Students, scores and remarks are
+/// never copied. Errors carry the table being written.
+fn copy_class_structure(
+    conn: &Connection,
+    src_class_id: &str,
+    dst_class_id: &str,
+    copy_comment_sets: bool,
+    copy_attendance_settings: bool,
+) -> Result<DuplicateCounts, (rusqlite::Error, &'static str)> {
+    let mut counts = DuplicateCounts::default();
+    let mark_set_ids = ids_where(
+        conn,
+        "SELECT id FROM mark_sets WHERE class_id = ? AND deleted_at IS NULL ORDER BY sort_order",
+        src_class_id,
+    )
+    .map_err(|e| (e, "mark_sets"))?;
+
+    for src_ms_id in mark_set_ids {
+        let dst_ms_id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO mark_sets(
+               id, class_id, code, file_prefix, description, weight, source_filename,
+               sort_order, full_code, room, day, period, weight_method, calc_method,
+               is_default, block_title
+             )
+             SELECT ?, ?, code, file_prefix, description, weight, source_filename,
+                    sort_order, full_code, room, day, period, weight_method, calc_method,
+                    is_default, block_title
+             FROM mark_sets WHERE id = ?",
+            (&dst_ms_id, dst_class_id, &src_ms_id),
+        )
+        .map_err(|e| (e, "mark_sets"))?;
+        counts.mark_sets += 1;
+
+        let category_ids = ids_where(
+            conn,
+            "SELECT id FROM categories WHERE mark_set_id = ? ORDER BY sort_order",
+            &src_ms_id,
+        )
+        .map_err(|e| (e, "categories"))?;
+        for src_id in category_ids {
+            conn.execute(
+                "INSERT INTO categories(id, mark_set_id, name, weight, sort_order, drop_lowest)
+                 SELECT ?, ?, name, weight, sort_order, drop_lowest
+                 FROM categories WHERE id = ?",
+                (Uuid::new_v4().to_string(), &dst_ms_id, &src_id),
+            )
+            .map_err(|e| (e, "categories"))?;
+            counts.categories += 1;
+        }
+
+        // Cached averages describe the source class's scores, so they are reset.
+        let assessment_ids = ids_where(
+            conn,
+            "SELECT id FROM assessments WHERE mark_set_id = ? ORDER BY idx",
+            &src_ms_id,
+        )
+        .map_err(|e| (e, "assessments"))?;
+        for src_id in assessment_ids {
+            conn.execute(
+                "INSERT INTO assessments(
+                   id, mark_set_id, idx, date, category_name, title, term,
+                   legacy_kind, legacy_type, weight, out_of, avg_percent, avg_raw
+                 )
+                 SELECT ?, ?, idx, date, category_name, title, term,
+                        legacy_kind, legacy_type, weight, out_of, NULL, NULL
+                 FROM assessments WHERE id = ?",
+                (Uuid::new_v4().to_string(), &dst_ms_id, &src_id),
+            )
+            .map_err(|e| (e, "assessments"))?;
+            counts.assessments += 1;
+        }
+
+        if copy_comment_sets {
+            let set_ids = ids_where(
+                conn,
+                "SELECT id FROM comment_set_indexes WHERE mark_set_id = ? ORDER BY set_number",
+                &src_ms_id,
+            )
+            .map_err(|e| (e, "comment_set_indexes"))?;
+            for src_id in set_ids {
+                conn.execute(
+                    "INSERT INTO comment_set_indexes(
+                       id, class_id, mark_set_id, set_number, title, fit_mode, fit_font_size,
+                       fit_width, fit_lines, fit_subj, max_chars, is_default, bank_short
+                     )
+                     SELECT ?, ?, ?, set_number, title, fit_mode, fit_font_size,
+                            fit_width, fit_lines, fit_subj, max_chars, is_default, bank_short
+                     FROM comment_set_indexes WHERE id = ?",
+                    (
+                        Uuid::new_v4().to_string(),
+                        dst_class_id,
+                        &dst_ms_id,
+                        &src_id,
+                    ),
+                )
+                .map_err(|e| (e, "comment_set_indexes"))?;
+                counts.comment_sets += 1;
+            }
+        }
+    }
+
+    if copy_attendance_settings {
+        let copied = conn
+            .execute(
+                "INSERT INTO attendance_settings(class_id, school_year_start_month)
+                 SELECT ?, school_year_start_month
+                 FROM attendance_settings WHERE class_id = ?",
+                (dst_class_id, src_class_id),
+            )
+            .map_err(|e| (e, "attendance_settings"))?;
+        counts.attendance_settings = copied > 0;
+    }
+
+    Ok(counts)
+}
+
+fn handle_classes_duplicate(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let src_class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let name = match req.params.get("name").and_then(|v| v.as_str()) {
+        Some(v) => v.trim().to_string(),
+        None => return err(&req.id, "bad_params", "missing name", None),
+    };
+    if name.is_empty() {
+        return err(&req.id, "bad_params", "name must not be empty", None);
+    }
+    let copy_options = req.params.get("copyOptions");
+    let copy_flag = |key: &str| {
+        copy_options
+            .and_then(|o| o.get(key))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+    let copy_comment_sets = copy_flag("commentSets");
+    let copy_attendance_settings = copy_flag("attendanceSettings");
+
+    let exists: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&src_class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if exists.is_none() {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    let class_id = Uuid::new_v4().to_string();
+    if let Err(e) = tx.execute(
+        "INSERT INTO classes(id, name, sort_order)
+         VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
+        (&class_id, &name),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_insert_failed",
+            e.to_string(),
+            Some(json!({ "table": "classes" })),
+        );
+    }
+    let counts = match copy_class_structure(
+        &tx,
+        &src_class_id,
+        &class_id,
+        copy_comment_sets,
+        copy_attendance_settings,
+    ) {
+        Ok(v) => v,
+        Err((e, table)) => {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_insert_failed",
+                e.to_string(),
+                Some(json!({ "table": table })),
+            );
+        }
+    };
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(
+        &req.id,
+        json!({
+            "classId": class_id,
+            "name": name,
+            "copied": {
+                "markSets": counts.mark_sets,
+                "categories": counts.categories,
+                "assessments": counts.assessments,
+                "commentSets": counts.comment_sets,
+                "attendanceSettings": counts.attendance_settings
+            }
+        }),
+    )
+}
+
 fn normalize_opt_string(v: Option<&serde_json::Value>) -> Result<Option<String>, &'static str> {
     let Some(v) = v else {
         return Ok(None);
@@ -184,11 +604,11 @@ fn handle_classes_create_from_wizard(state: &mut AppState, req: &Request) -> ser
         .get("calcMethodDefault")
         .and_then(|v| v.as_i64())
         .unwrap_or(0);
-    if !(0..=4).contains(&calc_method_default) {
+    if !(0..=5).contains(&calc_method_default) {
         return err(
             &req.id,
             "bad_params",
-            "calcMethodDefault must be 0..4",
+            "calcMethodDefault must be 0..5",
             None,
         );
     }
@@ -224,7 +644,8 @@ fn handle_classes_create_from_wizard(state: &mut AppState, req: &Request) -> ser
     };
 
     if let Err(e) = tx.execute(
-        "INSERT INTO classes(id, name) VALUES(?, ?)",
+        "INSERT INTO classes(id, name, sort_order)
+         VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
         (&class_id, &name),
     ) {
         let _ = tx.rollback();
@@ -557,12 +978,12 @@ fn handle_classes_meta_update(state: &mut AppState, req: &Request) -> serde_json
             set_parts.push("calc_method_default = ?".into());
             bind_values.push(Value::Null);
         } else if let Some(n) = v.as_i64() {
-            if !(0..=4).contains(&n) {
+            if !(0..=5).contains(&n) {
                 let _ = tx.rollback();
                 return err(
                     &req.id,
                     "bad_params",
-                    "patch.calcMethodDefault must be 0..4",
+                    "patch.calcMethodDefault must be 0..5",
                     None,
                 );
             }
@@ -853,6 +1274,25 @@ fn handle_classes_delete(state: &mut AppState, req: &Request) -> serde_json::Val
         );
     }
 
+    if let Err(e) = tx.execute(
+        "DELETE FROM score_history
+         WHERE assessment_id IN (
+           SELECT a.id
+           FROM assessments a
+           JOIN mark_sets ms ON ms.id = a.mark_set_id
+           WHERE ms.class_id = ?
+         )",
+        [&class_id],
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "score_history" })),
+        );
+    }
+
     if let Err(e) = tx.execute(
         "DELETE FROM comment_set_remarks
          WHERE comment_set_index_id IN (
@@ -1050,6 +1490,16 @@ fn handle_classes_delete(state: &mut AppState, req: &Request) -> serde_json::Val
         );
     }
 
+    if let Err(e) = tx.execute("DELETE FROM edit_log WHERE class_id = ?", [&class_id]) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "edit_log" })),
+        );
+    }
+
     if let Err(e) = tx.execute("DELETE FROM classes WHERE id = ?", [&class_id]) {
         let _ = tx.rollback();
         return err(
@@ -1067,10 +1517,32 @@ fn handle_classes_delete(state: &mut AppState, req: &Request) -> serde_json::Val
     ok(&req.id, json!({ "ok": true }))
 }
 
+pub const METHODS: &[&str] = &[
+    "classes.list",
+    "classes.create",
+    "classes.reorder",
+    "classes.archive",
+    "classes.unarchive",
+    "classes.rename",
+    "classes.duplicate",
+    "classes.wizardDefaults",
+    "classes.createFromWizard",
+    "classes.meta.get",
+    "classes.meta.update",
+    "classes.importLink.get",
+    "classes.importLink.set",
+    "classes.delete",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "classes.list" => Some(handle_classes_list(state, req)),
         "classes.create" => Some(handle_classes_create(state, req)),
+        "classes.reorder" => Some(handle_classes_reorder(state, req)),
+        "classes.archive" => Some(handle_classes_archive(state, req)),
+        "classes.unarchive" => Some(handle_classes_unarchive(state, req)),
+        "classes.rename" => Some(handle_classes_rename(state, req)),
+        "classes.duplicate" => Some(handle_classes_duplicate(state, req)),
         "classes.wizardDefaults" => Some(handle_classes_wizard_defaults(state, req)),
         "classes.createFromWizard" => Some(handle_classes_create_from_wizard(state, req)),
         "classes.meta.get" => Some(handle_classes_meta_get(state, req)),
@@ -1,3 +1,4 @@
+use crate::calc::{self, CommentStudent, Pronouns};
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
 use crate::legacy;
@@ -44,6 +45,7 @@ struct CommentSetFitMeta {
     max_chars: usize,
     fit_width: usize,
     fit_lines: usize,
+    fit_subj: String,
     bank_short: Option<String>,
 }
 
@@ -156,7 +158,7 @@ fn load_comment_set_fit_meta(
 ) -> Result<CommentSetFitMeta, HandlerErr> {
     let row: Option<CommentSetFitMeta> = conn
         .query_row(
-            "SELECT id, max_chars, fit_width, fit_lines, bank_short
+            "SELECT id, max_chars, fit_width, fit_lines, fit_subj, bank_short
              FROM comment_set_indexes
              WHERE class_id = ? AND mark_set_id = ? AND set_number = ?",
             (class_id, mark_set_id, set_number),
@@ -169,7 +171,8 @@ fn load_comment_set_fit_meta(
                     max_chars: max_chars.max(0) as usize,
                     fit_width: fit_width.max(0) as usize,
                     fit_lines: fit_lines.max(0) as usize,
-                    bank_short: r.get(4)?,
+                    fit_subj: r.get(4)?,
+                    bank_short: r.get(5)?,
                 })
             },
         )
@@ -283,6 +286,16 @@ fn resolve_effective_fit_constraints(
     Ok((meta.max_chars.max(1), fit_width, fit_lines))
 }
 
+/// Length of a remark as it will be written out: trimmed, with the set's
+/// subject (`fit_subj`) substituted for any `{subject}` field.
+fn fitted_remark_len(remark: &str, fit_subj: &str) -> usize {
+    let trimmed = remark.trim();
+    let placeholders = trimmed.matches("{subject}").count();
+    let placeholder_len = "{subject}".chars().count();
+    trimmed.chars().count() - placeholders * placeholder_len
+        + placeholders * fit_subj.trim().chars().count()
+}
+
 fn truncate_chars(s: &str, max_chars: usize) -> String {
     s.chars().take(max_chars).collect()
 }
@@ -398,7 +411,7 @@ fn comments_sets_list(
     }
     let mut stmt = conn
         .prepare(
-            "SELECT set_number, title, fit_mode, fit_font_size, fit_width, fit_lines, fit_subj, max_chars, is_default, bank_short
+            "SELECT set_number, title, fit_mode, fit_font_size, fit_width, fit_lines, fit_subj, max_chars, is_default, bank_short, id
              FROM comment_set_indexes
              WHERE class_id = ? AND mark_set_id = ?
              ORDER BY set_number",
@@ -420,7 +433,8 @@ fn comments_sets_list(
                 "fitSubj": r.get::<_, String>(6)?,
                 "maxChars": r.get::<_, i64>(7)?,
                 "isDefault": r.get::<_, i64>(8)? != 0,
-                "bankShort": r.get::<_, Option<String>>(9)?
+                "bankShort": r.get::<_, Option<String>>(9)?,
+                "id": r.get::<_, String>(10)?
             }))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
@@ -550,6 +564,132 @@ fn comments_sets_open(
     }))
 }
 
+fn comments_render(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let mark_set_id = get_required_str(params, "markSetId")?;
+    let set_number = params
+        .get("setNumber")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "missing setNumber".to_string(),
+            details: None,
+        })?;
+    let meta = load_comment_set_fit_meta(conn, &class_id, &mark_set_id, set_number)?;
+    let remark_by_student = load_remarks_for_set(conn, &meta.set_id)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, last_name, first_name, pronouns
+             FROM students
+             WHERE class_id = ? AND archived = 0
+             ORDER BY sort_order",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let students = stmt
+        .query_map([&class_id], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+
+    let mut remarks: Vec<serde_json::Value> = Vec::new();
+    let mut warnings: Vec<serde_json::Value> = Vec::new();
+    for (student_id, last_name, first_name, pronouns) in students {
+        let Some(remark) = remark_by_student
+            .get(&student_id)
+            .filter(|r| !r.trim().is_empty())
+        else {
+            continue;
+        };
+        let expanded = calc::expand_comment(
+            remark,
+            &CommentStudent {
+                first_name: &first_name,
+                last_name: &last_name,
+                pronouns: pronouns.as_deref().and_then(Pronouns::parse),
+            },
+        );
+        for placeholder in expanded.unknown_fields {
+            warnings.push(json!({
+                "code": "unknown_merge_field",
+                "studentId": student_id,
+                "placeholder": placeholder
+            }));
+        }
+        remarks.push(json!({
+            "studentId": student_id,
+            "displayName": format!("{}, {}", last_name, first_name),
+            "remark": remark,
+            "rendered": expanded.text
+        }));
+    }
+
+    Ok(json!({ "remarks": remarks, "warnings": warnings }))
+}
+
+fn comments_fit_check(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let mark_set_id = get_required_str(params, "markSetId")?;
+    let set_number = params
+        .get("setNumber")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "missing setNumber".to_string(),
+            details: None,
+        })?;
+    let meta = load_comment_set_fit_meta(conn, &class_id, &mark_set_id, set_number)?;
+    let remark_by_student = load_remarks_for_set(conn, &meta.set_id)?;
+    let max_chars = meta.max_chars.max(1);
+
+    let mut checked = 0usize;
+    let mut over_limit: Vec<serde_json::Value> = Vec::new();
+    for student in list_students_for_class(conn, &class_id)? {
+        let Some(remark) = remark_by_student.get(&student.id) else {
+            continue;
+        };
+        if remark.trim().is_empty() {
+            continue;
+        }
+        checked += 1;
+        let length = fitted_remark_len(remark, &meta.fit_subj);
+        if length > max_chars {
+            over_limit.push(json!({
+                "studentId": student.id,
+                "displayName": student.display_name,
+                "length": length,
+                "overBy": length - max_chars
+            }));
+        }
+    }
+
+    Ok(json!({
+        "maxChars": max_chars,
+        "checked": checked,
+        "overLimit": over_limit
+    }))
+}
+
 fn parse_remarks_by_student(
     raw: Option<&serde_json::Value>,
 ) -> Result<Vec<(String, String)>, HandlerErr> {
@@ -820,6 +960,121 @@ fn comments_sets_delete(
     Ok(json!({ "ok": true }))
 }
 
+/// Rewrites `set_number` as 1..n in the given order. Remarks hang off the set id,
+/// so they move with their set.
+fn comments_sets_reorder(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let mark_set_id = get_required_str(params, "markSetId")?;
+    let Some(arr) = params.get("orderedSetIds").and_then(|v| v.as_array()) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "missing/invalid orderedSetIds".to_string(),
+            details: None,
+        });
+    };
+    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
+    for v in arr {
+        let Some(s) = v.as_str() else {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "orderedSetIds must be strings".to_string(),
+                details: None,
+            });
+        };
+        ordered.push(s.to_string());
+    }
+    if !mark_set_exists(conn, &class_id, &mark_set_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "mark set not found".to_string(),
+            details: None,
+        });
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM comment_set_indexes
+             WHERE class_id = ? AND mark_set_id = ?
+             ORDER BY set_number",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let current_ids: Vec<String> = stmt
+        .query_map((&class_id, &mark_set_id), |r| r.get::<_, String>(0))
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    if ordered.len() != current_ids.len() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "orderedSetIds must be a permutation of the mark set comment sets".to_string(),
+            details: Some(json!({ "expected": current_ids.len(), "got": ordered.len() })),
+        });
+    }
+    let current_set: HashSet<String> = current_ids.into_iter().collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    for id in &ordered {
+        if !seen.insert(id.clone()) {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "orderedSetIds contains duplicates".to_string(),
+                details: Some(json!({ "setId": id })),
+            });
+        }
+        if !current_set.contains(id) {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "orderedSetIds contains unknown setId".to_string(),
+                details: Some(json!({ "setId": id })),
+            });
+        }
+    }
+
+    let update_failed = |e: rusqlite::Error| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "comment_set_indexes" })),
+    };
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    // Avoid UNIQUE(mark_set_id, set_number) collisions by first moving every
+    // set into a temporary range.
+    tx.execute(
+        "UPDATE comment_set_indexes SET set_number = set_number + 1000000 WHERE mark_set_id = ?",
+        [&mark_set_id],
+    )
+    .map_err(update_failed)?;
+    {
+        let mut up = tx
+            .prepare(
+                "UPDATE comment_set_indexes SET set_number = ? WHERE id = ? AND mark_set_id = ?",
+            )
+            .map_err(update_failed)?;
+        for (i, set_id) in ordered.iter().enumerate() {
+            up.execute((i as i64 + 1, set_id, &mark_set_id))
+                .map_err(update_failed)?;
+        }
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    Ok(json!({ "ok": true }))
+}
+
 fn comments_remarks_upsert_one(
     conn: &Connection,
     params: &serde_json::Value,
@@ -1567,6 +1822,90 @@ fn comments_banks_open(
     Ok(json!({ "bank": bank, "entries": entries }))
 }
 
+fn comments_banks_search(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let query = get_required_str(params, "query")?.trim().to_lowercase();
+    if query.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "query must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let bank_id = params
+        .get("bankId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let limit = match params.get("limit") {
+        None | Some(serde_json::Value::Null) => 50,
+        Some(v) => match v.as_u64() {
+            Some(n) if n > 0 => n as i64,
+            _ => {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: "limit must be a positive integer".to_string(),
+                    details: None,
+                })
+            }
+        },
+    };
+    if let Some(bank_id) = bank_id.as_deref() {
+        let exists = conn
+            .query_row("SELECT 1 FROM comment_banks WHERE id = ?", [bank_id], |r| {
+                r.get::<_, i64>(0)
+            })
+            .optional()
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        if exists.is_none() {
+            return Err(HandlerErr {
+                code: "not_found",
+                message: "bank not found".to_string(),
+                details: None,
+            });
+        }
+    }
+
+    // instr() keeps user input literal; LIKE would treat % and _ as wildcards.
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.bank_id, e.id, e.type_code, e.level_code, e.text
+             FROM comment_bank_entries e
+             JOIN comment_banks b ON b.id = e.bank_id
+             WHERE instr(lower(e.text), ?1) > 0
+               AND (?2 IS NULL OR e.bank_id = ?2)
+             ORDER BY lower(b.short_name), e.sort_order
+             LIMIT ?3",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let results = stmt
+        .query_map((&query, bank_id.as_deref(), limit), |r| {
+            Ok(json!({
+                "bankId": r.get::<_, String>(0)?,
+                "entryId": r.get::<_, String>(1)?,
+                "typeCode": r.get::<_, String>(2)?,
+                "levelCode": r.get::<_, String>(3)?,
+                "text": r.get::<_, String>(4)?,
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    Ok(json!({ "results": results }))
+}
+
 fn comments_banks_create(
     conn: &Connection,
     params: &serde_json::Value,
@@ -1593,6 +1932,132 @@ fn comments_banks_create(
     Ok(json!({ "bankId": bank_id }))
 }
 
+/// Copies a bank and its entries under a new short name. The copy keeps the
+/// fit profile but is never the default bank and has no source file.
+fn comments_banks_duplicate(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let src_bank_id = get_required_str(params, "bankId")?;
+    let short_name = get_required_str(params, "shortName")?.trim().to_string();
+    if short_name.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "shortName must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let fit_profile: Option<Option<String>> = conn
+        .query_row(
+            "SELECT fit_profile FROM comment_banks WHERE id = ?",
+            [&src_bank_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let Some(fit_profile) = fit_profile else {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "bank not found".to_string(),
+            details: None,
+        });
+    };
+    let taken: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM comment_banks WHERE short_name = ?",
+            [&short_name],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    if taken.is_some() {
+        return Err(HandlerErr {
+            code: "duplicate_short_name",
+            message: "a bank with this shortName already exists".to_string(),
+            details: Some(json!({ "shortName": short_name })),
+        });
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let bank_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO comment_banks(id, short_name, is_default, fit_profile, source_path)
+         VALUES(?, ?, 0, ?, NULL)",
+        (&bank_id, &short_name, fit_profile.as_deref()),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "comment_banks" })),
+    })?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "SELECT sort_order, type_code, level_code, text
+                 FROM comment_bank_entries
+                 WHERE bank_id = ?
+                 ORDER BY sort_order",
+            )
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        let entries = stmt
+            .query_map([&src_bank_id], |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, String>(3)?,
+                ))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        for (sort_order, type_code, level_code, text) in entries {
+            tx.execute(
+                "INSERT INTO comment_bank_entries(id, bank_id, sort_order, type_code, level_code, text)
+                 VALUES(?, ?, ?, ?, ?, ?)",
+                (
+                    Uuid::new_v4().to_string(),
+                    &bank_id,
+                    sort_order,
+                    &type_code,
+                    &level_code,
+                    &text,
+                ),
+            )
+            .map_err(|e| HandlerErr {
+                code: "db_insert_failed",
+                message: e.to_string(),
+                details: Some(json!({ "table": "comment_bank_entries" })),
+            })?;
+        }
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    Ok(json!({ "bankId": bank_id }))
+}
+
 fn comments_banks_update_meta(
     conn: &Connection,
     params: &serde_json::Value,
@@ -1876,6 +2341,103 @@ fn comments_banks_entry_delete(
     Ok(json!({ "ok": true }))
 }
 
+/// Deletes a bank and its entries. Comment sets naming the bank in `bank_short`
+/// are left alone (their remarks do not depend on it) but are listed under
+/// `details` so the caller can warn that they now point at a missing bank.
+fn comments_banks_delete(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let bank_id = get_required_str(params, "bankId")?;
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let short_name: Option<String> = tx
+        .query_row(
+            "SELECT short_name FROM comment_banks WHERE id = ?",
+            [&bank_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let Some(short_name) = short_name else {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "bank not found".to_string(),
+            details: None,
+        });
+    };
+
+    let mut stmt = tx
+        .prepare(
+            "SELECT csi.class_id, csi.mark_set_id, ms.code, csi.set_number, csi.title
+             FROM comment_set_indexes csi
+             JOIN mark_sets ms ON ms.id = csi.mark_set_id
+             WHERE UPPER(TRIM(csi.bank_short)) = UPPER(?)
+             ORDER BY csi.class_id, ms.sort_order, csi.set_number",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let referenced_by = stmt
+        .query_map([short_name.trim()], |r| {
+            Ok(json!({
+                "classId": r.get::<_, String>(0)?,
+                "markSetId": r.get::<_, String>(1)?,
+                "markSetCode": r.get::<_, String>(2)?,
+                "setNumber": r.get::<_, i64>(3)?,
+                "title": r.get::<_, String>(4)?,
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    drop(stmt);
+
+    let entries_deleted = tx
+        .execute(
+            "DELETE FROM comment_bank_entries WHERE bank_id = ?",
+            [&bank_id],
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "comment_bank_entries" })),
+        })?;
+    tx.execute("DELETE FROM comment_banks WHERE id = ?", [&bank_id])
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "comment_banks" })),
+        })?;
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    let mut result = json!({ "ok": true, "entriesDeleted": entries_deleted });
+    if !referenced_by.is_empty() {
+        result["details"] = json!({
+            "warning": "bank_still_referenced",
+            "shortName": short_name,
+            "commentSets": referenced_by,
+        });
+    }
+    Ok(result)
+}
+
 fn comments_banks_import_bnk(
     conn: &Connection,
     params: &serde_json::Value,
@@ -1891,10 +2453,12 @@ fn comments_banks_import_bnk(
             details: None,
         })?
         .to_string();
-    let parsed = legacy::parse_bnk_file(&file_path).map_err(|e| HandlerErr {
-        code: "legacy_parse_failed",
-        message: e.to_string(),
-        details: Some(json!({ "path": path })),
+    let parsed = legacy::parse_bnk_file(&file_path, legacy::LegacyEncoding::Auto).map_err(|e| {
+        HandlerErr {
+            code: "legacy_parse_failed",
+            message: e.to_string(),
+            details: Some(json!({ "path": path })),
+        }
     })?;
 
     let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
@@ -1964,6 +2528,45 @@ fn comments_banks_import_bnk(
     Ok(json!({ "bankId": bank_id }))
 }
 
+/// Serializes one bank's entries in the legacy `.BNK` format.
+fn bank_bnk_text(
+    conn: &Connection,
+    bank_id: &str,
+    fit_profile: Option<String>,
+) -> Result<String, HandlerErr> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT sort_order, type_code, level_code, text
+             FROM comment_bank_entries
+             WHERE bank_id = ?
+             ORDER BY sort_order",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let entries = stmt
+        .query_map([bank_id], |r| {
+            Ok(legacy::ParsedBnkEntry {
+                sort_order: r.get::<_, i64>(0)? as usize,
+                type_code: r.get(1)?,
+                level_code: r.get(2)?,
+                text: r.get(3)?,
+            })
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    Ok(legacy::serialize_bnk_file(&legacy::ParsedBnkFile {
+        fit_profile,
+        entries,
+    }))
+}
+
 fn comments_banks_export_bnk(
     conn: &Connection,
     params: &serde_json::Value,
@@ -1989,26 +2592,82 @@ fn comments_banks_export_bnk(
             details: None,
         });
     };
+    let text = bank_bnk_text(conn, &bank_id, fit_profile)?;
+    let out = PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| HandlerErr {
+            code: "io_failed",
+            message: e.to_string(),
+            details: Some(json!({ "path": out_path })),
+        })?;
+    }
+    std::fs::write(&out, text).map_err(|e| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": out_path })),
+    })?;
+    Ok(json!({ "ok": true }))
+}
+
+/// File stem for a bank in a batch export: the short name without any `.BNK`
+/// suffix (banks imported from files keep it), with path separators and other
+/// characters Windows rejects replaced by `_`.
+fn bnk_file_stem(short_name: &str) -> String {
+    let trimmed = short_name.trim();
+    let stem = if trimmed.to_ascii_lowercase().ends_with(".bnk") {
+        &trimmed[..trimmed.len() - 4]
+    } else {
+        trimmed
+    };
+    let stem: String = stem
+        .chars()
+        .map(|ch| {
+            if ch.is_control() || matches!(ch, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+            {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(|ch: char| ch == '.' || ch.is_whitespace());
+    if stem.is_empty() {
+        "BANK".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+fn comments_banks_export_all_bnk(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let out_folder = get_required_str(params, "outFolder")?;
+    let folder = PathBuf::from(&out_folder);
+    std::fs::create_dir_all(&folder).map_err(|e| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": out_folder })),
+    })?;
+
     let mut stmt = conn
         .prepare(
-            "SELECT sort_order, type_code, level_code, text
-             FROM comment_bank_entries
-             WHERE bank_id = ?
-             ORDER BY sort_order",
+            "SELECT id, short_name, fit_profile
+             FROM comment_banks
+             ORDER BY lower(short_name), id",
         )
         .map_err(|e| HandlerErr {
             code: "db_query_failed",
             message: e.to_string(),
             details: None,
         })?;
-    let entries = stmt
-        .query_map([&bank_id], |r| {
-            Ok(legacy::ParsedBnkEntry {
-                sort_order: r.get::<_, i64>(0)? as usize,
-                type_code: r.get(1)?,
-                level_code: r.get(2)?,
-                text: r.get(3)?,
-            })
+    let banks = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, Option<String>>(2)?,
+            ))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
         .map_err(|e| HandlerErr {
@@ -2016,24 +2675,33 @@ fn comments_banks_export_bnk(
             message: e.to_string(),
             details: None,
         })?;
-    let text = legacy::serialize_bnk_file(&legacy::ParsedBnkFile {
-        fit_profile,
-        entries,
-    });
-    let out = PathBuf::from(&out_path);
-    if let Some(parent) = out.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| HandlerErr {
+
+    // File names are compared case-insensitively so two banks never share a
+    // file on Windows or macOS; later ones get a `_2`, `_3`, ... suffix.
+    let mut used: HashSet<String> = HashSet::new();
+    let mut files: Vec<serde_json::Value> = Vec::with_capacity(banks.len());
+    for (bank_id, short_name, fit_profile) in banks {
+        let stem = bnk_file_stem(&short_name);
+        let mut file_name = format!("{}.BNK", stem);
+        let mut n = 2;
+        while !used.insert(file_name.to_ascii_lowercase()) {
+            file_name = format!("{}_{}.BNK", stem, n);
+            n += 1;
+        }
+        let text = bank_bnk_text(conn, &bank_id, fit_profile)?;
+        let out = folder.join(&file_name);
+        std::fs::write(&out, text).map_err(|e| HandlerErr {
             code: "io_failed",
             message: e.to_string(),
-            details: Some(json!({ "path": out_path })),
+            details: Some(json!({ "path": out.to_string_lossy() })),
         })?;
+        files.push(json!({
+            "bankId": bank_id,
+            "shortName": short_name,
+            "path": out.to_string_lossy(),
+        }));
     }
-    std::fs::write(&out, text).map_err(|e| HandlerErr {
-        code: "io_failed",
-        message: e.to_string(),
-        details: Some(json!({ "path": out_path })),
-    })?;
-    Ok(json!({ "ok": true }))
+    Ok(json!({ "ok": true, "count": files.len(), "files": files }))
 }
 
 fn handle_comments_sets_list(state: &mut AppState, req: &Request) -> serde_json::Value {
@@ -2076,6 +2744,36 @@ fn handle_comments_sets_delete(state: &mut AppState, req: &Request) -> serde_jso
     }
 }
 
+fn handle_comments_sets_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_sets_reorder(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_comments_render(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_render(conn, &req.params) {
+        Ok(v) => ok(&req.id, v),
+        Err(e) => e.response(&req.id),
+    }
+}
+
+fn handle_comments_fit_check(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_fit_check(conn, &req.params) {
+        Ok(v) => ok(&req.id, v),
+        Err(e) => e.response(&req.id),
+    }
+}
+
 fn handle_comments_banks_list(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -2096,6 +2794,16 @@ fn handle_comments_banks_open(state: &mut AppState, req: &Request) -> serde_json
     }
 }
 
+fn handle_comments_banks_search(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_banks_search(conn, &req.params) {
+        Ok(v) => ok(&req.id, v),
+        Err(e) => e.response(&req.id),
+    }
+}
+
 fn handle_comments_banks_create(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -2106,6 +2814,16 @@ fn handle_comments_banks_create(state: &mut AppState, req: &Request) -> serde_js
     }
 }
 
+fn handle_comments_banks_duplicate(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_banks_duplicate(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_comments_banks_update_meta(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -2136,6 +2854,16 @@ fn handle_comments_banks_entry_delete(state: &mut AppState, req: &Request) -> se
     }
 }
 
+fn handle_comments_banks_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_banks_delete(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_comments_banks_import_bnk(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -2156,6 +2884,16 @@ fn handle_comments_banks_export_bnk(state: &mut AppState, req: &Request) -> serd
     }
 }
 
+fn handle_comments_banks_export_all_bnk(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match comments_banks_export_all_bnk(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
 fn handle_comments_transfer_preview(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -2186,21 +2924,54 @@ fn handle_comments_transfer_flood_fill(state: &mut AppState, req: &Request) -> s
     }
 }
 
+pub const METHODS: &[&str] = &[
+    "comments.sets.list",
+    "comments.sets.open",
+    "comments.sets.upsert",
+    "comments.sets.delete",
+    "comments.sets.reorder",
+    "comments.remarks.upsertOne",
+    "comments.render",
+    "comments.fitCheck",
+    "comments.banks.list",
+    "comments.banks.open",
+    "comments.banks.search",
+    "comments.banks.create",
+    "comments.banks.duplicate",
+    "comments.banks.updateMeta",
+    "comments.banks.entryUpsert",
+    "comments.banks.entryDelete",
+    "comments.banks.delete",
+    "comments.banks.importBnk",
+    "comments.banks.exportBnk",
+    "comments.banks.exportAllBnk",
+    "comments.transfer.preview",
+    "comments.transfer.apply",
+    "comments.transfer.floodFill",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "comments.sets.list" => Some(handle_comments_sets_list(state, req)),
         "comments.sets.open" => Some(handle_comments_sets_open(state, req)),
         "comments.sets.upsert" => Some(handle_comments_sets_upsert(state, req)),
         "comments.sets.delete" => Some(handle_comments_sets_delete(state, req)),
+        "comments.sets.reorder" => Some(handle_comments_sets_reorder(state, req)),
         "comments.remarks.upsertOne" => Some(handle_comments_remarks_upsert_one(state, req)),
+        "comments.render" => Some(handle_comments_render(state, req)),
+        "comments.fitCheck" => Some(handle_comments_fit_check(state, req)),
         "comments.banks.list" => Some(handle_comments_banks_list(state, req)),
         "comments.banks.open" => Some(handle_comments_banks_open(state, req)),
+        "comments.banks.search" => Some(handle_comments_banks_search(state, req)),
         "comments.banks.create" => Some(handle_comments_banks_create(state, req)),
+        "comments.banks.duplicate" => Some(handle_comments_banks_duplicate(state, req)),
         "comments.banks.updateMeta" => Some(handle_comments_banks_update_meta(state, req)),
         "comments.banks.entryUpsert" => Some(handle_comments_banks_entry_upsert(state, req)),
         "comments.banks.entryDelete" => Some(handle_comments_banks_entry_delete(state, req)),
+        "comments.banks.delete" => Some(handle_comments_banks_delete(state, req)),
         "comments.banks.importBnk" => Some(handle_comments_banks_import_bnk(state, req)),
         "comments.banks.exportBnk" => Some(handle_comments_banks_export_bnk(state, req)),
+        "comments.banks.exportAllBnk" => Some(handle_comments_banks_export_all_bnk(state, req)),
         "comments.transfer.preview" => Some(handle_comments_transfer_preview(state, req)),
         "comments.transfer.apply" => Some(handle_comments_transfer_apply(state, req)),
         "comments.transfer.floodFill" => Some(handle_comments_transfer_flood_fill(state, req)),
@@ -3,14 +3,26 @@ use crate::legacy;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
 use serde_json::json;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Size of a file in the workspace folder, or `None` when it does not exist.
+fn workspace_file_bytes(workspace: Option<&PathBuf>, name: &str) -> Option<u64> {
+    workspace
+        .and_then(|p| std::fs::metadata(p.join(name)).ok())
+        .map(|m| m.len())
+}
 
 fn handle_health(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let workspace = state.workspace.as_ref();
     ok(
         &req.id,
         json!({
             "version": env!("CARGO_PKG_VERSION"),
-            "workspacePath": state.workspace.as_ref().map(|p| p.to_string_lossy().to_string())
+            "workspacePath": workspace.map(|p| p.to_string_lossy().to_string()),
+            "dbOpen": state.db.is_some(),
+            "schemaVersion": state.db.as_ref().and_then(|conn| db::schema_version(conn).ok()),
+            "dbSizeBytes": workspace_file_bytes(workspace, "markbook.sqlite3"),
+            "walSizeBytes": workspace_file_bytes(workspace, "markbook.sqlite3-wal")
         }),
     )
 }
@@ -290,6 +302,17 @@ fn handle_calc_config_clear_override(state: &mut AppState, req: &Request) -> ser
     ok(&req.id, json!({ "ok": true }))
 }
 
+fn handle_system_methods(req: &Request) -> serde_json::Value {
+    let methods = crate::ipc::router::registered_methods();
+    ok(
+        &req.id,
+        json!({
+            "count": methods.len(),
+            "methods": methods
+        }),
+    )
+}
+
 fn find_usr_cfg(workspace: &std::path::Path) -> anyhow::Result<Option<std::path::PathBuf>> {
     let mut best: Option<std::path::PathBuf> = None;
     for ent in std::fs::read_dir(workspace)? {
@@ -311,13 +334,109 @@ fn find_usr_cfg(workspace: &std::path::Path) -> anyhow::Result<Option<std::path:
     Ok(best)
 }
 
+fn handle_db_integrity_check(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let include_orphans = req
+        .params
+        .get("includeOrphans")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    match db::integrity_problems(conn, include_orphans) {
+        Ok(problems) => ok(
+            &req.id,
+            json!({ "ok": problems.is_empty(), "problems": problems }),
+        ),
+        Err(e) => err(&req.id, "db_query_failed", e.to_string(), None),
+    }
+}
+
+/// On-disk size of the workspace database, including any pending WAL.
+fn workspace_db_bytes(workspace: &Path) -> u64 {
+    ["markbook.sqlite3", "markbook.sqlite3-wal"]
+        .iter()
+        .filter_map(|name| std::fs::metadata(workspace.join(name)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn handle_db_vacuum(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let (Some(conn), Some(workspace)) = (state.db.as_ref(), state.workspace.as_ref()) else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let size_before = workspace_db_bytes(workspace);
+    if let Err(e) = db::vacuum(conn) {
+        return err(&req.id, "db_vacuum_failed", e.to_string(), None);
+    }
+    let size_after = workspace_db_bytes(workspace);
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "sizeBefore": size_before,
+            "sizeAfter": size_after,
+            "bytesReclaimed": size_before.saturating_sub(size_after)
+        }),
+    )
+}
+
+/// Row totals across every class in the workspace.
+fn workspace_counts(conn: &rusqlite::Connection) -> rusqlite::Result<serde_json::Value> {
+    let count = |table: &str| -> rusqlite::Result<i64> {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+    };
+    Ok(json!({
+        "classCount": count("classes")?,
+        "studentCount": count("students")?,
+        "markSetCount": count("mark_sets")?,
+        "assessmentCount": count("assessments")?,
+        "scoreCount": count("scores")?,
+        "commentBankCount": count("comment_banks")?,
+        "activeClassCount": count("classes WHERE archived = 0")?,
+        "activeStudentCount": count(
+            "students s JOIN classes c ON c.id = s.class_id
+             WHERE c.archived = 0 AND s.archived = 0"
+        )?
+    }))
+}
+
+fn handle_workspace_stats(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let (Some(conn), Some(workspace)) = (state.db.as_ref(), state.workspace.as_ref()) else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let mut stats = match workspace_counts(conn) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    stats["workspacePath"] = json!(workspace.to_string_lossy());
+    stats["dbSizeBytes"] = json!(workspace_db_bytes(workspace));
+    ok(&req.id, stats)
+}
+
+pub const METHODS: &[&str] = &[
+    "health",
+    "workspace.select",
+    "workspace.stats",
+    "calc.config.get",
+    "calc.config.update",
+    "calc.config.clearOverride",
+    "system.methods",
+    "db.integrityCheck",
+    "db.vacuum",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "health" => Some(handle_health(state, req)),
         "workspace.select" => Some(handle_workspace_select(state, req)),
+        "workspace.stats" => Some(handle_workspace_stats(state, req)),
         "calc.config.get" => Some(handle_calc_config_get(state, req)),
         "calc.config.update" => Some(handle_calc_config_update(state, req)),
         "calc.config.clearOverride" => Some(handle_calc_config_clear_override(state, req)),
+        "system.methods" => Some(handle_system_methods(req)),
+        "db.integrityCheck" => Some(handle_db_integrity_check(state, req)),
+        "db.vacuum" => Some(handle_db_vacuum(state, req)),
         _ => None,
     }
 }
@@ -0,0 +1,459 @@
+use super::grid;
+use crate::db;
+use crate::ipc::error::{err, ok};
+use crate::ipc::types::{AppState, Request};
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Logged operations kept per class; older entries are pruned on every write.
+const EDIT_LOG_MAX_PER_CLASS: i64 = 200;
+const EDITS_LIST_DEFAULT_LIMIT: i64 = 50;
+
+/// Rows that `students.delete` removes alongside the student, restored in this
+/// order by `edits.undo`. `?1` is the student id, `?2` the class id.
+const STUDENT_SNAPSHOT_TABLES: &[(&str, &str)] = &[
+    (
+        "scores",
+        "SELECT sc.* FROM scores sc
+         JOIN assessments a ON a.id = sc.assessment_id
+         JOIN mark_sets ms ON ms.id = a.mark_set_id
+         WHERE sc.student_id = ?1 AND ms.class_id = ?2",
+    ),
+    (
+        "score_history",
+        "SELECT h.* FROM score_history h
+         JOIN assessments a ON a.id = h.assessment_id
+         JOIN mark_sets ms ON ms.id = a.mark_set_id
+         WHERE h.student_id = ?1 AND ms.class_id = ?2
+         ORDER BY h.id",
+    ),
+    (
+        "student_notes",
+        "SELECT * FROM student_notes WHERE student_id = ?1 AND class_id = ?2",
+    ),
+    (
+        "attendance_student_months",
+        "SELECT * FROM attendance_student_months WHERE student_id = ?1 AND class_id = ?2",
+    ),
+    (
+        "seating_assignments",
+        "SELECT * FROM seating_assignments WHERE student_id = ?1 AND class_id = ?2",
+    ),
+    (
+        "comment_set_remarks",
+        "SELECT r.* FROM comment_set_remarks r
+         JOIN comment_set_indexes csi ON csi.id = r.comment_set_index_id
+         WHERE r.student_id = ?1 AND csi.class_id = ?2",
+    ),
+];
+
+struct HandlerErr {
+    code: &'static str,
+    message: String,
+    details: Option<serde_json::Value>,
+}
+
+impl HandlerErr {
+    fn response(self, id: &str) -> serde_json::Value {
+        err(id, self.code, self.message, self.details)
+    }
+}
+
+fn query_failed(e: rusqlite::Error) -> HandlerErr {
+    HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    }
+}
+
+fn is_foreign_key_violation(e: &rusqlite::Error) -> bool {
+    matches!(
+        e,
+        rusqlite::Error::SqliteFailure(f, _)
+            if f.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY
+    )
+}
+
+/// Appends an operation to the class's undo log and prunes the oldest entries
+/// beyond `EDIT_LOG_MAX_PER_CLASS`. Call inside the transaction doing the edit.
+pub(crate) fn record(
+    conn: &Connection,
+    class_id: &str,
+    op: &str,
+    payload: &serde_json::Value,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO edit_log(class_id, op, payload_json, created_at)
+         VALUES(?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
+        (class_id, op, payload.to_string()),
+    )?;
+    conn.execute(
+        "DELETE FROM edit_log
+         WHERE class_id = ?1 AND id NOT IN (
+           SELECT id FROM edit_log WHERE class_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        (class_id, EDIT_LOG_MAX_PER_CLASS),
+    )?;
+    Ok(())
+}
+
+/// The score cell as it is before an edit; `prior` is null when the cell has
+/// no row yet, so undo knows to delete rather than restore.
+pub(crate) fn score_prior(
+    conn: &Connection,
+    assessment_id: &str,
+    student_id: &str,
+) -> rusqlite::Result<serde_json::Value> {
+    let prior = conn
+        .query_row(
+            "SELECT id, raw_value, status, remark FROM scores
+             WHERE assessment_id = ? AND student_id = ?",
+            (assessment_id, student_id),
+            |r| {
+                Ok(json!({
+                    "id": r.get::<_, String>(0)?,
+                    "rawValue": r.get::<_, Option<f64>>(1)?,
+                    "status": r.get::<_, String>(2)?,
+                    "remark": r.get::<_, Option<String>>(3)?
+                }))
+            },
+        )
+        .optional()?;
+    Ok(json!({
+        "assessmentId": assessment_id,
+        "studentId": student_id,
+        "prior": prior
+    }))
+}
+
+/// The student row plus every row `students.delete` removes with it.
+pub(crate) fn student_snapshot(
+    conn: &Connection,
+    class_id: &str,
+    student_id: &str,
+) -> rusqlite::Result<serde_json::Value> {
+    let student = db::query_rows_json(
+        conn,
+        "SELECT * FROM students WHERE id = ?1 AND class_id = ?2",
+        (student_id, class_id),
+    )?
+    .into_iter()
+    .next()
+    .unwrap_or(serde_json::Value::Null);
+    let mut rows = serde_json::Map::new();
+    for (table, sql) in STUDENT_SNAPSHOT_TABLES {
+        let table_rows = db::query_rows_json(conn, sql, (student_id, class_id))?;
+        rows.insert(table.to_string(), serde_json::Value::Array(table_rows));
+    }
+    Ok(json!({ "student": student, "rows": rows }))
+}
+
+/// Short description of a logged operation for the UI.
+fn entry_summary(op: &str, payload: &serde_json::Value) -> serde_json::Value {
+    match op {
+        "students.delete" => {
+            let student = &payload["student"];
+            let display_name = format!(
+                "{}, {}",
+                student["last_name"].as_str().unwrap_or(""),
+                student["first_name"].as_str().unwrap_or("")
+            );
+            json!({ "studentId": student["id"], "displayName": display_name })
+        }
+        _ => json!({
+            "cells": payload["cells"].as_array().map(|c| c.len()).unwrap_or(0)
+        }),
+    }
+}
+
+fn edits_list(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let Some(class_id) = params.get("classId").and_then(|v| v.as_str()) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "missing classId".to_string(),
+            details: None,
+        });
+    };
+    let limit = match params.get("limit") {
+        None => EDITS_LIST_DEFAULT_LIMIT,
+        Some(v) => match v.as_i64() {
+            Some(n) if (1..=EDIT_LOG_MAX_PER_CLASS).contains(&n) => n,
+            _ => {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: format!("limit must be 1..{}", EDIT_LOG_MAX_PER_CLASS),
+                    details: None,
+                })
+            }
+        },
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, op, payload_json, created_at FROM edit_log
+             WHERE class_id = ? ORDER BY id DESC LIMIT ?",
+        )
+        .map_err(query_failed)?;
+    let entries = stmt
+        .query_map((class_id, limit), |r| {
+            let op: String = r.get(1)?;
+            let payload: String = r.get(2)?;
+            let payload: serde_json::Value =
+                serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null);
+            Ok(json!({
+                "id": r.get::<_, i64>(0)?,
+                "op": op,
+                "createdAt": r.get::<_, String>(3)?,
+                "summary": entry_summary(&op, &payload)
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(query_failed)?;
+    Ok(json!({ "entries": entries }))
+}
+
+/// Restores each cell to its prior state, newest first so a cell edited twice
+/// in one bulk paste ends at its original value. Cells whose assessment or
+/// student has since been deleted are skipped.
+fn undo_scores(
+    conn: &Connection,
+    payload: &serde_json::Value,
+) -> Result<(usize, usize), rusqlite::Error> {
+    let cells = payload["cells"].as_array().cloned().unwrap_or_default();
+    let (mut restored, mut skipped) = (0usize, 0usize);
+    for cell in cells.iter().rev() {
+        let (Some(assessment_id), Some(student_id)) =
+            (cell["assessmentId"].as_str(), cell["studentId"].as_str())
+        else {
+            skipped += 1;
+            continue;
+        };
+        let prior = &cell["prior"];
+        let old = grid::read_score_cell(conn, assessment_id, student_id)?;
+        let result = if prior.is_null() {
+            conn.execute(
+                "DELETE FROM scores WHERE assessment_id = ? AND student_id = ?",
+                (assessment_id, student_id),
+            )
+        } else {
+            conn.execute(
+                "INSERT INTO scores(id, assessment_id, student_id, raw_value, status, remark)
+                 VALUES(?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(assessment_id, student_id) DO UPDATE SET
+                   raw_value = excluded.raw_value,
+                   status = excluded.status,
+                   remark = excluded.remark",
+                (
+                    prior["id"]
+                        .as_str()
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| Uuid::new_v4().to_string()),
+                    assessment_id,
+                    student_id,
+                    prior["rawValue"].as_f64(),
+                    prior["status"].as_str().unwrap_or("no_mark"),
+                    prior["remark"].as_str(),
+                ),
+            )
+        };
+        match result {
+            Ok(_) => restored += 1,
+            Err(e) if is_foreign_key_violation(&e) => {
+                skipped += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        let new = (!prior.is_null()).then(|| {
+            (
+                prior["rawValue"].as_f64(),
+                prior["status"].as_str().unwrap_or("no_mark").to_string(),
+            )
+        });
+        grid::record_score_history(conn, assessment_id, student_id, &old, &new)?;
+    }
+    Ok((restored, skipped))
+}
+
+/// Re-inserts a deleted student at their old roster position, then their
+/// dependent rows. Rows whose parent (assessment, comment set, seating plan)
+/// has since been deleted are skipped.
+fn undo_student_delete(
+    conn: &Connection,
+    class_id: &str,
+    payload: &serde_json::Value,
+) -> Result<(usize, usize), HandlerErr> {
+    let Some(student) = payload["student"].as_object() else {
+        return Err(HandlerErr {
+            code: "undo_failed",
+            message: "log entry has no student snapshot".to_string(),
+            details: None,
+        });
+    };
+    let student_id = student.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let exists: Option<i64> = conn
+        .query_row("SELECT 1 FROM students WHERE id = ?", [student_id], |r| {
+            r.get(0)
+        })
+        .optional()
+        .map_err(query_failed)?;
+    if exists.is_some() {
+        return Err(HandlerErr {
+            code: "undo_conflict",
+            message: "student already exists".to_string(),
+            details: Some(json!({ "studentId": student_id })),
+        });
+    }
+
+    let insert_failed = |table: &str, e: rusqlite::Error| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": table })),
+    };
+    let sort_order = student
+        .get("sort_order")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    conn.execute(
+        "UPDATE students
+         SET sort_order = sort_order + 1,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE class_id = ? AND sort_order >= ?",
+        (class_id, sort_order),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "students" })),
+    })?;
+    db::insert_row_json(conn, "students", student).map_err(|e| insert_failed("students", e))?;
+
+    let (mut restored, mut skipped) = (1usize, 0usize);
+    for (table, _) in STUDENT_SNAPSHOT_TABLES {
+        let rows = payload["rows"][table]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for row in rows {
+            let Some(row) = row.as_object() else {
+                skipped += 1;
+                continue;
+            };
+            match db::insert_row_json(conn, table, row) {
+                Ok(_) => restored += 1,
+                Err(e) if is_foreign_key_violation(&e) => skipped += 1,
+                Err(e) => return Err(insert_failed(table, e)),
+            }
+        }
+    }
+    Ok((restored, skipped))
+}
+
+fn edits_undo(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let Some(class_id) = params.get("classId").and_then(|v| v.as_str()) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "missing classId".to_string(),
+            details: None,
+        });
+    };
+
+    let latest: Option<(i64, String, String, String)> = conn
+        .query_row(
+            "SELECT id, op, payload_json, created_at FROM edit_log
+             WHERE class_id = ? ORDER BY id DESC LIMIT 1",
+            [class_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )
+        .optional()
+        .map_err(query_failed)?;
+    let Some((entry_id, op, payload, created_at)) = latest else {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "nothing to undo".to_string(),
+            details: None,
+        });
+    };
+    let payload: serde_json::Value = serde_json::from_str(&payload).map_err(|e| HandlerErr {
+        code: "undo_failed",
+        message: e.to_string(),
+        details: Some(json!({ "entryId": entry_id })),
+    })?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let (restored, skipped) = match op.as_str() {
+        "scores.setCell" | "scores.bulkSet" | "scores.clearColumn" | "scores.clearRow" => {
+            undo_scores(&tx, &payload).map_err(|e| HandlerErr {
+                code: "db_update_failed",
+                message: e.to_string(),
+                details: Some(json!({ "table": "scores" })),
+            })?
+        }
+        "students.delete" => undo_student_delete(&tx, class_id, &payload)?,
+        other => {
+            return Err(HandlerErr {
+                code: "undo_failed",
+                message: format!("unsupported logged op: {}", other),
+                details: Some(json!({ "entryId": entry_id })),
+            })
+        }
+    };
+    tx.execute("DELETE FROM edit_log WHERE id = ?", [entry_id])
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "edit_log" })),
+        })?;
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    Ok(json!({
+        "undone": {
+            "id": entry_id,
+            "op": op,
+            "createdAt": created_at,
+            "summary": entry_summary(&op, &payload)
+        },
+        "restored": restored,
+        "skipped": skipped
+    }))
+}
+
+fn handle_edits(
+    state: &mut AppState,
+    req: &Request,
+    f: fn(&Connection, &serde_json::Value) -> Result<serde_json::Value, HandlerErr>,
+) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match f(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(e) => e.response(&req.id),
+    }
+}
+
+pub const METHODS: &[&str] = &["edits.list", "edits.undo"];
+
+pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
+    match req.method.as_str() {
+        "edits.list" => Some(handle_edits(state, req, edits_list)),
+        "edits.undo" => Some(handle_edits(state, req, edits_undo)),
+        _ => None,
+    }
+}
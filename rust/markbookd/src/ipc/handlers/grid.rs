@@ -1,23 +1,27 @@
+use super::edits;
+use crate::calc;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
 use rusqlite::types::Value;
 use rusqlite::{params_from_iter, Connection, OptionalExtension};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 const GRID_GET_MAX_ROWS: i64 = 2000;
 const GRID_GET_MAX_COLS: i64 = 256;
 const GRID_BULK_UPDATE_MAX_EDITS: usize = 5000;
+/// History rows kept per score cell; older changes are pruned on every write.
+const SCORE_HISTORY_MAX_PER_CELL: i64 = 20;
 
-struct HandlerErr {
+pub(crate) struct HandlerErr {
     code: &'static str,
     message: String,
     details: Option<serde_json::Value>,
 }
 
 impl HandlerErr {
-    fn response(self, id: &str) -> serde_json::Value {
+    pub(crate) fn response(self, id: &str) -> serde_json::Value {
         err(id, self.code, self.message, self.details)
     }
 }
@@ -77,9 +81,13 @@ fn resolve_student_id_by_row(
     class_id: &str,
     row: i64,
 ) -> Result<String, HandlerErr> {
+    // Grid rows index the non-archived roster in sort order.
     let student_id: Option<String> = conn
         .query_row(
-            "SELECT id FROM students WHERE class_id = ? AND sort_order = ?",
+            "SELECT id FROM students
+             WHERE class_id = ? AND archived = 0
+             ORDER BY sort_order
+             LIMIT 1 OFFSET ?",
             (class_id, row),
             |r| r.get(0),
         )
@@ -129,6 +137,12 @@ fn upsert_score(
     raw_value: Option<f64>,
     status: &str,
 ) -> Result<(), HandlerErr> {
+    let old = read_score_cell(conn, assessment_id, student_id).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
     let score_id = Uuid::new_v4().to_string();
     conn.execute(
         "INSERT INTO scores(id, assessment_id, student_id, raw_value, status)
@@ -143,9 +157,170 @@ fn upsert_score(
         message: e.to_string(),
         details: Some(json!({ "table": "scores" })),
     })?;
+
+    record_score_history(
+        conn,
+        assessment_id,
+        student_id,
+        &old,
+        &Some((raw_value, status.to_string())),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "score_history" })),
+    })
+}
+
+/// A score cell's `(raw_value, status)`, or `None` when the cell has no row.
+pub(crate) type ScoreCell = Option<(Option<f64>, String)>;
+
+pub(crate) fn read_score_cell(
+    conn: &Connection,
+    assessment_id: &str,
+    student_id: &str,
+) -> rusqlite::Result<ScoreCell> {
+    conn.query_row(
+        "SELECT raw_value, status FROM scores WHERE assessment_id = ? AND student_id = ?",
+        (assessment_id, student_id),
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .optional()
+}
+
+/// Appends one change to the cell's history and prunes it back to
+/// `SCORE_HISTORY_MAX_PER_CELL` entries. Every write to `scores` goes through
+/// here; rewriting a cell with what it already holds is not recorded.
+pub(crate) fn record_score_history(
+    conn: &Connection,
+    assessment_id: &str,
+    student_id: &str,
+    old: &ScoreCell,
+    new: &ScoreCell,
+) -> rusqlite::Result<()> {
+    if old == new {
+        return Ok(());
+    }
+    let (old_raw, old_status) = old.clone().unzip();
+    let (new_raw, new_status) = new.clone().unzip();
+    conn.execute(
+        "INSERT INTO score_history(
+           assessment_id, student_id, old_raw, old_status, new_raw, new_status, changed_at
+         )
+         VALUES(?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
+        (
+            assessment_id,
+            student_id,
+            old_raw.flatten(),
+            old_status,
+            new_raw.flatten(),
+            new_status,
+        ),
+    )?;
+    conn.execute(
+        "DELETE FROM score_history
+         WHERE assessment_id = ?1 AND student_id = ?2 AND id NOT IN (
+           SELECT id FROM score_history
+           WHERE assessment_id = ?1 AND student_id = ?2
+           ORDER BY id DESC LIMIT ?3
+         )",
+        (assessment_id, student_id, SCORE_HISTORY_MAX_PER_CELL),
+    )?;
     Ok(())
 }
 
+/// Deletes the score rows matching `where_sql`, recording each cleared cell in
+/// its history. Returns the rows deleted.
+pub(crate) fn delete_scores_with_history(
+    conn: &Connection,
+    where_sql: &str,
+    params: &[&str],
+) -> rusqlite::Result<usize> {
+    let cells: Vec<(String, String, ScoreCell)> = conn
+        .prepare(&format!(
+            "SELECT assessment_id, student_id, raw_value, status FROM scores WHERE {}",
+            where_sql
+        ))?
+        .query_map(params_from_iter(params.iter()), |r| {
+            Ok((r.get(0)?, r.get(1)?, Some((r.get(2)?, r.get(3)?))))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let deleted = conn.execute(
+        &format!("DELETE FROM scores WHERE {}", where_sql),
+        params_from_iter(params.iter()),
+    )?;
+    for (assessment_id, student_id, old) in &cells {
+        record_score_history(conn, assessment_id, student_id, old, &None)?;
+    }
+    Ok(deleted)
+}
+
+fn score_prior(
+    conn: &Connection,
+    assessment_id: &str,
+    student_id: &str,
+) -> Result<serde_json::Value, HandlerErr> {
+    edits::score_prior(conn, assessment_id, student_id).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })
+}
+
+fn record_score_edit(
+    conn: &Connection,
+    class_id: &str,
+    op: &str,
+    cells: Vec<serde_json::Value>,
+) -> Result<(), HandlerErr> {
+    edits::record(conn, class_id, op, &json!({ "cells": cells })).map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "edit_log" })),
+    })
+}
+
+fn assessment_in_class(
+    conn: &Connection,
+    class_id: &str,
+    assessment_id: &str,
+) -> Result<bool, HandlerErr> {
+    conn.query_row(
+        "SELECT 1
+         FROM assessments a
+         JOIN mark_sets ms ON ms.id = a.mark_set_id
+         WHERE a.id = ? AND ms.class_id = ?",
+        (assessment_id, class_id),
+        |r| r.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|v| v.is_some())
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })
+}
+
+pub(crate) fn student_in_class(
+    conn: &Connection,
+    class_id: &str,
+    student_id: &str,
+) -> Result<bool, HandlerErr> {
+    conn.query_row(
+        "SELECT 1 FROM students WHERE id = ? AND class_id = ?",
+        (student_id, class_id),
+        |r| r.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|v| v.is_some())
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })
+}
+
 fn handle_grid_get(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -217,9 +392,12 @@ fn handle_grid_get(state: &mut AppState, req: &Request) -> serde_json::Value {
         );
     }
 
-    let mut student_stmt = match conn
-        .prepare("SELECT id FROM students WHERE class_id = ? ORDER BY sort_order LIMIT ? OFFSET ?")
-    {
+    let mut student_stmt = match conn.prepare(
+        "SELECT id FROM students
+             WHERE class_id = ? AND archived = 0
+             ORDER BY sort_order
+             LIMIT ? OFFSET ?",
+    ) {
         Ok(s) => s,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
     };
@@ -570,12 +748,626 @@ fn handle_grid_bulk_update(state: &mut AppState, req: &Request) -> serde_json::V
     ok(&req.id, result)
 }
 
+fn handle_scores_set_cell(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let assessment_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing assessmentId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+
+    let value = match req.params.get("rawValue") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_f64() {
+            Some(n) => Some(n),
+            None => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "rawValue must be a number or null",
+                    None,
+                )
+            }
+        },
+    };
+    let status_value = match req.params.get("status") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_str() {
+            Some(s) => Some(s),
+            None => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "status must be one of: scored, zero, no_mark",
+                    None,
+                )
+            }
+        },
+    };
+    let (raw_value, status) = match resolve_score_state(status_value, value) {
+        Ok(v) => v,
+        Err(e) => return e.response(&req.id),
+    };
+
+    match assessment_in_class(conn, &class_id, &assessment_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "assessment not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+    match student_in_class(conn, &class_id, &student_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "student not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    let prior = match score_prior(&tx, &assessment_id, &student_id) {
+        Ok(v) => v,
+        Err(e) => return e.response(&req.id),
+    };
+    if let Err(e) = upsert_score(&tx, &assessment_id, &student_id, raw_value, status) {
+        return e.response(&req.id);
+    }
+    if let Err(e) = record_score_edit(&tx, &class_id, "scores.setCell", vec![prior]) {
+        return e.response(&req.id);
+    }
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(
+        &req.id,
+        json!({
+            "rawValue": raw_value,
+            "status": status
+        }),
+    )
+}
+
+fn handle_grid_get_scores(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+
+    let exists: Option<i64> = match conn
+        .query_row(
+            "SELECT 1 FROM mark_sets WHERE id = ? AND class_id = ? AND deleted_at IS NULL",
+            (&mark_set_id, &class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if exists.is_none() {
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+
+    let mut stmt = match conn.prepare(
+        "SELECT sc.assessment_id, sc.student_id, sc.raw_value, sc.status, sc.remark
+         FROM scores sc
+         JOIN assessments a ON a.id = sc.assessment_id
+         JOIN students s ON s.id = sc.student_id
+         WHERE a.mark_set_id = ? AND s.class_id = ?
+         ORDER BY s.sort_order, a.idx",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let rows = stmt
+        .query_map((&mark_set_id, &class_id), |row| {
+            let assessment_id: String = row.get(0)?;
+            let student_id: String = row.get(1)?;
+            let raw_value: Option<f64> = row.get(2)?;
+            let status: String = row.get(3)?;
+            let remark: Option<String> = row.get(4)?;
+            Ok(json!({
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": raw_value,
+                "status": status,
+                "remark": remark
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>());
+
+    match rows {
+        Ok(scores) => ok(&req.id, json!({ "scores": scores })),
+        Err(e) => err(&req.id, "db_query_failed", e.to_string(), None),
+    }
+}
+
+fn class_id_set(
+    conn: &Connection,
+    sql: &str,
+    class_id: &str,
+) -> Result<HashSet<String>, HandlerErr> {
+    let mut stmt = conn.prepare(sql).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    stmt.query_map([class_id], |r| r.get::<_, String>(0))
+        .and_then(|it| it.collect::<Result<HashSet<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })
+}
+
+fn handle_scores_bulk_set(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let Some(edits_arr) = req.params.get("edits").and_then(|v| v.as_array()) else {
+        return err(&req.id, "bad_params", "missing edits[]", None);
+    };
+    if edits_arr.len() > GRID_BULK_UPDATE_MAX_EDITS {
+        return err(
+            &req.id,
+            "bad_params",
+            "bulk payload exceeds max edits",
+            Some(json!({
+                "edits": edits_arr.len(),
+                "maxEdits": GRID_BULK_UPDATE_MAX_EDITS
+            })),
+        );
+    }
+
+    let assessment_ids = match class_id_set(
+        conn,
+        "SELECT a.id
+         FROM assessments a
+         JOIN mark_sets ms ON ms.id = a.mark_set_id
+         WHERE ms.class_id = ?",
+        &class_id,
+    ) {
+        Ok(v) => v,
+        Err(e) => return e.response(&req.id),
+    };
+    let student_ids = match class_id_set(
+        conn,
+        "SELECT id FROM students WHERE class_id = ?",
+        &class_id,
+    ) {
+        Ok(v) => v,
+        Err(e) => return e.response(&req.id),
+    };
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+
+    let mut updated: usize = 0;
+    let mut skipped: usize = 0;
+    let mut priors: Vec<serde_json::Value> = Vec::new();
+    for edit in edits_arr {
+        let Some(obj) = edit.as_object() else {
+            skipped += 1;
+            continue;
+        };
+        let (Some(assessment_id), Some(student_id)) = (
+            obj.get("assessmentId").and_then(|v| v.as_str()),
+            obj.get("studentId").and_then(|v| v.as_str()),
+        ) else {
+            skipped += 1;
+            continue;
+        };
+        if !assessment_ids.contains(assessment_id) || !student_ids.contains(student_id) {
+            skipped += 1;
+            continue;
+        }
+        // Only a missing or null field means "not given"; a value of the wrong
+        // type (e.g. "abc" or "8,5") must not fall through to a clear.
+        let state_value = match obj.get("status") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(v) => match v.as_str() {
+                Some(s) => Some(s),
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            },
+        };
+        let value = match obj.get("rawValue") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(v) => match v.as_f64() {
+                Some(n) => Some(n),
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            },
+        };
+        let Ok((raw_value, status)) = resolve_score_state(state_value, value) else {
+            skipped += 1;
+            continue;
+        };
+
+        // Any DB failure rolls back the whole paste.
+        match score_prior(&tx, assessment_id, student_id) {
+            Ok(prior) => priors.push(prior),
+            Err(e) => {
+                let _ = tx.rollback();
+                return e.response(&req.id);
+            }
+        }
+        if let Err(e) = upsert_score(&tx, assessment_id, student_id, raw_value, status) {
+            let _ = tx.rollback();
+            return e.response(&req.id);
+        }
+        updated += 1;
+    }
+
+    // The whole paste is one undo step.
+    if updated > 0 {
+        if let Err(e) = record_score_edit(&tx, &class_id, "scores.bulkSet", priors) {
+            let _ = tx.rollback();
+            return e.response(&req.id);
+        }
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "updated": updated, "skipped": skipped }))
+}
+
+/// Deletes the class's score rows matching `where_sql` in one transaction and
+/// logs their prior values as a single undo step. Returns the rows cleared.
+fn clear_scores(
+    conn: &Connection,
+    class_id: &str,
+    op: &str,
+    where_sql: &str,
+    params: &[&str],
+) -> Result<usize, HandlerErr> {
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let cells: Vec<(String, String)> = {
+        let mut stmt = tx
+            .prepare(&format!(
+                "SELECT assessment_id, student_id FROM scores WHERE {}",
+                where_sql
+            ))
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        stmt.query_map(params_from_iter(params.iter()), |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?
+    };
+    if cells.is_empty() {
+        return Ok(0);
+    }
+
+    let priors = cells
+        .iter()
+        .map(|(assessment_id, student_id)| score_prior(&tx, assessment_id, student_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let cleared = delete_scores_with_history(&tx, where_sql, params).map_err(|e| HandlerErr {
+        code: "db_delete_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "scores" })),
+    })?;
+    record_score_edit(&tx, class_id, op, priors)?;
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    Ok(cleared)
+}
+
+fn handle_scores_history(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let assessment_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing assessmentId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+
+    match assessment_in_class(conn, &class_id, &assessment_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "assessment not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+    match student_in_class(conn, &class_id, &student_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "student not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let mut stmt = match conn.prepare(
+        "SELECT old_raw, old_status, new_raw, new_status, changed_at
+         FROM score_history
+         WHERE assessment_id = ? AND student_id = ?
+         ORDER BY id",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let rows = stmt
+        .query_map((&assessment_id, &student_id), |row| {
+            let old_raw: Option<f64> = row.get(0)?;
+            let old_status: Option<String> = row.get(1)?;
+            let new_raw: Option<f64> = row.get(2)?;
+            let new_status: Option<String> = row.get(3)?;
+            let changed_at: String = row.get(4)?;
+            Ok(json!({
+                "oldRaw": old_raw,
+                "oldStatus": old_status,
+                "newRaw": new_raw,
+                "newStatus": new_status,
+                "changedAt": changed_at
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>());
+
+    match rows {
+        Ok(history) => ok(&req.id, json!({ "history": history })),
+        Err(e) => err(&req.id, "db_query_failed", e.to_string(), None),
+    }
+}
+
+fn handle_scores_clear_column(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let assessment_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing assessmentId", None),
+    };
+
+    match assessment_in_class(conn, &class_id, &assessment_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "assessment not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    match clear_scores(
+        conn,
+        &class_id,
+        "scores.clearColumn",
+        "assessment_id = ?1",
+        &[&assessment_id],
+    ) {
+        Ok(cleared) => ok(&req.id, json!({ "cleared": cleared })),
+        Err(e) => e.response(&req.id),
+    }
+}
+
+fn handle_scores_clear_row(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+
+    let mark_set_found: Option<i64> = match conn
+        .query_row(
+            "SELECT 1 FROM mark_sets WHERE id = ? AND class_id = ?",
+            (&mark_set_id, &class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if mark_set_found.is_none() {
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+    match student_in_class(conn, &class_id, &student_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "student not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    match clear_scores(
+        conn,
+        &class_id,
+        "scores.clearRow",
+        "student_id = ?1 AND assessment_id IN (SELECT id FROM assessments WHERE mark_set_id = ?2)",
+        &[&student_id, &mark_set_id],
+    ) {
+        Ok(cleared) => ok(&req.id, json!({ "cleared": cleared })),
+        Err(e) => e.response(&req.id),
+    }
+}
+
+/// Rewrites the legacy-style stored `avg_percent`/`avg_raw` on each assessment
+/// from the current scores, using the same per-assessment stats as the summary
+/// (no_mark excluded, zero counted). Only rows whose values change are counted.
+fn handle_scores_recalc_averages(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => match v.as_str() {
+            Some(s) => Some(s.to_string()),
+            None => return err(&req.id, "bad_params", "markSetId must be a string", None),
+        },
+    };
+
+    let class_found: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if class_found.is_none() {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    let mut stmt = match conn.prepare(
+        "SELECT id FROM mark_sets
+         WHERE class_id = ?1 AND deleted_at IS NULL AND (?2 IS NULL OR id = ?2)
+         ORDER BY sort_order",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let mark_set_ids = match stmt
+        .query_map((&class_id, &mark_set_id), |r| r.get::<_, String>(0))
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if mark_set_id.is_some() && mark_set_ids.is_empty() {
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+
+    let mut stats = Vec::new();
+    for ms_id in &mark_set_ids {
+        let ctx = calc::CalcContext {
+            conn,
+            class_id: &class_id,
+            mark_set_id: ms_id,
+        };
+        match calc::compute_assessment_stats(&ctx, &calc::SummaryFilters::default()) {
+            Ok(v) => stats.extend(v),
+            Err(e) => return err(&req.id, &e.code, e.message, e.details),
+        }
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    let mut updated = 0usize;
+    for a in &stats {
+        match tx.execute(
+            "UPDATE assessments SET avg_percent = ?2, avg_raw = ?3
+             WHERE id = ?1 AND (avg_percent IS NOT ?2 OR avg_raw IS NOT ?3)",
+            (&a.assessment_id, a.avg_percent, a.avg_raw),
+        ) {
+            Ok(n) => updated += n,
+            Err(e) => {
+                let _ = tx.rollback();
+                return err(
+                    &req.id,
+                    "db_update_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "assessments" })),
+                );
+            }
+        }
+    }
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "updated": updated }))
+}
+
+pub const METHODS: &[&str] = &[
+    "grid.get",
+    "grid.updateCell",
+    "grid.setState",
+    "grid.bulkUpdate",
+    "grid.getScores",
+    "scores.setCell",
+    "scores.bulkSet",
+    "scores.clearColumn",
+    "scores.clearRow",
+    "scores.history",
+    "scores.recalcAverages",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "grid.get" => Some(handle_grid_get(state, req)),
         "grid.updateCell" => Some(handle_grid_update_cell(state, req)),
         "grid.setState" => Some(handle_grid_set_state(state, req)),
         "grid.bulkUpdate" => Some(handle_grid_bulk_update(state, req)),
+        "grid.getScores" => Some(handle_grid_get_scores(state, req)),
+        "scores.setCell" => Some(handle_scores_set_cell(state, req)),
+        "scores.bulkSet" => Some(handle_scores_bulk_set(state, req)),
+        "scores.clearColumn" => Some(handle_scores_clear_column(state, req)),
+        "scores.clearRow" => Some(handle_scores_clear_row(state, req)),
+        "scores.history" => Some(handle_scores_history(state, req)),
+        "scores.recalcAverages" => Some(handle_scores_recalc_averages(state, req)),
         _ => None,
     }
 }
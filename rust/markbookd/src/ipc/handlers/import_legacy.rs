@@ -1,3 +1,4 @@
+use crate::calc;
 use crate::ipc::handlers::classes as classes_handler;
 use crate::ipc::types::{AppState, Request};
 use crate::legacy;
@@ -89,6 +90,194 @@ fn cleanup_temp_class(state: &mut AppState, temp_class_id: &str) {
     let _ = classes_handler::try_handle(state, &cleanup_req);
 }
 
+const COMMENT_REMARK_UPSERT_SQL: &str =
+    "INSERT INTO comment_set_remarks(id, comment_set_index_id, student_id, remark)
+     VALUES(?, ?, ?, ?)
+     ON CONFLICT(comment_set_index_id, student_id) DO UPDATE SET
+       remark = excluded.remark";
+
+fn db_write_err(code: &str, table: &str, e: rusqlite::Error) -> ErrObj {
+    ErrObj {
+        code: code.into(),
+        message: e.to_string(),
+        details: Some(json!({ "table": table })),
+    }
+}
+
+fn legacy_parse_err(file_key: &str, path: &Path, e: anyhow::Error) -> ErrObj {
+    ErrObj {
+        code: "legacy_parse_failed".into(),
+        message: e.to_string(),
+        details: Some(json!({ file_key: path.to_string_lossy() })),
+    }
+}
+
+// The helpers below keep their prepared statements to themselves: a statement
+// borrows the transaction, so the caller can only roll back once it is gone.
+
+/// Upserts `*NOTE.TXT` notes by roster position.
+fn insert_student_notes(
+    conn: &Connection,
+    class_id: &str,
+    student_ids_by_sort: &[String],
+    notes: &[String],
+) -> Result<(), ErrObj> {
+    let mut ins = conn
+        .prepare(
+            "INSERT INTO student_notes(id, class_id, student_id, note)
+             VALUES(?, ?, ?, ?)
+             ON CONFLICT(class_id, student_id) DO UPDATE SET
+               note = excluded.note",
+        )
+        .map_err(|e| db_write_err("db_insert_failed", "student_notes", e))?;
+    let max = std::cmp::min(notes.len(), student_ids_by_sort.len());
+    for s_idx in 0..max {
+        let note = notes[s_idx].trim().to_string();
+        if note.is_empty() {
+            continue;
+        }
+        let nid = Uuid::new_v4().to_string();
+        let student_id = &student_ids_by_sort[s_idx];
+        ins.execute((&nid, class_id, student_id, &note))
+            .map_err(|e| db_write_err("db_insert_failed", "student_notes", e))?;
+    }
+    Ok(())
+}
+
+/// Inserts a mark file's scores with legacy mark-state parity:
+/// - raw == 0  => no_mark (excluded, displays blank)
+/// - raw < 0   => zero (counts as 0, displays 0)
+/// - raw > 0   => scored
+fn insert_mark_scores(
+    conn: &Connection,
+    parsed_mark: &legacy::ParsedMarkFile,
+    assessment_ids_by_idx: &[String],
+    student_ids_by_sort: &[String],
+) -> Result<usize, ErrObj> {
+    let mut ins_score = conn
+        .prepare(
+            "INSERT INTO scores(id, assessment_id, student_id, raw_value, status) VALUES(?, ?, ?, ?, ?)",
+        )
+        .map_err(|e| db_write_err("db_insert_failed", "scores", e))?;
+    let mut inserted = 0usize;
+    for (a_idx, a) in parsed_mark.assessments.iter().enumerate() {
+        let assessment_id = &assessment_ids_by_idx[a_idx];
+        let max_students = std::cmp::min(student_ids_by_sort.len(), parsed_mark.last_student);
+        for s_idx in 0..max_students {
+            let student_id = &student_ids_by_sort[s_idx];
+            let (raw_value, status) = match a.raw_scores[s_idx] {
+                legacy::LegacyScore::NoMark => (Some(0.0), "no_mark"),
+                legacy::LegacyScore::Zero => (None, "zero"),
+                legacy::LegacyScore::Scored(v) => (Some(v), "scored"),
+            };
+            let sid = Uuid::new_v4().to_string();
+            ins_score
+                .execute((&sid, assessment_id, student_id, raw_value, status))
+                .map_err(|e| db_write_err("db_insert_failed", "scores", e))?;
+            inserted += 1;
+        }
+    }
+    Ok(inserted)
+}
+
+/// `.TYP` companion (assessment types by entry index); a missing file is skipped.
+fn apply_typ_companion(
+    conn: &Connection,
+    typ_file: &Path,
+    assessment_ids_by_idx: &[String],
+) -> Result<(), ErrObj> {
+    if !typ_file.is_file() {
+        return Ok(());
+    }
+    let types = legacy::parse_legacy_typ_file(typ_file)
+        .map_err(|e| legacy_parse_err("typFile", typ_file, e))?;
+    let max = std::cmp::min(types.len(), assessment_ids_by_idx.len());
+    let mut up = conn
+        .prepare("UPDATE assessments SET legacy_type = ? WHERE id = ?")
+        .map_err(|e| db_write_err("db_update_failed", "assessments", e))?;
+    for i in 0..max {
+        up.execute((types[i] as i64, &assessment_ids_by_idx[i]))
+            .map_err(|e| db_write_err("db_update_failed", "assessments", e))?;
+    }
+    Ok(())
+}
+
+/// Best-effort `.RMK` companion (per-score remarks); a missing file is skipped.
+fn apply_rmk_companion(
+    conn: &Connection,
+    rmk_file: &Path,
+    encoding: legacy::LegacyEncoding,
+    assessment_ids_by_idx: &[String],
+    student_ids_by_sort: &[String],
+) -> Result<(), ErrObj> {
+    if !rmk_file.is_file() {
+        return Ok(());
+    }
+    let rmk = legacy::parse_legacy_rmk_file(rmk_file, encoding)
+        .map_err(|e| legacy_parse_err("rmkFile", rmk_file, e))?;
+    let max_entries = std::cmp::min(rmk.remarks_by_entry.len(), assessment_ids_by_idx.len());
+    let max_students = std::cmp::min(student_ids_by_sort.len(), rmk.last_student);
+    let mut up = conn
+        .prepare("UPDATE scores SET remark = ? WHERE assessment_id = ? AND student_id = ?")
+        .map_err(|e| db_write_err("db_update_failed", "scores", e))?;
+    for a_idx in 0..max_entries {
+        let assessment_id = &assessment_ids_by_idx[a_idx];
+        let remarks = &rmk.remarks_by_entry[a_idx];
+        for s_idx in 0..max_students {
+            let remark = remarks.get(s_idx).cloned().unwrap_or_default();
+            let remark = remark.trim().to_string();
+            if remark.is_empty() {
+                continue;
+            }
+            let student_id = &student_ids_by_sort[s_idx];
+            up.execute((&remark, assessment_id, student_id))
+                .map_err(|e| db_write_err("db_update_failed", "scores", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Upserts an `R<n>` comment file into comment set `csi_id`; returns the number
+/// of remarks written.
+fn import_r_comment_file(
+    conn: &Connection,
+    r_file: &Path,
+    encoding: legacy::LegacyEncoding,
+    csi_id: &str,
+    student_ids_by_sort: &[String],
+) -> Result<usize, ErrObj> {
+    let parsed_r = legacy::parse_legacy_r_comment_file(r_file, encoding)
+        .map_err(|e| legacy_parse_err("remarkFile", r_file, e))?;
+    let max_students = std::cmp::min(student_ids_by_sort.len(), parsed_r.remarks.len());
+    let mut ins_remark = conn
+        .prepare(COMMENT_REMARK_UPSERT_SQL)
+        .map_err(|e| db_write_err("db_insert_failed", "comment_set_remarks", e))?;
+    let mut written = 0usize;
+    for s_idx in 0..max_students {
+        let remark = parsed_r.remarks[s_idx].trim().to_string();
+        if remark.is_empty() {
+            continue;
+        }
+        let rid = Uuid::new_v4().to_string();
+        let student_id = &student_ids_by_sort[s_idx];
+        ins_remark
+            .execute((&rid, csi_id, student_id, &remark))
+            .map_err(|e| db_write_err("db_insert_failed", "comment_set_remarks", e))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Sections accepted by `class.importLegacy`'s `include` param, in import order.
+const LEGACY_IMPORT_SECTIONS: [&str; 6] = [
+    "students",
+    "marks",
+    "attendance",
+    "seating",
+    "comments",
+    "loaned",
+];
+
 fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return json!(ErrResp {
@@ -120,6 +309,111 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
         });
     };
 
+    let encoding = match req.params.get("encoding") {
+        None => legacy::LegacyEncoding::Auto,
+        Some(v) if v.is_null() => legacy::LegacyEncoding::Auto,
+        Some(v) => match v.as_str().and_then(legacy::LegacyEncoding::parse) {
+            Some(enc) => enc,
+            None => {
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "bad_params".into(),
+                        message: "encoding must be one of: auto, utf8, cp437, cp850".into(),
+                        details: None
+                    }
+                })
+            }
+        },
+    };
+
+    // `include` gates each section below; omitting it imports everything. Without
+    // "students" the sections attach to an existing class, matched by sort order.
+    let sections: Vec<&'static str> = match req.params.get("include") {
+        None => LEGACY_IMPORT_SECTIONS.to_vec(),
+        Some(v) if v.is_null() => LEGACY_IMPORT_SECTIONS.to_vec(),
+        Some(v) => {
+            let requested: Option<Vec<&str>> = v
+                .as_array()
+                .and_then(|arr| arr.iter().map(|x| x.as_str()).collect());
+            match requested {
+                Some(list)
+                    if !list.is_empty()
+                        && list.iter().all(|s| LEGACY_IMPORT_SECTIONS.contains(s)) =>
+                {
+                    LEGACY_IMPORT_SECTIONS
+                        .iter()
+                        .copied()
+                        .filter(|s| list.contains(s))
+                        .collect()
+                }
+                _ => {
+                    return json!(ErrResp {
+                        id: req.id,
+                        ok: false,
+                        error: ErrObj {
+                            code: "bad_params".into(),
+                            message: format!(
+                                "include must be a non-empty array of: {}",
+                                LEGACY_IMPORT_SECTIONS.join(", ")
+                            ),
+                            details: None
+                        }
+                    })
+                }
+            }
+        }
+    };
+    let runs = |section: &str| sections.contains(&section);
+
+    let attach_to: Option<(String, String)> = if runs("students") {
+        None
+    } else {
+        let Some(class_id) = req.params.get("classId").and_then(|v| v.as_str()) else {
+            return json!(ErrResp {
+                id: req.id,
+                ok: false,
+                error: ErrObj {
+                    code: "bad_params".into(),
+                    message: "classId is required when students is not included".into(),
+                    details: None
+                }
+            });
+        };
+        match conn
+            .query_row("SELECT name FROM classes WHERE id = ?", [class_id], |r| {
+                r.get::<_, String>(0)
+            })
+            .optional()
+        {
+            Ok(Some(name)) => Some((class_id.to_string(), name)),
+            Ok(None) => {
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "not_found".into(),
+                        message: "class not found".into(),
+                        details: None
+                    }
+                })
+            }
+            Err(e) => {
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "db_query_failed".into(),
+                        message: e.to_string(),
+                        details: None
+                    }
+                })
+            }
+        }
+    };
+    let attaching = attach_to.is_some();
+
     let cl_file = match legacy::find_cl_file(&legacy_folder) {
         Ok(v) => v,
         Err(e) => {
@@ -135,7 +429,7 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
         }
     };
 
-    let parsed = match legacy::parse_legacy_cl(&cl_file) {
+    let parsed = match legacy::parse_legacy_cl(&cl_file, encoding) {
         Ok(v) => v,
         Err(e) => {
             return json!(ErrResp {
@@ -150,8 +444,8 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
         }
     };
 
-    let class_id = Uuid::new_v4().to_string();
-    let class_name = parsed.class_name;
+    let (class_id, class_name) =
+        attach_to.unwrap_or_else(|| (Uuid::new_v4().to_string(), parsed.class_name));
 
     let tx = match conn.unchecked_transaction() {
         Ok(t) => t,
@@ -168,140 +462,95 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
         }
     };
 
-    if let Err(e) = tx.execute(
-        "INSERT INTO classes(id, name) VALUES(?, ?)",
-        [&class_id, &class_name],
-    ) {
-        let _ = tx.rollback();
-        return json!(ErrResp {
-            id: req.id,
-            ok: false,
-            error: ErrObj {
-                code: "db_insert_failed".into(),
-                message: e.to_string(),
-                details: None
-            }
-        });
-    }
-
     let mut imported = 0usize;
     let mut student_ids_by_sort: Vec<String> = Vec::new();
-    for (sort_order, s) in parsed.students.into_iter().enumerate() {
-        let sid = Uuid::new_v4().to_string();
-        let active_i = if s.active { 1 } else { 0 };
-        let student_no = s.student_no.unwrap_or_default();
-        let birth_date = s.birth_date.unwrap_or_default();
-        let mark_set_mask = s.mark_set_mask.unwrap_or_else(|| "TBA".into());
-        let res = tx.execute(
-            "INSERT INTO students(id, class_id, last_name, first_name, student_no, birth_date, active, sort_order, raw_line, mark_set_mask)
-             VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            (
-                &sid,
-                &class_id,
-                &s.last_name,
-                &s.first_name,
-                &student_no,
-                &birth_date,
-                active_i,
-                sort_order as i64,
-                &s.raw_line,
-                &mark_set_mask,
-            ),
-        );
-        if res.is_ok() {
-            imported += 1;
-            student_ids_by_sort.push(sid);
-        }
-    }
-
-    // Best-effort import class-level student notes (*NOTE.TXT).
-    if let Some(note_file) = match legacy::find_note_file(&legacy_folder) {
-        Ok(v) => v,
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
-                }
+    if attaching {
+        let ids = tx
+            .prepare("SELECT id FROM students WHERE class_id = ? ORDER BY sort_order")
+            .and_then(|mut stmt| {
+                stmt.query_map([&class_id], |r| r.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()
             });
-        }
-    } {
-        let notes = match legacy::parse_legacy_note_file(&note_file) {
-            Ok(v) => v,
+        match ids {
+            Ok(ids) => student_ids_by_sort = ids,
             Err(e) => {
                 let _ = tx.rollback();
                 return json!(ErrResp {
                     id: req.id,
                     ok: false,
                     error: ErrObj {
-                        code: "legacy_parse_failed".into(),
+                        code: "db_query_failed".into(),
                         message: e.to_string(),
-                        details: Some(json!({ "noteFile": note_file.to_string_lossy() }))
+                        details: Some(json!({ "table": "students" }))
                     }
                 });
             }
-        };
-
-        let mut ins = match tx.prepare(
-            "INSERT INTO student_notes(id, class_id, student_id, note)
-             VALUES(?, ?, ?, ?)
-             ON CONFLICT(class_id, student_id) DO UPDATE SET
-               note = excluded.note",
+        }
+    } else {
+        if let Err(e) = tx.execute(
+            "INSERT INTO classes(id, name, sort_order)
+             VALUES(?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM classes))",
+            [&class_id, &class_name],
         ) {
-            Ok(s) => s,
-            Err(e) => {
-                return json!(ErrResp {
-                    id: req.id,
-                    ok: false,
-                    error: ErrObj {
-                        code: "db_insert_failed".into(),
-                        message: e.to_string(),
-                        details: Some(json!({ "table": "student_notes" }))
-                    }
-                });
-            }
-        };
+            let _ = tx.rollback();
+            return json!(ErrResp {
+                id: req.id,
+                ok: false,
+                error: ErrObj {
+                    code: "db_insert_failed".into(),
+                    message: e.to_string(),
+                    details: None
+                }
+            });
+        }
 
-        let max = std::cmp::min(notes.len(), student_ids_by_sort.len());
-        for s_idx in 0..max {
-            let note = notes[s_idx].trim().to_string();
-            if note.is_empty() {
-                continue;
+        for (sort_order, s) in parsed.students.into_iter().enumerate() {
+            let sid = Uuid::new_v4().to_string();
+            let active_i = if s.active { 1 } else { 0 };
+            let student_no = s.student_no.unwrap_or_default();
+            let birth_date = s.birth_date.unwrap_or_default();
+            let mark_set_mask = s.mark_set_mask.unwrap_or_else(|| "TBA".into());
+            let res = tx.execute(
+                "INSERT INTO students(id, class_id, last_name, first_name, student_no, birth_date, active, sort_order, raw_line, mark_set_mask)
+                 VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (
+                    &sid,
+                    &class_id,
+                    &s.last_name,
+                    &s.first_name,
+                    &student_no,
+                    &birth_date,
+                    active_i,
+                    sort_order as i64,
+                    &s.raw_line,
+                    &mark_set_mask,
+                ),
+            );
+            if res.is_ok() {
+                imported += 1;
+                student_ids_by_sort.push(sid);
             }
-            let nid = Uuid::new_v4().to_string();
-            let student_id = &student_ids_by_sort[s_idx];
-            if let Err(e) = ins.execute((&nid, &class_id, student_id, &note)) {
+        }
+    }
+
+    // Best-effort import class-level student notes (*NOTE.TXT).
+    if runs("students") {
+        if let Some(note_file) = match legacy::find_note_file(&legacy_folder) {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = tx.rollback();
                 return json!(ErrResp {
                     id: req.id,
                     ok: false,
                     error: ErrObj {
-                        code: "db_insert_failed".into(),
+                        code: "legacy_read_failed".into(),
                         message: e.to_string(),
-                        details: Some(json!({ "table": "student_notes" }))
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
                     }
                 });
             }
-        }
-    }
-
-    let mut attendance_imported = false;
-    let mut seating_imported = false;
-    let mut banks_imported = 0usize;
-    let mut comment_sets_imported = 0usize;
-    let mut comment_remarks_imported = 0usize;
-    let mut loaned_items_imported = 0usize;
-    let mut device_mappings_imported = 0usize;
-    let mut combined_comment_sets_imported = 0usize;
-    let mut warnings: Vec<serde_json::Value> = Vec::new();
-
-    // Best-effort attendance import (.ATN).
-    match legacy::find_attendance_file(&legacy_folder) {
-        Ok(Some(att_file)) => {
-            let att = match legacy::parse_legacy_attendance_file(&att_file) {
+        } {
+            let notes = match legacy::parse_legacy_note_file(&note_file, encoding) {
                 Ok(v) => v,
                 Err(e) => {
                     let _ = tx.rollback();
@@ -311,38 +560,61 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                         error: ErrObj {
                             code: "legacy_parse_failed".into(),
                             message: e.to_string(),
-                            details: Some(json!({ "attendanceFile": att_file.to_string_lossy() }))
+                            details: Some(json!({ "noteFile": note_file.to_string_lossy() }))
                         }
                     });
                 }
             };
 
-            if let Err(e) = tx.execute(
-                "INSERT INTO attendance_settings(class_id, school_year_start_month)
-                 VALUES(?, ?)
-                 ON CONFLICT(class_id) DO UPDATE SET
-                   school_year_start_month = excluded.school_year_start_month",
-                (&class_id, att.school_year_start_month as i64),
-            ) {
+            if let Err(e) = insert_student_notes(&tx, &class_id, &student_ids_by_sort, &notes) {
                 let _ = tx.rollback();
                 return json!(ErrResp {
                     id: req.id,
                     ok: false,
-                    error: ErrObj {
-                        code: "db_insert_failed".into(),
-                        message: e.to_string(),
-                        details: Some(json!({ "table": "attendance_settings" }))
-                    }
+                    error: e
                 });
             }
+        }
+    }
+
+    let mut attendance_imported = false;
+    let mut seating_imported = false;
+    let mut banks_imported = 0usize;
+    let mut comment_sets_imported = 0usize;
+    let mut comment_remarks_imported = 0usize;
+    let mut loaned_items_imported = 0usize;
+    let mut device_mappings_imported = 0usize;
+    let mut combined_comment_sets_imported = 0usize;
+    let mut warnings: Vec<serde_json::Value> = Vec::new();
+
+    // Best-effort attendance import (.ATN).
+    if runs("attendance") {
+        match legacy::find_attendance_file(&legacy_folder) {
+            Ok(Some(att_file)) => {
+                let att = match legacy::parse_legacy_attendance_file(&att_file) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        let _ = tx.rollback();
+                        return json!(ErrResp {
+                            id: req.id,
+                            ok: false,
+                            error: ErrObj {
+                                code: "legacy_parse_failed".into(),
+                                message: e.to_string(),
+                                details: Some(
+                                    json!({ "attendanceFile": att_file.to_string_lossy() })
+                                )
+                            }
+                        });
+                    }
+                };
 
-            for m in &att.months {
                 if let Err(e) = tx.execute(
-                    "INSERT INTO attendance_months(class_id, month, type_of_day_codes)
-                     VALUES(?, ?, ?)
-                     ON CONFLICT(class_id, month) DO UPDATE SET
-                       type_of_day_codes = excluded.type_of_day_codes",
-                    (&class_id, m.month as i64, &m.type_of_day_codes),
+                    "INSERT INTO attendance_settings(class_id, school_year_start_month)
+                     VALUES(?, ?)
+                     ON CONFLICT(class_id) DO UPDATE SET
+                       school_year_start_month = excluded.school_year_start_month",
+                    (&class_id, att.school_year_start_month as i64),
                 ) {
                     let _ = tx.rollback();
                     return json!(ErrResp {
@@ -351,22 +623,18 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                         error: ErrObj {
                             code: "db_insert_failed".into(),
                             message: e.to_string(),
-                            details: Some(json!({ "table": "attendance_months" }))
+                            details: Some(json!({ "table": "attendance_settings" }))
                         }
                     });
                 }
 
-                let max_students =
-                    std::cmp::min(student_ids_by_sort.len(), m.student_day_codes.len());
-                for s_idx in 0..max_students {
-                    let student_id = &student_ids_by_sort[s_idx];
-                    let day_codes = &m.student_day_codes[s_idx];
+                for m in &att.months {
                     if let Err(e) = tx.execute(
-                        "INSERT INTO attendance_student_months(class_id, student_id, month, day_codes)
-                         VALUES(?, ?, ?, ?)
-                         ON CONFLICT(class_id, student_id, month) DO UPDATE SET
-                           day_codes = excluded.day_codes",
-                        (&class_id, student_id, m.month as i64, day_codes),
+                        "INSERT INTO attendance_months(class_id, month, type_of_day_codes)
+                         VALUES(?, ?, ?)
+                         ON CONFLICT(class_id, month) DO UPDATE SET
+                           type_of_day_codes = excluded.type_of_day_codes",
+                        (&class_id, m.month as i64, &m.type_of_day_codes),
                     ) {
                         let _ = tx.rollback();
                         return json!(ErrResp {
@@ -375,231 +643,202 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                             error: ErrObj {
                                 code: "db_insert_failed".into(),
                                 message: e.to_string(),
-                                details: Some(json!({ "table": "attendance_student_months" }))
+                                details: Some(json!({ "table": "attendance_months" }))
                             }
                         });
                     }
-                }
-            }
 
-            attendance_imported = true;
-        }
-        Ok(None) => {
-            warnings.push(json!({
-                "code": "legacy_missing_attendance_file",
-                "folder": legacy_folder.to_string_lossy()
-            }));
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
-                }
-            });
-        }
-    }
-
-    // Best-effort seating import (.SPL).
-    match legacy::find_seating_file(&legacy_folder) {
-        Ok(Some(spl_file)) => {
-            let spl = match legacy::parse_legacy_seating_file(&spl_file) {
-                Ok(v) => v,
-                Err(e) => {
-                    let _ = tx.rollback();
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "legacy_parse_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "seatingFile": spl_file.to_string_lossy() }))
+                    let max_students =
+                        std::cmp::min(student_ids_by_sort.len(), m.student_day_codes.len());
+                    for s_idx in 0..max_students {
+                        let student_id = &student_ids_by_sort[s_idx];
+                        let day_codes = &m.student_day_codes[s_idx];
+                        if let Err(e) = tx.execute(
+                            "INSERT INTO attendance_student_months(class_id, student_id, month, day_codes)
+                             VALUES(?, ?, ?, ?)
+                             ON CONFLICT(class_id, student_id, month) DO UPDATE SET
+                               day_codes = excluded.day_codes",
+                            (&class_id, student_id, m.month as i64, day_codes),
+                        ) {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: ErrObj {
+                                    code: "db_insert_failed".into(),
+                                    message: e.to_string(),
+                                    details: Some(json!({ "table": "attendance_student_months" }))
+                                }
+                            });
                         }
-                    });
+                    }
                 }
-            };
 
-            if let Err(e) = tx.execute(
-                "INSERT INTO seating_plans(class_id, rows, seats_per_row, blocked_mask)
-                 VALUES(?, ?, ?, ?)
-                 ON CONFLICT(class_id) DO UPDATE SET
-                   rows = excluded.rows,
-                   seats_per_row = excluded.seats_per_row,
-                   blocked_mask = excluded.blocked_mask",
-                (
-                    &class_id,
-                    spl.rows as i64,
-                    spl.seats_per_row as i64,
-                    &spl.blocked_mask,
-                ),
-            ) {
-                let _ = tx.rollback();
-                return json!(ErrResp {
-                    id: req.id,
-                    ok: false,
-                    error: ErrObj {
-                        code: "db_insert_failed".into(),
-                        message: e.to_string(),
-                        details: Some(json!({ "table": "seating_plans" }))
-                    }
-                });
+                attendance_imported = true;
             }
-            if let Err(e) = tx.execute(
-                "DELETE FROM seating_assignments WHERE class_id = ?",
-                [&class_id],
-            ) {
+            Ok(None) => {
+                warnings.push(json!({
+                    "code": "legacy_missing_attendance_file",
+                    "folder": legacy_folder.to_string_lossy()
+                }));
+            }
+            Err(e) => {
                 let _ = tx.rollback();
                 return json!(ErrResp {
                     id: req.id,
                     ok: false,
                     error: ErrObj {
-                        code: "db_delete_failed".into(),
+                        code: "legacy_read_failed".into(),
                         message: e.to_string(),
-                        details: Some(json!({ "table": "seating_assignments" }))
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
                     }
                 });
             }
-            let max_students = std::cmp::min(student_ids_by_sort.len(), spl.seat_codes.len());
-            for s_idx in 0..max_students {
-                let seat_code = spl.seat_codes[s_idx];
-                if seat_code <= 0 {
-                    continue;
-                }
-                let student_id = &student_ids_by_sort[s_idx];
-                if let Err(e) = tx.execute(
-                    "INSERT INTO seating_assignments(class_id, student_id, seat_code)
-                     VALUES(?, ?, ?)",
-                    (&class_id, student_id, seat_code as i64),
-                ) {
-                    let _ = tx.rollback();
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_insert_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "seating_assignments" }))
-                        }
-                    });
-                }
-            }
-            seating_imported = true;
-        }
-        Ok(None) => {
-            warnings.push(json!({
-                "code": "legacy_missing_seating_file",
-                "folder": legacy_folder.to_string_lossy()
-            }));
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
-                }
-            });
         }
     }
 
-    // Best-effort ICC import (device/class codes matrix).
-    match legacy::find_icc_file(&legacy_folder) {
-        Ok(Some(icc_file)) => {
-            let icc = match legacy::parse_legacy_icc_file(&icc_file) {
-                Ok(v) => v,
-                Err(e) => {
+    // Best-effort seating import (.SPL).
+    if runs("seating") {
+        match legacy::find_seating_file(&legacy_folder) {
+            Ok(Some(spl_file)) => {
+                let spl = match legacy::parse_legacy_seating_file(&spl_file) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        let _ = tx.rollback();
+                        return json!(ErrResp {
+                            id: req.id,
+                            ok: false,
+                            error: ErrObj {
+                                code: "legacy_parse_failed".into(),
+                                message: e.to_string(),
+                                details: Some(json!({ "seatingFile": spl_file.to_string_lossy() }))
+                            }
+                        });
+                    }
+                };
+
+                // Re-running seating on an existing class refreshes its default plan.
+                let existing_plan_id = if attaching {
+                    match tx
+                        .query_row(
+                            "SELECT id FROM seating_plans WHERE class_id = ? AND name = 'Default'",
+                            [&class_id],
+                            |r| r.get::<_, String>(0),
+                        )
+                        .optional()
+                    {
+                        Ok(v) => v,
+                        Err(e) => {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: ErrObj {
+                                    code: "db_query_failed".into(),
+                                    message: e.to_string(),
+                                    details: Some(json!({ "table": "seating_plans" }))
+                                }
+                            });
+                        }
+                    }
+                } else {
+                    None
+                };
+                let seating_plan_id =
+                    existing_plan_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+                if let Err(e) = tx.execute(
+                    "INSERT INTO seating_plans(id, class_id, name, is_default, rows, seats_per_row, blocked_mask)
+                     VALUES(?, ?, 'Default', 1, ?, ?, ?)
+                     ON CONFLICT(id) DO UPDATE SET
+                       rows = excluded.rows,
+                       seats_per_row = excluded.seats_per_row,
+                       blocked_mask = excluded.blocked_mask",
+                    (
+                        &seating_plan_id,
+                        &class_id,
+                        spl.rows as i64,
+                        spl.seats_per_row as i64,
+                        &spl.blocked_mask,
+                    ),
+                ) {
                     let _ = tx.rollback();
                     return json!(ErrResp {
                         id: req.id,
                         ok: false,
                         error: ErrObj {
-                            code: "legacy_parse_failed".into(),
+                            code: "db_insert_failed".into(),
                             message: e.to_string(),
-                            details: Some(json!({ "iccFile": icc_file.to_string_lossy() }))
+                            details: Some(json!({ "table": "seating_plans" }))
                         }
                     });
                 }
-            };
-
-            let max_students = std::cmp::min(student_ids_by_sort.len(), icc.last_student);
-            for s_idx in 0..max_students {
-                let student_id = &student_ids_by_sort[s_idx];
-                let codes_row = icc
-                    .codes
-                    .get(s_idx + 1)
-                    .cloned()
-                    .unwrap_or_else(|| vec![String::new(); icc.subject_count + 1]);
-                let primary_code = codes_row
-                    .iter()
-                    .skip(1)
-                    .map(|s| s.trim())
-                    .find(|s| !s.is_empty())
-                    .unwrap_or("")
-                    .to_string();
-                let raw_line = serde_json::to_string(&json!({
-                    "subjectCount": icc.subject_count,
-                    "codes": codes_row
-                }))
-                .unwrap_or_else(|_| "[]".to_string());
-                let did = Uuid::new_v4().to_string();
                 if let Err(e) = tx.execute(
-                    "INSERT INTO student_device_map(id, class_id, student_id, device_code, raw_line)
-                     VALUES(?, ?, ?, ?, ?)
-                     ON CONFLICT(class_id, student_id) DO UPDATE SET
-                       device_code = excluded.device_code,
-                       raw_line = excluded.raw_line",
-                    (&did, &class_id, student_id, &primary_code, &raw_line),
+                    "DELETE FROM seating_assignments WHERE plan_id = ?",
+                    [&seating_plan_id],
                 ) {
                     let _ = tx.rollback();
                     return json!(ErrResp {
                         id: req.id,
                         ok: false,
                         error: ErrObj {
-                            code: "db_insert_failed".into(),
+                            code: "db_delete_failed".into(),
                             message: e.to_string(),
-                            details: Some(json!({ "table": "student_device_map" }))
+                            details: Some(json!({ "table": "seating_assignments" }))
                         }
                     });
                 }
-                device_mappings_imported += 1;
-            }
-        }
-        Ok(None) => {
-            warnings.push(json!({
-                "code": "legacy_missing_icc_file",
-                "folder": legacy_folder.to_string_lossy()
-            }));
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
+                let max_students = std::cmp::min(student_ids_by_sort.len(), spl.seat_codes.len());
+                for s_idx in 0..max_students {
+                    let seat_code = spl.seat_codes[s_idx];
+                    if seat_code <= 0 {
+                        continue;
+                    }
+                    let student_id = &student_ids_by_sort[s_idx];
+                    if let Err(e) = tx.execute(
+                        "INSERT INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+                         VALUES(?, ?, ?, ?)",
+                        (&class_id, &seating_plan_id, student_id, seat_code as i64),
+                    ) {
+                        let _ = tx.rollback();
+                        return json!(ErrResp {
+                            id: req.id,
+                            ok: false,
+                            error: ErrObj {
+                                code: "db_insert_failed".into(),
+                                message: e.to_string(),
+                                details: Some(json!({ "table": "seating_assignments" }))
+                            }
+                        });
+                    }
                 }
-            });
+                seating_imported = true;
+            }
+            Ok(None) => {
+                warnings.push(json!({
+                    "code": "legacy_missing_seating_file",
+                    "folder": legacy_folder.to_string_lossy()
+                }));
+            }
+            Err(e) => {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "legacy_read_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
+                    }
+                });
+            }
         }
     }
 
-    // Best-effort bank import from parent fixture folder.
-    let bnk_folder = legacy_folder
-        .parent()
-        .unwrap_or(&legacy_folder)
-        .to_path_buf();
-    match legacy::find_bnk_files(&bnk_folder) {
-        Ok(files) => {
-            for bnk_file in files {
-                let parsed_bnk = match legacy::parse_bnk_file(&bnk_file) {
+    // Best-effort ICC import (device/class codes matrix).
+    if runs("loaned") {
+        match legacy::find_icc_file(&legacy_folder) {
+            Ok(Some(icc_file)) => {
+                let icc = match legacy::parse_legacy_icc_file(&icc_file) {
                     Ok(v) => v,
                     Err(e) => {
                         let _ = tx.rollback();
@@ -609,122 +848,212 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                             error: ErrObj {
                                 code: "legacy_parse_failed".into(),
                                 message: e.to_string(),
-                                details: Some(json!({ "bnkFile": bnk_file.to_string_lossy() }))
+                                details: Some(json!({ "iccFile": icc_file.to_string_lossy() }))
                             }
                         });
                     }
                 };
-                let short_name = bnk_file
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("")
-                    .to_string();
-                if short_name.is_empty() {
-                    continue;
-                }
-                let bank_id = Uuid::new_v4().to_string();
-                if let Err(e) = tx.execute(
-                    "INSERT INTO comment_banks(id, short_name, is_default, fit_profile, source_path)
-                     VALUES(?, ?, 0, ?, ?)
-                     ON CONFLICT(short_name) DO UPDATE SET
-                       fit_profile = excluded.fit_profile,
-                       source_path = excluded.source_path",
-                    (
-                        &bank_id,
-                        &short_name,
-                        parsed_bnk.fit_profile.as_deref(),
-                        bnk_file.to_string_lossy().as_ref(),
-                    ),
-                ) {
-                    let _ = tx.rollback();
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_insert_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "comment_banks" }))
-                        }
-                    });
+
+                let max_students = std::cmp::min(student_ids_by_sort.len(), icc.last_student);
+                for s_idx in 0..max_students {
+                    let student_id = &student_ids_by_sort[s_idx];
+                    let codes_row = icc
+                        .codes
+                        .get(s_idx + 1)
+                        .cloned()
+                        .unwrap_or_else(|| vec![String::new(); icc.subject_count + 1]);
+                    let primary_code = codes_row
+                        .iter()
+                        .skip(1)
+                        .map(|s| s.trim())
+                        .find(|s| !s.is_empty())
+                        .unwrap_or("")
+                        .to_string();
+                    let raw_line = serde_json::to_string(&json!({
+                        "subjectCount": icc.subject_count,
+                        "codes": codes_row
+                    }))
+                    .unwrap_or_else(|_| "[]".to_string());
+                    let did = Uuid::new_v4().to_string();
+                    if let Err(e) = tx.execute(
+                        "INSERT INTO student_device_map(id, class_id, student_id, device_code, raw_line)
+                         VALUES(?, ?, ?, ?, ?)
+                         ON CONFLICT(class_id, student_id) DO UPDATE SET
+                           device_code = excluded.device_code,
+                           raw_line = excluded.raw_line",
+                        (&did, &class_id, student_id, &primary_code, &raw_line),
+                    ) {
+                        let _ = tx.rollback();
+                        return json!(ErrResp {
+                            id: req.id,
+                            ok: false,
+                            error: ErrObj {
+                                code: "db_insert_failed".into(),
+                                message: e.to_string(),
+                                details: Some(json!({ "table": "student_device_map" }))
+                            }
+                        });
+                    }
+                    device_mappings_imported += 1;
                 }
+            }
+            Ok(None) => {
+                warnings.push(json!({
+                    "code": "legacy_missing_icc_file",
+                    "folder": legacy_folder.to_string_lossy()
+                }));
+            }
+            Err(e) => {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "legacy_read_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
+                    }
+                });
+            }
+        }
+    }
 
-                let resolved_bank_id: String = match tx.query_row(
-                    "SELECT id FROM comment_banks WHERE short_name = ?",
-                    [&short_name],
-                    |r| r.get(0),
-                ) {
-                    Ok(v) => v,
-                    Err(e) => {
+    // Best-effort bank import from parent fixture folder.
+    if runs("comments") {
+        let bnk_folder = legacy_folder
+            .parent()
+            .unwrap_or(&legacy_folder)
+            .to_path_buf();
+        match legacy::find_bnk_files(&bnk_folder) {
+            Ok(files) => {
+                for bnk_file in files {
+                    let parsed_bnk = match legacy::parse_bnk_file(&bnk_file, encoding) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: ErrObj {
+                                    code: "legacy_parse_failed".into(),
+                                    message: e.to_string(),
+                                    details: Some(json!({ "bnkFile": bnk_file.to_string_lossy() }))
+                                }
+                            });
+                        }
+                    };
+                    let short_name = bnk_file
+                        .file_name()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("")
+                        .to_string();
+                    if short_name.is_empty() {
+                        continue;
+                    }
+                    let bank_id = Uuid::new_v4().to_string();
+                    if let Err(e) = tx.execute(
+                        "INSERT INTO comment_banks(id, short_name, is_default, fit_profile, source_path)
+                         VALUES(?, ?, 0, ?, ?)
+                         ON CONFLICT(short_name) DO UPDATE SET
+                           fit_profile = excluded.fit_profile,
+                           source_path = excluded.source_path",
+                        (
+                            &bank_id,
+                            &short_name,
+                            parsed_bnk.fit_profile.as_deref(),
+                            bnk_file.to_string_lossy().as_ref(),
+                        ),
+                    ) {
                         let _ = tx.rollback();
                         return json!(ErrResp {
                             id: req.id,
                             ok: false,
                             error: ErrObj {
-                                code: "db_query_failed".into(),
+                                code: "db_insert_failed".into(),
                                 message: e.to_string(),
                                 details: Some(json!({ "table": "comment_banks" }))
                             }
                         });
                     }
-                };
 
-                if let Err(e) = tx.execute(
-                    "DELETE FROM comment_bank_entries WHERE bank_id = ?",
-                    [&resolved_bank_id],
-                ) {
-                    let _ = tx.rollback();
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_delete_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "comment_bank_entries" }))
+                    let resolved_bank_id: String = match tx.query_row(
+                        "SELECT id FROM comment_banks WHERE short_name = ?",
+                        [&short_name],
+                        |r| r.get(0),
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: ErrObj {
+                                    code: "db_query_failed".into(),
+                                    message: e.to_string(),
+                                    details: Some(json!({ "table": "comment_banks" }))
+                                }
+                            });
                         }
-                    });
-                }
+                    };
 
-                for (sort_order, entry) in parsed_bnk.entries.iter().enumerate() {
-                    let eid = Uuid::new_v4().to_string();
                     if let Err(e) = tx.execute(
-                        "INSERT INTO comment_bank_entries(id, bank_id, sort_order, type_code, level_code, text)
-                         VALUES(?, ?, ?, ?, ?, ?)",
-                        (
-                            &eid,
-                            &resolved_bank_id,
-                            sort_order as i64,
-                            &entry.type_code,
-                            &entry.level_code,
-                            &entry.text,
-                        ),
+                        "DELETE FROM comment_bank_entries WHERE bank_id = ?",
+                        [&resolved_bank_id],
                     ) {
                         let _ = tx.rollback();
                         return json!(ErrResp {
                             id: req.id,
                             ok: false,
                             error: ErrObj {
-                                code: "db_insert_failed".into(),
+                                code: "db_delete_failed".into(),
                                 message: e.to_string(),
                                 details: Some(json!({ "table": "comment_bank_entries" }))
                             }
                         });
                     }
-                }
 
-                banks_imported += 1;
-            }
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": bnk_folder.to_string_lossy() }))
+                    for (sort_order, entry) in parsed_bnk.entries.iter().enumerate() {
+                        let eid = Uuid::new_v4().to_string();
+                        if let Err(e) = tx.execute(
+                            "INSERT INTO comment_bank_entries(id, bank_id, sort_order, type_code, level_code, text)
+                             VALUES(?, ?, ?, ?, ?, ?)",
+                            (
+                                &eid,
+                                &resolved_bank_id,
+                                sort_order as i64,
+                                &entry.type_code,
+                                &entry.level_code,
+                                &entry.text,
+                            ),
+                        ) {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: ErrObj {
+                                    code: "db_insert_failed".into(),
+                                    message: e.to_string(),
+                                    details: Some(json!({ "table": "comment_bank_entries" }))
+                                }
+                            });
+                        }
+                    }
+
+                    banks_imported += 1;
                 }
-            });
+            }
+            Err(e) => {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "legacy_read_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "folder": bnk_folder.to_string_lossy() }))
+                    }
+                });
+            }
         }
     }
 
@@ -734,6 +1063,7 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
     let mut imported_mark_files: Vec<String> = Vec::new();
     let mut missing_mark_files: Vec<serde_json::Value> = Vec::new();
     let mut mark_set_id_by_source_stem: HashMap<String, String> = HashMap::new();
+    let mut comment_targets: Vec<(PathBuf, String)> = Vec::new();
 
     for def in &parsed.mark_sets {
         let mark_file = match legacy::find_mark_file(&legacy_folder, &def.file_prefix) {
@@ -759,6 +1089,51 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
             continue;
         };
 
+        // When attaching, sets that already exist are left as-is; other sections
+        // (comments, loaned items) still resolve to them by file stem.
+        if attaching {
+            let existing = match tx
+                .query_row(
+                    "SELECT id FROM mark_sets WHERE class_id = ? AND code = ? AND deleted_at IS NULL",
+                    (&class_id, &def.code),
+                    |r| r.get::<_, String>(0),
+                )
+                .optional()
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    let _ = tx.rollback();
+                    return json!(ErrResp {
+                        id: req.id,
+                        ok: false,
+                        error: ErrObj {
+                            code: "db_query_failed".into(),
+                            message: e.to_string(),
+                            details: Some(json!({ "table": "mark_sets" }))
+                        }
+                    });
+                }
+            };
+            if let Some(mark_set_id) = existing {
+                if let Some(stem) = mark_file.file_stem().and_then(|s| s.to_str()) {
+                    mark_set_id_by_source_stem
+                        .insert(stem.to_ascii_uppercase(), mark_set_id.clone());
+                }
+                if runs("marks") {
+                    warnings.push(json!({
+                        "code": "legacy_mark_set_exists",
+                        "markSetCode": def.code,
+                        "markFile": mark_file.to_string_lossy()
+                    }));
+                }
+                comment_targets.push((mark_file, mark_set_id));
+                continue;
+            }
+        }
+        if !runs("marks") {
+            continue;
+        }
+
         let parsed_mark = match legacy::parse_legacy_mark_file(&mark_file) {
             Ok(v) => v,
             Err(e) => {
@@ -892,320 +1267,60 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
             assessment_ids_by_idx.push(aid);
         }
 
-        // Insert scores with legacy mark-state parity:
-        // - raw == 0  => no_mark (excluded, displays blank)
-        // - raw < 0   => zero (counts as 0, displays 0)
-        // - raw > 0   => scored
-        for (a_idx, a) in parsed_mark.assessments.iter().enumerate() {
-            let assessment_id = &assessment_ids_by_idx[a_idx];
-            let max_students = std::cmp::min(student_ids_by_sort.len(), parsed_mark.last_student);
-            for s_idx in 0..max_students {
-                let student_id = &student_ids_by_sort[s_idx];
-                let (raw_value, status) = match a.raw_scores[s_idx] {
-                    legacy::LegacyScore::NoMark => (Some(0.0), "no_mark"),
-                    legacy::LegacyScore::Zero => (None, "zero"),
-                    legacy::LegacyScore::Scored(v) => (Some(v), "scored"),
-                };
-                let sid = Uuid::new_v4().to_string();
-                if let Err(e) = tx.execute(
-                    "INSERT INTO scores(id, assessment_id, student_id, raw_value, status) VALUES(?, ?, ?, ?, ?)",
-                    (&sid, assessment_id, student_id, raw_value, status),
-                ) {
-                    let _ = tx.rollback();
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_insert_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "scores" }))
-                        }
-                    });
-                }
-                scores_imported += 1;
-            }
-        }
-
-        // Best-effort import companions: .TYP (assessment types) and .RMK (remarks).
-        // These aren't required for the grid to function, but they matter for parity.
-        let typ_file = mark_file.with_extension("TYP");
-        if typ_file.is_file() {
-            let types = match legacy::parse_legacy_typ_file(&typ_file) {
-                Ok(v) => v,
-                Err(e) => {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "legacy_parse_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "typFile": typ_file.to_string_lossy() }))
-                        }
-                    });
-                }
-            };
-            let max = std::cmp::min(types.len(), assessment_ids_by_idx.len());
-            let mut up = match tx.prepare("UPDATE assessments SET legacy_type = ? WHERE id = ?") {
-                Ok(s) => s,
-                Err(e) => {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_update_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "assessments" }))
-                        }
-                    });
-                }
-            };
-            for i in 0..max {
-                if let Err(e) = up.execute((types[i] as i64, &assessment_ids_by_idx[i])) {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_update_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "assessments" }))
-                        }
-                    });
-                }
-            }
-        }
-
-        let rmk_file = mark_file.with_extension("RMK");
-        if rmk_file.is_file() {
-            let rmk = match legacy::parse_legacy_rmk_file(&rmk_file) {
-                Ok(v) => v,
-                Err(e) => {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "legacy_parse_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "rmkFile": rmk_file.to_string_lossy() }))
-                        }
-                    });
-                }
-            };
-
-            let max_entries =
-                std::cmp::min(rmk.remarks_by_entry.len(), assessment_ids_by_idx.len());
-            let max_students = std::cmp::min(student_ids_by_sort.len(), rmk.last_student);
-
-            let mut up = match tx
-                .prepare("UPDATE scores SET remark = ? WHERE assessment_id = ? AND student_id = ?")
-            {
-                Ok(s) => s,
-                Err(e) => {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_update_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "scores" }))
-                        }
-                    });
-                }
-            };
-
-            for a_idx in 0..max_entries {
-                let assessment_id = &assessment_ids_by_idx[a_idx];
-                let remarks = &rmk.remarks_by_entry[a_idx];
-                for s_idx in 0..max_students {
-                    let remark = remarks.get(s_idx).cloned().unwrap_or_default();
-                    let remark = remark.trim().to_string();
-                    if remark.is_empty() {
-                        continue;
-                    }
-                    let student_id = &student_ids_by_sort[s_idx];
-                    if let Err(e) = up.execute((&remark, assessment_id, student_id)) {
-                        return json!(ErrResp {
-                            id: req.id,
-                            ok: false,
-                            error: ErrObj {
-                                code: "db_update_failed".into(),
-                                message: e.to_string(),
-                                details: Some(json!({ "table": "scores" }))
-                            }
-                        });
-                    }
-                }
-            }
-        }
-
-        // Best-effort import IDX + per-set Rn files for comment sets.
-        let idx_file = mark_file.with_extension("IDX");
-        if idx_file.is_file() {
-            let parsed_idx = match legacy::parse_legacy_idx_file(&idx_file) {
-                Ok(v) => v,
-                Err(e) => {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "legacy_parse_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "idxFile": idx_file.to_string_lossy() }))
-                        }
-                    });
-                }
-            };
-
-            // Clear existing imported sets for this mark set before writing.
-            if let Err(e) = tx.execute(
-                "DELETE FROM comment_set_remarks
-                 WHERE comment_set_index_id IN (
-                   SELECT id FROM comment_set_indexes WHERE mark_set_id = ?
-                 )",
-                [&mark_set_id],
-            ) {
-                return json!(ErrResp {
-                    id: req.id,
-                    ok: false,
-                    error: ErrObj {
-                        code: "db_delete_failed".into(),
-                        message: e.to_string(),
-                        details: Some(json!({ "table": "comment_set_remarks" }))
-                    }
-                });
-            }
-            if let Err(e) = tx.execute(
-                "DELETE FROM comment_set_indexes WHERE mark_set_id = ?",
-                [&mark_set_id],
-            ) {
+        match insert_mark_scores(
+            &tx,
+            &parsed_mark,
+            &assessment_ids_by_idx,
+            &student_ids_by_sort,
+        ) {
+            Ok(n) => scores_imported += n,
+            Err(e) => {
+                let _ = tx.rollback();
                 return json!(ErrResp {
                     id: req.id,
                     ok: false,
-                    error: ErrObj {
-                        code: "db_delete_failed".into(),
-                        message: e.to_string(),
-                        details: Some(json!({ "table": "comment_set_indexes" }))
-                    }
+                    error: e
                 });
             }
+        }
 
-            let idx_bank_short = parsed_idx.bank_short.clone();
-            for set in parsed_idx.sets {
-                let csi_id = Uuid::new_v4().to_string();
-                let bank_short = set
-                    .bank_short
-                    .clone()
-                    .or_else(|| idx_bank_short.clone())
-                    .map(|s| s.trim().to_string())
-                    .and_then(|s| if s.is_empty() { None } else { Some(s) });
-                if let Err(e) = tx.execute(
-                    "INSERT INTO comment_set_indexes(
-                       id,
-                       class_id,
-                       mark_set_id,
-                       set_number,
-                       title,
-                       fit_mode,
-                       fit_font_size,
-                       fit_width,
-                       fit_lines,
-                       fit_subj,
-                       max_chars,
-                       is_default,
-                       bank_short
-                     ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                    (
-                        &csi_id,
-                        &class_id,
-                        &mark_set_id,
-                        set.set_number as i64,
-                        &set.title,
-                        set.fit_mode as i64,
-                        set.fit_font_size as i64,
-                        set.fit_width as i64,
-                        set.fit_lines as i64,
-                        &set.fit_subj,
-                        set.max_chars as i64,
-                        if set.is_default { 1 } else { 0 },
-                        bank_short.as_deref(),
-                    ),
-                ) {
-                    return json!(ErrResp {
-                        id: req.id,
-                        ok: false,
-                        error: ErrObj {
-                            code: "db_insert_failed".into(),
-                            message: e.to_string(),
-                            details: Some(json!({ "table": "comment_set_indexes" }))
-                        }
-                    });
-                }
-
-                comment_sets_imported += 1;
-
-                let r_file = mark_file.with_extension(format!("R{}", set.set_number));
-                if !r_file.is_file() {
-                    continue;
-                }
-                let parsed_r = match legacy::parse_legacy_r_comment_file(&r_file) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        return json!(ErrResp {
-                            id: req.id,
-                            ok: false,
-                            error: ErrObj {
-                                code: "legacy_parse_failed".into(),
-                                message: e.to_string(),
-                                details: Some(json!({ "remarkFile": r_file.to_string_lossy() }))
-                            }
-                        });
-                    }
-                };
-                let max_students = std::cmp::min(student_ids_by_sort.len(), parsed_r.remarks.len());
-                for s_idx in 0..max_students {
-                    let remark = parsed_r.remarks[s_idx].trim().to_string();
-                    if remark.is_empty() {
-                        continue;
-                    }
-                    let rid = Uuid::new_v4().to_string();
-                    let student_id = &student_ids_by_sort[s_idx];
-                    if let Err(e) = tx.execute(
-                        "INSERT INTO comment_set_remarks(id, comment_set_index_id, student_id, remark)
-                         VALUES(?, ?, ?, ?)
-                         ON CONFLICT(comment_set_index_id, student_id) DO UPDATE SET
-                           remark = excluded.remark",
-                        (&rid, &csi_id, student_id, &remark),
-                    ) {
-                        return json!(ErrResp {
-                            id: req.id,
-                            ok: false,
-                            error: ErrObj {
-                                code: "db_insert_failed".into(),
-                                message: e.to_string(),
-                                details: Some(json!({ "table": "comment_set_remarks" }))
-                            }
-                        });
-                    }
-                    comment_remarks_imported += 1;
-                }
-            }
+        // Best-effort import companions: .TYP (assessment types) and .RMK (remarks).
+        // These aren't required for the grid to function, but they matter for parity.
+        let companions = apply_typ_companion(
+            &tx,
+            &mark_file.with_extension("TYP"),
+            &assessment_ids_by_idx,
+        )
+        .and_then(|_| {
+            apply_rmk_companion(
+                &tx,
+                &mark_file.with_extension("RMK"),
+                encoding,
+                &assessment_ids_by_idx,
+                &student_ids_by_sort,
+            )
+        });
+        if let Err(e) = companions {
+            let _ = tx.rollback();
+            return json!(ErrResp {
+                id: req.id,
+                ok: false,
+                error: e
+            });
         }
 
         mark_sets_imported += 1;
         assessments_imported += parsed_mark.assessments.len();
         imported_mark_files.push(mark_filename);
+        comment_targets.push((mark_file, mark_set_id));
     }
 
-    // Best-effort import TBK companion files (loaned items).
-    match legacy::find_tbk_files(&legacy_folder) {
-        Ok(tbk_files) => {
-            if tbk_files.is_empty() {
-                warnings.push(json!({
-                    "code": "legacy_missing_tbk_file",
-                    "folder": legacy_folder.to_string_lossy()
-                }));
-            }
-            for tbk_file in tbk_files {
-                let parsed_tbk = match legacy::parse_legacy_tbk_file(&tbk_file) {
+    if runs("comments") {
+        for (mark_file, mark_set_id) in &comment_targets {
+            // Best-effort import IDX + per-set Rn files for comment sets.
+            let idx_file = mark_file.with_extension("IDX");
+            if idx_file.is_file() {
+                let parsed_idx = match legacy::parse_legacy_idx_file(&idx_file, encoding) {
                     Ok(v) => v,
                     Err(e) => {
                         let _ = tx.rollback();
@@ -1215,161 +1330,49 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                             error: ErrObj {
                                 code: "legacy_parse_failed".into(),
                                 message: e.to_string(),
-                                details: Some(json!({ "tbkFile": tbk_file.to_string_lossy() }))
+                                details: Some(json!({ "idxFile": idx_file.to_string_lossy() }))
                             }
                         });
                     }
                 };
-                let source_stem = tbk_file
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("")
-                    .to_ascii_uppercase();
-                let mark_set_id = mark_set_id_by_source_stem.get(&source_stem).cloned();
-                let max_students =
-                    std::cmp::min(student_ids_by_sort.len(), parsed_tbk.last_student);
-                for item in parsed_tbk.items {
-                    for s_idx in 0..max_students {
-                        let item_id = item
-                            .assignments
-                            .get(s_idx)
-                            .map(|a| a.item_id.trim().to_string())
-                            .unwrap_or_default();
-                        let note = item
-                            .assignments
-                            .get(s_idx)
-                            .map(|a| a.note.trim().to_string())
-                            .unwrap_or_default();
-                        if item_id.is_empty() && note.is_empty() {
-                            continue;
-                        }
-                        let raw_line = serde_json::to_string(&json!({
-                            "title": item.title,
-                            "publisher": item.publisher,
-                            "cost": item.cost,
-                            "itemId": item_id,
-                            "note": note
-                        }))
-                        .unwrap_or_else(|_| "{}".to_string());
-                        let loaned_id = Uuid::new_v4().to_string();
-                        let student_id = &student_ids_by_sort[s_idx];
-                        if let Err(e) = tx.execute(
-                            "INSERT INTO loaned_items(id, class_id, student_id, mark_set_id, item_name, quantity, notes, raw_line)
-                             VALUES(?, ?, ?, ?, ?, ?, ?, ?)",
-                            (
-                                &loaned_id,
-                                &class_id,
-                                student_id,
-                                mark_set_id.as_deref(),
-                                &item.title,
-                                item.cost,
-                                if note.is_empty() { None } else { Some(note.as_str()) },
-                                &raw_line,
-                            ),
-                        ) {
-                            let _ = tx.rollback();
-                            return json!(ErrResp {
-                                id: req.id,
-                                ok: false,
-                                error: ErrObj {
-                                    code: "db_insert_failed".into(),
-                                    message: e.to_string(),
-                                    details: Some(json!({ "table": "loaned_items" }))
-                                }
-                            });
-                        }
-                        loaned_items_imported += 1;
-                    }
-                }
-            }
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
-                }
-            });
-        }
-    }
 
-    // Best-effort merge ALL!<class>.IDX combined comment sets.
-    match legacy::find_all_idx_file(&legacy_folder) {
-        Ok(Some(all_idx_file)) => {
-            let parsed_idx = match legacy::parse_legacy_idx_file(&all_idx_file) {
-                Ok(v) => v,
-                Err(e) => {
+                // Clear existing imported sets for this mark set before writing.
+                if let Err(e) = tx.execute(
+                    "DELETE FROM comment_set_remarks
+                         WHERE comment_set_index_id IN (
+                           SELECT id FROM comment_set_indexes WHERE mark_set_id = ?
+                         )",
+                    [&mark_set_id],
+                ) {
                     let _ = tx.rollback();
                     return json!(ErrResp {
                         id: req.id,
                         ok: false,
                         error: ErrObj {
-                            code: "legacy_parse_failed".into(),
+                            code: "db_delete_failed".into(),
                             message: e.to_string(),
-                            details: Some(json!({ "idxFile": all_idx_file.to_string_lossy() }))
+                            details: Some(json!({ "table": "comment_set_remarks" }))
                         }
                     });
                 }
-            };
-
-            let mut mark_set_ids: Vec<String> =
-                mark_set_id_by_source_stem.values().cloned().collect();
-            mark_set_ids.sort();
-            mark_set_ids.dedup();
-
-            let idx_bank_short = parsed_idx.bank_short.clone();
-            for mark_set_id in mark_set_ids {
-                for set in &parsed_idx.sets {
-                    let existing_id: Option<String> = match tx
-                        .query_row(
-                            "SELECT id FROM comment_set_indexes WHERE mark_set_id = ? AND set_number = ?",
-                            (&mark_set_id, set.set_number as i64),
-                            |r| r.get(0),
-                        )
-                        .optional()
-                    {
-                        Ok(v) => v,
-                        Err(e) => {
-                            let _ = tx.rollback();
-                            return json!(ErrResp {
-                                id: req.id,
-                                ok: false,
-                                error: ErrObj {
-                                    code: "db_query_failed".into(),
-                                    message: e.to_string(),
-                                    details: Some(json!({ "table": "comment_set_indexes" }))
-                                }
-                            });
-                        }
-                    };
-                    let target_set_number = if existing_id.is_some() {
-                        match tx.query_row(
-                            "SELECT COALESCE(MAX(set_number), 0) FROM comment_set_indexes WHERE mark_set_id = ?",
-                            [&mark_set_id],
-                            |r| r.get::<_, i64>(0),
-                        ) {
-                            Ok(v) => v + 1,
-                            Err(e) => {
-                                let _ = tx.rollback();
-                                return json!(ErrResp {
-                                    id: req.id,
-                                    ok: false,
-                                    error: ErrObj {
-                                        code: "db_query_failed".into(),
-                                        message: e.to_string(),
-                                        details: Some(json!({ "table": "comment_set_indexes" }))
-                                    }
-                                });
-                            }
+                if let Err(e) = tx.execute(
+                    "DELETE FROM comment_set_indexes WHERE mark_set_id = ?",
+                    [&mark_set_id],
+                ) {
+                    let _ = tx.rollback();
+                    return json!(ErrResp {
+                        id: req.id,
+                        ok: false,
+                        error: ErrObj {
+                            code: "db_delete_failed".into(),
+                            message: e.to_string(),
+                            details: Some(json!({ "table": "comment_set_indexes" }))
                         }
-                    } else {
-                        set.set_number as i64
-                    };
+                    });
+                }
 
+                let idx_bank_short = parsed_idx.bank_short.clone();
+                for set in parsed_idx.sets {
                     let csi_id = Uuid::new_v4().to_string();
                     let bank_short = set
                         .bank_short
@@ -1379,25 +1382,25 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                         .and_then(|s| if s.is_empty() { None } else { Some(s) });
                     if let Err(e) = tx.execute(
                         "INSERT INTO comment_set_indexes(
-                           id,
-                           class_id,
-                           mark_set_id,
-                           set_number,
-                           title,
-                           fit_mode,
-                           fit_font_size,
-                           fit_width,
-                           fit_lines,
-                           fit_subj,
-                           max_chars,
-                           is_default,
-                           bank_short
-                         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                               id,
+                               class_id,
+                               mark_set_id,
+                               set_number,
+                               title,
+                               fit_mode,
+                               fit_font_size,
+                               fit_width,
+                               fit_lines,
+                               fit_subj,
+                               max_chars,
+                               is_default,
+                               bank_short
+                             ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                         (
                             &csi_id,
                             &class_id,
                             &mark_set_id,
-                            target_set_number,
+                            set.set_number as i64,
                             &set.title,
                             set.fit_mode as i64,
                             set.fit_font_size as i64,
@@ -1420,14 +1423,65 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                             }
                         });
                     }
+
                     comment_sets_imported += 1;
-                    combined_comment_sets_imported += 1;
 
-                    let r_file = all_idx_file.with_extension(format!("R{}", set.set_number));
+                    let r_file = mark_file.with_extension(format!("R{}", set.set_number));
                     if !r_file.is_file() {
                         continue;
                     }
-                    let parsed_r = match legacy::parse_legacy_r_comment_file(&r_file) {
+                    match import_r_comment_file(
+                        &tx,
+                        &r_file,
+                        encoding,
+                        &csi_id,
+                        &student_ids_by_sort,
+                    ) {
+                        Ok(n) => comment_remarks_imported += n,
+                        Err(e) => {
+                            let _ = tx.rollback();
+                            return json!(ErrResp {
+                                id: req.id,
+                                ok: false,
+                                error: e
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Best-effort import TBK companion files (loaned items).
+    if runs("loaned") {
+        // Replace previously imported TBK rows rather than stacking duplicates.
+        if attaching {
+            if let Err(e) = tx.execute(
+                "DELETE FROM loaned_items WHERE class_id = ? AND raw_line <> ''",
+                [&class_id],
+            ) {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "db_delete_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "table": "loaned_items" }))
+                    }
+                });
+            }
+        }
+        match legacy::find_tbk_files(&legacy_folder) {
+            Ok(tbk_files) => {
+                if tbk_files.is_empty() {
+                    warnings.push(json!({
+                        "code": "legacy_missing_tbk_file",
+                        "folder": legacy_folder.to_string_lossy()
+                    }));
+                }
+                for tbk_file in tbk_files {
+                    let parsed_tbk = match legacy::parse_legacy_tbk_file(&tbk_file) {
                         Ok(v) => v,
                         Err(e) => {
                             let _ = tx.rollback();
@@ -1437,28 +1491,201 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                                 error: ErrObj {
                                     code: "legacy_parse_failed".into(),
                                     message: e.to_string(),
-                                    details: Some(
-                                        json!({ "remarkFile": r_file.to_string_lossy() })
-                                    )
+                                    details: Some(json!({ "tbkFile": tbk_file.to_string_lossy() }))
                                 }
                             });
                         }
                     };
+                    let source_stem = tbk_file
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("")
+                        .to_ascii_uppercase();
+                    let mark_set_id = mark_set_id_by_source_stem.get(&source_stem).cloned();
                     let max_students =
-                        std::cmp::min(student_ids_by_sort.len(), parsed_r.remarks.len());
-                    for s_idx in 0..max_students {
-                        let remark = parsed_r.remarks[s_idx].trim().to_string();
-                        if remark.is_empty() {
-                            continue;
+                        std::cmp::min(student_ids_by_sort.len(), parsed_tbk.last_student);
+                    for item in parsed_tbk.items {
+                        for s_idx in 0..max_students {
+                            let item_id = item
+                                .assignments
+                                .get(s_idx)
+                                .map(|a| a.item_id.trim().to_string())
+                                .unwrap_or_default();
+                            let note = item
+                                .assignments
+                                .get(s_idx)
+                                .map(|a| a.note.trim().to_string())
+                                .unwrap_or_default();
+                            if item_id.is_empty() && note.is_empty() {
+                                continue;
+                            }
+                            let raw_line = serde_json::to_string(&json!({
+                                "title": item.title,
+                                "publisher": item.publisher,
+                                "cost": item.cost,
+                                "itemId": item_id,
+                                "note": note
+                            }))
+                            .unwrap_or_else(|_| "{}".to_string());
+                            let loaned_id = Uuid::new_v4().to_string();
+                            let student_id = &student_ids_by_sort[s_idx];
+                            if let Err(e) = tx.execute(
+                                "INSERT INTO loaned_items(id, class_id, student_id, mark_set_id, item_name, quantity, notes, raw_line)
+                                 VALUES(?, ?, ?, ?, ?, ?, ?, ?)",
+                                (
+                                    &loaned_id,
+                                    &class_id,
+                                    student_id,
+                                    mark_set_id.as_deref(),
+                                    &item.title,
+                                    item.cost,
+                                    if note.is_empty() { None } else { Some(note.as_str()) },
+                                    &raw_line,
+                                ),
+                            ) {
+                                let _ = tx.rollback();
+                                return json!(ErrResp {
+                                    id: req.id,
+                                    ok: false,
+                                    error: ErrObj {
+                                        code: "db_insert_failed".into(),
+                                        message: e.to_string(),
+                                        details: Some(json!({ "table": "loaned_items" }))
+                                    }
+                                });
+                            }
+                            loaned_items_imported += 1;
                         }
-                        let rid = Uuid::new_v4().to_string();
-                        let student_id = &student_ids_by_sort[s_idx];
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "legacy_read_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
+                    }
+                });
+            }
+        }
+    }
+
+    // Best-effort merge ALL!<class>.IDX combined comment sets.
+    if runs("comments") {
+        match legacy::find_all_idx_file(&legacy_folder) {
+            Ok(Some(all_idx_file)) => {
+                let parsed_idx = match legacy::parse_legacy_idx_file(&all_idx_file, encoding) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        let _ = tx.rollback();
+                        return json!(ErrResp {
+                            id: req.id,
+                            ok: false,
+                            error: ErrObj {
+                                code: "legacy_parse_failed".into(),
+                                message: e.to_string(),
+                                details: Some(json!({ "idxFile": all_idx_file.to_string_lossy() }))
+                            }
+                        });
+                    }
+                };
+
+                let mut mark_set_ids: Vec<String> =
+                    mark_set_id_by_source_stem.values().cloned().collect();
+                mark_set_ids.sort();
+                mark_set_ids.dedup();
+
+                let idx_bank_short = parsed_idx.bank_short.clone();
+                for mark_set_id in mark_set_ids {
+                    for set in &parsed_idx.sets {
+                        let existing_id: Option<String> = match tx
+                            .query_row(
+                                "SELECT id FROM comment_set_indexes WHERE mark_set_id = ? AND set_number = ?",
+                                (&mark_set_id, set.set_number as i64),
+                                |r| r.get(0),
+                            )
+                            .optional()
+                        {
+                            Ok(v) => v,
+                            Err(e) => {
+                                let _ = tx.rollback();
+                                return json!(ErrResp {
+                                    id: req.id,
+                                    ok: false,
+                                    error: ErrObj {
+                                        code: "db_query_failed".into(),
+                                        message: e.to_string(),
+                                        details: Some(json!({ "table": "comment_set_indexes" }))
+                                    }
+                                });
+                            }
+                        };
+                        let target_set_number = if existing_id.is_some() {
+                            match tx.query_row(
+                                "SELECT COALESCE(MAX(set_number), 0) FROM comment_set_indexes WHERE mark_set_id = ?",
+                                [&mark_set_id],
+                                |r| r.get::<_, i64>(0),
+                            ) {
+                                Ok(v) => v + 1,
+                                Err(e) => {
+                                    let _ = tx.rollback();
+                                    return json!(ErrResp {
+                                        id: req.id,
+                                        ok: false,
+                                        error: ErrObj {
+                                            code: "db_query_failed".into(),
+                                            message: e.to_string(),
+                                            details: Some(json!({ "table": "comment_set_indexes" }))
+                                        }
+                                    });
+                                }
+                            }
+                        } else {
+                            set.set_number as i64
+                        };
+
+                        let csi_id = Uuid::new_v4().to_string();
+                        let bank_short = set
+                            .bank_short
+                            .clone()
+                            .or_else(|| idx_bank_short.clone())
+                            .map(|s| s.trim().to_string())
+                            .and_then(|s| if s.is_empty() { None } else { Some(s) });
                         if let Err(e) = tx.execute(
-                            "INSERT INTO comment_set_remarks(id, comment_set_index_id, student_id, remark)
-                             VALUES(?, ?, ?, ?)
-                             ON CONFLICT(comment_set_index_id, student_id) DO UPDATE SET
-                               remark = excluded.remark",
-                            (&rid, &csi_id, student_id, &remark),
+                            "INSERT INTO comment_set_indexes(
+                               id,
+                               class_id,
+                               mark_set_id,
+                               set_number,
+                               title,
+                               fit_mode,
+                               fit_font_size,
+                               fit_width,
+                               fit_lines,
+                               fit_subj,
+                               max_chars,
+                               is_default,
+                               bank_short
+                             ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                            (
+                                &csi_id,
+                                &class_id,
+                                &mark_set_id,
+                                target_set_number,
+                                &set.title,
+                                set.fit_mode as i64,
+                                set.fit_font_size as i64,
+                                set.fit_width as i64,
+                                set.fit_lines as i64,
+                                &set.fit_subj,
+                                set.max_chars as i64,
+                                if set.is_default { 1 } else { 0 },
+                                bank_short.as_deref(),
+                            ),
                         ) {
                             let _ = tx.rollback();
                             return json!(ErrResp {
@@ -1467,32 +1694,55 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
                                 error: ErrObj {
                                     code: "db_insert_failed".into(),
                                     message: e.to_string(),
-                                    details: Some(json!({ "table": "comment_set_remarks" }))
+                                    details: Some(json!({ "table": "comment_set_indexes" }))
                                 }
                             });
                         }
-                        comment_remarks_imported += 1;
+                        comment_sets_imported += 1;
+                        combined_comment_sets_imported += 1;
+
+                        let r_file = all_idx_file.with_extension(format!("R{}", set.set_number));
+                        if !r_file.is_file() {
+                            continue;
+                        }
+                        match import_r_comment_file(
+                            &tx,
+                            &r_file,
+                            encoding,
+                            &csi_id,
+                            &student_ids_by_sort,
+                        ) {
+                            Ok(n) => comment_remarks_imported += n,
+                            Err(e) => {
+                                let _ = tx.rollback();
+                                return json!(ErrResp {
+                                    id: req.id,
+                                    ok: false,
+                                    error: e
+                                });
+                            }
+                        }
                     }
                 }
             }
-        }
-        Ok(None) => {
-            warnings.push(json!({
-                "code": "legacy_missing_all_idx_file",
-                "folder": legacy_folder.to_string_lossy()
-            }));
-        }
-        Err(e) => {
-            let _ = tx.rollback();
-            return json!(ErrResp {
-                id: req.id,
-                ok: false,
-                error: ErrObj {
-                    code: "legacy_read_failed".into(),
-                    message: e.to_string(),
-                    details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
-                }
-            });
+            Ok(None) => {
+                warnings.push(json!({
+                    "code": "legacy_missing_all_idx_file",
+                    "folder": legacy_folder.to_string_lossy()
+                }));
+            }
+            Err(e) => {
+                let _ = tx.rollback();
+                return json!(ErrResp {
+                    id: req.id,
+                    ok: false,
+                    error: ErrObj {
+                        code: "legacy_read_failed".into(),
+                        message: e.to_string(),
+                        details: Some(json!({ "folder": legacy_folder.to_string_lossy() }))
+                    }
+                });
+            }
         }
     }
 
@@ -1530,6 +1780,7 @@ fn handle_class_import_legacy(state: &mut AppState, req: Request) -> serde_json:
             "importedMarkFiles": imported_mark_files,
             "missingMarkFiles": missing_mark_files,
             "warnings": warnings,
+            "sectionsRun": sections,
         })
     })
 }
@@ -1672,7 +1923,7 @@ fn handle_classes_legacy_preview(state: &mut AppState, req: Request) -> serde_js
             })
         }
     };
-    let parsed = match legacy::parse_legacy_cl(&cl_file) {
+    let parsed = match legacy::parse_legacy_cl(&cl_file, legacy::LegacyEncoding::Auto) {
         Ok(v) => v,
         Err(e) => {
             return json!(ErrResp {
@@ -3144,6 +3395,20 @@ fn handle_markset_open(state: &mut AppState, req: Request) -> serde_json::Value
             })
         }
     };
+    let term_filters = match calc::parse_term_filters(&req.params) {
+        Ok(v) => v,
+        Err(e) => {
+            return json!(ErrResp {
+                id: req.id,
+                ok: false,
+                error: ErrObj {
+                    code: e.code,
+                    message: e.message,
+                    details: e.details
+                }
+            })
+        }
+    };
 
     let ms_row: Option<(String, String, String)> = match conn
         .query_row(
@@ -3180,8 +3445,16 @@ fn handle_markset_open(state: &mut AppState, req: Request) -> serde_json::Value
         });
     };
 
+    // Hides withdrawn students from the grid. Each student keeps its gridRow
+    // (its row in grid.get and scores.updateCell), which then skips hidden rows.
+    let active_only = req
+        .params
+        .get("activeOnly")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
     let mut stud_stmt = match conn.prepare(
-        "SELECT id, last_name, first_name, sort_order, active FROM students WHERE class_id = ? ORDER BY sort_order",
+        "SELECT id, last_name, first_name, sort_order, active FROM students WHERE class_id = ? AND archived = 0 ORDER BY sort_order",
     ) {
         Ok(s) => s,
         Err(e) => {
@@ -3203,17 +3476,24 @@ fn handle_markset_open(state: &mut AppState, req: Request) -> serde_json::Value
             let first: String = row.get(2)?;
             let sort_order: i64 = row.get(3)?;
             let active: i64 = row.get(4)?;
-            let display_name = format!("{}, {}", last, first);
-            Ok(json!({
-                "id": id,
-                "displayName": display_name,
-                "sortOrder": sort_order,
-                "active": active != 0
-            }))
+            Ok((id, format!("{}, {}", last, first), sort_order, active != 0))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
     {
-        Ok(v) => v,
+        Ok(v) => v
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, _, _, active))| !active_only || *active)
+            .map(|(grid_row, (id, display_name, sort_order, active))| {
+                json!({
+                    "id": id,
+                    "displayName": display_name,
+                    "sortOrder": sort_order,
+                    "active": active,
+                    "gridRow": grid_row
+                })
+            })
+            .collect(),
         Err(e) => {
             return json!(ErrResp {
                 id: req.id,
@@ -3228,7 +3508,7 @@ fn handle_markset_open(state: &mut AppState, req: Request) -> serde_json::Value
     };
 
     let mut assess_stmt = match conn.prepare(
-        "SELECT id, idx, date, category_name, title, weight, out_of FROM assessments WHERE mark_set_id = ? ORDER BY idx",
+        "SELECT id, idx, date, category_name, title, weight, out_of, term FROM assessments WHERE mark_set_id = ? ORDER BY idx",
     ) {
         Ok(s) => s,
         Err(e) => {
@@ -3252,19 +3532,28 @@ fn handle_markset_open(state: &mut AppState, req: Request) -> serde_json::Value
             let title: String = row.get(4)?;
             let weight: Option<f64> = row.get(5)?;
             let out_of: Option<f64> = row.get(6)?;
-            Ok(json!({
-                "id": id,
-                "idx": idx,
-                "date": date,
-                "categoryName": category_name,
-                "title": title,
-                "weight": weight,
-                "outOf": out_of
-            }))
+            let term: Option<i64> = row.get(7)?;
+            Ok((
+                term,
+                json!({
+                    "id": id,
+                    "idx": idx,
+                    "date": date,
+                    "categoryName": category_name,
+                    "title": title,
+                    "term": term,
+                    "weight": weight,
+                    "outOf": out_of
+                }),
+            ))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
     {
-        Ok(v) => v,
+        Ok(v) => v
+            .into_iter()
+            .filter(|(term, _)| term_filters.term_matches(*term))
+            .map(|(_, a)| a)
+            .collect(),
         Err(e) => {
             return json!(ErrResp {
                 id: req.id,
@@ -3405,6 +3694,675 @@ fn handle_classes_update_from_attached_legacy(
     handle_classes_update_from_legacy(state, proxy_req)
 }
 
+fn err_obj(code: &str, message: impl Into<String>, details: Option<serde_json::Value>) -> ErrObj {
+    ErrObj {
+        code: code.into(),
+        message: message.into(),
+        details,
+    }
+}
+
+/// Uppercase alphanumerics only, so the token is safe inside an 8.3-style legacy file name.
+fn legacy_name_token(s: &str) -> String {
+    s.chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+fn export_class_legacy(
+    conn: &Connection,
+    class_id: &str,
+    out_folder: &Path,
+) -> Result<serde_json::Value, ErrObj> {
+    let query_err = |e: rusqlite::Error| err_obj("db_query_failed", e.to_string(), None);
+
+    let class_name: Option<String> = conn
+        .query_row("SELECT name FROM classes WHERE id = ?", [class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+        .map_err(query_err)?;
+    let Some(class_name) = class_name else {
+        return Err(err_obj("not_found", "class not found", None));
+    };
+    #[derive(Default)]
+    struct ExportMeta {
+        class_code: Option<String>,
+        school_name: Option<String>,
+        teacher_name: Option<String>,
+        legacy_cl_file: Option<String>,
+        legacy_year_token: Option<String>,
+    }
+    let meta = conn
+        .query_row(
+            "SELECT class_code, school_name, teacher_name, legacy_cl_file, legacy_year_token
+             FROM class_meta WHERE class_id = ?",
+            [class_id],
+            |r| {
+                Ok(ExportMeta {
+                    class_code: r.get(0)?,
+                    school_name: r.get(1)?,
+                    teacher_name: r.get(2)?,
+                    legacy_cl_file: r.get(3)?,
+                    legacy_year_token: r.get(4)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(query_err)?
+        .unwrap_or_default();
+
+    let mut students: Vec<(String, legacy::ParsedStudent)> = Vec::new();
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, active, last_name, first_name, student_no, birth_date, mark_set_mask, raw_line
+                 FROM students WHERE class_id = ? ORDER BY sort_order",
+            )
+            .map_err(query_err)?;
+        let rows = stmt
+            .query_map([class_id], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    legacy::ParsedStudent {
+                        active: r.get::<_, i64>(1)? != 0,
+                        last_name: r.get(2)?,
+                        first_name: r.get(3)?,
+                        student_no: r
+                            .get::<_, Option<String>>(4)?
+                            .filter(|s| !s.trim().is_empty()),
+                        birth_date: r
+                            .get::<_, Option<String>>(5)?
+                            .filter(|s| !s.trim().is_empty()),
+                        mark_set_mask: r.get(6)?,
+                        raw_line: r.get(7)?,
+                    },
+                ))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            .map_err(query_err)?;
+        students.extend(rows);
+    }
+
+    struct MarkSetRow {
+        id: String,
+        def: legacy::ParsedMarkSetDef,
+        source_filename: Option<String>,
+        misc: legacy::ParsedMiscInfo,
+    }
+    let mark_sets: Vec<MarkSetRow> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, code, file_prefix, description, weight, source_filename, full_code,
+                        room, day, period, weight_method, calc_method
+                 FROM mark_sets
+                 WHERE class_id = ? AND deleted_at IS NULL
+                 ORDER BY sort_order",
+            )
+            .map_err(query_err)?;
+        let rows = stmt
+            .query_map([class_id], |r| {
+                Ok(MarkSetRow {
+                    id: r.get(0)?,
+                    def: legacy::ParsedMarkSetDef {
+                        code: r.get(1)?,
+                        file_prefix: r.get(2)?,
+                        description: r.get(3)?,
+                        weight: r.get::<_, Option<f64>>(4)?.unwrap_or(0.0),
+                        sort_order: 0,
+                    },
+                    source_filename: r.get(5)?,
+                    misc: legacy::ParsedMiscInfo {
+                        full_code: r.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                        room: r.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                        day: r.get::<_, Option<String>>(8)?.unwrap_or_default(),
+                        period: r.get::<_, Option<String>>(9)?.unwrap_or_default(),
+                        weight_method: r.get::<_, i64>(10)? as i32,
+                        calc_method: r.get::<_, i64>(11)? as i32,
+                        legacy_serial: None,
+                    },
+                })
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            .map_err(query_err)?;
+        rows
+    };
+
+    // Reuse the names the class was imported from when we know them: the linked CL file,
+    // else any mark file named <prefix><token>.Yxx. Otherwise derive the token from the
+    // class code (or name) and the year from today.
+    let split_legacy_name = |name: &str, prefix: &str| -> Option<(String, String)> {
+        let name = Path::new(name).file_name()?.to_str()?.trim();
+        let (stem, ext) = name.rsplit_once('.')?;
+        let stem = legacy_name_token(stem);
+        if !legacy::is_legacy_year_file(name) || stem.len() <= prefix.len() {
+            return None;
+        }
+        stem.strip_prefix(prefix)
+            .map(|token| (token.to_string(), ext.to_ascii_uppercase()))
+    };
+    let imported_names = meta
+        .legacy_cl_file
+        .as_deref()
+        .and_then(|p| split_legacy_name(p, "CL"))
+        .or_else(|| {
+            mark_sets.iter().find_map(|ms| {
+                let name = ms.source_filename.as_deref()?;
+                split_legacy_name(name, &legacy_name_token(&ms.def.file_prefix))
+            })
+        });
+    let year_token = meta
+        .legacy_year_token
+        .map(|s| s.trim().to_ascii_uppercase())
+        .filter(|s| s.starts_with('Y') && s.len() >= 2)
+        .or_else(|| imported_names.as_ref().map(|(_, year)| year.clone()))
+        .unwrap_or_else(|| format!("Y{}", chrono::Local::now().format("%y")));
+    let class_token = match imported_names {
+        Some((token, _)) => token,
+        None => {
+            let token = legacy_name_token(meta.class_code.as_deref().unwrap_or(&class_name));
+            if token.is_empty() {
+                "CLASS".to_string()
+            } else {
+                token.chars().take(6).collect()
+            }
+        }
+    };
+
+    if let Err(e) = std::fs::create_dir_all(out_folder) {
+        return Err(err_obj(
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": out_folder.to_string_lossy() })),
+        ));
+    }
+    let mut written: Vec<String> = Vec::new();
+    let write_file = |name: &str, body: String, written: &mut Vec<String>| {
+        let path = out_folder.join(name);
+        std::fs::write(&path, body).map_err(|e| {
+            err_obj(
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": path.to_string_lossy() })),
+            )
+        })?;
+        written.push(path.to_string_lossy().to_string());
+        Ok::<(), ErrObj>(())
+    };
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let student_index: HashMap<&str, usize> = students
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (id.as_str(), i))
+        .collect();
+    let mark_set_defs: Vec<legacy::ParsedMarkSetDef> = mark_sets
+        .iter()
+        .enumerate()
+        .map(|(i, ms)| legacy::ParsedMarkSetDef {
+            sort_order: i,
+            ..ms.def.clone()
+        })
+        .collect();
+    let mut mark_files: Vec<(String, String)> = Vec::new();
+    for ms in mark_sets {
+        let categories: Vec<legacy::ParsedCategory> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT name, weight FROM categories WHERE mark_set_id = ? ORDER BY sort_order",
+                )
+                .map_err(query_err)?;
+            let rows = stmt
+                .query_map([&ms.id], |r| {
+                    Ok(legacy::ParsedCategory {
+                        name: r.get(0)?,
+                        weight: r.get::<_, Option<f64>>(1)?.unwrap_or(0.0),
+                    })
+                })
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+                .map_err(query_err)?;
+            rows
+        };
+
+        let mut assessments: Vec<(String, legacy::ParsedAssessment)> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, idx, date, category_name, title, term, legacy_kind, weight, out_of
+                     FROM assessments WHERE mark_set_id = ? ORDER BY idx",
+                )
+                .map_err(query_err)?;
+            let rows = stmt
+                .query_map([&ms.id], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        legacy::ParsedAssessment {
+                            idx: r.get::<_, i64>(1)? as usize,
+                            // Legacy files require a date on every assessment.
+                            date: r
+                                .get::<_, Option<String>>(2)?
+                                .filter(|s| !s.trim().is_empty())
+                                .unwrap_or_else(|| today.clone()),
+                            category_name: r.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                            title: r.get(4)?,
+                            term: r.get::<_, Option<i64>>(5)?.unwrap_or(0) as i32,
+                            legacy_kind: r.get::<_, Option<i64>>(6)?.unwrap_or(0) as i32,
+                            weight: r.get::<_, Option<f64>>(7)?.unwrap_or(1.0),
+                            out_of: r.get::<_, Option<f64>>(8)?.unwrap_or(0.0),
+                            avg_percent: 0.0,
+                            avg_raw: 0.0,
+                            raw_scores: vec![legacy::LegacyScore::NoMark; students.len()],
+                        },
+                    ))
+                })
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+                .map_err(query_err)?;
+            rows
+        };
+
+        let mut score_stmt = conn
+            .prepare("SELECT student_id, raw_value, status FROM scores WHERE assessment_id = ?")
+            .map_err(query_err)?;
+        for (assessment_id, a) in assessments.iter_mut() {
+            let scores = score_stmt
+                .query_map([assessment_id.as_str()], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, Option<f64>>(1)?,
+                        r.get::<_, String>(2)?,
+                    ))
+                })
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+                .map_err(query_err)?;
+            for (student_id, raw_value, status) in scores {
+                let Some(&i) = student_index.get(student_id.as_str()) else {
+                    continue;
+                };
+                a.raw_scores[i] = match (status.as_str(), raw_value) {
+                    ("zero", _) => legacy::LegacyScore::Zero,
+                    ("scored", Some(v)) if v > 0.0 => legacy::LegacyScore::Scored(v),
+                    _ => legacy::LegacyScore::NoMark,
+                };
+            }
+
+            // Class averages are stored in the file; recompute them from the marks that
+            // count (zeros included, no-marks excluded) rather than trusting stale columns.
+            let counted: Vec<f64> = a
+                .raw_scores
+                .iter()
+                .filter_map(|s| match s {
+                    legacy::LegacyScore::Scored(v) => Some(*v),
+                    legacy::LegacyScore::Zero => Some(0.0),
+                    legacy::LegacyScore::NoMark => None,
+                })
+                .collect();
+            if !counted.is_empty() {
+                a.avg_raw = counted.iter().sum::<f64>() / counted.len() as f64;
+                if a.out_of > 0.0 {
+                    a.avg_percent = (a.avg_raw * 1000.0 / a.out_of).round() / 10.0;
+                }
+            }
+        }
+
+        let parsed = legacy::ParsedMarkFile {
+            misc: Some(ms.misc),
+            categories,
+            last_student: students.len(),
+            assessments: assessments.into_iter().map(|(_, a)| a).collect(),
+        };
+        let prefix = legacy_name_token(&ms.def.file_prefix);
+        let file_name = ms
+            .source_filename
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| {
+                legacy::is_legacy_year_file(name) && name.to_ascii_uppercase().starts_with(&prefix)
+            })
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{}{}.{}", prefix, class_token, year_token));
+        mark_files.push((file_name, legacy::serialize_legacy_mark_file(&parsed)));
+    }
+
+    let cl = legacy::ParsedCl {
+        class_name,
+        mark_sets: mark_set_defs,
+        students: students.into_iter().map(|(_, s)| s).collect(),
+    };
+    let cl_name = format!("CL{}.{}", class_token, year_token);
+    write_file(
+        &cl_name,
+        legacy::serialize_legacy_cl(
+            &cl,
+            meta.school_name.as_deref().unwrap_or(""),
+            meta.teacher_name.as_deref().unwrap_or(""),
+        ),
+        &mut written,
+    )?;
+    for (name, body) in mark_files {
+        write_file(&name, body, &mut written)?;
+    }
+
+    Ok(json!({ "classId": class_id, "files": written }))
+}
+
+fn handle_legacy_export_class(state: &mut AppState, req: Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error: err_obj("no_workspace", "select a workspace first", None)
+        });
+    };
+    let Some(class_id) = req.params.get("classId").and_then(|v| v.as_str()) else {
+        return json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error: err_obj("bad_params", "missing classId", None)
+        });
+    };
+    let Some(out_folder) = req
+        .params
+        .get("outFolder")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    else {
+        return json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error: err_obj("bad_params", "missing outFolder", None)
+        });
+    };
+
+    match export_class_legacy(conn, class_id, Path::new(out_folder)) {
+        Ok(result) => json!(OkResp {
+            id: req.id,
+            ok: true,
+            result
+        }),
+        Err(error) => json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error
+        }),
+    }
+}
+
+/// Dry run of `class.importLegacy`: the same discovery and parsing, nothing written.
+/// Missing companions are reported with the import's warning codes.
+fn preview_legacy_import(legacy_folder: &Path) -> Result<serde_json::Value, ErrObj> {
+    let (report, mut errors) = scan_legacy_folder(legacy_folder);
+    match report {
+        Some(report) if errors.is_empty() => Ok(report),
+        _ => Err(errors.remove(0)),
+    }
+}
+
+/// Runs every discovery and parse step of the import over one class folder.
+/// Failures are collected in the order the import would hit them instead of
+/// stopping the scan; the report is `None` only when the class file itself is
+/// missing or unreadable.
+fn scan_legacy_folder(legacy_folder: &Path) -> (Option<serde_json::Value>, Vec<ErrObj>) {
+    let mut errors: Vec<ErrObj> = Vec::new();
+    let folder_details = || Some(json!({ "folder": legacy_folder.to_string_lossy() }));
+    let read_err =
+        |e: anyhow::Error| err_obj("legacy_read_failed", e.to_string(), folder_details());
+    let parse_err = |key: &str, path: &Path, e: anyhow::Error| {
+        err_obj(
+            "legacy_parse_failed",
+            e.to_string(),
+            Some(json!({ (key): path.to_string_lossy() })),
+        )
+    };
+
+    let cl_file = match legacy::find_cl_file(legacy_folder) {
+        Ok(v) => v,
+        Err(e) => {
+            errors.push(err_obj("legacy_no_cl", e.to_string(), folder_details()));
+            return (None, errors);
+        }
+    };
+    let parsed = match legacy::parse_legacy_cl(&cl_file, legacy::LegacyEncoding::Auto) {
+        Ok(v) => v,
+        Err(e) => {
+            errors.push(parse_err("clFile", &cl_file, e));
+            return (None, errors);
+        }
+    };
+
+    let mut mark_files: Vec<String> = Vec::new();
+    let mut missing_mark_files: Vec<serde_json::Value> = Vec::new();
+    let mut assessments_found = 0usize;
+    for def in &parsed.mark_sets {
+        let mark_file = match legacy::find_mark_file(legacy_folder, &def.file_prefix) {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                missing_mark_files.push(json!({ "code": def.code, "filePrefix": def.file_prefix }));
+                continue;
+            }
+            Err(e) => {
+                errors.push(err_obj(
+                    "legacy_read_failed",
+                    e.to_string(),
+                    Some(json!({
+                        "folder": legacy_folder.to_string_lossy(),
+                        "filePrefix": def.file_prefix
+                    })),
+                ));
+                continue;
+            }
+        };
+        match legacy::parse_legacy_mark_file(&mark_file) {
+            Ok(parsed_mark) => {
+                assessments_found += parsed_mark.assessments.len();
+                mark_files.push(
+                    mark_file
+                        .file_name()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("")
+                        .to_string(),
+                );
+            }
+            Err(e) => errors.push(parse_err("markFile", &mark_file, e)),
+        }
+    }
+
+    let mut companion_files: Vec<serde_json::Value> = Vec::new();
+    let mut warnings: Vec<serde_json::Value> = Vec::new();
+    let mut found = |kind: &str, path: &Path| {
+        companion_files.push(json!({ "kind": kind, "path": path.to_string_lossy() }));
+    };
+    let missing = |code: &str| json!({ "code": code, "folder": legacy_folder.to_string_lossy() });
+
+    match legacy::find_note_file(legacy_folder) {
+        Ok(Some(note_file)) => {
+            match legacy::parse_legacy_note_file(&note_file, legacy::LegacyEncoding::Auto) {
+                Ok(_) => found("note", &note_file),
+                Err(e) => errors.push(parse_err("noteFile", &note_file, e)),
+            }
+        }
+        Ok(None) => {}
+        Err(e) => errors.push(read_err(e)),
+    }
+    match legacy::find_attendance_file(legacy_folder) {
+        Ok(Some(att_file)) => match legacy::parse_legacy_attendance_file(&att_file) {
+            Ok(_) => found("attendance", &att_file),
+            Err(e) => errors.push(parse_err("attendanceFile", &att_file, e)),
+        },
+        Ok(None) => warnings.push(missing("legacy_missing_attendance_file")),
+        Err(e) => errors.push(read_err(e)),
+    }
+    match legacy::find_seating_file(legacy_folder) {
+        Ok(Some(spl_file)) => match legacy::parse_legacy_seating_file(&spl_file) {
+            Ok(_) => found("seating", &spl_file),
+            Err(e) => errors.push(parse_err("seatingFile", &spl_file, e)),
+        },
+        Ok(None) => warnings.push(missing("legacy_missing_seating_file")),
+        Err(e) => errors.push(read_err(e)),
+    }
+    match legacy::find_icc_file(legacy_folder) {
+        Ok(Some(icc_file)) => match legacy::parse_legacy_icc_file(&icc_file) {
+            Ok(_) => found("icc", &icc_file),
+            Err(e) => errors.push(parse_err("iccFile", &icc_file, e)),
+        },
+        Ok(None) => warnings.push(missing("legacy_missing_icc_file")),
+        Err(e) => errors.push(read_err(e)),
+    }
+    // The import picks banks up from the parent folder, alongside the class folders.
+    let bnk_folder = legacy_folder.parent().unwrap_or(legacy_folder);
+    match legacy::find_bnk_files(bnk_folder) {
+        Ok(bnk_files) => {
+            for bnk_file in bnk_files {
+                match legacy::parse_bnk_file(&bnk_file, legacy::LegacyEncoding::Auto) {
+                    Ok(_) => found("bnk", &bnk_file),
+                    Err(e) => errors.push(parse_err("bnkFile", &bnk_file, e)),
+                }
+            }
+        }
+        Err(e) => errors.push(read_err(e)),
+    }
+    match legacy::find_tbk_files(legacy_folder) {
+        Ok(tbk_files) => {
+            if tbk_files.is_empty() {
+                warnings.push(missing("legacy_missing_tbk_file"));
+            }
+            for tbk_file in tbk_files {
+                match legacy::parse_legacy_tbk_file(&tbk_file) {
+                    Ok(_) => found("tbk", &tbk_file),
+                    Err(e) => errors.push(parse_err("tbkFile", &tbk_file, e)),
+                }
+            }
+        }
+        Err(e) => errors.push(read_err(e)),
+    }
+    match legacy::find_all_idx_file(legacy_folder) {
+        Ok(Some(idx_file)) => {
+            match legacy::parse_legacy_idx_file(&idx_file, legacy::LegacyEncoding::Auto) {
+                Ok(_) => found("allIdx", &idx_file),
+                Err(e) => errors.push(parse_err("idxFile", &idx_file, e)),
+            }
+        }
+        Ok(None) => warnings.push(missing("legacy_missing_all_idx_file")),
+        Err(e) => errors.push(read_err(e)),
+    }
+
+    let report = json!({
+        "name": parsed.class_name,
+        "sourceClFile": cl_file.to_string_lossy(),
+        "studentsFound": parsed.students.len(),
+        "markSetsFound": mark_files.len(),
+        "assessmentsFound": assessments_found,
+        "markFiles": mark_files,
+        "missingMarkFiles": missing_mark_files,
+        "companionFiles": companion_files,
+        "warnings": warnings,
+    });
+    (Some(report), errors)
+}
+
+/// Lints every immediate subfolder of `parentPath` as a legacy class folder,
+/// without importing. Each folder gets the preview report (or `null` when no
+/// class file could be read) plus every read/parse error found.
+fn validate_legacy_folders(parent: &Path) -> Result<serde_json::Value, ErrObj> {
+    let read_err = |e: std::io::Error| {
+        err_obj(
+            "legacy_read_failed",
+            e.to_string(),
+            Some(json!({ "folder": parent.to_string_lossy() })),
+        )
+    };
+    let mut folders: Vec<PathBuf> = Vec::new();
+    for entry in std::fs::read_dir(parent).map_err(read_err)? {
+        let path = entry.map_err(read_err)?.path();
+        if path.is_dir() {
+            folders.push(path);
+        }
+    }
+    folders.sort();
+
+    let reports: Vec<serde_json::Value> = folders
+        .iter()
+        .map(|folder| {
+            let (report, errors) = scan_legacy_folder(folder);
+            json!({
+                "folder": folder.to_string_lossy(),
+                "ok": report.is_some() && errors.is_empty(),
+                "report": report,
+                "errors": errors,
+            })
+        })
+        .collect();
+    Ok(json!({ "folders": reports }))
+}
+
+fn handle_legacy_validate_folder(req: Request) -> serde_json::Value {
+    let Some(parent) = req
+        .params
+        .get("parentPath")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+    else {
+        return json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error: err_obj("bad_params", "missing parentPath", None)
+        });
+    };
+
+    match validate_legacy_folders(&parent) {
+        Ok(result) => json!(OkResp {
+            id: req.id,
+            ok: true,
+            result
+        }),
+        Err(error) => json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error
+        }),
+    }
+}
+
+fn handle_legacy_preview_import(req: Request) -> serde_json::Value {
+    let Some(legacy_folder) = req
+        .params
+        .get("legacyClassFolderPath")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+    else {
+        return json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error: err_obj("bad_params", "missing legacyClassFolderPath", None)
+        });
+    };
+
+    match preview_legacy_import(&legacy_folder) {
+        Ok(result) => json!(OkResp {
+            id: req.id,
+            ok: true,
+            result
+        }),
+        Err(error) => json!(ErrResp {
+            id: req.id,
+            ok: false,
+            error
+        }),
+    }
+}
+
+pub const METHODS: &[&str] = &[
+    "class.importLegacy",
+    "classes.legacyPreview",
+    "classes.updateFromLegacy",
+    "classes.updateFromAttachedLegacy",
+    "marksets.list",
+    "markset.open",
+    "legacy.exportClass",
+    "legacy.previewImport",
+    "legacy.validateFolder",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "class.importLegacy" => Some(handle_class_import_legacy(state, req.clone())),
@@ -3415,6 +4373,9 @@ pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Val
         }
         "marksets.list" => Some(handle_marksets_list(state, req.clone())),
         "markset.open" => Some(handle_markset_open(state, req.clone())),
+        "legacy.exportClass" => Some(handle_legacy_export_class(state, req.clone())),
+        "legacy.previewImport" => Some(handle_legacy_preview_import(req.clone())),
+        "legacy.validateFolder" => Some(handle_legacy_validate_folder(req.clone())),
         _ => None,
     }
 }
@@ -1,3 +1,4 @@
+use super::grid;
 use crate::calc;
 use crate::db;
 use crate::ipc::error::{err, ok};
@@ -1991,6 +1992,11 @@ fn upsert_score(
     status: &str,
     remark: Option<&str>,
 ) -> Result<(), HandlerErr> {
+    let old = grid::read_score_cell(conn, assessment_id, student_id).map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
     let score_id = Uuid::new_v4().to_string();
     conn.execute(
         "INSERT INTO scores(id, assessment_id, student_id, raw_value, status, remark)
@@ -2006,7 +2012,18 @@ fn upsert_score(
         message: e.to_string(),
         details: Some(json!({ "table": "scores" })),
     })?;
-    Ok(())
+    grid::record_score_history(
+        conn,
+        assessment_id,
+        student_id,
+        &old,
+        &Some((raw_value, status.to_string())),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "score_history" })),
+    })
 }
 
 fn ensure_target_mark_set(
@@ -2488,6 +2505,16 @@ fn handle_admin_transfer_apply_package(state: &mut AppState, req: &Request) -> V
     )
 }
 
+pub const METHODS: &[&str] = &[
+    "integrations.sis.previewImport",
+    "integrations.sis.applyImport",
+    "integrations.sis.exportRoster",
+    "integrations.sis.exportMarks",
+    "integrations.adminTransfer.previewPackage",
+    "integrations.adminTransfer.applyPackage",
+    "integrations.adminTransfer.exportPackage",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<Value> {
     match req.method.as_str() {
         "integrations.sis.previewImport" => Some(handle_sis_preview_import(state, req)),
@@ -1,3 +1,5 @@
+use super::grid;
+use crate::calc;
 use crate::db;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
@@ -188,7 +190,7 @@ fn handle_categories_list(state: &mut AppState, req: &Request) -> serde_json::Va
     }
 
     let mut stmt = match conn.prepare(
-        "SELECT id, name, weight, sort_order FROM categories WHERE mark_set_id = ? ORDER BY sort_order",
+        "SELECT id, name, weight, sort_order, drop_lowest FROM categories WHERE mark_set_id = ? ORDER BY sort_order",
     ) {
         Ok(s) => s,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
@@ -199,11 +201,13 @@ fn handle_categories_list(state: &mut AppState, req: &Request) -> serde_json::Va
             let name: String = row.get(1)?;
             let weight: Option<f64> = row.get(2)?;
             let sort_order: i64 = row.get(3)?;
+            let drop_lowest: Option<i64> = row.get(4)?;
             Ok(json!({
                 "id": id,
                 "name": name,
                 "weight": weight,
-                "sortOrder": sort_order
+                "sortOrder": sort_order,
+                "dropLowest": drop_lowest
             }))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>());
@@ -324,6 +328,22 @@ fn handle_categories_update(state: &mut AppState, req: &Request) -> serde_json::
             );
         }
     }
+    if let Some(v) = patch.get("dropLowest") {
+        if v.is_null() {
+            set_parts.push("drop_lowest = ?".into());
+            bind_values.push(Value::Null);
+        } else if let Some(n) = v.as_u64() {
+            set_parts.push("drop_lowest = ?".into());
+            bind_values.push(Value::Integer(n.min(i64::MAX as u64) as i64));
+        } else {
+            return err(
+                &req.id,
+                "bad_params",
+                "patch.dropLowest must be a non-negative integer or null",
+                None,
+            );
+        }
+    }
 
     if set_parts.is_empty() {
         return err(
@@ -446,6 +466,117 @@ fn handle_categories_delete(state: &mut AppState, req: &Request) -> serde_json::
     ok(&req.id, json!({ "ok": true }))
 }
 
+fn handle_categories_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+    let Some(arr) = req
+        .params
+        .get("orderedCategoryIds")
+        .and_then(|v| v.as_array())
+    else {
+        return err(
+            &req.id,
+            "bad_params",
+            "missing/invalid orderedCategoryIds",
+            None,
+        );
+    };
+    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
+    for v in arr {
+        let Some(s) = v.as_str() else {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedCategoryIds must be strings",
+                None,
+            );
+        };
+        ordered.push(s.to_string());
+    }
+
+    match mark_set_exists(conn, &class_id, &mark_set_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let mut stmt =
+        match conn.prepare("SELECT id FROM categories WHERE mark_set_id = ? ORDER BY sort_order") {
+            Ok(s) => s,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+    let current_ids: Vec<String> = match stmt
+        .query_map([&mark_set_id], |row| row.get::<_, String>(0))
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if ordered.len() != current_ids.len() {
+        return err(
+            &req.id,
+            "bad_params",
+            "orderedCategoryIds must be a permutation of the mark set categories",
+            Some(json!({ "expected": current_ids.len(), "got": ordered.len() })),
+        );
+    }
+
+    let current_set: HashSet<String> = current_ids.into_iter().collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    for id in &ordered {
+        if !seen.insert(id.clone()) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedCategoryIds contains duplicates",
+                Some(json!({ "categoryId": id })),
+            );
+        }
+        if !current_set.contains(id) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedCategoryIds contains unknown categoryId",
+                Some(json!({ "categoryId": id })),
+            );
+        }
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    for (i, cid) in ordered.iter().enumerate() {
+        if let Err(e) = tx.execute(
+            "UPDATE categories SET sort_order = ? WHERE id = ? AND mark_set_id = ?",
+            (i as i64, cid, &mark_set_id),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "categories" })),
+            );
+        }
+    }
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
 fn handle_assessments_list(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -464,6 +595,10 @@ fn handle_assessments_list(state: &mut AppState, req: &Request) -> serde_json::V
         .get("hideDeleted")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let term_filters = match calc::parse_term_filters(&req.params) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, &e.code, e.message, e.details),
+    };
 
     match mark_set_exists(conn, &class_id, &mark_set_id) {
         Ok(true) => {}
@@ -503,6 +638,7 @@ fn handle_assessments_list(state: &mut AppState, req: &Request) -> serde_json::V
             Ok((
                 category_name.clone(),
                 weight,
+                term,
                 json!({
                 "id": id,
                 "idx": idx,
@@ -521,7 +657,10 @@ fn handle_assessments_list(state: &mut AppState, req: &Request) -> serde_json::V
     match rows {
         Ok(assessments_raw) => {
             let mut assessments = Vec::with_capacity(assessments_raw.len());
-            for (category_name, weight, mut row) in assessments_raw {
+            for (category_name, weight, term, mut row) in assessments_raw {
+                if !term_filters.term_matches(term) {
+                    continue;
+                }
                 let deleted_like = is_assessment_deleted_like(
                     weight_method,
                     &category_weights,
@@ -542,6 +681,78 @@ fn handle_assessments_list(state: &mut AppState, req: &Request) -> serde_json::V
     }
 }
 
+fn handle_assessments_stats(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+
+    let exists: Option<i64> = match conn
+        .query_row(
+            "SELECT 1 FROM mark_sets WHERE id = ? AND class_id = ? AND deleted_at IS NULL",
+            (&mark_set_id, &class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if exists.is_none() {
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+
+    // Same per-assessment figures as calc.assessmentStats: no_mark is excluded
+    // entirely and zero counts as 0.
+    let ctx = calc::CalcContext {
+        conn,
+        class_id: &class_id,
+        mark_set_id: &mark_set_id,
+    };
+    let per_assessment =
+        match calc::compute_assessment_stats(&ctx, &calc::SummaryFilters::default()) {
+            Ok(v) => v,
+            Err(e) => return err(&req.id, &e.code, e.message, e.details),
+        };
+
+    let stats: Vec<serde_json::Value> = per_assessment
+        .iter()
+        .map(|a| {
+            let n = a.scored_count + a.zero_count;
+            if n == 0 {
+                return json!({
+                    "assessmentId": a.assessment_id,
+                    "mean": null,
+                    "median": null,
+                    "stdDev": null,
+                    "min": null,
+                    "max": null,
+                    "n": 0
+                });
+            }
+            json!({
+                "assessmentId": a.assessment_id,
+                "mean": a.avg_percent,
+                "median": a.median_percent,
+                "stdDev": a.std_dev_percent,
+                "min": a.min_percent,
+                "max": a.max_percent,
+                "n": n
+            })
+        })
+        .collect();
+
+    ok(&req.id, json!({ "stats": stats }))
+}
+
 fn handle_assessments_create(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -570,12 +781,17 @@ fn handle_assessments_create(state: &mut AppState, req: &Request) -> serde_json:
         .and_then(|v| v.as_str())
         .map(|s| s.trim().to_string())
         .and_then(|s| if s.is_empty() { None } else { Some(s) });
-    let category_name = req
+    let mut category_name = req
         .params
         .get("categoryName")
         .and_then(|v| v.as_str())
         .map(|s| s.trim().to_string())
         .and_then(|s| if s.is_empty() { None } else { Some(s) });
+    let strict_category = req
+        .params
+        .get("strictCategory")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
     let term = req.params.get("term").and_then(|v| v.as_i64());
     let legacy_type = req.params.get("legacyType").and_then(|v| v.as_i64());
     let weight = req.params.get("weight").and_then(|v| v.as_f64());
@@ -587,6 +803,31 @@ fn handle_assessments_create(state: &mut AppState, req: &Request) -> serde_json:
         Err(e) => return e.response(&req.id),
     }
 
+    // Lenient by default: legacy imports carry category labels with no
+    // matching row. Strict mode stores the category's own spelling.
+    if let (true, Some(name)) = (strict_category, category_name.as_deref()) {
+        let valid: Vec<String> = match conn
+            .prepare("SELECT name FROM categories WHERE mark_set_id = ? ORDER BY sort_order")
+            .and_then(|mut stmt| {
+                stmt.query_map([&mark_set_id], |r| r.get(0))
+                    .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            }) {
+            Ok(v) => v,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        match valid.iter().find(|v| v.trim().eq_ignore_ascii_case(name)) {
+            Some(matched) => category_name = Some(matched.trim().to_string()),
+            None => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "categoryName does not match a category of this mark set",
+                    Some(json!({ "categoryName": name, "validCategories": valid })),
+                )
+            }
+        }
+    }
+
     let append_idx: i64 = match conn.query_row(
         "SELECT COALESCE(MAX(idx), -1) + 1 FROM assessments WHERE mark_set_id = ?",
         [&mark_set_id],
@@ -695,7 +936,7 @@ fn handle_assessments_create(state: &mut AppState, req: &Request) -> serde_json:
     ok(&req.id, json!({ "assessmentId": assessment_id }))
 }
 
-fn handle_assessments_update(state: &mut AppState, req: &Request) -> serde_json::Value {
+fn handle_assessments_duplicate(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
@@ -708,12 +949,16 @@ fn handle_assessments_update(state: &mut AppState, req: &Request) -> serde_json:
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing markSetId", None),
     };
-    let assessment_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
+    let source_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing assessmentId", None),
     };
-    let Some(patch) = req.params.get("patch").and_then(|v| v.as_object()) else {
-        return err(&req.id, "bad_params", "missing/invalid patch", None);
+    let copy_scores = match req.params.get("copyScores") {
+        None => false,
+        Some(v) => match v.as_bool() {
+            Some(b) => b,
+            None => return err(&req.id, "bad_params", "copyScores must be boolean", None),
+        },
     };
 
     match mark_set_exists(conn, &class_id, &mark_set_id) {
@@ -721,46 +966,226 @@ fn handle_assessments_update(state: &mut AppState, req: &Request) -> serde_json:
         Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
         Err(e) => return e.response(&req.id),
     }
+    let source_idx: Option<i64> = match conn
+        .query_row(
+            "SELECT idx FROM assessments WHERE id = ? AND mark_set_id = ?",
+            (&source_id, &mark_set_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let Some(source_idx) = source_idx else {
+        return err(&req.id, "not_found", "assessment not found", None);
+    };
+    let idx = source_idx + 1;
 
-    let mut set_parts: Vec<String> = Vec::new();
-    let mut bind_values: Vec<Value> = Vec::new();
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
 
-    if let Some(v) = patch.get("date") {
-        if v.is_null() {
-            set_parts.push("date = ?".into());
-            bind_values.push(Value::Null);
-        } else if let Some(s) = v.as_str() {
-            let t = s.trim().to_string();
-            set_parts.push("date = ?".into());
-            if t.is_empty() {
-                bind_values.push(Value::Null);
-            } else {
-                bind_values.push(Value::Text(t));
+    // Make room right after the source, shifting later idx values up by 1 (descending).
+    {
+        let mut stmt = match tx.prepare(
+            "SELECT id, idx FROM assessments WHERE mark_set_id = ? AND idx >= ? ORDER BY idx DESC",
+        ) {
+            Ok(s) => s,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        let rows: Vec<(String, i64)> = match stmt
+            .query_map((&mark_set_id, idx), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(v) => v,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        let mut up = match tx.prepare("UPDATE assessments SET idx = ? WHERE id = ?") {
+            Ok(s) => s,
+            Err(e) => {
+                return err(
+                    &req.id,
+                    "db_update_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "assessments" })),
+                )
+            }
+        };
+        for (aid, cur_idx) in rows {
+            if let Err(e) = up.execute((cur_idx + 1, &aid)) {
+                return err(
+                    &req.id,
+                    "db_update_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "assessments" })),
+                );
             }
-        } else {
-            return err(
-                &req.id,
-                "bad_params",
-                "patch.date must be a string or null",
-                None,
-            );
         }
     }
-    if let Some(v) = patch.get("categoryName") {
-        if v.is_null() {
-            set_parts.push("category_name = ?".into());
-            bind_values.push(Value::Null);
-        } else if let Some(s) = v.as_str() {
-            let t = s.trim().to_string();
-            set_parts.push("category_name = ?".into());
-            if t.is_empty() {
-                bind_values.push(Value::Null);
-            } else {
-                bind_values.push(Value::Text(t));
-            }
-        } else {
-            return err(
-                &req.id,
+
+    let assessment_id = Uuid::new_v4().to_string();
+    if let Err(e) = tx.execute(
+        "INSERT INTO assessments(
+           id,
+           mark_set_id,
+           idx,
+           date,
+           category_name,
+           title,
+           term,
+           legacy_kind,
+           legacy_type,
+           weight,
+           out_of
+         )
+         SELECT ?, mark_set_id, ?, date, category_name, title, term, legacy_kind, legacy_type, weight, out_of
+         FROM assessments WHERE id = ?",
+        (&assessment_id, idx, &source_id),
+    ) {
+        return err(
+            &req.id,
+            "db_insert_failed",
+            e.to_string(),
+            Some(json!({ "table": "assessments" })),
+        );
+    }
+
+    let mut scores_copied = 0usize;
+    if copy_scores {
+        let mut stmt = match tx.prepare(
+            "SELECT student_id, raw_value, status, remark FROM scores WHERE assessment_id = ?",
+        ) {
+            Ok(s) => s,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        let source_scores: Vec<(String, Option<f64>, String, Option<String>)> = match stmt
+            .query_map([&source_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(v) => v,
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        };
+        let mut ins = match tx.prepare(
+            "INSERT INTO scores(id, assessment_id, student_id, raw_value, status, remark)
+             VALUES(?, ?, ?, ?, ?, ?)",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                return err(
+                    &req.id,
+                    "db_insert_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "scores" })),
+                )
+            }
+        };
+        for (student_id, raw_value, status, remark) in source_scores {
+            let score_id = Uuid::new_v4().to_string();
+            if let Err(e) = ins.execute((
+                &score_id,
+                &assessment_id,
+                &student_id,
+                raw_value,
+                &status,
+                remark.as_deref(),
+            )) {
+                return err(
+                    &req.id,
+                    "db_insert_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "scores" })),
+                );
+            }
+            scores_copied += 1;
+        }
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(
+        &req.id,
+        json!({
+            "assessmentId": assessment_id,
+            "idx": idx,
+            "scoresCopied": scores_copied
+        }),
+    )
+}
+
+fn handle_assessments_update(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+    let assessment_id = match req.params.get("assessmentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing assessmentId", None),
+    };
+    let Some(patch) = req.params.get("patch").and_then(|v| v.as_object()) else {
+        return err(&req.id, "bad_params", "missing/invalid patch", None);
+    };
+
+    match mark_set_exists(conn, &class_id, &mark_set_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let mut set_parts: Vec<String> = Vec::new();
+    let mut bind_values: Vec<Value> = Vec::new();
+
+    if let Some(v) = patch.get("date") {
+        if v.is_null() {
+            set_parts.push("date = ?".into());
+            bind_values.push(Value::Null);
+        } else if let Some(s) = v.as_str() {
+            let t = s.trim().to_string();
+            set_parts.push("date = ?".into());
+            if t.is_empty() {
+                bind_values.push(Value::Null);
+            } else {
+                bind_values.push(Value::Text(t));
+            }
+        } else {
+            return err(
+                &req.id,
+                "bad_params",
+                "patch.date must be a string or null",
+                None,
+            );
+        }
+    }
+    if let Some(v) = patch.get("categoryName") {
+        if v.is_null() {
+            set_parts.push("category_name = ?".into());
+            bind_values.push(Value::Null);
+        } else if let Some(s) = v.as_str() {
+            let t = s.trim().to_string();
+            set_parts.push("category_name = ?".into());
+            if t.is_empty() {
+                bind_values.push(Value::Null);
+            } else {
+                bind_values.push(Value::Text(t));
+            }
+        } else {
+            return err(
+                &req.id,
                 "bad_params",
                 "patch.categoryName must be a string or null",
                 None,
@@ -921,16 +1346,18 @@ fn handle_assessments_delete(state: &mut AppState, req: &Request) -> serde_json:
         Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
     };
 
-    if let Err(e) = tx.execute(
-        "DELETE FROM scores WHERE assessment_id = ?",
-        [&assessment_id],
-    ) {
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "scores" })),
-        );
+    for table in ["scores", "score_history"] {
+        if let Err(e) = tx.execute(
+            &format!("DELETE FROM {} WHERE assessment_id = ?", table),
+            [&assessment_id],
+        ) {
+            return err(
+                &req.id,
+                "db_delete_failed",
+                e.to_string(),
+                Some(json!({ "table": table })),
+            );
+        }
     }
 
     let changed = match tx.execute(
@@ -1763,6 +2190,46 @@ fn handle_markset_settings_get(state: &mut AppState, req: &Request) -> serde_jso
     )
 }
 
+/// `mark_sets.weight_method` values: entry, category or equal weighting.
+const WEIGHT_METHODS: std::ops::RangeInclusive<i64> = 0..=2;
+/// `mark_sets.calc_method` values; see the mapping in `calc`.
+const CALC_METHODS: std::ops::RangeInclusive<i64> = 0..=5;
+
+/// Validates a patch's `weightMethod`/`calcMethod` and adds them to the UPDATE
+/// being built, for both `markset.settings.update` and `marksets.update`.
+fn push_mark_set_method_patch(
+    patch: &serde_json::Map<String, serde_json::Value>,
+    set_parts: &mut Vec<String>,
+    bind_values: &mut Vec<Value>,
+) -> Result<(), HandlerErr> {
+    let bad = |message: &str| HandlerErr {
+        code: "bad_params",
+        message: message.to_string(),
+        details: None,
+    };
+    if let Some(v) = patch.get("weightMethod") {
+        let Some(n) = v.as_i64() else {
+            return Err(bad("patch.weightMethod must be integer"));
+        };
+        if !WEIGHT_METHODS.contains(&n) {
+            return Err(bad("patch.weightMethod must be 0, 1, or 2"));
+        }
+        set_parts.push("weight_method = ?".into());
+        bind_values.push(Value::Integer(n));
+    }
+    if let Some(v) = patch.get("calcMethod") {
+        let Some(n) = v.as_i64() else {
+            return Err(bad("patch.calcMethod must be integer"));
+        };
+        if !CALC_METHODS.contains(&n) {
+            return Err(bad("patch.calcMethod must be 0..5"));
+        }
+        set_parts.push("calc_method = ?".into());
+        bind_values.push(Value::Integer(n));
+    }
+    Ok(())
+}
+
 fn handle_markset_settings_update(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -1888,40 +2355,8 @@ fn handle_markset_settings_update(state: &mut AppState, req: &Request) -> serde_
             );
         }
     }
-    if let Some(v) = patch.get("weightMethod") {
-        let Some(n) = v.as_i64() else {
-            return err(
-                &req.id,
-                "bad_params",
-                "patch.weightMethod must be integer",
-                None,
-            );
-        };
-        if !(0..=2).contains(&n) {
-            return err(
-                &req.id,
-                "bad_params",
-                "patch.weightMethod must be 0, 1, or 2",
-                None,
-            );
-        }
-        set_parts.push("weight_method = ?".into());
-        bind_values.push(Value::Integer(n));
-    }
-    if let Some(v) = patch.get("calcMethod") {
-        let Some(n) = v.as_i64() else {
-            return err(
-                &req.id,
-                "bad_params",
-                "patch.calcMethod must be integer",
-                None,
-            );
-        };
-        if !(0..=4).contains(&n) {
-            return err(&req.id, "bad_params", "patch.calcMethod must be 0..4", None);
-        }
-        set_parts.push("calc_method = ?".into());
-        bind_values.push(Value::Integer(n));
+    if let Err(e) = push_mark_set_method_patch(patch, &mut set_parts, &mut bind_values) {
+        return e.response(&req.id);
     }
 
     if set_parts.is_empty() {
@@ -1994,9 +2429,9 @@ fn ensure_mark_set_code_unique(
 
     if existing.is_some() {
         return Err(HandlerErr {
-            code: "bad_params",
+            code: "duplicate_code",
             message: "mark set code already exists in class".into(),
-            details: Some(json!({ "field": "code" })),
+            details: Some(json!({ "code": code })),
         });
     }
     Ok(())
@@ -2051,7 +2486,21 @@ fn handle_marksets_create(state: &mut AppState, req: &Request) -> serde_json::Va
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| code.clone());
 
-    let weight = req.params.get("weight").and_then(|v| v.as_f64());
+    let weight = match req.params.get("weight") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_f64() {
+            Some(n) if n >= 0.0 => Some(n),
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "weight must be a non-negative number or null",
+                    None,
+                )
+            }
+        },
+    };
     let full_code = match normalized_opt_str(req.params.get("fullCode"), "fullCode") {
         Ok(v) => v,
         Err(e) => return e.response(&req.id),
@@ -2078,7 +2527,7 @@ fn handle_marksets_create(state: &mut AppState, req: &Request) -> serde_json::Va
         .get("weightMethod")
         .and_then(|v| v.as_i64())
         .unwrap_or(1);
-    if !(0..=2).contains(&weight_method) {
+    if !WEIGHT_METHODS.contains(&weight_method) {
         return err(
             &req.id,
             "bad_params",
@@ -2091,8 +2540,8 @@ fn handle_marksets_create(state: &mut AppState, req: &Request) -> serde_json::Va
         .get("calcMethod")
         .and_then(|v| v.as_i64())
         .unwrap_or(0);
-    if !(0..=4).contains(&calc_method) {
-        return err(&req.id, "bad_params", "calcMethod must be 0..4", None);
+    if !CALC_METHODS.contains(&calc_method) {
+        return err(&req.id, "bad_params", "calcMethod must be 0..5", None);
     }
     let make_default = req
         .params
@@ -2139,126 +2588,643 @@ fn handle_marksets_create(state: &mut AppState, req: &Request) -> serde_json::Va
             let _ = tx.rollback();
             return err(
                 &req.id,
-                "db_update_failed",
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "mark_sets" })),
+            );
+        }
+    }
+
+    if let Err(e) = tx.execute(
+        "INSERT INTO mark_sets(
+            id,
+            class_id,
+            code,
+            file_prefix,
+            description,
+            weight,
+            source_filename,
+            sort_order,
+            full_code,
+            room,
+            day,
+            period,
+            weight_method,
+            calc_method,
+            is_default,
+            deleted_at,
+            block_title
+        ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, ?)",
+        (
+            &mark_set_id,
+            &class_id,
+            &code,
+            &file_prefix,
+            &description,
+            weight,
+            Option::<&str>::None,
+            sort_order,
+            full_code.as_deref(),
+            room.as_deref(),
+            day.as_deref(),
+            period.as_deref(),
+            weight_method,
+            calc_method,
+            if make_default { 1 } else { 0 },
+            block_title.as_deref(),
+        ),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_insert_failed",
+            e.to_string(),
+            Some(json!({ "table": "mark_sets" })),
+        );
+    }
+
+    for (idx, item) in starter_categories.iter().enumerate() {
+        let Some(obj) = item.as_object() else {
+            continue;
+        };
+        let Some(name) = obj.get("name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let weight = obj.get("weight").and_then(|v| v.as_f64());
+        let category_id = Uuid::new_v4().to_string();
+        if let Err(e) = tx.execute(
+            "INSERT INTO categories(id, mark_set_id, name, weight, sort_order) VALUES(?, ?, ?, ?, ?)",
+            (&category_id, &mark_set_id, name, weight, idx as i64),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_insert_failed",
+                e.to_string(),
+                Some(json!({ "table": "categories" })),
+            );
+        }
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "markSetId": mark_set_id }))
+}
+
+fn handle_marksets_update(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+    let Some(patch) = req.params.get("patch").and_then(|v| v.as_object()) else {
+        return err(&req.id, "bad_params", "missing/invalid patch", None);
+    };
+
+    match mark_set_exists(conn, &class_id, &mark_set_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let mut set_parts: Vec<String> = Vec::new();
+    let mut bind_values: Vec<Value> = Vec::new();
+
+    if let Some(v) = patch.get("code") {
+        let Some(s) = v.as_str() else {
+            return err(&req.id, "bad_params", "patch.code must be a string", None);
+        };
+        let s = s.trim().to_string();
+        if s.is_empty() {
+            return err(&req.id, "bad_params", "code must not be empty", None);
+        }
+        if s.len() > 15 {
+            return err(
+                &req.id,
+                "bad_params",
+                "code must be 15 chars or fewer",
+                None,
+            );
+        }
+        if let Err(e) = ensure_mark_set_code_unique(conn, &class_id, &s, Some(&mark_set_id)) {
+            return e.response(&req.id);
+        }
+        set_parts.push("code = ?".into());
+        bind_values.push(Value::Text(s));
+    }
+    if let Some(v) = patch.get("description") {
+        let Some(s) = v.as_str() else {
+            return err(
+                &req.id,
+                "bad_params",
+                "patch.description must be a string",
+                None,
+            );
+        };
+        let s = s.trim().to_string();
+        if s.is_empty() {
+            return err(&req.id, "bad_params", "description must not be empty", None);
+        }
+        set_parts.push("description = ?".into());
+        bind_values.push(Value::Text(s));
+    }
+    if let Some(v) = patch.get("weight") {
+        if v.is_null() {
+            set_parts.push("weight = ?".into());
+            bind_values.push(Value::Null);
+        } else if let Some(n) = v.as_f64().filter(|n| *n >= 0.0) {
+            set_parts.push("weight = ?".into());
+            bind_values.push(Value::Real(n));
+        } else {
+            return err(
+                &req.id,
+                "bad_params",
+                "patch.weight must be a non-negative number or null",
+                None,
+            );
+        }
+    }
+    if let Err(e) = push_mark_set_method_patch(patch, &mut set_parts, &mut bind_values) {
+        return e.response(&req.id);
+    }
+
+    if set_parts.is_empty() {
+        return err(
+            &req.id,
+            "bad_params",
+            "patch must include at least one field",
+            None,
+        );
+    }
+
+    let sql = format!(
+        "UPDATE mark_sets SET {} WHERE id = ? AND class_id = ? AND deleted_at IS NULL",
+        set_parts.join(", ")
+    );
+    bind_values.push(Value::Text(mark_set_id.clone()));
+    bind_values.push(Value::Text(class_id.clone()));
+
+    let changed = match conn.execute(&sql, params_from_iter(bind_values)) {
+        Ok(v) => v,
+        Err(e) => {
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "mark_sets" })),
+            )
+        }
+    };
+    if changed == 0 {
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
+fn handle_marksets_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+    let permanent = req
+        .params
+        .get("permanent")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if permanent {
+        return purge_mark_set(conn, req, &class_id, &mark_set_id);
+    }
+    match mark_set_exists(conn, &class_id, &mark_set_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    let changed = match tx.execute(
+        "UPDATE mark_sets
+         SET deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ','now'),
+             is_default = 0
+         WHERE id = ? AND class_id = ?",
+        (&mark_set_id, &class_id),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "mark_sets" })),
+            );
+        }
+    };
+    if changed == 0 {
+        let _ = tx.rollback();
+        return err(&req.id, "not_found", "mark set not found", None);
+    }
+
+    let next_default: Option<String> = match tx
+        .query_row(
+            "SELECT id FROM mark_sets
+             WHERE class_id = ? AND deleted_at IS NULL
+             ORDER BY sort_order LIMIT 1",
+            [&class_id],
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(&req.id, "db_query_failed", e.to_string(), None);
+        }
+    };
+    if let Some(next_id) = next_default {
+        if let Err(e) = tx.execute(
+            "UPDATE mark_sets SET is_default = 1 WHERE id = ?",
+            [&next_id],
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "mark_sets" })),
+            );
+        }
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
+/// Drops the membership bit for a removed mark set so later bits keep lining up with
+/// `sort_order`. Returns None when the mask is TBA/implicit or does not reach `idx`.
+fn mask_without_bit(mask: &str, idx: usize) -> Option<String> {
+    let up = mask.trim().to_ascii_uppercase();
+    if up.is_empty() || !up.chars().all(|ch| ch == '0' || ch == '1') || idx >= up.len() {
+        return None;
+    }
+    let mut out = up;
+    out.remove(idx);
+    Some(out)
+}
+
+fn purge_mark_set(
+    conn: &Connection,
+    req: &Request,
+    class_id: &str,
+    mark_set_id: &str,
+) -> serde_json::Value {
+    // Permanent delete also applies to mark sets already in the soft-deleted state.
+    let row: Option<(i64, i64)> = match conn
+        .query_row(
+            "SELECT sort_order, is_default FROM mark_sets WHERE id = ? AND class_id = ?",
+            (mark_set_id, class_id),
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let Some((sort_order, was_default)) = row else {
+        return err(&req.id, "not_found", "mark set not found", None);
+    };
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+
+    // Explicitly delete in dependency order (no ON DELETE CASCADE), mirroring classes.delete.
+    let steps: [(&str, &str, &str); 7] = [
+        (
+            "scores",
+            "db_delete_failed",
+            "DELETE FROM scores
+             WHERE assessment_id IN (SELECT id FROM assessments WHERE mark_set_id = ?)",
+        ),
+        (
+            "score_history",
+            "db_delete_failed",
+            "DELETE FROM score_history
+             WHERE assessment_id IN (SELECT id FROM assessments WHERE mark_set_id = ?)",
+        ),
+        (
+            "comment_set_remarks",
+            "db_delete_failed",
+            "DELETE FROM comment_set_remarks
+             WHERE comment_set_index_id IN (
+               SELECT id FROM comment_set_indexes WHERE mark_set_id = ?
+             )",
+        ),
+        (
+            "comment_set_indexes",
+            "db_delete_failed",
+            "DELETE FROM comment_set_indexes WHERE mark_set_id = ?",
+        ),
+        (
+            "loaned_items",
+            "db_update_failed",
+            "UPDATE loaned_items SET mark_set_id = NULL WHERE mark_set_id = ?",
+        ),
+        (
+            "assessments",
+            "db_delete_failed",
+            "DELETE FROM assessments WHERE mark_set_id = ?",
+        ),
+        (
+            "categories",
+            "db_delete_failed",
+            "DELETE FROM categories WHERE mark_set_id = ?",
+        ),
+    ];
+    for (table, code, sql) in steps {
+        if let Err(e) = tx.execute(sql, [mark_set_id]) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                code,
                 e.to_string(),
-                Some(json!({ "table": "mark_sets" })),
+                Some(json!({ "table": table })),
             );
         }
     }
 
     if let Err(e) = tx.execute(
-        "INSERT INTO mark_sets(
-            id,
-            class_id,
-            code,
-            file_prefix,
-            description,
-            weight,
-            source_filename,
-            sort_order,
-            full_code,
-            room,
-            day,
-            period,
-            weight_method,
-            calc_method,
-            is_default,
-            deleted_at,
-            block_title
-        ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, ?)",
-        (
-            &mark_set_id,
-            &class_id,
-            &code,
-            &file_prefix,
-            &description,
-            weight,
-            Option::<&str>::None,
-            sort_order,
-            full_code.as_deref(),
-            room.as_deref(),
-            day.as_deref(),
-            period.as_deref(),
-            weight_method,
-            calc_method,
-            if make_default { 1 } else { 0 },
-            block_title.as_deref(),
-        ),
+        "DELETE FROM mark_sets WHERE id = ? AND class_id = ?",
+        (mark_set_id, class_id),
     ) {
         let _ = tx.rollback();
         return err(
             &req.id,
-            "db_insert_failed",
+            "db_delete_failed",
             e.to_string(),
             Some(json!({ "table": "mark_sets" })),
         );
     }
 
-    for (idx, item) in starter_categories.iter().enumerate() {
-        let Some(obj) = item.as_object() else {
-            continue;
-        };
-        let Some(name) = obj.get("name").and_then(|v| v.as_str()) else {
+    // Keep sort_order contiguous.
+    if let Err(e) = tx.execute(
+        "UPDATE mark_sets
+         SET sort_order = sort_order - 1
+         WHERE class_id = ? AND sort_order > ?",
+        (class_id, sort_order),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_update_failed",
+            e.to_string(),
+            Some(json!({ "table": "mark_sets" })),
+        );
+    }
+
+    // Student membership masks are positional by sort_order; shift them with the renumbering.
+    let masks: Vec<(String, Option<String>)> = match tx
+        .prepare("SELECT id, mark_set_mask FROM students WHERE class_id = ?")
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        }) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(&req.id, "db_query_failed", e.to_string(), None);
+        }
+    };
+    let bit_idx = usize::try_from(sort_order).unwrap_or(usize::MAX);
+    for (student_id, mask) in masks {
+        let Some(next) = mask.as_deref().and_then(|m| mask_without_bit(m, bit_idx)) else {
             continue;
         };
-        let name = name.trim();
-        if name.is_empty() {
-            continue;
+        if let Err(e) = tx.execute(
+            "UPDATE students SET mark_set_mask = ? WHERE id = ?",
+            (&next, &student_id),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "students" })),
+            );
         }
-        let weight = obj.get("weight").and_then(|v| v.as_f64());
-        let category_id = Uuid::new_v4().to_string();
+    }
+
+    if was_default != 0 {
         if let Err(e) = tx.execute(
-            "INSERT INTO categories(id, mark_set_id, name, weight, sort_order) VALUES(?, ?, ?, ?, ?)",
-            (&category_id, &mark_set_id, name, weight, idx as i64),
+            "UPDATE mark_sets SET is_default = 1
+             WHERE id = (
+               SELECT id FROM mark_sets
+               WHERE class_id = ? AND deleted_at IS NULL
+               ORDER BY sort_order LIMIT 1
+             )",
+            [class_id],
         ) {
             let _ = tx.rollback();
             return err(
                 &req.id,
-                "db_insert_failed",
+                "db_update_failed",
                 e.to_string(),
-                Some(json!({ "table": "categories" })),
+                Some(json!({ "table": "mark_sets" })),
             );
         }
     }
 
+    if let Err(e) = db::settings_delete(&tx, &hide_deleted_pref_key(class_id, mark_set_id)) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "workspace_settings" })),
+        );
+    }
+
     if let Err(e) = tx.commit() {
         return err(&req.id, "db_commit_failed", e.to_string(), None);
     }
 
-    ok(&req.id, json!({ "markSetId": mark_set_id }))
+    ok(&req.id, json!({ "ok": true, "permanent": true }))
 }
 
-fn handle_marksets_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+/// Rebuilds a positional membership mask after a reorder: bit `i` of the result is the old
+/// bit at `old_positions[i]`. Missing bits default to included, matching `is_valid_kid`.
+fn mask_permuted(mask: &str, old_positions: &[usize]) -> Option<String> {
+    let up = mask.trim().to_ascii_uppercase();
+    if up.is_empty() || !up.chars().all(|ch| ch == '0' || ch == '1') {
+        return None;
+    }
+    let bytes = up.as_bytes();
+    let out: String = old_positions
+        .iter()
+        .map(|&p| {
+            if bytes.get(p) == Some(&b'0') {
+                '0'
+            } else {
+                '1'
+            }
+        })
+        .collect();
+    if out == up {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+fn handle_marksets_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
+
     let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing classId", None),
     };
-    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
-        Some(v) => v.to_string(),
-        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    let Some(arr) = req
+        .params
+        .get("orderedMarkSetIds")
+        .and_then(|v| v.as_array())
+    else {
+        return err(
+            &req.id,
+            "bad_params",
+            "missing/invalid orderedMarkSetIds",
+            None,
+        );
     };
-    match mark_set_exists(conn, &class_id, &mark_set_id) {
+    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
+    for v in arr {
+        let Some(s) = v.as_str() else {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedMarkSetIds must be strings",
+                None,
+            );
+        };
+        ordered.push(s.to_string());
+    }
+
+    match class_exists(conn, &class_id) {
         Ok(true) => {}
-        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Ok(false) => return err(&req.id, "not_found", "class not found", None),
         Err(e) => return e.response(&req.id),
     }
 
+    let mut stmt = match conn.prepare(
+        "SELECT id, sort_order, deleted_at IS NOT NULL
+         FROM mark_sets
+         WHERE class_id = ?
+         ORDER BY sort_order",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let current: Vec<(String, i64, bool)> = match stmt
+        .query_map([&class_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    drop(stmt);
+
+    let active_ids: Vec<&str> = current
+        .iter()
+        .filter(|(_, _, deleted)| !deleted)
+        .map(|(id, _, _)| id.as_str())
+        .collect();
+    if ordered.len() != active_ids.len() {
+        return err(
+            &req.id,
+            "bad_params",
+            "orderedMarkSetIds must be a permutation of the class mark sets",
+            Some(json!({ "expected": active_ids.len(), "got": ordered.len() })),
+        );
+    }
+
+    let active_set: HashSet<&str> = active_ids.iter().copied().collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for id in &ordered {
+        if !seen.insert(id.as_str()) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedMarkSetIds contains duplicates",
+                Some(json!({ "markSetId": id })),
+            );
+        }
+        if !active_set.contains(id.as_str()) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedMarkSetIds contains unknown markSetId",
+                Some(json!({ "markSetId": id })),
+            );
+        }
+    }
+
+    // Soft-deleted mark sets keep their relative order after the active ones so that
+    // undelete and membership masks still have a slot for them.
+    let mut final_order: Vec<&str> = ordered.iter().map(|s| s.as_str()).collect();
+    final_order.extend(
+        current
+            .iter()
+            .filter(|(_, _, deleted)| *deleted)
+            .map(|(id, _, _)| id.as_str()),
+    );
+    let old_pos: HashMap<&str, usize> = current
+        .iter()
+        .map(|(id, sort_order, _)| (id.as_str(), usize::try_from(*sort_order).unwrap_or(0)))
+        .collect();
+    let old_positions: Vec<usize> = final_order
+        .iter()
+        .map(|id| old_pos.get(id).copied().unwrap_or(0))
+        .collect();
+
     let tx = match conn.unchecked_transaction() {
         Ok(t) => t,
         Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
     };
-    let changed = match tx.execute(
-        "UPDATE mark_sets
-         SET deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ','now'),
-             is_default = 0
-         WHERE id = ? AND class_id = ?",
-        (&mark_set_id, &class_id),
-    ) {
-        Ok(v) => v,
-        Err(e) => {
+
+    for (i, mid) in final_order.iter().enumerate() {
+        if let Err(e) = tx.execute(
+            "UPDATE mark_sets SET sort_order = ? WHERE id = ? AND class_id = ?",
+            (i as i64, mid, &class_id),
+        ) {
             let _ = tx.rollback();
             return err(
                 &req.id,
@@ -2267,39 +3233,37 @@ fn handle_marksets_delete(state: &mut AppState, req: &Request) -> serde_json::Va
                 Some(json!({ "table": "mark_sets" })),
             );
         }
-    };
-    if changed == 0 {
-        let _ = tx.rollback();
-        return err(&req.id, "not_found", "mark set not found", None);
     }
 
-    let next_default: Option<String> = match tx
-        .query_row(
-            "SELECT id FROM mark_sets
-             WHERE class_id = ? AND deleted_at IS NULL
-             ORDER BY sort_order LIMIT 1",
-            [&class_id],
-            |r| r.get(0),
-        )
-        .optional()
-    {
+    let masks: Vec<(String, Option<String>)> = match tx
+        .prepare("SELECT id, mark_set_mask FROM students WHERE class_id = ?")
+        .and_then(|mut stmt| {
+            stmt.query_map([&class_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        }) {
         Ok(v) => v,
         Err(e) => {
             let _ = tx.rollback();
             return err(&req.id, "db_query_failed", e.to_string(), None);
         }
     };
-    if let Some(next_id) = next_default {
+    for (student_id, mask) in masks {
+        let Some(next) = mask
+            .as_deref()
+            .and_then(|m| mask_permuted(m, &old_positions))
+        else {
+            continue;
+        };
         if let Err(e) = tx.execute(
-            "UPDATE mark_sets SET is_default = 1 WHERE id = ?",
-            [&next_id],
+            "UPDATE students SET mark_set_mask = ? WHERE id = ?",
+            (&next, &student_id),
         ) {
             let _ = tx.rollback();
             return err(
                 &req.id,
                 "db_update_failed",
                 e.to_string(),
-                Some(json!({ "table": "mark_sets" })),
+                Some(json!({ "table": "students" })),
             );
         }
     }
@@ -2406,6 +3370,32 @@ fn handle_marksets_set_default(state: &mut AppState, req: &Request) -> serde_jso
     ok(&req.id, json!({ "ok": true }))
 }
 
+fn handle_marksets_weight_check(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let mark_set_id = match req.params.get("markSetId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing markSetId", None),
+    };
+
+    match mark_set_exists(conn, &class_id, &mark_set_id) {
+        Ok(true) => {}
+        Ok(false) => return err(&req.id, "not_found", "mark set not found", None),
+        Err(e) => return e.response(&req.id),
+    }
+
+    match calc::validate_category_weights(conn, &mark_set_id) {
+        Ok(report) => ok(&req.id, json!(report)),
+        Err(e) => err(&req.id, &e.code, e.message, e.details),
+    }
+}
+
 fn handle_marksets_clone(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -3379,6 +4369,10 @@ fn handle_marksets_transfer_apply(state: &mut AppState, req: &Request) -> serde_
                 }));
                 continue;
             };
+            let old = match grid::read_score_cell(&tx, &target_assessment_id, target_student_id) {
+                Ok(v) => v,
+                Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+            };
             let score_id = Uuid::new_v4().to_string();
             if let Err(e) = tx.execute(
                 "INSERT INTO scores(id, assessment_id, student_id, raw_value, status, remark)
@@ -3403,6 +4397,20 @@ fn handle_marksets_transfer_apply(state: &mut AppState, req: &Request) -> serde_
                     Some(json!({ "table": "scores" })),
                 );
             }
+            if let Err(e) = grid::record_score_history(
+                &tx,
+                &target_assessment_id,
+                target_student_id,
+                &old,
+                &Some((raw_value, status)),
+            ) {
+                return err(
+                    &req.id,
+                    "db_insert_failed",
+                    e.to_string(),
+                    Some(json!({ "table": "score_history" })),
+                );
+            }
             scores_upserted += 1;
             if remark
                 .as_deref()
@@ -3821,6 +4829,41 @@ fn handle_assessments_bulk_update(state: &mut AppState, req: &Request) -> serde_
     )
 }
 
+pub const METHODS: &[&str] = &[
+    "marks.pref.hideDeleted.get",
+    "marks.pref.hideDeleted.set",
+    "entries.delete",
+    "entries.clone.save",
+    "entries.clone.peek",
+    "entries.clone.apply",
+    "marksets.create",
+    "marksets.update",
+    "marksets.delete",
+    "marksets.undelete",
+    "marksets.reorder",
+    "marksets.setDefault",
+    "marksets.weightCheck",
+    "marksets.clone",
+    "marksets.transfer.preview",
+    "marksets.transfer.apply",
+    "categories.list",
+    "categories.create",
+    "categories.update",
+    "categories.delete",
+    "categories.reorder",
+    "assessments.list",
+    "assessments.create",
+    "assessments.duplicate",
+    "assessments.bulkCreate",
+    "assessments.update",
+    "assessments.bulkUpdate",
+    "assessments.delete",
+    "assessments.reorder",
+    "assessments.stats",
+    "markset.settings.get",
+    "markset.settings.update",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "marks.pref.hideDeleted.get" => Some(handle_marks_pref_hide_deleted_get(state, req)),
@@ -3830,9 +4873,12 @@ pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Val
         "entries.clone.peek" => Some(handle_entries_clone_peek(state, req)),
         "entries.clone.apply" => Some(handle_entries_clone_apply(state, req)),
         "marksets.create" => Some(handle_marksets_create(state, req)),
+        "marksets.update" => Some(handle_marksets_update(state, req)),
         "marksets.delete" => Some(handle_marksets_delete(state, req)),
         "marksets.undelete" => Some(handle_marksets_undelete(state, req)),
+        "marksets.reorder" => Some(handle_marksets_reorder(state, req)),
         "marksets.setDefault" => Some(handle_marksets_set_default(state, req)),
+        "marksets.weightCheck" => Some(handle_marksets_weight_check(state, req)),
         "marksets.clone" => Some(handle_marksets_clone(state, req)),
         "marksets.transfer.preview" => Some(handle_marksets_transfer_preview(state, req)),
         "marksets.transfer.apply" => Some(handle_marksets_transfer_apply(state, req)),
@@ -3840,13 +4886,16 @@ pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Val
         "categories.create" => Some(handle_categories_create(state, req)),
         "categories.update" => Some(handle_categories_update(state, req)),
         "categories.delete" => Some(handle_categories_delete(state, req)),
+        "categories.reorder" => Some(handle_categories_reorder(state, req)),
         "assessments.list" => Some(handle_assessments_list(state, req)),
         "assessments.create" => Some(handle_assessments_create(state, req)),
+        "assessments.duplicate" => Some(handle_assessments_duplicate(state, req)),
         "assessments.bulkCreate" => Some(handle_assessments_bulk_create(state, req)),
         "assessments.update" => Some(handle_assessments_update(state, req)),
         "assessments.bulkUpdate" => Some(handle_assessments_bulk_update(state, req)),
         "assessments.delete" => Some(handle_assessments_delete(state, req)),
         "assessments.reorder" => Some(handle_assessments_reorder(state, req)),
+        "assessments.stats" => Some(handle_assessments_stats(state, req)),
         "markset.settings.get" => Some(handle_markset_settings_get(state, req)),
         "markset.settings.update" => Some(handle_markset_settings_update(state, req)),
         _ => None,
@@ -5,6 +5,7 @@ pub mod backup_exchange;
 pub mod classes;
 pub mod comments;
 pub mod core;
+pub mod edits;
 pub mod grid;
 pub mod import_legacy;
 pub mod integrations;
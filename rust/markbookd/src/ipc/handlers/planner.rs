@@ -2515,6 +2515,32 @@ pub fn reports_time_management_model(
     generate_time_management_model(conn, class_id, options)
 }
 
+pub const METHODS: &[&str] = &[
+    "planner.units.list",
+    "planner.units.open",
+    "planner.units.create",
+    "planner.units.update",
+    "planner.units.reorder",
+    "planner.units.archive",
+    "planner.units.clone",
+    "planner.lessons.list",
+    "planner.lessons.open",
+    "planner.lessons.create",
+    "planner.lessons.update",
+    "planner.lessons.reorder",
+    "planner.lessons.archive",
+    "planner.lessons.copyForward",
+    "planner.lessons.bulkAssignUnit",
+    "planner.publish.list",
+    "planner.publish.preview",
+    "planner.publish.commit",
+    "planner.publish.updateStatus",
+    "courseDescription.getProfile",
+    "courseDescription.updateProfile",
+    "courseDescription.generateModel",
+    "courseDescription.timeManagementModel",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "planner.units.list" => Some(handle_units_list(state, req)),
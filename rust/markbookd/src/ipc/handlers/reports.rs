@@ -1,11 +1,14 @@
 use crate::calc;
+use crate::pdf;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
+use crate::xlsx::xml_escape;
 use rusqlite::{params_from_iter, types::Value, Connection, OptionalExtension};
 use serde_json::json;
 use std::collections::HashMap;
+use uuid::Uuid;
 
-use super::{analytics, assets, attendance, planner};
+use super::{analytics, assets, attendance, grid, planner};
 
 fn required_str(req: &Request, key: &str) -> Result<String, serde_json::Value> {
     req.params
@@ -194,6 +197,438 @@ fn handle_calc_markset_summary(state: &mut AppState, req: &Request) -> serde_jso
     }
 }
 
+fn handle_calc_markset_averages(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mark_set_id = match required_str(req, "markSetId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let filters = match calc::parse_term_filters(&req.params) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+
+    if let Some(student_id) = req.params.get("studentId").and_then(|v| v.as_str()) {
+        match grid::student_in_class(conn, &class_id, student_id) {
+            Ok(true) => {}
+            Ok(false) => return err(&req.id, "not_found", "student not found", None),
+            Err(e) => return e.response(&req.id),
+        }
+        return match calc::markset_average(conn, &mark_set_id, student_id, &filters) {
+            Ok(percent) => ok(
+                &req.id,
+                json!({ "averages": [{ "studentId": student_id, "percent": percent }] }),
+            ),
+            Err(e) => calc_err(req, e),
+        };
+    }
+
+    match calc::markset_averages(&calc_context(conn, &class_id, &mark_set_id), &filters) {
+        Ok(per_student) => {
+            let averages: Vec<serde_json::Value> = per_student
+                .iter()
+                .map(|s| json!({ "studentId": s.student_id, "percent": s.final_mark }))
+                .collect();
+            ok(&req.id, json!({ "averages": averages }))
+        }
+        Err(e) => calc_err(req, e),
+    }
+}
+
+fn handle_calc_class_rank(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mark_set_id = match required_str(req, "markSetId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let filters = match calc::parse_term_filters(&req.params) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+
+    let summary = match calc::compute_mark_set_summary(
+        &calc_context(conn, &class_id, &mark_set_id),
+        &filters,
+    ) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+    let percents: Vec<Option<f64>> = summary.per_student.iter().map(|s| s.final_mark).collect();
+    let ranks = calc::competition_ranks(&percents);
+    // Best first; ties keep roster order and unranked students go last.
+    let mut rows: Vec<(Option<usize>, serde_json::Value)> = summary
+        .per_student
+        .iter()
+        .zip(ranks)
+        .map(|(s, rank)| {
+            (
+                rank,
+                json!({ "studentId": s.student_id, "percent": s.final_mark, "rank": rank }),
+            )
+        })
+        .collect();
+    rows.sort_by_key(|(rank, _)| rank.unwrap_or(usize::MAX));
+    let ranks: Vec<serde_json::Value> = rows.into_iter().map(|(_, row)| row).collect();
+    ok(&req.id, json!({ "ranks": ranks }))
+}
+
+/// Students whose combined average over the selected mark sets meets `threshold`.
+/// Each set's final mark counts by the set's `weight`; when every set a student
+/// has a mark in weighs 0 the marks count equally, as in combined analysis.
+fn handle_reports_honor_roll(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let Some(threshold) = req
+        .params
+        .get("threshold")
+        .and_then(|v| v.as_f64())
+        .filter(|v| v.is_finite())
+    else {
+        return err(&req.id, "bad_params", "threshold must be a number", None);
+    };
+    let requested: Option<Vec<String>> = match req.params.get("markSetIds") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::Array(items)) => {
+            let ids: Option<Vec<String>> = items
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+            match ids {
+                Some(ids) => Some(ids),
+                None => {
+                    return err(
+                        &req.id,
+                        "bad_params",
+                        "markSetIds must be an array of strings",
+                        None,
+                    )
+                }
+            }
+        }
+        Some(_) => {
+            return err(
+                &req.id,
+                "bad_params",
+                "markSetIds must be an array of strings",
+                None,
+            )
+        }
+    };
+    let filters = match calc::parse_term_filters(&req.params) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+    if let Err(e) = class_name_or_err(conn, req, &class_id) {
+        return e;
+    }
+
+    let mut stmt = match conn.prepare(
+        "SELECT id, weight
+         FROM mark_sets
+         WHERE class_id = ? AND deleted_at IS NULL
+         ORDER BY sort_order",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let all_mark_sets: Vec<(String, f64)> = match stmt
+        .query_map([&class_id], |r| {
+            Ok((r.get(0)?, r.get::<_, Option<f64>>(1)?.unwrap_or(0.0)))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let mark_sets: Vec<(String, f64)> = match requested {
+        None => all_mark_sets,
+        Some(ids) => {
+            let mut selected = Vec::with_capacity(ids.len());
+            for id in ids {
+                let Some(ms) = all_mark_sets.iter().find(|(ms_id, _)| *ms_id == id) else {
+                    return err(
+                        &req.id,
+                        "not_found",
+                        "mark set not found",
+                        Some(json!({ "markSetId": id })),
+                    );
+                };
+                if !selected.iter().any(|(ms_id, _)| *ms_id == id) {
+                    selected.push(ms.clone());
+                }
+            }
+            selected
+        }
+    };
+
+    // studentId -> (displayName, weighted sum, weight total, equal-weight marks)
+    let mut order: Vec<String> = Vec::new();
+    let mut totals: HashMap<String, (String, f64, f64, Vec<f64>)> = HashMap::new();
+    for (mark_set_id, weight) in &mark_sets {
+        let per_student =
+            match calc::markset_averages(&calc_context(conn, &class_id, mark_set_id), &filters) {
+                Ok(v) => v,
+                Err(e) => return calc_err(req, e),
+            };
+        for s in per_student {
+            if !s.active {
+                continue;
+            }
+            let Some(mark) = s.final_mark else {
+                continue;
+            };
+            let entry = totals.entry(s.student_id.clone()).or_insert_with(|| {
+                order.push(s.student_id.clone());
+                (s.display_name.clone(), 0.0, 0.0, Vec::new())
+            });
+            if *weight > 0.0 {
+                entry.1 += mark * weight;
+                entry.2 += weight;
+            }
+            entry.3.push(mark);
+        }
+    }
+
+    let mut honor_roll: Vec<(f64, serde_json::Value)> = Vec::new();
+    for student_id in order {
+        let (display_name, weighted_sum, weight_total, marks) = &totals[&student_id];
+        let average = if *weight_total > 0.0 {
+            weighted_sum / weight_total
+        } else {
+            marks.iter().sum::<f64>() / (marks.len() as f64)
+        };
+        let average = calc::round_off_1_decimal(average);
+        if average >= threshold {
+            honor_roll.push((
+                average,
+                json!({
+                    "studentId": student_id,
+                    "displayName": display_name,
+                    "average": average
+                }),
+            ));
+        }
+    }
+    // Highest first; equal averages keep roster order.
+    honor_roll.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    let honor_roll: Vec<serde_json::Value> = honor_roll.into_iter().map(|(_, row)| row).collect();
+    ok(&req.id, json!({ "honorRoll": honor_roll }))
+}
+
+fn handle_grade_scales_list(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match calc::load_grade_scale(conn) {
+        Ok(bands) => ok(&req.id, json!({ "bands": bands })),
+        Err(e) => calc_err(req, e),
+    }
+}
+
+fn handle_grade_scales_upsert(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let Some(raw_bands) = req.params.get("bands").and_then(|v| v.as_array()) else {
+        return err(&req.id, "bad_params", "missing bands", None);
+    };
+
+    let mut bands: Vec<calc::GradeBand> = Vec::with_capacity(raw_bands.len());
+    for (i, raw) in raw_bands.iter().enumerate() {
+        let Some(min_percent) = raw
+            .get("minPercent")
+            .and_then(|v| v.as_f64())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+        else {
+            return err(
+                &req.id,
+                "bad_params",
+                "minPercent must be a non-negative number",
+                Some(json!({ "index": i })),
+            );
+        };
+        let label = raw
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default();
+        if label.is_empty() {
+            return err(
+                &req.id,
+                "bad_params",
+                "label must not be empty",
+                Some(json!({ "index": i })),
+            );
+        }
+        if bands.iter().any(|b| b.min_percent == min_percent) {
+            return err(
+                &req.id,
+                "bad_params",
+                "duplicate minPercent",
+                Some(json!({ "index": i, "minPercent": min_percent })),
+            );
+        }
+        bands.push(calc::GradeBand { min_percent, label });
+    }
+    bands.sort_by(|a, b| {
+        b.min_percent
+            .partial_cmp(&a.min_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    if let Err(e) = tx.execute("DELETE FROM grade_scales", []) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "grade_scales" })),
+        );
+    }
+    for (i, band) in bands.iter().enumerate() {
+        if let Err(e) = tx.execute(
+            "INSERT INTO grade_scales(id, min_percent, label, sort_order) VALUES(?, ?, ?, ?)",
+            (
+                Uuid::new_v4().to_string(),
+                band.min_percent,
+                &band.label,
+                i as i64,
+            ),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_insert_failed",
+                e.to_string(),
+                Some(json!({ "table": "grade_scales" })),
+            );
+        }
+    }
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "bands": bands }))
+}
+
+fn handle_calc_letter_grade(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mark_set_id = match required_str(req, "markSetId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let scale = match calc::load_grade_scale(conn) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+    match calc::compute_mark_set_summary(
+        &calc_context(conn, &class_id, &mark_set_id),
+        &calc::SummaryFilters::default(),
+    ) {
+        Ok(summary) => {
+            let grades: Vec<serde_json::Value> = summary
+                .per_student
+                .iter()
+                .map(|s| {
+                    let letter = s
+                        .final_mark
+                        .and_then(|pct| calc::letter_for_percent(&scale, pct));
+                    json!({
+                        "studentId": s.student_id,
+                        "percent": s.final_mark,
+                        "letter": letter
+                    })
+                })
+                .collect();
+            ok(&req.id, json!({ "grades": grades }))
+        }
+        Err(e) => calc_err(req, e),
+    }
+}
+
+/// "What do I need on the final?": the score on one assessment that brings a
+/// student's mark-set final to `targetPercent`.
+fn handle_calc_project_needed(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mark_set_id = match required_str(req, "markSetId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let student_id = match required_str(req, "studentId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let assessment_id = match required_str(req, "assessmentId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let Some(target_percent) = req
+        .params
+        .get("targetPercent")
+        .and_then(|v| v.as_f64())
+        .filter(|v| v.is_finite() && *v >= 0.0)
+    else {
+        return err(
+            &req.id,
+            "bad_params",
+            "targetPercent must be a non-negative number",
+            None,
+        );
+    };
+
+    match calc::project_needed(
+        &calc_context(conn, &class_id, &mark_set_id),
+        &student_id,
+        &assessment_id,
+        target_percent,
+    ) {
+        Ok(projection) => ok(&req.id, json!(projection)),
+        Err(e) => calc_err(req, e),
+    }
+}
+
 fn handle_reports_markset_summary_model(state: &mut AppState, req: &Request) -> serde_json::Value {
     let conn = match db_conn(state, req) {
         Ok(v) => v,
@@ -249,6 +684,68 @@ fn handle_reports_markset_summary_model(state: &mut AppState, req: &Request) ->
     }
 }
 
+/// One-page overview for a mark set: each assessment's class mean (zeros count,
+/// no-marks don't) and the mean of active students' final marks.
+fn handle_reports_markset_summary(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mark_set_id = match required_str(req, "markSetId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let summary = match calc::compute_mark_set_summary(
+        &calc_context(conn, &class_id, &mark_set_id),
+        &calc::SummaryFilters::default(),
+    ) {
+        Ok(v) => v,
+        Err(e) => return calc_err(req, e),
+    };
+
+    let assessments: Vec<serde_json::Value> = summary
+        .per_assessment
+        .iter()
+        .map(|a| {
+            let n = a.scored_count + a.zero_count;
+            json!({
+                "assessmentId": a.assessment_id,
+                "idx": a.idx,
+                "title": a.title,
+                "outOf": a.out_of,
+                "classMean": if n > 0 { Some(a.avg_percent) } else { None },
+                "n": n
+            })
+        })
+        .collect();
+    let active: Vec<&calc::StudentFinal> =
+        summary.per_student.iter().filter(|s| s.active).collect();
+    let finals: Vec<f64> = active.iter().filter_map(|s| s.final_mark).collect();
+    let overall_class_mean = if finals.is_empty() {
+        None
+    } else {
+        Some(calc::round_off_1_decimal(
+            finals.iter().sum::<f64>() / finals.len() as f64,
+        ))
+    };
+
+    ok(
+        &req.id,
+        json!({
+            "class": summary.class,
+            "markSet": summary.mark_set,
+            "activeStudents": active.len(),
+            "assessments": assessments,
+            "overallClassMean": overall_class_mean
+        }),
+    )
+}
+
 fn handle_reports_category_analysis_model(
     state: &mut AppState,
     req: &Request,
@@ -955,11 +1452,730 @@ fn handle_reports_time_management_model(
     }
 }
 
+// Printable HTML reports keep every style inline so the file prints the same
+// from any browser without a stylesheet.
+const HTML_BODY_STYLE: &str =
+    "font-family: Helvetica, Arial, sans-serif; font-size: 12px; color: #000000; margin: 0;";
+const HTML_H1_STYLE: &str = "font-size: 18px; margin: 0 0 4px 0;";
+const HTML_H2_STYLE: &str = "font-size: 14px; margin: 18px 0 6px 0;";
+const HTML_SUBTITLE_STYLE: &str = "color: #555555; margin: 0 0 12px 0;";
+const HTML_TABLE_STYLE: &str = "border-collapse: collapse; width: 100%;";
+const HTML_TH_STYLE: &str =
+    "border: 1px solid #333333; background: #eeeeee; padding: 4px 6px; text-align: left;";
+const HTML_TD_STYLE: &str = "border: 1px solid #333333; padding: 4px 6px; vertical-align: top;";
+const HTML_TD_NUM_STYLE: &str =
+    "border: 1px solid #333333; padding: 4px 6px; text-align: right; white-space: nowrap;";
+const HTML_EMPTY_STYLE: &str = "color: #777777; font-style: italic; margin: 0;";
+
+fn html_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"{}\">\n{}</body>\n</html>\n",
+        xml_escape(title),
+        HTML_BODY_STYLE,
+        body
+    )
+}
+
+fn html_table(headers: &[&str], rows: &[Vec<(String, bool)>]) -> String {
+    let mut out = format!("<table style=\"{}\">\n<tr>", HTML_TABLE_STYLE);
+    for h in headers {
+        out.push_str(&format!(
+            "<th style=\"{}\">{}</th>",
+            HTML_TH_STYLE,
+            xml_escape(h)
+        ));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for (text, numeric) in row {
+            let style = if *numeric {
+                HTML_TD_NUM_STYLE
+            } else {
+                HTML_TD_STYLE
+            };
+            out.push_str(&format!(
+                "<td style=\"{}\">{}</td>",
+                style,
+                xml_escape(text)
+            ));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Writes an HTML report to `outPath` when given, otherwise hands it back inline.
+fn html_report_result(
+    req: &Request,
+    html: String,
+    mut result: serde_json::Value,
+) -> serde_json::Value {
+    let out_path = req
+        .params
+        .get("outPath")
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty());
+    match out_path {
+        Some(out_path) => {
+            let out = std::path::PathBuf::from(out_path);
+            if let Some(parent) = out.parent() {
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    return err(
+                        &req.id,
+                        "io_failed",
+                        e.to_string(),
+                        Some(json!({ "path": out_path })),
+                    );
+                }
+            }
+            if let Err(e) = std::fs::write(&out, html) {
+                return err(
+                    &req.id,
+                    "io_failed",
+                    e.to_string(),
+                    Some(json!({ "path": out_path })),
+                );
+            }
+            result["path"] = json!(out_path);
+        }
+        None => result["html"] = json!(html),
+    }
+    ok(&req.id, result)
+}
+
+fn class_name_or_err(
+    conn: &Connection,
+    req: &Request,
+    class_id: &str,
+) -> Result<String, serde_json::Value> {
+    match conn
+        .query_row("SELECT name FROM classes WHERE id = ?", [class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(Some(name)) => Ok(name),
+        Ok(None) => Err(err(&req.id, "not_found", "class not found", None)),
+        Err(e) => Err(err(&req.id, "db_query_failed", e.to_string(), None)),
+    }
+}
+
+struct ReportCardMarkSet {
+    label: String,
+    finals: HashMap<String, Option<f64>>,
+    remarks: HashMap<String, String>,
+}
+
+/// What one student's report card shows, shared by the HTML and PDF renderers.
+struct ReportCard {
+    display_name: String,
+    subtitle: String,
+    /// Mark set, average and comment per mark set; empty when the class has none.
+    marks: Vec<Vec<(String, bool)>>,
+    /// Present, absent, late and excused counts.
+    attendance: Vec<(String, bool)>,
+    /// Term, skill and rating per recorded cell.
+    skills: Vec<Vec<(String, bool)>>,
+}
+
+const REPORT_CARD_MARKS_HEADERS: [&str; 3] = ["Mark Set", "Average", "Comment"];
+const REPORT_CARD_ATTENDANCE_HEADERS: [&str; 4] = ["Present", "Absent", "Late", "Excused"];
+const REPORT_CARD_SKILLS_HEADERS: [&str; 3] = ["Term", "Skill", "Rating"];
+
+/// The class name and a report card per student (or just `only_student`):
+/// an average and default-set comment for each mark set, an attendance tally
+/// and the learning-skills cells by term.
+fn report_cards(
+    conn: &Connection,
+    req: &Request,
+    class_id: &str,
+    only_student: Option<&str>,
+) -> Result<(String, Vec<ReportCard>), serde_json::Value> {
+    let class_name = class_name_or_err(conn, req, class_id)?;
+    let db_err = |e: rusqlite::Error| err(&req.id, "db_query_failed", e.to_string(), None);
+
+    let students: Vec<(String, String, Option<String>)> = conn
+        .prepare(
+            "SELECT id, last_name, first_name, student_no
+             FROM students
+             WHERE class_id = ?
+             ORDER BY sort_order",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| {
+                let last: String = r.get(1)?;
+                let first: String = r.get(2)?;
+                Ok((r.get(0)?, format!("{}, {}", last, first), r.get(3)?))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        })
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|(id, _, _)| only_student.is_none_or(|s| s == id))
+        .collect();
+    if only_student.is_some() && students.is_empty() {
+        return Err(err(&req.id, "not_found", "student not found", None));
+    }
+
+    let mark_set_rows: Vec<(String, String, String)> = conn
+        .prepare(
+            "SELECT id, code, description
+             FROM mark_sets
+             WHERE class_id = ? AND deleted_at IS NULL
+             ORDER BY sort_order",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        })
+        .map_err(db_err)?;
+
+    let mut mark_sets: Vec<ReportCardMarkSet> = Vec::new();
+    for (mark_set_id, code, description) in mark_set_rows {
+        let summary = calc::compute_mark_set_summary(
+            &calc_context(conn, class_id, &mark_set_id),
+            &calc::SummaryFilters::default(),
+        )
+        .map_err(|e| calc_err(req, e))?;
+        let finals = summary
+            .per_student
+            .into_iter()
+            .map(|s| (s.student_id, s.final_mark))
+            .collect();
+        // The flagged default comment set, else the mark set's first one.
+        let remarks = conn
+            .prepare(
+                "SELECT r.student_id, r.remark
+                 FROM comment_set_remarks r
+                 WHERE r.comment_set_index_id = (
+                   SELECT id FROM comment_set_indexes
+                   WHERE mark_set_id = ?
+                   ORDER BY is_default DESC, set_number
+                   LIMIT 1
+                 )",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([&mark_set_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                    .and_then(|it| it.collect::<Result<HashMap<String, String>, _>>())
+            })
+            .map_err(db_err)?;
+        mark_sets.push(ReportCardMarkSet {
+            label: format!("{} \u{2014} {}", code, description),
+            finals,
+            remarks,
+        });
+    }
+
+    let attendance = attendance::attendance_summary(conn, &json!({ "classId": class_id }))
+        .map_err(|e| e.response(&req.id))?;
+    let attendance_by_student: HashMap<&str, &serde_json::Value> = attendance["summaries"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|s| s["studentId"].as_str().map(|id| (id, s)))
+        .collect();
+
+    let mut skills_by_student: HashMap<String, Vec<Vec<(String, bool)>>> = HashMap::new();
+    let skills = conn
+        .prepare(
+            "SELECT student_id, term, skill_code, value
+             FROM learning_skills_cells
+             WHERE class_id = ? AND TRIM(value) <> ''
+             ORDER BY term, skill_code",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([class_id], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, i64>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, String>(3)?,
+                ))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        })
+        .map_err(db_err)?;
+    for (student_id, term, skill_code, value) in skills {
+        skills_by_student.entry(student_id).or_default().push(vec![
+            (term.to_string(), true),
+            (skill_code, false),
+            (value, false),
+        ]);
+    }
+
+    let cards = students
+        .into_iter()
+        .map(|(student_id, display_name, student_no)| {
+            let subtitle = match student_no.as_deref().filter(|s| !s.trim().is_empty()) {
+                Some(no) => format!("{} \u{00b7} Student No. {}", class_name, no),
+                None => class_name.clone(),
+            };
+            let marks = mark_sets
+                .iter()
+                .map(|ms| {
+                    let average = match ms.finals.get(&student_id).copied().flatten() {
+                        Some(v) => format!("{:.1}%", v),
+                        None => "\u{2014}".to_string(),
+                    };
+                    vec![
+                        (ms.label.clone(), false),
+                        (average, true),
+                        (
+                            ms.remarks.get(&student_id).cloned().unwrap_or_default(),
+                            false,
+                        ),
+                    ]
+                })
+                .collect();
+            let tally = attendance_by_student.get(student_id.as_str());
+            let count = |key: &str| {
+                let n = tally.and_then(|t| t[key].as_u64()).unwrap_or(0);
+                (n.to_string(), true)
+            };
+            ReportCard {
+                display_name,
+                subtitle,
+                marks,
+                attendance: vec![
+                    count("present"),
+                    count("absent"),
+                    count("late"),
+                    count("excused"),
+                ],
+                skills: skills_by_student.remove(&student_id).unwrap_or_default(),
+            }
+        })
+        .collect();
+    Ok((class_name, cards))
+}
+
+/// One printed page per student, from the shared report-card model.
+fn handle_reports_report_card_html(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let only_student = req.params.get("studentId").and_then(|v| v.as_str());
+    let (class_name, cards) = match report_cards(conn, req, &class_id, only_student) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut body = String::new();
+    for (i, card) in cards.iter().enumerate() {
+        let page_style = if i == 0 {
+            "padding: 24px;"
+        } else {
+            "padding: 24px; page-break-before: always;"
+        };
+        body.push_str(&format!("<section style=\"{}\">\n", page_style));
+        body.push_str(&format!(
+            "<h1 style=\"{}\">{}</h1>\n",
+            HTML_H1_STYLE,
+            xml_escape(&card.display_name)
+        ));
+        body.push_str(&format!(
+            "<p style=\"{}\">{}</p>\n",
+            HTML_SUBTITLE_STYLE,
+            xml_escape(&card.subtitle)
+        ));
+
+        body.push_str(&format!("<h2 style=\"{}\">Marks</h2>\n", HTML_H2_STYLE));
+        if card.marks.is_empty() {
+            body.push_str(&format!(
+                "<p style=\"{}\">No mark sets.</p>\n",
+                HTML_EMPTY_STYLE
+            ));
+        } else {
+            body.push_str(&html_table(&REPORT_CARD_MARKS_HEADERS, &card.marks));
+        }
+
+        body.push_str(&format!(
+            "<h2 style=\"{}\">Attendance</h2>\n",
+            HTML_H2_STYLE
+        ));
+        body.push_str(&html_table(
+            &REPORT_CARD_ATTENDANCE_HEADERS,
+            std::slice::from_ref(&card.attendance),
+        ));
+
+        body.push_str(&format!(
+            "<h2 style=\"{}\">Learning Skills</h2>\n",
+            HTML_H2_STYLE
+        ));
+        if card.skills.is_empty() {
+            body.push_str(&format!(
+                "<p style=\"{}\">No learning skills recorded.</p>\n",
+                HTML_EMPTY_STYLE
+            ));
+        } else {
+            body.push_str(&html_table(&REPORT_CARD_SKILLS_HEADERS, &card.skills));
+        }
+        body.push_str("</section>\n");
+    }
+
+    let html = html_document(&format!("{} \u{2014} Report Cards", class_name), &body);
+    html_report_result(
+        req,
+        html,
+        json!({ "classId": class_id, "studentCount": cards.len() }),
+    )
+}
+
+const PDF_H1_SIZE: f64 = 18.0;
+const PDF_H2_SIZE: f64 = 13.0;
+const PDF_BODY_SIZE: f64 = 10.0;
+
+/// Lays out a table across the printable width; `widths` are fractions of it.
+fn pdf_table(
+    flow: &mut pdf::Flow,
+    headers: &[&str],
+    widths: &[f64],
+    rows: &[Vec<(String, bool)>],
+) {
+    let printable = pdf::PAGE_WIDTH - 2.0 * pdf::MARGIN;
+    let gutter = 8.0;
+    let mut x = pdf::MARGIN;
+    let columns: Vec<pdf::Column> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            let width = printable * w;
+            let column = pdf::Column {
+                x,
+                width: width - gutter,
+                right: rows.first().is_some_and(|r| r[i].1),
+            };
+            x += width;
+            column
+        })
+        .collect();
+    flow.row(&columns, headers, PDF_BODY_SIZE, pdf::Font::Bold);
+    flow.rule();
+    for row in rows {
+        let cells: Vec<&str> = row.iter().map(|(text, _)| text.as_str()).collect();
+        flow.row(&columns, &cells, PDF_BODY_SIZE, pdf::Font::Regular);
+    }
+}
+
+fn pdf_heading(flow: &mut pdf::Flow, text: &str) {
+    let full = [pdf::Column {
+        x: pdf::MARGIN,
+        width: pdf::PAGE_WIDTH - 2.0 * pdf::MARGIN,
+        right: false,
+    }];
+    flow.gap(PDF_H2_SIZE);
+    flow.row(&full, &[text], PDF_H2_SIZE, pdf::Font::Bold);
+    flow.gap(4.0);
+}
+
+/// The report cards for a whole class as one PDF, each student starting on a
+/// new page; a long card continues onto further pages.
+fn handle_reports_class_report_cards_pdf(
+    state: &mut AppState,
+    req: &Request,
+) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let out_path = match required_str(req, "outPath") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (class_name, cards) = match report_cards(conn, req, &class_id, None) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let full = [pdf::Column {
+        x: pdf::MARGIN,
+        width: pdf::PAGE_WIDTH - 2.0 * pdf::MARGIN,
+        right: false,
+    }];
+    let mut flow = pdf::Flow::new();
+    for (i, card) in cards.iter().enumerate() {
+        if i > 0 {
+            flow.new_page();
+        }
+        flow.row(&full, &[&card.display_name], PDF_H1_SIZE, pdf::Font::Bold);
+        flow.row(&full, &[&card.subtitle], PDF_BODY_SIZE, pdf::Font::Regular);
+
+        pdf_heading(&mut flow, "Marks");
+        if card.marks.is_empty() {
+            flow.row(&full, &["No mark sets."], PDF_BODY_SIZE, pdf::Font::Regular);
+        } else {
+            pdf_table(
+                &mut flow,
+                &REPORT_CARD_MARKS_HEADERS,
+                &[0.35, 0.15, 0.5],
+                &card.marks,
+            );
+        }
+
+        pdf_heading(&mut flow, "Attendance");
+        pdf_table(
+            &mut flow,
+            &REPORT_CARD_ATTENDANCE_HEADERS,
+            &[0.25, 0.25, 0.25, 0.25],
+            std::slice::from_ref(&card.attendance),
+        );
+
+        pdf_heading(&mut flow, "Learning Skills");
+        if card.skills.is_empty() {
+            flow.row(
+                &full,
+                &["No learning skills recorded."],
+                PDF_BODY_SIZE,
+                pdf::Font::Regular,
+            );
+        } else {
+            pdf_table(
+                &mut flow,
+                &REPORT_CARD_SKILLS_HEADERS,
+                &[0.15, 0.35, 0.5],
+                &card.skills,
+            );
+        }
+    }
+    let pages = flow.into_pages();
+
+    let out = std::path::PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return err(
+                &req.id,
+                "io_failed",
+                e.to_string(),
+                Some(json!({ "path": out_path })),
+            );
+        }
+    }
+    let title = format!("{} \u{2014} Report Cards", class_name);
+    if let Err(e) = pdf::write_document(&out, &title, &pages) {
+        return err(
+            &req.id,
+            "io_failed",
+            e.to_string(),
+            Some(json!({ "path": out_path })),
+        );
+    }
+    ok(
+        &req.id,
+        json!({ "path": out_path, "pageCount": pages.len() }),
+    )
+}
+
+/// Every non-empty comment-set remark a student has, across the class's mark
+/// sets, for a printable comment sheet.
+fn handle_reports_student_comments(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let student_id = match required_str(req, "studentId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match conn
+        .query_row(
+            "SELECT 1 FROM students WHERE id = ? AND class_id = ?",
+            (&student_id, &class_id),
+            |r| r.get::<_, i64>(0),
+        )
+        .optional()
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return err(&req.id, "not_found", "student not found", None),
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    }
+
+    let mut stmt = match conn.prepare(
+        "SELECT ms.code, csi.set_number, csi.title, r.remark
+         FROM comment_set_remarks r
+         JOIN comment_set_indexes csi ON csi.id = r.comment_set_index_id
+         JOIN mark_sets ms ON ms.id = csi.mark_set_id
+         WHERE r.student_id = ?
+           AND csi.class_id = ?
+           AND ms.deleted_at IS NULL
+           AND TRIM(r.remark) <> ''
+         ORDER BY ms.sort_order, csi.set_number",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let sets: Vec<serde_json::Value> = match stmt
+        .query_map((&student_id, &class_id), |r| {
+            Ok(json!({
+                "markSetCode": r.get::<_, String>(0)?,
+                "setNumber": r.get::<_, i64>(1)?,
+                "title": r.get::<_, String>(2)?,
+                "remark": r.get::<_, String>(3)?,
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    ok(&req.id, json!({ "sets": sets }))
+}
+
+const ROSTER_DEFAULT_CHECK_COLUMNS: u64 = 5;
+const ROSTER_MAX_CHECK_COLUMNS: u64 = 20;
+
+/// Day-one roster sheet in `sort_order`, with blank columns for checkmarks.
+fn handle_reports_roster_html(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let conn = match db_conn(state, req) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let class_id = match required_str(req, "classId") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match req.params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => {}
+        _ => return err(&req.id, "bad_params", "missing outPath", None),
+    }
+    let check_columns = match req.params.get("checkColumns") {
+        None => ROSTER_DEFAULT_CHECK_COLUMNS,
+        Some(v) => match v.as_u64() {
+            Some(n) if n <= ROSTER_MAX_CHECK_COLUMNS => n,
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    format!("checkColumns must be 0..{}", ROSTER_MAX_CHECK_COLUMNS),
+                    None,
+                )
+            }
+        },
+    };
+    let class_name = match class_name_or_err(conn, req, &class_id) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut stmt = match conn.prepare(
+        "SELECT last_name, first_name, student_no, birth_date, active
+         FROM students
+         WHERE class_id = ?
+         ORDER BY sort_order",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let rows: Vec<Vec<(String, bool)>> = match stmt
+        .query_map([&class_id], |r| {
+            let last: String = r.get(0)?;
+            let first: String = r.get(1)?;
+            let student_no: Option<String> = r.get(2)?;
+            let birth_date: Option<String> = r.get(3)?;
+            let active: i64 = r.get(4)?;
+            Ok((
+                format!("{}, {}", last, first),
+                student_no,
+                birth_date,
+                active != 0,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => v
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, student_no, birth_date, active))| {
+                let mut row = vec![
+                    ((i + 1).to_string(), true),
+                    (name, false),
+                    (student_no.unwrap_or_default(), false),
+                    (birth_date.unwrap_or_default(), false),
+                    ((if active { "Yes" } else { "No" }).to_string(), false),
+                ];
+                row.extend((0..check_columns).map(|_| (String::new(), false)));
+                row
+            })
+            .collect(),
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    let mut headers = vec!["#", "Student", "Student No.", "Birth Date", "Active"];
+    headers.extend((0..check_columns).map(|_| ""));
+    let title = format!("{} \u{2014} Class Roster", class_name);
+    let body = format!(
+        "<section style=\"padding: 24px;\">\n<h1 style=\"{}\">{}</h1>\n<p style=\"{}\">{} students</p>\n{}</section>\n",
+        HTML_H1_STYLE,
+        xml_escape(&title),
+        HTML_SUBTITLE_STYLE,
+        rows.len(),
+        html_table(&headers, &rows)
+    );
+    html_report_result(
+        req,
+        html_document(&title, &body),
+        json!({ "classId": class_id, "studentCount": rows.len() }),
+    )
+}
+
+pub const METHODS: &[&str] = &[
+    "calc.assessmentStats",
+    "calc.markSetSummary",
+    "calc.markSetAverages",
+    "calc.classRank",
+    "calc.letterGrade",
+    "calc.projectNeeded",
+    "gradeScales.list",
+    "gradeScales.upsert",
+    "reports.markSetSummaryModel",
+    "reports.markSetSummary",
+    "reports.categoryAnalysisModel",
+    "reports.studentSummaryModel",
+    "reports.attendanceMonthlyModel",
+    "reports.classListModel",
+    "reports.learningSkillsSummaryModel",
+    "reports.combinedAnalysisModel",
+    "reports.classAssessmentDrilldownModel",
+    "reports.plannerUnitModel",
+    "reports.plannerLessonModel",
+    "reports.courseDescriptionModel",
+    "reports.timeManagementModel",
+    "reports.markSetGridModel",
+    "reports.reportCardHtml",
+    "reports.classReportCardsPdf",
+    "reports.rosterHtml",
+    "reports.honorRoll",
+    "reports.studentComments",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "calc.assessmentStats" => Some(handle_calc_assessment_stats(state, req)),
         "calc.markSetSummary" => Some(handle_calc_markset_summary(state, req)),
+        "calc.markSetAverages" => Some(handle_calc_markset_averages(state, req)),
+        "calc.classRank" => Some(handle_calc_class_rank(state, req)),
+        "calc.letterGrade" => Some(handle_calc_letter_grade(state, req)),
+        "calc.projectNeeded" => Some(handle_calc_project_needed(state, req)),
+        "gradeScales.list" => Some(handle_grade_scales_list(state, req)),
+        "gradeScales.upsert" => Some(handle_grade_scales_upsert(state, req)),
         "reports.markSetSummaryModel" => Some(handle_reports_markset_summary_model(state, req)),
+        "reports.markSetSummary" => Some(handle_reports_markset_summary(state, req)),
         "reports.categoryAnalysisModel" => Some(handle_reports_category_analysis_model(state, req)),
         "reports.studentSummaryModel" => Some(handle_reports_student_summary_model(state, req)),
         "reports.attendanceMonthlyModel" => {
@@ -978,6 +2194,13 @@ pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Val
         "reports.courseDescriptionModel" => Some(handle_reports_course_description_model(state, req)),
         "reports.timeManagementModel" => Some(handle_reports_time_management_model(state, req)),
         "reports.markSetGridModel" => Some(handle_reports_mark_set_grid_model(state, req)),
+        "reports.reportCardHtml" => Some(handle_reports_report_card_html(state, req)),
+        "reports.classReportCardsPdf" => {
+            Some(handle_reports_class_report_cards_pdf(state, req))
+        }
+        "reports.rosterHtml" => Some(handle_reports_roster_html(state, req)),
+        "reports.honorRoll" => Some(handle_reports_honor_roll(state, req)),
+        "reports.studentComments" => Some(handle_reports_student_comments(state, req)),
         _ => None,
     }
 }
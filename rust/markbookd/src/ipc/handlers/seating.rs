@@ -1,8 +1,11 @@
+use super::assets;
 use crate::ipc::error::{err, ok};
 use crate::ipc::types::{AppState, Request};
+use crate::xlsx::xml_escape;
 use rusqlite::{Connection, OptionalExtension};
 use serde_json::json;
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 struct HandlerErr {
     code: &'static str,
@@ -20,8 +23,11 @@ impl HandlerErr {
 struct BasicStudent {
     id: String,
     display_name: String,
+    initials: String,
+    photo_path: Option<String>,
     sort_order: i64,
     active: bool,
+    archived: bool,
 }
 
 fn get_required_str(params: &serde_json::Value, key: &str) -> Result<String, HandlerErr> {
@@ -55,7 +61,7 @@ fn list_students_for_class(
 ) -> Result<Vec<BasicStudent>, HandlerErr> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, last_name, first_name, sort_order, active
+            "SELECT id, last_name, first_name, sort_order, active, archived, photo_path
              FROM students
              WHERE class_id = ?
              ORDER BY sort_order",
@@ -68,11 +74,19 @@ fn list_students_for_class(
     stmt.query_map([class_id], |r| {
         let last: String = r.get(1)?;
         let first: String = r.get(2)?;
+        let initials: String = [&first, &last]
+            .iter()
+            .filter_map(|n| n.trim().chars().next())
+            .flat_map(char::to_uppercase)
+            .collect();
         Ok(BasicStudent {
             id: r.get(0)?,
             display_name: format!("{}, {}", last, first),
+            initials,
+            photo_path: r.get(6)?,
             sort_order: r.get(3)?,
             active: r.get::<_, i64>(4)? != 0,
+            archived: r.get::<_, i64>(5)? != 0,
         })
     })
     .and_then(|it| it.collect::<Result<Vec<_>, _>>())
@@ -111,6 +125,68 @@ fn seat_code_to_index(seat_code: i64, rows: i64, seats_per_row: i64) -> Option<u
     Some((row * seats_per_row + (col - 1)) as usize)
 }
 
+#[derive(Debug, Clone)]
+struct SeatingPlan {
+    id: String,
+    name: String,
+    rows: i64,
+    seats_per_row: i64,
+    blocked_mask: String,
+}
+
+fn plan_id_param(params: &serde_json::Value) -> Option<&str> {
+    params.get("planId").and_then(|v| v.as_str())
+}
+
+/// Resolves `planId` within the class, or the class's default plan when it is
+/// omitted. An explicit but unknown `planId` is `not_found`.
+fn load_plan(
+    conn: &Connection,
+    class_id: &str,
+    plan_id: Option<&str>,
+) -> Result<Option<SeatingPlan>, HandlerErr> {
+    let map_row = |r: &rusqlite::Row<'_>| {
+        Ok(SeatingPlan {
+            id: r.get(0)?,
+            name: r.get(1)?,
+            rows: r.get(2)?,
+            seats_per_row: r.get(3)?,
+            blocked_mask: r.get(4)?,
+        })
+    };
+    let plan = match plan_id {
+        Some(plan_id) => conn
+            .query_row(
+                "SELECT id, name, rows, seats_per_row, blocked_mask
+                 FROM seating_plans WHERE id = ? AND class_id = ?",
+                (plan_id, class_id),
+                map_row,
+            )
+            .optional(),
+        None => conn
+            .query_row(
+                "SELECT id, name, rows, seats_per_row, blocked_mask
+                 FROM seating_plans WHERE class_id = ? AND is_default = 1",
+                [class_id],
+                map_row,
+            )
+            .optional(),
+    }
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    if plan.is_none() && plan_id.is_some() {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "seating plan not found".to_string(),
+            details: None,
+        });
+    }
+    Ok(plan)
+}
+
 fn seating_get(
     conn: &Connection,
     params: &serde_json::Value,
@@ -125,20 +201,12 @@ fn seating_get(
     }
     let default_rows = 6_i64;
     let default_seats = 5_i64;
-    let plan_row: Option<(i64, i64, String)> = conn
-        .query_row(
-            "SELECT rows, seats_per_row, blocked_mask FROM seating_plans WHERE class_id = ?",
-            [&class_id],
-            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
-        )
-        .optional()
-        .map_err(|e| HandlerErr {
-            code: "db_query_failed",
-            message: e.to_string(),
-            details: None,
-        })?;
-    let (rows, seats_per_row, blocked_mask) =
-        plan_row.unwrap_or((default_rows, default_seats, "0".repeat(100)));
+    let plan = load_plan(conn, &class_id, plan_id_param(params))?;
+    let (rows, seats_per_row, blocked_mask) = match &plan {
+        Some(p) => (p.rows, p.seats_per_row, p.blocked_mask.clone()),
+        None => (default_rows, default_seats, "0".repeat(100)),
+    };
+    let plan_id = plan.as_ref().map(|p| p.id.clone()).unwrap_or_default();
     let seat_count = ((rows.max(1) * seats_per_row.max(1)) as usize).max(1);
     let blocked = normalize_day_codes(&blocked_mask, 100);
     let blocked_codes: Vec<usize> = blocked
@@ -157,7 +225,7 @@ fn seating_get(
         .prepare(
             "SELECT student_id, seat_code
              FROM seating_assignments
-             WHERE class_id = ?",
+             WHERE plan_id = ?",
         )
         .map_err(|e| HandlerErr {
             code: "db_query_failed",
@@ -165,7 +233,7 @@ fn seating_get(
             details: None,
         })?;
     let rows_iter = stmt
-        .query_map([&class_id], |r| {
+        .query_map([&plan_id], |r| {
             Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>())
@@ -188,6 +256,8 @@ fn seating_get(
     }
 
     Ok(json!({
+        "planId": plan.as_ref().map(|p| p.id.as_str()),
+        "planName": plan.as_ref().map(|p| p.name.as_str()),
         "rows": rows,
         "seatsPerRow": seats_per_row,
         "blockedSeatCodes": blocked_codes,
@@ -254,6 +324,7 @@ fn seating_save(
     }
     let blocked_mask: String = blocked_mask_chars.into_iter().collect();
 
+    let plan = load_plan(conn, &class_id, plan_id_param(params))?;
     let students = list_students_for_class(conn, &class_id)?;
     let by_sort_order: HashMap<i64, String> = students
         .iter()
@@ -266,14 +337,19 @@ fn seating_save(
         details: None,
     })?;
 
+    // Clients that predate named plans save without a planId; the first save
+    // for a class creates its default plan.
+    let plan_id = plan
+        .map(|p| p.id)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     tx.execute(
-        "INSERT INTO seating_plans(class_id, rows, seats_per_row, blocked_mask)
-         VALUES(?, ?, ?, ?)
-         ON CONFLICT(class_id) DO UPDATE SET
+        "INSERT INTO seating_plans(id, class_id, name, is_default, rows, seats_per_row, blocked_mask)
+         VALUES(?, ?, 'Default', 1, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
            rows = excluded.rows,
            seats_per_row = excluded.seats_per_row,
            blocked_mask = excluded.blocked_mask",
-        (&class_id, rows, seats_per_row, &blocked_mask),
+        (&plan_id, &class_id, rows, seats_per_row, &blocked_mask),
     )
     .map_err(|e| HandlerErr {
         code: "db_update_failed",
@@ -281,8 +357,8 @@ fn seating_save(
         details: Some(json!({ "table": "seating_plans" })),
     })?;
     tx.execute(
-        "DELETE FROM seating_assignments WHERE class_id = ?",
-        [&class_id],
+        "DELETE FROM seating_assignments WHERE plan_id = ?",
+        [&plan_id],
     )
     .map_err(|e| HandlerErr {
         code: "db_delete_failed",
@@ -306,9 +382,11 @@ fn seating_save(
         }
         seen_students.insert(student_id.clone());
         tx.execute(
-            "INSERT INTO seating_assignments(class_id, student_id, seat_code) VALUES(?, ?, ?)",
+            "INSERT INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+             VALUES(?, ?, ?, ?)",
             (
                 &class_id,
+                &plan_id,
                 &student_id,
                 seat_index_to_code(idx, seats_per_row),
             ),
@@ -324,7 +402,906 @@ fn seating_save(
         message: e.to_string(),
         details: None,
     })?;
-    Ok(json!({ "ok": true }))
+    Ok(json!({ "ok": true, "planId": plan_id }))
+}
+
+/// SplitMix64; small and deterministic so seeded arrangements are stable
+/// across platforms without pulling in a rand dependency.
+struct SeatRng(u64);
+
+impl SeatRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+fn seating_auto_arrange(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let mode = get_required_str(params, "mode")?;
+    if !matches!(mode.as_str(), "alphabetical" | "random" | "reverse") {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "mode must be alphabetical, random, or reverse".to_string(),
+            details: None,
+        });
+    }
+    let seed = match params.get("seed") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => Some(v.as_u64().ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "seed must be a non-negative integer".to_string(),
+            details: None,
+        })?),
+    };
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let Some(plan) = load_plan(conn, &class_id, plan_id_param(params))? else {
+        return Err(HandlerErr {
+            code: "no_plan",
+            message: "save a seating plan for this class first".to_string(),
+            details: None,
+        });
+    };
+    let seat_count = (plan.rows.max(1) * plan.seats_per_row.max(1)) as usize;
+    let blocked: Vec<char> = normalize_day_codes(&plan.blocked_mask, 100)
+        .chars()
+        .collect();
+    let open_seats: Vec<usize> = (0..seat_count)
+        .filter(|idx| blocked.get(*idx) != Some(&'1'))
+        .collect();
+
+    let mut students: Vec<BasicStudent> = list_students_for_class(conn, &class_id)?
+        .into_iter()
+        .filter(|s| s.active && !s.archived)
+        .collect();
+    match mode.as_str() {
+        "random" => {
+            let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u128() as u64);
+            SeatRng(seed).shuffle(&mut students);
+        }
+        _ => {
+            students.sort_by(|a, b| {
+                a.display_name
+                    .to_lowercase()
+                    .cmp(&b.display_name.to_lowercase())
+                    .then(a.sort_order.cmp(&b.sort_order))
+            });
+            if mode == "reverse" {
+                students.reverse();
+            }
+        }
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    tx.execute(
+        "DELETE FROM seating_assignments WHERE plan_id = ?",
+        [&plan.id],
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_delete_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "seating_assignments" })),
+    })?;
+    for (student, idx) in students.iter().zip(open_seats.iter()) {
+        tx.execute(
+            "INSERT INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+             VALUES(?, ?, ?, ?)",
+            (
+                &class_id,
+                &plan.id,
+                &student.id,
+                seat_index_to_code(*idx, plan.seats_per_row),
+            ),
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_insert_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "seating_assignments" })),
+        })?;
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    let unseated: Vec<&str> = students
+        .iter()
+        .skip(open_seats.len())
+        .map(|s| s.id.as_str())
+        .collect();
+    let mut result = seating_get(conn, &json!({ "classId": class_id, "planId": plan.id }))?;
+    result["unseatedStudentIds"] = json!(unseated);
+    Ok(result)
+}
+
+fn seat_of(conn: &Connection, plan_id: &str, student_id: &str) -> Result<Option<i64>, HandlerErr> {
+    conn.query_row(
+        "SELECT seat_code FROM seating_assignments WHERE plan_id = ? AND student_id = ?",
+        (plan_id, student_id),
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| HandlerErr {
+        code: "db_query_failed",
+        message: e.to_string(),
+        details: None,
+    })
+}
+
+fn seating_swap(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let student_id = get_required_str(params, "studentId")?;
+    let target_student_id = params
+        .get("targetStudentId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let target_seat = params.get("seatCode").and_then(|v| v.as_i64());
+    if target_student_id.as_deref() == Some(student_id.as_str()) {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "cannot swap a student with themselves".to_string(),
+            details: None,
+        });
+    }
+    if !class_exists(conn, &class_id)? {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    }
+    let students = list_students_for_class(conn, &class_id)?;
+    for id in std::iter::once(&student_id).chain(target_student_id.iter()) {
+        if !students.iter().any(|s| &s.id == id) {
+            return Err(HandlerErr {
+                code: "not_found",
+                message: "student not found".to_string(),
+                details: Some(json!({ "studentId": id })),
+            });
+        }
+    }
+
+    let Some(plan) = load_plan(conn, &class_id, plan_id_param(params))? else {
+        return Err(HandlerErr {
+            code: "no_plan",
+            message: "save a seating plan for this class first".to_string(),
+            details: None,
+        });
+    };
+    let own_seat = seat_of(conn, &plan.id, &student_id)?;
+    // (other student, seat student_id ends up in)
+    let (other_id, new_seat): (Option<String>, Option<i64>) = match (target_student_id, target_seat)
+    {
+        (Some(other), None) => {
+            let other_seat = seat_of(conn, &plan.id, &other)?;
+            if own_seat.is_none() && other_seat.is_none() {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: "neither student is seated".to_string(),
+                    details: None,
+                });
+            }
+            (Some(other), other_seat)
+        }
+        (None, Some(seat_code)) => {
+            let blocked: Vec<char> = normalize_day_codes(&plan.blocked_mask, 100)
+                .chars()
+                .collect();
+            let idx = seat_code_to_index(seat_code, plan.rows, plan.seats_per_row);
+            let Some(idx) = idx.filter(|i| blocked.get(*i) != Some(&'1')) else {
+                return Err(HandlerErr {
+                    code: "bad_params",
+                    message: "seatCode is outside the plan or blocked".to_string(),
+                    details: Some(json!({ "seatCode": seat_code })),
+                });
+            };
+            let seat_code = seat_index_to_code(idx, plan.seats_per_row);
+            let occupant: Option<String> = conn
+                .query_row(
+                    "SELECT student_id FROM seating_assignments
+                     WHERE plan_id = ? AND seat_code = ? AND student_id <> ?",
+                    (&plan.id, seat_code, &student_id),
+                    |r| r.get(0),
+                )
+                .optional()
+                .map_err(|e| HandlerErr {
+                    code: "db_query_failed",
+                    message: e.to_string(),
+                    details: None,
+                })?;
+            (occupant, Some(seat_code))
+        }
+        _ => {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "provide exactly one of targetStudentId or seatCode".to_string(),
+                details: None,
+            })
+        }
+    };
+
+    let mut updates: Vec<(String, Option<i64>)> = vec![(student_id, new_seat)];
+    if let Some(other) = other_id {
+        updates.push((other, own_seat));
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    for (id, seat) in &updates {
+        tx.execute(
+            "DELETE FROM seating_assignments WHERE plan_id = ? AND student_id = ?",
+            (&plan.id, id),
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "seating_assignments" })),
+        })?;
+        if let Some(seat) = seat {
+            tx.execute(
+                "INSERT INTO seating_assignments(class_id, plan_id, student_id, seat_code)
+                 VALUES(?, ?, ?, ?)",
+                (&class_id, &plan.id, id, seat),
+            )
+            .map_err(|e| HandlerErr {
+                code: "db_insert_failed",
+                message: e.to_string(),
+                details: Some(json!({ "table": "seating_assignments" })),
+            })?;
+        }
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    let assignments: Vec<serde_json::Value> = updates
+        .into_iter()
+        .map(|(id, seat)| json!({ "studentId": id, "seatCode": seat }))
+        .collect();
+    Ok(json!({ "assignments": assignments }))
+}
+
+fn require_class(conn: &Connection, class_id: &str) -> Result<(), HandlerErr> {
+    if class_exists(conn, class_id)? {
+        Ok(())
+    } else {
+        Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        })
+    }
+}
+
+fn seating_plans_list(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    require_class(conn, &class_id)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.name, p.is_default, p.rows, p.seats_per_row,
+                    (SELECT COUNT(*) FROM seating_assignments sa WHERE sa.plan_id = p.id)
+             FROM seating_plans p
+             WHERE p.class_id = ?
+             ORDER BY p.is_default DESC, p.rowid",
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let plans = stmt
+        .query_map([&class_id], |r| {
+            Ok(json!({
+                "planId": r.get::<_, String>(0)?,
+                "name": r.get::<_, String>(1)?,
+                "isDefault": r.get::<_, i64>(2)? != 0,
+                "rows": r.get::<_, i64>(3)?,
+                "seatsPerRow": r.get::<_, i64>(4)?,
+                "seatedCount": r.get::<_, i64>(5)?
+            }))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    Ok(json!({ "plans": plans }))
+}
+
+fn seating_plans_create(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let name = get_required_str(params, "name")?.trim().to_string();
+    if name.is_empty() {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "name must not be empty".to_string(),
+            details: None,
+        });
+    }
+    let rows = params
+        .get("rows")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(6)
+        .max(1);
+    let seats_per_row = params
+        .get("seatsPerRow")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(5)
+        .max(1);
+    require_class(conn, &class_id)?;
+
+    let existing: Vec<(String, bool)> = {
+        let mut stmt = conn
+            .prepare("SELECT name, is_default FROM seating_plans WHERE class_id = ?")
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        let rows = stmt
+            .query_map([&class_id], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)? != 0))
+            })
+            .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        rows
+    };
+    if existing.iter().any(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "a seating plan with that name already exists".to_string(),
+            details: Some(json!({ "name": name })),
+        });
+    }
+    // The first plan for a class becomes its default so planId-less clients see it.
+    let is_default = !existing.iter().any(|(_, d)| *d);
+
+    let plan_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO seating_plans(id, class_id, name, is_default, rows, seats_per_row, blocked_mask)
+         VALUES(?, ?, ?, ?, ?, ?, ?)",
+        (
+            &plan_id,
+            &class_id,
+            &name,
+            is_default as i64,
+            rows,
+            seats_per_row,
+            "0".repeat(100),
+        ),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_insert_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "seating_plans" })),
+    })?;
+    Ok(json!({ "planId": plan_id, "name": name, "isDefault": is_default }))
+}
+
+fn seating_plans_delete(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let plan_id = get_required_str(params, "planId")?;
+    require_class(conn, &class_id)?;
+    let is_default: Option<bool> = conn
+        .query_row(
+            "SELECT is_default FROM seating_plans WHERE id = ? AND class_id = ?",
+            (&plan_id, &class_id),
+            |r| Ok(r.get::<_, i64>(0)? != 0),
+        )
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let Some(is_default) = is_default else {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "seating plan not found".to_string(),
+            details: None,
+        });
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    tx.execute(
+        "DELETE FROM seating_assignments WHERE plan_id = ?",
+        [&plan_id],
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_delete_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "seating_assignments" })),
+    })?;
+    tx.execute("DELETE FROM seating_plans WHERE id = ?", [&plan_id])
+        .map_err(|e| HandlerErr {
+            code: "db_delete_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "seating_plans" })),
+        })?;
+    // Keep a default while any plan remains: promote the oldest survivor.
+    if is_default {
+        tx.execute(
+            "UPDATE seating_plans SET is_default = 1
+             WHERE id = (SELECT id FROM seating_plans WHERE class_id = ? ORDER BY rowid LIMIT 1)",
+            [&class_id],
+        )
+        .map_err(|e| HandlerErr {
+            code: "db_update_failed",
+            message: e.to_string(),
+            details: Some(json!({ "table": "seating_plans" })),
+        })?;
+    }
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    let default_plan_id = load_plan(conn, &class_id, None)?.map(|p| p.id);
+    Ok(json!({ "ok": true, "defaultPlanId": default_plan_id }))
+}
+
+/// Empties seat assignments but keeps each plan's geometry: the given plan,
+/// or every plan of the class when `planId` is omitted.
+fn seating_clear(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    require_class(conn, &class_id)?;
+    let cleared = match plan_id_param(params) {
+        Some(plan_id) => {
+            // Only checks that the plan belongs to the class.
+            load_plan(conn, &class_id, Some(plan_id))?;
+            conn.execute(
+                "DELETE FROM seating_assignments WHERE plan_id = ?",
+                [plan_id],
+            )
+        }
+        None => conn.execute(
+            "DELETE FROM seating_assignments WHERE class_id = ?",
+            [&class_id],
+        ),
+    }
+    .map_err(|e| HandlerErr {
+        code: "db_delete_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "seating_assignments" })),
+    })?;
+    Ok(json!({ "cleared": cleared }))
+}
+
+/// Blocks or unblocks one seat of a plan, leaving the rest of the mask as is.
+/// An occupied seat cannot be blocked.
+fn seating_set_blocked(
+    conn: &Connection,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let seat_code = params
+        .get("seatCode")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "missing seatCode".to_string(),
+            details: None,
+        })?;
+    let blocked = params
+        .get("blocked")
+        .and_then(|v| v.as_bool())
+        .ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "blocked must be a boolean".to_string(),
+            details: None,
+        })?;
+    require_class(conn, &class_id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| HandlerErr {
+        code: "db_tx_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+    let Some(plan) = load_plan(&tx, &class_id, plan_id_param(params))? else {
+        return Err(HandlerErr {
+            code: "no_plan",
+            message: "save a seating plan for this class first".to_string(),
+            details: None,
+        });
+    };
+    let Some(idx) = seat_code_to_index(seat_code, plan.rows, plan.seats_per_row) else {
+        return Err(HandlerErr {
+            code: "bad_params",
+            message: "seatCode is outside the plan".to_string(),
+            details: Some(json!({ "seatCode": seat_code })),
+        });
+    };
+    if blocked {
+        let occupant: Option<String> = tx
+            .query_row(
+                "SELECT student_id FROM seating_assignments WHERE plan_id = ? AND seat_code = ?",
+                (&plan.id, seat_index_to_code(idx, plan.seats_per_row)),
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| HandlerErr {
+                code: "db_query_failed",
+                message: e.to_string(),
+                details: None,
+            })?;
+        if let Some(student_id) = occupant {
+            return Err(HandlerErr {
+                code: "seat_occupied",
+                message: "move the seated student before blocking this seat".to_string(),
+                details: Some(json!({ "seatCode": seat_code, "studentId": student_id })),
+            });
+        }
+    }
+
+    // Older plans may carry a short or space-padded mask; write it back as
+    // the 100 '0'/'1' cells `seating.save` produces.
+    let mut mask: Vec<char> = normalize_day_codes(&plan.blocked_mask, 100)
+        .chars()
+        .map(|ch| if ch == '1' { '1' } else { '0' })
+        .collect();
+    mask[idx] = if blocked { '1' } else { '0' };
+    let blocked_mask: String = mask.iter().collect();
+    tx.execute(
+        "UPDATE seating_plans SET blocked_mask = ? WHERE id = ?",
+        (&blocked_mask, &plan.id),
+    )
+    .map_err(|e| HandlerErr {
+        code: "db_update_failed",
+        message: e.to_string(),
+        details: Some(json!({ "table": "seating_plans" })),
+    })?;
+    tx.commit().map_err(|e| HandlerErr {
+        code: "db_commit_failed",
+        message: e.to_string(),
+        details: None,
+    })?;
+
+    let blocked_codes: Vec<usize> = mask
+        .iter()
+        .enumerate()
+        .filter_map(|(i, ch)| if *ch == '1' { Some(i + 1) } else { None })
+        .collect();
+    Ok(json!({
+        "planId": plan.id,
+        "blockedMask": blocked_mask,
+        "blockedSeatCodes": blocked_codes
+    }))
+}
+
+const SVG_CELL_W: i64 = 150;
+const SVG_CELL_H: i64 = 56;
+const SVG_GAP: i64 = 8;
+const SVG_MARGIN: i64 = 24;
+const SVG_HEADER_H: i64 = 44;
+const SVG_LINE_H: i64 = 18;
+const SVG_PHOTO_CELL_H: i64 = 100;
+const SVG_PHOTO: i64 = 56;
+
+/// What a seat shows: the student's name plus, on photo charts, either the
+/// photo as a `data:` URL or the initials drawn in its place.
+struct SeatCell {
+    name: String,
+    initials: String,
+    photo: Option<String>,
+}
+
+/// Renders a printable chart: row 0 is drawn nearest the "Front" label,
+/// blocked seats are shaded, and unseated students are listed underneath.
+/// With `with_photos`, seats are taller and show a thumbnail above the name.
+fn render_seating_svg(
+    title: &str,
+    plan: &SeatingPlan,
+    seats: &[Option<SeatCell>],
+    unseated: &[String],
+    with_photos: bool,
+) -> String {
+    let rows = plan.rows.max(1);
+    let cols = plan.seats_per_row.max(1);
+    let blocked: Vec<char> = normalize_day_codes(&plan.blocked_mask, 100)
+        .chars()
+        .collect();
+    let grid_w = cols * SVG_CELL_W + (cols - 1) * SVG_GAP;
+    let cell_h = if with_photos {
+        SVG_PHOTO_CELL_H
+    } else {
+        SVG_CELL_H
+    };
+    let grid_h = rows * cell_h + (rows - 1) * SVG_GAP;
+    let grid_top = SVG_MARGIN + SVG_HEADER_H;
+    let footer_h = if unseated.is_empty() {
+        0
+    } else {
+        SVG_MARGIN + SVG_LINE_H * (unseated.len() as i64 + 1)
+    };
+    let width = grid_w + 2 * SVG_MARGIN;
+    let height = grid_top + grid_h + SVG_MARGIN + footer_h;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Helvetica, Arial, sans-serif">"#,
+        w = width,
+        h = height
+    );
+    svg.push_str(&format!(
+        r##"<rect x="0" y="0" width="{}" height="{}" fill="#ffffff"/>"##,
+        width, height
+    ));
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}" font-size="16" font-weight="bold">{}</text>"#,
+        SVG_MARGIN,
+        SVG_MARGIN + 4,
+        xml_escape(title)
+    ));
+    svg.push_str(&format!(
+        r##"<text x="{}" y="{}" font-size="11" fill="#555555" text-anchor="middle">Front</text>"##,
+        SVG_MARGIN + grid_w / 2,
+        grid_top - 8
+    ));
+
+    for idx in 0..(rows * cols) as usize {
+        let r = idx as i64 / cols;
+        let c = idx as i64 % cols;
+        let x = SVG_MARGIN + c * (SVG_CELL_W + SVG_GAP);
+        let y = grid_top + r * (cell_h + SVG_GAP);
+        let is_blocked = blocked.get(idx) == Some(&'1');
+        svg.push_str(&format!(
+            r##"<rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="{}" stroke="#333333"/>"##,
+            x,
+            y,
+            SVG_CELL_W,
+            cell_h,
+            if is_blocked { "#cccccc" } else { "#ffffff" }
+        ));
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" font-size="9" fill="#777777">{}</text>"##,
+            x + 4,
+            y + 11,
+            seat_index_to_code(idx, cols)
+        ));
+        if is_blocked {
+            continue;
+        }
+        let Some(Some(seat)) = seats.get(idx) else {
+            continue;
+        };
+        let label: String = if seat.name.chars().count() > 22 {
+            format!("{}…", seat.name.chars().take(21).collect::<String>())
+        } else {
+            seat.name.clone()
+        };
+        let mut label_y = y + SVG_CELL_H / 2 + 5;
+        if with_photos {
+            let px = x + (SVG_CELL_W - SVG_PHOTO) / 2;
+            let py = y + 14;
+            match &seat.photo {
+                Some(href) => svg.push_str(&format!(
+                    r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="xMidYMid slice" href="{}"/>"#,
+                    px, py, SVG_PHOTO, SVG_PHOTO, href
+                )),
+                None => {
+                    svg.push_str(&format!(
+                        r##"<circle cx="{}" cy="{}" r="{}" fill="#dddddd"/>"##,
+                        px + SVG_PHOTO / 2,
+                        py + SVG_PHOTO / 2,
+                        SVG_PHOTO / 2
+                    ));
+                    svg.push_str(&format!(
+                        r##"<text x="{}" y="{}" font-size="20" fill="#555555" text-anchor="middle">{}</text>"##,
+                        px + SVG_PHOTO / 2,
+                        py + SVG_PHOTO / 2 + 7,
+                        xml_escape(&seat.initials)
+                    ));
+                }
+            }
+            label_y = py + SVG_PHOTO + 16;
+        }
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="13" text-anchor="middle">{}</text>"#,
+            x + SVG_CELL_W / 2,
+            label_y,
+            xml_escape(&label)
+        ));
+    }
+
+    if !unseated.is_empty() {
+        let mut y = grid_top + grid_h + SVG_MARGIN + SVG_LINE_H / 2;
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="12" font-weight="bold">Unseated</text>"#,
+            SVG_MARGIN, y
+        ));
+        for name in unseated {
+            y += SVG_LINE_H;
+            svg.push_str(&format!(
+                r#"<text x="{}" y="{}" font-size="12">{}</text>"#,
+                SVG_MARGIN,
+                y,
+                xml_escape(name)
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn seating_export_svg(
+    conn: &Connection,
+    workspace: &Path,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, HandlerErr> {
+    let class_id = get_required_str(params, "classId")?;
+    let with_photos = match params.get("withPhotos") {
+        None | Some(serde_json::Value::Null) => false,
+        Some(v) => v.as_bool().ok_or_else(|| HandlerErr {
+            code: "bad_params",
+            message: "withPhotos must be a boolean".to_string(),
+            details: None,
+        })?,
+    };
+    let out_path = match params.get("outPath").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+        _ => {
+            return Err(HandlerErr {
+                code: "bad_params",
+                message: "missing outPath".to_string(),
+                details: None,
+            })
+        }
+    };
+    let class_name: Option<String> = conn
+        .query_row("SELECT name FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let Some(class_name) = class_name else {
+        return Err(HandlerErr {
+            code: "not_found",
+            message: "class not found".to_string(),
+            details: None,
+        });
+    };
+    let Some(plan) = load_plan(conn, &class_id, plan_id_param(params))? else {
+        return Err(HandlerErr {
+            code: "no_plan",
+            message: "save a seating plan for this class first".to_string(),
+            details: None,
+        });
+    };
+
+    let students = list_students_for_class(conn, &class_id)?;
+    let seat_count = (plan.rows.max(1) * plan.seats_per_row.max(1)) as usize;
+    let mut seats: Vec<Option<SeatCell>> = (0..seat_count).map(|_| None).collect();
+    let mut photos_embedded = 0;
+    let mut seated: HashSet<String> = HashSet::new();
+    let mut stmt = conn
+        .prepare("SELECT student_id, seat_code FROM seating_assignments WHERE plan_id = ?")
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    let assigned = stmt
+        .query_map([&plan.id], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| HandlerErr {
+            code: "db_query_failed",
+            message: e.to_string(),
+            details: None,
+        })?;
+    for (student_id, seat_code) in assigned {
+        let Some(idx) = seat_code_to_index(seat_code, plan.rows, plan.seats_per_row) else {
+            continue;
+        };
+        let Some(student) = students.iter().find(|s| s.id == student_id) else {
+            continue;
+        };
+        if let Some(slot) = seats.get_mut(idx) {
+            let photo = match (&student.photo_path, with_photos) {
+                (Some(rel_path), true) => assets::photo_data_url(workspace, rel_path),
+                _ => None,
+            };
+            if photo.is_some() {
+                photos_embedded += 1;
+            }
+            *slot = Some(SeatCell {
+                name: student.display_name.clone(),
+                initials: student.initials.clone(),
+                photo,
+            });
+            seated.insert(student_id);
+        }
+    }
+    let unseated: Vec<String> = students
+        .iter()
+        .filter(|s| s.active && !s.archived && !seated.contains(&s.id))
+        .map(|s| s.display_name.clone())
+        .collect();
+
+    let title = format!("{} \u{2014} {}", class_name, plan.name);
+    let svg = render_seating_svg(&title, &plan, &seats, &unseated, with_photos);
+
+    let out = PathBuf::from(&out_path);
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| HandlerErr {
+            code: "io_failed",
+            message: e.to_string(),
+            details: Some(json!({ "path": out_path })),
+        })?;
+    }
+    std::fs::write(&out, svg).map_err(|e| HandlerErr {
+        code: "io_failed",
+        message: e.to_string(),
+        details: Some(json!({ "path": out_path })),
+    })?;
+
+    let mut result = json!({
+        "path": out_path,
+        "rows": plan.rows,
+        "seatsPerRow": plan.seats_per_row
+    });
+    if with_photos {
+        result["photosEmbedded"] = json!(photos_embedded);
+    }
+    Ok(result)
 }
 
 fn handle_seating_get(state: &mut AppState, req: &Request) -> serde_json::Value {
@@ -347,10 +1324,111 @@ fn handle_seating_save(state: &mut AppState, req: &Request) -> serde_json::Value
     }
 }
 
+fn handle_seating_auto_arrange(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_auto_arrange(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_swap(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_swap(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_plans_list(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_plans_list(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_plans_create(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_plans_create(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_plans_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_plans_delete(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_clear(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_clear(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_set_blocked(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_set_blocked(conn, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+fn handle_seating_export_svg(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let (Some(conn), Some(workspace)) = (state.db.as_ref(), state.workspace.as_ref()) else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+    match seating_export_svg(conn, workspace, &req.params) {
+        Ok(result) => ok(&req.id, result),
+        Err(error) => error.response(&req.id),
+    }
+}
+
+pub const METHODS: &[&str] = &[
+    "seating.get",
+    "seating.save",
+    "seating.autoArrange",
+    "seating.swap",
+    "seating.plans.list",
+    "seating.plans.create",
+    "seating.plans.delete",
+    "seating.clear",
+    "seating.setBlocked",
+    "seating.exportSvg",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "seating.get" => Some(handle_seating_get(state, req)),
         "seating.save" => Some(handle_seating_save(state, req)),
+        "seating.autoArrange" => Some(handle_seating_auto_arrange(state, req)),
+        "seating.swap" => Some(handle_seating_swap(state, req)),
+        "seating.plans.list" => Some(handle_seating_plans_list(state, req)),
+        "seating.plans.create" => Some(handle_seating_plans_create(state, req)),
+        "seating.plans.delete" => Some(handle_seating_plans_delete(state, req)),
+        "seating.clear" => Some(handle_seating_clear(state, req)),
+        "seating.setBlocked" => Some(handle_seating_set_blocked(state, req)),
+        "seating.exportSvg" => Some(handle_seating_export_svg(state, req)),
         _ => None,
     }
 }
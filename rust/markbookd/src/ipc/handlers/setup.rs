@@ -7,6 +7,7 @@ use serde_json::{json, Map, Value};
 enum SetupSection {
     Analysis,
     Marks,
+    Students,
     Exchange,
     Analytics,
     Attendance,
@@ -25,6 +26,7 @@ impl SetupSection {
         match s {
             "analysis" => Some(Self::Analysis),
             "marks" => Some(Self::Marks),
+            "students" => Some(Self::Students),
             "exchange" => Some(Self::Exchange),
             "analytics" => Some(Self::Analytics),
             "attendance" => Some(Self::Attendance),
@@ -44,6 +46,7 @@ impl SetupSection {
         match self {
             Self::Analysis => "setup.analysis",
             Self::Marks => "setup.marks",
+            Self::Students => "setup.students",
             Self::Exchange => "setup.exchange",
             Self::Analytics => "setup.analytics",
             Self::Attendance => "setup.attendance",
@@ -73,6 +76,9 @@ fn default_section(section: SetupSection) -> Value {
             "defaultHideDeletedEntries": true,
             "defaultAutoPreviewBeforeBulkApply": false
         }),
+        SetupSection::Students => json!({
+            "requireUniqueStudentNo": false
+        }),
         SetupSection::Exchange => json!({
             "defaultExportStudentScope": "valid",
             "includeStateColumnsByDefault": true
@@ -246,6 +252,12 @@ fn merge_section_patch(
                 }
                 _ => return Err(format!("unknown marks field: {}", k)),
             },
+            SetupSection::Students => match k.as_str() {
+                "requireUniqueStudentNo" => {
+                    obj.insert(k.clone(), Value::Bool(parse_bool(v, k)?));
+                }
+                _ => return Err(format!("unknown students field: {}", k)),
+            },
             SetupSection::Exchange => match k.as_str() {
                 "defaultExportStudentScope" => {
                     let s = parse_string_max(v, k, 16)?.to_ascii_lowercase();
@@ -596,6 +608,10 @@ fn handle_setup_get(state: &mut AppState, req: &Request) -> serde_json::Value {
         Ok(v) => v,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
     };
+    let students = match load_section(conn, SetupSection::Students) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
     let exchange = match load_section(conn, SetupSection::Exchange) {
         Ok(v) => v,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
@@ -646,6 +662,7 @@ fn handle_setup_get(state: &mut AppState, req: &Request) -> serde_json::Value {
         json!({
             "analysis": analysis,
             "marks": marks,
+            "students": students,
             "exchange": exchange,
             "analytics": analytics,
             "attendance": attendance,
@@ -688,6 +705,8 @@ fn handle_setup_update(state: &mut AppState, req: &Request) -> serde_json::Value
     ok(&req.id, json!({ "ok": true }))
 }
 
+pub const METHODS: &[&str] = &["setup.get", "setup.update"];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "setup.get" => Some(handle_setup_get(state, req)),
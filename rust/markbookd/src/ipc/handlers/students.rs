@@ -1,7 +1,11 @@
+use super::edits;
+use crate::calc::Pronouns;
+use crate::db;
 use crate::ipc::error::{err, ok};
+use crate::ipc::helpers::validate_iso_date;
 use crate::ipc::types::{AppState, Request};
 use rusqlite::types::Value;
-use rusqlite::{params_from_iter, OptionalExtension};
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
 use serde_json::json;
 use std::collections::HashSet;
 use uuid::Uuid;
@@ -15,64 +19,237 @@ fn handle_students_list(state: &mut AppState, req: &Request) -> serde_json::Valu
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing classId", None),
     };
+    let include_archived = req
+        .params
+        .get("includeArchived")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    // Paging is opt-in; without limit/offset the whole roster comes back as before.
+    let limit = match req.params.get("limit") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => match v.as_u64() {
+            Some(n) if n > 0 => Some(n as i64),
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "limit must be a positive integer",
+                    None,
+                )
+            }
+        },
+    };
+    let offset = match req.params.get("offset") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => match v.as_u64() {
+            Some(n) => Some(n as i64),
+            None => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "offset must be a non-negative integer",
+                    None,
+                )
+            }
+        },
+    };
+    let paged = limit.is_some() || offset.is_some();
 
     let mut stmt = match conn.prepare(
-        "SELECT id, last_name, first_name, student_no, birth_date, active, sort_order
+        "SELECT id, last_name, first_name, student_no, birth_date, active, sort_order, archived,
+                pronouns
          FROM students
-         WHERE class_id = ?
-         ORDER BY sort_order",
+         WHERE class_id = ? AND (? OR archived = 0)
+         ORDER BY sort_order
+         LIMIT ? OFFSET ?",
     ) {
         Ok(s) => s,
         Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
     };
 
     let rows = stmt
-        .query_map([&class_id], |row| {
-            let id: String = row.get(0)?;
-            let last_name: String = row.get(1)?;
-            let first_name: String = row.get(2)?;
-            let student_no: Option<String> = row.get(3)?;
-            let birth_date: Option<String> = row.get(4)?;
-            let active: i64 = row.get(5)?;
-            let sort_order: i64 = row.get(6)?;
-
-            let display_name = format!("{}, {}", last_name, first_name);
-            let student_no = student_no.and_then(|s| {
-                let t = s.trim().to_string();
-                if t.is_empty() {
-                    None
-                } else {
-                    Some(t)
-                }
-            });
-            let birth_date = birth_date.and_then(|s| {
-                let t = s.trim().to_string();
-                if t.is_empty() {
-                    None
-                } else {
-                    Some(t)
-                }
-            });
+        .query_map(
+            (
+                &class_id,
+                include_archived,
+                limit.unwrap_or(-1),
+                offset.unwrap_or(0),
+            ),
+            |row| {
+                let id: String = row.get(0)?;
+                let last_name: String = row.get(1)?;
+                let first_name: String = row.get(2)?;
+                let student_no: Option<String> = row.get(3)?;
+                let birth_date: Option<String> = row.get(4)?;
+                let active: i64 = row.get(5)?;
+                let sort_order: i64 = row.get(6)?;
+                let archived: i64 = row.get(7)?;
+                let pronouns: Option<String> = row.get(8)?;
+
+                let display_name = format!("{}, {}", last_name, first_name);
+                let student_no = student_no.and_then(|s| {
+                    let t = s.trim().to_string();
+                    if t.is_empty() {
+                        None
+                    } else {
+                        Some(t)
+                    }
+                });
+                let birth_date = birth_date.and_then(|s| {
+                    let t = s.trim().to_string();
+                    if t.is_empty() {
+                        None
+                    } else {
+                        Some(t)
+                    }
+                });
+
+                Ok(json!({
+                    "id": id,
+                    "lastName": last_name,
+                    "firstName": first_name,
+                    "displayName": display_name,
+                    "studentNo": student_no,
+                    "birthDate": birth_date,
+                    "active": active != 0,
+                    "sortOrder": sort_order,
+                    "archived": archived != 0,
+                    "pronouns": pronouns
+                }))
+            },
+        )
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>());
+
+    let students = match rows {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if !paged {
+        return ok(&req.id, json!({ "students": students }));
+    }
+    let total: i64 = match conn.query_row(
+        "SELECT COUNT(*) FROM students WHERE class_id = ? AND (? OR archived = 0)",
+        (&class_id, include_archived),
+        |r| r.get(0),
+    ) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    ok(&req.id, json!({ "students": students, "total": total }))
+}
+
+fn handle_students_search(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let query = match req.params.get("query").and_then(|v| v.as_str()) {
+        Some(v) if !v.trim().is_empty() => v.trim().to_lowercase(),
+        Some(_) => return err(&req.id, "bad_params", "query must not be empty", None),
+        None => return err(&req.id, "bad_params", "missing query", None),
+    };
+    let limit = match req.params.get("limit") {
+        None | Some(serde_json::Value::Null) => 50,
+        Some(v) => match v.as_u64() {
+            Some(n) if n > 0 => n as i64,
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "limit must be a positive integer",
+                    None,
+                )
+            }
+        },
+    };
+
+    // instr() keeps user input literal; LIKE would treat % and _ as wildcards.
+    let mut stmt = match conn.prepare(
+        "SELECT s.id, s.class_id, c.name, s.last_name, s.first_name, s.student_no
+         FROM students s
+         JOIN classes c ON c.id = s.class_id
+         WHERE s.archived = 0
+           AND (instr(lower(s.last_name), ?1) > 0
+             OR instr(lower(s.first_name), ?1) > 0
+             OR instr(lower(COALESCE(s.student_no, '')), ?1) > 0)
+         ORDER BY lower(s.last_name), lower(s.first_name), c.name
+         LIMIT ?2",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
 
+    let rows = stmt
+        .query_map((&query, limit), |row| {
+            let last_name: String = row.get(3)?;
+            let first_name: String = row.get(4)?;
+            let student_no: Option<String> = row.get(5)?;
             Ok(json!({
-                "id": id,
-                "lastName": last_name,
-                "firstName": first_name,
-                "displayName": display_name,
-                "studentNo": student_no,
-                "birthDate": birth_date,
-                "active": active != 0,
-                "sortOrder": sort_order
+                "studentId": row.get::<_, String>(0)?,
+                "classId": row.get::<_, String>(1)?,
+                "className": row.get::<_, String>(2)?,
+                "displayName": format!("{}, {}", last_name, first_name),
+                "studentNo": student_no
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
             }))
         })
         .and_then(|it| it.collect::<Result<Vec<_>, _>>());
 
     match rows {
-        Ok(students) => ok(&req.id, json!({ "students": students })),
+        Ok(results) => ok(&req.id, json!({ "results": results })),
         Err(e) => err(&req.id, "db_query_failed", e.to_string(), None),
     }
 }
 
+/// Whether `setup.students.requireUniqueStudentNo` is switched on for the workspace.
+fn unique_student_no_required(conn: &Connection) -> anyhow::Result<bool> {
+    Ok(db::settings_get_json(conn, "setup.students")?
+        .and_then(|v| v.get("requireUniqueStudentNo").and_then(|b| b.as_bool()))
+        .unwrap_or(false))
+}
+
+/// True when another student in the class already uses `student_no`.
+fn student_no_taken(
+    conn: &Connection,
+    class_id: &str,
+    student_no: &str,
+    except_student_id: Option<&str>,
+) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(
+           SELECT 1 FROM students
+           WHERE class_id = ? AND TRIM(student_no) = ? AND id IS NOT ?
+         )",
+        (class_id, student_no, except_student_id),
+        |r| r.get(0),
+    )
+}
+
+/// True when the uniqueness rule is on and `student_no` is already taken.
+fn student_no_conflict(
+    conn: &Connection,
+    class_id: &str,
+    student_no: &str,
+    except_student_id: Option<&str>,
+) -> anyhow::Result<bool> {
+    Ok(unique_student_no_required(conn)?
+        && student_no_taken(conn, class_id, student_no, except_student_id)?)
+}
+
+fn duplicate_student_no(id: &str, student_no: &str, index: Option<usize>) -> serde_json::Value {
+    let mut details = json!({ "studentNo": student_no });
+    if let Some(index) = index {
+        details["index"] = json!(index);
+    }
+    err(
+        id,
+        "duplicate_student_no",
+        "another student in this class has that student number",
+        Some(details),
+    )
+}
+
 fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -112,12 +289,38 @@ fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Va
         .and_then(|v| v.as_str())
         .map(|s| s.trim().to_string())
         .and_then(|s| if s.is_empty() { None } else { Some(s) });
+    if birth_date.as_deref().is_some_and(|d| !validate_iso_date(d)) {
+        return err(
+            &req.id,
+            "bad_params",
+            "birthDate must be a YYYY-MM-DD date",
+            Some(json!({ "field": "birthDate" })),
+        );
+    }
     let active = req
         .params
         .get("active")
         .and_then(|v| v.as_bool())
         .unwrap_or(true);
     let active_i = if active { 1 } else { 0 };
+    let pronouns = match req.params.get("pronouns") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(v) => match v.as_str().map(str::trim) {
+            Some("") => None,
+            Some(s) => match Pronouns::parse(s) {
+                Some(p) => Some(p.as_str()),
+                None => {
+                    return err(
+                        &req.id,
+                        "bad_params",
+                        "pronouns must be he, she or they",
+                        None,
+                    )
+                }
+            },
+            None => return err(&req.id, "bad_params", "pronouns must be a string", None),
+        },
+    };
 
     let class_exists: Option<i64> = match conn
         .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
@@ -132,6 +335,14 @@ fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Va
         return err(&req.id, "not_found", "class not found", None);
     }
 
+    if let Some(no) = student_no.as_deref() {
+        match student_no_conflict(conn, &class_id, no, None) {
+            Ok(false) => {}
+            Ok(true) => return duplicate_student_no(&req.id, no, None),
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        }
+    }
+
     let sort_order: i64 = match conn.query_row(
         "SELECT COALESCE(MAX(sort_order), -1) + 1 FROM students WHERE class_id = ?",
         [&class_id],
@@ -154,8 +365,9 @@ fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Va
            sort_order,
            raw_line,
            mark_set_mask,
+           pronouns,
            updated_at
-         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
+         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
         (
             &student_id,
             &class_id,
@@ -167,6 +379,7 @@ fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Va
             sort_order,
             "",
             "TBA",
+            pronouns,
         ),
     ) {
         return err(
@@ -180,6 +393,177 @@ fn handle_students_create(state: &mut AppState, req: &Request) -> serde_json::Va
     ok(&req.id, json!({ "studentId": student_id }))
 }
 
+struct NewStudent {
+    last_name: String,
+    first_name: String,
+    student_no: Option<String>,
+    birth_date: Option<String>,
+    active: bool,
+}
+
+/// Validation failure for one bulk entry: the message and, when one field is to
+/// blame, its name.
+type EntryError = (&'static str, Option<&'static str>);
+
+fn parse_new_student(entry: &serde_json::Value) -> Result<NewStudent, EntryError> {
+    let Some(obj) = entry.as_object() else {
+        return Err(("student entry must be an object", None));
+    };
+    let name = |key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let last_name = name("lastName");
+    let first_name = name("firstName");
+    if last_name.is_empty() || first_name.is_empty() {
+        return Err(("firstName/lastName must not be empty", None));
+    }
+    let optional = |key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let birth_date = optional("birthDate");
+    if birth_date.as_deref().is_some_and(|d| !validate_iso_date(d)) {
+        return Err(("birthDate must be a YYYY-MM-DD date", Some("birthDate")));
+    }
+    Ok(NewStudent {
+        last_name,
+        first_name,
+        student_no: optional("studentNo"),
+        birth_date,
+        active: obj.get("active").and_then(|v| v.as_bool()).unwrap_or(true),
+    })
+}
+
+fn handle_students_bulk_create(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let Some(entries) = req.params.get("students").and_then(|v| v.as_array()) else {
+        return err(&req.id, "bad_params", "missing students", None);
+    };
+
+    // Validate everything up front so a bad row never leaves a partial roster.
+    let mut students = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        match parse_new_student(entry) {
+            Ok(s) => students.push(s),
+            Err((message, field)) => {
+                let mut details = json!({ "index": index });
+                if let Some(field) = field {
+                    details["field"] = json!(field);
+                }
+                return err(&req.id, "bad_params", message, Some(details));
+            }
+        }
+    }
+
+    let class_exists: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if class_exists.is_none() {
+        return err(&req.id, "not_found", "class not found", None);
+    }
+
+    let require_unique = match unique_student_no_required(conn) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if require_unique {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for (index, s) in students.iter().enumerate() {
+            let Some(no) = s.student_no.as_deref() else {
+                continue;
+            };
+            let taken = match student_no_taken(conn, &class_id, no, None) {
+                Ok(v) => v,
+                Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+            };
+            if taken || !seen.insert(no) {
+                return duplicate_student_no(&req.id, no, Some(index));
+            }
+        }
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+    let next_sort_order: i64 = match tx.query_row(
+        "SELECT COALESCE(MAX(sort_order), -1) + 1 FROM students WHERE class_id = ?",
+        [&class_id],
+        |r| r.get(0),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(&req.id, "db_query_failed", e.to_string(), None);
+        }
+    };
+
+    let mut created_ids = Vec::with_capacity(students.len());
+    for (offset, s) in students.iter().enumerate() {
+        let student_id = Uuid::new_v4().to_string();
+        if let Err(e) = tx.execute(
+            "INSERT INTO students(
+               id,
+               class_id,
+               last_name,
+               first_name,
+               student_no,
+               birth_date,
+               active,
+               sort_order,
+               raw_line,
+               mark_set_mask,
+               updated_at
+             ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ','now'))",
+            (
+                &student_id,
+                &class_id,
+                &s.last_name,
+                &s.first_name,
+                s.student_no.as_deref(),
+                s.birth_date.as_deref(),
+                if s.active { 1 } else { 0 },
+                next_sort_order + offset as i64,
+                "",
+                "TBA",
+            ),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_insert_failed",
+                e.to_string(),
+                Some(json!({ "table": "students", "index": offset })),
+            );
+        }
+        created_ids.push(student_id);
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "createdStudentIds": created_ids }))
+}
+
 fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -200,6 +584,7 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
 
     let mut set_parts: Vec<String> = Vec::new();
     let mut bind_values: Vec<Value> = Vec::new();
+    let mut new_student_no: Option<String> = None;
 
     if let Some(v) = patch.get("lastName") {
         let Some(s) = v.as_str() else {
@@ -245,6 +630,7 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
             if t.is_empty() {
                 bind_values.push(Value::Null);
             } else {
+                new_student_no = Some(t.clone());
                 bind_values.push(Value::Text(t));
             }
         } else {
@@ -263,6 +649,14 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
             bind_values.push(Value::Null);
         } else if let Some(s) = v.as_str() {
             let t = s.trim().to_string();
+            if !t.is_empty() && !validate_iso_date(&t) {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "birthDate must be a YYYY-MM-DD date",
+                    Some(json!({ "field": "birthDate" })),
+                );
+            }
             set_parts.push("birth_date = ?".into());
             if t.is_empty() {
                 bind_values.push(Value::Null);
@@ -292,6 +686,34 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
         bind_values.push(Value::Integer(if b { 1 } else { 0 }));
     }
 
+    if let Some(v) = patch.get("pronouns") {
+        let value = match v.as_str().map(str::trim) {
+            _ if v.is_null() => Value::Null,
+            Some("") => Value::Null,
+            Some(s) => match Pronouns::parse(s) {
+                Some(p) => Value::Text(p.as_str().to_string()),
+                None => {
+                    return err(
+                        &req.id,
+                        "bad_params",
+                        "patch.pronouns must be he, she or they",
+                        None,
+                    )
+                }
+            },
+            None => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "patch.pronouns must be a string or null",
+                    None,
+                )
+            }
+        };
+        set_parts.push("pronouns = ?".into());
+        bind_values.push(value);
+    }
+
     if set_parts.is_empty() {
         return err(
             &req.id,
@@ -301,6 +723,14 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
         );
     }
 
+    if let Some(no) = new_student_no.as_deref() {
+        match student_no_conflict(conn, &class_id, no, Some(&student_id)) {
+            Ok(false) => {}
+            Ok(true) => return duplicate_student_no(&req.id, no, None),
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        }
+    }
+
     set_parts.push("updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')".into());
 
     let sql = format!(
@@ -329,7 +759,646 @@ fn handle_students_update(state: &mut AppState, req: &Request) -> serde_json::Va
     ok(&req.id, json!({ "ok": true }))
 }
 
-fn handle_students_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
+fn handle_students_reorder(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+
+    let Some(arr) = req
+        .params
+        .get("orderedStudentIds")
+        .and_then(|v| v.as_array())
+    else {
+        return err(
+            &req.id,
+            "bad_params",
+            "missing/invalid orderedStudentIds",
+            None,
+        );
+    };
+
+    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
+    for v in arr {
+        let Some(s) = v.as_str() else {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedStudentIds must be strings",
+                None,
+            );
+        };
+        ordered.push(s.to_string());
+    }
+
+    // Archived students are not part of the visible roster; they keep their
+    // relative order after everyone in orderedStudentIds.
+    let mut stmt = match conn
+        .prepare("SELECT id, archived FROM students WHERE class_id = ? ORDER BY sort_order")
+    {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    let (archived_ids, current_ids): (Vec<String>, Vec<String>) = match stmt
+        .query_map([&class_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? != 0))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(v) => {
+            let (archived, current): (Vec<_>, Vec<_>) = v.into_iter().partition(|(_, a)| *a);
+            (
+                archived.into_iter().map(|(id, _)| id).collect(),
+                current.into_iter().map(|(id, _)| id).collect(),
+            )
+        }
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    if ordered.len() != current_ids.len() {
+        return err(
+            &req.id,
+            "bad_params",
+            "orderedStudentIds must be a permutation of the class students",
+            Some(json!({ "expected": current_ids.len(), "got": ordered.len() })),
+        );
+    }
+
+    let current_set: HashSet<String> = current_ids.into_iter().collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    for id in &ordered {
+        if !seen.insert(id.clone()) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedStudentIds contains duplicates",
+                Some(json!({ "studentId": id })),
+            );
+        }
+        if !current_set.contains(id) {
+            return err(
+                &req.id,
+                "bad_params",
+                "orderedStudentIds contains unknown studentId",
+                Some(json!({ "studentId": id })),
+            );
+        }
+    }
+
+    if seen.len() != current_set.len() {
+        return err(
+            &req.id,
+            "bad_params",
+            "orderedStudentIds must include every student exactly once",
+            None,
+        );
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+
+    for (i, sid) in ordered.iter().chain(archived_ids.iter()).enumerate() {
+        if let Err(e) = tx.execute(
+            "UPDATE students
+             SET sort_order = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+             WHERE id = ? AND class_id = ?",
+            (i as i64, sid, &class_id),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": "students" })),
+            );
+        }
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
+fn handle_students_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+
+    let sort_order: Option<i64> = match conn
+        .query_row(
+            "SELECT sort_order FROM students WHERE id = ? AND class_id = ?",
+            (&student_id, &class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let Some(sort_order) = sort_order else {
+        return err(&req.id, "not_found", "student not found", None);
+    };
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+
+    // Snapshot everything the delete removes so edits.undo can restore it.
+    let snapshot = match edits::student_snapshot(&tx, &class_id, &student_id) {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    if let Err(e) = tx.execute("DELETE FROM scores WHERE student_id = ?", [&student_id]) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "scores" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM score_history WHERE student_id = ?",
+        [&student_id],
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "score_history" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM student_notes WHERE class_id = ? AND student_id = ?",
+        (&class_id, &student_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "student_notes" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM attendance_student_months WHERE class_id = ? AND student_id = ?",
+        (&class_id, &student_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "attendance_student_months" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM seating_assignments WHERE class_id = ? AND student_id = ?",
+        (&class_id, &student_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "seating_assignments" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM comment_set_remarks WHERE student_id = ?",
+        (&student_id,),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "comment_set_remarks" })),
+        );
+    }
+
+    let changed = match tx.execute(
+        "DELETE FROM students WHERE id = ? AND class_id = ?",
+        (&student_id, &class_id),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_delete_failed",
+                e.to_string(),
+                Some(json!({ "table": "students" })),
+            );
+        }
+    };
+    if changed == 0 {
+        let _ = tx.rollback();
+        return err(&req.id, "not_found", "student not found", None);
+    }
+
+    // Keep sort_order contiguous so grid row indices remain stable.
+    if let Err(e) = tx.execute(
+        "UPDATE students
+         SET sort_order = sort_order - 1,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE class_id = ? AND sort_order > ?",
+        (&class_id, sort_order),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_update_failed",
+            e.to_string(),
+            Some(json!({ "table": "students" })),
+        );
+    }
+
+    if let Err(e) = edits::record(&tx, &class_id, "students.delete", &snapshot) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_insert_failed",
+            e.to_string(),
+            Some(json!({ "table": "edit_log" })),
+        );
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(&req.id, json!({ "ok": true }))
+}
+
+/// Moves a student to another class, carrying notes, attendance, learning
+/// skills, loaned items, device mapping and (when the seat is free) seating.
+/// Scores and comment remarks hang off the source class's mark sets, so they
+/// are dropped; the mark set mask is reset since it indexes source mark sets.
+fn handle_students_move(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let from_class_id = match req.params.get("fromClassId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing fromClassId", None),
+    };
+    let to_class_id = match req.params.get("toClassId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing toClassId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+    if from_class_id == to_class_id {
+        return err(
+            &req.id,
+            "bad_params",
+            "fromClassId and toClassId must differ",
+            None,
+        );
+    }
+
+    let sort_order: Option<i64> = match conn
+        .query_row(
+            "SELECT sort_order FROM students WHERE id = ? AND class_id = ?",
+            (&student_id, &from_class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let Some(sort_order) = sort_order else {
+        return err(&req.id, "not_found", "student not found", None);
+    };
+    let to_exists: Option<i64> = match conn
+        .query_row("SELECT 1 FROM classes WHERE id = ?", [&to_class_id], |r| {
+            r.get(0)
+        })
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    if to_exists.is_none() {
+        return err(&req.id, "not_found", "destination class not found", None);
+    }
+
+    let tx = match conn.unchecked_transaction() {
+        Ok(t) => t,
+        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
+    };
+
+    let dropped_scores = match tx.execute(
+        "DELETE FROM scores
+         WHERE student_id = ?
+           AND assessment_id IN (
+             SELECT a.id
+             FROM assessments a
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             WHERE ms.class_id = ?
+           )",
+        (&student_id, &from_class_id),
+    ) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_delete_failed",
+                e.to_string(),
+                Some(json!({ "table": "scores" })),
+            );
+        }
+    };
+    if let Err(e) = tx.execute(
+        "DELETE FROM score_history
+         WHERE student_id = ?
+           AND assessment_id IN (
+             SELECT a.id
+             FROM assessments a
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             WHERE ms.class_id = ?
+           )",
+        (&student_id, &from_class_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "score_history" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "DELETE FROM comment_set_remarks
+         WHERE student_id = ?
+           AND comment_set_index_id IN (
+             SELECT id FROM comment_set_indexes WHERE class_id = ?
+           )",
+        (&student_id, &from_class_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "comment_set_remarks" })),
+        );
+    }
+
+    // Each statement binds ?1 = toClassId, ?2 = fromClassId, ?3 = studentId.
+    // The default-plan seat only follows the student when it is free in the
+    // destination's default plan; seats in other named plans are dropped.
+    let reassign: [(&str, &str); 6] = [
+        (
+            "student_notes",
+            "UPDATE student_notes SET class_id = ?1 WHERE class_id = ?2 AND student_id = ?3",
+        ),
+        (
+            "attendance_student_months",
+            "UPDATE attendance_student_months SET class_id = ?1
+             WHERE class_id = ?2 AND student_id = ?3",
+        ),
+        (
+            "learning_skills_cells",
+            "UPDATE learning_skills_cells SET class_id = ?1
+             WHERE class_id = ?2 AND student_id = ?3",
+        ),
+        (
+            "loaned_items",
+            "UPDATE loaned_items SET class_id = ?1, mark_set_id = NULL
+             WHERE class_id = ?2 AND student_id = ?3",
+        ),
+        (
+            "student_device_map",
+            "UPDATE student_device_map SET class_id = ?1 WHERE class_id = ?2 AND student_id = ?3",
+        ),
+        (
+            "seating_assignments",
+            "UPDATE seating_assignments
+             SET class_id = ?1,
+                 plan_id = (SELECT id FROM seating_plans WHERE class_id = ?1 AND is_default = 1)
+             WHERE class_id = ?2 AND student_id = ?3
+               AND plan_id = (SELECT id FROM seating_plans WHERE class_id = ?2 AND is_default = 1)
+               AND EXISTS (SELECT 1 FROM seating_plans WHERE class_id = ?1 AND is_default = 1)
+               AND seat_code NOT IN (
+                 SELECT sa.seat_code FROM seating_assignments sa
+                 JOIN seating_plans sp ON sp.id = sa.plan_id
+                 WHERE sp.class_id = ?1 AND sp.is_default = 1
+               )",
+        ),
+    ];
+    for (table, sql) in reassign {
+        if let Err(e) = tx.execute(sql, (&to_class_id, &from_class_id, &student_id)) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_update_failed",
+                e.to_string(),
+                Some(json!({ "table": table })),
+            );
+        }
+    }
+    if let Err(e) = tx.execute(
+        "DELETE FROM seating_assignments WHERE class_id = ? AND student_id = ?",
+        (&from_class_id, &student_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_delete_failed",
+            e.to_string(),
+            Some(json!({ "table": "seating_assignments" })),
+        );
+    }
+
+    if let Err(e) = tx.execute(
+        "UPDATE students
+         SET class_id = ?1,
+             sort_order = (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM students WHERE class_id = ?1),
+             mark_set_mask = 'TBA',
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE id = ?2",
+        (&to_class_id, &student_id),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_update_failed",
+            e.to_string(),
+            Some(json!({ "table": "students" })),
+        );
+    }
+
+    // Keep the source class's sort_order contiguous.
+    if let Err(e) = tx.execute(
+        "UPDATE students
+         SET sort_order = sort_order - 1,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE class_id = ? AND sort_order > ?",
+        (&from_class_id, sort_order),
+    ) {
+        let _ = tx.rollback();
+        return err(
+            &req.id,
+            "db_update_failed",
+            e.to_string(),
+            Some(json!({ "table": "students" })),
+        );
+    }
+
+    if let Err(e) = tx.commit() {
+        return err(&req.id, "db_commit_failed", e.to_string(), None);
+    }
+
+    ok(
+        &req.id,
+        json!({ "ok": true, "droppedScores": dropped_scores }),
+    )
+}
+
+/// Lower-cased name part with runs of whitespace collapsed, so "  van  Dyke"
+/// and "Van Dyke" compare equal.
+fn normalize_name_part(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Groups students whose normalized last and first names match (and birth
+/// dates, with `matchBirthDate`). Archived students are included since they
+/// are the usual leftovers of a re-import.
+fn handle_students_find_duplicates(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let match_birth_date = req
+        .params
+        .get("matchBirthDate")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let mut stmt = match conn.prepare(
+        "SELECT id, last_name, first_name, student_no, birth_date, active, sort_order, archived
+         FROM students
+         WHERE class_id = ?
+         ORDER BY sort_order",
+    ) {
+        Ok(s) => s,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let rows = stmt
+        .query_map([&class_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, i64>(5)? != 0,
+                row.get::<_, i64>(6)?,
+                row.get::<_, i64>(7)? != 0,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>());
+    let rows = match rows {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+
+    // Groups keep roster order: first by their earliest student, then within.
+    let mut keys: Vec<(String, String, Option<String>)> = Vec::new();
+    let mut groups: Vec<Vec<serde_json::Value>> = Vec::new();
+    for (id, last_name, first_name, student_no, birth_date, active, sort_order, archived) in rows {
+        let birth_date = birth_date
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let key = (
+            normalize_name_part(&last_name),
+            normalize_name_part(&first_name),
+            if match_birth_date {
+                birth_date.clone()
+            } else {
+                None
+            },
+        );
+        let student = json!({
+            "id": id,
+            "displayName": format!("{}, {}", last_name, first_name),
+            "studentNo": student_no.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            "birthDate": birth_date,
+            "active": active,
+            "sortOrder": sort_order,
+            "archived": archived
+        });
+        match keys.iter().position(|k| *k == key) {
+            Some(i) => groups[i].push(student),
+            None => {
+                keys.push(key);
+                groups.push(vec![student]);
+            }
+        }
+    }
+
+    let groups: Vec<serde_json::Value> = keys
+        .into_iter()
+        .zip(groups)
+        .filter(|(_, students)| students.len() > 1)
+        .map(|((last_name, first_name, birth_date), students)| {
+            json!({
+                "lastName": last_name,
+                "firstName": first_name,
+                "birthDate": birth_date,
+                "students": students
+            })
+        })
+        .collect();
+    ok(&req.id, json!({ "groups": groups }))
+}
+
+/// Folds `mergeIds` into `keepId` within one class: every row that references a
+/// merged student is re-pointed to the kept one, then the merged students are
+/// deleted. Where the kept student already has the conflicting row (same score
+/// cell, month, seat plan, ...) the kept row wins and the merged row is dropped.
+fn handle_students_merge(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
@@ -338,84 +1407,59 @@ fn handle_students_reorder(state: &mut AppState, req: &Request) -> serde_json::V
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing classId", None),
     };
-
-    let Some(arr) = req
-        .params
-        .get("orderedStudentIds")
-        .and_then(|v| v.as_array())
-    else {
-        return err(
-            &req.id,
-            "bad_params",
-            "missing/invalid orderedStudentIds",
-            None,
-        );
+    let keep_id = match req.params.get("keepId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing keepId", None),
     };
-
-    let mut ordered: Vec<String> = Vec::with_capacity(arr.len());
-    for v in arr {
-        let Some(s) = v.as_str() else {
-            return err(
-                &req.id,
-                "bad_params",
-                "orderedStudentIds must be strings",
-                None,
-            );
-        };
-        ordered.push(s.to_string());
-    }
-
-    let mut stmt =
-        match conn.prepare("SELECT id FROM students WHERE class_id = ? ORDER BY sort_order") {
-            Ok(s) => s,
-            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
-        };
-
-    let current_ids: Vec<String> = match stmt
-        .query_map([&class_id], |row| row.get::<_, String>(0))
-        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
-    {
-        Ok(v) => v,
-        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    let Some(raw_merge_ids) = req.params.get("mergeIds").and_then(|v| v.as_array()) else {
+        return err(&req.id, "bad_params", "missing mergeIds", None);
     };
-
-    if ordered.len() != current_ids.len() {
-        return err(
-            &req.id,
-            "bad_params",
-            "orderedStudentIds must be a permutation of the class students",
-            Some(json!({ "expected": current_ids.len(), "got": ordered.len() })),
-        );
-    }
-
-    let current_set: HashSet<String> = current_ids.into_iter().collect();
-    let mut seen: HashSet<String> = HashSet::new();
-    for id in &ordered {
-        if !seen.insert(id.clone()) {
+    let mut merge_ids: Vec<String> = Vec::with_capacity(raw_merge_ids.len());
+    for v in raw_merge_ids {
+        let Some(id) = v.as_str() else {
+            return err(&req.id, "bad_params", "mergeIds must be strings", None);
+        };
+        if id == keep_id {
             return err(
                 &req.id,
                 "bad_params",
-                "orderedStudentIds contains duplicates",
-                Some(json!({ "studentId": id })),
+                "mergeIds must not include keepId",
+                None,
             );
         }
-        if !current_set.contains(id) {
+        if merge_ids.iter().any(|m| m == id) {
             return err(
                 &req.id,
                 "bad_params",
-                "orderedStudentIds contains unknown studentId",
+                "mergeIds contains duplicates",
                 Some(json!({ "studentId": id })),
             );
         }
+        merge_ids.push(id.to_string());
     }
-
-    if seen.len() != current_set.len() {
-        return err(
-            &req.id,
-            "bad_params",
-            "orderedStudentIds must include every student exactly once",
-            None,
-        );
+    if merge_ids.is_empty() {
+        return err(&req.id, "bad_params", "mergeIds must not be empty", None);
+    }
+    for id in std::iter::once(&keep_id).chain(merge_ids.iter()) {
+        match conn
+            .query_row(
+                "SELECT 1 FROM students WHERE id = ? AND class_id = ?",
+                (id, &class_id),
+                |r| r.get::<_, i64>(0),
+            )
+            .optional()
+        {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return err(
+                    &req.id,
+                    "not_found",
+                    "student not found",
+                    Some(json!({ "studentId": id })),
+                )
+            }
+            Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+        }
     }
 
     let tx = match conn.unchecked_transaction() {
@@ -423,7 +1467,123 @@ fn handle_students_reorder(state: &mut AppState, req: &Request) -> serde_json::V
         Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
     };
 
-    for (i, sid) in ordered.iter().enumerate() {
+    // Each statement binds ?1 = keepId, ?2 = merged student id. Rows the
+    // UPDATE OR IGNORE leaves behind collided with the kept student's own.
+    // Legacy import writes a no_mark row for every blank cell, so a merged
+    // mark first replaces a kept no_mark before the collision drops it.
+    const FILL_NO_MARK_SCORES: &str = "UPDATE scores
+         SET raw_value = (SELECT m.raw_value FROM scores m
+                          WHERE m.student_id = ?2 AND m.assessment_id = scores.assessment_id),
+             status = (SELECT m.status FROM scores m
+                       WHERE m.student_id = ?2 AND m.assessment_id = scores.assessment_id),
+             remark = COALESCE((SELECT m.remark FROM scores m
+                                WHERE m.student_id = ?2 AND m.assessment_id = scores.assessment_id),
+                               remark)
+         WHERE student_id = ?1 AND status = 'no_mark'
+           AND EXISTS (SELECT 1 FROM scores m
+                       WHERE m.student_id = ?2 AND m.assessment_id = scores.assessment_id
+                         AND m.status <> 'no_mark')";
+    let tables: [&str; 9] = [
+        "scores",
+        "score_history",
+        "student_notes",
+        "attendance_student_months",
+        "seating_assignments",
+        "comment_set_remarks",
+        "learning_skills_cells",
+        "loaned_items",
+        "student_device_map",
+    ];
+    let mut repointed: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    let mut dropped: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    for table in tables {
+        let mut moved = 0usize;
+        let mut removed = 0usize;
+        for merge_id in &merge_ids {
+            let mut filled = 0usize;
+            if table == "scores" {
+                match tx.execute(FILL_NO_MARK_SCORES, (&keep_id, merge_id)) {
+                    Ok(n) => filled = n,
+                    Err(e) => {
+                        let _ = tx.rollback();
+                        return err(
+                            &req.id,
+                            "db_update_failed",
+                            e.to_string(),
+                            Some(json!({ "table": table })),
+                        );
+                    }
+                }
+            }
+            moved += filled;
+            match tx.execute(
+                &format!(
+                    "UPDATE OR IGNORE {} SET student_id = ?1 WHERE student_id = ?2",
+                    table
+                ),
+                (&keep_id, merge_id),
+            ) {
+                Ok(n) => moved += n,
+                Err(e) => {
+                    let _ = tx.rollback();
+                    return err(
+                        &req.id,
+                        "db_update_failed",
+                        e.to_string(),
+                        Some(json!({ "table": table })),
+                    );
+                }
+            }
+            match tx.execute(
+                &format!("DELETE FROM {} WHERE student_id = ?", table),
+                [merge_id],
+            ) {
+                Ok(n) => removed += n - filled,
+                Err(e) => {
+                    let _ = tx.rollback();
+                    return err(
+                        &req.id,
+                        "db_delete_failed",
+                        e.to_string(),
+                        Some(json!({ "table": table })),
+                    );
+                }
+            }
+        }
+        repointed.insert(table.to_string(), json!(moved));
+        dropped.insert(table.to_string(), json!(removed));
+    }
+
+    for merge_id in &merge_ids {
+        if let Err(e) = tx.execute(
+            "DELETE FROM students WHERE id = ? AND class_id = ?",
+            (merge_id, &class_id),
+        ) {
+            let _ = tx.rollback();
+            return err(
+                &req.id,
+                "db_delete_failed",
+                e.to_string(),
+                Some(json!({ "table": "students" })),
+            );
+        }
+    }
+
+    // Keep sort_order contiguous so grid row indices remain stable. Renumber from
+    // the current order one row at a time, as students.reorder does.
+    let remaining: Vec<String> = match tx
+        .prepare("SELECT id FROM students WHERE class_id = ? ORDER BY sort_order")
+        .and_then(|mut stmt| {
+            stmt.query_map([&class_id], |r| r.get(0))
+                .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        }) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = tx.rollback();
+            return err(&req.id, "db_query_failed", e.to_string(), None);
+        }
+    };
+    for (i, sid) in remaining.iter().enumerate() {
         if let Err(e) = tx.execute(
             "UPDATE students
              SET sort_order = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
@@ -444,10 +1604,21 @@ fn handle_students_reorder(state: &mut AppState, req: &Request) -> serde_json::V
         return err(&req.id, "db_commit_failed", e.to_string(), None);
     }
 
-    ok(&req.id, json!({ "ok": true }))
+    ok(
+        &req.id,
+        json!({
+            "ok": true,
+            "keepId": keep_id,
+            "merged": merge_ids.len(),
+            "repointed": repointed,
+            "dropped": dropped
+        }),
+    )
 }
 
-fn handle_students_delete(state: &mut AppState, req: &Request) -> serde_json::Value {
+/// Archiving hides a student from rosters, the marks grid and calc averages
+/// without touching any of their data; unarchive restores them in place.
+fn set_student_archived(state: &mut AppState, req: &Request, archived: bool) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
     };
@@ -461,130 +1632,35 @@ fn handle_students_delete(state: &mut AppState, req: &Request) -> serde_json::Va
         None => return err(&req.id, "bad_params", "missing studentId", None),
     };
 
-    let sort_order: Option<i64> = match conn
-        .query_row(
-            "SELECT sort_order FROM students WHERE id = ? AND class_id = ?",
-            (&student_id, &class_id),
-            |r| r.get(0),
-        )
-        .optional()
-    {
-        Ok(v) => v,
-        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
-    };
-    let Some(sort_order) = sort_order else {
-        return err(&req.id, "not_found", "student not found", None);
-    };
-
-    let tx = match conn.unchecked_transaction() {
-        Ok(t) => t,
-        Err(e) => return err(&req.id, "db_tx_failed", e.to_string(), None),
-    };
-
-    if let Err(e) = tx.execute("DELETE FROM scores WHERE student_id = ?", [&student_id]) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "scores" })),
-        );
-    }
-
-    if let Err(e) = tx.execute(
-        "DELETE FROM student_notes WHERE class_id = ? AND student_id = ?",
-        (&class_id, &student_id),
-    ) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "student_notes" })),
-        );
-    }
-
-    if let Err(e) = tx.execute(
-        "DELETE FROM attendance_student_months WHERE class_id = ? AND student_id = ?",
-        (&class_id, &student_id),
-    ) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "attendance_student_months" })),
-        );
-    }
-
-    if let Err(e) = tx.execute(
-        "DELETE FROM seating_assignments WHERE class_id = ? AND student_id = ?",
-        (&class_id, &student_id),
-    ) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "seating_assignments" })),
-        );
-    }
-
-    if let Err(e) = tx.execute(
-        "DELETE FROM comment_set_remarks WHERE student_id = ?",
-        (&student_id,),
-    ) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_delete_failed",
-            e.to_string(),
-            Some(json!({ "table": "comment_set_remarks" })),
-        );
-    }
-
-    let changed = match tx.execute(
-        "DELETE FROM students WHERE id = ? AND class_id = ?",
-        (&student_id, &class_id),
+    let changed = match conn.execute(
+        "UPDATE students
+         SET archived = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
+         WHERE id = ? AND class_id = ?",
+        (archived as i64, &student_id, &class_id),
     ) {
         Ok(v) => v,
         Err(e) => {
-            let _ = tx.rollback();
             return err(
                 &req.id,
-                "db_delete_failed",
+                "db_update_failed",
                 e.to_string(),
                 Some(json!({ "table": "students" })),
-            );
+            )
         }
     };
     if changed == 0 {
-        let _ = tx.rollback();
         return err(&req.id, "not_found", "student not found", None);
     }
 
-    // Keep sort_order contiguous so grid row indices remain stable.
-    if let Err(e) = tx.execute(
-        "UPDATE students
-         SET sort_order = sort_order - 1,
-             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ','now')
-         WHERE class_id = ? AND sort_order > ?",
-        (&class_id, sort_order),
-    ) {
-        let _ = tx.rollback();
-        return err(
-            &req.id,
-            "db_update_failed",
-            e.to_string(),
-            Some(json!({ "table": "students" })),
-        );
-    }
+    ok(&req.id, json!({ "ok": true, "archived": archived }))
+}
 
-    if let Err(e) = tx.commit() {
-        return err(&req.id, "db_commit_failed", e.to_string(), None);
-    }
+fn handle_students_archive(state: &mut AppState, req: &Request) -> serde_json::Value {
+    set_student_archived(state, req, true)
+}
 
-    ok(&req.id, json!({ "ok": true }))
+fn handle_students_unarchive(state: &mut AppState, req: &Request) -> serde_json::Value {
+    set_student_archived(state, req, false)
 }
 
 fn normalize_mark_set_mask(raw: Option<String>, mark_set_count: usize) -> String {
@@ -980,6 +2056,42 @@ fn handle_notes_get(state: &mut AppState, req: &Request) -> serde_json::Value {
     }
 }
 
+fn handle_notes_get_one(state: &mut AppState, req: &Request) -> serde_json::Value {
+    let Some(conn) = state.db.as_ref() else {
+        return err(&req.id, "no_workspace", "select a workspace first", None);
+    };
+
+    let class_id = match req.params.get("classId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing classId", None),
+    };
+    let student_id = match req.params.get("studentId").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None => return err(&req.id, "bad_params", "missing studentId", None),
+    };
+
+    let note: Option<Option<String>> = match conn
+        .query_row(
+            "SELECT sn.note
+             FROM students s
+             LEFT JOIN student_notes sn
+               ON sn.class_id = s.class_id AND sn.student_id = s.id
+             WHERE s.id = ? AND s.class_id = ?",
+            (&student_id, &class_id),
+            |r| r.get(0),
+        )
+        .optional()
+    {
+        Ok(v) => v,
+        Err(e) => return err(&req.id, "db_query_failed", e.to_string(), None),
+    };
+    let Some(note) = note else {
+        return err(&req.id, "not_found", "student not found", None);
+    };
+
+    ok(&req.id, json!({ "note": note.unwrap_or_default() }))
+}
+
 fn handle_notes_update(state: &mut AppState, req: &Request) -> serde_json::Value {
     let Some(conn) = state.db.as_ref() else {
         return err(&req.id, "no_workspace", "select a workspace first", None);
@@ -997,6 +2109,33 @@ fn handle_notes_update(state: &mut AppState, req: &Request) -> serde_json::Value
         Some(v) => v.to_string(),
         None => return err(&req.id, "bad_params", "missing note", None),
     };
+    // Optional cap so notes fit the report layout; the UI passes its limit.
+    let max_length = match req.params.get("maxLength") {
+        None => None,
+        Some(v) if v.is_null() => None,
+        Some(v) => match v.as_u64() {
+            Some(n) if n > 0 => Some(n as usize),
+            _ => {
+                return err(
+                    &req.id,
+                    "bad_params",
+                    "maxLength must be a positive integer",
+                    None,
+                )
+            }
+        },
+    };
+    if let Some(max_length) = max_length {
+        let length = note.trim().chars().count();
+        if length > max_length {
+            return err(
+                &req.id,
+                "bad_params",
+                format!("note exceeds {} characters", max_length),
+                Some(json!({ "maxLength": max_length, "length": length })),
+            );
+        }
+    }
 
     let student_exists: Option<i64> = match conn
         .query_row(
@@ -1048,17 +2187,46 @@ fn handle_notes_update(state: &mut AppState, req: &Request) -> serde_json::Value
     ok(&req.id, json!({ "ok": true }))
 }
 
+pub const METHODS: &[&str] = &[
+    "students.list",
+    "students.search",
+    "students.create",
+    "students.bulkCreate",
+    "students.update",
+    "students.reorder",
+    "students.delete",
+    "students.move",
+    "students.findDuplicates",
+    "students.merge",
+    "students.archive",
+    "students.unarchive",
+    "students.membership.get",
+    "students.membership.set",
+    "students.membership.bulkSet",
+    "notes.get",
+    "notes.getOne",
+    "notes.update",
+];
+
 pub fn try_handle(state: &mut AppState, req: &Request) -> Option<serde_json::Value> {
     match req.method.as_str() {
         "students.list" => Some(handle_students_list(state, req)),
+        "students.search" => Some(handle_students_search(state, req)),
         "students.create" => Some(handle_students_create(state, req)),
+        "students.bulkCreate" => Some(handle_students_bulk_create(state, req)),
         "students.update" => Some(handle_students_update(state, req)),
         "students.reorder" => Some(handle_students_reorder(state, req)),
         "students.delete" => Some(handle_students_delete(state, req)),
+        "students.move" => Some(handle_students_move(state, req)),
+        "students.findDuplicates" => Some(handle_students_find_duplicates(state, req)),
+        "students.merge" => Some(handle_students_merge(state, req)),
+        "students.archive" => Some(handle_students_archive(state, req)),
+        "students.unarchive" => Some(handle_students_unarchive(state, req)),
         "students.membership.get" => Some(handle_students_membership_get(state, req)),
         "students.membership.set" => Some(handle_students_membership_set(state, req)),
         "students.membership.bulkSet" => Some(handle_students_membership_bulk_set(state, req)),
         "notes.get" => Some(handle_notes_get(state, req)),
+        "notes.getOne" => Some(handle_notes_get_one(state, req)),
         "notes.update" => Some(handle_notes_update(state, req)),
         _ => None,
     }
@@ -2,3 +2,105 @@
 pub fn method_in(method: &str, methods: &[&str]) -> bool {
     methods.iter().any(|m| *m == method)
 }
+
+/// True when `value` is a zero-padded `YYYY-MM-DD` naming a real calendar date
+/// (so `2010-02-29` and `2010-13-40` are rejected).
+pub fn validate_iso_date(value: &str) -> bool {
+    let b = value.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter()
+            .enumerate()
+            .all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+        && chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard (RFC 4648) base64 with `=` padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes standard base64. Padding is optional and ASCII whitespace is
+/// skipped; any other character outside the alphabet yields `None`.
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        if c == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return None;
+        }
+        let v = BASE64_ALPHABET.iter().position(|a| *a == c)? as u32;
+        acc = (acc << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    // A lone trailing sextet cannot encode a byte.
+    if bits >= 6 || padding > 2 {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64_decode, base64_encode, validate_iso_date};
+
+    #[test]
+    fn base64_round_trips_and_rejects_garbage() {
+        for (raw, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"\xff\x00\xfe", "/wD+"),
+        ] {
+            assert_eq!(base64_encode(raw), encoded);
+            assert_eq!(base64_decode(encoded).as_deref(), Some(raw));
+        }
+        assert_eq!(base64_decode("Zm9v\nYg").as_deref(), Some(&b"foob"[..]));
+        assert_eq!(base64_decode("Zm9v!"), None);
+        assert_eq!(base64_decode("Z"), None);
+        assert_eq!(base64_decode("Zg==Zg"), None);
+    }
+
+    #[test]
+    fn validate_iso_date_requires_padded_real_dates() {
+        assert!(validate_iso_date("2012-02-29"));
+        assert!(validate_iso_date("2000-02-29"));
+        assert!(!validate_iso_date("2011-02-29"));
+        assert!(!validate_iso_date("1900-02-29"));
+        assert!(!validate_iso_date("2010-13-40"));
+        assert!(!validate_iso_date("2010-4-1"));
+        assert!(!validate_iso_date("2010/04/01"));
+        assert!(!validate_iso_date("+010-04-01"));
+    }
+}
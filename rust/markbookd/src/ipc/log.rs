@@ -0,0 +1,85 @@
+//! Per-request log lines on stderr. Stdout carries the JSON protocol, so nothing here
+//! may ever write to it. Verbosity comes from `MARKBOOKD_LOG`: `off` (default),
+//! `error` (failed requests only), `info` (every request) or `debug` (adds request
+//! ids and error messages).
+
+use std::io::Write;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn parse(s: &str) -> Level {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Level::Error,
+            "info" => Level::Info,
+            "debug" | "trace" => Level::Debug,
+            _ => Level::Off,
+        }
+    }
+}
+
+pub fn level() -> Level {
+    static LEVEL: OnceLock<Level> = OnceLock::new();
+    *LEVEL.get_or_init(|| {
+        std::env::var("MARKBOOKD_LOG")
+            .map(|v| Level::parse(&v))
+            .unwrap_or(Level::Off)
+    })
+}
+
+/// Writes one logfmt-style line for a finished request, e.g.
+/// `markbookd level=error method=grid.get ms=1.25 ok=false code=not_found`.
+pub fn request_done(method: &str, elapsed: Duration, resp: &serde_json::Value) {
+    let level = level();
+    let failed = resp.get("ok").and_then(|v| v.as_bool()) != Some(true);
+    let wanted = if failed { Level::Error } else { Level::Info };
+    if level < wanted {
+        return;
+    }
+
+    let mut line = format!(
+        "markbookd level={} method={} ms={:.2} ok={}",
+        if failed { "error" } else { "info" },
+        method,
+        elapsed.as_secs_f64() * 1000.0,
+        !failed
+    );
+    if level >= Level::Debug {
+        if let Some(id) = resp.get("id").and_then(|v| v.as_str()) {
+            line.push_str(&format!(" id={:?}", id));
+        }
+    }
+    if let Some(error) = resp.get("error") {
+        if let Some(code) = error.get("code").and_then(|v| v.as_str()) {
+            line.push_str(&format!(" code={}", code));
+        }
+        if level >= Level::Debug {
+            if let Some(message) = error.get("message").and_then(|v| v.as_str()) {
+                line.push_str(&format!(" message={:?}", message));
+            }
+        }
+    }
+    let _ = writeln!(std::io::stderr().lock(), "{}", line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Level;
+
+    #[test]
+    fn parse_level_is_case_insensitive_and_defaults_off() {
+        assert_eq!(Level::parse("DEBUG"), Level::Debug);
+        assert_eq!(Level::parse(" info "), Level::Info);
+        assert_eq!(Level::parse("error"), Level::Error);
+        assert_eq!(Level::parse(""), Level::Off);
+        assert_eq!(Level::parse("verbose"), Level::Off);
+    }
+}
@@ -1,8 +1,10 @@
 mod error;
+pub mod framing;
 mod handlers;
 mod helpers;
+mod log;
 mod router;
 mod types;
 
-pub use router::handle_request;
+pub use router::{handle_batch, handle_request};
 pub use types::{AppState, Request};
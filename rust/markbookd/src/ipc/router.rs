@@ -1,8 +1,51 @@
 use super::handlers;
+use super::log;
 use super::types::{AppState, Request};
 use crate::ipc::error::err;
+use serde_json::json;
 
+/// Every method name the router dispatches, sorted. Each handler module keeps its `METHODS`
+/// list next to its `try_handle` match so the two stay in sync.
+pub fn registered_methods() -> Vec<&'static str> {
+    let families: [&[&str]; 17] = [
+        handlers::analytics::METHODS,
+        handlers::core::METHODS,
+        handlers::setup::METHODS,
+        handlers::planner::METHODS,
+        handlers::classes::METHODS,
+        handlers::import_legacy::METHODS,
+        handlers::grid::METHODS,
+        handlers::students::METHODS,
+        handlers::markset_setup::METHODS,
+        handlers::attendance::METHODS,
+        handlers::seating::METHODS,
+        handlers::comments::METHODS,
+        handlers::reports::METHODS,
+        handlers::integrations::METHODS,
+        handlers::backup_exchange::METHODS,
+        handlers::assets::METHODS,
+        handlers::edits::METHODS,
+    ];
+    let mut out: Vec<&'static str> = families.iter().flat_map(|m| m.iter().copied()).collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Dispatches one request and, when `MARKBOOKD_LOG` asks for it, logs its method,
+/// duration and error code to stderr.
 pub fn handle_request(state: &mut AppState, req: Request) -> serde_json::Value {
+    if log::level() == log::Level::Off {
+        return dispatch(state, req);
+    }
+    let method = req.method.clone();
+    let started = std::time::Instant::now();
+    let resp = dispatch(state, req);
+    log::request_done(&method, started.elapsed(), &resp);
+    resp
+}
+
+fn dispatch(state: &mut AppState, req: Request) -> serde_json::Value {
     if let Some(resp) = handlers::analytics::try_handle(state, &req) {
         return resp;
     }
@@ -51,11 +94,33 @@ pub fn handle_request(state: &mut AppState, req: Request) -> serde_json::Value {
     if let Some(resp) = handlers::assets::try_handle(state, &req) {
         return resp;
     }
+    if let Some(resp) = handlers::edits::try_handle(state, &req) {
+        return resp;
+    }
 
     err(
         &req.id,
-        "not_implemented",
+        "unknown_method",
         format!("unknown method: {}", req.method),
-        None,
+        Some(json!({ "method": req.method })),
     )
 }
+
+/// Processes a JSON array of requests in order and returns one response per element.
+/// Elements that are not valid requests get their own `bad_json` error instead of
+/// aborting the rest of the batch.
+pub fn handle_batch(state: &mut AppState, items: Vec<serde_json::Value>) -> serde_json::Value {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        let id = item
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        match serde_json::from_value::<Request>(item) {
+            Ok(req) => out.push(handle_request(state, req)),
+            Err(e) => out.push(err(&id, "bad_json", e.to_string(), None)),
+        }
+    }
+    serde_json::Value::Array(out)
+}
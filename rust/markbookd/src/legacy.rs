@@ -1,5 +1,62 @@
 use std::path::{Path, PathBuf};
 
+/// Text encoding of legacy class files. DOS-era MarkBook wrote the OEM code page,
+/// so accented names are CP437/CP850 bytes rather than UTF-8.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LegacyEncoding {
+    /// UTF-8 when the bytes are valid UTF-8, otherwise CP437.
+    #[default]
+    Auto,
+    Utf8,
+    Cp437,
+    Cp850,
+}
+
+impl LegacyEncoding {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "utf8" | "utf-8" => Some(Self::Utf8),
+            "cp437" | "ibm437" => Some(Self::Cp437),
+            "cp850" | "ibm850" => Some(Self::Cp850),
+            _ => None,
+        }
+    }
+}
+
+// Code points for bytes 0x80..=0xFF; the low half of both code pages is ASCII.
+const CP437_HIGH: &str = "\
+ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»\
+░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀\
+αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+const CP850_HIGH: &str = "\
+ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»\
+░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀\
+ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{ad}±‗¾¶§÷¸°¨·¹³²■\u{a0}";
+
+pub fn decode_legacy_bytes(bytes: &[u8], encoding: LegacyEncoding) -> String {
+    let high = match encoding {
+        LegacyEncoding::Utf8 => return String::from_utf8_lossy(bytes).into_owned(),
+        LegacyEncoding::Auto => match std::str::from_utf8(bytes) {
+            Ok(s) => return s.to_string(),
+            Err(_) => CP437_HIGH,
+        },
+        LegacyEncoding::Cp437 => CP437_HIGH,
+        LegacyEncoding::Cp850 => CP850_HIGH,
+    };
+    let table: Vec<char> = high.chars().collect();
+    bytes
+        .iter()
+        .map(|&b| {
+            if b < 0x80 {
+                b as char
+            } else {
+                table[(b - 0x80) as usize]
+            }
+        })
+        .collect()
+}
+
 pub fn find_cl_file(folder: &Path) -> anyhow::Result<PathBuf> {
     let entries = std::fs::read_dir(folder)?;
     for ent in entries {
@@ -50,9 +107,9 @@ pub struct ParsedLegacyUserCfg {
     pub roff_default: bool,
 }
 
-pub fn parse_legacy_cl(cl_path: &Path) -> anyhow::Result<ParsedCl> {
+pub fn parse_legacy_cl(cl_path: &Path, encoding: LegacyEncoding) -> anyhow::Result<ParsedCl> {
     let bytes = std::fs::read(cl_path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
 
     let mut section: Option<String> = None;
     let mut general: Vec<String> = Vec::new();
@@ -334,7 +391,7 @@ fn parse_mark_set_def(line: &str, sort_order: usize) -> Option<ParsedMarkSetDef>
     })
 }
 
-fn is_legacy_year_file(name: &str) -> bool {
+pub fn is_legacy_year_file(name: &str) -> bool {
     let name_up = name.to_ascii_uppercase();
     if name_up.len() < 4 {
         return false;
@@ -740,9 +797,12 @@ pub struct ParsedRmkFile {
     pub remarks_by_entry: Vec<Vec<String>>,
 }
 
-pub fn parse_legacy_rmk_file(path: &Path) -> anyhow::Result<ParsedRmkFile> {
+pub fn parse_legacy_rmk_file(
+    path: &Path,
+    encoding: LegacyEncoding,
+) -> anyhow::Result<ParsedRmkFile> {
     let bytes = std::fs::read(path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
     let lines: Vec<String> = text
         .lines()
         .map(|l| l.trim_end_matches('\r').to_string())
@@ -793,9 +853,12 @@ pub fn parse_legacy_rmk_file(path: &Path) -> anyhow::Result<ParsedRmkFile> {
     })
 }
 
-pub fn parse_legacy_note_file(path: &Path) -> anyhow::Result<Vec<String>> {
+pub fn parse_legacy_note_file(
+    path: &Path,
+    encoding: LegacyEncoding,
+) -> anyhow::Result<Vec<String>> {
     let bytes = std::fs::read(path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
     let lines: Vec<String> = text
         .lines()
         .map(|l| l.trim_end_matches('\r').to_string())
@@ -958,9 +1021,12 @@ pub struct ParsedIdxFile {
     pub bank_short: Option<String>,
 }
 
-pub fn parse_legacy_idx_file(path: &Path) -> anyhow::Result<ParsedIdxFile> {
+pub fn parse_legacy_idx_file(
+    path: &Path,
+    encoding: LegacyEncoding,
+) -> anyhow::Result<ParsedIdxFile> {
     let bytes = std::fs::read(path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
     let lines: Vec<String> = text
         .lines()
         .map(|l| l.trim_end_matches('\r').to_string())
@@ -1079,9 +1145,12 @@ pub struct ParsedRCommentFile {
     pub remarks: Vec<String>,
 }
 
-pub fn parse_legacy_r_comment_file(path: &Path) -> anyhow::Result<ParsedRCommentFile> {
+pub fn parse_legacy_r_comment_file(
+    path: &Path,
+    encoding: LegacyEncoding,
+) -> anyhow::Result<ParsedRCommentFile> {
     let bytes = std::fs::read(path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
     let lines: Vec<String> = text
         .lines()
         .map(|l| l.trim_end_matches('\r').to_string())
@@ -1120,9 +1189,9 @@ pub struct ParsedBnkFile {
     pub entries: Vec<ParsedBnkEntry>,
 }
 
-pub fn parse_bnk_file(path: &Path) -> anyhow::Result<ParsedBnkFile> {
+pub fn parse_bnk_file(path: &Path, encoding: LegacyEncoding) -> anyhow::Result<ParsedBnkFile> {
     let bytes = std::fs::read(path)?;
-    let text = String::from_utf8_lossy(&bytes);
+    let text = decode_legacy_bytes(&bytes, encoding);
     let mut entries: Vec<ParsedBnkEntry> = Vec::new();
     let mut fit_profile: Option<String> = None;
 
@@ -1349,6 +1418,148 @@ pub fn serialize_bnk_file(parsed: &ParsedBnkFile) -> String {
     out
 }
 
+/// Inverse of `parse_legacy_cl`. Columns MarkBook doesn't model (sex, homeroom, phones)
+/// are carried over from the student's original `raw_line` when it has the full layout.
+pub fn serialize_legacy_cl(cl: &ParsedCl, school_name: &str, teacher_name: &str) -> String {
+    let mut out = String::new();
+    let mut line = |s: &str| {
+        out.push_str(s);
+        out.push_str("\r\n");
+    };
+    line("[MarkBook - Version 11.2.18]");
+    line("[Version 11.2.18]");
+    line("\"\"");
+    line("[Mark Sets created for this class]");
+    line(&format!(" {} ", cl.mark_sets.len()));
+    for def in &cl.mark_sets {
+        line(&format!(
+            "{}&{},{},{}",
+            legacy_field(&def.file_prefix),
+            legacy_field(&def.code),
+            legacy_field(&def.description),
+            def.weight
+        ));
+    }
+    line("\"\"");
+    // The parser drops empty values here and picks the class name by position, so every
+    // slot needs a placeholder: phone, school, class name, teacher.
+    line("[General Information]");
+    for v in ["", school_name, &cl.class_name, teacher_name] {
+        line(&format!("\"{}\"", legacy_field_or_dash(v)));
+    }
+    line("[Class List]");
+    line(&format!(" {} ", cl.students.len()));
+    for s in &cl.students {
+        line(&serialize_student_line(s));
+    }
+    line("\"\"");
+    out
+}
+
+fn serialize_student_line(s: &ParsedStudent) -> String {
+    let mut parts: Vec<String> = s
+        .raw_line
+        .split(',')
+        .map(|x| x.trim().to_string())
+        .collect();
+    if parts.len() < 11 {
+        parts = vec![String::new(); 11];
+    }
+    let last = parts.len() - 1;
+    parts[0] = format!(" {} ", if s.active { 1 } else { 0 });
+    parts[1] = legacy_field(&s.last_name);
+    parts[2] = legacy_field(&s.first_name);
+    parts[4] = legacy_field(s.student_no.as_deref().unwrap_or(""));
+    parts[9] = legacy_field(s.birth_date.as_deref().unwrap_or(""));
+    parts[last] = s.mark_set_mask.clone().unwrap_or_else(|| "TBA".into());
+    parts.join(",")
+}
+
+/// Inverse of `parse_legacy_mark_file`, keeping the legacy mark-state encoding:
+/// no mark => raw 0, zero => raw -1, scored => raw > 0.
+pub fn serialize_legacy_mark_file(parsed: &ParsedMarkFile) -> String {
+    let mut out = String::new();
+    let mut line = |s: &str| {
+        out.push_str(s);
+        out.push_str("\r\n");
+    };
+    line("[MarkBook - Version 11.2.17]");
+    line("[Version 11.2.17]");
+    line("\" \"");
+    if let Some(m) = parsed.misc.as_ref() {
+        // Positional block; see parse_legacy_mark_file for the slot order.
+        line("[Misc Info]");
+        line(&format!("\"{}\"", legacy_field(&m.full_code)));
+        line(&format!("\"{}\"", legacy_field(&m.room)));
+        line(&format!("\"{}\"", legacy_field(&m.day)));
+        line(&format!("\"{}\"", legacy_field(&m.period)));
+        line(&format!("\"{}\"", m.weight_method));
+        line(&format!(
+            "\"{}\"",
+            m.legacy_serial.map(|v| v.to_string()).unwrap_or_default()
+        ));
+        line(&format!("\"{}\"", m.calc_method));
+        line("\"\"");
+    }
+    line("[Categories]");
+    line(&parsed.categories.len().to_string());
+    for c in &parsed.categories {
+        line(&format!("{},{}", legacy_field(&c.name), c.weight));
+    }
+    line("\" \"");
+    line("[LastStudent]");
+    line(&format!(" {} ", parsed.last_student));
+    line("\"\"");
+    line("[Marks]");
+    line(&format!(" {} ", parsed.assessments.len()));
+    for a in &parsed.assessments {
+        line(&a.date.replace('-', " "));
+        line(&legacy_field_or_dash(&a.category_name));
+        line(&legacy_field_or_dash(&a.title));
+        line(&a.term.to_string());
+        line(&format!(
+            " {} , {} , {} , {} , {} ",
+            a.legacy_kind, a.weight, a.avg_percent, a.out_of, a.avg_raw
+        ));
+        for i in 0..parsed.last_student {
+            let raw = match a.raw_scores.get(i) {
+                Some(LegacyScore::Scored(v)) => *v,
+                Some(LegacyScore::Zero) => -1.0,
+                Some(LegacyScore::NoMark) | None => 0.0,
+            };
+            let percent = if a.out_of > 0.0 {
+                (raw * 1000.0 / a.out_of).round() / 10.0
+            } else {
+                0.0
+            };
+            line(&format!(" {} , {} ", percent, raw));
+        }
+    }
+    out
+}
+
+/// Legacy files are line- and comma-delimited with no escaping.
+fn legacy_field(s: &str) -> String {
+    s.chars()
+        .map(|ch| match ch {
+            ',' | '"' | '\r' | '\n' => ' ',
+            ch => ch,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The parsers skip blank lines, so positional values that are empty are written as `-`.
+fn legacy_field_or_dash(s: &str) -> String {
+    let v = legacy_field(s);
+    if v.is_empty() {
+        "-".into()
+    } else {
+        v
+    }
+}
+
 pub struct ParsedLegacyExportBlock {
     pub title: String,
     pub out_of: f64,
@@ -1677,7 +1888,7 @@ mod tests {
     #[test]
     fn parse_cl_includes_mark_sets() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/CL8D.Y25");
-        let cl = parse_legacy_cl(&p).expect("parse cl");
+        let cl = parse_legacy_cl(&p, LegacyEncoding::Auto).expect("parse cl");
         assert_eq!(cl.mark_sets.len(), 6);
         assert!(cl.mark_sets.iter().any(|m| m.code == "MAT1"));
 
@@ -1726,7 +1937,7 @@ mod tests {
     #[test]
     fn parse_mat18d_rmk_file() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/MAT18D.RMK");
-        let r = parse_legacy_rmk_file(&p).expect("parse rmk");
+        let r = parse_legacy_rmk_file(&p, LegacyEncoding::Auto).expect("parse rmk");
         assert_eq!(r.last_student, 27);
         assert_eq!(r.last_entry, 18);
         assert_eq!(r.entry_titles.len(), 18);
@@ -1737,7 +1948,7 @@ mod tests {
     #[test]
     fn parse_class_note_file() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/8DNOTE.TXT");
-        let v = parse_legacy_note_file(&p).expect("parse notes");
+        let v = parse_legacy_note_file(&p, LegacyEncoding::Auto).expect("parse notes");
         assert_eq!(v.len(), 27);
         assert!(v[0].contains("called re Math"));
     }
@@ -1757,7 +1968,7 @@ mod tests {
     #[test]
     fn parse_legacy_idx_file_new_format() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/MAT18D.IDX");
-        let idx = parse_legacy_idx_file(&p).expect("parse idx");
+        let idx = parse_legacy_idx_file(&p, LegacyEncoding::Auto).expect("parse idx");
         assert_eq!(idx.sets.len(), 1);
         assert_eq!(idx.default_set, 1);
         assert_eq!(idx.sets[0].title, "First MAT1 Set");
@@ -1772,7 +1983,7 @@ mod tests {
     #[test]
     fn parse_legacy_r_comment_file_fixture() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/MAT18D.R1");
-        let parsed = parse_legacy_r_comment_file(&p).expect("parse r1");
+        let parsed = parse_legacy_r_comment_file(&p, LegacyEncoding::Auto).expect("parse r1");
         assert_eq!(parsed.last_student, 27);
         assert_eq!(parsed.remarks.len(), 27);
         assert!(parsed.remarks.iter().any(|s| s.contains("Daniella")));
@@ -1781,7 +1992,7 @@ mod tests {
     #[test]
     fn parse_legacy_bnk_file() {
         let p = fixture_path("fixtures/legacy/Sample25/COMMENT.BNK");
-        let parsed = parse_bnk_file(&p).expect("parse bnk");
+        let parsed = parse_bnk_file(&p, LegacyEncoding::Auto).expect("parse bnk");
         assert!(!parsed.entries.is_empty());
         assert!(
             parsed
@@ -1795,6 +2006,102 @@ mod tests {
         assert!(serialized.contains("FIT"));
     }
 
+    #[test]
+    fn serialize_legacy_cl_and_mark_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!(
+            "markbook-serialize-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("clock")
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).expect("create tmp dir");
+
+        let cl = parse_legacy_cl(
+            &fixture_path("fixtures/legacy/Sample25/MB8D25/CL8D.Y25"),
+            LegacyEncoding::Auto,
+        )
+        .expect("parse cl");
+        let cl_path = dir.join("CL8D.Y25");
+        fs::write(&cl_path, serialize_legacy_cl(&cl, "", "")).expect("write cl");
+        let again = parse_legacy_cl(&cl_path, LegacyEncoding::Auto).expect("reparse cl");
+        assert_eq!(again.class_name, cl.class_name);
+        assert_eq!(again.mark_sets.len(), cl.mark_sets.len());
+        assert_eq!(again.students.len(), cl.students.len());
+        for (a, b) in again.students.iter().zip(&cl.students) {
+            assert_eq!((&a.last_name, &a.first_name), (&b.last_name, &b.first_name));
+            assert_eq!(a.active, b.active);
+            assert_eq!(a.mark_set_mask, b.mark_set_mask);
+        }
+
+        let marks =
+            parse_legacy_mark_file(&fixture_path("fixtures/legacy/Sample25/MB8D25/MAT18D.Y25"))
+                .expect("parse marks");
+        let marks_path = dir.join("MAT18D.Y25");
+        fs::write(&marks_path, serialize_legacy_mark_file(&marks)).expect("write marks");
+        let again = parse_legacy_mark_file(&marks_path).expect("reparse marks");
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(again.last_student, marks.last_student);
+        assert_eq!(again.assessments.len(), marks.assessments.len());
+        for (a, b) in again.assessments.iter().zip(&marks.assessments) {
+            assert_eq!(a.title, b.title);
+            assert_eq!(a.raw_scores, b.raw_scores);
+        }
+    }
+
+    #[test]
+    fn decode_legacy_bytes_maps_oem_code_pages() {
+        assert_eq!(CP437_HIGH.chars().count(), 128);
+        assert_eq!(CP850_HIGH.chars().count(), 128);
+        let bytes = b"Pe\xa4a S\x9brensen";
+        assert_eq!(
+            decode_legacy_bytes(bytes, LegacyEncoding::Cp437),
+            "Peña S¢rensen"
+        );
+        assert_eq!(
+            decode_legacy_bytes(bytes, LegacyEncoding::Cp850),
+            "Peña Sørensen"
+        );
+        // Valid UTF-8 is left alone in auto mode.
+        assert_eq!(
+            decode_legacy_bytes("Zoë".as_bytes(), LegacyEncoding::Auto),
+            "Zoë"
+        );
+    }
+
+    #[test]
+    fn legacy_encoding_parse_accepts_aliases() {
+        assert_eq!(LegacyEncoding::parse(" AUTO "), Some(LegacyEncoding::Auto));
+        assert_eq!(LegacyEncoding::parse("utf-8"), Some(LegacyEncoding::Utf8));
+        assert_eq!(LegacyEncoding::parse("ibm437"), Some(LegacyEncoding::Cp437));
+        assert_eq!(LegacyEncoding::parse("CP850"), Some(LegacyEncoding::Cp850));
+        assert_eq!(LegacyEncoding::parse("latin1"), None);
+        // Forced UTF-8 replaces invalid bytes instead of falling back to CP437.
+        assert_eq!(
+            decode_legacy_bytes(b"Pe\xa4a", LegacyEncoding::Utf8),
+            "Pe\u{fffd}a"
+        );
+    }
+
+    #[test]
+    fn parse_cl_decodes_dos_encoded_names() {
+        let p = fixture_path("fixtures/legacy/Encoding/MBCP25/CLCP.Y25");
+        let cl = parse_legacy_cl(&p, LegacyEncoding::Auto).expect("parse cl");
+        assert_eq!(cl.class_name, "9Z Français (2025)");
+        let names: Vec<(&str, &str)> = cl
+            .students
+            .iter()
+            .map(|s| (s.last_name.as_str(), s.first_name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("Müller", "José"), ("Peña", "Zoë"), ("S¢rensen", "Åse")]
+        );
+
+        let cl = parse_legacy_cl(&p, LegacyEncoding::Cp850).expect("parse cl");
+        assert_eq!(cl.students[2].last_name, "Sørensen");
+    }
+
     #[test]
     fn parse_legacy_tbk_file_fixture() {
         let p = fixture_path("fixtures/legacy/Sample25/MB8D25/MAT18D.TBK");
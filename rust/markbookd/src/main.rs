@@ -1,50 +1,88 @@
 mod backup;
 mod calc;
+mod class_bundle;
+mod class_json;
 mod db;
 mod ipc;
 mod legacy;
+mod pdf;
+mod xlsx;
 
-use std::io::{self, BufRead, Write};
+use ipc::framing::{self, Frame, Framing};
+use serde_json::json;
+use std::io;
 
 fn main() {
     // Keep this binary dependency-light for now. Use simple error mapping.
+    let framing = match Framing::from_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("markbookd: {}", e);
+            std::process::exit(2);
+        }
+    };
     let mut state = ipc::AppState {
         workspace: None,
         db: None,
     };
 
     let stdin = io::stdin();
+    let mut input = stdin.lock();
     let mut stdout = io::stdout();
 
-    for line in stdin.lock().lines() {
-        let line = match line {
-            Ok(v) => v,
-            Err(_) => break,
+    while let Ok(Some(frame)) = framing::read_frame(&mut input, framing) {
+        let resp = match frame {
+            Frame::Message(bytes) => match handle_message(&mut state, &bytes) {
+                Some(v) => v,
+                None => continue,
+            },
+            Frame::Bad(message) => bad_input(None, "bad_frame", message),
         };
-        if line.trim().is_empty() {
-            continue;
+        let body = serde_json::to_string(&resp).unwrap_or_else(|_| "{\"ok\":false}".to_string());
+        if framing::write_frame(&mut stdout, framing, &body).is_err() {
+            break;
         }
+    }
+}
 
-        let req: ipc::Request = match serde_json::from_str(&line) {
-            Ok(v) => v,
-            Err(e) => {
-                // Can't reply without id; ignore.
-                let _ = writeln!(
-                    stdout,
-                    "{{\"ok\":false,\"error\":{{\"code\":\"bad_json\",\"message\":\"{}\"}}}}",
-                    e
-                );
-                let _ = stdout.flush();
-                continue;
-            }
-        };
+/// Answers one request or batch. Returns `None` for blank input.
+fn handle_message(state: &mut ipc::AppState, bytes: &[u8]) -> Option<serde_json::Value> {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return Some(bad_input(None, "bad_json", "request is not valid UTF-8"));
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    // A message holding a JSON array is a batch: one array of responses, in request order.
+    if text.starts_with('[') {
+        return Some(match serde_json::from_str::<Vec<serde_json::Value>>(text) {
+            Ok(items) => ipc::handle_batch(state, items),
+            Err(e) => bad_input(None, "bad_json", e.to_string()),
+        });
+    }
+
+    let value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(e) => return Some(bad_input(None, "bad_json", e.to_string())),
+    };
+    // Echo the id whenever the message has one, so the client can fail the
+    // matching call instead of waiting on it.
+    let id = value.get("id").and_then(|v| v.as_str()).map(str::to_string);
+    match serde_json::from_value::<ipc::Request>(value) {
+        Ok(req) => Some(ipc::handle_request(state, req)),
+        Err(e) => Some(bad_input(id.as_deref(), "bad_json", e.to_string())),
+    }
+}
 
-        let resp = ipc::handle_request(&mut state, req);
-        let _ = writeln!(
-            stdout,
-            "{}",
-            serde_json::to_string(&resp).unwrap_or_else(|_| "{\"ok\":false}".to_string())
-        );
-        let _ = stdout.flush();
+fn bad_input(id: Option<&str>, code: &str, message: impl Into<String>) -> serde_json::Value {
+    let mut resp = json!({
+        "ok": false,
+        "error": { "code": code, "message": message.into() },
+    });
+    if let Some(id) = id {
+        resp["id"] = json!(id);
     }
+    resp
 }
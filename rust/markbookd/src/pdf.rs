@@ -0,0 +1,376 @@
+use anyhow::Context;
+use std::path::Path;
+
+// Minimal PDF 1.4 writer: US Letter pages of positioned text in the standard
+// Helvetica faces, no embedded fonts, images or compression. Enough for
+// printable reports to open in any viewer.
+
+pub const PAGE_WIDTH: f64 = 612.0;
+pub const PAGE_HEIGHT: f64 = 792.0;
+pub const MARGIN: f64 = 54.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource_name(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    content: String,
+}
+
+impl Page {
+    /// Places `text` with its baseline starting at (`x`, `y`), measured in
+    /// points from the bottom-left corner.
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, text: &str) {
+        self.content.push_str(&format!(
+            "BT /{} {} Tf {} {} Td ({}) Tj ET\n",
+            font.resource_name(),
+            number(size),
+            number(x),
+            number(y),
+            escape_text(text)
+        ));
+    }
+
+    /// A thin horizontal line, for table header rules.
+    pub fn rule(&mut self, x1: f64, x2: f64, y: f64) {
+        self.content.push_str(&format!(
+            "0.5 w {} {} m {} {} l S\n",
+            number(x1),
+            number(y),
+            number(x2),
+            number(y)
+        ));
+    }
+}
+
+/// A table column or block of text: left edge and width in points, and
+/// whether the text hugs the right edge (numbers).
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub x: f64,
+    pub width: f64,
+    pub right: bool,
+}
+
+/// Lays rows of text down the page from the top margin, starting a new page
+/// whenever the next row would run into the bottom margin.
+pub struct Flow {
+    pages: Vec<Page>,
+    y: f64,
+}
+
+impl Default for Flow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Flow {
+    pub fn new() -> Self {
+        Flow {
+            pages: vec![Page::default()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(Page::default());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    pub fn gap(&mut self, height: f64) {
+        self.y -= height;
+    }
+
+    /// Writes one row of cells, wrapping each within its column. A row is
+    /// never split across pages.
+    pub fn row(&mut self, columns: &[Column], cells: &[&str], size: f64, font: Font) {
+        let leading = size * 1.3;
+        let wrapped: Vec<Vec<String>> = columns
+            .iter()
+            .zip(cells)
+            .map(|(c, text)| wrap_text(text, size, font, c.width))
+            .collect();
+        let line_count = wrapped.iter().map(|l| l.len()).max().unwrap_or(0).max(1);
+        let height = line_count as f64 * leading;
+        if self.y - height < MARGIN && self.y < PAGE_HEIGHT - MARGIN {
+            self.new_page();
+        }
+        let page = self.pages.last_mut().expect("flow always has a page");
+        for (column, lines) in columns.iter().zip(&wrapped) {
+            for (i, line) in lines.iter().enumerate() {
+                let x = if column.right {
+                    column.x + column.width - text_width(line, size, font)
+                } else {
+                    column.x
+                };
+                page.text(x, self.y - size - i as f64 * leading, size, font, line);
+            }
+        }
+        self.y -= height;
+    }
+
+    /// A full-width rule under the previous row.
+    pub fn rule(&mut self) {
+        self.y -= 2.0;
+        let y = self.y;
+        let page = self.pages.last_mut().expect("flow always has a page");
+        page.rule(MARGIN, PAGE_WIDTH - MARGIN, y);
+        self.y -= 2.0;
+    }
+
+    pub fn into_pages(self) -> Vec<Page> {
+        self.pages
+    }
+}
+
+pub fn write_document(out_path: &Path, title: &str, pages: &[Page]) -> anyhow::Result<()> {
+    std::fs::write(out_path, document_bytes(title, pages))
+        .with_context(|| format!("failed to write {}", out_path.to_string_lossy()))
+}
+
+// Objects 1-5 are the catalog, page tree, info dictionary and the two fonts;
+// each page then takes a page object and its content stream.
+const FIRST_PAGE_OBJECT: usize = 6;
+
+fn document_bytes(title: &str, pages: &[Page]) -> Vec<u8> {
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", FIRST_PAGE_OBJECT + i * 2))
+        .collect();
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        format!("<< /Title ({}) /Producer (MarkBook) >>", escape_text(title)).into_bytes(),
+        font_object("Helvetica"),
+        font_object("Helvetica-Bold"),
+    ];
+    for (i, page) in pages.iter().enumerate() {
+        let content_object = FIRST_PAGE_OBJECT + i * 2 + 1;
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents {} 0 R >>",
+                number(PAGE_WIDTH),
+                number(PAGE_HEIGHT),
+                content_object
+            )
+            .into_bytes(),
+        );
+        let stream = page.content.as_bytes();
+        let mut body = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        body.extend_from_slice(stream);
+        body.extend_from_slice(b"endstream");
+        objects.push(body);
+    }
+
+    let mut out: Vec<u8> = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, body) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let xref_offset = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+    out.extend_from_slice(b"0000000000 65535 f \n");
+    for offset in offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+    out
+}
+
+fn font_object(base_font: &str) -> Vec<u8> {
+    format!(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+        base_font
+    )
+    .into_bytes()
+}
+
+fn number(v: f64) -> String {
+    let s = format!("{:.2}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Maps a character onto its WinAnsiEncoding byte; anything outside the
+/// encoding prints as '?'.
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{00a0}'..='\u{00ff}' => c as u32 as u8,
+        '\u{2013}' => 0x96,
+        '\u{2014}' => 0x97,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201c}' => 0x93,
+        '\u{201d}' => 0x94,
+        '\u{2022}' => 0x95,
+        '\u{2026}' => 0x85,
+        '\u{20ac}' => 0x80,
+        '\t' => b' ',
+        _ => b'?',
+    }
+}
+
+/// Literal string contents with the delimiters escaped and non-ASCII bytes
+/// written as octal, so the content stream itself stays ASCII.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match win_ansi(c) {
+            b'(' => out.push_str("\\("),
+            b')' => out.push_str("\\)"),
+            b'\\' => out.push_str("\\\\"),
+            b @ 0x20..=0x7e => out.push(b as char),
+            b => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out
+}
+
+// Advance widths (1/1000 em) of the printable ASCII range from the standard
+// Helvetica metrics; other characters use the digit width.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+pub fn text_width(text: &str, size: f64, font: Font) -> f64 {
+    let widths = match font {
+        Font::Regular => &HELVETICA_WIDTHS,
+        Font::Bold => &HELVETICA_BOLD_WIDTHS,
+    };
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => widths[c as usize - 0x20] as u32,
+            '\u{2014}' => 1000,
+            _ => 556,
+        })
+        .sum();
+    units as f64 * size / 1000.0
+}
+
+/// Breaks `text` into lines no wider than `max_width`, at spaces where
+/// possible; a single word longer than the line is split by character.
+pub fn wrap_text(text: &str, size: f64, font: Font, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if text_width(&candidate, size, font) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if text_width(&line, size, font) > max_width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xref_offsets_point_at_each_object() {
+        let mut page = Page::default();
+        page.text(72.0, 700.0, 12.0, Font::Regular, "Adams, Pat");
+        let bytes = document_bytes("Report", &[page.clone(), page]);
+        let text = String::from_utf8_lossy(&bytes).to_string();
+        assert!(bytes.starts_with(b"%PDF-1.4\n"));
+        assert!(text.contains("/Count 2"));
+
+        let startxref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|s| s.lines().next())
+            .and_then(|s| s.parse().ok())
+            .expect("startxref");
+        assert!(bytes[startxref..].starts_with(b"xref\n0 10\n"));
+        // The binary comment line makes `text` lossy, so index the raw bytes.
+        let xref = std::str::from_utf8(&bytes[startxref..]).expect("ascii xref");
+        let entries: Vec<usize> = xref
+            .lines()
+            .skip(3)
+            .take(9)
+            .map(|l| l[..10].parse().expect("offset"))
+            .collect();
+        for (i, offset) in entries.iter().enumerate() {
+            assert!(bytes[*offset..].starts_with(format!("{} 0 obj\n", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn text_is_escaped_into_win_ansi() {
+        assert_eq!(escape_text("a (b) \\ c"), "a \\(b\\) \\\\ c");
+        assert_eq!(escape_text("T1 \u{2014} Caf\u{e9}"), "T1 \\227 Caf\\351");
+        assert_eq!(escape_text("\u{4e2d}"), "?");
+    }
+
+    #[test]
+    fn wrap_breaks_at_spaces_and_splits_long_words() {
+        let width = text_width("Works hard", 10.0, Font::Regular);
+        assert_eq!(
+            wrap_text("Works hard in class", 10.0, Font::Regular, width),
+            vec!["Works hard", "in class"]
+        );
+        let long = wrap_text("abcdefghij", 10.0, Font::Regular, 20.0);
+        assert!(long.len() > 1);
+        assert_eq!(long.concat(), "abcdefghij");
+        assert_eq!(wrap_text("", 10.0, Font::Regular, 20.0), vec![""]);
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::Context;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Minimal SpreadsheetML writer: inline strings, no shared string table or
+// styles. Enough for Excel/LibreOffice/Sheets to open gradebook exports.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+pub fn write_workbook(out_path: &Path, sheets: &[Sheet]) -> anyhow::Result<()> {
+    let out_file = File::create(out_path)
+        .with_context(|| format!("failed to create {}", out_path.to_string_lossy()))?;
+    let mut zip = ZipWriter::new(out_file);
+    let opts = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let names = sheet_names(sheets);
+    let mut entries: Vec<(String, String)> = vec![
+        (
+            "[Content_Types].xml".to_string(),
+            content_types_xml(sheets.len()),
+        ),
+        ("_rels/.rels".to_string(), ROOT_RELS_XML.to_string()),
+        ("xl/workbook.xml".to_string(), workbook_xml(&names)),
+        (
+            "xl/_rels/workbook.xml.rels".to_string(),
+            workbook_rels_xml(sheets.len()),
+        ),
+    ];
+    for (i, sheet) in sheets.iter().enumerate() {
+        entries.push((
+            format!("xl/worksheets/sheet{}.xml", i + 1),
+            worksheet_xml(&sheet.rows),
+        ));
+    }
+
+    for (name, body) in entries {
+        zip.start_file(name.as_str(), opts)
+            .with_context(|| format!("failed to start {}", name))?;
+        zip.write_all(body.as_bytes())
+            .with_context(|| format!("failed to write {}", name))?;
+    }
+    zip.finish().context("failed to finalize xlsx")?;
+    Ok(())
+}
+
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+fn content_types_xml(sheet_count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    );
+    for i in 1..=sheet_count {
+        xml.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            i
+        ));
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+fn workbook_xml(names: &[String]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+    );
+    for (i, name) in names.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+            xml_escape(name),
+            i + 1,
+            i + 1
+        ));
+    }
+    xml.push_str("</sheets></workbook>");
+    xml
+}
+
+fn workbook_rels_xml(sheet_count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    );
+    for i in 1..=sheet_count {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+            i, i
+        ));
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+fn worksheet_xml(rows: &[Vec<Cell>]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    for (r, row) in rows.iter().enumerate() {
+        xml.push_str(&format!(r#"<row r="{}">"#, r + 1));
+        for (c, cell) in row.iter().enumerate() {
+            let cell_ref = format!("{}{}", column_name(c), r + 1);
+            match cell {
+                Cell::Empty => {}
+                Cell::Number(v) if v.is_finite() => {
+                    xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, cell_ref, v));
+                }
+                Cell::Number(_) => {}
+                Cell::Text(s) => {
+                    xml.push_str(&format!(
+                        r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        cell_ref,
+                        xml_escape(s)
+                    ));
+                }
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Zero-based column index to spreadsheet letters (0 => A, 26 => AA).
+fn column_name(mut idx: usize) -> String {
+    let mut out = Vec::new();
+    loop {
+        out.push(b'A' + (idx % 26) as u8);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+/// Excel rejects sheet names over 31 chars, containing `[]:*?/\`, or
+/// duplicated (case-insensitively) within a workbook.
+fn sheet_names(sheets: &[Sheet]) -> Vec<String> {
+    let mut used: Vec<String> = Vec::new();
+    let mut out = Vec::with_capacity(sheets.len());
+    for (i, sheet) in sheets.iter().enumerate() {
+        let cleaned: String = sheet
+            .name
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .collect();
+        let cleaned = cleaned.trim().trim_matches('\'').to_string();
+        let base: String = if cleaned.is_empty() {
+            format!("Sheet{}", i + 1)
+        } else {
+            cleaned.chars().take(31).collect()
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while used.contains(&name.to_lowercase()) {
+            let suffix = format!(" ({})", n);
+            let keep = 31usize.saturating_sub(suffix.chars().count());
+            name = format!("{}{}", base.chars().take(keep).collect::<String>(), suffix);
+            n += 1;
+        }
+        used.push(name.to_lowercase());
+        out.push(name);
+    }
+    out
+}
+
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_name_rolls_over_after_z() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn sheet_names_are_sanitized_and_unique() {
+        let sheet = |name: &str| Sheet {
+            name: name.to_string(),
+            rows: Vec::new(),
+        };
+        let names = sheet_names(&[
+            sheet("Term 1/2"),
+            sheet("term 1/2"),
+            sheet(""),
+            sheet("A very long mark set description that overflows"),
+        ]);
+        assert_eq!(names[0], "Term 1_2");
+        assert_eq!(names[1], "term 1_2 (2)");
+        assert_eq!(names[2], "Sheet3");
+        assert_eq!(names[3].chars().count(), 31);
+    }
+
+    #[test]
+    fn worksheet_keeps_numbers_numeric_and_skips_empty_cells() {
+        let xml = worksheet_xml(&[
+            vec![
+                Cell::Text("Student".into()),
+                Cell::Text("Quiz & Lab".into()),
+            ],
+            vec![Cell::Text("Adams, Pat".into()), Cell::Number(17.5)],
+            vec![Cell::Text("Baker, Sam".into()), Cell::Empty],
+        ]);
+        assert!(xml.contains(
+            r#"<c r="B1" t="inlineStr"><is><t xml:space="preserve">Quiz &amp; Lab</t></is></c>"#
+        ));
+        assert!(xml.contains(r#"<c r="B2"><v>17.5</v></c>"#));
+        assert!(!xml.contains(r#"r="B3""#));
+    }
+}
@@ -0,0 +1,147 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn strict_category_rejects_unknown_names_and_lenient_mode_keeps_them() {
+    let workspace = temp_dir("markbook-assessments-strict-category");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "SC1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    for (i, name) in ["Tests", "Labs"].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("category-{}", i),
+            "categories.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "name": name, "weight": 50 }),
+        );
+    }
+    let valid: Vec<serde_json::Value> = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    )["categories"]
+        .as_array()
+        .expect("categories")
+        .iter()
+        .map(|c| c["name"].clone())
+        .collect();
+    assert!(valid.contains(&json!("Tests")));
+
+    // Lenient (default): a typo is stored as given.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Quiz 1",
+            "categoryName": "Tset"
+        }),
+    );
+
+    let rejected = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Quiz 2",
+            "categoryName": "Tset",
+            "strictCategory": true
+        }),
+    );
+    assert_eq!(rejected["error"]["code"], json!("bad_params"));
+    assert_eq!(
+        rejected["error"]["details"],
+        json!({ "categoryName": "Tset", "validCategories": valid })
+    );
+
+    // Strict mode matches case-insensitively and stores the category's spelling.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Lab 1",
+            "categoryName": " labs ",
+            "strictCategory": true
+        }),
+    );
+    // No category at all is still allowed.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Notes",
+            "strictCategory": true
+        }),
+    );
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assessments.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let categories: Vec<(&str, serde_json::Value)> = listed["assessments"]
+        .as_array()
+        .expect("assessments")
+        .iter()
+        .map(|a| {
+            (
+                a["title"].as_str().expect("title"),
+                a["categoryName"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        categories,
+        vec![
+            ("Quiz 1", json!("Tset")),
+            ("Lab 1", json!("Labs")),
+            ("Notes", json!(null)),
+        ]
+    );
+}
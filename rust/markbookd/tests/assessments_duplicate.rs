@@ -0,0 +1,192 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn assessments_duplicate_inserts_after_source_with_optional_scores() {
+    let workspace = temp_dir("markbook-assessments-duplicate");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Duplicate Class" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    for (i, last) in ["Adams", "Baker"].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s-{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        );
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+
+    let mut ids = Vec::new();
+    for (i, title) in ["Unit Test", "Quiz"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a-{i}"),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": "Tests",
+                "term": 2,
+                "weight": 3,
+                "outOf": 40
+            }),
+        );
+        ids.push(
+            created["assessmentId"]
+                .as_str()
+                .expect("assessmentId")
+                .to_string(),
+        );
+    }
+    for (row, value) in [(0, 31), (1, 22)] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("cell-{row}"),
+            "grid.updateCell",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "row": row, "col": 0, "value": value }),
+        );
+    }
+
+    let with_scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.duplicate",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "assessmentId": ids[0],
+            "copyScores": true
+        }),
+    );
+    assert_eq!(with_scores["idx"], json!(1));
+    assert_eq!(with_scores["scoresCopied"], json!(2));
+    let copy_id = with_scores["assessmentId"].as_str().expect("assessmentId");
+
+    let blank = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "assessments.duplicate",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "assessmentId": ids[1],
+            "copyScores": false
+        }),
+    );
+    assert_eq!(blank["idx"], json!(3));
+    assert_eq!(blank["scoresCopied"], json!(0));
+    let blank_id = blank["assessmentId"].as_str().expect("assessmentId");
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "assessments.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let assessments = listed["assessments"].as_array().expect("assessments");
+    let order: Vec<(&str, i64)> = assessments
+        .iter()
+        .map(|a| {
+            (
+                a["id"].as_str().expect("id"),
+                a["idx"].as_i64().expect("idx"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (ids[0].as_str(), 0),
+            (copy_id, 1),
+            (ids[1].as_str(), 2),
+            (blank_id, 3)
+        ]
+    );
+    for key in ["title", "categoryName", "term", "weight", "outOf"] {
+        assert_eq!(assessments[1][key], assessments[0][key], "{}", key);
+    }
+
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let values_for = |assessment_id: &str| -> Vec<serde_json::Value> {
+        scores["scores"]
+            .as_array()
+            .expect("scores")
+            .iter()
+            .filter(|s| s["assessmentId"] == assessment_id)
+            .map(|s| s["rawValue"].clone())
+            .collect()
+    };
+    assert_eq!(values_for(copy_id), values_for(&ids[0]));
+    assert_eq!(values_for(copy_id), vec![json!(31.0), json!(22.0)]);
+    assert!(values_for(blank_id).is_empty());
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assessments.duplicate",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "assessmentId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+    let bad_flag = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "assessments.duplicate",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "assessmentId": ids[0],
+            "copyScores": "yes"
+        }),
+    );
+    assert_eq!(bad_flag["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,182 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn assessments_reorder_rewrites_idx_and_keeps_scores() {
+    let workspace = temp_dir("markbook-assessments-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Reorder Class" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Doe", "firstName": "Jane" }),
+    );
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+
+    let mut ids = Vec::new();
+    for (i, title) in ["Quiz 1", "Quiz 2", "Test"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a-{i}"),
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+        );
+        ids.push(
+            created["assessmentId"]
+                .as_str()
+                .expect("assessmentId")
+                .to_string(),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "grid.updateCell",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "row": 0, "col": 0, "value": 7 }),
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "assessments.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedAssessmentIds": [ids[2], ids[0], ids[1]]
+        }),
+    );
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "assessments.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let order: Vec<(String, i64)> = listed["assessments"]
+        .as_array()
+        .expect("assessments")
+        .iter()
+        .map(|a| {
+            (
+                a["id"].as_str().expect("id").to_string(),
+                a["idx"].as_i64().expect("idx"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (ids[2].clone(), 0),
+            (ids[0].clone(), 1),
+            (ids[1].clone(), 2)
+        ]
+    );
+
+    // The score stays on its assessment, which is now the second column.
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let scored: Vec<&serde_json::Value> = scores["scores"]
+        .as_array()
+        .expect("scores")
+        .iter()
+        .filter(|s| s["status"] == "scored")
+        .collect();
+    assert_eq!(scored.len(), 1);
+    assert_eq!(scored[0]["assessmentId"], json!(ids[0]));
+    assert_eq!(scored[0]["rawValue"], json!(7.0));
+
+    let short = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assessments.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedAssessmentIds": [ids[0], ids[1]]
+        }),
+    );
+    assert_eq!(short["error"]["code"], json!("bad_params"));
+    assert_eq!(short["error"]["details"]["expected"], json!(3));
+    assert_eq!(short["error"]["details"]["got"], json!(2));
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "assessments.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedAssessmentIds": [ids[0], ids[0], ids[1]]
+        }),
+    );
+    assert_eq!(dup["error"]["code"], json!("bad_params"));
+    assert_eq!(dup["error"]["details"]["assessmentId"], json!(ids[0]));
+
+    let unknown = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "assessments.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedAssessmentIds": [ids[0], ids[1], "nope"]
+        }),
+    );
+    assert_eq!(unknown["error"]["code"], json!("bad_params"));
+    assert_eq!(unknown["error"]["details"]["assessmentId"], json!("nope"));
+
+    let missing_set = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "assessments.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": "nope",
+            "orderedAssessmentIds": []
+        }),
+    );
+    assert_eq!(missing_set["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,132 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn assessments_stats_exclude_no_mark_and_count_zero_as_percent() {
+    let workspace = temp_dir("markbook-assessments-stats");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Stats" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Diaz"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut assessment_ids = Vec::new();
+    for (i, title) in ["Quiz 1", "Quiz 2"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 20 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+
+    // Quiz 1: 18/20, 14/20, zero, no_mark => 90, 70, 0 over n=3.
+    let edits = [
+        (0, json!(18), "scored"),
+        (1, json!(14), "scored"),
+        (2, json!(null), "zero"),
+        (3, json!(null), "no_mark"),
+    ];
+    for (i, (student, value, status)) in edits.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_ids[0],
+                "studentId": student_ids[*student],
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assessments.stats",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let stats = res["stats"].as_array().expect("stats");
+    assert_eq!(stats.len(), 2);
+    assert_eq!(
+        stats[0],
+        json!({
+            "assessmentId": assessment_ids[0],
+            "mean": 53.3,
+            "median": 70.0,
+            "stdDev": 38.6,
+            "min": 0.0,
+            "max": 90.0,
+            "n": 3
+        })
+    );
+    assert_eq!(
+        stats[1],
+        json!({
+            "assessmentId": assessment_ids[1],
+            "mean": null,
+            "median": null,
+            "stdDev": null,
+            "min": null,
+            "max": null,
+            "n": 0
+        })
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.stats",
+        json!({ "classId": class_id, "markSetId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,128 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+const PNG_B64: &str = "iVBORw0KGgoAAAANSUhEUi1mYWtl";
+const JPEG_B64: &str = "/9j/4EpGSUYtZmFrZS1ib2R5";
+
+#[test]
+fn student_photo_put_get_round_trips_and_validates_payloads() {
+    let workspace = temp_dir("markbook-student-photo");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Photos" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Lens", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let empty = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assets.getStudentPhoto",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(empty["photo"], json!(null));
+
+    for (id, data) in [
+        ("5", "aGVsbG8gd29ybGQ=".to_string()),
+        ("6", "not base64!".to_string()),
+        ("7", "A".repeat(3 * 1024 * 1024)),
+    ] {
+        let rejected = request(
+            &mut stdin,
+            &mut reader,
+            id,
+            "assets.putStudentPhoto",
+            json!({ "classId": class_id, "studentId": student_id, "dataBase64": data }),
+        );
+        assert_eq!(
+            rejected["error"]["code"],
+            json!("bad_params"),
+            "{}",
+            rejected
+        );
+    }
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "assets.putStudentPhoto",
+        json!({ "classId": class_id, "studentId": "nope", "dataBase64": PNG_B64 }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    let png = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assets.putStudentPhoto",
+        json!({ "classId": class_id, "studentId": student_id, "dataBase64": PNG_B64 }),
+    );
+    assert_eq!(png["mimeType"], json!("image/png"));
+    assert_eq!(png["bytes"], json!(21));
+    let png_path = workspace.join(png["path"].as_str().expect("path"));
+    assert!(png_path.is_file());
+
+    let fetched = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "assets.getStudentPhoto",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(fetched["photo"]["dataBase64"], json!(PNG_B64));
+    assert_eq!(fetched["photo"]["mimeType"], json!("image/png"));
+
+    // Data URLs are accepted, and a new format replaces the old file.
+    let jpeg = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "assets.putStudentPhoto",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "dataBase64": format!("data:image/jpeg;base64,{}", JPEG_B64)
+        }),
+    );
+    assert_eq!(jpeg["mimeType"], json!("image/jpeg"));
+    assert!(workspace
+        .join(jpeg["path"].as_str().expect("path"))
+        .is_file());
+    assert!(!png_path.exists());
+
+    let fetched = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "assets.getStudentPhoto",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(fetched["photo"]["dataBase64"], json!(JPEG_B64));
+    assert_eq!(fetched["photo"]["bytes"], json!(18));
+}
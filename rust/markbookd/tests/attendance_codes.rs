@@ -0,0 +1,171 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn attendance_codes_legend_drives_summary_tallies() {
+    let workspace = temp_dir("markbook-attendance-codes");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "attendance.codes.list",
+        json!({}),
+    );
+    assert_eq!(
+        listed["codes"],
+        json!([
+            { "code": "P", "label": "Present", "countsAsAbsent": false, "countsAsLate": false, "isExcused": false },
+            { "code": "A", "label": "Absent", "countsAsAbsent": true, "countsAsLate": false, "isExcused": false },
+            { "code": "L", "label": "Late", "countsAsAbsent": false, "countsAsLate": true, "isExcused": false },
+            { "code": "E", "label": "Excused", "countsAsAbsent": true, "countsAsLate": false, "isExcused": true }
+        ])
+    );
+
+    let upserted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "attendance.codes.upsert",
+        json!({ "code": "s", "label": "Suspension", "countsAsAbsent": true }),
+    );
+    assert_eq!(upserted["codes"].as_array().map(|a| a.len()), Some(5));
+    assert_eq!(
+        upserted["codes"][4],
+        json!({ "code": "S", "label": "Suspension", "countsAsAbsent": true, "countsAsLate": false, "isExcused": false })
+    );
+    let relabelled = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.codes.upsert",
+        json!({ "code": "L", "label": "Tardy", "countsAsLate": true }),
+    );
+    assert_eq!(relabelled["codes"][2]["label"], json!("Tardy"));
+
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    for (day, code) in [(1, "S"), (2, "P"), (3, "L"), (4, "X")] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("d{day}"),
+            "attendance.setStudentDay",
+            json!({
+                "classId": class_id,
+                "month": "9",
+                "studentId": student_id,
+                "day": day,
+                "code": code
+            }),
+        );
+    }
+    let summary = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "attendance.summary",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        summary["summaries"][0],
+        json!({ "studentId": student_id, "present": 1, "absent": 1, "late": 1, "excused": 0 })
+    );
+
+    for (i, params) in [
+        json!({ "code": "AB", "label": "Absent" }),
+        json!({ "code": "A", "label": "  " }),
+        json!({ "code": "A", "label": "Absent", "isExcused": "yes" }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let bad = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad{i}"),
+            "attendance.codes.upsert",
+            params,
+        );
+        assert_eq!(bad["error"]["code"], json!("bad_params"));
+    }
+}
+
+#[test]
+fn attendance_codes_seed_from_customized_setup_codes() {
+    let workspace = temp_dir("markbook-attendance-codes-seed");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "setup.update",
+        json!({ "section": "attendance", "patch": { "absentCode": "x" } }),
+    );
+    // Simulate a workspace created before the legend existed (schema v2).
+    {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute("DROP TABLE attendance_codes", [])
+            .expect("drop legend");
+        conn.pragma_update(None, "user_version", 2)
+            .expect("set user_version");
+    }
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.codes.list",
+        json!({}),
+    );
+    let codes: Vec<&str> = listed["codes"]
+        .as_array()
+        .expect("codes")
+        .iter()
+        .map(|c| c["code"].as_str().expect("code"))
+        .collect();
+    assert_eq!(codes, vec!["P", "X", "L", "E"]);
+}
@@ -0,0 +1,149 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn dated_months_stay_in_their_own_school_year() {
+    let workspace = temp_dir("markbook-attendance-school-year");
+    let csv_path = workspace.join("exports").join("attendance.csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    // 2023-24 has a leap-day absence; September repeats in 2024-25.
+    let stamps = [
+        ("2023-09", 1, "A"),
+        ("2024-02", 29, "A"),
+        ("2024-09", 1, "L"),
+    ];
+    for (i, (month, day, code)) in stamps.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("d{}", i),
+            "attendance.setStudentDay",
+            json!({
+                "classId": class_id,
+                "month": month,
+                "studentId": student_id,
+                "day": day,
+                "code": code
+            }),
+        );
+    }
+
+    // Without schoolYear the latest one is read; months never merge across years.
+    let latest = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.yearOpen",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(latest["schoolYear"], json!(2024));
+    assert_eq!(
+        latest["months"][0]["rows"][0]["dayCodes"],
+        json!(format!("L{}", " ".repeat(29)))
+    );
+    assert_eq!(latest["months"][5]["daysInMonth"], json!(28));
+
+    let earlier = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "attendance.yearOpen",
+        json!({ "classId": class_id, "schoolYear": 2023 }),
+    );
+    assert_eq!(
+        earlier["months"][0]["rows"][0]["dayCodes"],
+        json!(format!("A{}", " ".repeat(29)))
+    );
+    assert_eq!(earlier["months"][5]["daysInMonth"], json!(29));
+    assert_eq!(
+        earlier["months"][5]["rows"][0]["dayCodes"],
+        json!(format!("{}A", " ".repeat(28)))
+    );
+
+    let summary = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "attendance.summary",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(summary["summaries"][0]["absent"], json!(0));
+    assert_eq!(summary["summaries"][0]["late"], json!(1));
+    let summary = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "attendance.summary",
+        json!({ "classId": class_id, "schoolYear": 2023 }),
+    );
+    assert_eq!(summary["summaries"][0]["absent"], json!(2));
+    assert_eq!(summary["summaries"][0]["late"], json!(0));
+
+    // The export keeps the year, so the leap day survives a round trip.
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "exchange.exportAttendanceCsv",
+        json!({
+            "classId": class_id,
+            "schoolYear": 2023,
+            "monthFrom": "2",
+            "monthTo": "2",
+            "outPath": csv_path.to_string_lossy()
+        }),
+    );
+    assert_eq!(exported["rowsExported"], json!(29));
+    let csv = std::fs::read_to_string(&csv_path).expect("read csv");
+    assert!(csv.lines().last().expect("row").ends_with(",2024-02,29,A"));
+
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "exchange.importAttendanceCsv",
+        json!({ "classId": class_id, "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(imported["updated"], json!(29));
+    assert_eq!(imported["skipped"], json!(0));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "attendance.yearOpen",
+        json!({ "classId": class_id, "schoolYear": "soon" }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,114 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn set_student_range_stamps_school_days_and_skips_non_school_days() {
+    let workspace = temp_dir("markbook-attendance-set-student-range");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Lee", "firstName": "Sam" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.setTypeOfDay",
+        json!({ "classId": class_id, "month": "10", "day": 5, "code": "H" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "attendance.setStudentDay",
+        json!({ "classId": class_id, "month": "10", "studentId": student_id, "day": 9, "code": "L" }),
+    );
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "attendance.setStudentRange",
+        json!({
+            "classId": class_id,
+            "month": "10",
+            "studentId": student_id,
+            "dayFrom": 3,
+            "dayTo": 7,
+            "code": "A"
+        }),
+    );
+    assert_eq!(res, json!({ "updated": 4 }));
+
+    let month = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "attendance.monthOpen",
+        json!({ "classId": class_id, "month": "10" }),
+    );
+    let codes = month["rows"][0]["dayCodes"].as_str().expect("dayCodes");
+    assert_eq!(codes.len(), 31);
+    // Day 5 is a holiday and day 9 keeps its earlier code.
+    assert_eq!(&codes[..10], "  AA AA L ");
+
+    for (i, (from, to)) in [(0, 3), (5, 4), (30, 32)].into_iter().enumerate() {
+        let bad = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad-{}", i),
+            "attendance.setStudentRange",
+            json!({
+                "classId": class_id,
+                "month": "10",
+                "studentId": student_id,
+                "dayFrom": from,
+                "dayTo": to,
+                "code": "A"
+            }),
+        );
+        assert_eq!(bad["error"]["code"], json!("bad_params"));
+    }
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "attendance.setStudentRange",
+        json!({
+            "classId": class_id,
+            "month": "10",
+            "studentId": "nope",
+            "dayFrom": 1,
+            "dayTo": 2,
+            "code": "A"
+        }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
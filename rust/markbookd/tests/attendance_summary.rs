@@ -0,0 +1,107 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn attendance_summary_tallies_codes_within_month_range() {
+    let workspace = temp_dir("markbook-attendance-summary");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+
+    // September: day 3 is a holiday. October: one more absence.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "attendance.setTypeOfDay",
+        json!({ "classId": class_id, "month": "9", "day": 3, "code": "H" }),
+    );
+    let stamps = [
+        ("9", 1, "P"),
+        ("9", 2, "A"),
+        ("9", 3, "P"),
+        ("9", 4, "L"),
+        ("9", 5, "E"),
+        ("10", 1, "A"),
+    ];
+    for (i, (month, day, code)) in stamps.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("d{}", i),
+            "attendance.setStudentDay",
+            json!({
+                "classId": class_id,
+                "month": month,
+                "studentId": student_ids[0],
+                "day": day,
+                "code": code
+            }),
+        );
+    }
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.summary",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        all,
+        json!({ "summaries": [
+            { "studentId": student_ids[0], "present": 1, "absent": 2, "late": 1, "excused": 1 },
+            { "studentId": student_ids[1], "present": 0, "absent": 0, "late": 0, "excused": 0 }
+        ] })
+    );
+
+    let september = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "attendance.summary",
+        json!({ "classId": class_id, "monthFrom": "9", "monthTo": "9" }),
+    );
+    assert_eq!(september["summaries"][0]["absent"], json!(1));
+
+    let backwards = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "attendance.summary",
+        json!({ "classId": class_id, "monthFrom": "10", "monthTo": "9" }),
+    );
+    assert_eq!(backwards["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,104 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn attendance_year_open_returns_every_month_from_start_month() {
+    let workspace = temp_dir("markbook-attendance-year-open");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Lee", "firstName": "Sam" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.setTypeOfDay",
+        json!({ "classId": class_id, "month": "9", "day": 1, "code": "H" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "attendance.setStudentDay",
+        json!({ "classId": class_id, "month": "10", "studentId": student_id, "day": 2, "code": "A" }),
+    );
+
+    let year = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "attendance.yearOpen",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(year["schoolYearStartMonth"], json!(9));
+    assert_eq!(year["students"][0]["id"], json!(student_id));
+    let months = year["months"].as_array().expect("months");
+    let keys: Vec<&str> = months
+        .iter()
+        .map(|m| m["month"].as_str().expect("month"))
+        .collect();
+    assert_eq!(
+        keys,
+        vec!["9", "10", "11", "12", "1", "2", "3", "4", "5", "6", "7", "8"]
+    );
+
+    let sep = &months[0];
+    assert_eq!(sep["daysInMonth"], json!(30));
+    assert!(sep["typeOfDayCodes"]
+        .as_str()
+        .expect("codes")
+        .starts_with('H'));
+    let oct_codes = months[1]["rows"][0]["dayCodes"].as_str().expect("dayCodes");
+    assert_eq!(oct_codes.len(), 31);
+    assert_eq!(oct_codes.chars().nth(1), Some('A'));
+    // Untouched months still come back with blank codes for every student.
+    assert_eq!(months[5]["typeOfDayCodes"], json!(" ".repeat(28)));
+    assert_eq!(months[5]["rows"][0]["dayCodes"], json!(" ".repeat(28)));
+
+    let jan_start = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "attendance.yearOpen",
+        json!({ "classId": class_id, "schoolYearStartMonth": 1 }),
+    );
+    assert_eq!(jan_start["months"][0]["month"], json!("1"));
+    assert_eq!(jan_start["months"][11]["month"], json!("12"));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "attendance.yearOpen",
+        json!({ "classId": class_id, "schoolYearStartMonth": 13 }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
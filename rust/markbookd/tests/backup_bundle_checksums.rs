@@ -0,0 +1,90 @@
+mod test_support;
+
+use serde_json::json;
+use std::fs::File;
+use std::io::{Read, Write};
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+/// Copies a bundle, replacing the database entry while keeping the original
+/// manifest (and therefore its now-stale checksums).
+fn tamper_db_entry(src: &std::path::Path, dst: &std::path::Path) {
+    let mut archive = zip::ZipArchive::new(File::open(src).expect("open bundle")).expect("zip");
+    let mut out = zip::ZipWriter::new(File::create(dst).expect("create tampered"));
+    let opts = zip::write::FileOptions::default();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).expect("entry");
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).expect("read entry");
+        if name == "db/markbook.sqlite3" {
+            let last = bytes.len() - 1;
+            bytes[last] ^= 0xFF;
+        }
+        out.start_file(name, opts).expect("start entry");
+        out.write_all(&bytes).expect("write entry");
+    }
+    out.finish().expect("finish tampered");
+}
+
+#[test]
+fn backup_import_reports_manifest_and_rejects_tampered_bundle() {
+    let workspace = temp_dir("markbook-backup-checksums");
+    let restored = temp_dir("markbook-backup-checksums-restored");
+    let bundle = workspace.join("exports").join("workspace.mbcbackup.zip");
+    let tampered = workspace.join("exports").join("tampered.mbcbackup.zip");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Checksums" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "backup.exportWorkspaceBundle",
+        json!({ "outPath": bundle.to_string_lossy() }),
+    );
+    tamper_db_entry(&bundle, &tampered);
+
+    let rejected = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "backup.importWorkspaceBundle",
+        json!({
+            "inPath": tampered.to_string_lossy(),
+            "workspacePath": restored.to_string_lossy()
+        }),
+    );
+    assert_eq!(rejected["error"]["code"], json!("bundle_corrupt"));
+    assert_eq!(
+        rejected["error"]["details"]["entry"],
+        json!("db/markbook.sqlite3")
+    );
+    assert!(!restored.join("markbook.sqlite3").exists());
+
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "backup.importWorkspaceBundle",
+        json!({
+            "inPath": bundle.to_string_lossy(),
+            "workspacePath": restored.to_string_lossy()
+        }),
+    );
+    assert_eq!(imported["manifestPresent"], json!(true));
+    let classes = request_ok(&mut stdin, &mut reader, "6", "classes.list", json!({}));
+    assert_eq!(classes["classes"][0]["name"], json!("Checksums"));
+}
@@ -0,0 +1,197 @@
+mod test_support;
+
+use serde_json::json;
+use std::fs::File;
+use std::io::{Read, Write};
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn class_row_count(workspace: &std::path::Path, class_id: &str, sql: &str) -> i64 {
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    conn.query_row(sql, [class_id], |r| r.get(0))
+        .expect("count rows")
+}
+
+const SCORES_SQL: &str = "SELECT COUNT(*) FROM scores sc
+     JOIN assessments a ON a.id = sc.assessment_id
+     JOIN mark_sets ms ON ms.id = a.mark_set_id
+     WHERE ms.class_id = ?";
+
+#[test]
+fn class_bundle_round_trips_into_fresh_and_same_workspace() {
+    let source = temp_dir("markbook-class-bundle-source");
+    let target = temp_dir("markbook-class-bundle-target");
+    let bundle = source.join("exports").join("class.mbcclass.zip");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": source.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "backup.exportClassBundle",
+        json!({ "classId": "nope", "outPath": bundle.to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "backup.exportClassBundle",
+        json!({ "classId": class_id, "outPath": bundle.to_string_lossy() }),
+    );
+    assert_eq!(exported["bundleFormat"], json!("markbook-class-v1"));
+    assert_eq!(exported["classId"], json!(class_id));
+    let counts = exported["counts"].clone();
+    assert!(counts["students"].as_u64().expect("students") > 0);
+    assert!(counts["scores"].as_u64().expect("scores") > 0);
+    assert!(counts["markSets"].as_u64().expect("markSets") > 0);
+    assert_eq!(
+        counts["scores"].as_i64(),
+        Some(class_row_count(&source, &class_id, SCORES_SQL))
+    );
+
+    // Re-importing into the source workspace must not collide with the original.
+    let copy = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "backup.importClassBundle",
+        json!({ "inPath": bundle.to_string_lossy() }),
+    );
+    assert_eq!(copy["counts"], counts);
+    let copy_id = copy["classId"].as_str().expect("classId").to_string();
+    assert_ne!(copy_id, class_id);
+    assert_eq!(copy["className"], exported["className"]);
+    assert_eq!(
+        class_row_count(&source, &copy_id, SCORES_SQL),
+        class_row_count(&source, &class_id, SCORES_SQL)
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "workspace.select",
+        json!({ "path": target.to_string_lossy() }),
+    );
+    let restored = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "backup.importClassBundle",
+        json!({ "inPath": bundle.to_string_lossy() }),
+    );
+    assert_eq!(restored["counts"], counts);
+    let restored_id = restored["classId"].as_str().expect("classId");
+
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "students.list",
+        json!({ "classId": restored_id }),
+    );
+    assert_eq!(
+        students["students"].as_array().map(|a| a.len() as u64),
+        counts["students"].as_u64()
+    );
+    let mark_sets = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "marksets.list",
+        json!({ "classId": restored_id }),
+    );
+    assert!(!mark_sets["markSets"]
+        .as_array()
+        .expect("markSets")
+        .is_empty());
+    let integrity = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "db.integrityCheck",
+        json!({ "includeOrphans": true }),
+    );
+    assert_eq!(integrity, json!({ "ok": true, "problems": [] }));
+}
+
+#[test]
+fn class_bundle_import_rejects_tampered_rows() {
+    let workspace = temp_dir("markbook-class-bundle-tampered");
+    let bundle = workspace.join("exports").join("class.mbcclass.zip");
+    let tampered = workspace.join("exports").join("tampered.mbcclass.zip");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Tampered" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "backup.exportClassBundle",
+        json!({ "classId": created["classId"], "outPath": bundle.to_string_lossy() }),
+    );
+
+    let mut archive = zip::ZipArchive::new(File::open(&bundle).expect("open bundle")).expect("zip");
+    let mut out = zip::ZipWriter::new(File::create(&tampered).expect("create tampered"));
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).expect("entry");
+        let name = entry.name().to_string();
+        let mut text = String::new();
+        entry.read_to_string(&mut text).expect("read entry");
+        if name == "class/rows.json" {
+            text = text.replace("Tampered", "Edited");
+        }
+        out.start_file(name, zip::write::FileOptions::default())
+            .expect("start entry");
+        out.write_all(text.as_bytes()).expect("write entry");
+    }
+    out.finish().expect("finish tampered");
+
+    let rejected = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "backup.importClassBundle",
+        json!({ "inPath": tampered.to_string_lossy() }),
+    );
+    assert_eq!(rejected["error"]["code"], json!("bundle_corrupt"));
+    assert_eq!(
+        rejected["error"]["details"]["entry"],
+        json!("class/rows.json")
+    );
+
+    let classes = request_ok(&mut stdin, &mut reader, "5", "classes.list", json!({}));
+    assert_eq!(classes["classes"].as_array().map(|a| a.len()), Some(1));
+}
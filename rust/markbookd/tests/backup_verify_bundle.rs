@@ -0,0 +1,78 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn backup_verify_bundle_checks_exported_bundle() {
+    let workspace = temp_dir("markbook-backup-verify");
+    let bundle = workspace.join("exports").join("class.mbcbackup.zip");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "backup.exportWorkspaceBundle",
+        json!({ "outPath": bundle.to_string_lossy() }),
+    );
+
+    let verified = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "backup.verifyBundle",
+        json!({ "inPath": bundle.to_string_lossy() }),
+    );
+    assert_eq!(
+        verified,
+        json!({
+            "ok": true,
+            "bundleFormat": "markbook-workspace-v2",
+            "entryCount": 3,
+            "problems": []
+        })
+    );
+
+    let bytes = std::fs::read(&bundle).expect("read bundle");
+    let truncated = workspace.join("exports").join("truncated.mbcbackup.zip");
+    std::fs::write(&truncated, &bytes[..bytes.len() - 64]).expect("write truncated");
+    let damaged = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "backup.verifyBundle",
+        json!({ "inPath": truncated.to_string_lossy() }),
+    );
+    assert_eq!(damaged["ok"], json!(false));
+    assert!(!damaged["problems"].as_array().expect("problems").is_empty());
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "backup.verifyBundle",
+        json!({ "inPath": workspace.join("nope.zip").to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    // Verification never touches the open workspace.
+    let classes = request_ok(&mut stdin, &mut reader, "7", "classes.list", json!({}));
+    assert_eq!(classes["classes"].as_array().map(|a| a.len()), Some(1));
+}
@@ -2,7 +2,7 @@
 mod backup;
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -47,8 +47,17 @@ fn zip_export_and_import_roundtrip() {
         .by_name("db/markbook.sqlite3")
         .expect("database entry in bundle");
 
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).expect("manifest json");
+    assert_eq!(manifest["entries"][0]["path"], "db/markbook.sqlite3");
+    assert_eq!(manifest["entries"][0]["size"], bytes.len());
+    assert_eq!(
+        manifest["entries"][0]["sha256"].as_str().map(|s| s.len()),
+        Some(64)
+    );
+
     let import = backup::import_workspace_bundle(&bundle_path, &workspace2).expect("import bundle");
     assert_eq!(import.bundle_format_detected, backup::BUNDLE_FORMAT_V2);
+    assert!(import.manifest_present);
 
     let db_dst = workspace2.join("markbook.sqlite3");
     let restored = std::fs::read(&db_dst).expect("read restored db");
@@ -78,3 +87,109 @@ fn legacy_sqlite_import_is_supported() {
     let _ = std::fs::remove_dir_all(out_dir);
     let _ = std::fs::remove_dir_all(workspace);
 }
+
+#[test]
+fn verify_reports_unreadable_database_and_truncated_bundle() {
+    let workspace = temp_dir("markbook-backup-verify-src");
+    let out_dir = temp_dir("markbook-backup-verify-out");
+
+    let db_path = workspace.join("markbook.sqlite3");
+    {
+        let conn = rusqlite::Connection::open(&db_path).expect("create db");
+        conn.execute_batch("CREATE TABLE t(x TEXT); INSERT INTO t VALUES('kept');")
+            .expect("seed db");
+    }
+    let good = out_dir.join("good.mbcbackup.zip");
+    backup::export_workspace_bundle(&workspace, &good).expect("export good bundle");
+    let verify = backup::verify_workspace_bundle(&good).expect("verify good bundle");
+    assert_eq!(verify.bundle_format, backup::BUNDLE_FORMAT_V2);
+    assert_eq!(verify.entry_count, 3);
+    assert!(verify.problems.is_empty(), "{:?}", verify.problems);
+
+    std::fs::write(&db_path, b"sqlite-test-payload").expect("overwrite db");
+    let bad_db = out_dir.join("bad-db.mbcbackup.zip");
+    backup::export_workspace_bundle(&workspace, &bad_db).expect("export bad bundle");
+    let verify = backup::verify_workspace_bundle(&bad_db).expect("verify bad bundle");
+    assert_eq!(verify.problems.len(), 1, "{:?}", verify.problems);
+    assert_eq!(
+        verify.problems[0].entry.as_deref(),
+        Some("db/markbook.sqlite3")
+    );
+
+    let bytes = std::fs::read(&good).expect("read good bundle");
+    let truncated = out_dir.join("truncated.mbcbackup.zip");
+    std::fs::write(&truncated, &bytes[..bytes.len() / 2]).expect("write truncated bundle");
+    let verify = backup::verify_workspace_bundle(&truncated).expect("verify truncated bundle");
+    assert_eq!(verify.entry_count, 0);
+    assert!(verify.problems[0].message.contains("invalid zip archive"));
+
+    let _ = std::fs::remove_dir_all(workspace);
+    let _ = std::fs::remove_dir_all(out_dir);
+}
+
+fn write_bundle(path: &PathBuf, manifest: serde_json::Value, db_bytes: &[u8]) {
+    let mut zip = zip::ZipWriter::new(File::create(path).expect("create bundle"));
+    let opts = zip::write::FileOptions::default();
+    zip.start_file("manifest.json", opts)
+        .expect("start manifest");
+    zip.write_all(manifest.to_string().as_bytes())
+        .expect("write manifest");
+    zip.start_file("db/markbook.sqlite3", opts)
+        .expect("start db");
+    zip.write_all(db_bytes).expect("write db");
+    zip.finish().expect("finish bundle");
+}
+
+#[test]
+fn import_rejects_checksum_mismatch_and_accepts_unlisted_bundles() {
+    let out_dir = temp_dir("markbook-backup-checksums");
+    let workspace = temp_dir("markbook-backup-checksums-dst");
+    let existing = b"existing-workspace-db";
+    std::fs::write(workspace.join("markbook.sqlite3"), existing).expect("seed workspace db");
+
+    let tampered = out_dir.join("tampered.mbcbackup.zip");
+    write_bundle(
+        &tampered,
+        serde_json::json!({
+            "format": backup::BUNDLE_FORMAT_V2,
+            "entries": [{
+                "path": "db/markbook.sqlite3",
+                "sha256": "0".repeat(64),
+                "size": 7
+            }]
+        }),
+        b"payload",
+    );
+    let e = backup::import_workspace_bundle(&tampered, &workspace)
+        .expect_err("tampered bundle must fail");
+    let corrupt = e
+        .downcast_ref::<backup::BundleCorrupt>()
+        .expect("BundleCorrupt error");
+    assert_eq!(corrupt.entry, "db/markbook.sqlite3");
+    let verify = backup::verify_workspace_bundle(&tampered).expect("verify tampered");
+    assert!(verify
+        .problems
+        .iter()
+        .any(|p| p.message.contains("checksum")));
+    // The live database is untouched when verification fails.
+    assert_eq!(
+        std::fs::read(workspace.join("markbook.sqlite3")).expect("read db"),
+        existing
+    );
+
+    let older = out_dir.join("older.mbcbackup.zip");
+    write_bundle(
+        &older,
+        serde_json::json!({ "format": backup::BUNDLE_FORMAT_V2, "version": 2 }),
+        b"older-payload",
+    );
+    let import = backup::import_workspace_bundle(&older, &workspace).expect("import older bundle");
+    assert!(!import.manifest_present);
+    assert_eq!(
+        std::fs::read(workspace.join("markbook.sqlite3")).expect("read db"),
+        b"older-payload"
+    );
+
+    let _ = std::fs::remove_dir_all(out_dir);
+    let _ = std::fs::remove_dir_all(workspace);
+}
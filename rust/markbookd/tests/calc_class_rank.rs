@@ -0,0 +1,131 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn class_rank_uses_competition_ranking_and_puts_missing_averages_last() {
+    let workspace = temp_dir("markbook-calc-class-rank");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Ranks" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Diaz", "Evans"]
+        .iter()
+        .enumerate()
+    {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Test 1",
+            "categoryName": "Tests",
+            "outOf": 10
+        }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    // Adams and Chen tie at 80%; Diaz has no mark and so no average.
+    let edits = [
+        (0, json!(8), "scored"),
+        (1, json!(9), "scored"),
+        (2, json!(8), "scored"),
+        (3, json!(null), "no_mark"),
+        (4, json!(5), "scored"),
+    ];
+    for (i, (student, value, status)) in edits.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_ids[*student],
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "calc.classRank",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "ranks": [
+                { "studentId": student_ids[1], "percent": 90.0, "rank": 1 },
+                { "studentId": student_ids[0], "percent": 80.0, "rank": 2 },
+                { "studentId": student_ids[2], "percent": 80.0, "rank": 2 },
+                { "studentId": student_ids[4], "percent": 50.0, "rank": 4 },
+                { "studentId": student_ids[3], "percent": null, "rank": null }
+            ]
+        })
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "calc.classRank",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,153 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn markset_averages_exclude_no_mark_and_count_zero() {
+    let workspace = temp_dir("markbook-calc-markset-averages");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Averages" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let mut assessment_ids = Vec::new();
+    for (i, title) in ["Test 1", "Test 2"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": "Tests",
+                "outOf": 10
+            }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+
+    // Adams: 8/10 plus a no_mark (excluded); Baker: zero plus 6/10; Chen: nothing counted.
+    let edits = [
+        (0, 0, json!(8), "scored"),
+        (0, 1, json!(null), "no_mark"),
+        (1, 0, json!(null), "zero"),
+        (1, 1, json!(6), "scored"),
+        (2, 0, json!(null), "no_mark"),
+    ];
+    for (i, (student, assessment, value, status)) in edits.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_ids[*assessment],
+                "studentId": student_ids[*student],
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "calc.markSetAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "averages": [
+                { "studentId": student_ids[0], "percent": 80.0 },
+                { "studentId": student_ids[1], "percent": 30.0 },
+                { "studentId": student_ids[2], "percent": null }
+            ]
+        })
+    );
+
+    let one = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "calc.markSetAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "studentId": student_ids[1] }),
+    );
+    assert_eq!(
+        one,
+        json!({ "averages": [{ "studentId": student_ids[1], "percent": 30.0 }] })
+    );
+
+    // The student must belong to classId, not just to the mark set's class.
+    let other_class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let foreign = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "calc.markSetAverages",
+        json!({ "classId": other_class_id, "markSetId": mark_set_id, "studentId": student_ids[1] }),
+    );
+    assert_eq!(foreign["error"]["code"], json!("not_found"));
+}
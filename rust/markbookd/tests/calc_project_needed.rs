@@ -0,0 +1,199 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn project_needed_finds_the_score_that_reaches_the_target() {
+    let workspace = temp_dir("markbook-calc-project-needed");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Physics" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let mut assessment_ids = Vec::new();
+    for (title, weight, out_of) in [("Quiz", 1, 10), ("Final", 3, 50)] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("assessment-{}", title),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": "Tests",
+                "weight": weight,
+                "outOf": out_of
+            }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "scores.setCell",
+        json!({
+            "classId": class_id,
+            "assessmentId": assessment_ids[0],
+            "studentId": student_id,
+            "rawValue": 6,
+            "status": "scored"
+        }),
+    );
+    let project = |stdin: &mut _, reader: &mut _, id: &str, target: f64| {
+        request_ok(
+            stdin,
+            reader,
+            id,
+            "calc.projectNeeded",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "studentId": student_id,
+                "assessmentId": assessment_ids[1],
+                "targetPercent": target
+            }),
+        )
+    };
+
+    // (60 * 1 + p * 3) / 4 = 75 needs p = 80%, i.e. 40 / 50, but the final
+    // mark rounds to one decimal: 39.97 gives 74.955, which shows as 75.0.
+    let exact = project(&mut stdin, &mut reader, "7", 75.0);
+    assert_eq!(
+        exact,
+        json!({
+            "targetPercent": 75.0,
+            "outOf": 50.0,
+            "currentMark": 60.0,
+            "maxMark": 90.0,
+            "neededRaw": 39.97,
+            "neededPercent": 79.9,
+            "projectedMark": 75.0,
+            "alreadyAchieved": false,
+            "impossible": false
+        })
+    );
+
+    let rounded = project(&mut stdin, &mut reader, "8", 70.0);
+    assert_eq!(rounded["neededRaw"], json!(36.64));
+    assert_eq!(rounded["projectedMark"], json!(70.0));
+
+    let impossible = project(&mut stdin, &mut reader, "9", 95.0);
+    assert_eq!(impossible["impossible"], json!(true));
+    assert_eq!(impossible["neededRaw"], json!(null));
+    assert_eq!(impossible["maxMark"], json!(90.0));
+
+    // A zero on the final still leaves 60 / 4 = 15.
+    let achieved = project(&mut stdin, &mut reader, "10", 15.0);
+    assert_eq!(achieved["alreadyAchieved"], json!(true));
+    assert_eq!(achieved["neededRaw"], json!(0.0));
+    assert_eq!(achieved["projectedMark"], json!(15.0));
+
+    // Nothing was written: the final is still unmarked.
+    let averages = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "calc.markSetAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(averages["averages"][0]["percent"], json!(60.0));
+
+    let base = json!({
+        "classId": class_id,
+        "markSetId": mark_set_id,
+        "studentId": student_id,
+        "assessmentId": assessment_ids[1],
+        "targetPercent": 75
+    });
+    for (i, (key, value, code)) in [
+        ("targetPercent", json!(-1), "bad_params"),
+        ("targetPercent", json!("high"), "bad_params"),
+        ("studentId", json!("nope"), "not_found"),
+        ("assessmentId", json!("nope"), "not_found"),
+        ("markSetId", json!("nope"), "not_found"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut params = base.clone();
+        params[key] = value;
+        let res = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad-{}", i),
+            "calc.projectNeeded",
+            params,
+        );
+        assert_eq!(res["error"]["code"], json!(code), "{}", key);
+    }
+
+    // A mode mark can drop when one score rises, so bisection would be wrong.
+    for calc_method in [2, 3] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("mode-{}", calc_method),
+            "marksets.update",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "patch": { "calcMethod": calc_method }
+            }),
+        );
+        let res = request(
+            &mut stdin,
+            &mut reader,
+            &format!("mode-project-{}", calc_method),
+            "calc.projectNeeded",
+            base.clone(),
+        );
+        assert_eq!(res["error"]["code"], json!("bad_params"));
+        assert_eq!(res["error"]["details"]["calcMethod"], json!(calc_method));
+    }
+}
@@ -0,0 +1,137 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn calc_methods_differ_on_a_skewed_distribution() {
+    let workspace = temp_dir("markbook-calc-trimmed-mean");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Methods" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+
+    // One low outlier and one perfect score around a cluster in the 70s.
+    for (i, score) in [20, 70, 72, 80, 100].iter().enumerate() {
+        let assessment_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": format!("Test {}", i + 1),
+                "categoryName": "Tests",
+                "outOf": 100
+            }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": score,
+                "status": "scored"
+            }),
+        );
+    }
+
+    // 0 average: 342 / 5; 1 median: middle mark; 5 trimmed mean: (70 + 72 + 80) / 3.
+    for (calc_method, expected) in [(0, 68.4), (1, 72.0), (5, 74.0)] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("u{}", calc_method),
+            "marksets.update",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "patch": { "calcMethod": calc_method }
+            }),
+        );
+        let summary = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", calc_method),
+            "calc.markSetSummary",
+            json!({ "classId": class_id, "markSetId": mark_set_id }),
+        );
+        assert_eq!(
+            summary["settingsApplied"]["calcMethodApplied"],
+            json!(calc_method)
+        );
+        let mark = summary["perStudent"][0]["finalMark"]
+            .as_f64()
+            .expect("finalMark");
+        assert!(
+            (mark - expected).abs() < 1e-6,
+            "calcMethod {} gave {}, expected {}",
+            calc_method,
+            mark,
+            expected
+        );
+    }
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "u6",
+        "marksets.update",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "patch": { "calcMethod": 6 }
+        }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,92 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn categories_update_patches_drop_lowest() {
+    let workspace = temp_dir("markbook-categories-drop-lowest");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Quizzes" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let category_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Quizzes", "weight": 40 }),
+    )["categoryId"]
+        .as_str()
+        .expect("categoryId")
+        .to_string();
+
+    let list = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(list["categories"][0]["dropLowest"], json!(null));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "categories.update",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "categoryId": category_id,
+            "patch": { "dropLowest": 1 }
+        }),
+    );
+    let list = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "categories.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(list["categories"][0]["dropLowest"], json!(1));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "categories.update",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "categoryId": category_id,
+            "patch": { "dropLowest": -1 }
+        }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
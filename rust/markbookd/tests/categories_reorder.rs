@@ -0,0 +1,144 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn categories_reorder_rewrites_sort_order() {
+    let workspace = temp_dir("markbook-categories-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Categories" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut category_ids = Vec::new();
+    for (i, name) in ["Knowledge", "Thinking", "Application"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("cat{}", i),
+            "categories.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "name": name, "weight": 30 }),
+        )["categoryId"]
+            .as_str()
+            .expect("categoryId")
+            .to_string();
+        category_ids.push(id);
+    }
+
+    let reordered = vec![
+        category_ids[2].clone(),
+        category_ids[0].clone(),
+        category_ids[1].clone(),
+    ];
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "categories.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedCategoryIds": reordered
+        }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let rows: Vec<(String, i64)> = listed["categories"]
+        .as_array()
+        .expect("categories")
+        .iter()
+        .map(|c| {
+            (
+                c["name"].as_str().expect("name").to_string(),
+                c["sortOrder"].as_i64().expect("sortOrder"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("Application".to_string(), 0),
+            ("Knowledge".to_string(), 1),
+            ("Thinking".to_string(), 2)
+        ]
+    );
+
+    for (i, (ids, message)) in [
+        (
+            vec![category_ids[0].clone(), category_ids[1].clone()],
+            "orderedCategoryIds must be a permutation of the mark set categories",
+        ),
+        (
+            vec![
+                category_ids[0].clone(),
+                category_ids[0].clone(),
+                category_ids[1].clone(),
+            ],
+            "orderedCategoryIds contains duplicates",
+        ),
+        (
+            vec![
+                category_ids[0].clone(),
+                category_ids[1].clone(),
+                "nope".to_string(),
+            ],
+            "orderedCategoryIds contains unknown categoryId",
+        ),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let rejected = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad{}", i),
+            "categories.reorder",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "orderedCategoryIds": ids
+            }),
+        );
+        assert_eq!(rejected["error"]["code"], json!("bad_params"));
+        assert_eq!(rejected["error"]["message"], json!(message));
+    }
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "categories.reorder",
+        json!({ "classId": class_id, "markSetId": "nope", "orderedCategoryIds": [] }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
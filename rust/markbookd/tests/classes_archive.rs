@@ -0,0 +1,119 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn class_names(classes: &serde_json::Value) -> Vec<String> {
+    classes["classes"]
+        .as_array()
+        .expect("classes")
+        .iter()
+        .map(|c| c["name"].as_str().expect("name").to_string())
+        .collect()
+}
+
+#[test]
+fn archived_classes_are_hidden_from_list_and_active_stats() {
+    let workspace = temp_dir("markbook-classes-archive");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut ids = Vec::new();
+    for name in ["Math 2024", "Math 2025", "Science 2025"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("create-{}", name),
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("student-{}", name),
+            "students.create",
+            json!({ "classId": id, "lastName": "Adams", "firstName": "Pat" }),
+        );
+        ids.push(id);
+    }
+
+    let archived = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.archive",
+        json!({ "classId": ids[0] }),
+    );
+    assert_eq!(archived, json!({ "ok": true, "archived": true }));
+
+    let list = request_ok(&mut stdin, &mut reader, "3", "classes.list", json!({}));
+    assert_eq!(class_names(&list), vec!["Math 2025", "Science 2025"]);
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "classes.list",
+        json!({ "includeArchived": true }),
+    );
+    assert_eq!(
+        class_names(&all),
+        vec!["Math 2024", "Math 2025", "Science 2025"]
+    );
+    assert_eq!(all["classes"][0]["archived"], json!(true));
+    assert_eq!(all["classes"][0]["studentCount"], json!(1));
+
+    // The archived class is still fully readable by id.
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.list",
+        json!({ "classId": ids[0] }),
+    );
+    assert_eq!(students["students"][0]["lastName"], json!("Adams"));
+
+    let stats = request_ok(&mut stdin, &mut reader, "6", "workspace.stats", json!({}));
+    assert_eq!(stats["classCount"], json!(3));
+    assert_eq!(stats["activeClassCount"], json!(2));
+    assert_eq!(stats["studentCount"], json!(3));
+    assert_eq!(stats["activeStudentCount"], json!(2));
+
+    // Reordering covers only the visible classes; the archived one stays last.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "classes.reorder",
+        json!({ "orderedClassIds": [ids[2], ids[1]] }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "classes.unarchive",
+        json!({ "classId": ids[0] }),
+    );
+    let list = request_ok(&mut stdin, &mut reader, "9", "classes.list", json!({}));
+    assert_eq!(
+        class_names(&list),
+        vec!["Science 2025", "Math 2025", "Math 2024"]
+    );
+    assert_eq!(list["classes"][2]["archived"], json!(false));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "classes.archive",
+        json!({ "classId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
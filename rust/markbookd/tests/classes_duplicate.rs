@@ -0,0 +1,251 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn classes_duplicate_copies_structure_without_students_or_scores() {
+    let workspace = temp_dir("markbook-classes-duplicate");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Math 10" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    for (i, name) in ["Tests", "Labs"].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("cat-{}", i),
+            "categories.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "name": name, "weight": 50 }),
+        );
+    }
+    let mut assessment_ids = Vec::new();
+    for (i, (title, category)) in [
+        ("Quiz 1", "Tests"),
+        ("Lab 1", "Labs"),
+        ("Unit Test", "Tests"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("asmt-{}", i),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": category,
+                "outOf": 10
+            }),
+        );
+        assessment_ids.push(
+            created["assessmentId"]
+                .as_str()
+                .expect("assessmentId")
+                .to_string(),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "scores.setCell",
+        json!({
+            "classId": class_id,
+            "assessmentId": assessment_ids[0],
+            "studentId": student_id,
+            "rawValue": 8,
+            "status": "scored"
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.sets.upsert",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "setNumber": 1,
+            "title": "Report Comments",
+            "remarksByStudent": [{ "studentId": student_id, "remark": "Great work" }]
+        }),
+    );
+    {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute(
+            "INSERT INTO attendance_settings(class_id, school_year_start_month) VALUES(?, 8)",
+            [&class_id],
+        )
+        .expect("insert attendance settings");
+    }
+
+    let dup = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "classes.duplicate",
+        json!({
+            "classId": class_id,
+            "name": " Math 10 (Copy) ",
+            "copyOptions": { "commentSets": true, "attendanceSettings": true }
+        }),
+    );
+    let new_class_id = dup["classId"].as_str().expect("classId").to_string();
+    assert_ne!(new_class_id, class_id);
+    assert_eq!(dup["name"], json!("Math 10 (Copy)"));
+    assert_eq!(
+        dup["copied"],
+        json!({
+            "markSets": 1,
+            "categories": 2,
+            "assessments": 3,
+            "commentSets": 1,
+            "attendanceSettings": true
+        })
+    );
+
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "students.list",
+        json!({ "classId": new_class_id }),
+    );
+    assert_eq!(students["students"].as_array().map(|a| a.len()), Some(0));
+
+    let mark_sets = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "marksets.list",
+        json!({ "classId": new_class_id }),
+    );
+    let mark_sets = mark_sets["markSets"].as_array().expect("markSets");
+    assert_eq!(mark_sets.len(), 1);
+    assert_eq!(mark_sets[0]["code"], json!("T1"));
+    let new_mark_set_id = mark_sets[0]["id"].as_str().expect("id").to_string();
+    assert_ne!(new_mark_set_id, mark_set_id);
+
+    let categories = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "categories.list",
+        json!({ "classId": new_class_id, "markSetId": new_mark_set_id }),
+    );
+    let names: Vec<&str> = categories["categories"]
+        .as_array()
+        .expect("categories")
+        .iter()
+        .filter_map(|c| c["name"].as_str())
+        .collect();
+    assert_eq!(names, vec!["Tests", "Labs"]);
+
+    let assessments = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "assessments.list",
+        json!({ "classId": new_class_id, "markSetId": new_mark_set_id }),
+    );
+    let assessments = assessments["assessments"].as_array().expect("assessments");
+    let titles: Vec<(i64, &str)> = assessments
+        .iter()
+        .map(|a| {
+            (
+                a["idx"].as_i64().expect("idx"),
+                a["title"].as_str().expect("title"),
+            )
+        })
+        .collect();
+    assert_eq!(titles, vec![(0, "Quiz 1"), (1, "Lab 1"), (2, "Unit Test")]);
+
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let score_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM scores s
+             JOIN assessments a ON a.id = s.assessment_id
+             WHERE a.mark_set_id = ?",
+            [&new_mark_set_id],
+            |r| r.get(0),
+        )
+        .expect("count scores");
+    assert_eq!(score_count, 0);
+    let (set_title, remark_count): (String, i64) = conn
+        .query_row(
+            "SELECT csi.title,
+                    (SELECT COUNT(*) FROM comment_set_remarks r WHERE r.comment_set_index_id = csi.id)
+             FROM comment_set_indexes csi
+             WHERE csi.class_id = ? AND csi.mark_set_id = ?",
+            [&new_class_id, &new_mark_set_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .expect("copied comment set");
+    assert_eq!(set_title, "Report Comments");
+    assert_eq!(remark_count, 0);
+    let start_month: i64 = conn
+        .query_row(
+            "SELECT school_year_start_month FROM attendance_settings WHERE class_id = ?",
+            [&new_class_id],
+            |r| r.get(0),
+        )
+        .expect("copied attendance settings");
+    assert_eq!(start_month, 8);
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "classes.duplicate",
+        json!({ "classId": "nope", "name": "Copy" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    let blank = request(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "classes.duplicate",
+        json!({ "classId": class_id, "name": "   " }),
+    );
+    assert_eq!(blank["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,61 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn classes_rename_trims_and_validates_name() {
+    let workspace = temp_dir("markbook-classes-rename");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Old Name" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+
+    let renamed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.rename",
+        json!({ "classId": class_id, "name": "  Math 10 Period 2 " }),
+    );
+    assert_eq!(
+        renamed,
+        json!({ "classId": class_id, "name": "Math 10 Period 2" })
+    );
+
+    let list = request_ok(&mut stdin, &mut reader, "4", "classes.list", json!({}));
+    assert_eq!(list["classes"][0]["name"], json!("Math 10 Period 2"));
+
+    let empty = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "classes.rename",
+        json!({ "classId": class_id, "name": "   " }),
+    );
+    assert_eq!(empty["error"]["code"], json!("bad_params"));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "classes.rename",
+        json!({ "classId": "nope", "name": "Anything" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
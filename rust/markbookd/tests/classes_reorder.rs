@@ -0,0 +1,118 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn class_names(classes: &serde_json::Value) -> Vec<String> {
+    classes["classes"]
+        .as_array()
+        .expect("classes")
+        .iter()
+        .map(|c| c["name"].as_str().expect("name").to_string())
+        .collect()
+}
+
+#[test]
+fn classes_reorder_sets_list_order_and_validates_permutation() {
+    let workspace = temp_dir("markbook-classes-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut ids = Vec::new();
+    for name in ["Science", "Math", "History"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("create-{}", name),
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        ids.push(id);
+    }
+
+    // New classes are appended in creation order.
+    let list = request_ok(&mut stdin, &mut reader, "2", "classes.list", json!({}));
+    assert_eq!(class_names(&list), vec!["Science", "Math", "History"]);
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.reorder",
+        json!({ "orderedClassIds": [ids[2], ids[0], ids[1]] }),
+    );
+    let list = request_ok(&mut stdin, &mut reader, "4", "classes.list", json!({}));
+    assert_eq!(class_names(&list), vec!["History", "Science", "Math"]);
+    assert_eq!(list["classes"][0]["studentCount"], json!(0));
+
+    for (i, ordered) in [
+        json!([ids[0], ids[1]]),
+        json!([ids[0], ids[0], ids[1]]),
+        json!([ids[0], ids[1], "nope"]),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let bad = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad-{}", i),
+            "classes.reorder",
+            json!({ "orderedClassIds": ordered }),
+        );
+        assert_eq!(bad["error"]["code"], json!("bad_params"));
+    }
+    let list = request_ok(&mut stdin, &mut reader, "5", "classes.list", json!({}));
+    assert_eq!(class_names(&list), vec!["History", "Science", "Math"]);
+}
+
+#[test]
+fn workspace_without_class_sort_order_is_backfilled_by_name() {
+    let workspace = temp_dir("markbook-classes-sort-order-backfill");
+    {
+        let (_child, mut stdin, mut reader) = spawn_sidecar();
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            "1",
+            "workspace.select",
+            json!({ "path": workspace.to_string_lossy() }),
+        );
+        for name in ["Science", "Math", "History"] {
+            let _ = request_ok(
+                &mut stdin,
+                &mut reader,
+                &format!("create-{}", name),
+                "classes.create",
+                json!({ "name": name }),
+            );
+        }
+    }
+    {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute("ALTER TABLE classes DROP COLUMN sort_order", [])
+            .expect("drop sort_order");
+        // Schema v5: before classes became reorderable.
+        conn.pragma_update(None, "user_version", 5)
+            .expect("set user_version");
+    }
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let list = request_ok(&mut stdin, &mut reader, "2", "classes.list", json!({}));
+    assert_eq!(class_names(&list), vec!["History", "Math", "Science"]);
+}
@@ -0,0 +1,146 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn delete_removes_bank_and_entries_and_reports_referencing_sets() {
+    let workspace = temp_dir("markbook-banks-delete");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let mut bank_ids = Vec::new();
+    for short_name in ["SNC", "MAT"] {
+        let bank_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("bank-{}", short_name),
+            "comments.banks.create",
+            json!({ "shortName": short_name }),
+        )["bankId"]
+            .as_str()
+            .expect("bankId")
+            .to_string();
+        bank_ids.push(bank_id);
+    }
+    for (i, text) in ["Works carefully.", "Asks good questions."]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("entry-{}", i),
+            "comments.banks.entryUpsert",
+            json!({ "bankId": bank_ids[0], "typeCode": "E", "levelCode": "1", "text": text }),
+        );
+    }
+
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "SC1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "comments.sets.upsert",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "setNumber": 1,
+            "title": "Report",
+            "bankShort": "snc"
+        }),
+    );
+
+    let deleted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.banks.delete",
+        json!({ "bankId": bank_ids[0] }),
+    );
+    assert_eq!(deleted["entriesDeleted"], json!(2));
+    assert_eq!(
+        deleted["details"],
+        json!({
+            "warning": "bank_still_referenced",
+            "shortName": "SNC",
+            "commentSets": [{
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "markSetCode": "SC1",
+                "setNumber": 1,
+                "title": "Report"
+            }]
+        })
+    );
+
+    let banks = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.banks.list",
+        json!({}),
+    );
+    let names: Vec<&str> = banks["banks"]
+        .as_array()
+        .expect("banks")
+        .iter()
+        .map(|b| b["shortName"].as_str().expect("shortName"))
+        .collect();
+    assert_eq!(names, vec!["MAT"]);
+    {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        let orphans: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM comment_bank_entries WHERE bank_id = ?",
+                [&bank_ids[0]],
+                |r| r.get(0),
+            )
+            .expect("count entries");
+        assert_eq!(orphans, 0);
+    }
+
+    // An unreferenced bank deletes without details.
+    let plain = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "comments.banks.delete",
+        json!({ "bankId": bank_ids[1] }),
+    );
+    assert_eq!(plain, json!({ "ok": true, "entriesDeleted": 0 }));
+
+    let again = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "comments.banks.delete",
+        json!({ "bankId": bank_ids[1] }),
+    );
+    assert_eq!(again["error"]["code"], json!("not_found"));
+}
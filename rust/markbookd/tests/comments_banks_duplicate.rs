@@ -0,0 +1,137 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn comments_banks_duplicate_copies_entries_under_new_short_name() {
+    let workspace = temp_dir("markbook-comments-banks-duplicate");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let src_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "comments.banks.create",
+        json!({ "shortName": "SNC" }),
+    )["bankId"]
+        .as_str()
+        .expect("bankId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "comments.banks.updateMeta",
+        json!({ "bankId": src_id, "patch": { "fitProfile": "80,2" } }),
+    );
+    for (i, (type_code, level_code, text)) in [
+        ("E", "1", "Works with care."),
+        ("P", "3", "Needs to review notes."),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("entry-{}", i),
+            "comments.banks.entryUpsert",
+            json!({
+                "bankId": src_id,
+                "typeCode": type_code,
+                "levelCode": level_code,
+                "text": text
+            }),
+        );
+    }
+
+    let copy_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "comments.banks.duplicate",
+        json!({ "bankId": src_id, "shortName": "SNC copy" }),
+    )["bankId"]
+        .as_str()
+        .expect("bankId")
+        .to_string();
+    assert_ne!(copy_id, src_id);
+
+    let src = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.banks.open",
+        json!({ "bankId": src_id }),
+    );
+    let copy = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.banks.open",
+        json!({ "bankId": copy_id }),
+    );
+    assert_eq!(copy["bank"]["shortName"], json!("SNC copy"));
+    assert_eq!(copy["bank"]["fitProfile"], src["bank"]["fitProfile"]);
+    let strip_ids = |v: &serde_json::Value| -> Vec<serde_json::Value> {
+        v["entries"]
+            .as_array()
+            .expect("entries")
+            .iter()
+            .map(|e| json!([e["sortOrder"], e["typeCode"], e["levelCode"], e["text"]]))
+            .collect()
+    };
+    assert_eq!(strip_ids(&copy), strip_ids(&src));
+    assert_eq!(strip_ids(&copy).len(), 2);
+    assert_ne!(copy["entries"][0]["id"], src["entries"][0]["id"]);
+
+    // Editing the copy leaves the source alone.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "comments.banks.entryDelete",
+        json!({ "bankId": copy_id, "entryId": copy["entries"][0]["id"] }),
+    );
+    let src_after = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "comments.banks.open",
+        json!({ "bankId": src_id }),
+    );
+    assert_eq!(strip_ids(&src_after).len(), 2);
+
+    let taken = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "comments.banks.duplicate",
+        json!({ "bankId": src_id, "shortName": "SNC" }),
+    );
+    assert_eq!(taken["error"]["code"], json!("duplicate_short_name"));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "comments.banks.duplicate",
+        json!({ "bankId": "nope", "shortName": "Other" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+    let banks = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "comments.banks.list",
+        json!({}),
+    );
+    assert_eq!(banks["banks"].as_array().map(|a| a.len()), Some(2));
+}
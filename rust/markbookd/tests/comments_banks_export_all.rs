@@ -0,0 +1,102 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn export_all_bnk_writes_one_file_per_bank_and_suffixes_collisions() {
+    let workspace = temp_dir("markbook-banks-export-all");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    // "snc.BNK" came from a file import and maps onto the same file as "SNC".
+    let mut bank_ids = Vec::new();
+    for (i, short_name) in ["SNC", "snc.BNK", "Math/Gr9"].iter().enumerate() {
+        let bank_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("bank-{}", i),
+            "comments.banks.create",
+            json!({ "shortName": short_name }),
+        )["bankId"]
+            .as_str()
+            .expect("bankId")
+            .to_string();
+        bank_ids.push(bank_id);
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "comments.banks.entryUpsert",
+        json!({
+            "bankId": bank_ids[0],
+            "typeCode": "E",
+            "levelCode": "1",
+            "text": "Lab reports show effort and care."
+        }),
+    );
+
+    let out_folder = workspace.join("banks");
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "comments.banks.exportAllBnk",
+        json!({ "outFolder": out_folder.to_string_lossy() }),
+    );
+    assert_eq!(res["count"], json!(3));
+    let files: Vec<(String, String)> = res["files"]
+        .as_array()
+        .expect("files")
+        .iter()
+        .map(|f| {
+            let path = std::path::PathBuf::from(f["path"].as_str().expect("path"));
+            assert!(path.is_file(), "missing {}", path.display());
+            (
+                f["shortName"].as_str().expect("shortName").to_string(),
+                path.file_name()
+                    .and_then(|s| s.to_str())
+                    .expect("file name")
+                    .to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            ("Math/Gr9".to_string(), "Math_Gr9.BNK".to_string()),
+            ("SNC".to_string(), "SNC.BNK".to_string()),
+            ("snc.BNK".to_string(), "snc_2.BNK".to_string()),
+        ]
+    );
+
+    // Each file is byte-for-byte what the single-bank export writes.
+    let single = workspace.join("single.BNK");
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "comments.banks.exportBnk",
+        json!({ "bankId": bank_ids[0], "path": single.to_string_lossy() }),
+    );
+    assert_eq!(
+        std::fs::read(out_folder.join("SNC.BNK")).expect("read batch file"),
+        std::fs::read(&single).expect("read single file")
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.banks.exportAllBnk",
+        json!({}),
+    );
+    assert_eq!(missing["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,126 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn comments_banks_search_matches_text_case_insensitively_across_banks() {
+    let workspace = temp_dir("markbook-comments-banks-search");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let mut bank_ids = Vec::new();
+    for (i, short_name) in ["SNC", "MAT"].iter().enumerate() {
+        let bank_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("bank-{}", i),
+            "comments.banks.create",
+            json!({ "shortName": short_name }),
+        )["bankId"]
+            .as_str()
+            .expect("bankId")
+            .to_string();
+        bank_ids.push(bank_id);
+    }
+    let (snc, mat) = (&bank_ids[0], &bank_ids[1]);
+
+    let entries = [
+        (snc, "E", "1", "Lab reports show Effort and care."),
+        (snc, "E", "2", "Needs to hand in labs on time."),
+        (mat, "E", "1", "Shows great effort in problem solving."),
+        (mat, "P", "3", "Uses 100% of class time well."),
+    ];
+    let mut entry_ids = Vec::new();
+    for (i, (bank_id, type_code, level_code, text)) in entries.iter().enumerate() {
+        let entry_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("entry-{}", i),
+            "comments.banks.entryUpsert",
+            json!({
+                "bankId": bank_id,
+                "typeCode": type_code,
+                "levelCode": level_code,
+                "text": text
+            }),
+        )["entryId"]
+            .as_str()
+            .expect("entryId")
+            .to_string();
+        entry_ids.push(entry_id);
+    }
+
+    // MAT sorts before SNC; entries within a bank keep their sort order.
+    let found = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "comments.banks.search",
+        json!({ "query": "EFFORT" }),
+    );
+    assert_eq!(
+        found["results"],
+        json!([
+            {
+                "bankId": mat,
+                "entryId": entry_ids[2],
+                "typeCode": "E",
+                "levelCode": "1",
+                "text": "Shows great effort in problem solving."
+            },
+            {
+                "bankId": snc,
+                "entryId": entry_ids[0],
+                "typeCode": "E",
+                "levelCode": "1",
+                "text": "Lab reports show Effort and care."
+            }
+        ])
+    );
+
+    let scoped = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "comments.banks.search",
+        json!({ "query": "lab", "bankId": snc, "limit": 1 }),
+    );
+    let scoped = scoped["results"].as_array().expect("results");
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0]["entryId"], json!(entry_ids[0]));
+
+    // Wildcard characters are matched literally.
+    let literal = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "comments.banks.search",
+        json!({ "query": "0%" }),
+    );
+    assert_eq!(literal["results"].as_array().map(|a| a.len()), Some(1));
+
+    let blank = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.banks.search",
+        json!({ "query": "  " }),
+    );
+    assert_eq!(blank["error"]["code"], json!("bad_params"));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.banks.search",
+        json!({ "query": "lab", "bankId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,102 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn comments_fit_check_flags_remarks_over_max_chars_after_subject_substitution() {
+    let workspace = temp_dir("markbook-comments-fit-check");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Math 8" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Dunn"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        );
+        ids.push(
+            created["studentId"]
+                .as_str()
+                .expect("studentId")
+                .to_string(),
+        );
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let subject_remark = format!("{} {{subject}}", "x".repeat(90));
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "comments.sets.upsert",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "setNumber": 1,
+            "title": "Report",
+            "fitSubj": "Mathematics",
+            "maxChars": 100,
+            "remarksByStudent": [
+                // 100 chars as stored, 102 once the subject is filled in.
+                { "studentId": ids[0], "remark": subject_remark },
+                { "studentId": ids[1], "remark": "  Well done.  " },
+                { "studentId": ids[2], "remark": "y".repeat(110) },
+                { "studentId": ids[3], "remark": "" }
+            ]
+        }),
+    );
+
+    let checked = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.fitCheck",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "setNumber": 1 }),
+    );
+    assert_eq!(checked["maxChars"], json!(100));
+    assert_eq!(checked["checked"], json!(3));
+    assert_eq!(
+        checked["overLimit"],
+        json!([
+            { "studentId": ids[0], "displayName": "Adams, Pat", "length": 102, "overBy": 2 },
+            { "studentId": ids[2], "displayName": "Chen, Pat", "length": 110, "overBy": 10 }
+        ])
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.fitCheck",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "setNumber": 2 }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,147 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn comments_render_expands_names_and_pronouns_per_student() {
+    let workspace = temp_dir("markbook-comments-render");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "English 9" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+
+    let mut ids = Vec::new();
+    for (i, (last, first, pronouns)) in [
+        ("Adams", "Ava", json!("she/her")),
+        ("Baker", "Ben", json!("he")),
+        ("Chen", "Cam", json!(null)),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first, "pronouns": pronouns }),
+        );
+        ids.push(
+            created["studentId"]
+                .as_str()
+                .expect("studentId")
+                .to_string(),
+        );
+    }
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.update",
+        json!({ "classId": class_id, "studentId": ids[2], "patch": { "pronouns": "xe" } }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.update",
+        json!({ "classId": class_id, "studentId": ids[2], "patch": { "pronouns": "They/Them" } }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let pronouns: Vec<serde_json::Value> = listed["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| s["pronouns"].clone())
+        .collect();
+    assert_eq!(pronouns, vec![json!("she"), json!("he"), json!("they")]);
+
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let template = "{firstName} revised {his/her} essay. {He/She} should be proud.";
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "comments.sets.upsert",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "setNumber": 1,
+            "title": "Report",
+            "remarksByStudent": [
+                { "studentId": ids[0], "remark": template },
+                { "studentId": ids[1], "remark": template },
+                { "studentId": ids[2], "remark": "Ask {him/her} about {lastName}'s {grade}." }
+            ]
+        }),
+    );
+
+    let rendered = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "comments.render",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "setNumber": 1 }),
+    );
+    let texts: Vec<&str> = rendered["remarks"]
+        .as_array()
+        .expect("remarks")
+        .iter()
+        .map(|r| r["rendered"].as_str().expect("rendered"))
+        .collect();
+    assert_eq!(
+        texts,
+        vec![
+            "Ava revised her essay. She should be proud.",
+            "Ben revised his essay. He should be proud.",
+            "Ask them about Chen's {grade}.",
+        ]
+    );
+    assert_eq!(rendered["remarks"][0]["remark"], json!(template));
+    assert_eq!(
+        rendered["warnings"],
+        json!([{ "code": "unknown_merge_field", "studentId": ids[2], "placeholder": "{grade}" }])
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "comments.render",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "setNumber": 9 }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,153 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn comments_sets_reorder_renumbers_sets_and_keeps_remarks() {
+    let workspace = temp_dir("markbook-comments-sets-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Comments" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Roe", "firstName": "Ann" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    // Imported sets often have gaps, e.g. after the ALL! merge.
+    for (i, (set_number, title)) in [(1, "Midterm"), (4, "Final"), (7, "Extra")]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("set{i}"),
+            "comments.sets.upsert",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "setNumber": set_number,
+                "title": title,
+                "remarksByStudent": [{ "studentId": student_id, "remark": format!("{} remark", title) }]
+            }),
+        );
+    }
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.sets.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let ids: Vec<String> = listed["sets"]
+        .as_array()
+        .expect("sets")
+        .iter()
+        .map(|s| s["id"].as_str().expect("set id").to_string())
+        .collect();
+    assert_eq!(ids.len(), 3);
+
+    let short = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "comments.sets.reorder",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "orderedSetIds": [ids[0], ids[1]] }),
+    );
+    assert_eq!(short["error"]["code"], json!("bad_params"));
+    let duplicated = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "comments.sets.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedSetIds": [ids[0], ids[0], ids[1]]
+        }),
+    );
+    assert_eq!(duplicated["error"]["code"], json!("bad_params"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "comments.sets.reorder",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "orderedSetIds": [ids[2], ids[0], ids[1]]
+        }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "comments.sets.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let order: Vec<(i64, String)> = listed["sets"]
+        .as_array()
+        .expect("sets")
+        .iter()
+        .map(|s| {
+            (
+                s["setNumber"].as_i64().expect("setNumber"),
+                s["title"].as_str().expect("title").to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (1, "Extra".to_string()),
+            (2, "Midterm".to_string()),
+            (3, "Final".to_string())
+        ]
+    );
+
+    let opened = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "comments.sets.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "setNumber": 1 }),
+    );
+    assert_eq!(opened["set"]["id"], json!(ids[2]));
+    assert_eq!(
+        opened["remarksByStudent"][0]["remark"],
+        json!("Extra remark")
+    );
+}
@@ -0,0 +1,118 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn problem_kinds(result: &serde_json::Value) -> Vec<String> {
+    result["problems"]
+        .as_array()
+        .expect("problems")
+        .iter()
+        .map(|p| p["kind"].as_str().expect("kind").to_string())
+        .collect()
+}
+
+#[test]
+fn db_integrity_check_reports_orphaned_scores() {
+    let workspace = temp_dir("markbook-db-integrity-check");
+    let student_id = {
+        let (_child, mut stdin, mut reader) = spawn_sidecar();
+        let no_workspace = request(&mut stdin, &mut reader, "0", "db.integrityCheck", json!({}));
+        assert_eq!(no_workspace["error"]["code"], json!("no_workspace"));
+
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            "1",
+            "workspace.select",
+            json!({ "path": workspace.to_string_lossy() }),
+        );
+        let imported = request_ok(
+            &mut stdin,
+            &mut reader,
+            "2",
+            "class.importLegacy",
+            json!({
+                "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+            }),
+        );
+        let class_id = imported["classId"].as_str().expect("classId");
+
+        let clean = request_ok(
+            &mut stdin,
+            &mut reader,
+            "3",
+            "db.integrityCheck",
+            json!({ "includeOrphans": true }),
+        );
+        assert_eq!(clean, json!({ "ok": true, "problems": [] }));
+
+        let students = request_ok(
+            &mut stdin,
+            &mut reader,
+            "4",
+            "students.list",
+            json!({ "classId": class_id }),
+        );
+        students["students"][0]["id"]
+            .as_str()
+            .expect("student id")
+            .to_string()
+    };
+
+    // Simulate a bad bundle import: drop a student behind the app's back.
+    let orphaned_scores: i64 = {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute("PRAGMA foreign_keys = OFF", [])
+            .expect("disable foreign keys");
+        let n = conn
+            .query_row(
+                "SELECT COUNT(*) FROM scores WHERE student_id = ?",
+                [&student_id],
+                |r| r.get(0),
+            )
+            .expect("count scores");
+        conn.execute("DELETE FROM students WHERE id = ?", [&student_id])
+            .expect("delete student");
+        n
+    };
+    assert!(orphaned_scores > 0);
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let without_orphans = request_ok(&mut stdin, &mut reader, "2", "db.integrityCheck", json!({}));
+    assert_eq!(without_orphans["ok"], json!(false));
+    let kinds = problem_kinds(&without_orphans);
+    assert!(kinds.iter().all(|k| k == "foreign_key"), "{:?}", kinds);
+    assert!(without_orphans["problems"]
+        .as_array()
+        .expect("problems")
+        .iter()
+        .any(|p| p["table"] == "scores" && p["parent"] == "students"));
+
+    let with_orphans = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "db.integrityCheck",
+        json!({ "includeOrphans": true }),
+    );
+    let orphans: Vec<&serde_json::Value> = with_orphans["problems"]
+        .as_array()
+        .expect("problems")
+        .iter()
+        .filter(|p| p["kind"] == "orphan_score")
+        .collect();
+    assert_eq!(orphans.len() as i64, orphaned_scores);
+    for orphan in orphans {
+        assert_eq!(orphan["studentId"], json!(student_id));
+        assert_eq!(orphan["missing"], json!(["student"]));
+    }
+}
@@ -213,6 +213,8 @@ fn v0_snapshot_migrates_and_supports_legacy_import() {
     assert!(table_has_column(&conn, "students", "sort_order"));
     assert!(table_has_column(&conn, "students", "updated_at"));
     assert!(table_has_column(&conn, "students", "mark_set_mask"));
+    assert!(table_has_column(&conn, "students", "archived"));
+    assert!(table_has_column(&conn, "students", "pronouns"));
     assert!(table_has_column(&conn, "scores", "remark"));
     assert!(table_has_column(&conn, "assessments", "legacy_type"));
     assert!(table_has_column(&conn, "mark_sets", "calc_method"));
@@ -0,0 +1,233 @@
+mod test_support;
+
+use rusqlite::Connection;
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn user_version(conn: &Connection) -> i64 {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+        .expect("user_version")
+}
+
+fn schema_object_exists(conn: &Connection, kind: &str, name: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = ? AND name = ?",
+        [kind, name],
+        |r| r.get::<_, i64>(0),
+    )
+    .expect("schema lookup")
+        > 0
+}
+
+fn index_exists(conn: &Connection, name: &str) -> bool {
+    schema_object_exists(conn, "index", name)
+}
+
+/// Creates a workspace with one class, student and mark set, and
+/// returns (schemaVersion, classId).
+fn seed_workspace(workspace: &std::path::Path) -> (i64, String) {
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let health = request_ok(&mut stdin, &mut reader, "2", "health", json!({}));
+    let version = health["schemaVersion"].as_i64().expect("schemaVersion");
+
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.create",
+        json!({ "name": "Migrations" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    );
+    (version, class_id)
+}
+
+#[test]
+fn health_reports_schema_version_only_with_workspace() {
+    let workspace = temp_dir("markbook-schema-version-health");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let before = request_ok(&mut stdin, &mut reader, "1", "health", json!({}));
+    assert_eq!(before["schemaVersion"], json!(null));
+    assert_eq!(before["dbOpen"], json!(false));
+    assert_eq!(before["dbSizeBytes"], json!(null));
+    assert_eq!(before["walSizeBytes"], json!(null));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let after = request_ok(&mut stdin, &mut reader, "3", "health", json!({}));
+    let version = after["schemaVersion"].as_i64().expect("schemaVersion");
+    assert!(version >= 1, "{}", version);
+    assert_eq!(after["dbOpen"], json!(true));
+    assert_eq!(after["version"], json!(env!("CARGO_PKG_VERSION")));
+    assert_eq!(after["workspacePath"], json!(workspace.to_string_lossy()));
+
+    let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    assert_eq!(user_version(&conn), version);
+    assert!(index_exists(&conn, "idx_mark_sets_class_code"));
+    let db_len = std::fs::metadata(workspace.join("markbook.sqlite3"))
+        .expect("db metadata")
+        .len();
+    assert_eq!(after["dbSizeBytes"], json!(db_len));
+}
+
+#[test]
+fn previous_schema_version_migrates_without_data_loss() {
+    let workspace = temp_dir("markbook-schema-version-upgrade");
+    let (version, class_id) = seed_workspace(&workspace);
+
+    // Roll the workspace back to v0 as an older build would have left it:
+    // no mark set code index, undo log or score history yet.
+    {
+        let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute("DROP INDEX idx_mark_sets_class_code", [])
+            .expect("drop index");
+        conn.execute("DROP TABLE edit_log", [])
+            .expect("drop edit_log");
+        conn.execute("DROP TABLE score_history", [])
+            .expect("drop score_history");
+        conn.pragma_update(None, "user_version", 0)
+            .expect("set user_version");
+    }
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let health = request_ok(&mut stdin, &mut reader, "2", "health", json!({}));
+    assert_eq!(health["schemaVersion"], json!(version));
+
+    let classes = request_ok(&mut stdin, &mut reader, "3", "classes.list", json!({}));
+    assert_eq!(classes["classes"][0]["id"], json!(class_id));
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(students["students"][0]["lastName"], json!("Adams"));
+    let mark_sets = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(mark_sets["markSets"][0]["code"], json!("MAT"));
+
+    let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    assert_eq!(user_version(&conn), version);
+    assert!(index_exists(&conn, "idx_mark_sets_class_code"));
+    assert!(schema_object_exists(&conn, "table", "edit_log"));
+    assert!(schema_object_exists(&conn, "table", "score_history"));
+}
+
+#[test]
+fn legacy_score_statuses_are_normalized_on_every_open() {
+    let workspace = temp_dir("markbook-schema-version-score-status");
+    let (version, class_id) = seed_workspace(&workspace);
+
+    {
+        let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        let student_id: String = conn
+            .query_row(
+                "SELECT id FROM students WHERE class_id = ?",
+                [&class_id],
+                |r| r.get(0),
+            )
+            .expect("student");
+        let mark_set_id: String = conn
+            .query_row(
+                "SELECT id FROM mark_sets WHERE class_id = ?",
+                [&class_id],
+                |r| r.get(0),
+            )
+            .expect("mark set");
+        conn.execute(
+            "INSERT INTO assessments(id, mark_set_id, idx, title, out_of) VALUES('a1', ?, 0, 'Quiz', 10)",
+            [&mark_set_id],
+        )
+        .expect("insert assessment");
+        conn.execute(
+            "INSERT INTO scores(id, assessment_id, student_id, raw_value, status) VALUES('s1', 'a1', ?, NULL, 'missing')",
+            [&student_id],
+        )
+        .expect("insert score");
+        // Not a versioned step: the rewrite runs on every open, as it did before.
+        assert_eq!(user_version(&conn), version);
+    }
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let health = request_ok(&mut stdin, &mut reader, "2", "health", json!({}));
+    assert_eq!(health["schemaVersion"], json!(version));
+
+    let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let status: String = conn
+        .query_row("SELECT status FROM scores WHERE id = 's1'", [], |r| {
+            r.get(0)
+        })
+        .expect("score status");
+    assert_eq!(status, "zero");
+}
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    let workspace = temp_dir("markbook-schema-version-newer");
+    let (version, _class_id) = seed_workspace(&workspace);
+    {
+        let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.pragma_update(None, "user_version", version + 1)
+            .expect("set user_version");
+    }
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let resp = request(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    assert_eq!(resp["ok"], json!(false), "{:?}", resp);
+    let health = request_ok(&mut stdin, &mut reader, "2", "health", json!({}));
+    assert_eq!(health["workspacePath"], json!(null));
+}
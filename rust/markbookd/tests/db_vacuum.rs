@@ -0,0 +1,62 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn db_vacuum_reclaims_space_after_class_delete() {
+    let workspace = temp_dir("markbook-db-vacuum");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let no_workspace = request(&mut stdin, &mut reader, "0", "db.vacuum", json!({}));
+    assert_eq!(no_workspace["error"]["code"], json!("no_workspace"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let legacy_folder = fixture_path("fixtures/legacy/Sample25/MB8D25");
+    let kept = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    let dropped = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "classes.delete",
+        json!({ "classId": dropped["classId"] }),
+    );
+
+    let vacuumed = request_ok(&mut stdin, &mut reader, "5", "db.vacuum", json!({}));
+    assert_eq!(vacuumed["ok"], json!(true));
+    let before = vacuumed["sizeBefore"].as_u64().expect("sizeBefore");
+    let after = vacuumed["sizeAfter"].as_u64().expect("sizeAfter");
+    assert!(after > 0);
+    assert!(after < before, "{} -> {}", before, after);
+    assert_eq!(vacuumed["bytesReclaimed"], json!(before - after));
+    let on_disk = std::fs::metadata(workspace.join("markbook.sqlite3"))
+        .expect("db metadata")
+        .len();
+    assert_eq!(on_disk, after);
+
+    // The connection stays usable and data survives the rebuild.
+    let classes = request_ok(&mut stdin, &mut reader, "6", "classes.list", json!({}));
+    assert_eq!(classes["classes"].as_array().map(|a| a.len()), Some(1));
+    assert_eq!(classes["classes"][0]["id"], kept["classId"]);
+    let integrity = request_ok(&mut stdin, &mut reader, "7", "db.integrityCheck", json!({}));
+    assert_eq!(integrity["ok"], json!(true));
+}
@@ -0,0 +1,133 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn devices_create_and_delete_one_device_per_student() {
+    let workspace = temp_dir("markbook-devices-create-delete");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut class_ids = Vec::new();
+    for name in ["Devices", "Other"] {
+        let class_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            "c",
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        class_ids.push(class_id);
+    }
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "students.create",
+        json!({ "classId": class_ids[0], "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "devices.create",
+        json!({
+            "classId": class_ids[0],
+            "studentId": student_id,
+            "deviceCode": " CB-17 ",
+            "rawLine": "CB-17,Chromebook"
+        }),
+    );
+    assert!(created["deviceId"].as_str().is_some());
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "devices.get",
+        json!({ "classId": class_ids[0], "studentId": student_id }),
+    );
+    assert_eq!(got["device"]["deviceCode"], json!("CB-17"));
+    assert_eq!(got["device"]["rawLine"], json!("CB-17,Chromebook"));
+
+    let again = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "devices.create",
+        json!({ "classId": class_ids[0], "studentId": student_id, "deviceCode": "CB-18" }),
+    );
+    assert_eq!(again["error"]["code"], json!("duplicate_device"));
+    assert_eq!(again["error"]["details"]["studentId"], json!(student_id));
+
+    let blank = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "devices.create",
+        json!({ "classId": class_ids[0], "studentId": student_id, "deviceCode": "  " }),
+    );
+    assert_eq!(blank["error"]["code"], json!("bad_params"));
+    let wrong_class = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "devices.create",
+        json!({ "classId": class_ids[1], "studentId": student_id, "deviceCode": "CB-19" }),
+    );
+    assert_eq!(wrong_class["error"]["code"], json!("not_found"));
+
+    // Deletes are scoped to the class.
+    let other_class = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "devices.delete",
+        json!({ "classId": class_ids[1], "studentId": student_id }),
+    );
+    assert_eq!(other_class["error"]["code"], json!("not_found"));
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "devices.delete",
+        json!({ "classId": class_ids[0], "studentId": student_id }),
+    );
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "devices.get",
+        json!({ "classId": class_ids[0], "studentId": student_id }),
+    );
+    assert_eq!(got["device"]["deviceCode"], json!(""));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "devices.delete",
+        json!({ "classId": class_ids[0], "studentId": student_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "devices.create",
+        json!({ "classId": class_ids[0], "studentId": student_id, "deviceCode": "CB-18" }),
+    );
+}
@@ -0,0 +1,385 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::BufReader;
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn score_of(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    class_id: &str,
+    mark_set_id: &str,
+    student_id: &str,
+) -> Option<serde_json::Value> {
+    let scores = request_ok(
+        stdin,
+        reader,
+        "scores",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    scores["scores"]
+        .as_array()
+        .expect("scores")
+        .iter()
+        .find(|s| s["studentId"] == json!(student_id))
+        .map(|s| json!({ "rawValue": s["rawValue"], "status": s["status"] }))
+}
+
+#[test]
+fn edits_undo_reverts_score_and_roster_edits() {
+    let workspace = temp_dir("markbook-edits-undo");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Undo" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben", "Cal"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Roe", "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Test", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let empty = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(empty["error"]["code"], json!("not_found"));
+
+    // setCell over an empty cell, then over its own value.
+    for (i, raw) in [7.0, 9.0].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("set{i}"),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_ids[0],
+                "rawValue": raw
+            }),
+        );
+    }
+    let undone = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(undone["undone"]["op"], json!("scores.setCell"));
+    assert_eq!(undone["restored"], json!(1));
+    assert_eq!(
+        score_of(
+            &mut stdin,
+            &mut reader,
+            &class_id,
+            &mark_set_id,
+            &student_ids[0]
+        ),
+        Some(json!({ "rawValue": 7.0, "status": "scored" }))
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        score_of(
+            &mut stdin,
+            &mut reader,
+            &class_id,
+            &mark_set_id,
+            &student_ids[0]
+        ),
+        None
+    );
+
+    // A paste touching the same cell twice is one undo step back to the start.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "scores.setCell",
+        json!({
+            "classId": class_id,
+            "assessmentId": assessment_id,
+            "studentId": student_ids[1],
+            "rawValue": 4
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "scores.bulkSet",
+        json!({
+            "classId": class_id,
+            "edits": [
+                { "assessmentId": assessment_id, "studentId": student_ids[1], "rawValue": 5 },
+                { "assessmentId": assessment_id, "studentId": student_ids[1], "status": "zero" },
+                { "assessmentId": assessment_id, "studentId": student_ids[2], "rawValue": 6 }
+            ]
+        }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "edits.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(listed["entries"][0]["op"], json!("scores.bulkSet"));
+    assert_eq!(listed["entries"][0]["summary"], json!({ "cells": 3 }));
+    assert_eq!(listed["entries"][1]["op"], json!("scores.setCell"));
+    let undone = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(undone["restored"], json!(3));
+    assert_eq!(
+        score_of(
+            &mut stdin,
+            &mut reader,
+            &class_id,
+            &mark_set_id,
+            &student_ids[1]
+        ),
+        Some(json!({ "rawValue": 4.0, "status": "scored" }))
+    );
+    assert_eq!(
+        score_of(
+            &mut stdin,
+            &mut reader,
+            &class_id,
+            &mark_set_id,
+            &student_ids[2]
+        ),
+        None
+    );
+
+    // Deleting a student logs their rows; undo restores them in place.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "students.delete",
+        json!({ "classId": class_id, "studentId": student_ids[1] }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "edits.list",
+        json!({ "classId": class_id, "limit": 1 }),
+    );
+    assert_eq!(listed["entries"].as_array().map(|a| a.len()), Some(1));
+    assert_eq!(
+        listed["entries"][0]["summary"],
+        json!({ "studentId": student_ids[1], "displayName": "Roe, Ben" })
+    );
+    let undone = request_ok(
+        &mut stdin,
+        &mut reader,
+        "14",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(undone["undone"]["op"], json!("students.delete"));
+    // The student, their one score and that cell's five history entries.
+    assert_eq!(undone["restored"], json!(7));
+    assert_eq!(undone["skipped"], json!(0));
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "15",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let order: Vec<&str> = students["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| s["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(
+        order,
+        student_ids.iter().map(|s| s.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        score_of(
+            &mut stdin,
+            &mut reader,
+            &class_id,
+            &mark_set_id,
+            &student_ids[1]
+        ),
+        Some(json!({ "rawValue": 4.0, "status": "scored" }))
+    );
+
+    let bad_limit = request(
+        &mut stdin,
+        &mut reader,
+        "16",
+        "edits.list",
+        json!({ "classId": class_id, "limit": 0 }),
+    );
+    assert_eq!(bad_limit["error"]["code"], json!("bad_params"));
+}
+
+#[test]
+fn edit_log_keeps_only_recent_entries_per_class() {
+    let workspace = temp_dir("markbook-edits-bounded");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Bounded" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Roe", "firstName": "Ann" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "outOf": 300 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    for i in 0..210 {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("set{i}"),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": i
+            }),
+        );
+    }
+
+    let logged: i64 = rusqlite::Connection::open(workspace.join("markbook.sqlite3"))
+        .expect("open db")
+        .query_row(
+            "SELECT COUNT(*) FROM edit_log WHERE class_id = ?",
+            [&class_id],
+            |r| r.get(0),
+        )
+        .expect("count edit_log");
+    assert_eq!(logged, 200);
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "edits.list",
+        json!({ "classId": class_id, "limit": 200 }),
+    );
+    assert_eq!(listed["entries"].as_array().map(|a| a.len()), Some(200));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "classes.delete",
+        json!({ "classId": class_id }),
+    );
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "edits.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(listed["entries"], json!([]));
+}
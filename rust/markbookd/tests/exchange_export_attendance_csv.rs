@@ -0,0 +1,109 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn export_attendance_csv_writes_school_days_only() {
+    let workspace = temp_dir("markbook-export-attendance-csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "attendance.setTypeOfDay",
+        json!({ "classId": class_id, "month": "9", "day": 2, "code": "H" }),
+    );
+    for (i, (month, day, code)) in [("9", 1, "P"), ("9", 3, "A"), ("10", 1, "L")]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("d{}", i),
+            "attendance.setStudentDay",
+            json!({
+                "classId": class_id,
+                "month": month,
+                "studentId": student_id,
+                "day": day,
+                "code": code
+            }),
+        );
+    }
+
+    let out_path = workspace.join("exports").join("attendance.csv");
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.exportAttendanceCsv",
+        json!({
+            "classId": class_id,
+            "outPath": out_path.to_string_lossy(),
+            "monthFrom": "9",
+            "monthTo": "9"
+        }),
+    );
+    // September has 30 days; the holiday on day 2 is skipped.
+    assert_eq!(res["rowsExported"].as_u64(), Some(29));
+
+    let text = std::fs::read_to_string(&out_path).expect("read export");
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "student_id,student_name,month,day,code");
+    assert_eq!(lines.len(), 30);
+    assert!(lines[1].starts_with(&format!("{},", student_id)));
+    assert!(lines[1].ends_with(",9,1,P"));
+    assert!(lines[2].ends_with(",9,3,A"));
+    assert!(lines[1..]
+        .iter()
+        .all(|l| l.split(',').rev().nth(2) == Some("9")));
+
+    let full = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "exchange.exportAttendanceCsv",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(full["rowsExported"].as_u64(), Some(29 + 31));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "exchange.exportAttendanceCsv",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("bad_params"));
+}
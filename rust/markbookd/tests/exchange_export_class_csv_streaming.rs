@@ -0,0 +1,164 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+fn csv_quote(s: &str) -> String {
+    if s.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// The CSV as the exporter used to build it: whole file in one `String`.
+fn in_memory_class_csv(conn: &rusqlite::Connection, class_id: &str) -> String {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.last_name, s.first_name, ms.code, a.idx, a.title, sc.status, sc.raw_value
+             FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             JOIN students s ON s.id = sc.student_id
+             WHERE s.class_id = ?
+             ORDER BY s.sort_order, ms.sort_order, a.idx",
+        )
+        .expect("prepare");
+    let rows = stmt
+        .query_map([class_id], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, i64>(4)?,
+                r.get::<_, String>(5)?,
+                r.get::<_, String>(6)?,
+                r.get::<_, Option<f64>>(7)?,
+            ))
+        })
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .expect("rows");
+    let mut csv = String::from(
+        "student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\n",
+    );
+    for (student_id, last, first, mark_set_code, assessment_idx, title, status, raw_value) in rows {
+        let display_name = format!("{}, {}", last, first);
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_quote(&student_id),
+            csv_quote(&display_name),
+            csv_quote(&mark_set_code),
+            assessment_idx,
+            csv_quote(&title),
+            csv_quote(&status),
+            raw_value.map(|v| v.to_string()).unwrap_or_default()
+        ));
+    }
+    csv
+}
+
+#[test]
+fn export_class_csv_streams_thousands_of_rows_unchanged() {
+    let workspace = temp_dir("markbook-export-class-csv-streaming");
+    let out = workspace.join("exports").join("class.csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Large" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+
+    // 80 students x 50 assessments, with names and titles that need quoting.
+    let (students, assessments) = (80, 50);
+    {
+        let mut conn =
+            rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        let tx = conn.transaction().expect("tx");
+        for a in 0..assessments {
+            tx.execute(
+                "INSERT INTO assessments(id, mark_set_id, idx, title, out_of) VALUES(?, ?, ?, ?, 10)",
+                (
+                    format!("a{}", a),
+                    &mark_set_id,
+                    a,
+                    format!("Quiz \"{}\", part {}", a, a % 3),
+                ),
+            )
+            .expect("insert assessment");
+        }
+        for s in 0..students {
+            let student_id = format!("s{}", s);
+            tx.execute(
+                "INSERT INTO students(id, class_id, last_name, first_name, active, sort_order, raw_line)
+                 VALUES(?, ?, ?, ?, 1, ?, '')",
+                (&student_id, &class_id, format!("O'Neil-{}", s), format!("Pat\n{}", s), s),
+            )
+            .expect("insert student");
+            for a in 0..assessments {
+                let (raw_value, status) = match (s + a) % 4 {
+                    0 => (None, "no_mark"),
+                    1 => (Some(0.0), "zero"),
+                    _ => (Some((s * a) as f64 / 7.0), "scored"),
+                };
+                tx.execute(
+                    "INSERT INTO scores(id, assessment_id, student_id, raw_value, status) VALUES(?, ?, ?, ?, ?)",
+                    (
+                        format!("sc{}-{}", s, a),
+                        format!("a{}", a),
+                        &student_id,
+                        raw_value,
+                        status,
+                    ),
+                )
+                .expect("insert score");
+            }
+        }
+        tx.commit().expect("commit");
+    }
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.exportClassCsv",
+        json!({ "classId": class_id, "outPath": out.to_string_lossy() }),
+    );
+    assert_eq!(
+        exported,
+        json!({
+            "ok": true,
+            "rowsExported": students * assessments,
+            "path": out.to_string_lossy(),
+            "layout": "long"
+        })
+    );
+
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let expected = in_memory_class_csv(&conn, &class_id);
+    let actual = std::fs::read(&out).expect("read csv");
+    assert_eq!(actual, expected.into_bytes());
+}
@@ -0,0 +1,145 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn export_class_csv_wide_writes_a_student_by_assessment_grid() {
+    let workspace = temp_dir("markbook-export-class-csv-wide");
+    let out = workspace.join("exports").join("grid.csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Grid" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Adams", "firstName": "Ann" },
+                { "lastName": "Baker", "firstName": "Ben" }
+            ]
+        }),
+    );
+    let ids: Vec<String> = created["createdStudentIds"]
+        .as_array()
+        .expect("createdStudentIds")
+        .iter()
+        .map(|v| v.as_str().expect("id").to_string())
+        .collect();
+
+    let mut assessment_ids = Vec::new();
+    for (code, titles) in [("MAT", vec!["Quiz", "Test"]), ("SCI", vec!["Lab"])] {
+        let mark_set_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("markset-{}", code),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        )["markSetId"]
+            .as_str()
+            .expect("markSetId")
+            .to_string();
+        for title in titles {
+            let id = request_ok(
+                &mut stdin,
+                &mut reader,
+                &format!("assessment-{}-{}", code, title),
+                "assessments.create",
+                json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+            )["assessmentId"]
+                .as_str()
+                .expect("assessmentId")
+                .to_string();
+            assessment_ids.push(id);
+        }
+    }
+
+    // Baker's lab is never entered at all.
+    for (i, (student, assessment, raw, status)) in [
+        (0, 0, json!(8.5), "scored"),
+        (0, 1, json!(null), "zero"),
+        (0, 2, json!(null), "no_mark"),
+        (1, 0, json!(null), "no_mark"),
+        (1, 1, json!(7), "scored"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("score-{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_ids[assessment],
+                "studentId": ids[student],
+                "rawValue": raw,
+                "status": status
+            }),
+        );
+    }
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.exportClassCsv",
+        json!({ "classId": class_id, "outPath": out.to_string_lossy(), "layout": "wide" }),
+    );
+    assert_eq!(
+        exported,
+        json!({
+            "ok": true,
+            "rowsExported": 2,
+            "path": out.to_string_lossy(),
+            "layout": "wide"
+        })
+    );
+    let csv = std::fs::read_to_string(&out).expect("read csv");
+    assert_eq!(
+        csv,
+        format!(
+            "student_id,student_name,MAT/0,MAT/1,SCI/0\n{},\"Adams, Ann\",8.5,0,\n{},\"Baker, Ben\",,7,\n",
+            ids[0], ids[1]
+        )
+    );
+
+    // The long layout stays the default.
+    let long = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.exportClassCsv",
+        json!({ "classId": class_id, "outPath": out.to_string_lossy() }),
+    );
+    assert_eq!(long["layout"], json!("long"));
+    assert_eq!(long["rowsExported"], json!(5));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "exchange.exportClassCsv",
+        json!({ "classId": class_id, "outPath": out.to_string_lossy(), "layout": "tall" }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,111 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn export_class_json_writes_nested_document() {
+    let workspace = temp_dir("markbook-export-class-json");
+    let out_path = workspace.join("exports").join("class.json");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "exchange.exportClassJson",
+        json!({ "classId": "nope", "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+    let no_path = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.exportClassJson",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(no_path["error"]["code"], json!("bad_params"));
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.exportClassJson",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    let text = std::fs::read_to_string(&out_path).expect("read export");
+    assert_eq!(exported["bytes"].as_u64(), Some(text.len() as u64));
+    assert_eq!(exported["path"], json!(out_path.to_string_lossy()));
+
+    let doc: serde_json::Value = serde_json::from_str(&text).expect("parse export");
+    assert_eq!(doc["format"], json!("markbook-class-json"));
+    assert_eq!(doc["schemaVersion"], json!(1));
+    assert_eq!(doc["class"]["id"], json!(class_id));
+
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let count = |sql: &str| -> usize {
+        conn.query_row(sql, [&class_id], |r| r.get::<_, i64>(0))
+            .expect("count") as usize
+    };
+
+    let students = doc["students"].as_array().expect("students");
+    assert_eq!(
+        students.len(),
+        count("SELECT COUNT(*) FROM students WHERE class_id = ?")
+    );
+    assert!(students[0]["active"].is_boolean());
+    assert!(students[0]["sortOrder"].is_i64());
+
+    let mark_sets = doc["markSets"].as_array().expect("markSets");
+    assert!(!mark_sets.is_empty());
+    let mut score_total = 0;
+    for ms in mark_sets {
+        assert!(ms["categories"].is_array());
+        assert!(ms["commentSets"].is_array());
+        for a in ms["assessments"].as_array().expect("assessments") {
+            assert!(a["outOf"].is_number() || a["outOf"].is_null());
+            for s in a["scores"].as_array().expect("scores") {
+                assert!(s["status"].is_string());
+                assert!(s["rawValue"].is_number() || s["rawValue"].is_null());
+                assert!(s.get("assessmentId").is_none());
+                score_total += 1;
+            }
+        }
+    }
+    assert!(score_total > 0);
+    assert_eq!(
+        score_total,
+        count(
+            "SELECT COUNT(*) FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets ms ON ms.id = a.mark_set_id
+             WHERE ms.class_id = ?"
+        )
+    );
+
+    assert!(doc["attendance"]["months"].is_array());
+    assert!(doc["attendance"]["students"].is_array());
+    assert!(doc["seating"]["plans"].is_array());
+    assert_eq!(
+        doc["loanedItems"].as_array().expect("loanedItems").len(),
+        count("SELECT COUNT(*) FROM loaned_items WHERE class_id = ?")
+    );
+}
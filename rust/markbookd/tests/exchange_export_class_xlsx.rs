@@ -0,0 +1,131 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::Read;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn read_entry(path: &std::path::Path, name: &str) -> String {
+    let file = std::fs::File::open(path).expect("open xlsx");
+    let mut archive = zip::ZipArchive::new(file).expect("xlsx is a zip");
+    let mut text = String::new();
+    archive
+        .by_name(name)
+        .expect("entry")
+        .read_to_string(&mut text)
+        .expect("read entry");
+    text
+}
+
+#[test]
+fn export_class_xlsx_writes_one_sheet_per_mark_set() {
+    let workspace = temp_dir("markbook-export-class-xlsx");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Xlsx" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mut mark_set_ids = Vec::new();
+    for (i, code) in ["T1", "T2"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("m{}", i),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        )["markSetId"]
+            .as_str()
+            .expect("markSetId")
+            .to_string();
+        mark_set_ids.push(id);
+    }
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_ids[0], "title": "Lab & Report", "outOf": 20 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let edits = [
+        (0, json!(17.5), "scored"),
+        (1, json!(null), "zero"),
+        (2, json!(null), "no_mark"),
+    ];
+    for (i, (student, value, status)) in edits.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_ids[*student],
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+
+    let out_path = workspace.join("exports").join("class.xlsx");
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.exportClassXlsx",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(res["sheetCount"].as_u64(), Some(2));
+    assert_eq!(res["studentsExported"].as_u64(), Some(3));
+
+    let workbook = read_entry(&out_path, "xl/workbook.xml");
+    assert!(workbook.contains(r#"<sheet name="T1" sheetId="1" r:id="rId1"/>"#));
+    assert!(workbook.contains(r#"<sheet name="T2" sheetId="2" r:id="rId2"/>"#));
+
+    let sheet = read_entry(&out_path, "xl/worksheets/sheet1.xml");
+    assert!(sheet.contains("Lab &amp; Report"));
+    assert!(sheet.contains(r#"<c r="B2"><v>17.5</v></c>"#));
+    assert!(sheet.contains(r#"<c r="B3"><v>0</v></c>"#));
+    assert!(!sheet.contains(r#"r="B4""#));
+    assert!(sheet.contains("Chen, Pat"));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.exportClassXlsx",
+        json!({ "classId": "nope", "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,100 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn exchange_export_learning_skills_csv_writes_a_column_per_skill() {
+    let workspace = temp_dir("markbook-exchange-learning-skills-csv");
+    let out_all = workspace.join("exports").join("skills.csv");
+    let out_term = workspace.join("exports").join("skills-t1.csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Skills" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, (last, first)) in [("O\"Neil, Jr", "Ann"), ("Roe", "Ben")].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    for (i, (term, skill, value)) in [(1, "R", "G"), (1, "O", "E"), (2, "R", "S")]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("c{i}"),
+            "learningSkills.updateCell",
+            json!({
+                "classId": class_id,
+                "studentId": student_ids[0],
+                "term": term,
+                "skillCode": skill,
+                "value": value
+            }),
+        );
+    }
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "exchange.exportLearningSkillsCsv",
+        json!({ "classId": class_id, "outPath": out_all.to_string_lossy() }),
+    );
+    assert_eq!(all["rowsExported"], json!(2));
+    assert_eq!(all["path"], json!(out_all.to_string_lossy()));
+    let csv = std::fs::read_to_string(&out_all).expect("read csv");
+    assert_eq!(
+        csv,
+        format!(
+            "student_id,student_name,T1_O,T1_R,T2_R\n{},\"O\"\"Neil, Jr, Ann\",E,G,S\n{},\"Roe, Ben\",,,\n",
+            student_ids[0], student_ids[1]
+        )
+    );
+
+    let term = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.exportLearningSkillsCsv",
+        json!({ "classId": class_id, "outPath": out_term.to_string_lossy(), "term": 1 }),
+    );
+    assert_eq!(term["rowsExported"], json!(2));
+    let csv = std::fs::read_to_string(&out_term).expect("read term csv");
+    assert_eq!(csv.lines().next(), Some("student_id,student_name,O,R"));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.exportLearningSkillsCsv",
+        json!({ "classId": "nope", "outPath": out_all.to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
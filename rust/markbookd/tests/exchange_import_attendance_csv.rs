@@ -0,0 +1,205 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::BufReader;
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn day_codes(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    class_id: &str,
+    month: &str,
+) -> Vec<(String, String)> {
+    let opened = request_ok(
+        stdin,
+        reader,
+        "open",
+        "attendance.monthOpen",
+        json!({ "classId": class_id, "month": month }),
+    );
+    opened["rows"]
+        .as_array()
+        .expect("rows")
+        .iter()
+        .map(|s| {
+            (
+                s["studentId"].as_str().expect("studentId").to_string(),
+                s["dayCodes"].as_str().expect("dayCodes").to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn import_attendance_csv_round_trips_export_and_skips_bad_rows() {
+    let workspace = temp_dir("markbook-import-attendance-csv");
+    let csv_path = workspace.join("exports").join("attendance.csv");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut class_ids = Vec::new();
+    let mut student_ids = Vec::new();
+    for name in ["Source", "Target"] {
+        let class_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            "c",
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        let student_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            "s",
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        class_ids.push(class_id);
+        student_ids.push(student_id);
+    }
+    for (month, day, code) in [("9", 1, "P"), ("9", 3, "A"), ("10", 2, "L")] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            "d",
+            "attendance.setStudentDay",
+            json!({
+                "classId": class_ids[0],
+                "month": month,
+                "studentId": student_ids[0],
+                "day": day,
+                "code": code
+            }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "exchange.exportAttendanceCsv",
+        json!({ "classId": class_ids[0], "outPath": csv_path.to_string_lossy() }),
+    );
+
+    // Re-importing the export into its own class changes nothing.
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "exchange.importAttendanceCsv",
+        json!({ "classId": class_ids[0], "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(imported["updated"].as_u64(), Some(30 + 31));
+    assert_eq!(imported["skipped"].as_u64(), Some(0));
+    let september = day_codes(&mut stdin, &mut reader, &class_ids[0], "9");
+    assert_eq!(september[0].1, format!("P A{}", " ".repeat(27)));
+
+    // Another class's students are skipped; its months are created as needed.
+    let foreign = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.importAttendanceCsv",
+        json!({ "classId": class_ids[1], "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(foreign["updated"].as_u64(), Some(0));
+    assert_eq!(foreign["skipped"].as_u64(), Some(30 + 31));
+    assert_eq!(foreign["warnings"][0]["code"], json!("missing_student"));
+
+    std::fs::write(
+        &csv_path,
+        format!(
+            "student_id,month,day,code\n\
+             {sid},11,5,E\n\
+             {sid},13,1,A\n\
+             {sid},2,29,A\n\
+             {sid},11,x,A\n",
+            sid = student_ids[1]
+        ),
+    )
+    .expect("write csv");
+    let upserted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.importAttendanceCsv",
+        json!({ "classId": class_ids[1], "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(upserted["updated"].as_u64(), Some(1));
+    assert_eq!(upserted["skipped"].as_u64(), Some(3));
+    let codes: Vec<&str> = upserted["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .map(|w| w["code"].as_str().expect("code"))
+        .collect();
+    assert_eq!(codes, vec!["bad_month", "bad_day", "bad_day"]);
+    let november = day_codes(&mut stdin, &mut reader, &class_ids[1], "11");
+    assert_eq!(november[0].1, format!("    E{}", " ".repeat(25)));
+    let opened = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "attendance.monthOpen",
+        json!({ "classId": class_ids[1], "month": "11" }),
+    );
+    assert_eq!(opened["typeOfDayCodes"], json!(" ".repeat(30)));
+
+    // Replace clears the class's existing student attendance first.
+    std::fs::write(
+        &csv_path,
+        format!("student_id,month,day,code\n{},12,1,L\n", student_ids[1]),
+    )
+    .expect("write csv");
+    let replaced = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "exchange.importAttendanceCsv",
+        json!({
+            "classId": class_ids[1],
+            "inPath": csv_path.to_string_lossy(),
+            "mode": "replace"
+        }),
+    );
+    assert_eq!(replaced["updated"].as_u64(), Some(1));
+    let november = day_codes(&mut stdin, &mut reader, &class_ids[1], "11");
+    assert_eq!(november[0].1.trim(), "");
+    let december = day_codes(&mut stdin, &mut reader, &class_ids[1], "12");
+    assert!(december[0].1.starts_with('L'));
+
+    std::fs::write(&csv_path, "student_id,month,day\n").expect("write csv");
+    let bad_header = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "exchange.importAttendanceCsv",
+        json!({ "classId": class_ids[1], "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(bad_header["error"]["code"], json!("bad_params"));
+
+    let bad_mode = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "exchange.importAttendanceCsv",
+        json!({
+            "classId": class_ids[1],
+            "inPath": csv_path.to_string_lossy(),
+            "mode": "merge"
+        }),
+    );
+    assert_eq!(bad_mode["error"]["code"], json!("bad_params"));
+}
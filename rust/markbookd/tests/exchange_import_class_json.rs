@@ -0,0 +1,262 @@
+mod test_support;
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::Path;
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn export(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    class_id: &str,
+    out_path: &Path,
+) -> Value {
+    let _ = request_ok(
+        stdin,
+        reader,
+        "export",
+        "exchange.exportClassJson",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    serde_json::from_str(&std::fs::read_to_string(out_path).expect("read export"))
+        .expect("parse export")
+}
+
+/// Replaces workspace ids with stable placeholders so two exports of the
+/// same data compare equal.
+fn normalize(doc: &Value) -> Value {
+    let mut ids: HashMap<String, String> = HashMap::new();
+    for (i, s) in doc["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .enumerate()
+    {
+        ids.insert(s["id"].as_str().expect("id").to_string(), format!("S{}", i));
+    }
+    for (i, m) in doc["markSets"]
+        .as_array()
+        .expect("markSets")
+        .iter()
+        .enumerate()
+    {
+        ids.insert(m["id"].as_str().expect("id").to_string(), format!("M{}", i));
+    }
+    fn walk(v: &Value, ids: &HashMap<String, String>) -> Value {
+        match v {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(k, _)| k.as_str() != "id" && k.as_str() != "exportedAt")
+                    .map(|(k, v)| (k.clone(), walk(v, ids)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| walk(v, ids)).collect()),
+            Value::String(s) => Value::String(ids.get(s).cloned().unwrap_or_else(|| s.clone())),
+            other => other.clone(),
+        }
+    }
+    walk(doc, &ids)
+}
+
+#[test]
+fn import_class_json_round_trips_export() {
+    let workspace = temp_dir("markbook-import-class-json");
+    let first = workspace.join("exports").join("first.json");
+    let second = workspace.join("exports").join("second.json");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+    let original = export(&mut stdin, &mut reader, &class_id, &first);
+
+    let copy = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "exchange.importClassJson",
+        json!({ "inPath": first.to_string_lossy() }),
+    );
+    let copy_id = copy["classId"].as_str().expect("classId").to_string();
+    assert_ne!(copy_id, class_id);
+    assert_eq!(copy["merged"], json!(false));
+    assert_eq!(copy["warnings"], json!([]));
+    assert_eq!(
+        copy["counts"]["students"].as_u64(),
+        Some(original["students"].as_array().expect("students").len() as u64)
+    );
+    assert!(copy["counts"]["scores"].as_u64().expect("scores") > 0);
+
+    let round_tripped = export(&mut stdin, &mut reader, &copy_id, &second);
+    assert_eq!(normalize(&round_tripped), normalize(&original));
+}
+
+#[test]
+fn import_class_json_validates_version_and_merges_by_student_no() {
+    let workspace = temp_dir("markbook-import-class-json-merge");
+    let doc_path = workspace.join("class.json");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let write = |doc: &Value| {
+        std::fs::write(&doc_path, serde_json::to_string(doc).expect("serialize")).expect("write")
+    };
+
+    write(&json!({ "schemaVersion": 99, "class": { "name": "Future" } }));
+    let future = request(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "exchange.importClassJson",
+        json!({ "inPath": doc_path.to_string_lossy() }),
+    );
+    assert_eq!(future["error"]["code"], json!("unsupported_schema_version"));
+
+    write(&json!({
+        "schemaVersion": 1,
+        "class": { "name": "Science 9" },
+        "students": [
+            { "id": "a", "lastName": "Ng", "firstName": "Ava", "studentNo": "1001" },
+            { "id": "b", "lastName": "Oak", "firstName": "Ben", "studentNo": "1002" },
+            { "id": "c", "firstName": "Nameless" }
+        ],
+        "markSets": [{
+            "id": "m",
+            "code": "SCI",
+            "assessments": [{
+                "idx": 0,
+                "title": "Quiz",
+                "outOf": 10,
+                "scores": [
+                    { "studentId": "a", "rawValue": 8, "status": "scored" },
+                    { "studentId": "b", "rawValue": null, "status": "zero" },
+                    { "studentId": "c", "rawValue": 5, "status": "scored" }
+                ]
+            }]
+        }],
+        "loanedItems": [{ "studentId": "a", "markSetId": "m", "itemName": "Textbook", "quantity": 1 }]
+    }));
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "exchange.importClassJson",
+        json!({ "inPath": doc_path.to_string_lossy() }),
+    );
+    let class_id = created["classId"].as_str().expect("classId").to_string();
+    assert_eq!(created["className"], json!("Science 9"));
+    assert_eq!(created["counts"]["students"], json!(2));
+    assert_eq!(created["counts"]["scores"], json!(2));
+    assert_eq!(created["counts"]["loanedItems"], json!(1));
+    let codes: Vec<&str> = created["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .map(|w| w["code"].as_str().expect("code"))
+        .collect();
+    assert_eq!(codes, vec!["missing_name", "unknown_student"]);
+    assert_eq!(
+        created["warnings"][1]["path"],
+        json!("markSets[0].assessments[0].scores[2]")
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "exchange.importClassJson",
+        json!({ "inPath": doc_path.to_string_lossy(), "mergeIntoClassId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    write(&json!({
+        "schemaVersion": 1,
+        "class": { "name": "Other copy" },
+        "students": [
+            { "id": "x", "lastName": "Ng", "firstName": "Ava", "studentNo": "1001" },
+            { "id": "y", "lastName": "Park", "firstName": "Cy", "studentNo": "1003" }
+        ],
+        "markSets": [
+            {
+                "code": "SCI",
+                "assessments": [
+                    { "idx": 0, "scores": [
+                        { "studentId": "x", "rawValue": 9, "status": "scored" },
+                        { "studentId": "y", "rawValue": 6, "status": "scored" }
+                    ] },
+                    { "idx": 4, "scores": [] }
+                ]
+            },
+            { "code": "ART", "assessments": [] }
+        ],
+        "loanedItems": [{ "studentId": "x", "itemName": "Calculator" }]
+    }));
+    let merged = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "exchange.importClassJson",
+        json!({ "inPath": doc_path.to_string_lossy(), "mergeIntoClassId": class_id }),
+    );
+    assert_eq!(merged["classId"], json!(class_id));
+    assert_eq!(merged["className"], json!("Science 9"));
+    assert_eq!(merged["merged"], json!(true));
+    assert_eq!(merged["counts"]["students"], json!(1));
+    assert_eq!(merged["counts"]["studentsMatched"], json!(1));
+    assert_eq!(merged["counts"]["scores"], json!(2));
+    assert_eq!(merged["counts"]["markSets"], json!(0));
+    assert_eq!(merged["counts"]["loanedItems"], json!(0));
+    let codes: Vec<&str> = merged["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .map(|w| w["code"].as_str().expect("code"))
+        .collect();
+    assert_eq!(codes, vec!["missing_assessment", "missing_mark_set"]);
+
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let scores: Vec<(String, Option<f64>, String)> = conn
+        .prepare(
+            "SELECT s.last_name, sc.raw_value, sc.status
+             FROM scores sc
+             JOIN students s ON s.id = sc.student_id
+             WHERE s.class_id = ?
+             ORDER BY s.sort_order",
+        )
+        .expect("prepare")
+        .query_map([&class_id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .expect("query")
+        .map(|r| r.expect("row"))
+        .collect();
+    assert_eq!(
+        scores,
+        vec![
+            ("Ng".to_string(), Some(9.0), "scored".to_string()),
+            ("Oak".to_string(), None, "zero".to_string()),
+            ("Park".to_string(), Some(6.0), "scored".to_string()),
+        ]
+    );
+}
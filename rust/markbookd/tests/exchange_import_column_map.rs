@@ -0,0 +1,152 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn import_class_csv_reads_columns_by_mapped_header_names() {
+    let workspace = temp_dir("markbook-exchange-column-map");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Mapped" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Lab", "outOf": 20 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    // Another system's export: different names, different order, an extra column.
+    let csv_path = workspace.join("other-system.csv");
+    let csv = format!(
+        "Mark,Course,Student Number,Notes,Task #,State\r\n\
+         15,T1,{sid},late,0,scored\r\n",
+        sid = student_id
+    );
+    std::fs::write(&csv_path, csv).expect("write csv");
+    let column_map = json!({
+        "student number": "studentId",
+        "Course": "markSetCode",
+        "Task #": "assessmentIdx",
+        "State": "status",
+        "Mark": "rawValue"
+    });
+
+    let applied = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "exchange.importClassCsv",
+        json!({
+            "classId": class_id,
+            "inPath": csv_path.to_string_lossy(),
+            "columnMap": column_map
+        }),
+    );
+    assert_eq!(applied["updated"].as_u64(), Some(1));
+    assert_eq!(applied["warningsCount"].as_u64(), Some(0));
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(scores["scores"][0]["assessmentId"], json!(assessment_id));
+    assert_eq!(scores["scores"][0]["rawValue"], json!(15.0));
+
+    // Without a map the same file is read positionally and every row is rejected.
+    let positional = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "exchange.importClassCsv",
+        json!({
+            "classId": class_id,
+            "inPath": csv_path.to_string_lossy(),
+            "dryRun": true
+        }),
+    );
+    assert_eq!(positional["wouldUpdate"].as_u64(), Some(0));
+    assert_eq!(positional["warnings"][0]["code"], json!("bad_columns"));
+
+    let missing_header = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "exchange.importClassCsv",
+        json!({
+            "classId": class_id,
+            "inPath": csv_path.to_string_lossy(),
+            "columnMap": {
+                "Student ID": "studentId",
+                "Course": "markSetCode",
+                "Task #": "assessmentIdx",
+                "State": "status",
+                "Mark": "rawValue"
+            }
+        }),
+    );
+    assert_eq!(missing_header["error"]["code"], json!("bad_params"));
+    assert_eq!(
+        missing_header["error"]["details"],
+        json!({ "column": "Student ID", "field": "studentId" })
+    );
+
+    let unmapped_field = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "exchange.importClassCsv",
+        json!({
+            "classId": class_id,
+            "inPath": csv_path.to_string_lossy(),
+            "columnMap": { "Student Number": "studentId", "Mark": "rawValue" }
+        }),
+    );
+    assert_eq!(unmapped_field["error"]["code"], json!("bad_params"));
+    assert_eq!(
+        unmapped_field["error"]["details"]["missingFields"],
+        json!(["markSetCode", "assessmentIdx", "status"])
+    );
+}
@@ -0,0 +1,109 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn import_class_csv_dry_run_reports_counts_without_writing() {
+    let workspace = temp_dir("markbook-exchange-dry-run");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Dry Run" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Lab", "outOf": 20 }),
+    );
+
+    let csv_path = workspace.join("dry-run.csv");
+    let csv = format!(
+        "student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\n\
+         {sid},\"Adams, Pat\",T1,0,Lab,scored,17\n\
+         ghost,\"Ghost, Casper\",T1,0,Lab,scored,12\n\
+         ghost,\"Ghost, Casper\",T1,0,Lab,scored,13\n\
+         {sid},\"Adams, Pat\",T9,4,Missing,scored,10\n",
+        sid = student_id
+    );
+    std::fs::write(&csv_path, csv).expect("write csv");
+
+    let preview = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "exchange.importClassCsv",
+        json!({
+            "classId": class_id,
+            "inPath": csv_path.to_string_lossy(),
+            "dryRun": true
+        }),
+    );
+    assert_eq!(preview["dryRun"], json!(true));
+    assert_eq!(preview["wouldUpdate"].as_u64(), Some(1));
+    assert_eq!(preview["wouldSkip"].as_u64(), Some(3));
+    assert_eq!(preview["unmatchedStudents"], json!(["ghost"]));
+    assert_eq!(
+        preview["unmatchedAssessments"],
+        json!([{ "markSetCode": "T9", "assessmentIdx": 4 }])
+    );
+    let sample = preview["sampleRows"].as_array().expect("sampleRows");
+    assert_eq!(sample.len(), 4);
+    assert_eq!(sample[0]["action"], json!("update"));
+    assert_eq!(sample[1]["reason"], json!("missing_student"));
+
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(scores["scores"], json!([]));
+
+    let applied = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "exchange.importClassCsv",
+        json!({ "classId": class_id, "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(applied["updated"].as_u64(), Some(1));
+    assert!(applied.get("dryRun").is_none());
+}
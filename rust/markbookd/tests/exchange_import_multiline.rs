@@ -0,0 +1,95 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn import_class_csv_accepts_quoted_fields_spanning_lines() {
+    let workspace = temp_dir("markbook-exchange-multiline");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Multiline" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Lab", "outOf": 20 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let csv_path = workspace.join("multiline.csv");
+    let csv = format!(
+        "student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\r\n\
+         {sid},\"Adams, Pat\",T1,0,\"Lab\nwith notes\",scored,17\r\n",
+        sid = student_id
+    );
+    std::fs::write(&csv_path, csv).expect("write csv");
+
+    let applied = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "exchange.importClassCsv",
+        json!({ "classId": class_id, "inPath": csv_path.to_string_lossy() }),
+    );
+    assert_eq!(applied["updated"].as_u64(), Some(1));
+    assert_eq!(applied["rowsTotal"].as_u64(), Some(1));
+    assert_eq!(applied["warningsCount"].as_u64(), Some(0));
+
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(
+        scores["scores"],
+        json!([{
+            "assessmentId": assessment_id,
+            "studentId": student_id,
+            "rawValue": 17.0,
+            "status": "scored",
+            "remark": null
+        }])
+    );
+}
@@ -0,0 +1,145 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn grade_scales_roundtrip_and_letter_grades_use_upper_band_on_boundary() {
+    let workspace = temp_dir("markbook-grade-scales");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let empty = request_ok(&mut stdin, &mut reader, "2", "gradeScales.list", json!({}));
+    assert_eq!(empty, json!({ "bands": [] }));
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "gradeScales.upsert",
+        json!({ "bands": [
+            { "minPercent": 80, "label": "A" },
+            { "minPercent": 80, "label": "A-" }
+        ] }),
+    );
+    assert_eq!(dup["error"]["code"], json!("bad_params"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "gradeScales.upsert",
+        json!({ "bands": [
+            { "minPercent": 50, "label": "C" },
+            { "minPercent": 80, "label": "A" },
+            { "minPercent": 70, "label": "B" }
+        ] }),
+    );
+    let listed = request_ok(&mut stdin, &mut reader, "5", "gradeScales.list", json!({}));
+    assert_eq!(
+        listed,
+        json!({ "bands": [
+            { "minPercent": 80.0, "label": "A" },
+            { "minPercent": 70.0, "label": "B" },
+            { "minPercent": 50.0, "label": "C" }
+        ] })
+    );
+
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "classes.create",
+        json!({ "name": "Letters" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Diaz"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Test 1",
+            "categoryName": "Tests",
+            "outOf": 20
+        }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    // 80% (boundary), 75%, 40% (below every band); the last student has no mark.
+    for (i, value) in [16.0, 15.0, 8.0].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_ids[i],
+                "rawValue": value
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "calc.letterGrade",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(
+        res,
+        json!({ "grades": [
+            { "studentId": student_ids[0], "percent": 80.0, "letter": "A" },
+            { "studentId": student_ids[1], "percent": 75.0, "letter": "B" },
+            { "studentId": student_ids[2], "percent": 40.0, "letter": null },
+            { "studentId": student_ids[3], "percent": null, "letter": null }
+        ] })
+    );
+}
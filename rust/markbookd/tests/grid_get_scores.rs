@@ -0,0 +1,132 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn grid_get_scores_returns_existing_cells_in_grid_order() {
+    let workspace = temp_dir("markbook-grid-get-scores");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Scores" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut assessment_ids = Vec::new();
+    for (i, title) in ["Quiz 1", "Quiz 2"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+
+    // Write out of grid order; one cell stays empty.
+    let edits = [
+        (&student_ids[1], &assessment_ids[0], json!(7), "scored"),
+        (&student_ids[0], &assessment_ids[1], json!(null), "zero"),
+        (&student_ids[0], &assessment_ids[0], json!(9), "scored"),
+    ];
+    for (i, (student_id, assessment_id, value, status)) in edits.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let scores = res["scores"].as_array().expect("scores");
+    assert_eq!(scores.len(), 3);
+    assert_eq!(
+        scores[0],
+        json!({
+            "assessmentId": assessment_ids[0],
+            "studentId": student_ids[0],
+            "rawValue": 9.0,
+            "status": "scored",
+            "remark": null
+        })
+    );
+    assert_eq!(scores[1]["assessmentId"], json!(assessment_ids[1]));
+    assert_eq!(scores[1]["status"], json!("zero"));
+    assert_eq!(scores[2]["studentId"], json!(student_ids[1]));
+    assert_eq!(scores[2]["rawValue"], json!(7.0));
+
+    let other_class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let wrong = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "grid.getScores",
+        json!({ "classId": other_class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(wrong["ok"], json!(false));
+    assert_eq!(wrong["error"]["code"], json!("not_found"));
+}
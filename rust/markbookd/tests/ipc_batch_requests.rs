@@ -0,0 +1,79 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+fn send_raw(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    line: &str,
+) -> serde_json::Value {
+    writeln!(stdin, "{}", line).expect("write batch");
+    stdin.flush().expect("flush batch");
+    let mut out = String::new();
+    reader.read_line(&mut out).expect("read batch response");
+    serde_json::from_str(out.trim()).expect("parse batch response")
+}
+
+#[test]
+fn batch_line_returns_responses_in_order_without_aborting_on_failures() {
+    let workspace = temp_dir("markbook-ipc-batch");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let batch = json!([
+        { "id": "b1", "method": "classes.create", "params": { "name": "Batch A" } },
+        { "id": "b2", "method": "classes.create", "params": { "name": "" } },
+        { "id": "b3", "method": "nope" },
+        { "id": "b4", "params": {} },
+        { "id": "b5", "method": "classes.list", "params": {} }
+    ]);
+    let resp = send_raw(&mut stdin, &mut reader, &batch.to_string());
+    let items = resp.as_array().expect("batch response array");
+    assert_eq!(items.len(), 5);
+
+    let ids: Vec<&str> = items
+        .iter()
+        .map(|v| v.get("id").and_then(|x| x.as_str()).unwrap_or(""))
+        .collect();
+    assert_eq!(ids, vec!["b1", "b2", "b3", "b4", "b5"]);
+
+    assert_eq!(items[0].get("ok").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(
+        items[1].pointer("/error/code").and_then(|v| v.as_str()),
+        Some("bad_params")
+    );
+    assert_eq!(items[2].get("ok").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(
+        items[3].pointer("/error/code").and_then(|v| v.as_str()),
+        Some("bad_json")
+    );
+    let classes = items[4]
+        .pointer("/result/classes")
+        .and_then(|v| v.as_array())
+        .expect("classes");
+    assert_eq!(classes.len(), 1);
+
+    let empty = send_raw(&mut stdin, &mut reader, "[]");
+    assert_eq!(empty, json!([]));
+
+    let broken = send_raw(&mut stdin, &mut reader, "[{\"id\":\"x\",");
+    assert_eq!(broken.get("ok").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(
+        broken.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("bad_json")
+    );
+
+    // Single-request lines keep working after a batch.
+    let listed = request_ok(&mut stdin, &mut reader, "2", "classes.list", json!({}));
+    assert!(listed.get("classes").is_some());
+}
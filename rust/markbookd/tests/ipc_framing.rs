@@ -0,0 +1,144 @@
+mod test_support;
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use test_support::{spawn_sidecar, temp_dir};
+
+fn spawn_framed() -> (Child, ChildStdin, BufReader<ChildStdout>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markbookd"))
+        .args(["--framing", "length"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spawn markbookd");
+    let stdin = child.stdin.take().expect("child stdin");
+    let stdout = child.stdout.take().expect("child stdout");
+    (child, stdin, BufReader::new(stdout))
+}
+
+fn send_frame(stdin: &mut ChildStdin, reader: &mut BufReader<ChildStdout>, body: &[u8]) -> Value {
+    writeln!(stdin, "{}", body.len()).expect("write header");
+    // Split the payload across several writes, like a client flushing early.
+    for chunk in body.chunks(64 * 1024) {
+        stdin.write_all(chunk).expect("write chunk");
+        stdin.flush().expect("flush chunk");
+    }
+
+    let mut header = String::new();
+    reader.read_line(&mut header).expect("read header");
+    let len: usize = header.trim().parse().expect("frame length");
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).expect("read payload");
+    serde_json::from_slice(&payload).expect("parse response")
+}
+
+fn framed_ok(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    id: &str,
+    method: &str,
+    params: Value,
+) -> Value {
+    let body = json!({ "id": id, "method": method, "params": params }).to_string();
+    let resp = send_frame(stdin, reader, body.as_bytes());
+    assert_eq!(resp["id"], json!(id));
+    assert_eq!(resp["ok"], json!(true), "{} failed: {}", method, resp);
+    resp["result"].clone()
+}
+
+#[test]
+fn length_framing_round_trips_a_2mb_request() {
+    let workspace = temp_dir("markbook-ipc-framing");
+    let (_child, mut stdin, mut reader) = spawn_framed();
+
+    let _ = framed_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = framed_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Framed" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = framed_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Long", "firstName": "Note" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    // Pretty-printed, so the request itself spans many lines.
+    let note = "0123456789abcdef".repeat(2 * 1024 * 1024 / 16);
+    let body = serde_json::to_string_pretty(&json!({
+        "id": "4",
+        "method": "notes.update",
+        "params": { "classId": class_id, "studentId": student_id, "note": note },
+    }))
+    .expect("serialize");
+    assert!(body.len() > 2 * 1024 * 1024);
+    let updated = send_frame(&mut stdin, &mut reader, body.as_bytes());
+    assert_eq!(updated["ok"], json!(true), "{}", updated);
+
+    let fetched = framed_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "notes.getOne",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(fetched["note"].as_str().map(str::len), Some(note.len()));
+
+    let bad = send_frame(&mut stdin, &mut reader, b"{\"id\":\"6\",\"method\":");
+    assert_eq!(bad["error"]["code"], json!("bad_json"));
+    let _ = framed_ok(&mut stdin, &mut reader, "7", "health", json!({}));
+}
+
+#[test]
+fn newline_mode_survives_invalid_utf8_and_echoes_ids() {
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let mut read_line = |stdin: &mut ChildStdin, raw: &[u8]| -> Value {
+        stdin.write_all(raw).expect("write");
+        stdin.flush().expect("flush");
+        let mut line = String::new();
+        reader.read_line(&mut line).expect("read response");
+        serde_json::from_str(line.trim()).expect("parse response")
+    };
+
+    let invalid = read_line(&mut stdin, b"{\"id\":\"1\",\"method\":\"health\xff\"}\n");
+    assert_eq!(invalid["error"]["code"], json!("bad_json"));
+    assert!(invalid.get("id").is_none());
+
+    let no_method = read_line(&mut stdin, b"{\"id\":\"2\",\"params\":{}}\n");
+    assert_eq!(no_method["id"], json!("2"));
+    assert_eq!(no_method["error"]["code"], json!("bad_json"));
+
+    let health = read_line(&mut stdin, b"{\"id\":\"3\",\"method\":\"health\"}\n");
+    assert_eq!(health["id"], json!("3"));
+    assert_eq!(health["ok"], json!(true));
+}
+
+#[test]
+fn unknown_framing_arg_exits_with_an_error() {
+    let status = Command::new(env!("CARGO_BIN_EXE_markbookd"))
+        .args(["--framing", "xml"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("run markbookd");
+    assert!(!status.success());
+}
@@ -57,7 +57,7 @@ fn request(
             .and_then(|v| v.as_str())
             .unwrap_or("unknown");
         assert_ne!(
-            code, "not_implemented",
+            code, "unknown_method",
             "unexpected unknown method for {}",
             method
         );
@@ -0,0 +1,22 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, spawn_sidecar};
+
+#[test]
+fn unknown_method_returns_structured_error_with_method_details() {
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let resp = request(&mut stdin, &mut reader, "42", "nope.method", json!({}));
+    assert_eq!(resp.get("ok").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(resp.get("id").and_then(|v| v.as_str()), Some("42"));
+    assert_eq!(
+        resp.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("unknown_method")
+    );
+    assert_eq!(
+        resp.pointer("/error/details/method")
+            .and_then(|v| v.as_str()),
+        Some("nope.method")
+    );
+}
@@ -0,0 +1,125 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn learning_skills_bulk_set_stamps_active_students() {
+    let workspace = temp_dir("markbook-learning-skills-bulk-set");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Skills" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben", "Cal"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({
+                "classId": class_id,
+                "lastName": "Roe",
+                "firstName": first,
+                "active": i != 2
+            }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "learningSkills.updateCell",
+        json!({
+            "classId": class_id,
+            "studentId": student_ids[1],
+            "term": 2,
+            "skillCode": "R",
+            "value": "S"
+        }),
+    );
+
+    let values = |stdin: &mut _, reader: &mut _, id: &str| -> Vec<serde_json::Value> {
+        let open = request_ok(
+            stdin,
+            reader,
+            id,
+            "learningSkills.open",
+            json!({ "classId": class_id, "term": 2 }),
+        );
+        open["rows"]
+            .as_array()
+            .expect("rows")
+            .iter()
+            .map(|r| r["values"]["R"].clone())
+            .collect()
+    };
+
+    let only_empty = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "learningSkills.bulkSet",
+        json!({
+            "classId": class_id,
+            "term": 2,
+            "skillCode": "r",
+            "value": "E",
+            "onlyEmpty": true
+        }),
+    );
+    assert_eq!(only_empty["updated"], json!(1));
+    assert_eq!(
+        values(&mut stdin, &mut reader, "5"),
+        vec![json!("E"), json!("S"), json!("")]
+    );
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "learningSkills.bulkSet",
+        json!({ "classId": class_id, "term": 2, "skillCode": "R", "value": "G" }),
+    );
+    assert_eq!(all["updated"], json!(2));
+    assert_eq!(
+        values(&mut stdin, &mut reader, "7"),
+        vec![json!("G"), json!("G"), json!("")]
+    );
+
+    let blank = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "learningSkills.bulkSet",
+        json!({ "classId": class_id, "skillCode": "R", "value": " " }),
+    );
+    assert_eq!(blank["error"]["code"], json!("bad_params"));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "learningSkills.bulkSet",
+        json!({ "classId": "nope", "skillCode": "R", "value": "E" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,138 @@
+mod test_support;
+
+use rusqlite::Connection;
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+type StudentRow = (String, String, String, String, i64, String);
+type ScoreRow = (String, i64, i64, String, Option<f64>);
+
+fn class_snapshot(conn: &Connection, class_id: &str) -> (Vec<StudentRow>, Vec<ScoreRow>) {
+    let mut stmt = conn
+        .prepare(
+            "SELECT last_name, first_name, COALESCE(student_no, ''), COALESCE(birth_date, ''),
+                    active, COALESCE(mark_set_mask, '')
+             FROM students WHERE class_id = ? ORDER BY sort_order",
+        )
+        .expect("prepare students");
+    let students = stmt
+        .query_map([class_id], |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+            ))
+        })
+        .expect("query students")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("students");
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.code, a.idx, s.sort_order, sc.status, sc.raw_value
+             FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets m ON m.id = a.mark_set_id
+             JOIN students s ON s.id = sc.student_id
+             WHERE m.class_id = ?
+             ORDER BY m.sort_order, a.idx, s.sort_order",
+        )
+        .expect("prepare scores");
+    let scores = stmt
+        .query_map([class_id], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+        })
+        .expect("query scores")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("scores");
+    (students, scores)
+}
+
+#[test]
+fn legacy_export_class_round_trips_students_and_scores() {
+    let workspace = temp_dir("markbook-legacy-export-class");
+    let out_folder = workspace.join("export").join("MB8D25");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "legacy.exportClass",
+        json!({ "classId": class_id, "outFolder": out_folder.to_string_lossy() }),
+    );
+    let files: Vec<String> = exported["files"]
+        .as_array()
+        .expect("files")
+        .iter()
+        .map(|v| v.as_str().expect("path").to_string())
+        .collect();
+    let names: Vec<String> = files
+        .iter()
+        .map(|p| {
+            std::path::Path::new(p)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .expect("file name")
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names[0], "CL8D.Y25");
+    assert!(names.contains(&"MAT18D.Y25".to_string()), "{:?}", names);
+    assert_eq!(
+        names.len(),
+        1 + imported["markSetsImported"].as_u64().expect("count") as usize
+    );
+    assert!(files.iter().all(|p| std::path::Path::new(p).is_file()));
+
+    let reimported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": out_folder.to_string_lossy() }),
+    );
+    let reimported_id = reimported["classId"].as_str().expect("classId").to_string();
+    assert_eq!(reimported["name"], imported["name"]);
+    assert_eq!(reimported["studentsImported"], imported["studentsImported"]);
+    assert_eq!(reimported["scoresImported"], imported["scoresImported"]);
+
+    let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let original = class_snapshot(&conn, &class_id);
+    let round_tripped = class_snapshot(&conn, &reimported_id);
+    assert!(!original.1.is_empty());
+    assert!(original.1.iter().any(|s| s.3 == "zero"));
+    assert!(original.1.iter().any(|s| s.3 == "no_mark"));
+    assert_eq!(round_tripped.0, original.0);
+    assert_eq!(round_tripped.1, original.1);
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "legacy.exportClass",
+        json!({ "classId": "nope", "outFolder": out_folder.to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,88 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn student_names(
+    stdin: &mut std::process::ChildStdin,
+    reader: &mut std::io::BufReader<std::process::ChildStdout>,
+    id: &str,
+    class_id: &str,
+) -> Vec<String> {
+    let listed = request_ok(
+        stdin,
+        reader,
+        id,
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    listed["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| {
+            format!(
+                "{}, {}",
+                s["lastName"].as_str().expect("lastName"),
+                s["firstName"].as_str().expect("firstName")
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn class_import_legacy_decodes_dos_code_pages() {
+    let workspace = temp_dir("markbook-legacy-import-encoding");
+    let legacy_folder = fixture_path("fixtures/legacy/Encoding/MBCP25");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let auto = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(auto["name"], json!("9Z Français (2025)"));
+    let auto_id = auto["classId"].as_str().expect("classId").to_string();
+    assert_eq!(
+        student_names(&mut stdin, &mut reader, "3", &auto_id),
+        vec!["Müller, José", "Peña, Zoë", "S¢rensen, Åse"]
+    );
+
+    let cp850 = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "encoding": "cp850"
+        }),
+    );
+    let cp850_id = cp850["classId"].as_str().expect("classId").to_string();
+    assert_eq!(
+        student_names(&mut stdin, &mut reader, "5", &cp850_id),
+        vec!["Müller, José", "Peña, Zoë", "Sørensen, Åse"]
+    );
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "encoding": "latin-9"
+        }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
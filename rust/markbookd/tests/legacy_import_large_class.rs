@@ -0,0 +1,141 @@
+mod test_support;
+
+use rusqlite::Connection;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+const STUDENTS: usize = 30;
+const ASSESSMENTS: usize = 50;
+
+fn score_statuses(conn: &Connection, class_id: &str) -> Vec<(i64, i64, String)> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.idx, s.sort_order, sc.status
+             FROM scores sc
+             JOIN assessments a ON a.id = sc.assessment_id
+             JOIN mark_sets m ON m.id = a.mark_set_id
+             JOIN students s ON s.id = sc.student_id
+             WHERE m.class_id = ?
+             ORDER BY a.idx, s.sort_order",
+        )
+        .expect("prepare scores");
+    stmt.query_map([class_id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .expect("query scores")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("scores")
+}
+
+#[test]
+fn legacy_import_of_30_students_by_50_assessments() {
+    let workspace = temp_dir("markbook-legacy-import-large");
+    let out_folder = workspace.join("export").join("BIG");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Large" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    for s in 0..STUDENTS {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", s),
+            "students.create",
+            json!({
+                "classId": class_id,
+                "lastName": format!("Student{:02}", s),
+                "firstName": "Pat"
+            }),
+        );
+    }
+    for a in 0..ASSESSMENTS {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", a),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": format!("Quiz {}", a),
+                "outOf": 10
+            }),
+        );
+    }
+
+    {
+        let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.execute(
+            "INSERT INTO scores(id, assessment_id, student_id, raw_value, status)
+             SELECT a.id || '-' || s.id, a.id, s.id,
+                    CASE (a.idx + s.sort_order) % 3 WHEN 0 THEN NULL ELSE (a.idx + s.sort_order) % 10 + 1 END,
+                    CASE (a.idx + s.sort_order) % 3 WHEN 0 THEN 'zero' ELSE 'scored' END
+             FROM assessments a, students s
+             WHERE a.mark_set_id = ?1 AND s.class_id = ?2
+             ON CONFLICT(assessment_id, student_id) DO UPDATE SET
+               raw_value = excluded.raw_value,
+               status = excluded.status",
+            (&mark_set_id, &class_id),
+        )
+        .expect("seed scores");
+    }
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "legacy.exportClass",
+        json!({ "classId": class_id, "outFolder": out_folder.to_string_lossy() }),
+    );
+
+    let started = Instant::now();
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": out_folder.to_string_lossy() }),
+    );
+    let elapsed = started.elapsed();
+    eprintln!(
+        "imported {} scores in {:?}",
+        imported["scoresImported"], elapsed
+    );
+    assert_eq!(imported["studentsImported"], json!(STUDENTS));
+    assert_eq!(imported["assessmentsImported"], json!(ASSESSMENTS));
+    assert_eq!(imported["scoresImported"], json!(STUDENTS * ASSESSMENTS));
+    // Generous ceiling: catches a regression to per-cell SQL parsing on slow
+    // CI hosts without being flaky.
+    assert!(elapsed < Duration::from_secs(20), "{:?}", elapsed);
+
+    let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let reimported_id = imported["classId"].as_str().expect("classId");
+    assert_eq!(
+        score_statuses(&conn, reimported_id),
+        score_statuses(&conn, &class_id)
+    );
+}
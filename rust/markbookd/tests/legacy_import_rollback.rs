@@ -0,0 +1,62 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn malformed_typ_companion_rolls_back_the_whole_import() {
+    let workspace = temp_dir("markbook-legacy-import-rollback");
+    let legacy_folder = workspace.join("legacy").join("MB8D25");
+    std::fs::create_dir_all(&legacy_folder).expect("create legacy folder");
+    for name in ["CL8D.Y25", "MAT18D.Y25"] {
+        std::fs::copy(
+            fixture_path(&format!("fixtures/legacy/Sample25/MB8D25/{}", name)),
+            legacy_folder.join(name),
+        )
+        .expect("copy legacy file");
+    }
+    let typ_file = legacy_folder.join("MAT18D.TYP");
+    std::fs::write(&typ_file, "not a type file\r\n").expect("write malformed TYP");
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let failed = request(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(failed["ok"], json!(false));
+    assert_eq!(failed["error"]["code"], json!("legacy_parse_failed"));
+    assert_eq!(
+        failed["error"]["details"]["typFile"],
+        json!(typ_file.to_string_lossy())
+    );
+
+    // Students and the mark set were written before the .TYP failed; none of it
+    // may survive.
+    let classes = request_ok(&mut stdin, &mut reader, "3", "classes.list", json!({}));
+    assert_eq!(classes["classes"], json!([]));
+
+    // The connection is left usable: a clean retry opens a new transaction.
+    std::fs::remove_file(&typ_file).expect("remove malformed TYP");
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(imported["studentsImported"], json!(27));
+    assert_eq!(imported["markSetsImported"], json!(1));
+    let classes = request_ok(&mut stdin, &mut reader, "5", "classes.list", json!({}));
+    assert_eq!(classes["classes"].as_array().map(|a| a.len()), Some(1));
+}
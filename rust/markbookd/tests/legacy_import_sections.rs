@@ -0,0 +1,196 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn count(conn: &rusqlite::Connection, sql: &str, class_id: &str) -> i64 {
+    conn.query_row(sql, [class_id], |r| r.get(0))
+        .expect("count query")
+}
+
+#[test]
+fn legacy_import_sections_attach_to_existing_class() {
+    let workspace = temp_dir("markbook-legacy-import-sections");
+    let legacy_folder = fixture_path("fixtures/legacy/Sample25/MB8D25");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let full = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(
+        full["sectionsRun"],
+        json!([
+            "students",
+            "marks",
+            "attendance",
+            "seating",
+            "comments",
+            "loaned"
+        ])
+    );
+
+    let students_only = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "include": ["students"]
+        }),
+    );
+    let class_id = students_only["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    assert_eq!(students_only["sectionsRun"], json!(["students"]));
+    assert_eq!(students_only["studentsImported"], full["studentsImported"]);
+    assert_eq!(students_only["markSetsImported"], json!(0));
+    assert_eq!(students_only["seatingImported"], json!(false));
+    assert_eq!(students_only["commentSetsImported"], json!(0));
+    assert_eq!(students_only["loanedItemsImported"], json!(0));
+    assert_eq!(students_only["warnings"], json!([]));
+
+    let attached = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "classId": class_id,
+            "include": ["loaned", "comments", "seating", "marks"]
+        }),
+    );
+    assert_eq!(attached["classId"], json!(class_id));
+    assert_eq!(attached["name"], students_only["name"]);
+    assert_eq!(
+        attached["sectionsRun"],
+        json!(["marks", "seating", "comments", "loaned"])
+    );
+    assert_eq!(attached["studentsImported"], json!(0));
+    for key in [
+        "markSetsImported",
+        "assessmentsImported",
+        "scoresImported",
+        "seatingImported",
+        "commentSetsImported",
+        "commentRemarksImported",
+        "loanedItemsImported",
+        "deviceMappingsImported",
+        "combinedCommentSetsImported",
+        "importedMarkFiles",
+    ] {
+        assert_eq!(attached[key], full[key], "{}", key);
+    }
+
+    // Re-running sections on the attached class refreshes rather than duplicates.
+    let rerun = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "classId": class_id,
+            "include": ["marks", "seating", "loaned"]
+        }),
+    );
+    assert_eq!(rerun["markSetsImported"], json!(0));
+    assert_eq!(rerun["seatingImported"], json!(true));
+    assert_eq!(rerun["loanedItemsImported"], full["loanedItemsImported"]);
+    let exists: Vec<&serde_json::Value> = rerun["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .filter(|w| w["code"] == "legacy_mark_set_exists")
+        .collect();
+    assert_eq!(
+        exists.len(),
+        full["importedMarkFiles"].as_array().expect("files").len()
+    );
+
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM mark_sets WHERE class_id = ?",
+            &class_id
+        ),
+        full["markSetsImported"].as_i64().expect("markSetsImported")
+    );
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM seating_plans WHERE class_id = ?",
+            &class_id
+        ),
+        1
+    );
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM loaned_items WHERE class_id = ?",
+            &class_id
+        ),
+        full["loanedItemsImported"]
+            .as_i64()
+            .expect("loanedItemsImported")
+    );
+}
+
+#[test]
+fn legacy_import_sections_validate_params() {
+    let workspace = temp_dir("markbook-legacy-import-sections-params");
+    let legacy_folder = fixture_path("fixtures/legacy/Sample25/MB8D25");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let cases = [
+        (json!(["grades"]), None, "bad_params"),
+        (json!([]), None, "bad_params"),
+        (json!("marks"), None, "bad_params"),
+        (json!(["marks"]), None, "bad_params"),
+        (json!(["marks"]), Some("missing-class"), "not_found"),
+    ];
+    for (i, (include, class_id, code)) in cases.into_iter().enumerate() {
+        let mut params = json!({
+            "legacyClassFolderPath": legacy_folder.to_string_lossy(),
+            "include": include
+        });
+        if let Some(class_id) = class_id {
+            params["classId"] = json!(class_id);
+        }
+        let resp = request(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "class.importLegacy",
+            params,
+        );
+        assert_eq!(resp["ok"], json!(false), "{:?}", resp);
+        assert_eq!(resp["error"]["code"], json!(code), "{:?}", resp);
+    }
+
+    let classes = request_ok(&mut stdin, &mut reader, "2", "classes.list", json!({}));
+    assert_eq!(classes["classes"], json!([]));
+}
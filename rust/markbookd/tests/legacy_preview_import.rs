@@ -0,0 +1,114 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn warning_codes(result: &serde_json::Value) -> Vec<String> {
+    result["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .map(|w| w["code"].as_str().expect("code").to_string())
+        .collect()
+}
+
+#[test]
+fn legacy_preview_import_matches_import_counts_without_writing() {
+    let workspace = temp_dir("markbook-legacy-preview-import");
+    let legacy_folder = fixture_path("fixtures/legacy/Sample25/MB8D25");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let preview = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "legacy.previewImport",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    let classes = request_ok(&mut stdin, &mut reader, "3", "classes.list", json!({}));
+    assert_eq!(classes["classes"], json!([]));
+
+    let kinds: Vec<&str> = preview["companionFiles"]
+        .as_array()
+        .expect("companionFiles")
+        .iter()
+        .map(|f| f["kind"].as_str().expect("kind"))
+        .collect();
+    for kind in ["note", "seating", "icc", "allIdx", "bnk"] {
+        assert!(kinds.contains(&kind), "missing {} in {:?}", kind, kinds);
+    }
+
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "class.importLegacy",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(preview["name"], imported["name"]);
+    assert_eq!(preview["sourceClFile"], imported["sourceClFile"]);
+    assert_eq!(preview["studentsFound"], imported["studentsImported"]);
+    assert_eq!(preview["markSetsFound"], imported["markSetsImported"]);
+    assert_eq!(preview["assessmentsFound"], imported["assessmentsImported"]);
+    assert_eq!(preview["markFiles"], imported["importedMarkFiles"]);
+    assert_eq!(preview["missingMarkFiles"], imported["missingMarkFiles"]);
+    assert_eq!(preview["warnings"], imported["warnings"]);
+}
+
+#[test]
+fn legacy_preview_import_reports_missing_companions() {
+    let workspace = temp_dir("markbook-legacy-preview-missing");
+    let legacy_folder = workspace.join("legacy").join("MB8D25");
+    std::fs::create_dir_all(&legacy_folder).expect("create legacy folder");
+    std::fs::copy(
+        fixture_path("fixtures/legacy/Sample25/MB8D25/CL8D.Y25"),
+        legacy_folder.join("CL8D.Y25"),
+    )
+    .expect("copy CL file");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let preview = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "legacy.previewImport",
+        json!({ "legacyClassFolderPath": legacy_folder.to_string_lossy() }),
+    );
+    assert_eq!(preview["studentsFound"], json!(27));
+    assert_eq!(preview["markSetsFound"], json!(0));
+    assert_eq!(preview["assessmentsFound"], json!(0));
+    assert_eq!(
+        preview["missingMarkFiles"].as_array().map(|a| a.len()),
+        Some(6)
+    );
+    assert_eq!(preview["companionFiles"], json!([]));
+    assert_eq!(
+        warning_codes(&preview),
+        vec![
+            "legacy_missing_attendance_file",
+            "legacy_missing_seating_file",
+            "legacy_missing_icc_file",
+            "legacy_missing_tbk_file",
+            "legacy_missing_all_idx_file",
+        ]
+    );
+
+    let empty = workspace.join("empty");
+    std::fs::create_dir_all(&empty).expect("create empty folder");
+    let no_cl = request(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "legacy.previewImport",
+        json!({ "legacyClassFolderPath": empty.to_string_lossy() }),
+    );
+    assert_eq!(no_cl["error"]["code"], json!("legacy_no_cl"));
+}
@@ -0,0 +1,115 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn validate_folder_reports_each_class_folder_without_importing() {
+    let parent = temp_dir("markbook-legacy-validate");
+    let sample = parent.join("MB8D25");
+    std::fs::create_dir_all(&sample).expect("create sample folder");
+    for entry in std::fs::read_dir(fixture_path("fixtures/legacy/Sample25/MB8D25"))
+        .expect("read fixture folder")
+    {
+        let path = entry.expect("fixture entry").path();
+        if path.is_file() {
+            std::fs::copy(&path, sample.join(path.file_name().expect("file name")))
+                .expect("copy fixture file");
+        }
+    }
+
+    // A class file whose first mark file and seating chart are both unreadable.
+    let broken = parent.join("Broken");
+    std::fs::create_dir_all(&broken).expect("create broken folder");
+    std::fs::copy(
+        fixture_path("fixtures/legacy/Sample25/MB8D25/CL8D.Y25"),
+        broken.join("CL8D.Y25"),
+    )
+    .expect("copy CL file");
+    std::fs::write(broken.join("MAT18D.Y25"), "garbage\r\n").expect("write mark file");
+    std::fs::write(broken.join("8D.SPL"), "garbage\r\n").expect("write seating file");
+
+    std::fs::create_dir_all(parent.join("Empty")).expect("create empty folder");
+    std::fs::write(parent.join("README.txt"), "not a class").expect("write stray file");
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "legacy.validateFolder",
+        json!({ "parentPath": parent.to_string_lossy() }),
+    );
+    let folders = res["folders"].as_array().expect("folders");
+    let names: Vec<String> = folders
+        .iter()
+        .map(|f| {
+            std::path::Path::new(f["folder"].as_str().expect("folder"))
+                .file_name()
+                .and_then(|s| s.to_str())
+                .expect("folder name")
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, vec!["Broken", "Empty", "MB8D25"]);
+
+    let broken_report = &folders[0];
+    assert_eq!(broken_report["ok"], json!(false));
+    assert_eq!(broken_report["report"]["studentsFound"], json!(27));
+    let error_keys: Vec<(&str, bool, bool)> = broken_report["errors"]
+        .as_array()
+        .expect("errors")
+        .iter()
+        .map(|e| {
+            (
+                e["code"].as_str().expect("code"),
+                e["details"].get("markFile").is_some(),
+                e["details"].get("seatingFile").is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        error_keys,
+        vec![
+            ("legacy_parse_failed", true, false),
+            ("legacy_parse_failed", false, true)
+        ]
+    );
+
+    let empty = &folders[1];
+    assert_eq!(empty["ok"], json!(false));
+    assert!(empty["report"].is_null());
+    assert_eq!(empty["errors"][0]["code"], json!("legacy_no_cl"));
+
+    let sample_report = &folders[2];
+    assert_eq!(sample_report["ok"], json!(true));
+    assert_eq!(sample_report["errors"], json!([]));
+    assert_eq!(sample_report["report"]["studentsFound"], json!(27));
+    assert_eq!(sample_report["report"]["missingMarkFiles"], json!([]));
+    let preview = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "legacy.previewImport",
+        json!({ "legacyClassFolderPath": sample.to_string_lossy() }),
+    );
+    assert_eq!(sample_report["report"], preview);
+
+    // Nothing was imported, so no workspace is needed or touched.
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "legacy.validateFolder",
+        json!({ "parentPath": parent.join("nope").to_string_lossy() }),
+    );
+    assert_eq!(missing["error"]["code"], json!("legacy_read_failed"));
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "legacy.validateFolder",
+        json!({}),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
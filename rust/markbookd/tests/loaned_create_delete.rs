@@ -0,0 +1,241 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn loaned_create_and_delete_manual_items() {
+    let workspace = temp_dir("markbook-loaned-create-delete");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+    let imported_count = imported["loanedItemsImported"]
+        .as_u64()
+        .expect("loanedItemsImported") as usize;
+
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let student_id = students["students"][0]["id"]
+        .as_str()
+        .expect("student id")
+        .to_string();
+    let mark_sets = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.list",
+        json!({ "classId": class_id }),
+    );
+    let mark_set_id = mark_sets["markSets"][0]["id"]
+        .as_str()
+        .expect("mark set id")
+        .to_string();
+
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "loaned.create",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "markSetId": mark_set_id,
+            "itemName": "  Graphing Calculator ",
+            "quantity": 1,
+            "notes": "Serial 4471"
+        }),
+    );
+    let item_id = created["itemId"].as_str().expect("itemId").to_string();
+
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "loaned.get",
+        json!({ "classId": class_id, "itemId": item_id }),
+    );
+    assert_eq!(got["item"]["studentId"], json!(student_id));
+    assert_eq!(got["item"]["markSetId"], json!(mark_set_id));
+    assert_eq!(got["item"]["itemName"], json!("Graphing Calculator"));
+    assert_eq!(got["item"]["quantity"], json!(1.0));
+    assert_eq!(got["item"]["notes"], json!("Serial 4471"));
+    assert_eq!(got["item"]["rawLine"], json!(""));
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "loaned.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        listed["items"].as_array().expect("items").len(),
+        imported_count + 1
+    );
+
+    // Re-importing TBK data keeps manually created items.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy(),
+            "classId": class_id,
+            "include": ["loaned"]
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "loaned.get",
+        json!({ "classId": class_id, "itemId": item_id }),
+    );
+
+    let deleted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "loaned.delete",
+        json!({ "classId": class_id, "itemId": item_id }),
+    );
+    assert_eq!(deleted["ok"], json!(true));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "loaned.get",
+        json!({ "classId": class_id, "itemId": item_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+    let again = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "loaned.delete",
+        json!({ "classId": class_id, "itemId": item_id }),
+    );
+    assert_eq!(again["error"]["code"], json!("not_found"));
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "loaned.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        listed["items"].as_array().expect("items").len(),
+        imported_count
+    );
+}
+
+#[test]
+fn loaned_create_rejects_student_from_another_class() {
+    let workspace = temp_dir("markbook-loaned-create-validation");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut class_ids = Vec::new();
+    for (i, name) in ["Loans A", "Loans B"].iter().enumerate() {
+        let class_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("c{}", i),
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        class_ids.push(class_id);
+    }
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "students.create",
+        json!({ "classId": class_ids[0], "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let cases = [
+        (
+            json!({ "classId": class_ids[1], "studentId": student_id, "itemName": "Atlas" }),
+            "not_found",
+        ),
+        (
+            json!({ "classId": "missing-class", "studentId": student_id, "itemName": "Atlas" }),
+            "not_found",
+        ),
+        (
+            json!({ "classId": class_ids[0], "studentId": student_id, "itemName": "Atlas", "markSetId": "missing-set" }),
+            "not_found",
+        ),
+        (
+            json!({ "classId": class_ids[0], "studentId": student_id, "itemName": "   " }),
+            "bad_params",
+        ),
+        (
+            json!({ "classId": class_ids[0], "studentId": student_id }),
+            "bad_params",
+        ),
+    ];
+    for (i, (params, code)) in cases.into_iter().enumerate() {
+        let resp = request(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "loaned.create",
+            params,
+        );
+        assert_eq!(resp["error"]["code"], json!(code), "{:?}", resp);
+    }
+
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "loaned.create",
+        json!({ "classId": class_ids[0], "studentId": student_id, "itemName": "Atlas" }),
+    );
+    let item_id = created["itemId"].as_str().expect("itemId").to_string();
+    let wrong_class = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "loaned.delete",
+        json!({ "classId": class_ids[1], "itemId": item_id }),
+    );
+    assert_eq!(wrong_class["error"]["code"], json!("not_found"));
+}
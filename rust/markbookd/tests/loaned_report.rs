@@ -0,0 +1,155 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn loaned_report_groups_items_by_student_order() {
+    let workspace = temp_dir("markbook-loaned-report");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Loans" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "ENG", "description": "English" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+
+    let mut student_ids = Vec::new();
+    for (i, (last, first)) in [("Adams", "Pat"), ("Baker", "Sam"), ("Cole", "Ria")]
+        .iter()
+        .enumerate()
+    {
+        let student_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(student_id);
+    }
+
+    let items = [
+        (
+            &student_ids[1],
+            Some(&mark_set_id),
+            "Novel",
+            json!(2),
+            json!("Worn cover"),
+        ),
+        (&student_ids[0], None, "Calculator", json!(1), json!(null)),
+        (&student_ids[1], None, "Atlas", json!(null), json!(null)),
+    ];
+    for (i, (student_id, mark_set_id, item_name, quantity, notes)) in items.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("i{}", i),
+            "loaned.create",
+            json!({
+                "classId": class_id,
+                "studentId": student_id,
+                "markSetId": mark_set_id,
+                "itemName": item_name,
+                "quantity": quantity,
+                "notes": notes
+            }),
+        );
+    }
+
+    let report = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "loaned.report",
+        json!({ "classId": class_id }),
+    );
+    let students = report["students"].as_array().expect("students");
+    assert_eq!(students.len(), 2);
+    assert_eq!(students[0]["studentId"], json!(student_ids[0]));
+    assert_eq!(students[0]["displayName"], json!("Adams, Pat"));
+    assert_eq!(students[0]["itemCount"], json!(1));
+    assert_eq!(students[1]["studentId"], json!(student_ids[1]));
+    assert_eq!(students[1]["itemCount"], json!(2));
+    assert_eq!(students[1]["totalQuantity"], json!(2.0));
+    assert_eq!(students[1]["items"][0]["itemName"], json!("Atlas"));
+    assert_eq!(students[1]["items"][1]["itemName"], json!("Novel"));
+    assert_eq!(
+        report["outstanding"],
+        json!([
+            {
+                "studentId": student_ids[0],
+                "displayName": "Adams, Pat",
+                "itemName": "Calculator",
+                "quantity": 1.0,
+                "notes": null
+            },
+            {
+                "studentId": student_ids[1],
+                "displayName": "Baker, Sam",
+                "itemName": "Atlas",
+                "quantity": null,
+                "notes": null
+            },
+            {
+                "studentId": student_ids[1],
+                "displayName": "Baker, Sam",
+                "itemName": "Novel",
+                "quantity": 2.0,
+                "notes": "Worn cover"
+            }
+        ])
+    );
+    assert_eq!(
+        report["totals"],
+        json!({ "students": 2, "items": 3, "quantity": 3.0 })
+    );
+
+    let filtered = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "loaned.report",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(filtered["students"].as_array().map(|a| a.len()), Some(1));
+    assert_eq!(filtered["outstanding"][0]["itemName"], json!("Novel"));
+    assert_eq!(
+        filtered["totals"],
+        json!({ "students": 1, "items": 1, "quantity": 2.0 })
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "loaned.report",
+        json!({ "classId": "missing-class" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,138 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn markset_open_active_only_hides_inactive_students() {
+    let workspace = temp_dir("markbook-markset-open-active-only");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Grid" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Adams", "firstName": "Ann" },
+                { "lastName": "Baker", "firstName": "Ben", "active": false },
+                { "lastName": "Chen", "firstName": "Cy" }
+            ]
+        }),
+    );
+    let ids: Vec<String> = created["createdStudentIds"]
+        .as_array()
+        .expect("createdStudentIds")
+        .iter()
+        .map(|v| v.as_str().expect("id").to_string())
+        .collect();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "scores.setCell",
+        json!({
+            "classId": class_id,
+            "assessmentId": assessment_id,
+            "studentId": ids[1],
+            "rawValue": 8,
+            "status": "scored"
+        }),
+    );
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(all["rowCount"], json!(3));
+
+    let active = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "activeOnly": true }),
+    );
+    let students = active["students"].as_array().expect("students");
+    let shown: Vec<&str> = students
+        .iter()
+        .map(|s| s["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(shown, vec![ids[0].as_str(), ids[2].as_str()]);
+    assert_eq!(active["rowCount"], json!(2));
+    assert_eq!(active["colCount"], json!(1));
+
+    // gridRow still indexes the full roster, so row-based edits hit the right student.
+    let grid_rows: Vec<i64> = students
+        .iter()
+        .map(|s| s["gridRow"].as_i64().expect("gridRow"))
+        .collect();
+    assert_eq!(grid_rows, vec![0, 2]);
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "grid.updateCell",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "row": grid_rows[1],
+            "col": 0,
+            "value": 6
+        }),
+    );
+
+    // The hidden student's mark is still stored.
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(scores["scores"][0]["studentId"], json!(ids[1]));
+    assert_eq!(scores["scores"][0]["rawValue"], json!(8.0));
+    assert_eq!(scores["scores"][1]["studentId"], json!(ids[2]));
+    assert_eq!(scores["scores"][1]["rawValue"], json!(6.0));
+}
@@ -0,0 +1,133 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn marksets_create_assigns_sort_order_and_rejects_duplicate_codes() {
+    let workspace = temp_dir("markbook-marksets-create-validation");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let created_class = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Empty Class" }),
+    );
+    let class_id = created_class
+        .get("classId")
+        .and_then(|v| v.as_str())
+        .expect("classId")
+        .to_string();
+
+    let first = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({
+            "classId": class_id,
+            "code": "T1",
+            "description": "Term 1",
+            "weight": 40
+        }),
+    );
+    assert!(first.get("markSetId").and_then(|v| v.as_str()).is_some());
+    let second = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({
+            "classId": class_id,
+            "code": "T2",
+            "description": "Term 2"
+        }),
+    );
+    let second_id = second
+        .get("markSetId")
+        .and_then(|v| v.as_str())
+        .expect("markSetId")
+        .to_string();
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.list",
+        json!({ "classId": class_id }),
+    );
+    let mark_sets = listed
+        .get("markSets")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(mark_sets.len(), 2);
+    let second_row = mark_sets
+        .iter()
+        .find(|m| m.get("id").and_then(|v| v.as_str()) == Some(second_id.as_str()))
+        .expect("second mark set listed");
+    assert_eq!(second_row.get("sortOrder").and_then(|v| v.as_i64()), Some(1));
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "marksets.create",
+        json!({
+            "classId": class_id,
+            "code": "t1",
+            "description": "Duplicate"
+        }),
+    );
+    assert_eq!(dup.get("ok").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(
+        dup.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("duplicate_code")
+    );
+    assert_eq!(
+        dup.pointer("/error/details/code").and_then(|v| v.as_str()),
+        Some("t1")
+    );
+
+    let missing_class = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "marksets.create",
+        json!({
+            "classId": "no-such-class",
+            "code": "X1",
+            "description": "Nope"
+        }),
+    );
+    assert_eq!(
+        missing_class.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("not_found")
+    );
+
+    let bad_weight = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "marksets.create",
+        json!({
+            "classId": class_id,
+            "code": "T3",
+            "description": "Term 3",
+            "weight": "heavy"
+        }),
+    );
+    assert_eq!(
+        bad_weight.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("bad_params")
+    );
+}
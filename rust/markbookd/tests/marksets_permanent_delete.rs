@@ -0,0 +1,159 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn marksets_permanent_delete_cascades_and_renumbers() {
+    let workspace = temp_dir("markbook-marksets-permanent-delete");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Purge Class" }),
+    )
+    .get("classId")
+    .and_then(|v| v.as_str())
+    .expect("classId")
+    .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Doe", "firstName": "Jane" }),
+    )
+    .get("studentId")
+    .and_then(|v| v.as_str())
+    .expect("studentId")
+    .to_string();
+
+    let mut ids = Vec::new();
+    for (i, code) in ["A", "B", "C"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("ms-{i}"),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        );
+        ids.push(
+            created
+                .get("markSetId")
+                .and_then(|v| v.as_str())
+                .expect("markSetId")
+                .to_string(),
+        );
+    }
+
+    // Exclude the student from mark set C so the mask carries an explicit bit past B.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.membership.set",
+        json!({ "classId": class_id, "studentId": student_id, "markSetId": ids[2], "enabled": false }),
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": ids[1], "name": "Tests", "weight": 100 }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": ids[1], "title": "Quiz", "categoryName": "Tests", "outOf": 10 }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "grid.updateCell",
+        json!({ "classId": class_id, "markSetId": ids[1], "row": 0, "col": 0, "value": 7 }),
+    );
+
+    let deleted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "marksets.delete",
+        json!({ "classId": class_id, "markSetId": ids[1], "permanent": true }),
+    );
+    assert_eq!(deleted.get("permanent").and_then(|v| v.as_bool()), Some(true));
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "marksets.list",
+        json!({ "classId": class_id, "includeDeleted": true }),
+    );
+    let rows = listed
+        .get("markSets")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let got: Vec<(String, i64)> = rows
+        .iter()
+        .map(|m| {
+            (
+                m.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                m.get("sortOrder").and_then(|v| v.as_i64()).unwrap_or(-1),
+            )
+        })
+        .collect();
+    assert_eq!(got, vec![(ids[0].clone(), 0), (ids[2].clone(), 1)]);
+
+    let membership = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "students.membership.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        membership
+            .pointer("/students/0/mask")
+            .and_then(|v| v.as_str()),
+        Some("10")
+    );
+
+    // The purged mark set cannot be restored and a second purge reports not_found.
+    let undelete = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "marksets.undelete",
+        json!({ "classId": class_id, "markSetId": ids[1] }),
+    );
+    assert_eq!(
+        undelete.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("not_found")
+    );
+    let again = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "marksets.delete",
+        json!({ "classId": class_id, "markSetId": ids[1], "permanent": true }),
+    );
+    assert_eq!(
+        again.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("not_found")
+    );
+}
@@ -0,0 +1,142 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn marksets_reorder_rewrites_sort_order_and_membership_masks() {
+    let workspace = temp_dir("markbook-marksets-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Reorder Class" }),
+    )
+    .get("classId")
+    .and_then(|v| v.as_str())
+    .expect("classId")
+    .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Doe", "firstName": "Jane" }),
+    )
+    .get("studentId")
+    .and_then(|v| v.as_str())
+    .expect("studentId")
+    .to_string();
+
+    let mut ids = Vec::new();
+    for (i, code) in ["A", "B", "C"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("ms-{i}"),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        );
+        ids.push(
+            created
+                .get("markSetId")
+                .and_then(|v| v.as_str())
+                .expect("markSetId")
+                .to_string(),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.membership.set",
+        json!({ "classId": class_id, "studentId": student_id, "markSetId": ids[2], "enabled": false }),
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.reorder",
+        json!({ "classId": class_id, "orderedMarkSetIds": [ids[2], ids[0], ids[1]] }),
+    );
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "marksets.list",
+        json!({ "classId": class_id }),
+    );
+    let order: Vec<String> = listed
+        .get("markSets")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| m.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string())
+        .collect();
+    assert_eq!(order, vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]);
+
+    let membership = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.membership.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        membership.pointer("/students/0/mask").and_then(|v| v.as_str()),
+        Some("011")
+    );
+
+    let short = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "marksets.reorder",
+        json!({ "classId": class_id, "orderedMarkSetIds": [ids[0], ids[1]] }),
+    );
+    assert_eq!(
+        short.pointer("/error/code").and_then(|v| v.as_str()),
+        Some("bad_params")
+    );
+    assert_eq!(
+        short.pointer("/error/details/expected").and_then(|v| v.as_i64()),
+        Some(3)
+    );
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "marksets.reorder",
+        json!({ "classId": class_id, "orderedMarkSetIds": [ids[0], ids[0], ids[1]] }),
+    );
+    assert_eq!(
+        dup.pointer("/error/details/markSetId").and_then(|v| v.as_str()),
+        Some(ids[0].as_str())
+    );
+
+    let unknown = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "marksets.reorder",
+        json!({ "classId": class_id, "orderedMarkSetIds": [ids[0], ids[1], "bogus"] }),
+    );
+    assert_eq!(
+        unknown.pointer("/error/details/markSetId").and_then(|v| v.as_str()),
+        Some("bogus")
+    );
+}
@@ -0,0 +1,155 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn error_code(value: &serde_json::Value) -> Option<&str> {
+    value.pointer("/error/code").and_then(|v| v.as_str())
+}
+
+#[test]
+fn marksets_update_patches_fields_and_enforces_code_rules() {
+    let workspace = temp_dir("markbook-marksets-update");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Update Class" }),
+    )
+    .get("classId")
+    .and_then(|v| v.as_str())
+    .expect("classId")
+    .to_string();
+
+    let mut ids = Vec::new();
+    for (i, code) in ["T1", "T2"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("create-{i}"),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": format!("Term {}", i + 1) }),
+        );
+        ids.push(
+            created
+                .get("markSetId")
+                .and_then(|v| v.as_str())
+                .expect("markSetId")
+                .to_string(),
+        );
+    }
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.update",
+        json!({
+            "classId": class_id,
+            "markSetId": ids[0],
+            "patch": {
+                "code": "MID",
+                "description": "Midterm",
+                "weight": 25.5,
+                "weightMethod": 0,
+                "calcMethod": 2
+            }
+        }),
+    );
+    let settings = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "markset.settings.get",
+        json!({ "classId": class_id, "markSetId": ids[0] }),
+    );
+    let ms = settings.get("markSet").expect("markSet");
+    assert_eq!(ms.get("code").and_then(|v| v.as_str()), Some("MID"));
+    assert_eq!(
+        ms.get("description").and_then(|v| v.as_str()),
+        Some("Midterm")
+    );
+    assert_eq!(ms.get("weightMethod").and_then(|v| v.as_i64()), Some(0));
+    assert_eq!(ms.get("calcMethod").and_then(|v| v.as_i64()), Some(2));
+
+    // Re-saving the same code on the same mark set is not a collision.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": ids[0], "patch": { "code": "mid" } }),
+    );
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": ids[1], "patch": { "code": "MID" } }),
+    );
+    assert_eq!(error_code(&dup), Some("duplicate_code"));
+
+    let empty = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": ids[1], "patch": { "code": "  " } }),
+    );
+    assert_eq!(error_code(&empty), Some("bad_params"));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": "nope", "patch": { "description": "X" } }),
+    );
+    assert_eq!(error_code(&missing), Some("not_found"));
+
+    // Both update paths accept the same method ranges.
+    for (i, method) in ["marksets.update", "markset.settings.update"]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("range-ok-{i}"),
+            method,
+            json!({
+                "classId": class_id,
+                "markSetId": ids[1],
+                "patch": { "weightMethod": 2, "calcMethod": 5 }
+            }),
+        );
+        for (j, patch) in [
+            json!({ "weightMethod": 3 }),
+            json!({ "calcMethod": 6 }),
+            json!({ "calcMethod": "1" }),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let bad = request(
+                &mut stdin,
+                &mut reader,
+                &format!("range-bad-{i}-{j}"),
+                method,
+                json!({ "classId": class_id, "markSetId": ids[1], "patch": patch }),
+            );
+            assert_eq!(error_code(&bad), Some("bad_params"), "{method} {patch}");
+        }
+    }
+}
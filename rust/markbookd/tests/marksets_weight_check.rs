@@ -0,0 +1,127 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn marksets_weight_check_flags_category_totals_off_100() {
+    let workspace = temp_dir("markbook-marksets-weight-check");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Weights" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1", "weightMethod": 1 }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut category_ids = Vec::new();
+    for (i, (name, weight)) in [("Knowledge", 40.0), ("Thinking", 35.0), ("BONUS", 5.0)]
+        .iter()
+        .enumerate()
+    {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("cat{}", i),
+            "categories.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "name": name, "weight": weight }),
+        )["categoryId"]
+            .clone();
+        category_ids.push(id);
+    }
+
+    let check = |stdin: &mut _, reader: &mut _, id: &str| {
+        request_ok(
+            stdin,
+            reader,
+            id,
+            "marksets.weightCheck",
+            json!({ "classId": class_id, "markSetId": mark_set_id }),
+        )
+    };
+    let short = check(&mut stdin, &mut reader, "4");
+    assert_eq!(
+        short,
+        json!({
+            "weightMethod": 1,
+            "total": 75.0,
+            "expected": 100.0,
+            "ok": false,
+            "categories": [
+                { "name": "Knowledge", "weight": 40.0 },
+                { "name": "Thinking", "weight": 35.0 },
+                { "name": "BONUS", "weight": 5.0 }
+            ]
+        })
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.update",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "categoryId": category_ids[1],
+            "patch": { "weight": 60.004 }
+        }),
+    );
+    let balanced = check(&mut stdin, &mut reader, "6");
+    assert_eq!(balanced["ok"], json!(true));
+
+    // Entry weighting does not use category weights, so any total is fine.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "categories.update",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "categoryId": category_ids[1],
+            "patch": { "weight": 10 }
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "patch": { "weightMethod": 0 } }),
+    );
+    let entry = check(&mut stdin, &mut reader, "9");
+    assert_eq!(entry["weightMethod"], json!(0));
+    assert_eq!(entry["total"], json!(50.0));
+    assert_eq!(entry["ok"], json!(true));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "marksets.weightCheck",
+        json!({ "classId": class_id, "markSetId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
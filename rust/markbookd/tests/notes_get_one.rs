@@ -0,0 +1,103 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn notes_get_one_and_update_max_length() {
+    let workspace = temp_dir("markbook-notes-get-one");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Notes" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let other_class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Roe", "firstName": "Ann" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let empty = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "notes.getOne",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(empty, json!({ "note": "" }));
+
+    let too_long = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "notes.update",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "note": "Needs extra time",
+            "maxLength": 10
+        }),
+    );
+    assert_eq!(too_long["error"]["code"], json!("bad_params"));
+    assert_eq!(too_long["error"]["details"]["maxLength"], json!(10));
+    assert_eq!(too_long["error"]["details"]["length"], json!(16));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "notes.update",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "note": "Needs extra time",
+            "maxLength": 16
+        }),
+    );
+    let saved = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "notes.getOne",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    assert_eq!(saved, json!({ "note": "Needs extra time" }));
+
+    let wrong_class = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "notes.getOne",
+        json!({ "classId": other_class_id, "studentId": student_id }),
+    );
+    assert_eq!(wrong_class["error"]["code"], json!("not_found"));
+}
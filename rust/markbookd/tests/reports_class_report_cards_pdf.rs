@@ -0,0 +1,132 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+fn page_objects(pdf: &[u8]) -> usize {
+    String::from_utf8_lossy(pdf)
+        .matches("/Type /Page /Parent")
+        .count()
+}
+
+#[test]
+fn class_report_cards_pdf_writes_a_page_per_student() {
+    let workspace = temp_dir("markbook-report-cards-pdf");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science (8D)" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Adams", "firstName": "Pat" },
+                { "lastName": "Baker", "firstName": "Sam" },
+                { "lastName": "Chen", "firstName": "Lee" }
+            ]
+        }),
+    );
+
+    let out_path = workspace.join("reports").join("cards.pdf");
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "reports.classReportCardsPdf",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(
+        res,
+        json!({ "path": out_path.to_string_lossy(), "pageCount": 3 })
+    );
+    let bytes = std::fs::read(&out_path).expect("read pdf");
+    assert!(bytes.starts_with(b"%PDF-1.4\n"));
+    assert!(bytes.ends_with(b"%%EOF\n"));
+    assert_eq!(page_objects(&bytes), 3);
+    let text = String::from_utf8_lossy(&bytes);
+    for name in [
+        "(Adams, Pat)",
+        "(Baker, Sam)",
+        "(Chen, Lee)",
+        "(No mark sets.)",
+    ] {
+        assert!(text.contains(name), "{}", name);
+    }
+    assert!(text.contains("/Title (Science \\(8D\\) \\227 Report Cards)"));
+
+    let missing_path = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "reports.classReportCardsPdf",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing_path["error"]["code"], json!("bad_params"));
+    let missing_class = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "reports.classReportCardsPdf",
+        json!({ "classId": "nope", "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(missing_class["error"]["code"], json!("not_found"));
+}
+
+#[test]
+fn class_report_cards_pdf_covers_an_imported_class() {
+    let workspace = temp_dir("markbook-report-cards-pdf-legacy");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+
+    let out_path = workspace.join("cards.pdf");
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "reports.classReportCardsPdf",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    let page_count = res["pageCount"].as_u64().expect("pageCount") as usize;
+    assert!(page_count >= 27, "{}", page_count);
+    let bytes = std::fs::read(&out_path).expect("read pdf");
+    assert_eq!(page_objects(&bytes), page_count);
+    let text = String::from_utf8_lossy(&bytes);
+    assert!(text.contains(&format!("/Count {}", page_count)));
+    for heading in ["(Marks)", "(Attendance)", "(Learning Skills)"] {
+        assert_eq!(text.matches(heading).count(), 27, "{}", heading);
+    }
+}
@@ -0,0 +1,195 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::BufReader;
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+/// Creates a mark set with one out-of-100 assessment and scores it; `None`
+/// leaves the student without a mark.
+fn scored_mark_set(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    class_id: &str,
+    code: &str,
+    weight: f64,
+    scores: &[(&str, Option<f64>)],
+) -> String {
+    let mark_set_id = request_ok(
+        stdin,
+        reader,
+        &format!("{}-create", code),
+        "marksets.create",
+        json!({ "classId": class_id, "code": code, "description": code }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        stdin,
+        reader,
+        &format!("{}-weight", code),
+        "marksets.update",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "patch": { "weight": weight } }),
+    );
+    let _ = request_ok(
+        stdin,
+        reader,
+        &format!("{}-cat", code),
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let assessment_id = request_ok(
+        stdin,
+        reader,
+        &format!("{}-assessment", code),
+        "assessments.create",
+        json!({
+            "classId": class_id,
+            "markSetId": mark_set_id,
+            "title": "Test",
+            "categoryName": "Tests",
+            "outOf": 100
+        }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+    for (i, (student_id, score)) in scores.iter().enumerate() {
+        let (value, status) = match score {
+            Some(v) => (json!(v), "scored"),
+            None => (json!(null), "no_mark"),
+        };
+        let _ = request_ok(
+            stdin,
+            reader,
+            &format!("{}-score{}", code, i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": value,
+                "status": status
+            }),
+        );
+    }
+    mark_set_id
+}
+
+#[test]
+fn honor_roll_combines_mark_sets_by_weight_and_applies_threshold() {
+    let workspace = temp_dir("markbook-reports-honor-roll");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Honors" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Diaz"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        ids.push(id);
+    }
+
+    let term1 = scored_mark_set(
+        &mut stdin,
+        &mut reader,
+        &class_id,
+        "T1",
+        1.0,
+        &[
+            (&ids[0], Some(90.0)),
+            (&ids[1], Some(60.0)),
+            (&ids[2], Some(100.0)),
+            (&ids[3], Some(50.0)),
+        ],
+    );
+    let _term2 = scored_mark_set(
+        &mut stdin,
+        &mut reader,
+        &class_id,
+        "T2",
+        3.0,
+        &[
+            (&ids[0], Some(80.0)),
+            (&ids[1], Some(95.0)),
+            (&ids[2], None),
+            (&ids[3], Some(70.0)),
+        ],
+    );
+
+    // Adams (90 + 3 * 80) / 4 = 82.5, Baker (60 + 3 * 95) / 4 = 86.25, Chen
+    // only has T1 and Diaz's 65 misses the cut.
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "reports.honorRoll",
+        json!({ "classId": class_id, "threshold": 80 }),
+    );
+    assert_eq!(
+        all,
+        json!({
+            "honorRoll": [
+                { "studentId": ids[2], "displayName": "Chen, Pat", "average": 100.0 },
+                { "studentId": ids[1], "displayName": "Baker, Pat", "average": 86.3 },
+                { "studentId": ids[0], "displayName": "Adams, Pat", "average": 82.5 }
+            ]
+        })
+    );
+
+    let term1_only = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "reports.honorRoll",
+        json!({ "classId": class_id, "markSetIds": [term1], "threshold": 80 }),
+    );
+    let roll: Vec<&str> = term1_only["honorRoll"]
+        .as_array()
+        .expect("honorRoll")
+        .iter()
+        .map(|r| r["studentId"].as_str().expect("studentId"))
+        .collect();
+    assert_eq!(roll, vec![ids[2].as_str(), ids[0].as_str()]);
+
+    let unknown = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "reports.honorRoll",
+        json!({ "classId": class_id, "markSetIds": ["nope"], "threshold": 80 }),
+    );
+    assert_eq!(unknown["error"]["code"], json!("not_found"));
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "reports.honorRoll",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("bad_params"));
+}
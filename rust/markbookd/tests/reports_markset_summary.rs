@@ -0,0 +1,126 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn markset_summary_reports_class_means_per_assessment() {
+    let workspace = temp_dir("markbook-reports-markset-summary");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Summary" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben", "Cal", "Dee"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({
+                "classId": class_id,
+                "lastName": "Roe",
+                "firstName": first,
+                "active": i != 3
+            }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "c",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    let mut assessment_ids = Vec::new();
+    for (i, title) in ["Quiz", "Test"].iter().enumerate() {
+        let aid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{i}"),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": "Tests",
+                "outOf": 10
+            }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(aid);
+    }
+    // Quiz: 8, zero, no mark, and a 10 from the inactive student.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "scores.bulkSet",
+        json!({
+            "classId": class_id,
+            "edits": [
+                { "assessmentId": assessment_ids[0], "studentId": student_ids[0], "rawValue": 8 },
+                { "assessmentId": assessment_ids[0], "studentId": student_ids[1], "status": "zero" },
+                { "assessmentId": assessment_ids[0], "studentId": student_ids[3], "rawValue": 10 }
+            ]
+        }),
+    );
+
+    let summary = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "reports.markSetSummary",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(summary["activeStudents"], json!(3));
+    assert_eq!(summary["assessments"][0]["idx"], json!(0));
+    assert_eq!(summary["assessments"][0]["title"], json!("Quiz"));
+    assert_eq!(summary["assessments"][0]["classMean"], json!(40.0));
+    assert_eq!(summary["assessments"][0]["n"], json!(2));
+    assert_eq!(summary["assessments"][1]["classMean"], json!(null));
+    assert_eq!(summary["assessments"][1]["n"], json!(0));
+
+    // Ann 80%, Ben 0%, Cal has no final mark; Dee is inactive.
+    assert_eq!(summary["overallClassMean"], json!(40.0));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "reports.markSetSummary",
+        json!({ "classId": class_id, "markSetId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,123 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn report_card_html_combines_marks_attendance_and_comments() {
+    let workspace = temp_dir("markbook-report-card-html");
+    let out_path = workspace.join("reports").join("card.html");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let class_id = imported["classId"].as_str().expect("classId").to_string();
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let students = students["students"].as_array().expect("students").clone();
+    let mark_sets = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.list",
+        json!({ "classId": class_id }),
+    );
+    let mark_set_id = mark_sets["markSets"][0]["id"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "reports.reportCardHtml",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(all["studentCount"], json!(students.len()));
+    let html = all["html"].as_str().expect("html");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("<style"));
+    assert_eq!(html.matches("<section").count(), students.len());
+    assert_eq!(
+        html.matches("page-break-before: always").count(),
+        students.len() - 1
+    );
+
+    // A student with a final mark: the card shows the calc average.
+    let averages = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "calc.markSetAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let (student_id, percent) = averages["averages"]
+        .as_array()
+        .expect("averages")
+        .iter()
+        .find_map(|a| Some((a["studentId"].as_str()?.to_string(), a["percent"].as_f64()?)))
+        .expect("a student with an average");
+    let student = students
+        .iter()
+        .find(|s| s["id"] == json!(student_id))
+        .expect("student");
+    let display_name = format!(
+        "{}, {}",
+        student["lastName"].as_str().expect("lastName"),
+        student["firstName"].as_str().expect("firstName")
+    );
+
+    let one = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "reports.reportCardHtml",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "outPath": out_path.to_string_lossy()
+        }),
+    );
+    assert_eq!(one["studentCount"], json!(1));
+    assert_eq!(one["path"], json!(out_path.to_string_lossy()));
+    assert!(one.get("html").is_none());
+    let written = std::fs::read_to_string(&out_path).expect("read card");
+    assert!(written.contains(&display_name.replace('&', "&amp;")));
+    assert!(written.contains(&format!("{:.1}%", percent)));
+    for heading in ["Marks", "Attendance", "Learning Skills"] {
+        assert!(
+            written.contains(&format!("{}</h2>", heading)),
+            "{}",
+            heading
+        );
+    }
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "reports.reportCardHtml",
+        json!({ "classId": class_id, "studentId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
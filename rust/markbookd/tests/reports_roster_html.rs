@@ -0,0 +1,101 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn roster_html_lists_students_in_sort_order_with_check_columns() {
+    let workspace = temp_dir("markbook-roster-html");
+    let out_path = workspace.join("reports").join("roster.html");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Homeroom <9A>" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben", "Cal"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({
+                "classId": class_id,
+                "lastName": "Roe",
+                "firstName": first,
+                "studentNo": format!("10{i}"),
+                "birthDate": format!("2010-05-0{}", i + 1),
+                "active": i != 1
+            }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    // Cal first, then Ann, Ben.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.reorder",
+        json!({
+            "classId": class_id,
+            "orderedStudentIds": [student_ids[2], student_ids[0], student_ids[1]]
+        }),
+    );
+
+    let missing_out = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "reports.rosterHtml",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing_out["error"]["code"], json!("bad_params"));
+
+    let written = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "reports.rosterHtml",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy(), "checkColumns": 3 }),
+    );
+    assert_eq!(written["path"], json!(out_path.to_string_lossy()));
+    assert_eq!(written["studentCount"], json!(3));
+
+    let html = std::fs::read_to_string(&out_path).expect("read roster");
+    assert!(html.contains("Homeroom &lt;9A&gt; \u{2014} Class Roster"));
+    let cal = html.find("Roe, Cal").expect("Cal");
+    let ann = html.find("Roe, Ann").expect("Ann");
+    let ben = html.find("Roe, Ben").expect("Ben");
+    assert!(cal < ann && ann < ben);
+    assert!(html.contains(">101<") && html.contains(">2010-05-02<"));
+    assert_eq!(html.matches(">No<").count(), 1);
+    // 5 data columns + 3 blank check columns per header and student row.
+    assert_eq!(html.matches("<th ").count(), 8);
+    assert_eq!(html.matches("<td ").count(), 3 * 8);
+
+    let unknown = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "reports.rosterHtml",
+        json!({ "classId": "nope", "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(unknown["error"]["code"], json!("not_found"));
+}
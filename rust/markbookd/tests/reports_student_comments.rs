@@ -0,0 +1,108 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn student_comments_lists_remarks_by_mark_set_then_set_number() {
+    let workspace = temp_dir("markbook-reports-student-comments");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Comments" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for last in ["Adams", "Baker"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("student-{}", last),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mut mark_set_ids = Vec::new();
+    for code in ["T1", "T2"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("markset-{}", code),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        )["markSetId"]
+            .as_str()
+            .expect("markSetId")
+            .to_string();
+        mark_set_ids.push(id);
+    }
+
+    // Set 2 is written before set 1, and set 3 only has a blank remark.
+    let sets = [
+        (0, 2, "Final", "Keeps improving.", "Baker's final."),
+        (0, 1, "Midterm", "Good start.", ""),
+        (0, 3, "Extra", "   ", ""),
+        (1, 1, "Report", "Strong finish.", ""),
+    ];
+    for (i, (ms, set_number, title, adams, baker)) in sets.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("set-{}", i),
+            "comments.sets.upsert",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_ids[*ms],
+                "setNumber": set_number,
+                "title": title,
+                "remarksByStudent": [
+                    { "studentId": student_ids[0], "remark": adams },
+                    { "studentId": student_ids[1], "remark": baker }
+                ]
+            }),
+        );
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "reports.studentComments",
+        json!({ "classId": class_id, "studentId": student_ids[0] }),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "sets": [
+                { "markSetCode": "T1", "setNumber": 1, "title": "Midterm", "remark": "Good start." },
+                { "markSetCode": "T1", "setNumber": 2, "title": "Final", "remark": "Keeps improving." },
+                { "markSetCode": "T2", "setNumber": 1, "title": "Report", "remark": "Strong finish." }
+            ]
+        })
+    );
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "reports.studentComments",
+        json!({ "classId": class_id, "studentId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,66 @@
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+
+fn run_with_log_level(level: Option<&str>) -> (Vec<serde_json::Value>, String) {
+    let exe = env!("CARGO_BIN_EXE_markbookd");
+    let mut cmd = Command::new(exe);
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env_remove("MARKBOOKD_LOG");
+    if let Some(level) = level {
+        cmd.env("MARKBOOKD_LOG", level);
+    }
+    let mut child = cmd.spawn().expect("spawn markbookd");
+    {
+        let mut stdin = child.stdin.take().expect("child stdin");
+        for (id, method) in [("1", "health"), ("2", "db.vacuum")] {
+            let payload = json!({ "id": id, "method": method, "params": {} });
+            writeln!(stdin, "{}", payload).expect("write request");
+        }
+    }
+    let responses: Vec<serde_json::Value> = BufReader::new(child.stdout.take().expect("stdout"))
+        .lines()
+        .map(|l| serde_json::from_str(&l.expect("line")).expect("stdout stays json"))
+        .collect();
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .expect("stderr")
+        .read_to_string(&mut stderr)
+        .expect("read stderr");
+    child.wait().expect("wait");
+    (responses, stderr)
+}
+
+#[test]
+fn request_log_goes_to_stderr_at_requested_verbosity() {
+    let (responses, stderr) = run_with_log_level(None);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(stderr, "");
+
+    let (responses, stderr) = run_with_log_level(Some("error"));
+    assert_eq!(responses[1]["error"]["code"], json!("no_workspace"));
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", stderr);
+    assert!(lines[0].starts_with("markbookd level=error method=db.vacuum ms="));
+    assert!(
+        lines[0].ends_with(" ok=false code=no_workspace"),
+        "{}",
+        lines[0]
+    );
+
+    let (responses, stderr) = run_with_log_level(Some("debug"));
+    assert_eq!(responses[0]["ok"], json!(true));
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stderr);
+    assert!(lines[0].starts_with("markbookd level=info method=health ms="));
+    assert!(lines[0].ends_with(" ok=true id=\"1\""), "{}", lines[0]);
+    assert!(
+        lines[1].contains(" id=\"2\" code=no_workspace message=\"select a workspace first\""),
+        "{}",
+        lines[1]
+    );
+}
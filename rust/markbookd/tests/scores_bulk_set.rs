@@ -0,0 +1,101 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn scores_bulk_set_applies_valid_edits_and_skips_foreign_or_malformed_ones() {
+    let workspace = temp_dir("markbook-scores-bulk-set");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Paste" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Roe", "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Test", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let result = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "scores.bulkSet",
+        json!({
+            "classId": class_id,
+            "edits": [
+                { "assessmentId": assessment_id, "studentId": student_ids[0], "rawValue": 8 },
+                { "assessmentId": assessment_id, "studentId": student_ids[1], "status": "zero" },
+                { "assessmentId": "elsewhere", "studentId": student_ids[0], "rawValue": 5 },
+                { "assessmentId": assessment_id, "studentId": "ghost", "rawValue": 5 },
+                { "assessmentId": assessment_id, "studentId": student_ids[0], "status": "bogus" },
+                { "assessmentId": assessment_id, "studentId": student_ids[0], "rawValue": "abc" },
+                { "assessmentId": assessment_id, "studentId": student_ids[0], "rawValue": "8,5" },
+                "not an object"
+            ]
+        }),
+    );
+    assert_eq!(result, json!({ "updated": 2, "skipped": 6 }));
+
+    let grid = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "grid.get",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "rowStart": 0, "rowCount": 2, "colStart": 0, "colCount": 1 }),
+    );
+    assert_eq!(grid["cells"], json!([[8.0], [0.0]]));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "scores.bulkSet",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(missing["error"]["code"], "bad_params");
+}
@@ -0,0 +1,194 @@
+mod test_support;
+
+use serde_json::json;
+use std::io::BufReader;
+use std::process::{ChildStdin, ChildStdout};
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+/// (assessmentId, studentId) of every stored score in the mark set, sorted.
+fn stored_cells(
+    stdin: &mut ChildStdin,
+    reader: &mut BufReader<ChildStdout>,
+    class_id: &str,
+    mark_set_id: &str,
+) -> Vec<(String, String)> {
+    let scores = request_ok(
+        stdin,
+        reader,
+        "scores",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let mut cells: Vec<(String, String)> = scores["scores"]
+        .as_array()
+        .expect("scores")
+        .iter()
+        .map(|s| {
+            (
+                s["assessmentId"]
+                    .as_str()
+                    .expect("assessmentId")
+                    .to_string(),
+                s["studentId"].as_str().expect("studentId").to_string(),
+            )
+        })
+        .collect();
+    cells.sort();
+    cells
+}
+
+#[test]
+fn scores_clear_column_and_row_are_scoped_and_undoable() {
+    let workspace = temp_dir("markbook-scores-clear");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Clear" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Ann", "Ben"].iter().enumerate() {
+        let sid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Roe", "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(sid);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut assessment_ids = Vec::new();
+    for (i, title) in ["Quiz", "Test"].iter().enumerate() {
+        let aid = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(aid);
+    }
+    let mut edits = Vec::new();
+    for aid in &assessment_ids {
+        for sid in &student_ids {
+            edits.push(json!({ "assessmentId": aid, "studentId": sid, "rawValue": 8 }));
+        }
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "scores.bulkSet",
+        json!({ "classId": class_id, "edits": edits }),
+    );
+    let all = stored_cells(&mut stdin, &mut reader, &class_id, &mark_set_id);
+    assert_eq!(all.len(), 4);
+
+    let cleared = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "scores.clearColumn",
+        json!({ "classId": class_id, "assessmentId": assessment_ids[0] }),
+    );
+    assert_eq!(cleared, json!({ "cleared": 2 }));
+    let left = stored_cells(&mut stdin, &mut reader, &class_id, &mark_set_id);
+    assert!(left.iter().all(|(aid, _)| *aid == assessment_ids[1]));
+
+    let cleared = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "scores.clearRow",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "studentId": student_ids[1] }),
+    );
+    assert_eq!(cleared, json!({ "cleared": 1 }));
+    assert_eq!(
+        stored_cells(&mut stdin, &mut reader, &class_id, &mark_set_id),
+        vec![(assessment_ids[1].clone(), student_ids[0].clone())]
+    );
+
+    // Clearing again is a no-op and logs nothing.
+    let again = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "scores.clearRow",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "studentId": student_ids[1] }),
+    );
+    assert_eq!(again, json!({ "cleared": 0 }));
+
+    for (i, op) in ["scores.clearRow", "scores.clearColumn"].iter().enumerate() {
+        let undone = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("undo{}", i),
+            "edits.undo",
+            json!({ "classId": class_id }),
+        );
+        assert_eq!(undone["undone"]["op"], json!(op));
+    }
+    assert_eq!(
+        stored_cells(&mut stdin, &mut reader, &class_id, &mark_set_id),
+        all
+    );
+
+    // Ids from another class are not found.
+    let other_class = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .clone();
+    let foreign_column = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "scores.clearColumn",
+        json!({ "classId": other_class, "assessmentId": assessment_ids[0] }),
+    );
+    assert_eq!(foreign_column["error"]["code"], json!("not_found"));
+    let foreign_row = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "scores.clearRow",
+        json!({ "classId": other_class, "markSetId": mark_set_id, "studentId": student_ids[0] }),
+    );
+    assert_eq!(foreign_row["error"]["code"], json!("not_found"));
+    assert_eq!(
+        stored_cells(&mut stdin, &mut reader, &class_id, &mark_set_id),
+        all
+    );
+}
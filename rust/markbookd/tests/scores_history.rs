@@ -0,0 +1,335 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn scores_history_records_cell_changes_in_order_and_caps_retention() {
+    let workspace = temp_dir("markbook-scores-history");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "History" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let set_cell = |stdin: &mut _, reader: &mut _, id: &str, raw: serde_json::Value, status| {
+        let _ = request_ok(
+            stdin,
+            reader,
+            id,
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": raw,
+                "status": status
+            }),
+        );
+    };
+    set_cell(&mut stdin, &mut reader, "6", json!(7), "scored");
+    // Writing the same value again is not recorded.
+    set_cell(&mut stdin, &mut reader, "7", json!(7), "scored");
+    set_cell(&mut stdin, &mut reader, "8", json!(null), "zero");
+
+    let history_params = json!({
+        "classId": class_id,
+        "assessmentId": assessment_id,
+        "studentId": student_id
+    });
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "scores.history",
+        history_params.clone(),
+    );
+    let history = res["history"].as_array().expect("history");
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["oldRaw"], json!(null));
+    assert_eq!(history[0]["oldStatus"], json!(null));
+    assert_eq!(history[0]["newRaw"], json!(7.0));
+    assert_eq!(history[0]["newStatus"], json!("scored"));
+    assert_eq!(history[1]["oldRaw"], json!(7.0));
+    assert_eq!(history[1]["oldStatus"], json!("scored"));
+    assert_eq!(history[1]["newRaw"], json!(null));
+    assert_eq!(history[1]["newStatus"], json!("zero"));
+    assert!(history[1]["changedAt"]
+        .as_str()
+        .is_some_and(|s| s.ends_with('Z')));
+
+    // Only the most recent 20 changes survive.
+    for i in 1..=25 {
+        set_cell(
+            &mut stdin,
+            &mut reader,
+            &format!("edit-{}", i),
+            json!(i as f64 / 5.0 + 1.0),
+            "scored",
+        );
+    }
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "scores.history",
+        history_params.clone(),
+    );
+    let history = res["history"].as_array().expect("history");
+    assert_eq!(history.len(), 20);
+    assert_eq!(history[19]["newRaw"], json!(6.0));
+    assert_eq!(history[0]["oldRaw"], json!(2.0));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "scores.history",
+        json!({
+            "classId": class_id,
+            "assessmentId": assessment_id,
+            "studentId": "nope"
+        }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+
+    // Deleting the student takes the cell's history with it; undo brings it back.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "students.delete",
+        json!({ "classId": class_id, "studentId": student_id }),
+    );
+    let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let left: i64 = conn
+        .query_row("SELECT COUNT(*) FROM score_history", [], |r| r.get(0))
+        .expect("count history");
+    assert_eq!(left, 0);
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "14",
+        "scores.history",
+        history_params,
+    );
+    let history = res["history"].as_array().expect("history");
+    assert_eq!(history.len(), 20);
+    assert_eq!(history[19]["newRaw"], json!(6.0));
+
+    // Deleting the assessment clears its history explicitly.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "15",
+        "assessments.delete",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "assessmentId": assessment_id }),
+    );
+    let left: i64 = conn
+        .query_row("SELECT COUNT(*) FROM score_history", [], |r| r.get(0))
+        .expect("count history");
+    assert_eq!(left, 0);
+}
+
+fn history_rows(value: &serde_json::Value) -> Vec<serde_json::Value> {
+    value["history"]
+        .as_array()
+        .expect("history")
+        .iter()
+        .map(|h| json!([h["oldRaw"], h["oldStatus"], h["newRaw"], h["newStatus"]]))
+        .collect()
+}
+
+#[test]
+fn scores_history_records_clears_undo_imports_and_merges() {
+    let workspace = temp_dir("markbook-scores-history-writers");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "History" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, first) in ["Pat", "Pat "].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Adams", "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+    for (i, (student, raw)) in [(0, 7), (1, 4)].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("set{i}"),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_ids[*student],
+                "rawValue": raw
+            }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "scores.clearRow",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "studentId": student_ids[0] }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "edits.undo",
+        json!({ "classId": class_id }),
+    );
+    let csv_path = workspace.join("history.csv");
+    std::fs::write(
+        &csv_path,
+        format!(
+            "student_id,student_name,mark_set_code,assessment_idx,assessment_title,status,raw_value\n\
+             {},\"Adams, Pat\",T1,0,Quiz,scored,9\n",
+            student_ids[0]
+        ),
+    )
+    .expect("write csv");
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "exchange.importClassCsv",
+        json!({ "classId": class_id, "inPath": csv_path.to_string_lossy() }),
+    );
+
+    let history_params = json!({
+        "classId": class_id,
+        "assessmentId": assessment_id,
+        "studentId": student_ids[0]
+    });
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "scores.history",
+        history_params.clone(),
+    );
+    assert_eq!(
+        history_rows(&res),
+        vec![
+            json!([null, null, 7.0, "scored"]),
+            json!([7.0, "scored", null, null]),
+            json!([null, null, 7.0, "scored"]),
+            json!([7.0, "scored", 9.0, "scored"]),
+        ]
+    );
+
+    // The kept cell wins the merge, but the duplicate's history moves over.
+    let merged = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "students.merge",
+        json!({ "classId": class_id, "keepId": student_ids[0], "mergeIds": [student_ids[1]] }),
+    );
+    assert_eq!(merged["repointed"]["score_history"], json!(1));
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "scores.history",
+        history_params,
+    );
+    let history = history_rows(&res);
+    assert_eq!(history.len(), 5);
+    assert!(history.contains(&json!([null, null, 4.0, "scored"])));
+}
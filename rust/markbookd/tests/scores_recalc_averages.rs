@@ -0,0 +1,146 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn recalc_averages_writes_stored_assessment_averages_from_scores() {
+    let workspace = temp_dir("markbook-scores-recalc-averages");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Averages" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Adams", "firstName": "Ann" },
+                { "lastName": "Baker", "firstName": "Ben" },
+                { "lastName": "Chen", "firstName": "Cy" }
+            ]
+        }),
+    );
+    let ids: Vec<String> = created["createdStudentIds"]
+        .as_array()
+        .expect("createdStudentIds")
+        .iter()
+        .map(|v| v.as_str().expect("id").to_string())
+        .collect();
+    let mut mark_set_ids = Vec::new();
+    let mut assessment_ids = Vec::new();
+    for code in ["T1", "T2"] {
+        let mark_set_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("markset-{}", code),
+            "marksets.create",
+            json!({ "classId": class_id, "code": code, "description": code }),
+        )["markSetId"]
+            .as_str()
+            .expect("markSetId")
+            .to_string();
+        let assessment_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("assessment-{}", code),
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        mark_set_ids.push(mark_set_id);
+        assessment_ids.push(assessment_id);
+    }
+
+    // T1: 8 scored, a zero that counts, and a no_mark that does not.
+    for (i, (student, raw, status)) in [
+        (&ids[0], json!(8), "scored"),
+        (&ids[1], json!(null), "zero"),
+        (&ids[2], json!(null), "no_mark"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("score-{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_ids[0],
+                "studentId": student,
+                "rawValue": raw,
+                "status": status
+            }),
+        );
+    }
+
+    let only_t1 = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "scores.recalcAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_ids[0] }),
+    );
+    assert_eq!(only_t1, json!({ "updated": 1 }));
+
+    let stored = |assessment_id: &str| -> (Option<f64>, Option<f64>) {
+        let conn = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+        conn.query_row(
+            "SELECT avg_percent, avg_raw FROM assessments WHERE id = ?",
+            [assessment_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .expect("assessment averages")
+    };
+    assert_eq!(stored(&assessment_ids[0]), (Some(40.0), Some(4.0)));
+    assert_eq!(stored(&assessment_ids[1]), (None, None));
+
+    // The whole class: T2 has no marks yet, T1 is already current.
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "scores.recalcAverages",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(all, json!({ "updated": 1 }));
+    assert_eq!(stored(&assessment_ids[1]), (Some(0.0), Some(0.0)));
+    let again = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "scores.recalcAverages",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(again, json!({ "updated": 0 }));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "scores.recalcAverages",
+        json!({ "classId": class_id, "markSetId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
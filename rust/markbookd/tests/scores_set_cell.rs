@@ -0,0 +1,131 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn scores_set_cell_resolves_state_and_validates_ownership() {
+    let workspace = temp_dir("markbook-scores-set-cell");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Cells" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let other_class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Lee", "firstName": "Sam" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz 1", "outOf": 20 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+
+    let scored = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "scores.setCell",
+        json!({ "classId": class_id, "assessmentId": assessment_id, "studentId": student_id, "rawValue": 17.5 }),
+    );
+    assert_eq!(scored, json!({ "rawValue": 17.5, "status": "scored" }));
+
+    let zero = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "scores.setCell",
+        json!({ "classId": class_id, "assessmentId": assessment_id, "studentId": student_id, "status": "zero" }),
+    );
+    assert_eq!(zero, json!({ "rawValue": null, "status": "zero" }));
+
+    let cleared = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "scores.setCell",
+        json!({ "classId": class_id, "assessmentId": assessment_id, "studentId": student_id, "rawValue": null }),
+    );
+    assert_eq!(cleared, json!({ "rawValue": 0.0, "status": "no_mark" }));
+
+    let grid = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "grid.get",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "rowStart": 0, "rowCount": 1, "colStart": 0, "colCount": 1 }),
+    );
+    assert_eq!(grid["cells"], json!([[null]]));
+
+    let negative = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "scores.setCell",
+        json!({ "classId": class_id, "assessmentId": assessment_id, "studentId": student_id, "rawValue": -1 }),
+    );
+    assert_eq!(negative["error"]["code"], "bad_params");
+
+    let wrong_class = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "scores.setCell",
+        json!({ "classId": other_class_id, "assessmentId": assessment_id, "studentId": student_id, "rawValue": 5 }),
+    );
+    assert_eq!(wrong_class["error"]["code"], "not_found");
+
+    let unknown_student = request(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "scores.setCell",
+        json!({ "classId": class_id, "assessmentId": assessment_id, "studentId": "nobody", "rawValue": 5 }),
+    );
+    assert_eq!(unknown_student["error"]["code"], "not_found");
+}
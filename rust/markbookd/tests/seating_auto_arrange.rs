@@ -0,0 +1,159 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn seating_auto_arrange_fills_open_seats_by_mode() {
+    let workspace = temp_dir("markbook-seating-auto-arrange");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Seating" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+
+    let no_plan = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "alphabetical" }),
+    );
+    assert_eq!(no_plan["error"]["code"], json!("no_plan"));
+
+    // Sort orders 0..=4; Dunn is inactive and must never be seated.
+    let mut student_ids = Vec::new();
+    for (i, (last, active)) in [
+        ("Chen", true),
+        ("Adams", true),
+        ("Baker", true),
+        ("Dunn", false),
+        ("Evans", true),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat", "active": active }),
+        );
+        student_ids.push(
+            created["studentId"]
+                .as_str()
+                .expect("studentId")
+                .to_string(),
+        );
+    }
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "rows": 2,
+            "seatsPerRow": 3,
+            "blockedSeatCodes": [2],
+            "assignments": []
+        }),
+    );
+
+    let alpha = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "alphabetical" }),
+    );
+    assert_eq!(alpha["assignments"], json!([1, null, 2, 0, 4, null]));
+    assert_eq!(alpha["blockedSeatCodes"], json!([2]));
+    assert_eq!(alpha["unseatedStudentIds"], json!([]));
+
+    let reverse = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "reverse" }),
+    );
+    assert_eq!(reverse["assignments"], json!([4, null, 0, 2, 1, null]));
+
+    let random_a = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "random", "seed": 42 }),
+    );
+    let random_b = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "random", "seed": 42 }),
+    );
+    assert_eq!(random_a["assignments"], random_b["assignments"]);
+    let assignments = random_a["assignments"].as_array().expect("assignments");
+    assert_eq!(assignments[1], json!(null));
+    let mut seated: Vec<i64> = assignments.iter().filter_map(|v| v.as_i64()).collect();
+    seated.sort();
+    assert_eq!(seated, vec![0, 1, 2, 4]);
+
+    // Persisted: seating.get reflects the last arrangement.
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(got["assignments"], random_a["assignments"]);
+
+    // Fewer open seats than students leaves the tail unseated.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "seating.save",
+        json!({ "classId": class_id, "rows": 1, "seatsPerRow": 2, "assignments": [] }),
+    );
+    let small = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "alphabetical" }),
+    );
+    assert_eq!(small["assignments"], json!([1, 2]));
+    assert_eq!(
+        small["unseatedStudentIds"],
+        json!([student_ids[0], student_ids[4]])
+    );
+
+    let bad_mode = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "seating.autoArrange",
+        json!({ "classId": class_id, "mode": "height" }),
+    );
+    assert_eq!(bad_mode["error"]["code"], json!("bad_params"));
+}
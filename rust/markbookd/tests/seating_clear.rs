@@ -0,0 +1,153 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn seating_clear_empties_assignments_and_keeps_plans() {
+    let workspace = temp_dir("markbook-seating-clear");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    for (i, last) in ["Adams", "Baker"].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.save",
+        json!({ "classId": class_id, "rows": 2, "seatsPerRow": 3, "assignments": [0, 1] }),
+    );
+    let lab_plan_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.plans.create",
+        json!({ "classId": class_id, "name": "Lab", "rows": 1, "seatsPerRow": 2 }),
+    )["planId"]
+        .as_str()
+        .expect("planId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "planId": lab_plan_id,
+            "rows": 1,
+            "seatsPerRow": 2,
+            "assignments": [1, 0]
+        }),
+    );
+
+    // With a planId only that plan is emptied.
+    let cleared = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.clear",
+        json!({ "classId": class_id, "planId": lab_plan_id }),
+    );
+    assert_eq!(cleared, json!({ "cleared": 2 }));
+    let lab = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "seating.get",
+        json!({ "classId": class_id, "planId": lab_plan_id }),
+    );
+    assert_eq!(lab["seatsPerRow"], json!(2));
+    assert_eq!(lab["assignments"], json!([null, null]));
+    let default = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(default["assignments"][0], json!(0));
+
+    // Without one, every plan of the class is emptied and none is removed.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "planId": lab_plan_id,
+            "rows": 1,
+            "seatsPerRow": 2,
+            "assignments": [null, 0]
+        }),
+    );
+    let cleared = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "seating.clear",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(cleared, json!({ "cleared": 3 }));
+    let plans = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "seating.plans.list",
+        json!({ "classId": class_id }),
+    );
+    let summary: Vec<(i64, i64, i64)> = plans["plans"]
+        .as_array()
+        .expect("plans")
+        .iter()
+        .map(|p| {
+            (
+                p["rows"].as_i64().expect("rows"),
+                p["seatsPerRow"].as_i64().expect("seatsPerRow"),
+                p["seatedCount"].as_i64().expect("seatedCount"),
+            )
+        })
+        .collect();
+    assert_eq!(summary, vec![(2, 3, 0), (1, 2, 0)]);
+
+    let missing_plan = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "seating.clear",
+        json!({ "classId": class_id, "planId": "nope" }),
+    );
+    assert_eq!(missing_plan["error"]["code"], json!("not_found"));
+    let missing_class = request(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "seating.clear",
+        json!({ "classId": "nope" }),
+    );
+    assert_eq!(missing_class["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,105 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+const PNG_B64: &str = "iVBORw0KGgoAAAANSUhEUi1mYWtl";
+const JPEG_B64: &str = "/9j/4EpGSUYtZmFrZS1ib2R5";
+
+#[test]
+fn seating_export_svg_embeds_photos_and_falls_back_to_initials() {
+    let workspace = temp_dir("markbook-seating-export-photos");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Bio 12" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, (last, first)) in [("Adams", "Pat"), ("Baker", "Lou"), ("Chen", "Sam")]
+        .iter()
+        .enumerate()
+    {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    for (i, data) in [PNG_B64, JPEG_B64].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("p{}", i),
+            "assets.putStudentPhoto",
+            json!({ "classId": class_id, "studentId": student_ids[i], "dataBase64": data }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.save",
+        json!({ "classId": class_id, "rows": 2, "seatsPerRow": 2, "assignments": [0, 1, 2] }),
+    );
+
+    let out_path = workspace.join("exports").join("seating-photos.svg");
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy(), "withPhotos": true }),
+    );
+    assert_eq!(exported["photosEmbedded"], json!(2));
+
+    let svg = std::fs::read_to_string(&out_path).expect("read svg");
+    assert_eq!(svg.matches("<image ").count(), 2);
+    assert!(svg.contains(&format!("href=\"data:image/png;base64,{}\"", PNG_B64)));
+    assert!(svg.contains(&format!("href=\"data:image/jpeg;base64,{}\"", JPEG_B64)));
+    assert_eq!(svg.matches("<circle ").count(), 1);
+    assert!(svg.contains(">SC</text>"));
+    assert!(svg.contains(">Chen, Sam</text>"));
+
+    // Without the flag the chart keeps its plain layout.
+    let plain_path = workspace.join("exports").join("seating.svg");
+    let plain = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": plain_path.to_string_lossy() }),
+    );
+    assert!(plain.get("photosEmbedded").is_none());
+    let svg = std::fs::read_to_string(&plain_path).expect("read svg");
+    assert_eq!(svg.matches("<image ").count(), 0);
+    assert_eq!(svg.matches("<circle ").count(), 0);
+
+    let bad_flag = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": plain_path.to_string_lossy(), "withPhotos": "yes" }),
+    );
+    assert_eq!(bad_flag["error"]["code"], json!("bad_params"));
+}
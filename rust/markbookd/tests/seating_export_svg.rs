@@ -0,0 +1,97 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn seating_export_svg_renders_seats_blocked_cells_and_unseated_footer() {
+    let workspace = temp_dir("markbook-seating-export-svg");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Chem 11" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    for (i, (last, first)) in [("Adams", "Pat"), ("Smith & Jones", "Lee"), ("Chen", "Sam")]
+        .iter()
+        .enumerate()
+    {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first }),
+        );
+    }
+
+    let out_path = workspace.join("exports").join("seating.svg");
+    let no_plan = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(no_plan["error"]["code"], json!("no_plan"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "rows": 2,
+            "seatsPerRow": 2,
+            "blockedSeatCodes": [4],
+            "assignments": [0, null, 1]
+        }),
+    );
+
+    let exported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": out_path.to_string_lossy() }),
+    );
+    assert_eq!(
+        exported,
+        json!({ "path": out_path.to_string_lossy(), "rows": 2, "seatsPerRow": 2 })
+    );
+
+    let svg = std::fs::read_to_string(&out_path).expect("read svg");
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("Chem 11 \u{2014} Default"));
+    assert!(svg.contains(">Adams, Pat</text>"));
+    assert!(svg.contains(">Smith &amp; Jones, Lee</text>"));
+    assert_eq!(svg.matches("fill=\"#cccccc\"").count(), 1);
+    assert_eq!(svg.matches("<rect").count(), 5);
+    let footer = svg.split(">Unseated</text>").nth(1).expect("footer");
+    assert!(footer.contains(">Chen, Sam</text>"));
+    assert!(!footer.contains("Adams"));
+
+    let missing_path = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.exportSvg",
+        json!({ "classId": class_id, "outPath": "  " }),
+    );
+    assert_eq!(missing_path["error"]["code"], json!("bad_params"));
+}
@@ -0,0 +1,236 @@
+mod test_support;
+
+use rusqlite::Connection;
+use serde_json::json;
+use std::path::PathBuf;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+fn fixture_path(rel: &str) -> PathBuf {
+    let base = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    base.join("../../").join(rel)
+}
+
+#[test]
+fn seating_plans_are_named_and_default_plan_backs_plan_less_calls() {
+    let workspace = temp_dir("markbook-seating-plans");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    for (i, last) in ["Adams", "Baker"].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        );
+    }
+
+    // Legacy clients: save/get without planId operate on the default plan.
+    let saved = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.save",
+        json!({ "classId": class_id, "rows": 2, "seatsPerRow": 2, "assignments": [0, 1] }),
+    );
+    let default_plan_id = saved["planId"].as_str().expect("planId").to_string();
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(got["planId"], json!(default_plan_id));
+    assert_eq!(got["planName"], json!("Default"));
+    assert_eq!(got["assignments"], json!([0, 1, null, null]));
+
+    let lab = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.plans.create",
+        json!({ "classId": class_id, "name": " Lab ", "rows": 1, "seatsPerRow": 3 }),
+    );
+    assert_eq!(lab["name"], json!("Lab"));
+    assert_eq!(lab["isDefault"], json!(false));
+    let lab_plan_id = lab["planId"].as_str().expect("planId").to_string();
+
+    let dup = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.plans.create",
+        json!({ "classId": class_id, "name": "lab" }),
+    );
+    assert_eq!(dup["error"]["code"], json!("bad_params"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "planId": lab_plan_id,
+            "rows": 1,
+            "seatsPerRow": 3,
+            "assignments": [null, 1, 0]
+        }),
+    );
+    let lab_seats = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "seating.get",
+        json!({ "classId": class_id, "planId": lab_plan_id }),
+    );
+    assert_eq!(lab_seats["planName"], json!("Lab"));
+    assert_eq!(lab_seats["assignments"], json!([null, 1, 0]));
+    let default_seats = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(default_seats["assignments"], json!([0, 1, null, null]));
+
+    let plans = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "seating.plans.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(
+        plans["plans"],
+        json!([
+            {
+                "planId": default_plan_id,
+                "name": "Default",
+                "isDefault": true,
+                "rows": 2,
+                "seatsPerRow": 2,
+                "seatedCount": 2
+            },
+            {
+                "planId": lab_plan_id,
+                "name": "Lab",
+                "isDefault": false,
+                "rows": 1,
+                "seatsPerRow": 3,
+                "seatedCount": 2
+            }
+        ])
+    );
+
+    let deleted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "seating.plans.delete",
+        json!({ "classId": class_id, "planId": default_plan_id }),
+    );
+    assert_eq!(deleted["defaultPlanId"], json!(lab_plan_id));
+    let promoted = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(promoted["planId"], json!(lab_plan_id));
+    assert_eq!(promoted["assignments"], json!([null, 1, 0]));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "seating.get",
+        json!({ "classId": class_id, "planId": default_plan_id }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
+
+#[test]
+fn single_plan_workspaces_migrate_to_a_default_plan() {
+    let workspace = temp_dir("markbook-seating-plans-migration");
+    std::fs::copy(
+        fixture_path("rust/markbookd/tests/fixtures/db/v2/markbook.sqlite3"),
+        workspace.join("markbook.sqlite3"),
+    )
+    .expect("copy snapshot");
+    {
+        let conn = Connection::open(workspace.join("markbook.sqlite3")).expect("open snapshot");
+        conn.execute_batch(
+            "CREATE TABLE seating_plans(
+                class_id TEXT PRIMARY KEY,
+                rows INTEGER NOT NULL,
+                seats_per_row INTEGER NOT NULL,
+                blocked_mask TEXT NOT NULL
+             );
+             CREATE TABLE seating_assignments(
+                class_id TEXT NOT NULL,
+                student_id TEXT NOT NULL,
+                seat_code INTEGER NOT NULL,
+                PRIMARY KEY(class_id, student_id)
+             );
+             INSERT INTO seating_plans VALUES('c_old_v2', 1, 3, '0100');
+             INSERT INTO seating_assignments VALUES('c_old_v2', 's_old_v2_1', 1);
+             INSERT INTO seating_assignments VALUES('c_old_v2', 's_old_v2_2', 3);",
+        )
+        .expect("seed single-plan seating tables");
+    }
+
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+
+    let plans = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "seating.plans.list",
+        json!({ "classId": "c_old_v2" }),
+    );
+    let plans = plans["plans"].as_array().expect("plans");
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0]["name"], json!("Default"));
+    assert_eq!(plans[0]["isDefault"], json!(true));
+    assert_eq!(plans[0]["seatedCount"], json!(2));
+
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.get",
+        json!({ "classId": "c_old_v2" }),
+    );
+    assert_eq!(got["rows"], json!(1));
+    assert_eq!(got["seatsPerRow"], json!(3));
+    assert_eq!(got["blockedSeatCodes"], json!([2]));
+    assert_eq!(got["assignments"], json!([0, null, 1]));
+}
@@ -0,0 +1,138 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn set_blocked_toggles_one_seat_and_refuses_occupied_seats() {
+    let workspace = temp_dir("markbook-seating-set-blocked");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Science" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let no_plan = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.setBlocked",
+        json!({ "classId": class_id, "seatCode": 1, "blocked": true }),
+    );
+    assert_eq!(no_plan["error"]["code"], json!("no_plan"));
+
+    // Adams sits in the first seat (code 1); seat 4 is already blocked.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "rows": 2,
+            "seatsPerRow": 3,
+            "blockedSeatCodes": [4],
+            "assignments": [0]
+        }),
+    );
+
+    // Second row, second seat: index 4, the fifth mask cell.
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.setBlocked",
+        json!({ "classId": class_id, "seatCode": 12, "blocked": true }),
+    );
+    let mask = res["blockedMask"].as_str().expect("blockedMask");
+    assert_eq!(mask.len(), 100);
+    assert_eq!(&mask[..6], "000110");
+    assert_eq!(res["blockedSeatCodes"], json!([4, 5]));
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(got["blockedSeatCodes"], json!([4, 5]));
+    assert_eq!(got["assignments"][0], json!(0));
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "seating.setBlocked",
+        json!({ "classId": class_id, "seatCode": 11, "blocked": false }),
+    );
+    assert_eq!(res["blockedSeatCodes"], json!([5]));
+
+    let occupied = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "seating.setBlocked",
+        json!({ "classId": class_id, "seatCode": 1, "blocked": true }),
+    );
+    assert_eq!(occupied["error"]["code"], json!("seat_occupied"));
+    assert_eq!(
+        occupied["error"]["details"],
+        json!({ "seatCode": 1, "studentId": student_id })
+    );
+
+    for (i, seat_code) in [0, 4, 21].iter().enumerate() {
+        let outside = request(
+            &mut stdin,
+            &mut reader,
+            &format!("outside-{}", i),
+            "seating.setBlocked",
+            json!({ "classId": class_id, "seatCode": seat_code, "blocked": true }),
+        );
+        assert_eq!(
+            outside["error"]["code"],
+            json!("bad_params"),
+            "{}",
+            seat_code
+        );
+    }
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "seating.setBlocked",
+        json!({ "classId": class_id, "seatCode": 2 }),
+    );
+    assert_eq!(missing["error"]["code"], json!("bad_params"));
+
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(got["blockedSeatCodes"], json!([5]));
+}
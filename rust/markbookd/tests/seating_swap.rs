@@ -0,0 +1,157 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn seating_swap_swaps_moves_and_validates_seats() {
+    let workspace = temp_dir("markbook-seating-swap");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Seating" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen", "Dunn"].iter().enumerate() {
+        let created = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        );
+        ids.push(
+            created["studentId"]
+                .as_str()
+                .expect("studentId")
+                .to_string(),
+        );
+    }
+    let (a, b, c, d) = (&ids[0], &ids[1], &ids[2], &ids[3]);
+
+    // Adams in seat 1, Baker in seat 2; seat 13 (last of row two) blocked.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "seating.save",
+        json!({
+            "classId": class_id,
+            "rows": 2,
+            "seatsPerRow": 3,
+            "blockedSeatCodes": [6],
+            "assignments": [0, 1]
+        }),
+    );
+
+    let swapped = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "seating.swap",
+        json!({ "classId": class_id, "studentId": a, "targetStudentId": b }),
+    );
+    assert_eq!(
+        swapped["assignments"],
+        json!([
+            { "studentId": a, "seatCode": 2 },
+            { "studentId": b, "seatCode": 1 }
+        ])
+    );
+
+    // Unseated Chen takes Adams's seat; Adams becomes unseated.
+    let moved = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "seating.swap",
+        json!({ "classId": class_id, "studentId": c, "targetStudentId": a }),
+    );
+    assert_eq!(
+        moved["assignments"],
+        json!([
+            { "studentId": c, "seatCode": 2 },
+            { "studentId": a, "seatCode": null }
+        ])
+    );
+
+    let to_empty = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "seating.swap",
+        json!({ "classId": class_id, "studentId": b, "seatCode": 3 }),
+    );
+    assert_eq!(
+        to_empty["assignments"],
+        json!([{ "studentId": b, "seatCode": 3 }])
+    );
+
+    let to_occupied = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "seating.swap",
+        json!({ "classId": class_id, "studentId": c, "seatCode": 3 }),
+    );
+    assert_eq!(
+        to_occupied["assignments"],
+        json!([
+            { "studentId": c, "seatCode": 3 },
+            { "studentId": b, "seatCode": 2 }
+        ])
+    );
+
+    let got = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "seating.get",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(got["assignments"], json!([null, 1, 2, null, null, null]));
+
+    for (i, params) in [
+        json!({ "classId": class_id, "studentId": a, "seatCode": 13 }),
+        json!({ "classId": class_id, "studentId": a, "seatCode": 4 }),
+        json!({ "classId": class_id, "studentId": a, "seatCode": 21 }),
+        json!({ "classId": class_id, "studentId": a, "targetStudentId": d }),
+        json!({ "classId": class_id, "studentId": a }),
+        json!({ "classId": class_id, "studentId": a, "targetStudentId": a }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let resp = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad-{}", i),
+            "seating.swap",
+            params,
+        );
+        assert_eq!(resp["error"]["code"], json!("bad_params"), "case {}", i);
+    }
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "seating.swap",
+        json!({ "classId": class_id, "studentId": a, "targetStudentId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,208 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn archived_students_are_hidden_from_roster_grid_and_averages() {
+    let workspace = temp_dir("markbook-students-archive");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Archive" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut student_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        student_ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4c",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Quizzes", "weight": 100 }),
+    );
+    let assessment_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "assessments.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "title": "Quiz", "categoryName": "Quizzes", "outOf": 10 }),
+    )["assessmentId"]
+        .as_str()
+        .expect("assessmentId")
+        .to_string();
+    for (i, student_id) in student_ids.iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": 5 + i,
+                "status": "scored"
+            }),
+        );
+    }
+
+    let archived = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.archive",
+        json!({ "classId": class_id, "studentId": student_ids[1] }),
+    );
+    assert_eq!(archived["archived"], json!(true));
+
+    let list = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let visible: Vec<&str> = list["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| s["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(
+        visible,
+        vec![student_ids[0].as_str(), student_ids[2].as_str()]
+    );
+
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.list",
+        json!({ "classId": class_id, "includeArchived": true }),
+    );
+    assert_eq!(all["students"][1]["archived"], json!(true));
+
+    let open = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(open["students"].as_array().map(|s| s.len()), Some(2));
+
+    // Grid row 1 now addresses Chen, the second visible student.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "grid.updateCell",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "row": 1, "col": 0, "value": 9 }),
+    );
+
+    let averages = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "calc.markSetAverages",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(
+        averages["averages"],
+        json!([
+            { "studentId": student_ids[0], "percent": 50.0 },
+            { "studentId": student_ids[2], "percent": 90.0 }
+        ])
+    );
+
+    // Reorder only covers visible students; the archived one stays last.
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "students.reorder",
+        json!({ "classId": class_id, "orderedStudentIds": [student_ids[2], student_ids[0]] }),
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "students.unarchive",
+        json!({ "classId": class_id, "studentId": student_ids[1] }),
+    );
+    let restored = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let order: Vec<&str> = restored["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| s["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            student_ids[2].as_str(),
+            student_ids[0].as_str(),
+            student_ids[1].as_str()
+        ]
+    );
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "14",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(scores["scores"].as_array().map(|s| s.len()), Some(3));
+
+    let missing = request(
+        &mut stdin,
+        &mut reader,
+        "15",
+        "students.archive",
+        json!({ "classId": class_id, "studentId": "nope" }),
+    );
+    assert_eq!(missing["error"]["code"], json!("not_found"));
+}
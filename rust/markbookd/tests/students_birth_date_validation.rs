@@ -0,0 +1,145 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_birth_date_must_be_a_real_iso_date() {
+    let workspace = temp_dir("markbook-students-birth-date");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Dates" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({
+            "classId": class_id,
+            "lastName": "Roe",
+            "firstName": "Ann",
+            "birthDate": "2010-13-40"
+        }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+    assert_eq!(bad["error"]["details"]["field"], json!("birthDate"));
+
+    let non_leap = request(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.create",
+        json!({
+            "classId": class_id,
+            "lastName": "Roe",
+            "firstName": "Ann",
+            "birthDate": "2011-02-29"
+        }),
+    );
+    assert_eq!(non_leap["error"]["code"], json!("bad_params"));
+
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.create",
+        json!({
+            "classId": class_id,
+            "lastName": "Roe",
+            "firstName": "Ann",
+            "birthDate": "2012-02-29"
+        }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+
+    let bad_update = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "students.update",
+        json!({
+            "classId": class_id,
+            "studentId": student_id,
+            "patch": { "birthDate": "2013-02-29" }
+        }),
+    );
+    assert_eq!(bad_update["error"]["code"], json!("bad_params"));
+    assert_eq!(bad_update["error"]["details"]["field"], json!("birthDate"));
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(students["students"][0]["birthDate"], json!("2012-02-29"));
+
+    // Clearing still works with either null or an empty string.
+    for (i, cleared) in [json!(""), json!(null)].iter().enumerate() {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("clear{}", i),
+            "students.update",
+            json!({
+                "classId": class_id,
+                "studentId": student_id,
+                "patch": { "birthDate": cleared }
+            }),
+        );
+    }
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(students["students"][0]["birthDate"], json!(null));
+
+    let bad_bulk = request(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Doe", "firstName": "Bo", "birthDate": "2012-02-29" },
+                { "lastName": "Doe", "firstName": "Cy", "birthDate": "2010-4-1" }
+            ]
+        }),
+    );
+    assert_eq!(bad_bulk["error"]["code"], json!("bad_params"));
+    assert_eq!(
+        bad_bulk["error"]["details"],
+        json!({ "index": 1, "field": "birthDate" })
+    );
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(students["students"].as_array().map(|a| a.len()), Some(1));
+}
@@ -0,0 +1,98 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_bulk_create_appends_roster_and_rejects_bad_entries() {
+    let workspace = temp_dir("markbook-students-bulk-create");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Roster" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Existing", "firstName": "Pat" }),
+    );
+
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Adams", "firstName": "Ann", "studentNo": "1001" },
+                { "lastName": "Baker", "firstName": "Ben", "active": false },
+                { "lastName": "Chen", "firstName": "Cy", "birthDate": "2010-04-01" }
+            ]
+        }),
+    );
+    let ids = created["createdStudentIds"]
+        .as_array()
+        .expect("createdStudentIds");
+    assert_eq!(ids.len(), 3);
+
+    let list = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let students = list["students"].as_array().expect("students");
+    assert_eq!(students.len(), 4);
+    let sort_orders: Vec<i64> = students
+        .iter()
+        .map(|s| s["sortOrder"].as_i64().expect("sortOrder"))
+        .collect();
+    assert_eq!(sort_orders, vec![0, 1, 2, 3]);
+    assert_eq!(students[1]["id"], ids[0]);
+    assert_eq!(students[1]["studentNo"], json!("1001"));
+    assert_eq!(students[2]["active"], json!(false));
+    assert_eq!(students[3]["birthDate"], json!("2010-04-01"));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Diaz", "firstName": "Dee" },
+                { "lastName": "  ", "firstName": "Eve" }
+            ]
+        }),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+    assert_eq!(bad["error"]["details"]["index"], json!(1));
+
+    let after = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(after["students"].as_array().map(|s| s.len()), Some(4));
+}
@@ -0,0 +1,101 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_list_pages_large_rosters_in_sort_order() {
+    let workspace = temp_dir("markbook-students-list-pagination");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Large" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let roster: Vec<_> = (0..500)
+        .map(|i| json!({ "lastName": format!("Student{:03}", i), "firstName": "Pat" }))
+        .collect();
+    let created = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.bulkCreate",
+        json!({ "classId": class_id, "students": roster }),
+    );
+    let ids = created["createdStudentIds"]
+        .as_array()
+        .expect("createdStudentIds");
+    assert_eq!(ids.len(), 500);
+
+    let page = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.list",
+        json!({ "classId": class_id, "limit": 100, "offset": 100 }),
+    );
+    assert_eq!(page["total"], json!(500));
+    let students = page["students"].as_array().expect("students");
+    assert_eq!(students.len(), 100);
+    assert_eq!(students[0]["id"], ids[100]);
+    assert_eq!(students[0]["sortOrder"], json!(100));
+    assert_eq!(students[99]["lastName"], json!("Student199"));
+
+    // The last page is short; an offset past the end is empty but still counts.
+    let tail = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.list",
+        json!({ "classId": class_id, "limit": 100, "offset": 450 }),
+    );
+    assert_eq!(tail["students"].as_array().map(|s| s.len()), Some(50));
+    let past = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "students.list",
+        json!({ "classId": class_id, "offset": 600 }),
+    );
+    assert_eq!(past, json!({ "students": [], "total": 500 }));
+
+    // Without paging params the full roster comes back with no total.
+    let all = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(all["students"].as_array().map(|s| s.len()), Some(500));
+    assert!(all.get("total").is_none());
+
+    for (i, params) in [
+        json!({ "classId": class_id, "limit": 0 }),
+        json!({ "classId": class_id, "offset": -1 }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let bad = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad-{}", i),
+            "students.list",
+            params,
+        );
+        assert_eq!(bad["error"]["code"], json!("bad_params"));
+    }
+}
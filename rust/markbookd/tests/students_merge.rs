@@ -0,0 +1,411 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_find_duplicates_and_merge_repoint_rows() {
+    let workspace = temp_dir("markbook-students-merge");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Merge" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for (i, (last, first, birth)) in [
+        ("Roe", "Ann", "2010-04-01"),
+        ("Lee", "Ben", "2010-05-01"),
+        (" roe", "ANN ", "2010-04-01"),
+        ("Roe", "Ann", "2011-01-01"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": last, "firstName": first, "birthDate": birth }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        ids.push(id);
+    }
+
+    let by_name = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.findDuplicates",
+        json!({ "classId": class_id }),
+    );
+    let groups = by_name["groups"].as_array().expect("groups");
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["lastName"], json!("roe"));
+    let group_ids: Vec<&str> = groups[0]["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| s["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(
+        group_ids,
+        vec![ids[0].as_str(), ids[2].as_str(), ids[3].as_str()]
+    );
+    let by_birth = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.findDuplicates",
+        json!({ "classId": class_id, "matchBirthDate": true }),
+    );
+    assert_eq!(
+        by_birth["groups"][0]["students"]
+            .as_array()
+            .map(|a| a.len()),
+        Some(2)
+    );
+    assert_eq!(by_birth["groups"][0]["birthDate"], json!("2010-04-01"));
+
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut assessment_ids = Vec::new();
+    for title in ["Quiz", "Test"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            "a",
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+    // The kept student's Quiz score wins; the duplicate's Test score moves over.
+    for (student, assessment, raw) in [(0, 0, 8), (2, 0, 3), (2, 1, 9)] {
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            "score",
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_ids[assessment],
+                "studentId": ids[student],
+                "rawValue": raw
+            }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "notes.update",
+        json!({ "classId": class_id, "studentId": ids[3], "note": "Transferred in" }),
+    );
+
+    let merged = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "students.merge",
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [ids[2], ids[3]] }),
+    );
+    assert_eq!(merged["merged"], json!(2));
+    assert_eq!(merged["repointed"]["scores"], json!(1));
+    assert_eq!(merged["dropped"]["scores"], json!(1));
+    assert_eq!(merged["repointed"]["student_notes"], json!(1));
+
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "grid.getScores",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    let mut kept: Vec<(String, f64)> = scores["scores"]
+        .as_array()
+        .expect("scores")
+        .iter()
+        .filter(|s| s["studentId"] == json!(ids[0]))
+        .map(|s| {
+            (
+                s["assessmentId"]
+                    .as_str()
+                    .expect("assessmentId")
+                    .to_string(),
+                s["rawValue"].as_f64().expect("rawValue"),
+            )
+        })
+        .collect();
+    kept.sort_by(|a, b| a.1.total_cmp(&b.1));
+    assert_eq!(
+        kept,
+        vec![
+            (assessment_ids[0].clone(), 8.0),
+            (assessment_ids[1].clone(), 9.0)
+        ]
+    );
+    let note = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "notes.getOne",
+        json!({ "classId": class_id, "studentId": ids[0] }),
+    );
+    assert_eq!(note["note"], json!("Transferred in"));
+
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let roster: Vec<(String, i64)> = students["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| {
+            (
+                s["id"].as_str().expect("id").to_string(),
+                s["sortOrder"].as_i64().expect("sortOrder"),
+            )
+        })
+        .collect();
+    assert_eq!(roster, vec![(ids[0].clone(), 0), (ids[1].clone(), 1)]);
+
+    for (i, params) in [
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [] }),
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [ids[0]] }),
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [ids[1], ids[1]] }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let bad = request(
+            &mut stdin,
+            &mut reader,
+            &format!("bad{i}"),
+            "students.merge",
+            params,
+        );
+        assert_eq!(bad["error"]["code"], json!("bad_params"));
+    }
+    let gone = request(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "students.merge",
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [ids[2]] }),
+    );
+    assert_eq!(gone["error"]["code"], json!("not_found"));
+    assert_eq!(gone["error"]["details"]["studentId"], json!(ids[2]));
+}
+
+#[test]
+fn students_merge_keeps_marks_split_across_duplicates() {
+    let workspace = temp_dir("markbook-students-merge-split");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Merge" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for i in 0..2 {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": "Roe", "firstName": "Ann" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        ids.push(id);
+    }
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let mut assessment_ids = Vec::new();
+    for title in ["Quiz", "Test"] {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            "a",
+            "assessments.create",
+            json!({ "classId": class_id, "markSetId": mark_set_id, "title": title, "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        assessment_ids.push(id);
+    }
+    // Each duplicate has one real mark and a no_mark row for the other, as a
+    // legacy import leaves blank cells.
+    for (student, assessment, cell) in [
+        (0, 0, json!({ "status": "no_mark" })),
+        (0, 1, json!({ "rawValue": 7 })),
+        (1, 0, json!({ "rawValue": 6 })),
+        (1, 1, json!({ "status": "no_mark" })),
+    ] {
+        let mut params = json!({
+            "classId": class_id,
+            "assessmentId": assessment_ids[assessment],
+            "studentId": ids[student]
+        });
+        for (k, v) in cell.as_object().expect("cell") {
+            params[k] = v.clone();
+        }
+        let _ = request_ok(&mut stdin, &mut reader, "score", "scores.setCell", params);
+    }
+
+    let merged = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.merge",
+        json!({ "classId": class_id, "keepId": ids[0], "mergeIds": [ids[1]] }),
+    );
+    assert_eq!(merged["repointed"]["scores"], json!(1));
+    assert_eq!(merged["dropped"]["scores"], json!(1));
+
+    let grid = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "grid.get",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "rowStart": 0, "rowCount": 1, "colStart": 0, "colCount": 2 }),
+    );
+    assert_eq!(grid["cells"], json!([[6.0, 7.0]]));
+}
+
+#[test]
+fn students_merge_after_reorder_keeps_sort_order_contiguous() {
+    let workspace = temp_dir("markbook-students-merge-reorder");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Merge" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let mut ids = Vec::new();
+    for i in 0..6 {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{i}"),
+            "students.create",
+            json!({ "classId": class_id, "lastName": format!("Student{i}"), "firstName": "A" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        ids.push(id);
+    }
+    let reversed: Vec<String> = ids.iter().rev().cloned().collect();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.reorder",
+        json!({ "classId": class_id, "orderedStudentIds": reversed }),
+    );
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.merge",
+        json!({ "classId": class_id, "keepId": ids[2], "mergeIds": [ids[4], ids[5]] }),
+    );
+
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    let roster: Vec<(String, i64)> = students["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| {
+            (
+                s["id"].as_str().expect("id").to_string(),
+                s["sortOrder"].as_i64().expect("sortOrder"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        roster,
+        vec![
+            (ids[3].clone(), 0),
+            (ids[2].clone(), 1),
+            (ids[1].clone(), 2),
+            (ids[0].clone(), 3)
+        ]
+    );
+}
@@ -0,0 +1,188 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_move_carries_notes_and_attendance_and_drops_scores() {
+    let workspace = temp_dir("markbook-students-move");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut class_ids = Vec::new();
+    for (i, name) in ["Section A", "Section B"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("c{}", i),
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        class_ids.push(id);
+    }
+    let (from_class, to_class) = (class_ids[0].clone(), class_ids[1].clone());
+
+    let mut from_ids = Vec::new();
+    for (i, last) in ["Adams", "Baker", "Chen"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({ "classId": from_class, "lastName": last, "firstName": "Pat" }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        from_ids.push(id);
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.create",
+        json!({ "classId": to_class, "lastName": "Diaz", "firstName": "Dee" }),
+    );
+    let mover = from_ids[1].clone();
+
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "marksets.create",
+        json!({ "classId": from_class, "code": "T1", "description": "Term 1" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    for i in 0..2 {
+        let assessment_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{}", i),
+            "assessments.create",
+            json!({ "classId": from_class, "markSetId": mark_set_id, "title": format!("Quiz {}", i), "outOf": 10 }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("e{}", i),
+            "scores.setCell",
+            json!({
+                "classId": from_class,
+                "assessmentId": assessment_id,
+                "studentId": mover,
+                "rawValue": 8,
+                "status": "scored"
+            }),
+        );
+    }
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "notes.update",
+        json!({ "classId": from_class, "studentId": mover, "note": "Needs front seat" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "attendance.setStudentDay",
+        json!({ "classId": from_class, "month": "9", "studentId": mover, "day": 1, "code": "A" }),
+    );
+
+    let moved = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "students.move",
+        json!({ "fromClassId": from_class, "toClassId": to_class, "studentId": mover }),
+    );
+    assert_eq!(moved["droppedScores"].as_u64(), Some(2));
+
+    let source = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "students.list",
+        json!({ "classId": from_class }),
+    );
+    let source: Vec<(String, i64)> = source["students"]
+        .as_array()
+        .expect("students")
+        .iter()
+        .map(|s| {
+            (
+                s["id"].as_str().expect("id").to_string(),
+                s["sortOrder"].as_i64().expect("sortOrder"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        source,
+        vec![(from_ids[0].clone(), 0), (from_ids[2].clone(), 1)]
+    );
+
+    let dest = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "students.list",
+        json!({ "classId": to_class }),
+    );
+    assert_eq!(dest["students"][1]["id"], json!(mover));
+    assert_eq!(dest["students"][1]["sortOrder"], json!(1));
+
+    let notes = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "notes.get",
+        json!({ "classId": to_class }),
+    );
+    assert_eq!(
+        notes["notes"],
+        json!([{ "studentId": mover, "note": "Needs front seat" }])
+    );
+
+    let summary = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "attendance.summary",
+        json!({ "classId": to_class }),
+    );
+    assert_eq!(summary["summaries"][1]["studentId"], json!(mover));
+    assert_eq!(summary["summaries"][1]["absent"], json!(1));
+
+    let scores = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "grid.getScores",
+        json!({ "classId": from_class, "markSetId": mark_set_id }),
+    );
+    assert_eq!(scores["scores"], json!([]));
+
+    let again = request(
+        &mut stdin,
+        &mut reader,
+        "14",
+        "students.move",
+        json!({ "fromClassId": from_class, "toClassId": to_class, "studentId": mover }),
+    );
+    assert_eq!(again["error"]["code"], json!("not_found"));
+}
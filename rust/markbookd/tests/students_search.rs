@@ -0,0 +1,127 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn students_search_matches_names_and_numbers_across_classes() {
+    let workspace = temp_dir("markbook-students-search");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let mut class_ids = Vec::new();
+    for (i, name) in ["Math 9", "Science 10"].iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("c{}", i),
+            "classes.create",
+            json!({ "name": name }),
+        )["classId"]
+            .as_str()
+            .expect("classId")
+            .to_string();
+        class_ids.push(id);
+    }
+    let roster = [
+        (0, "Smithers", "Wayland", "A-100"),
+        (1, "Blacksmith", "Jo", "B_200"),
+        (1, "Adams", "Smitty", ""),
+        (0, "Zhou", "Li", "C-300"),
+    ];
+    let mut ids = Vec::new();
+    for (i, (class, last, first, no)) in roster.iter().enumerate() {
+        let id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("s{}", i),
+            "students.create",
+            json!({
+                "classId": class_ids[*class],
+                "lastName": last,
+                "firstName": first,
+                "studentNo": no
+            }),
+        )["studentId"]
+            .as_str()
+            .expect("studentId")
+            .to_string();
+        ids.push(id);
+    }
+
+    let res = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "students.search",
+        json!({ "query": "SMIT" }),
+    );
+    assert_eq!(
+        res["results"],
+        json!([
+            {
+                "studentId": ids[2],
+                "classId": class_ids[1],
+                "className": "Science 10",
+                "displayName": "Adams, Smitty",
+                "studentNo": null
+            },
+            {
+                "studentId": ids[1],
+                "classId": class_ids[1],
+                "className": "Science 10",
+                "displayName": "Blacksmith, Jo",
+                "studentNo": "B_200"
+            },
+            {
+                "studentId": ids[0],
+                "classId": class_ids[0],
+                "className": "Math 9",
+                "displayName": "Smithers, Wayland",
+                "studentNo": "A-100"
+            }
+        ])
+    );
+
+    let limited = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.search",
+        json!({ "query": "smit", "limit": 1 }),
+    );
+    assert_eq!(limited["results"].as_array().map(|r| r.len()), Some(1));
+
+    // Underscore is matched literally, not as a LIKE wildcard.
+    let by_number = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "students.search",
+        json!({ "query": "b_2" }),
+    );
+    assert_eq!(by_number["results"].as_array().map(|r| r.len()), Some(1));
+    let literal = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4b",
+        "students.search",
+        json!({ "query": "a_1" }),
+    );
+    assert_eq!(literal["results"], json!([]));
+
+    let empty = request(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "students.search",
+        json!({ "query": "  " }),
+    );
+    assert_eq!(empty["error"]["code"], json!("bad_params"));
+}
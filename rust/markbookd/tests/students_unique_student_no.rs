@@ -0,0 +1,133 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn duplicate_student_no_is_rejected_only_when_required() {
+    let workspace = temp_dir("markbook-students-unique-no");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let setup = request_ok(&mut stdin, &mut reader, "2", "setup.get", json!({}));
+    assert_eq!(
+        setup["students"],
+        json!({ "requireUniqueStudentNo": false })
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "classes.create",
+        json!({ "name": "Numbers" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let create = |stdin: &mut _, reader: &mut _, id: &str, first: &str, no: &str| {
+        request(
+            stdin,
+            reader,
+            id,
+            "students.create",
+            json!({
+                "classId": class_id,
+                "lastName": "Roe",
+                "firstName": first,
+                "studentNo": no
+            }),
+        )
+    };
+
+    // Without the rule, duplicates (and blank numbers) are accepted.
+    for (i, first) in ["Ann", "Ben"].iter().enumerate() {
+        let created = create(&mut stdin, &mut reader, &format!("dup{}", i), first, "1001");
+        assert_eq!(created["ok"], json!(true), "{}", created);
+    }
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "setup.update",
+        json!({ "section": "students", "patch": { "requireUniqueStudentNo": true } }),
+    );
+    let rejected = create(&mut stdin, &mut reader, "5", "Cal", " 1001 ");
+    assert_eq!(rejected["error"]["code"], json!("duplicate_student_no"));
+    assert_eq!(rejected["error"]["details"]["studentNo"], json!("1001"));
+    for (i, first) in ["Dee", "Eve"].iter().enumerate() {
+        let blank = create(&mut stdin, &mut reader, &format!("blank{}", i), first, "");
+        assert_eq!(blank["ok"], json!(true), "{}", blank);
+    }
+    let cal = create(&mut stdin, &mut reader, "6", "Cal", "1002");
+    let cal_id = cal["result"]["studentId"].as_str().expect("studentId");
+
+    let update = |stdin: &mut _, reader: &mut _, id: &str, no: serde_json::Value| {
+        request(
+            stdin,
+            reader,
+            id,
+            "students.update",
+            json!({ "classId": class_id, "studentId": cal_id, "patch": { "studentNo": no } }),
+        )
+    };
+    let clash = update(&mut stdin, &mut reader, "7", json!("1001"));
+    assert_eq!(clash["error"]["code"], json!("duplicate_student_no"));
+    let own = update(&mut stdin, &mut reader, "8", json!("1002"));
+    assert_eq!(own["ok"], json!(true), "{}", own);
+    let cleared = update(&mut stdin, &mut reader, "9", json!(null));
+    assert_eq!(cleared["ok"], json!(true), "{}", cleared);
+
+    let bulk = request(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "students.bulkCreate",
+        json!({
+            "classId": class_id,
+            "students": [
+                { "lastName": "Fox", "firstName": "Fay", "studentNo": "2001" },
+                { "lastName": "Fox", "firstName": "Gus", "studentNo": "2001" }
+            ]
+        }),
+    );
+    assert_eq!(bulk["error"]["code"], json!("duplicate_student_no"));
+    assert_eq!(
+        bulk["error"]["details"],
+        json!({ "studentNo": "2001", "index": 1 })
+    );
+
+    // The rule is per class.
+    let other_class = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "classes.create",
+        json!({ "name": "Other" }),
+    )["classId"]
+        .clone();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "students.bulkCreate",
+        json!({
+            "classId": other_class,
+            "students": [{ "lastName": "Fox", "firstName": "Fay", "studentNo": "1001" }]
+        }),
+    );
+    let students = request_ok(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "students.list",
+        json!({ "classId": class_id }),
+    );
+    assert_eq!(students["students"].as_array().map(|a| a.len()), Some(5));
+}
@@ -0,0 +1,41 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar};
+
+#[test]
+fn system_methods_lists_every_dispatched_method() {
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let result = request_ok(&mut stdin, &mut reader, "1", "system.methods", json!({}));
+    let methods: Vec<String> = result
+        .get("methods")
+        .and_then(|v| v.as_array())
+        .expect("methods")
+        .iter()
+        .map(|v| v.as_str().expect("method name").to_string())
+        .collect();
+    assert_eq!(
+        result.get("count").and_then(|v| v.as_u64()),
+        Some(methods.len() as u64)
+    );
+
+    let mut sorted = methods.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(methods, sorted, "methods must be sorted and unique");
+
+    for expected in ["health", "system.methods", "marksets.create", "grid.get"] {
+        assert!(methods.iter().any(|m| m == expected), "missing {expected}");
+    }
+
+    // Without a workspace every listed method must still be routed to a handler.
+    for (i, method) in methods.iter().enumerate() {
+        if method == "workspace.select" {
+            continue;
+        }
+        let resp = request(&mut stdin, &mut reader, &format!("m{i}"), method, json!({}));
+        let code = resp.pointer("/error/code").and_then(|v| v.as_str());
+        assert_ne!(code, Some("unknown_method"), "{method} is not dispatched");
+    }
+}
@@ -0,0 +1,200 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn term_param_limits_markset_open_assessments_list_and_averages() {
+    let workspace = temp_dir("markbook-term-filtering");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let class_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "2",
+        "classes.create",
+        json!({ "name": "Terms" }),
+    )["classId"]
+        .as_str()
+        .expect("classId")
+        .to_string();
+    let student_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "students.create",
+        json!({ "classId": class_id, "lastName": "Adams", "firstName": "Pat" }),
+    )["studentId"]
+        .as_str()
+        .expect("studentId")
+        .to_string();
+    let mark_set_id = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "marksets.create",
+        json!({ "classId": class_id, "code": "MAT", "description": "Math" }),
+    )["markSetId"]
+        .as_str()
+        .expect("markSetId")
+        .to_string();
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "categories.create",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "name": "Tests", "weight": 100 }),
+    );
+    // Term 1 scores 80%, term 2 scores 60%, the untermed project 100%.
+    for (i, (title, term, raw)) in [
+        ("Unit 1", json!(1), 8),
+        ("Unit 2", json!(2), 6),
+        ("Project", json!(null), 10),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let assessment_id = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("a{i}"),
+            "assessments.create",
+            json!({
+                "classId": class_id,
+                "markSetId": mark_set_id,
+                "title": title,
+                "categoryName": "Tests",
+                "term": term,
+                "outOf": 10
+            }),
+        )["assessmentId"]
+            .as_str()
+            .expect("assessmentId")
+            .to_string();
+        let _ = request_ok(
+            &mut stdin,
+            &mut reader,
+            &format!("sc{i}"),
+            "scores.setCell",
+            json!({
+                "classId": class_id,
+                "assessmentId": assessment_id,
+                "studentId": student_id,
+                "rawValue": raw
+            }),
+        );
+    }
+
+    let titles = |v: &serde_json::Value| -> Vec<String> {
+        v["assessments"]
+            .as_array()
+            .expect("assessments")
+            .iter()
+            .map(|a| a["title"].as_str().expect("title").to_string())
+            .collect()
+    };
+    let opened = request_ok(
+        &mut stdin,
+        &mut reader,
+        "6",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "term": 2 }),
+    );
+    assert_eq!(titles(&opened), vec!["Unit 2"]);
+    assert_eq!(opened["colCount"], json!(1));
+    assert_eq!(opened["assessments"][0]["term"], json!(2));
+    let opened = request_ok(
+        &mut stdin,
+        &mut reader,
+        "7",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "term": 2, "includeUntermed": true }),
+    );
+    assert_eq!(titles(&opened), vec!["Unit 2", "Project"]);
+    let opened = request_ok(
+        &mut stdin,
+        &mut reader,
+        "8",
+        "markset.open",
+        json!({ "classId": class_id, "markSetId": mark_set_id }),
+    );
+    assert_eq!(opened["colCount"], json!(3));
+
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "9",
+        "assessments.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "term": 1 }),
+    );
+    assert_eq!(titles(&listed), vec!["Unit 1"]);
+    let listed = request_ok(
+        &mut stdin,
+        &mut reader,
+        "10",
+        "assessments.list",
+        json!({ "classId": class_id, "markSetId": mark_set_id, "term": "ALL" }),
+    );
+    assert_eq!(titles(&listed).len(), 3);
+
+    let average = |stdin: &mut _, reader: &mut _, params: serde_json::Value| -> f64 {
+        request_ok(stdin, reader, "avg", "calc.markSetAverages", params)["averages"][0]["percent"]
+            .as_f64()
+            .expect("percent")
+    };
+    let base = json!({ "classId": class_id, "markSetId": mark_set_id });
+    let with = |extra: serde_json::Value| {
+        let mut p = base.clone();
+        for (k, v) in extra.as_object().expect("object") {
+            p[k] = v.clone();
+        }
+        p
+    };
+    let all = average(&mut stdin, &mut reader, base.clone());
+    assert!((all - 80.0).abs() < 1e-9, "all terms: {all}");
+    let term1 = average(&mut stdin, &mut reader, with(json!({ "term": 1 })));
+    assert!((term1 - 80.0).abs() < 1e-9, "term 1: {term1}");
+    let term2 = average(&mut stdin, &mut reader, with(json!({ "term": 2 })));
+    assert!((term2 - 60.0).abs() < 1e-9, "term 2: {term2}");
+    let term2_untermed = average(
+        &mut stdin,
+        &mut reader,
+        with(json!({ "term": 2, "includeUntermed": true })),
+    );
+    assert!(
+        (term2_untermed - 80.0).abs() < 1e-9,
+        "term 2 + untermed: {term2_untermed}"
+    );
+    let one_student = request_ok(
+        &mut stdin,
+        &mut reader,
+        "11",
+        "calc.markSetAverages",
+        with(json!({ "studentId": student_id, "term": 2 })),
+    );
+    assert_eq!(one_student["averages"][0]["percent"], json!(60.0));
+
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "12",
+        "assessments.list",
+        with(json!({ "term": "first" })),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+    let bad = request(
+        &mut stdin,
+        &mut reader,
+        "13",
+        "markset.open",
+        with(json!({ "term": 1, "includeUntermed": "yes" })),
+    );
+    assert_eq!(bad["error"]["code"], json!("bad_params"));
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use serde_json::json;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
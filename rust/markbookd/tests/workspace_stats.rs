@@ -0,0 +1,73 @@
+mod test_support;
+
+use serde_json::json;
+use test_support::{fixture_path, request, request_ok, spawn_sidecar, temp_dir};
+
+#[test]
+fn workspace_stats_counts_rows_across_classes() {
+    let workspace = temp_dir("markbook-workspace-stats");
+    let (_child, mut stdin, mut reader) = spawn_sidecar();
+
+    let no_workspace = request(&mut stdin, &mut reader, "0", "workspace.stats", json!({}));
+    assert_eq!(no_workspace["error"]["code"], json!("no_workspace"));
+
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "1",
+        "workspace.select",
+        json!({ "path": workspace.to_string_lossy() }),
+    );
+    let empty = request_ok(&mut stdin, &mut reader, "2", "workspace.stats", json!({}));
+    assert_eq!(empty["classCount"], json!(0));
+    assert_eq!(empty["scoreCount"], json!(0));
+    assert_eq!(empty["workspacePath"], json!(workspace.to_string_lossy()));
+    assert!(empty["dbSizeBytes"].as_u64().expect("dbSizeBytes") > 0);
+
+    let imported = request_ok(
+        &mut stdin,
+        &mut reader,
+        "3",
+        "class.importLegacy",
+        json!({
+            "legacyClassFolderPath": fixture_path("fixtures/legacy/Sample25/MB8D25").to_string_lossy()
+        }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "4",
+        "classes.create",
+        json!({ "name": "Empty" }),
+    );
+    let _ = request_ok(
+        &mut stdin,
+        &mut reader,
+        "5",
+        "comments.banks.create",
+        json!({ "shortName": "GEN" }),
+    );
+
+    let stats = request_ok(&mut stdin, &mut reader, "6", "workspace.stats", json!({}));
+    let db = rusqlite::Connection::open(workspace.join("markbook.sqlite3")).expect("open db");
+    let count = |table: &str| -> i64 {
+        db.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+            .expect("count")
+    };
+    assert_eq!(stats["classCount"], json!(2));
+    assert_eq!(stats["studentCount"], json!(count("students")));
+    assert_eq!(stats["markSetCount"], json!(count("mark_sets")));
+    assert_eq!(stats["assessmentCount"], json!(count("assessments")));
+    assert_eq!(stats["scoreCount"], json!(count("scores")));
+    assert_eq!(stats["commentBankCount"], json!(count("comment_banks")));
+    assert!(stats["scoreCount"].as_i64().expect("scoreCount") > 0);
+
+    let classes = request_ok(&mut stdin, &mut reader, "7", "classes.list", json!({}));
+    let listed = classes["classes"]
+        .as_array()
+        .expect("classes")
+        .iter()
+        .find(|c| c["id"] == imported["classId"])
+        .expect("imported class");
+    assert_eq!(stats["studentCount"], listed["studentCount"]);
+}